@@ -0,0 +1,23 @@
+// A namespace for helpers that build and emit account events.
+
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::ActorID;
+use fvm_shared::address::Address;
+
+/// Indicates an account actor has been created for a public key address,
+/// mapping the key to the actor's ID.
+/// This is emitted exactly once for each account.
+pub fn account_activated(
+    rt: &impl Runtime,
+    id: ActorID,
+    address: &Address,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("account-activated")
+            .field_indexed("id", &id)
+            .field_indexed("address", address)
+            .build()?,
+    )
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::address::Protocol;
+use fvm_shared::address::{Address, Protocol};
 use fvm_shared::crypto::signature::SignatureType::{BLS, Secp256k1};
 use fvm_shared::crypto::signature::{Signature, SignatureType};
 use fvm_shared::error::ExitCode;
@@ -12,13 +12,16 @@ use num_derive::FromPrimitive;
 use fil_actors_runtime::builtin::singletons::SYSTEM_ACTOR_ADDR;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{ActorDowncast, FIRST_EXPORTED_METHOD_NUMBER, actor_dispatch};
-use fil_actors_runtime::{ActorError, actor_error};
+use fil_actors_runtime::{ActorError, BatchReturnGen, actor_error};
 use types::{AuthenticateMessageReturn, ConstructorParams, PubkeyAddressReturn};
 
-use crate::types::AuthenticateMessageParams;
+use crate::types::{
+    AuthenticateMessageParams, BatchAuthenticateMessageParams, BatchAuthenticateMessageReturn,
+};
 
 pub use self::state::State;
 
+mod emit;
 mod state;
 pub mod testing;
 pub mod types;
@@ -35,13 +38,16 @@ pub enum Method {
     // Deprecated in v10
     // AuthenticateMessage = 3,
     AuthenticateMessageExported = frc42_dispatch::method_hash!("AuthenticateMessage"),
+    BatchAuthenticateMessageExported = frc42_dispatch::method_hash!("BatchAuthenticateMessage"),
 }
 
 /// Account Actor
 pub struct Actor;
 
 impl Actor {
-    /// Constructor for Account actor
+    /// Constructor for Account actor.
+    /// The account is constructed when its key address is first used on chain,
+    /// and announces the key's ID with an activation event.
     pub fn constructor(rt: &impl Runtime, params: ConstructorParams) -> Result<(), ActorError> {
         let address = params.address;
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
@@ -53,7 +59,7 @@ impl Actor {
             }
         }
         rt.create(&State { address })?;
-        Ok(())
+        emit::account_activated(rt, rt.message().receiver().id().unwrap(), &address)
     }
 
     /// Fetches the pubkey-type address from this actor.
@@ -72,26 +78,34 @@ impl Actor {
     ) -> Result<AuthenticateMessageReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let st: State = rt.state()?;
-        let address = st.address;
-        let sig_type: SignatureType = match address.protocol() {
-            Protocol::Secp256k1 => Secp256k1,
-            Protocol::BLS => BLS,
-            protocol => {
-                return Err(actor_error!(illegal_state;
-                    "account address must use BLS or SECP protocol, got {}", protocol));
-            }
-        };
-        let sig = Signature { sig_type, bytes: params.signature };
-        rt.verify_signature(&sig, &address, &params.message).map_err(|e| {
-            e.downcast_default(
-                ExitCode::USR_ILLEGAL_ARGUMENT,
-                "failed to authenticate message, signature invalid",
-            )
-        })?;
+        let sig_type = signature_type(&st.address)?;
+        verify_signature(rt, sig_type, &st.address, params)?;
 
         Ok(AuthenticateMessageReturn { authenticated: true })
     }
 
+    /// Authenticates a batch of signatures against their messages, loading the account's key once.
+    /// Each message which fails authentication is reported with USR_ILLEGAL_ARGUMENT
+    /// in the result, rather than aborting the whole batch.
+    pub fn batch_authenticate_message(
+        rt: &impl Runtime,
+        params: BatchAuthenticateMessageParams,
+    ) -> Result<BatchAuthenticateMessageReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let sig_type = signature_type(&st.address)?;
+
+        let mut results = BatchReturnGen::new(params.messages.len());
+        for message in params.messages {
+            match verify_signature(rt, sig_type, &st.address, message) {
+                Ok(()) => results.add_success(),
+                Err(e) => results.add_fail(e.exit_code()),
+            };
+        }
+
+        Ok(BatchAuthenticateMessageReturn { results: results.generate() })
+    }
+
     /// Fallback method for unimplemented method numbers.
     pub fn fallback(
         rt: &impl Runtime,
@@ -107,6 +121,30 @@ impl Actor {
     }
 }
 
+fn signature_type(address: &Address) -> Result<SignatureType, ActorError> {
+    match address.protocol() {
+        Protocol::Secp256k1 => Ok(Secp256k1),
+        Protocol::BLS => Ok(BLS),
+        protocol => Err(actor_error!(illegal_state;
+            "account address must use BLS or SECP protocol, got {}", protocol)),
+    }
+}
+
+fn verify_signature(
+    rt: &impl Runtime,
+    sig_type: SignatureType,
+    address: &Address,
+    params: AuthenticateMessageParams,
+) -> Result<(), ActorError> {
+    let sig = Signature { sig_type, bytes: params.signature };
+    rt.verify_signature(&sig, address, &params.message).map_err(|e| {
+        e.downcast_default(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "failed to authenticate message, signature invalid",
+        )
+    })
+}
+
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -118,6 +156,7 @@ impl ActorCode for Actor {
         Constructor => constructor,
         PubkeyAddress => pubkey_address,
         AuthenticateMessageExported => authenticate_message,
+        BatchAuthenticateMessageExported => batch_authenticate_message,
         _ => fallback,
     }
 }
@@ -2,6 +2,8 @@ use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 
+use fil_actors_runtime::BatchReturn;
+
 #[derive(Debug, Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
 pub struct ConstructorParams {
@@ -27,3 +29,16 @@ pub struct AuthenticateMessageParams {
 pub struct AuthenticateMessageReturn {
     pub authenticated: bool,
 }
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct BatchAuthenticateMessageParams {
+    pub messages: Vec<AuthenticateMessageParams>,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct BatchAuthenticateMessageReturn {
+    // Result for each message, in the same order as the parameters.
+    pub results: BatchReturn,
+}
@@ -10,11 +10,13 @@ use fvm_shared::address::Address;
 use fvm_shared::crypto::signature::Signature;
 use fvm_shared::error::ExitCode;
 
-use fil_actor_account::types::AuthenticateMessageParams;
+use fil_actor_account::types::{
+    AuthenticateMessageParams, BatchAuthenticateMessageParams, BatchAuthenticateMessageReturn,
+};
 use fil_actor_account::{Actor as AccountActor, Method, State, testing::check_state_invariants};
-use fil_actors_runtime::FIRST_EXPORTED_METHOD_NUMBER;
 use fil_actors_runtime::builtin::SYSTEM_ACTOR_ADDR;
 use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{BatchReturn, EventBuilder, FIRST_EXPORTED_METHOD_NUMBER};
 
 #[test]
 fn construction() {
@@ -24,6 +26,7 @@ fn construction() {
         rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
 
         if exit_code.is_success() {
+            expect_activated(&rt, &addr);
             rt.call::<AccountActor>(
                 Method::Constructor as MethodNum,
                 IpldBlock::serialize_cbor(&addr).unwrap(),
@@ -67,6 +70,7 @@ fn token_receiver() {
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
 
     let param = Address::new_secp256k1(&[2; fvm_shared::address::SECP_PUB_LEN]).unwrap();
+    expect_activated(&rt, &param);
     rt.call::<AccountActor>(
         Method::Constructor as MethodNum,
         IpldBlock::serialize_cbor(&param).unwrap(),
@@ -95,6 +99,7 @@ fn authenticate_message() {
 
     let addr = Address::new_secp256k1(&[2; fvm_shared::address::SECP_PUB_LEN]).unwrap();
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    expect_activated(&rt, &addr);
     rt.call::<AccountActor>(
         Method::Constructor as MethodNum,
         IpldBlock::serialize_cbor(&addr).unwrap(),
@@ -161,6 +166,50 @@ fn authenticate_message() {
     );
 }
 
+#[test]
+fn batch_authenticate_message() {
+    let rt = MockRuntime { receiver: Address::new_id(100), ..Default::default() };
+    rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+
+    let addr = Address::new_bls(&[3; fvm_shared::address::BLS_PUB_LEN]).unwrap();
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    expect_activated(&rt, &addr);
+    rt.call::<AccountActor>(
+        Method::Constructor as MethodNum,
+        IpldBlock::serialize_cbor(&addr).unwrap(),
+    )
+    .unwrap();
+
+    let messages: Vec<AuthenticateMessageParams> = (0..3u8)
+        .map(|i| AuthenticateMessageParams { signature: vec![i], message: vec![i, i] })
+        .collect();
+    rt.expect_validate_caller_any();
+    for (i, result) in [Ok(()), Err(anyhow!("bad signature")), Ok(())].into_iter().enumerate() {
+        let i = i as u8;
+        rt.expect_verify_signature(ExpectedVerifySig {
+            sig: Signature::new_bls(vec![i]),
+            signer: addr,
+            plaintext: vec![i, i],
+            result,
+        });
+    }
+
+    let ret: BatchAuthenticateMessageReturn = rt
+        .call::<AccountActor>(
+            Method::BatchAuthenticateMessageExported as MethodNum,
+            IpldBlock::serialize_cbor(&BatchAuthenticateMessageParams { messages }).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    assert_eq!(
+        BatchReturn::of(&[ExitCode::OK, ExitCode::USR_ILLEGAL_ARGUMENT, ExitCode::OK]),
+        ret.results
+    );
+    rt.verify();
+}
+
 #[test]
 fn test_fallback() {
     let rt = MockRuntime { receiver: Address::new_id(100), ..Default::default() };
@@ -168,6 +217,7 @@ fn test_fallback() {
 
     let addr = Address::new_secp256k1(&[2; fvm_shared::address::SECP_PUB_LEN]).unwrap();
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    expect_activated(&rt, &addr);
     rt.call::<AccountActor>(
         Method::Constructor as MethodNum,
         IpldBlock::serialize_cbor(&addr).unwrap(),
@@ -197,6 +247,17 @@ fn test_fallback() {
     rt.verify();
 }
 
+fn expect_activated(rt: &MockRuntime, addr: &Address) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("account-activated")
+            .field_indexed("id", &rt.receiver.id().unwrap())
+            .field_indexed("address", addr)
+            .build()
+            .unwrap(),
+    );
+}
+
 fn check_state(rt: &MockRuntime) {
     let test_address = Address::new_id(1000);
     let (_, acc) = check_state_invariants(&rt.get_state(), &test_address);
@@ -5,11 +5,14 @@ use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
     ActorError, SYSTEM_ACTOR_ADDR, actor_dispatch, actor_error, extract_send_result,
 };
+use std::collections::BTreeSet;
 
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::econ::TokenAmount;
 
 use fvm_shared::METHOD_CONSTRUCTOR;
+use fvm_shared::address::Protocol;
+use fvm_shared::sys::SendFlags;
 use num_derive::FromPrimitive;
 use num_traits::Zero;
 
@@ -29,6 +32,7 @@ fil_actors_runtime::wasm_trampoline!(Actor);
 pub enum Method {
     Constructor = METHOD_CONSTRUCTOR,
     EpochTick = 2,
+    SetEntries = 3,
 }
 
 /// Constructor parameters for Cron actor, contains entries
@@ -39,6 +43,16 @@ pub struct ConstructorParams {
     pub entries: Vec<Entry>,
 }
 
+/// Parameters to replace the Cron actor's entries, e.g. to reorder or re-budget them at an upgrade.
+#[derive(Default, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct SetEntriesParams {
+    /// The new entries, in the order they are to be called during EpochTick.
+    pub entries: Vec<Entry>,
+    /// Gas budgets for the calls to the new entries, by position.
+    /// An entry without a budget may use all remaining gas.
+    pub gas_limits: Vec<Option<u64>>,
+}
+
 /// Cron actor
 pub struct Actor;
 
@@ -46,9 +60,23 @@ impl Actor {
     /// Constructor for Cron actor
     fn constructor(rt: &impl Runtime, params: ConstructorParams) -> Result<(), ActorError> {
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
-        rt.create(&State { entries: params.entries })?;
+        validate_entries(&params.entries, &[])?;
+        rt.create(&State { entries: params.entries, gas_limits: vec![] })?;
         Ok(())
     }
+
+    /// Replaces the registry of entries called during EpochTick.
+    /// The new entries are called in the given order from the next tick.
+    fn set_entries(rt: &impl Runtime, params: SetEntriesParams) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
+        validate_entries(&params.entries, &params.gas_limits)?;
+        rt.transaction(|st: &mut State, _| {
+            st.entries = params.entries;
+            st.gas_limits = params.gas_limits;
+            Ok(())
+        })
+    }
+
     /// Executes built-in periodic actions, run at every Epoch.
     /// epoch_tick(r) is called after all other messages in the epoch have been applied.
     /// This can be seen as an implicit last message.
@@ -56,13 +84,16 @@ impl Actor {
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
 
         let st: State = rt.state()?;
-        for entry in st.entries {
-            // Intentionally ignore any error when calling cron methods
-            let res = extract_send_result(rt.send_simple(
+        for (i, entry) in st.entries.iter().enumerate() {
+            // Intentionally ignore any error when calling cron methods,
+            // including exhaustion of the entry's gas budget.
+            let res = extract_send_result(rt.send(
                 &entry.receiver,
                 entry.method_num,
                 None,
                 TokenAmount::zero(),
+                st.gas_limit(i),
+                SendFlags::empty(),
             ));
             if let Err(e) = res {
                 log::error!(
@@ -76,6 +107,46 @@ impl Actor {
     }
 }
 
+/// Checks that entries address ID receivers with valid methods and non-zero gas budgets,
+/// and that no receiver and method is registered twice.
+fn validate_entries(entries: &[Entry], gas_limits: &[Option<u64>]) -> Result<(), ActorError> {
+    if gas_limits.len() > entries.len() {
+        return Err(actor_error!(
+            illegal_argument,
+            "{} gas limits given for {} entries",
+            gas_limits.len(),
+            entries.len()
+        ));
+    }
+    let mut seen = BTreeSet::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.receiver.protocol() != Protocol::ID {
+            return Err(actor_error!(
+                illegal_argument,
+                "entry {} receiver address {} must be ID protocol",
+                i,
+                entry.receiver
+            ));
+        }
+        if entry.method_num == 0 {
+            return Err(actor_error!(illegal_argument, "entry {} has invalid method number 0", i));
+        }
+        if gas_limits.get(i) == Some(&Some(0)) {
+            return Err(actor_error!(illegal_argument, "entry {} has zero gas limit", i));
+        }
+        if !seen.insert((entry.receiver, entry.method_num)) {
+            return Err(actor_error!(
+                illegal_argument,
+                "entry {} duplicates receiver {} method {}",
+                i,
+                entry.receiver,
+                entry.method_num
+            ));
+        }
+    }
+    Ok(())
+}
+
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -86,5 +157,6 @@ impl ActorCode for Actor {
     actor_dispatch! {
         Constructor => constructor,
         EpochTick => epoch_tick,
+        SetEntries => set_entries,
     }
 }
@@ -8,8 +8,20 @@ use fvm_shared::address::Address;
 /// Cron actor state which holds entries to call during epoch tick
 #[derive(Default, Serialize_tuple, Deserialize_tuple, Clone, Debug)]
 pub struct State {
-    /// Entries is a set of actors (and corresponding methods) to call during EpochTick.
+    /// Entries is an ordered registry of actors (and corresponding methods) to call during
+    /// EpochTick. Entries are called in registry order, each after the previous has returned.
     pub entries: Vec<Entry>,
+    /// Gas budgets for the calls to entries, by position in the registry.
+    /// An entry without a budget (None, or past the end) may use all remaining gas.
+    #[serde(default)]
+    pub gas_limits: Vec<Option<u64>>,
+}
+
+impl State {
+    /// The gas budget for the call to the entry at an index, if any.
+    pub fn gas_limit(&self, index: usize) -> Option<u64> {
+        self.gas_limits.get(index).copied().flatten()
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
@@ -19,7 +19,18 @@ pub fn check_state_invariants(state: &State) -> (StateSummary, MessageAccumulato
             entry.method_num > 0,
             format!("entry {i} has invalid method number {}", entry.method_num),
         );
+        acc.require(state.gas_limit(i) != Some(0), format!("entry {i} has zero gas limit"));
+        acc.require(
+            !state.entries[..i]
+                .iter()
+                .any(|e| e.receiver == entry.receiver && e.method_num == entry.method_num),
+            format!("entry {i} duplicates receiver {} method {}", entry.receiver, entry.method_num),
+        );
     });
+    acc.require(
+        state.gas_limits.len() <= state.entries.len(),
+        format!("{} gas limits for {} entries", state.gas_limits.len(), state.entries.len()),
+    );
 
     (StateSummary { entry_count: state.entries.len() }, acc)
 }
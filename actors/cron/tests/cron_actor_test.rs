@@ -4,13 +4,16 @@
 use std::cell::RefCell;
 
 use fil_actor_cron::testing::check_state_invariants;
-use fil_actor_cron::{Actor as CronActor, ConstructorParams, Entry, State};
+use fil_actor_cron::{
+    Actor as CronActor, ConstructorParams, Entry, Method, SetEntriesParams, State,
+};
 use fil_actors_runtime::SYSTEM_ACTOR_ADDR;
 use fil_actors_runtime::test_utils::*;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
 use num_traits::Zero;
 
 fn check_state(rt: &MockRuntime) {
@@ -117,6 +120,124 @@ fn epoch_tick_with_entries() {
     epoch_tick_and_verify(&rt);
 }
 
+#[test]
+fn construct_rejects_invalid_entries() {
+    let entry = Entry { receiver: Address::new_id(1001), method_num: 1001 };
+    let invalid = [
+        vec![Entry { receiver: Address::new_id(1001), method_num: 0 }],
+        vec![Entry { receiver: Address::new_actor(b"cron"), method_num: 1001 }],
+        vec![entry.clone(), entry],
+    ];
+    for entries in invalid {
+        let rt = construct_runtime();
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            rt.call::<CronActor>(
+                Method::Constructor as u64,
+                IpldBlock::serialize_cbor(&ConstructorParams { entries }).unwrap(),
+            ),
+        );
+        rt.verify();
+    }
+}
+
+#[test]
+fn decode_state_without_gas_limits() {
+    let entry = Entry { receiver: Address::new_id(1001), method_num: 1001 };
+    let encoded = fvm_ipld_encoding::to_vec(&(vec![entry.clone()],)).unwrap();
+    let state: State = fvm_ipld_encoding::from_slice(&encoded).unwrap();
+    assert_eq!(vec![entry], state.entries);
+    assert!(state.gas_limits.is_empty());
+    assert_eq!(None, state.gas_limit(0));
+}
+
+#[test]
+fn set_entries_reorders_and_budgets_ticks() {
+    let rt = construct_runtime();
+
+    let entry1 = Entry { receiver: Address::new_id(1001), method_num: 1001 };
+    let entry2 = Entry { receiver: Address::new_id(1002), method_num: 1002 };
+    construct_and_verify(&rt, &ConstructorParams { entries: vec![entry1.clone(), entry2.clone()] });
+
+    let params = SetEntriesParams {
+        entries: vec![entry2.clone(), entry1.clone()],
+        gas_limits: vec![None, Some(1_000_000)],
+    };
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    let ret = rt
+        .call::<CronActor>(Method::SetEntries as u64, IpldBlock::serialize_cbor(&params).unwrap())
+        .unwrap();
+    assert!(ret.is_none());
+    rt.verify();
+    let state: State = rt.get_state();
+    assert_eq!(params.entries, state.entries);
+    assert_eq!(params.gas_limits, state.gas_limits);
+
+    // Entries are called in the new order, each with its budget.
+    rt.expect_send_simple(
+        entry2.receiver,
+        entry2.method_num,
+        None,
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    rt.expect_send(
+        entry1.receiver,
+        entry1.method_num,
+        None,
+        TokenAmount::zero(),
+        Some(1_000_000),
+        SendFlags::empty(),
+        None,
+        ExitCode::SYS_OUT_OF_GAS,
+        None,
+    );
+    epoch_tick_and_verify(&rt);
+}
+
+#[test]
+fn set_entries_restricted_to_system() {
+    let rt = construct_runtime();
+    let entry = Entry { receiver: Address::new_id(1001), method_num: 1001 };
+    construct_and_verify(&rt, &ConstructorParams { entries: vec![entry.clone()] });
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1000));
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<CronActor>(
+            Method::SetEntries as u64,
+            IpldBlock::serialize_cbor(&SetEntriesParams::default()).unwrap(),
+        ),
+    );
+    rt.verify();
+
+    // Duplicate entries, zero budgets and budgets without entries are rejected.
+    rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+    let invalid = [
+        SetEntriesParams { entries: vec![entry.clone(), entry.clone()], gas_limits: vec![] },
+        SetEntriesParams { entries: vec![entry.clone()], gas_limits: vec![Some(0)] },
+        SetEntriesParams { entries: vec![entry.clone()], gas_limits: vec![None, Some(1)] },
+    ];
+    for params in invalid {
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            rt.call::<CronActor>(
+                Method::SetEntries as u64,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        rt.verify();
+    }
+
+    let state: State = rt.get_state();
+    assert_eq!(vec![entry], state.entries);
+    check_state(&rt);
+}
+
 fn construct_and_verify(rt: &MockRuntime, params: &ConstructorParams) {
     rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
@@ -82,6 +82,7 @@ pub enum Method {
     BurnExported = frc42_dispatch::method_hash!("Burn"),
     BurnFromExported = frc42_dispatch::method_hash!("BurnFrom"),
     AllowanceExported = frc42_dispatch::method_hash!("Allowance"),
+    GetAllowancesExported = frc42_dispatch::method_hash!("GetAllowances"),
 }
 
 pub struct Actor;
@@ -97,7 +98,7 @@ impl Actor {
             .ok_or_else(|| actor_error!(illegal_argument, "failed to resolve governor address"))?;
 
         let st = State::new(rt.store(), governor).context("failed to create datacap state")?;
-        rt.create(&st)?;
+        rt.create_versioned(&st)?;
         Ok(())
     }
 
@@ -118,7 +119,7 @@ impl Actor {
 
     pub fn total_supply(rt: &impl Runtime) -> Result<TotalSupplyReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
-        let mut st: State = rt.state()?;
+        let mut st: State = rt.versioned_state()?;
         let syscalls = SyscallProvider { rt };
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
         let token = as_token(&mut st, &runtime);
@@ -128,7 +129,7 @@ impl Actor {
     pub fn balance(rt: &impl Runtime, params: BalanceParams) -> Result<BalanceReturn, ActorError> {
         // NOTE: mutability and method caller here are awkward for a read-only call
         rt.validate_immediate_caller_accept_any()?;
-        let mut st: State = rt.state()?;
+        let mut st: State = rt.versioned_state()?;
         let syscalls = SyscallProvider { rt };
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
         let token = as_token(&mut st, &runtime);
@@ -140,7 +141,7 @@ impl Actor {
         params: GetAllowanceParams,
     ) -> Result<GetAllowanceReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
-        let mut st: State = rt.state()?;
+        let mut st: State = rt.versioned_state()?;
         let syscalls = SyscallProvider { rt };
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
         let token = as_token(&mut st, &runtime);
@@ -150,13 +151,33 @@ impl Actor {
             .actor_result()
     }
 
+    /// Returns the allowances granted by an owner to each of a list of operators.
+    /// This method is not part of the fungible token standard.
+    pub fn get_allowances(
+        rt: &impl Runtime,
+        params: GetAllowancesParams,
+    ) -> Result<GetAllowancesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let mut st: State = rt.versioned_state()?;
+        let syscalls = SyscallProvider { rt };
+        let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
+        let token = as_token(&mut st, &runtime);
+        let allowances = params
+            .operators
+            .iter()
+            .map(|operator| token.allowance(&params.owner, operator))
+            .collect::<Result<_, _>>()
+            .actor_result()?;
+        Ok(GetAllowancesReturn { allowances })
+    }
+
     /// Mints new data cap tokens for an address (a verified client).
     /// Simultaneously sets the allowance for any specified operators to effectively infinite.
     /// Only the governor can call this method.
     /// This method is not part of the fungible token standard.
     pub fn mint(rt: &impl Runtime, params: MintParams) -> Result<MintReturn, ActorError> {
         let mut hook = rt
-            .transaction(|st: &mut State, rt| {
+            .versioned_transaction(|st: &mut State, rt| {
                 // Only the governor can mint datacap tokens.
                 rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
                 let operator = st.governor;
@@ -186,7 +207,7 @@ impl Actor {
             })
             .context("state transaction failed")?;
 
-        let mut st: State = rt.state()?;
+        let mut st: State = rt.versioned_state()?;
         let syscalls = SyscallProvider { rt };
         let intermediate = hook.call(&as_actor_runtime(&syscalls)).actor_result()?;
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
@@ -198,7 +219,7 @@ impl Actor {
     /// This method is not part of the fungible token standard, and is named distinctly from
     /// "burn" to reflect that distinction.
     pub fn destroy(rt: &impl Runtime, params: DestroyParams) -> Result<BurnReturn, ActorError> {
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             // Only the governor can destroy datacap tokens on behalf of a holder.
             rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
 
@@ -229,7 +250,7 @@ impl Actor {
         let to_address = Address::new_id(to);
 
         let mut hook = rt
-            .transaction(|st: &mut State, rt| {
+            .versioned_transaction(|st: &mut State, rt| {
                 let allowed = to_address == st.governor || *from == st.governor;
                 if !allowed {
                     return Err(actor_error!(
@@ -256,7 +277,7 @@ impl Actor {
             })
             .context("state transaction failed")?;
 
-        let mut st: State = rt.state()?;
+        let mut st: State = rt.versioned_state()?;
         let syscalls = SyscallProvider { rt };
         let intermediate = hook.call(&as_actor_runtime(&syscalls)).actor_result()?;
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
@@ -280,7 +301,7 @@ impl Actor {
         let to_address = Address::new_id(to);
 
         let mut hook = rt
-            .transaction(|st: &mut State, rt| {
+            .versioned_transaction(|st: &mut State, rt| {
                 let allowed = to_address == st.governor;
                 if !allowed {
                     return Err(actor_error!(
@@ -308,7 +329,7 @@ impl Actor {
             })
             .context("state transaction failed")?;
 
-        let mut st: State = rt.state()?;
+        let mut st: State = rt.versioned_state()?;
         let syscalls = SyscallProvider { rt };
         let intermediate = hook.call(&as_actor_runtime(&syscalls)).actor_result()?;
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
@@ -323,7 +344,7 @@ impl Actor {
         let owner = rt.message().caller();
         let operator = params.operator;
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -343,7 +364,7 @@ impl Actor {
         let owner = &rt.message().caller();
         let operator = &params.operator;
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -363,7 +384,7 @@ impl Actor {
         let owner = &rt.message().caller();
         let operator = &params.operator;
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -379,7 +400,7 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
         let owner = &rt.message().caller();
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -396,7 +417,7 @@ impl Actor {
         let operator = &rt.message().caller();
         let owner = &params.owner;
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -521,5 +542,6 @@ impl ActorCode for Actor {
         BurnExported => burn,
         BurnFromExported => burn_from,
         AllowanceExported => allowance,
+        GetAllowancesExported => get_allowances,
     }
 }
@@ -6,7 +6,7 @@ use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 
-use fil_actors_runtime::{ActorError, AsActorError};
+use fil_actors_runtime::{ActorError, AsActorError, VersionedState};
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct State {
@@ -14,6 +14,12 @@ pub struct State {
     pub token: token::state::TokenState,
 }
 
+// The state is still encoded as it was before versioning was adopted.
+// A schema change should bump the version and upgrade state stored at version 0.
+impl VersionedState for State {
+    const VERSION: u64 = 0;
+}
+
 impl State {
     pub fn new<BS: Blockstore>(store: &BS, governor: Address) -> Result<State, ActorError> {
         let token_state = token::state::TokenState::new(store)
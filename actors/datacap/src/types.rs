@@ -1,3 +1,4 @@
+pub use fil_actors_runtime::builtin::datacap::{DestroyParams, MintParams};
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
@@ -44,6 +45,19 @@ pub struct GetAllowanceReturn {
     pub allowance: TokenAmount,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetAllowancesParams {
+    pub owner: Address,
+    pub operators: Vec<Address>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetAllowancesReturn {
+    /// Allowances granted by the owner, parallel to the requested operators.
+    pub allowances: Vec<TokenAmount>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
 pub struct IncreaseAllowanceReturn {
@@ -62,22 +76,6 @@ pub struct RevokeAllowanceReturn {
     pub old_allowance: TokenAmount,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-pub struct MintParams {
-    // Recipient of the newly minted tokens.
-    pub to: Address,
-    // Amount of tokens to mint.
-    pub amount: TokenAmount,
-    // Addresses to be granted effectively-infinite operator allowance for the recipient.
-    pub operators: Vec<Address>,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-pub struct DestroyParams {
-    pub owner: Address,
-    pub amount: TokenAmount,
-}
-
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
 pub struct GranularityReturn {
@@ -98,7 +98,7 @@ mod mint {
     fn requires_verifreg_caller() {
         let (rt, h) = make_harness();
         let amt = TokenAmount::from_whole(1);
-        let params = MintParams { to: *ALICE, amount: amt, operators: vec![] };
+        let params = MintParams { to: *ALICE, amount: amt.try_into().unwrap(), operators: vec![] };
 
         rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
         rt.set_caller(*MARKET_ACTOR_CODE_ID, STORAGE_MARKET_ACTOR_ADDR);
@@ -240,7 +240,7 @@ mod destroy {
         h.mint(&rt, &ALICE, &(2 * amt.clone()), vec![*BOB]).unwrap();
 
         // destroying from operator does not work
-        let params = DestroyParams { owner: *ALICE, amount: amt.clone() };
+        let params = DestroyParams { owner: *ALICE, amount: amt.clone().try_into().unwrap() };
 
         rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *BOB);
@@ -261,6 +261,31 @@ mod destroy {
     }
 }
 
+mod allowance {
+    use crate::{ALICE, BOB, CARLA, make_harness};
+    use fil_actor_datacap::INFINITE_ALLOWANCE;
+    use fvm_shared::econ::TokenAmount;
+    use num_traits::Zero;
+
+    #[test]
+    fn get_allowances_in_batch() {
+        let (rt, h) = make_harness();
+        let amt = TokenAmount::from_whole(1);
+        h.mint(&rt, &ALICE, &amt, vec![*BOB]).unwrap();
+
+        let allowances = h.get_allowances(&rt, &ALICE, vec![*BOB, *CARLA, h.governor]);
+        assert_eq!(
+            vec![INFINITE_ALLOWANCE.clone(), TokenAmount::zero(), TokenAmount::zero()],
+            allowances
+        );
+        for (operator, allowance) in [*BOB, *CARLA, h.governor].iter().zip(&allowances) {
+            assert_eq!(&h.get_allowance_between(&rt, &ALICE, operator), allowance);
+        }
+        assert!(h.get_allowances(&rt, &ALICE, vec![]).is_empty());
+        h.check_state(&rt);
+    }
+}
+
 fn make_harness() -> (MockRuntime, Harness) {
     let rt = new_runtime();
     let h = Harness { governor: VERIFIED_REGISTRY_ACTOR_ADDR };
@@ -13,7 +13,10 @@ use fvm_shared::error::ExitCode;
 use num_traits::Zero;
 
 use fil_actor_datacap::testing::check_state_invariants;
-use fil_actor_datacap::{Actor as DataCapActor, DestroyParams, Method, MintParams, State};
+use fil_actor_datacap::{
+    Actor as DataCapActor, DestroyParams, GetAllowancesParams, GetAllowancesReturn, Method,
+    MintParams, State,
+};
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::test_utils::*;
@@ -95,7 +98,7 @@ impl Harness {
             ExitCode::OK,
         );
 
-        let params = MintParams { to: *to, amount: amount.clone(), operators };
+        let params = MintParams { to: *to, amount: amount.clone().try_into().unwrap(), operators };
         rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
         let ret = rt.call::<DataCapActor>(
             Method::MintExported as MethodNum,
@@ -114,7 +117,7 @@ impl Harness {
     ) -> Result<BurnReturn, ActorError> {
         rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
 
-        let params = DestroyParams { owner: *owner, amount: amount.clone() };
+        let params = DestroyParams { owner: *owner, amount: amount.clone().try_into().unwrap() };
 
         rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
         let ret = rt.call::<DataCapActor>(
@@ -241,6 +244,27 @@ impl Harness {
         ret
     }
 
+    pub fn get_allowances(
+        &self,
+        rt: &MockRuntime,
+        owner: &Address,
+        operators: Vec<Address>,
+    ) -> Vec<TokenAmount> {
+        rt.expect_validate_caller_any();
+        let ret: GetAllowancesReturn = rt
+            .call::<DataCapActor>(
+                Method::GetAllowancesExported as MethodNum,
+                IpldBlock::serialize_cbor(&GetAllowancesParams { owner: *owner, operators })
+                    .unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret.allowances
+    }
+
     // Reads allowance from state directly
     pub fn get_allowance_between(
         &self,
@@ -0,0 +1,152 @@
+use fil_actors_runtime::ActorError;
+use fvm_ipld_encoding::BytesDe;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+
+use crate::uints::U256;
+
+/// Selector of Solidity's `Error(string)`, raised by `require` and `revert` with a message.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of Solidity's `Panic(uint256)`, raised by failed assertions, arithmetic errors etc.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+const WORD: usize = 32;
+
+/// The reason an EVM contract reverted, decoded from its revert data.
+///
+/// A reverting contract's revert data is carried, CBOR-encoded as a byte string, in the data of
+/// the `ActorError` with which the invocation fails, so native callers can decode the reason
+/// from the error (or the exit data of a failed send) on-chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// The contract reverted without data.
+    Empty,
+    /// A message raised with `Error(string)`.
+    Error(String),
+    /// A panic code raised with `Panic(uint256)`.
+    Panic(U256),
+    /// Revert data in any other form, such as a custom error, left undecoded.
+    Custom(Vec<u8>),
+}
+
+impl RevertReason {
+    /// Decodes raw revert data.
+    /// Data which does not decode as a well-formed `Error(string)` or `Panic(uint256)` is
+    /// returned as `Custom`.
+    pub fn decode(data: &[u8]) -> Self {
+        if data.is_empty() {
+            return RevertReason::Empty;
+        }
+        let (selector, payload) = data.split_at(data.len().min(4));
+        if selector == ERROR_SELECTOR {
+            if let Some(msg) = decode_string(payload) {
+                return RevertReason::Error(msg);
+            }
+        } else if selector == PANIC_SELECTOR && payload.len() == WORD {
+            return RevertReason::Panic(U256::from_big_endian(payload));
+        }
+        RevertReason::Custom(data.to_vec())
+    }
+
+    /// Decodes the revert data carried by the data of an EVM actor's error.
+    /// Returns `None` if the data is not a byte string.
+    pub fn from_error_data(data: &IpldBlock) -> Option<Self> {
+        let BytesDe(bytes) = data.deserialize().ok()?;
+        Some(Self::decode(&bytes))
+    }
+
+    /// Decodes the revert data carried by an error returned by an EVM actor.
+    /// Returns `None` if the error carries no data, or data which is not a byte string.
+    pub fn from_error(err: &ActorError) -> Option<Self> {
+        err.data().and_then(Self::from_error_data)
+    }
+}
+
+impl std::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevertReason::Empty => f.write_str("no reason"),
+            RevertReason::Error(msg) => write!(f, "{:?}", msg),
+            RevertReason::Panic(code) => write!(f, "panic 0x{:x}", code),
+            RevertReason::Custom(data) => write!(f, "custom error 0x{}", hex::encode(data)),
+        }
+    }
+}
+
+/// Decodes an ABI-encoded string: an offset to a length-prefixed, zero-padded byte string.
+fn decode_string(payload: &[u8]) -> Option<String> {
+    let offset = decode_usize(payload.get(..WORD)?)?;
+    let len_end = offset.checked_add(WORD)?;
+    let len = decode_usize(payload.get(offset..len_end)?)?;
+    let bytes = payload.get(len_end..len_end.checked_add(len)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+/// Decodes a 32 byte big-endian word, if its value fits a usize.
+fn decode_usize(word: &[u8]) -> Option<usize> {
+    let value = U256::from_big_endian(word);
+    if value > U256::from(usize::MAX) {
+        return None;
+    }
+    Some(value.as_usize())
+}
+
+#[cfg(test)]
+mod tests {
+    use fvm_ipld_encoding::BytesSer;
+    use fvm_shared::error::ExitCode;
+
+    use super::*;
+
+    fn word(v: usize) -> Vec<u8> {
+        U256::from(v).to_big_endian().to_vec()
+    }
+
+    fn error_data(msg: &str) -> Vec<u8> {
+        let mut padded = msg.as_bytes().to_vec();
+        padded.resize(msg.len().div_ceil(WORD) * WORD, 0);
+        [ERROR_SELECTOR.to_vec(), word(WORD), word(msg.len()), padded].concat()
+    }
+
+    #[test]
+    fn decodes_error_string() {
+        assert_eq!(
+            RevertReason::Error("insufficient balance".into()),
+            RevertReason::decode(&error_data("insufficient balance"))
+        );
+        assert_eq!(RevertReason::Error("".into()), RevertReason::decode(&error_data("")));
+    }
+
+    #[test]
+    fn decodes_panic_code() {
+        let data = [PANIC_SELECTOR.to_vec(), word(0x11)].concat();
+        assert_eq!(RevertReason::Panic(U256::from(0x11)), RevertReason::decode(&data));
+        assert_eq!("panic 0x11", RevertReason::decode(&data).to_string());
+    }
+
+    #[test]
+    fn leaves_other_data_undecoded() {
+        assert_eq!(RevertReason::Empty, RevertReason::decode(&[]));
+
+        let custom = vec![0xde, 0xad, 0xbe, 0xef];
+        assert_eq!(RevertReason::Custom(custom.clone()), RevertReason::decode(&custom));
+
+        // Malformed encodings of known selectors are not decoded.
+        let mut truncated = error_data("insufficient balance");
+        truncated.truncate(truncated.len() - WORD);
+        assert_eq!(RevertReason::Custom(truncated.clone()), RevertReason::decode(&truncated));
+        let overflowing = [ERROR_SELECTOR.to_vec(), vec![0xff; WORD]].concat();
+        assert_eq!(RevertReason::Custom(overflowing.clone()), RevertReason::decode(&overflowing));
+        let short_panic = [PANIC_SELECTOR.to_vec(), vec![0x11]].concat();
+        assert_eq!(RevertReason::Custom(short_panic.clone()), RevertReason::decode(&short_panic));
+    }
+
+    #[test]
+    fn decodes_error_data() {
+        let data = IpldBlock::serialize_cbor(&BytesSer(&error_data("nope"))).unwrap();
+        let err = ActorError::unchecked_with_data(ExitCode::new(33), "reverted".into(), data);
+        assert_eq!(Some(RevertReason::Error("nope".into())), RevertReason::from_error(&err));
+
+        let err = ActorError::unchecked(ExitCode::new(33), "reverted".into());
+        assert_eq!(None, RevertReason::from_error(&err));
+    }
+}
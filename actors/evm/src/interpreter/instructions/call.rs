@@ -25,9 +25,6 @@ use {
     fvm_shared::error::ErrorNumber,
 };
 
-/// The gas granted on bare "transfers".
-const TRANSFER_GAS_LIMIT: U256 = U256::from_u64(10_000_000);
-
 pub fn calldataload(
     state: &mut ExecutionState,
     _: &System<impl Runtime>,
@@ -153,7 +150,7 @@ pub fn call_generic<RT: Runtime>(
 ) -> Result<U256, ActorError> {
     let ExecutionState { stack: _, memory, .. } = state;
 
-    let (mut gas, dst, value, input_offset, input_size, output_offset, output_size) = params;
+    let (gas, dst, value, input_offset, input_size, output_offset, output_size) = params;
 
     if system.readonly && value > U256::zero() {
         // non-zero sends are side-effects and hence a static mode violation
@@ -196,10 +193,8 @@ pub fn call_generic<RT: Runtime>(
             let call_result = match kind {
                 CallKind::Call | CallKind::StaticCall => {
                     let dst_addr: Address = dst.into();
-                    if (gas == 0 && value > 0) || (gas == 2300 && value == 0) {
-                        // We provide enough gas for the transfer to succeed in all case.
-                        gas = TRANSFER_GAS_LIMIT;
-                    }
+                    // We provide enough gas for bare transfers to succeed in all cases.
+                    let gas_limit = system.value_call_gas_limit(gas, value);
                     let params = if input_data.is_empty() {
                         None
                     } else {
@@ -220,7 +215,7 @@ pub fn call_generic<RT: Runtime>(
                         Method::InvokeContract as MethodNum,
                         params,
                         value,
-                        Some(system.call_gas_limit(gas)),
+                        Some(gas_limit),
                         send_flags,
                     )? {
                         Ok(resp) => {
@@ -1,6 +1,6 @@
 use crate::BytecodeHash;
 use crate::interpreter::instructions::memory::copy_to_memory;
-use crate::interpreter::precompiles::Precompiles;
+use crate::interpreter::precompiles::is_reserved_precompile_address;
 use cid::Cid;
 use fil_actors_evm_shared::address::EthAddress;
 use fil_actors_evm_shared::uints::U256;
@@ -29,9 +29,9 @@ pub fn extcodesize(
         ContractType::EVM(addr) => {
             get_evm_bytecode(system, &addr).map(|bytecode| bytecode.len())?
         }
+        // the single 0xFE byte returned by EXTCODECOPY
         ContractType::Native(_) => 1,
-        // account, not found, and precompiles are 0 size
-        _ => 0,
+        ContractType::Account | ContractType::Precompile | ContractType::NotFound => 0,
     };
 
     Ok(len.into())
@@ -82,15 +82,31 @@ pub fn extcodecopy(
         ContractType::NotFound | ContractType::Account | ContractType::Precompile => Vec::new(),
         // calling EXTCODECOPY on native actors results with a single byte 0xFE which solidtiy uses for its `assert`/`throw` methods
         // and in general invalid EVM bytecode
-        _ => vec![0xFE],
+        ContractType::Native(_) => vec![0xFE],
     };
 
     copy_to_memory(&mut state.memory, dest_offset, size, data_offset, bytecode.as_slice(), true)
 }
 
+/// The kind of actor at an address, as seen by the EXTCODE* instructions.
+///
+/// | Target                               | EXTCODESIZE | EXTCODEHASH         | EXTCODECOPY |
+/// |--------------------------------------|-------------|---------------------|-------------|
+/// | EVM contract                         | code length | keccak(code)        | code        |
+/// | EVM contract (self-destructed)       | 0           | keccak("")          | empty       |
+/// | Native actor (any other actor code)  | 1           | keccak([0xfe])      | [0xfe]      |
+/// | Account, EthAccount, or Placeholder  | 0           | keccak("")          | empty       |
+/// | Reserved precompile address          | 0           | keccak("")          | empty       |
+/// | No actor                             | 0           | 0                   | empty       |
+///
+/// The reserved precompile ranges (`0x00..00NN` and `0xfe00..00NN`, NN > 0) are treated as
+/// precompiles whether or not a precompile is implemented at the address, matching how calls
+/// to those addresses are routed. In particular, contract checks based on EXTCODESIZE
+/// (e.g. Solidity's `isContract`) consider only EVM contracts and native actors to be contracts.
 #[derive(Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum ContractType {
+    /// Any address in the reserved precompile ranges.
     Precompile,
     /// EVM ID Address and the CID of the actor (not the bytecode)
     EVM(Address),
@@ -101,9 +117,9 @@ pub enum ContractType {
 
 /// Resolves an address to the address type
 pub fn get_contract_type<RT: Runtime>(rt: &RT, addr: &EthAddress) -> ContractType {
-    // precompiles cant be resolved by the FVM
-    // addresses passed in precompile range will be returned as NotFound; EAM asserts that no actors can be deployed in the precompile reserved range
-    if Precompiles::<RT>::is_precompile(addr) {
+    // precompiles cant be resolved by the FVM, and EAM asserts that no actors can be deployed in
+    // the precompile reserved range, so the whole range is treated as precompiles (implemented or not)
+    if is_reserved_precompile_address(addr) {
         return ContractType::Precompile;
     }
 
@@ -156,6 +172,7 @@ mod tests {
     use crate::BytecodeHash;
     use crate::evm_unit_test;
     use cid::Cid;
+    use fil_actors_evm_shared::address::EthAddress;
     use fil_actors_evm_shared::uints::U256;
     use fil_actors_runtime::runtime::Primitives;
     use fil_actors_runtime::test_utils::EVM_ACTOR_CODE_ID;
@@ -375,6 +392,57 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_ext_reserved_precompile() {
+        // An implemented precompile, a disabled native precompile, and an unassigned address.
+        let addrs = [[0x01, 0x00], [0x04, 0xfe], [0x0a, 0x00]].map(|[index, prefix]| {
+            let mut addr = [0u8; 20];
+            addr[0] = prefix;
+            addr[19] = index;
+            EthAddress(addr)
+        });
+        for addr in addrs {
+            evm_unit_test! {
+                (m) {
+                    EXTCODESIZE;
+                    EXTCODEHASH;
+                }
+                m.state.stack.push(addr.as_evm_word()).unwrap();
+                assert!(m.step().is_ok(), "execution step failed");
+                assert_eq!(m.state.stack.pop().unwrap(), U256::from(0));
+                m.state.stack.push(addr.as_evm_word()).unwrap();
+                assert!(m.step().is_ok(), "execution step failed");
+                assert_eq!(m.state.stack.pop().unwrap(), U256::from(BytecodeHash::EMPTY));
+            };
+        }
+    }
+
+    #[test]
+    fn test_ext_native_actor() {
+        evm_unit_test! {
+            (rt) {
+                rt.set_address_actor_type(FilAddress::new_id(1001), Cid::default());
+            }
+            (m) {
+                EXTCODESIZE;
+                EXTCODEHASH;
+                EXTCODECOPY;
+            }
+            m.state.stack.push(EthAddress::from_id(1001).as_evm_word()).unwrap();
+            assert!(m.step().is_ok(), "execution step failed");
+            assert_eq!(m.state.stack.pop().unwrap(), U256::from(1));
+            m.state.stack.push(EthAddress::from_id(1001).as_evm_word()).unwrap();
+            assert!(m.step().is_ok(), "execution step failed");
+            assert_eq!(m.state.stack.pop().unwrap(), U256::from(BytecodeHash::NATIVE_ACTOR));
+            m.state.stack.push(U256::from(1)).unwrap(); // length
+            m.state.stack.push(U256::from(0)).unwrap(); // offset
+            m.state.stack.push(U256::from(0)).unwrap(); // destOffset
+            m.state.stack.push(EthAddress::from_id(1001).as_evm_word()).unwrap();
+            assert!(m.step().is_ok(), "execution step failed");
+            assert_eq!(m.state.memory[0..1], [0xfe]);
+        };
+    }
+
     #[test]
     fn test_extcodehash_nonexist() {
         evm_unit_test! {
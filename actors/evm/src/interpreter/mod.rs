@@ -14,7 +14,7 @@ pub use {
     bytecode::Bytecode,
     execution::{ExecutionState, execute, opcodes},
     output::{Outcome, Output},
-    system::System,
+    system::{CallGasRules, System},
 };
 
 /// The kind of call-like instruction.
@@ -1,8 +1,12 @@
-use crate::{EVM_MAX_RESERVED_METHOD, EVM_WORD_SIZE};
+use crate::{EVM_MAX_RESERVED_METHOD, EVM_WORD_SIZE, NATIVE_VALUE_RECEIVER_TYPE};
 use fil_actors_evm_shared::uints::U256;
-use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::runtime::{Runtime, RuntimePolicy};
+use fvm_actor_utils::receiver::UniversalReceiverParams;
+use fvm_ipld_encoding::RawBytes;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::{METHOD_SEND, address::Address, econ::TokenAmount, sys::SendFlags};
+use fvm_shared::{
+    METHOD_SEND, address::Address, clock::ChainEpoch, econ::TokenAmount, sys::SendFlags,
+};
 
 use crate::interpreter::{CallKind, System};
 
@@ -221,6 +225,81 @@ pub(super) fn call_actor_shared<RT: Runtime>(
     Ok(output)
 }
 
+/// FRC-42 method number of the universal receiver hook.
+const RECEIVER_HOOK_METHOD: u64 = frc42_dispatch::method_hash!("Receive");
+
+/// Sends value to an actor by address, optionally through the actor's receiver hook.
+///
+/// Parameters are encoded according to the solidity ABI, with no function selector:
+///
+/// ```text
+/// u256  value
+/// u64   flags (1 to invoke the recipient's receiver hook, 0 for a bare send)
+/// bytes address
+/// ```
+///
+/// A bare send transfers the value with `METHOD_SEND`, which actors accept without running
+/// any code. With the hook flag, the value is instead sent with a call to the recipient's FRC-42
+/// `Receive` method, with receiver type `NATIVE_VALUE_RECEIVER_TYPE` and an empty payload, so an
+/// actor which must account for the funds it receives can accept or reject them. If the hook
+/// fails, or the recipient has none, the transfer is not made and the value stays with the
+/// contract.
+///
+/// Returns (also solidity ABI encoded):
+///
+/// ```text
+/// i256  exit_code
+/// ```
+///
+/// for exit_code:
+/// - negative values are system errors
+/// - positive are user errors (from the receiver hook)
+/// - 0 is success
+pub(super) fn send_value<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    if ctx.call_type != CallKind::DelegateCall {
+        return Err(PrecompileError::CallForbidden);
+    }
+
+    let mut input_params = ValueReader::new(input);
+    let value: U256 = input_params.read_value()?;
+    let flags: u64 = input_params.read_value()?;
+    let addr_off: u32 = input_params.read_value()?;
+    input_params.seek(addr_off.try_into()?);
+    let addr_len: u32 = input_params.read_value()?;
+    let addr_bytes = input_params.read_padded(addr_len.try_into()?);
+    let address = Address::from_bytes(&addr_bytes).map_err(|_| PrecompileError::InvalidInput)?;
+
+    let (method, params) = match flags {
+        0 => (METHOD_SEND, None),
+        1 => {
+            let params = UniversalReceiverParams {
+                type_: NATIVE_VALUE_RECEIVER_TYPE,
+                payload: RawBytes::default(),
+            };
+            (RECEIVER_HOOK_METHOD, IpldBlock::serialize_cbor(&params).unwrap())
+        }
+        _ => return Err(PrecompileError::InvalidInput),
+    };
+
+    let result = system.send_raw(
+        &address,
+        method,
+        params,
+        TokenAmount::from(&value),
+        Some(system.call_gas_limit(ctx.gas)),
+        SendFlags::empty(),
+    )?;
+    let exit_code = match result {
+        Err(errno) => U256::from(errno as u32).i256_neg(),
+        Ok(resp) => U256::from(resp.exit_code.value()),
+    };
+    Ok(exit_code.to_bytes().to_vec())
+}
+
 /// Params:
 ///
 /// | Param            | Value                     |
@@ -238,3 +317,49 @@ pub(super) fn get_randomness<RT: Runtime>(
     let randomness = system.rt.get_beacon_randomness(randomness_epoch);
     randomness.map(|r| r.to_vec()).map_err(|_| PrecompileError::InvalidInput)
 }
+
+/// Returns the current epoch and the timestamp of the current tipset, as UNIX seconds.
+/// Input is ignored.
+///
+/// | Return           | Value                     |
+/// |------------------|---------------------------|
+/// | epoch            | U256 - low i64            |
+/// | timestamp        | U256 - low u64            |
+pub(super) fn get_chain_info<RT: Runtime>(
+    system: &mut System<RT>,
+    _: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut output = Vec::with_capacity(2 * EVM_WORD_SIZE);
+    output.extend_from_slice(&U256::from(system.rt.curr_epoch()).to_bytes());
+    output.extend_from_slice(&U256::from(system.rt.tipset_timestamp()).to_bytes());
+    Ok(output)
+}
+
+/// Params:
+///
+/// | Param            | Value                     |
+/// |------------------|---------------------------|
+/// | epoch            | U256 - low i64            |
+///
+/// Returns the bytes of the CID of the tipset at a recent epoch.
+/// The lookback is bounded by chain finality: the epoch must be earlier than the current epoch,
+/// and no more than `chain_finality - 1` epochs earlier. The CID of the oldest such tipset is
+/// available, and the next older is not.
+/// Empty array if the epoch is outside these bounds or the CID is unavailable, or `InvalidInput`
+/// if the epoch is larger than 2^63.
+pub(super) fn get_tipset_cid<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut input_params = ValueReader::new(input);
+    let epoch: ChainEpoch = input_params.read_value()?;
+
+    let curr_epoch = system.rt.curr_epoch();
+    let oldest = curr_epoch - (system.rt.policy().chain_finality - 1);
+    if epoch < oldest.max(0) || epoch >= curr_epoch {
+        return Ok(Vec::new());
+    }
+    Ok(system.rt.tipset_cid(epoch).map(|cid| cid.to_bytes()).unwrap_or_default())
+}
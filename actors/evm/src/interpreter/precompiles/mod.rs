@@ -14,7 +14,10 @@ mod evm;
 mod fvm;
 
 use evm::{blake2f, ec_add, ec_mul, ec_pairing, ec_recover, identity, modexp, ripemd160, sha256};
-use fvm::{call_actor, call_actor_id, get_randomness, lookup_delegated_address, resolve_address};
+use fvm::{
+    call_actor, call_actor_id, get_chain_info, get_randomness, get_tipset_cid,
+    lookup_delegated_address, resolve_address, send_value,
+};
 
 type PrecompileFn<RT> = fn(&mut System<RT>, &[u8], PrecompileContext) -> PrecompileResult;
 pub type PrecompileResult = Result<Vec<u8>, PrecompileError>;
@@ -42,13 +45,16 @@ pub struct Precompiles<RT>(PhantomData<RT>);
 
 impl<RT: Runtime> Precompiles<RT> {
     /// FEVM specific precompiles (0xfe prefix)
-    const NATIVE_PRECOMPILES: PrecompileTable<RT, 6> = PrecompileTable([
+    const NATIVE_PRECOMPILES: PrecompileTable<RT, 9> = PrecompileTable([
         Some(resolve_address::<RT>),          // 0xfe00..01
         Some(lookup_delegated_address::<RT>), // 0xfe00..02
         Some(call_actor::<RT>),               // 0xfe00..03
         None,                                 // 0xfe00..04 get_actor_type DISABLED
         Some(call_actor_id::<RT>),            // 0xfe00..05
         Some(get_randomness::<RT>),           // 0xfe00..06
+        Some(get_chain_info::<RT>),           // 0xfe00..07
+        Some(get_tipset_cid::<RT>),           // 0xfe00..08
+        Some(send_value::<RT>),               // 0xfe00..09
     ]);
 
     /// EVM specific precompiles
@@ -94,8 +100,8 @@ impl<RT: Runtime> Precompiles<RT> {
         // Then transfer the value. We do this second because we don't want to transfer if the
         // precompile reverts.
         //
-        // This shouldn't be observable as the only precompile with side-effects is the call_actor
-        // precompile, and that precompile can only be called with delegatecall.
+        // This shouldn't be observable as the only precompiles with side-effects are the
+        // call_actor and send_value precompiles, and those can only be called with delegatecall.
         if !context.value.is_zero() {
             let fil_addr: Address = precompile_addr.into();
             system
@@ -106,7 +112,7 @@ impl<RT: Runtime> Precompiles<RT> {
     }
 
     /// Checks if word is an existing precompile
-    #[inline]
+    #[cfg(test)]
     pub fn is_precompile(addr: &EthAddress) -> bool {
         !addr.is_null() && Self::lookup_precompile(addr).is_some()
     }
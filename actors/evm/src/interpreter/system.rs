@@ -13,6 +13,7 @@ use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::{ErrorNumber, ExitCode};
 use fvm_shared::sys::SendFlags;
+use fvm_shared::version::NetworkVersion;
 use fvm_shared::{IPLD_RAW, METHOD_SEND, MethodNum, Response};
 use multihash_codetable::Code;
 
@@ -20,7 +21,10 @@ use crate::BytecodeHash;
 use crate::state::{State, Tombstone, TransientData, TransientDataLifespan};
 
 use cid::Cid;
-use fil_actors_runtime::{ActorError, runtime::Runtime};
+use fil_actors_runtime::{
+    ActorError,
+    runtime::{Policy, Runtime},
+};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_kamt::{AsHashedKey, Config as KamtConfig, Kamt};
 
@@ -542,6 +546,62 @@ impl<'r, RT: Runtime> System<'r, RT> {
         let gas = gas.to_u64_saturating();
         std::cmp::min(gas, gas_rsvp)
     }
+
+    /// Return the gas limit for a CALL or STATICCALL given the requested gas limit and value,
+    /// following the call gas rules in effect at the current network version.
+    pub fn value_call_gas_limit(&self, gas: U256, value: U256) -> u64 {
+        CallGasRules::for_network(self.rt.policy(), self.rt.network_version()).gas_limit(
+            gas,
+            value,
+            self.rt.gas_available(),
+        )
+    }
+}
+
+/// The gas granted on bare "transfers".
+pub const TRANSFER_GAS_LIMIT: u64 = 10_000_000;
+
+/// Rules for the gas forwarded to the callee of a CALL or STATICCALL.
+///
+/// Ethereum grants the callee of a value-bearing call a 2300 gas stipend, which is too little
+/// to be meaningful under FVM gas. The stipend is instead the transfer gas limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallGasRules {
+    /// Bare transfers (value with no gas, or 2300 gas with no value) are granted the transfer
+    /// gas limit in place of the requested gas. Other calls are granted only the requested gas.
+    Legacy,
+    /// As in Ethereum, value-bearing calls are granted the stipend in addition to the requested
+    /// gas, subject to the 63/64 cap so the caller always keeps a reserve. Calls with 2300 gas
+    /// and no value (as made by Solidity's `transfer` of zero) are still granted the transfer
+    /// gas limit.
+    Ethereum,
+}
+
+impl CallGasRules {
+    /// Returns the call gas rules in effect at a network version under a policy.
+    pub fn for_network(policy: &Policy, nv: NetworkVersion) -> Self {
+        if nv >= policy.evm_ethereum_call_gas_version { Self::Ethereum } else { Self::Legacy }
+    }
+
+    /// Returns the gas limit for a call given the requested gas limit, the value transferred,
+    /// and the gas available to the caller.
+    /// The gas granted, including any stipend, is capped at 63/64 of the available gas.
+    pub fn gas_limit(self, gas: U256, value: U256, gas_available: u64) -> u64 {
+        let gas_rsvp = (63 * gas_available) / 64;
+        let stipend_only = (gas == U256::zero() && value > U256::zero())
+            || (gas == U256::from(2300) && value == U256::zero());
+        match self {
+            Self::Legacy if stipend_only => std::cmp::min(TRANSFER_GAS_LIMIT, gas_rsvp),
+            Self::Legacy => std::cmp::min(gas.to_u64_saturating(), gas_rsvp),
+            Self::Ethereum if value == U256::zero() => {
+                let gas = if stipend_only { TRANSFER_GAS_LIMIT } else { gas.to_u64_saturating() };
+                std::cmp::min(gas, gas_rsvp)
+            }
+            Self::Ethereum => {
+                std::cmp::min(gas.to_u64_saturating().saturating_add(TRANSFER_GAS_LIMIT), gas_rsvp)
+            }
+        }
+    }
 }
 
 /// Returns the current transient data lifespan based on the execution environment.
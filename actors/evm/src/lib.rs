@@ -1,4 +1,5 @@
 use fil_actors_evm_shared::address::EthAddress;
+use fil_actors_evm_shared::revert::RevertReason;
 use fil_actors_runtime::{
     ActorError, AsActorError, EAM_ACTOR_ADDR, INIT_ACTOR_ADDR, WithCodec,
     actor_dispatch_unrestricted, actor_error,
@@ -41,6 +42,10 @@ pub const EVM_CONTRACT_ILLEGAL_MEMORY_ACCESS: ExitCode = ExitCode::new(38);
 pub const EVM_CONTRACT_BAD_JUMPDEST: ExitCode = ExitCode::new(39);
 pub const EVM_CONTRACT_SELFDESTRUCT_FAILED: ExitCode = ExitCode::new(40);
 
+/// Receiver hook type with which the send_value precompile notifies a recipient of native value
+/// sent by a contract. The hook's payload is empty: the value and sender are those of the message.
+pub const NATIVE_VALUE_RECEIVER_TYPE: u32 = frc42_dispatch::method_hash!("FIL") as u32;
+
 const EVM_MAX_RESERVED_METHOD: u64 = 1023;
 pub const NATIVE_METHOD_SIGNATURE: &str = "handle_filecoin_method(uint64,uint64,bytes)";
 pub const NATIVE_METHOD_SELECTOR: [u8; 4] = [0x86, 0x8e, 0x10, 0xc4];
@@ -138,7 +143,7 @@ fn initialize_evm_contract(
         }
         Outcome::Revert => Err(ActorError::unchecked_with_data(
             EVM_CONTRACT_REVERTED,
-            "constructor reverted".to_string(),
+            format!("constructor reverted: {}", RevertReason::decode(&output.return_data)),
             IpldBlock::serialize_cbor(&BytesSer(&output.return_data)).unwrap(),
         )),
     }
@@ -177,7 +182,11 @@ where
         }
         Outcome::Revert => Err(ActorError::unchecked_with_data(
             EVM_CONTRACT_REVERTED,
-            format!("contract reverted at {0}", output.pc),
+            format!(
+                "contract reverted at {0}: {1}",
+                output.pc,
+                RevertReason::decode(&output.return_data)
+            ),
             IpldBlock::serialize_cbor(&BytesSer(&output.return_data)).unwrap(),
         )),
     }
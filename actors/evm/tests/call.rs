@@ -5,10 +5,12 @@ use std::fmt::Debug;
 use alloy_core::primitives::{Bytes, Uint};
 use alloy_core::sol;
 use alloy_core::sol_types::SolCall;
+use evm::interpreter::CallGasRules;
 use evm::{EVM_CONTRACT_REVERTED, Method};
 use fil_actor_evm as evm;
 use fil_actors_evm_shared::address::EthAddress;
 use fil_actors_evm_shared::uints::U256;
+use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{EAM_ACTOR_ID, INIT_ACTOR_ADDR, test_utils::*};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::{BytesDe, BytesSer, CBOR, IPLD_RAW};
@@ -19,6 +21,7 @@ use fvm_shared::bigint::Zero;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::{ErrorNumber, ExitCode};
 use fvm_shared::sys::SendFlags;
+use fvm_shared::version::NetworkVersion;
 
 mod util;
 
@@ -160,6 +163,40 @@ return
     asm::new_contract("call-proxy-gas2300", init, body).unwrap()
 }
 
+#[allow(dead_code)]
+pub fn call_proxy_value_contract() -> Vec<u8> {
+    let init = "";
+    let body = r#"
+# this contract takes an address and proxies a call with value and gas to that address
+# output offset and size
+push1 0x00
+push1 0x00
+# input offset and size
+push1 0x00
+push1 0x00
+# value
+push1 0x42
+# dest address
+push1 0x00
+calldataload
+# gas
+push4 0xffffffff
+# do the call
+call
+
+# return result through
+returndatasize
+push1 0x00
+push1 0x00
+returndatacopy
+returndatasize
+push1 0x00
+return
+"#;
+
+    asm::new_contract("call-proxy-value", init, body).unwrap()
+}
+
 #[test]
 fn test_call() {
     let contract = call_proxy_contract();
@@ -290,6 +327,117 @@ fn test_transfer_2300() {
     rt.verify();
 }
 
+// Make sure value-bearing calls with gas are granted the stipend on top of the requested gas
+// from the network version at which the Ethereum call gas rules apply, and not before.
+#[test]
+fn test_transfer_stipend_added_to_gas() {
+    for (nv, expected_gas) in [
+        (NetworkVersion::V21, 0xffffffff),
+        (Policy::default().evm_ethereum_call_gas_version, 0xffffffff + TRANSFER_GAS_VALUE),
+    ] {
+        let rt = util::construct_and_verify(call_proxy_value_contract());
+        rt.set_network_version(nv);
+
+        let target_id = 0x100;
+        let target = FILAddress::new_id(target_id);
+        rt.actor_code_cids.borrow_mut().insert(target, *EVM_ACTOR_CODE_ID);
+        let mut contract_params = vec![0u8; 32];
+        EthAddress::from_id(target_id).as_evm_word().write_as_big_endian(&mut contract_params);
+
+        rt.expect_gas_available(10_000_000_000u64);
+        rt.expect_send(
+            target,
+            Method::InvokeContract as u64,
+            None,
+            TokenAmount::from_atto(0x42),
+            Some(expected_gas),
+            SendFlags::empty(),
+            None,
+            ExitCode::OK,
+            None,
+        );
+
+        let result = util::invoke_contract(&rt, &contract_params);
+        assert!(result.is_empty());
+        rt.verify();
+    }
+}
+
+// Gas forwarded by CALL under each set of rules, with expectations derived from the gas that
+// geth grants the callee, substituting the transfer gas limit for the 2300 gas stipend.
+#[test]
+fn test_call_gas_rules() {
+    const AVAILABLE: u64 = 64_000_000;
+    const CAP: u64 = 63_000_000;
+    let cases: [(u64, u64, u64, u64); 8] = [
+        // (requested gas, value, legacy gas limit, ethereum gas limit)
+        (0, 0, 0, 0),
+        (0, 1, TRANSFER_GAS_VALUE, TRANSFER_GAS_VALUE),
+        (2300, 0, TRANSFER_GAS_VALUE, TRANSFER_GAS_VALUE),
+        (2300, 1, 2300, 2300 + TRANSFER_GAS_VALUE),
+        (50_000, 0, 50_000, 50_000),
+        (50_000, 1, 50_000, 50_000 + TRANSFER_GAS_VALUE),
+        // The gas granted, including the stipend, is capped at 63/64 of the available gas,
+        // leaving the caller a reserve.
+        (u64::MAX, 0, CAP, CAP),
+        (u64::MAX, 1, CAP, CAP),
+    ];
+    for (gas, value, legacy, ethereum) in cases {
+        let (gas, value) = (U256::from(gas), U256::from(value));
+        assert_eq!(legacy, CallGasRules::Legacy.gas_limit(gas, value, AVAILABLE));
+        assert_eq!(ethereum, CallGasRules::Ethereum.gas_limit(gas, value, AVAILABLE));
+    }
+
+    // The rules in effect are set by policy.
+    let mut policy = Policy::default();
+    let activation = policy.evm_ethereum_call_gas_version;
+    assert_eq!(CallGasRules::Legacy, CallGasRules::for_network(&policy, NetworkVersion::V21));
+    assert_eq!(CallGasRules::Ethereum, CallGasRules::for_network(&policy, activation));
+    policy.evm_ethereum_call_gas_version = NetworkVersion::V21;
+    assert_eq!(CallGasRules::Ethereum, CallGasRules::for_network(&policy, NetworkVersion::V21));
+}
+
+/// Gas granted to the callee of a CALL by geth (core/vm/gas.go `callGas` and
+/// core/vm/gas_table.go `gasCall`), with the stipend as a parameter.
+fn geth_callee_gas(available: u64, requested: u64, value: u64, stipend: u64) -> u64 {
+    // EIP-150: all but one 64th of the available gas.
+    let gas = std::cmp::min(requested, available - available / 64);
+    if value != 0 { gas + stipend } else { gas }
+}
+
+// Differential test of the Ethereum call gas rules against geth's, with the transfer gas limit
+// substituted for the 2300 gas stipend. The rules agree wherever the stipend fits under the
+// 63/64 cap; elsewhere the caller's reserve is kept and the callee gets exactly the cap.
+#[test]
+fn test_call_gas_rules_match_geth() {
+    let availables = [TRANSFER_GAS_VALUE / 2, 64_000_000, 10_000_000_000];
+    let requests = [0, 1, 2300, 50_000, 1_000_000, 62_000_000, 63_000_000, u64::MAX];
+    for available in availables {
+        let cap = available - available / 64;
+        for requested in requests {
+            for value in [0, 1] {
+                if requested == 2300 && value == 0 {
+                    // Solidity's zero-value transfer is granted the transfer gas limit,
+                    // which has no geth equivalent.
+                    continue;
+                }
+                let geth = geth_callee_gas(available, requested, value, TRANSFER_GAS_VALUE);
+                let fvm = CallGasRules::Ethereum.gas_limit(
+                    U256::from(requested),
+                    U256::from(value),
+                    available,
+                );
+                if value != 0 && requested.saturating_add(TRANSFER_GAS_VALUE) > cap {
+                    assert_eq!(cap, fvm, "available {available}, requested {requested}");
+                    assert!(fvm <= geth);
+                } else {
+                    assert_eq!(geth, fvm, "available {available}, requested {requested}");
+                }
+            }
+        }
+    }
+}
+
 #[test]
 pub fn test_call_output_region() {
     let init = "";
@@ -1,11 +1,17 @@
 mod asm;
 
+use cid::Cid;
+use fil_actor_evm::NATIVE_VALUE_RECEIVER_TYPE;
 use fil_actors_evm_shared::{address::EthAddress, uints::U256};
 use fil_actors_runtime::{
     EAM_ACTOR_ID,
     test_utils::{MockRuntime, new_bls_addr},
 };
+use fvm_actor_utils::receiver::UniversalReceiverParams;
+use fvm_ipld_encoding::{RawBytes, ipld_block::IpldBlock};
+use fvm_shared::sys::SendFlags;
 use fvm_shared::{METHOD_SEND, address::Address as FILAddress, econ::TokenAmount, error::ExitCode};
+use multihash_codetable::{Code, MultihashDigest};
 
 mod util;
 
@@ -254,6 +260,146 @@ fn test_precompile_randomness() {
     }
 }
 
+#[test]
+fn test_precompile_chain_info() {
+    let (init, body) = PrecompileTest::test_runner_assembly();
+    let mut rt =
+        util::construct_and_verify(asm::new_contract("precompile-tester", &init, &body).unwrap());
+    rt.set_epoch(1234);
+    rt.tipset_timestamp = 1_700_000_000;
+
+    let test = PrecompileTest {
+        precompile_address: NativePrecompile::GetChainInfo.eth_address(),
+        output_size: 64,
+        expected_exit_code: PrecompileExit::Success,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input: vec![],
+        expected_return: [U256::from(1234).to_bytes(), U256::from(1_700_000_000).to_bytes()]
+            .concat(),
+    };
+    test.run_test(&rt);
+}
+
+#[test]
+fn test_precompile_tipset_cid() {
+    let (init, body) = PrecompileTest::test_runner_assembly();
+    let mut rt =
+        util::construct_and_verify(asm::new_contract("precompile-tester", &init, &body).unwrap());
+    let current = 2000;
+    rt.set_epoch(current);
+    rt.tipset_cids = (0..current)
+        .map(|epoch| Cid::new_v1(0x71, Code::Blake2b256.digest(&epoch.to_be_bytes())))
+        .collect();
+    let oldest = current - (rt.policy.chain_finality - 1);
+
+    let test_tipset_cid = |epoch: i64, expected: Vec<u8>| {
+        let test = PrecompileTest {
+            precompile_address: NativePrecompile::GetTipsetCid.eth_address(),
+            output_size: 64,
+            expected_exit_code: PrecompileExit::Success,
+            call_op: util::PrecompileCallOpcode::StaticCall,
+            input: U256::from(epoch).to_bytes().to_vec(),
+            expected_return: expected,
+        };
+        test.run_test(&rt);
+    };
+
+    test_tipset_cid(current - 1, rt.tipset_cids[current as usize - 1].to_bytes());
+    // The oldest tipset within the lookback is available, and the next older is not.
+    test_tipset_cid(oldest, rt.tipset_cids[oldest as usize].to_bytes());
+    test_tipset_cid(oldest - 1, vec![]);
+    // Nor are the current and future tipsets.
+    test_tipset_cid(current, vec![]);
+    test_tipset_cid(current + 1, vec![]);
+
+    // An epoch which does not fit an i64 is invalid.
+    let test = PrecompileTest {
+        precompile_address: NativePrecompile::GetTipsetCid.eth_address(),
+        output_size: 64,
+        expected_exit_code: PrecompileExit::Reverted,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input: U256::MAX.to_bytes().to_vec(),
+        expected_return: vec![],
+    };
+    test.run_test(&rt);
+}
+
+#[test]
+fn test_precompile_send_value() {
+    let rt = util::construct_and_verify(tester_bytecode());
+    rt.set_balance(TokenAmount::from_atto(100));
+    let target = FILAddress::new_id(0x100);
+
+    let send_value_test = |flags: u64, call_op, exit, expected_return: Vec<u8>| {
+        let addr = target.to_bytes();
+        let mut padded_addr = addr.clone();
+        padded_addr.resize(32, 0);
+        let input = [
+            U256::from(10).to_bytes().to_vec(),
+            U256::from(flags).to_bytes().to_vec(),
+            U256::from(3 * 32).to_bytes().to_vec(),
+            U256::from(addr.len()).to_bytes().to_vec(),
+            padded_addr,
+        ]
+        .concat();
+        let test = PrecompileTest {
+            precompile_address: NativePrecompile::SendValue.eth_address(),
+            output_size: 32,
+            expected_exit_code: exit,
+            call_op,
+            input,
+            expected_return,
+        };
+        test.run_test(&rt);
+    };
+
+    // A bare send transfers the value without invoking the recipient.
+    rt.expect_gas_available(10_000_000_000);
+    rt.expect_send(
+        target,
+        METHOD_SEND,
+        None,
+        TokenAmount::from_atto(10),
+        Some(0),
+        SendFlags::empty(),
+        None,
+        ExitCode::OK,
+        None,
+    );
+    send_value_test(
+        0,
+        util::PrecompileCallOpcode::DelegateCall,
+        PrecompileExit::Success,
+        U256::ZERO.to_bytes().to_vec(),
+    );
+
+    // A hooked send invokes the recipient's receiver hook, which may reject the value.
+    let hook_params =
+        UniversalReceiverParams { type_: NATIVE_VALUE_RECEIVER_TYPE, payload: RawBytes::default() };
+    rt.expect_gas_available(10_000_000_000);
+    rt.expect_send(
+        target,
+        frc42_dispatch::method_hash!("Receive"),
+        IpldBlock::serialize_cbor(&hook_params).unwrap(),
+        TokenAmount::from_atto(10),
+        Some(0),
+        SendFlags::empty(),
+        None,
+        ExitCode::USR_FORBIDDEN,
+        None,
+    );
+    send_value_test(
+        1,
+        util::PrecompileCallOpcode::DelegateCall,
+        PrecompileExit::Success,
+        U256::from(ExitCode::USR_FORBIDDEN.value()).to_bytes().to_vec(),
+    );
+
+    // Unknown flags, and calls other than delegatecall, are rejected.
+    send_value_test(2, util::PrecompileCallOpcode::DelegateCall, PrecompileExit::Reverted, vec![]);
+    send_value_test(0, util::PrecompileCallOpcode::Call(0), PrecompileExit::Reverted, vec![]);
+}
+
 #[test]
 fn test_precompile_transfer() {
     let (init, body) = util::PrecompileTest::test_runner_assembly();
@@ -1,4 +1,6 @@
 use fil_actor_evm as evm;
+use fil_actors_evm_shared::revert::RevertReason;
+use fil_actors_evm_shared::uints::U256;
 use fvm_ipld_encoding::{BytesSer, RawBytes};
 
 mod asm;
@@ -27,8 +29,43 @@ revert
     assert!(result.is_err());
     let mut e = result.unwrap_err();
     assert_eq!(e.exit_code(), evm::EVM_CONTRACT_REVERTED);
+    assert_eq!(
+        Some(RevertReason::Custom(vec![0xde, 0xad, 0xbe, 0xef])),
+        RevertReason::from_error(&e)
+    );
+    assert!(e.msg().ends_with("custom error 0xdeadbeef"), "unexpected message: {}", e.msg());
     assert_eq!(
         e.take_data().unwrap().data,
         RawBytes::serialize(BytesSer(&[0xde, 0xad, 0xbe, 0xef])).unwrap().bytes()
     );
 }
+
+#[test]
+fn test_revert_panic() {
+    let contract = asm::new_contract(
+        "panic-revert",
+        "",
+        r#"
+%push(0x4e487b71)
+push1 0x00
+mstore
+push1 0x11
+push1 0x20
+mstore
+push1 0x24
+push1 0x1c # skip top 28 bytes
+revert
+"#,
+    )
+    .unwrap();
+
+    let rt = util::construct_and_verify(contract);
+    rt.expect_validate_caller_any();
+
+    let result = rt.call::<evm::EvmContractActor>(evm::Method::InvokeContract as u64, None);
+    let e = result.unwrap_err();
+    assert_eq!(e.exit_code(), evm::EVM_CONTRACT_REVERTED);
+    // The reason is decoded from the error data, and included in the message.
+    assert_eq!(Some(RevertReason::Panic(U256::from(0x11))), RevertReason::from_error(&e));
+    assert!(e.msg().ends_with("panic 0x11"), "unexpected message: {}", e.msg());
+}
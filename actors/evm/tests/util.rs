@@ -140,6 +140,9 @@ pub enum NativePrecompile {
     GetActorTypeDISABLED = 4,
     CallActorId = 5,
     GetRandomness = 6,
+    GetChainInfo = 7,
+    GetTipsetCid = 8,
+    SendValue = 9,
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,12 @@
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
+
+pub mod paych {
+    use super::*;
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct ConstructorParams {
+        pub from: Address,
+        pub to: Address,
+    }
+}
@@ -3,20 +3,24 @@
 
 use cid::Cid;
 use fil_actors_runtime::runtime::builtins::Type;
-use fil_actors_runtime::runtime::{ActorCode, Runtime};
+use fil_actors_runtime::runtime::{ActorCode, Primitives, Runtime};
 
 use fil_actors_runtime::{
-    ActorContext, ActorError, AsActorError, EAM_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, actor_dispatch,
-    actor_error, extract_send_result,
+    ActorContext, ActorError, AsActorError, EAM_ACTOR_ADDR, INIT_ACTOR_ID, SYSTEM_ACTOR_ADDR,
+    actor_dispatch, actor_error, extract_send_result,
 };
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::to_vec;
 use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::{ActorID, METHOD_CONSTRUCTOR};
+use fvm_shared::{ActorID, METHOD_CONSTRUCTOR, METHOD_SEND};
 use num_derive::FromPrimitive;
 
 pub use self::state::State;
 pub use self::types::*;
 
+pub mod ext;
 mod state;
 pub mod testing;
 mod types;
@@ -31,8 +35,15 @@ pub enum Method {
     Constructor = METHOD_CONSTRUCTOR,
     Exec = 2,
     Exec4 = 3,
+    // Method numbers derived from FRC-0042 standards
+    ExecPaymentChannelExported = frc42_dispatch::method_hash!("ExecPaymentChannel"),
+    GetRobustAddressExported = frc42_dispatch::method_hash!("GetRobustAddress"),
+    ExecBatchExported = frc42_dispatch::method_hash!("ExecBatch"),
 }
 
+/// Maximum number of actors which may be created by a single ExecBatch message.
+pub const EXEC_BATCH_MAX: usize = 32;
+
 /// Init actor
 pub struct Actor;
 
@@ -50,61 +61,59 @@ impl Actor {
     /// Exec init actor
     pub fn exec(rt: &impl Runtime, params: ExecParams) -> Result<ExecReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
+        let caller_code = caller_code(rt)?;
+        exec_actor(rt, &caller_code, params, rt.message().value_received())
+    }
 
-        log::trace!("called exec; params.code_cid: {:?}", &params.code_cid);
-
-        let caller_code =
-            rt.get_actor_code_cid(&rt.message().caller().id().unwrap()).ok_or_else(|| {
-                actor_error!(illegal_state, "no code for caller as {}", rt.message().caller())
-            })?;
-
-        log::trace!("caller code CID: {:?}", &caller_code);
-
-        if !can_exec(rt, &caller_code, &params.code_cid) {
-            return Err(actor_error!(forbidden;
-                    "called type {} cannot exec actor type {}",
-                    &caller_code, &params.code_cid
+    /// Creates several actors, as if by a sequence of exec calls, and returns their addresses
+    /// in order. The value received must equal the total value to send to the constructors.
+    /// If any actor cannot be created, the whole message fails and none are created.
+    pub fn exec_batch(
+        rt: &impl Runtime,
+        params: ExecBatchParams,
+    ) -> Result<ExecBatchReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.execs.is_empty() {
+            return Err(actor_error!(illegal_argument, "batch empty"));
+        }
+        if params.execs.len() > EXEC_BATCH_MAX {
+            return Err(actor_error!(
+                illegal_argument,
+                "batch of {} exceeds maximum of {}",
+                params.execs.len(),
+                EXEC_BATCH_MAX
             ));
         }
-
-        // Compute a re-org-stable address.
-        // This address exists for use by messages coming from outside the system, in order to
-        // stably address the newly created actor even if a chain re-org causes it to end up with
-        // a different ID.
-        let robust_address = rt.new_actor_address()?;
-
-        log::trace!("robust address: {:?}", &robust_address);
-
-        // Allocate an ID for this actor.
-        // Store mapping of actor addresses to the actor ID.
-        let (id_address, existing): (ActorID, bool) = rt.transaction(|s: &mut State, rt| {
-            s.map_addresses_to_id(rt.store(), &robust_address, None)
-                .context("failed to allocate ID address")
-        })?;
-
-        if existing {
-            // NOTE: this case should be impossible, but we check it anyways just in case something
-            // changes.
+        if let Some(i) = params.execs.iter().position(|e| e.value.is_negative()) {
             return Err(actor_error!(
-                forbidden,
-                "cannot exec over an existing actor {}",
-                id_address
+                illegal_argument,
+                "negative value {} for actor {}",
+                params.execs[i].value,
+                i
+            ));
+        }
+        let total_value: TokenAmount = params.execs.iter().map(|e| &e.value).sum();
+        if total_value != rt.message().value_received() {
+            return Err(actor_error!(
+                illegal_argument,
+                "value received {} does not equal total value {} of batch",
+                rt.message().value_received(),
+                total_value
             ));
         }
 
-        // Create an empty actor
-        rt.create_actor(params.code_cid, id_address, None)?;
-
-        // Invoke constructor
-        extract_send_result(rt.send_simple(
-            &Address::new_id(id_address),
-            METHOD_CONSTRUCTOR,
-            params.constructor_params.into(),
-            rt.message().value_received(),
-        ))
-        .context("constructor failed")?;
-
-        Ok(ExecReturn { id_address: Address::new_id(id_address), robust_address })
+        let caller_code = caller_code(rt)?;
+        let mut results = Vec::with_capacity(params.execs.len());
+        for (i, entry) in params.execs.into_iter().enumerate() {
+            let exec_params = ExecParams {
+                code_cid: entry.code_cid,
+                constructor_params: entry.constructor_params,
+            };
+            let ret = exec_actor(rt, &caller_code, exec_params, entry.value)
+                .with_context(|| format!("failed to exec actor {}", i))?;
+            results.push(ret);
+        }
+        Ok(ExecBatchReturn { results })
     }
 
     /// Exec4 init actor
@@ -162,6 +171,106 @@ impl Actor {
 
         Ok(Exec4Return { id_address: Address::new_id(id_address), robust_address })
     }
+
+    /// Creates a payment channel between two parties at an address derived from the parties
+    /// and a nonce (see `payment_channel_address`), so that it may be computed, and funded,
+    /// before the channel exists.
+    /// Either party may create the channel. Creation is idempotent: if the channel already
+    /// exists it is returned, and any value received is forwarded to it.
+    pub fn exec_payment_channel(
+        rt: &impl Runtime,
+        params: ExecPaymentChannelParams,
+    ) -> Result<ExecPaymentChannelReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let from = resolve_party(rt, &params.from)?;
+        let to = resolve_party(rt, &params.to)?;
+        let caller = rt.message().caller().id().unwrap();
+        if caller != from && caller != to {
+            return Err(actor_error!(
+                forbidden,
+                "caller {} is not a party to the payment channel from {} to {}",
+                caller,
+                from,
+                to
+            ));
+        }
+
+        let delegated_address = payment_channel_address(rt, from, to, params.nonce)?;
+        let paych_code = rt.get_code_cid_for_type(Type::PaymentChannel);
+
+        // The address may already be assigned, to the channel itself if created by an earlier
+        // call, or to a placeholder if it was funded before creation.
+        let st: State = rt.state()?;
+        if let Some(existing) = st.resolve_address(rt.store(), &delegated_address)? {
+            let existing_id = existing.id().unwrap();
+            let code_cid = rt.get_actor_code_cid(&existing_id).ok_or_else(|| {
+                actor_error!(
+                    forbidden,
+                    "payment channel {} has been collected, use a new nonce",
+                    delegated_address
+                )
+            })?;
+            if code_cid == paych_code {
+                let value = rt.message().value_received();
+                if value.is_positive() {
+                    extract_send_result(rt.send_simple(&existing, METHOD_SEND, None, value))
+                        .context("failed to forward value to payment channel")?;
+                }
+                return Ok(ExecPaymentChannelReturn {
+                    id_address: existing,
+                    delegated_address,
+                    created: false,
+                });
+            }
+            if code_cid != rt.get_code_cid_for_type(Type::Placeholder) {
+                return Err(actor_error!(
+                    forbidden,
+                    "cannot replace an existing non-placeholder actor with code: {}",
+                    code_cid
+                ));
+            }
+        }
+
+        let robust_address = rt.new_actor_address()?;
+        let (id_address, _) = rt.transaction(|s: &mut State, rt| {
+            s.map_addresses_to_id(rt.store(), &robust_address, Some(&delegated_address))
+                .context("failed to map addresses to ID")
+        })?;
+
+        rt.create_actor(paych_code, id_address, Some(delegated_address))?;
+
+        let constructor_params =
+            ext::paych::ConstructorParams { from: Address::new_id(from), to: Address::new_id(to) };
+        extract_send_result(rt.send_simple(
+            &Address::new_id(id_address),
+            METHOD_CONSTRUCTOR,
+            IpldBlock::serialize_cbor(&constructor_params)?,
+            rt.message().value_received(),
+        ))
+        .context("constructor failed")?;
+
+        Ok(ExecPaymentChannelReturn {
+            id_address: Address::new_id(id_address),
+            delegated_address,
+            created: true,
+        })
+    }
+
+    /// Resolves an actor ID to the robust (f2) address assigned when the actor was created
+    /// by exec, so that it may be presented in place of an ID which is not reorg-stable.
+    /// Fails with not-found for actors without one, including singletons and accounts.
+    pub fn get_robust_address(
+        rt: &impl Runtime,
+        params: GetRobustAddressParams,
+    ) -> Result<GetRobustAddressReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let robust_address =
+            st.lookup_robust_address(rt.store(), params.actor_id)?.ok_or_else(|| {
+                actor_error!(not_found, "no robust address for actor {}", params.actor_id)
+            })?;
+        Ok(GetRobustAddressReturn { robust_address })
+    }
 }
 
 impl ActorCode for Actor {
@@ -175,6 +284,9 @@ impl ActorCode for Actor {
         Constructor => constructor,
         Exec => exec,
         Exec4 => exec4,
+        ExecPaymentChannelExported => exec_payment_channel,
+        GetRobustAddressExported => get_robust_address,
+        ExecBatchExported => exec_batch,
     }
 }
 
@@ -187,3 +299,83 @@ fn can_exec(rt: &impl Runtime, caller: &Cid, exec: &Cid) -> bool {
         })
         .unwrap_or(false)
 }
+
+fn caller_code(rt: &impl Runtime) -> Result<Cid, ActorError> {
+    rt.get_actor_code_cid(&rt.message().caller().id().unwrap()).ok_or_else(|| {
+        actor_error!(illegal_state, "no code for caller as {}", rt.message().caller())
+    })
+}
+
+/// Creates an actor with a new robust address and ID, and invokes its constructor with a value.
+fn exec_actor(
+    rt: &impl Runtime,
+    caller_code: &Cid,
+    params: ExecParams,
+    value: TokenAmount,
+) -> Result<ExecReturn, ActorError> {
+    log::trace!("called exec; params.code_cid: {:?}", &params.code_cid);
+    log::trace!("caller code CID: {:?}", caller_code);
+
+    if !can_exec(rt, caller_code, &params.code_cid) {
+        return Err(actor_error!(forbidden;
+                "called type {} cannot exec actor type {}",
+                caller_code, &params.code_cid
+        ));
+    }
+
+    // Compute a re-org-stable address.
+    // This address exists for use by messages coming from outside the system, in order to
+    // stably address the newly created actor even if a chain re-org causes it to end up with
+    // a different ID.
+    let robust_address = rt.new_actor_address()?;
+
+    log::trace!("robust address: {:?}", &robust_address);
+
+    // Allocate an ID for this actor.
+    // Store mapping of actor addresses to the actor ID.
+    let (id_address, existing): (ActorID, bool) = rt.transaction(|s: &mut State, rt| {
+        s.map_addresses_to_id(rt.store(), &robust_address, None)
+            .context("failed to allocate ID address")
+    })?;
+
+    if existing {
+        // NOTE: this case should be impossible, but we check it anyways just in case something
+        // changes.
+        return Err(actor_error!(forbidden, "cannot exec over an existing actor {}", id_address));
+    }
+
+    // Create an empty actor
+    rt.create_actor(params.code_cid, id_address, None)?;
+
+    // Invoke constructor
+    extract_send_result(rt.send_simple(
+        &Address::new_id(id_address),
+        METHOD_CONSTRUCTOR,
+        params.constructor_params.into(),
+        value,
+    ))
+    .context("constructor failed")?;
+
+    Ok(ExecReturn { id_address: Address::new_id(id_address), robust_address })
+}
+
+/// Computes the address of the payment channel created by `ExecPaymentChannel` from one actor
+/// to another with a nonce.
+/// This is an f4 address in the init actor's namespace, with a subaddress of the Blake2b-256
+/// hash of the CBOR-encoded tuple `[from, to, nonce]` of the parties' actor IDs.
+pub fn payment_channel_address(
+    rt: &impl Primitives,
+    from: ActorID,
+    to: ActorID,
+    nonce: u64,
+) -> Result<Address, ActorError> {
+    let preimage = to_vec(&(from, to, nonce))
+        .map_err(|e| actor_error!(serialization, "failed to serialize channel parties: {}", e))?;
+    Address::new_delegated(INIT_ACTOR_ID, &rt.hash_blake2b(&preimage))
+        .map_err(|e| actor_error!(illegal_state, "invalid delegated address: {}", e))
+}
+
+fn resolve_party(rt: &impl Runtime, party: &Address) -> Result<ActorID, ActorError> {
+    rt.resolve_address(party)
+        .ok_or_else(|| actor_error!(illegal_argument, "unable to resolve address {}", party))
+}
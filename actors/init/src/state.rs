@@ -1,11 +1,14 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use anyhow::anyhow;
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::ActorID;
 use fvm_shared::address::{Address, Protocol};
+use multihash_codetable::Code;
 
 use fil_actors_runtime::{
     ActorError, DEFAULT_HAMT_CONFIG, FIRST_NON_SINGLETON_ADDR, Map2, actor_error,
@@ -17,14 +20,58 @@ pub struct State {
     pub address_map: Cid,
     pub next_id: ActorID,
     pub network_name: String,
+    /// Reverse index from the ID of each actor created by exec to its robust (f2) address.
+    /// Absent when no such actor has been created, or in state written before the index
+    /// was added until it is migrated.
+    /// HAMT[ActorID]Address
+    #[serde(default)]
+    pub robust_address_map: Option<Cid>,
 }
 
 pub type AddressMap<BS> = Map2<BS, Address, ActorID>;
+pub type RobustAddressMap<BS> = Map2<BS, ActorID, Address>;
 
 impl State {
     pub fn new<BS: Blockstore>(store: &BS, network_name: String) -> Result<Self, ActorError> {
         let empty = AddressMap::flush_empty(store, DEFAULT_HAMT_CONFIG)?;
-        Ok(Self { address_map: empty, next_id: FIRST_NON_SINGLETON_ADDR, network_name })
+        Ok(Self {
+            address_map: empty,
+            next_id: FIRST_NON_SINGLETON_ADDR,
+            network_name,
+            robust_address_map: None,
+        })
+    }
+
+    /// Re-encodes init state with the robust address index rebuilt from the address map,
+    /// backfilling it for state written before the index was added.
+    /// Returns the root of the migrated state.
+    pub fn migrate<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Cid> {
+        let mut st: State =
+            store.get_cbor(root)?.ok_or_else(|| anyhow!("init state {} not found", root))?;
+        let map = AddressMap::load(store, &st.address_map, DEFAULT_HAMT_CONFIG, "addresses")?;
+        let mut robust_map =
+            RobustAddressMap::empty(store, DEFAULT_HAMT_CONFIG, "robust addresses");
+        map.for_each(|addr, actor_id| {
+            if addr.protocol() == Protocol::Actor {
+                robust_map.set(actor_id, addr)?;
+            }
+            Ok(())
+        })?;
+        st.robust_address_map =
+            if robust_map.is_empty() { None } else { Some(robust_map.flush()?) };
+        Ok(store.put_cbor(&st, Code::Blake2b256)?)
+    }
+
+    pub fn load_robust_address_map<BS: Blockstore>(
+        &self,
+        store: BS,
+    ) -> Result<RobustAddressMap<BS>, ActorError> {
+        match &self.robust_address_map {
+            Some(root) => {
+                RobustAddressMap::load(store, root, DEFAULT_HAMT_CONFIG, "robust addresses")
+            }
+            None => Ok(RobustAddressMap::empty(store, DEFAULT_HAMT_CONFIG, "robust addresses")),
+        }
     }
 
     /// Maps argument addresses to to a new or existing actor ID.
@@ -32,6 +79,7 @@ impl State {
     /// allocates a new ID address and maps both to it.
     /// If the delegated address is already present, maps the robust address to that actor ID.
     /// Fails if the robust address is already mapped. The assignment of an ID to an address is one-time-only, even if the actor at that ID is deleted.
+    /// An actor-protocol (f2) robust address is also recorded in the reverse index from ID.
     /// Returns the actor ID and a boolean indicating whether or not the actor already exists.
     pub fn map_addresses_to_id<BS: Blockstore>(
         &mut self,
@@ -68,9 +116,26 @@ impl State {
             ));
         }
         self.address_map = map.flush()?;
+
+        if robust_addr.protocol() == Protocol::Actor {
+            let mut robust_map = self.load_robust_address_map(store)?;
+            robust_map.set(&id, *robust_addr)?;
+            self.robust_address_map = Some(robust_map.flush()?);
+        }
         Ok((id, existing))
     }
 
+    /// Resolves an actor ID to the robust (f2) address assigned when the actor was created by
+    /// exec. Returns `None` for actors without one, such as singletons and accounts, whose
+    /// addresses are assigned by the system.
+    pub fn lookup_robust_address<BS: Blockstore>(
+        &self,
+        store: &BS,
+        id: ActorID,
+    ) -> Result<Option<Address>, ActorError> {
+        Ok(self.load_robust_address_map(store)?.get(&id)?.copied())
+    }
+
     /// ResolveAddress resolves an address to an ID-address, if possible.
     /// If the provided address is an ID address, it is returned as-is.
     /// This means that mapped ID-addresses (which should only appear as values, not keys) and
@@ -73,5 +73,26 @@ pub fn check_state_invariants<BS: Blockstore>(
         Err(e) => acc.add(format!("error loading address map: {e}")),
     }
 
+    match state.load_robust_address_map(store) {
+        Ok(robust_address_map) => {
+            if state.robust_address_map.is_some() {
+                acc.require(!robust_address_map.is_empty(), "empty robust address map not absent");
+            }
+            let ret = robust_address_map.for_each(|actor_id, address| {
+                acc.require(
+                    address.protocol() == Protocol::Actor,
+                    format!("robust address {address} for ID {actor_id} is not an actor address"),
+                );
+                acc.require(
+                    init_summary.ids_by_address.get(address) == Some(&actor_id),
+                    format!("robust address {address} is not mapped to ID {actor_id}"),
+                );
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating robust address map");
+        }
+        Err(e) => acc.add(format!("error loading robust address map: {e}")),
+    }
+
     (init_summary, acc)
 }
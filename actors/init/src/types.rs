@@ -4,7 +4,9 @@
 use cid::Cid;
 use fvm_ipld_encoding::RawBytes;
 use fvm_ipld_encoding::tuple::*;
+use fvm_shared::ActorID;
 use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
 
 /// Init actor Constructor parameters
 #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -38,3 +40,63 @@ pub struct Exec4Params {
 
 /// Init actor Exec4 Return value
 pub type Exec4Return = ExecReturn;
+
+/// Init actor ExecPaymentChannel Params
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ExecPaymentChannelParams {
+    pub from: Address,
+    pub to: Address,
+    /// Distinguishes successive channels between the same parties.
+    pub nonce: u64,
+}
+
+/// Init actor ExecPaymentChannel Return value
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ExecPaymentChannelReturn {
+    /// ID based address for the channel
+    pub id_address: Address,
+    /// Deterministic f4 address for the channel, which is also reorg safe
+    pub delegated_address: Address,
+    /// Whether the channel was created by this call, rather than existing already
+    pub created: bool,
+}
+
+/// Init actor GetRobustAddress Params
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetRobustAddressParams {
+    pub actor_id: ActorID,
+}
+
+/// Init actor GetRobustAddress Return value
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetRobustAddressReturn {
+    /// Reorg safe address assigned to the actor at creation
+    pub robust_address: Address,
+}
+
+/// Init actor ExecBatch Params
+#[derive(Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct ExecBatchParams {
+    /// Actors to create, in order.
+    pub execs: Vec<ExecBatchEntry>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ExecBatchEntry {
+    pub code_cid: Cid,
+    pub constructor_params: RawBytes,
+    /// Funds sent to the new actor's constructor, out of the value sent with the message.
+    /// The values of all entries must sum to the value sent.
+    pub value: TokenAmount,
+}
+
+/// Init actor ExecBatch Return value
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct ExecBatchReturn {
+    /// Addresses of the created actors, in the order of the parameters.
+    pub results: Vec<ExecReturn>,
+}
@@ -6,20 +6,25 @@ use std::cell::RefCell;
 use cid::Cid;
 use fil_actor_init::testing::check_state_invariants;
 use fil_actor_init::{
-    Actor as InitActor, ConstructorParams, Exec4Params, Exec4Return, ExecParams, ExecReturn,
-    Method, State,
+    Actor as InitActor, ConstructorParams, EXEC_BATCH_MAX, Exec4Params, Exec4Return,
+    ExecBatchEntry, ExecBatchParams, ExecBatchReturn, ExecParams, ExecPaymentChannelParams,
+    ExecPaymentChannelReturn, ExecReturn, GetRobustAddressParams, GetRobustAddressReturn, Method,
+    State, ext, payment_channel_address,
 };
-use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::runtime::{Primitives, Runtime};
 use fil_actors_runtime::{
-    ActorError, FIRST_NON_SINGLETON_ADDR, Multimap, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    ActorError, FIRST_NON_SINGLETON_ADDR, INIT_ACTOR_ID, Multimap, STORAGE_POWER_ACTOR_ADDR,
+    SYSTEM_ACTOR_ADDR,
 };
 use fil_actors_runtime::{EAM_ACTOR_ADDR, EAM_ACTOR_ID, test_utils::*};
-use fvm_ipld_encoding::RawBytes;
+use fvm_ipld_blockstore::{Block, Blockstore};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::{DAG_CBOR, RawBytes};
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::{ActorID, HAMT_BIT_WIDTH, METHOD_CONSTRUCTOR};
+use fvm_shared::{ActorID, HAMT_BIT_WIDTH, METHOD_CONSTRUCTOR, METHOD_SEND};
+use multihash_codetable::Code;
 use num_traits::Zero;
 use serde::Serialize;
 
@@ -411,6 +416,302 @@ fn call_exec4_placeholder() {
     assert_eq!(expected_id_addr, resolved_id, "f4 address not assigned to the right actor");
 }
 
+#[test]
+fn create_payment_channel_at_derived_address() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    let (anne, bob) = (1001, 1002);
+    let paych_addr = payment_channel_address(&rt, anne, bob, 0).unwrap();
+
+    // The payee may create the channel, which is constructed with the parties' ID addresses.
+    rt.new_actor_addr.replace(Some(Address::new_actor(b"paych")));
+    rt.value_received.replace(TokenAmount::from_atto(100));
+    let expected_id = 100;
+    let expected_id_addr = Address::new_id(expected_id);
+    rt.expect_create_actor(*PAYCH_ACTOR_CODE_ID, expected_id, Some(paych_addr));
+    rt.expect_send_simple(
+        expected_id_addr,
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&ext::paych::ConstructorParams {
+            from: Address::new_id(anne),
+            to: Address::new_id(bob),
+        })
+        .unwrap(),
+        TokenAmount::from_atto(100),
+        None,
+        ExitCode::OK,
+    );
+    let ret = exec_paych_and_verify(&rt, bob, anne, bob, 0).unwrap();
+    assert_eq!(expected_id_addr, ret.id_address);
+    assert_eq!(paych_addr, ret.delegated_address);
+    assert!(ret.created);
+
+    let state: State = rt.get_state();
+    assert_eq!(Some(expected_id_addr), state.resolve_address(rt.store(), &paych_addr).unwrap());
+
+    // A racing call by the other party returns the channel, forwarding its value.
+    rt.new_actor_addr.replace(Some(Address::new_actor(b"paych2")));
+    rt.value_received.replace(TokenAmount::from_atto(50));
+    rt.expect_send_simple(
+        expected_id_addr,
+        METHOD_SEND,
+        None,
+        TokenAmount::from_atto(50),
+        None,
+        ExitCode::OK,
+    );
+    let ret = exec_paych_and_verify(&rt, anne, anne, bob, 0).unwrap();
+    assert_eq!(expected_id_addr, ret.id_address);
+    assert_eq!(paych_addr, ret.delegated_address);
+    assert!(!ret.created);
+
+    // Once collected, the channel cannot be recreated with the same nonce.
+    rt.actor_code_cids.borrow_mut().remove(&expected_id_addr);
+    rt.value_received.replace(TokenAmount::zero());
+    let err = exec_paych_and_verify(&rt, anne, anne, bob, 0).unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+}
+
+#[test]
+fn create_payment_channel_funded_before_creation() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    let (anne, bob) = (1001, 1002);
+    let paych_addr = payment_channel_address(&rt, anne, bob, 7).unwrap();
+
+    // Funding the address before creation registers a placeholder.
+    let expected_id = {
+        let mut state: State = rt.get_state();
+        let (id, existing) = state.map_addresses_to_id(rt.store(), &paych_addr, None).unwrap();
+        assert!(!existing);
+        rt.replace_state(&state);
+        id
+    };
+    let expected_id_addr = Address::new_id(expected_id);
+    rt.set_address_actor_type(expected_id_addr, *PLACEHOLDER_ACTOR_CODE_ID);
+    rt.set_delegated_address(expected_id, paych_addr);
+
+    rt.new_actor_addr.replace(Some(Address::new_actor(b"paych")));
+    rt.expect_create_actor(*PAYCH_ACTOR_CODE_ID, expected_id, Some(paych_addr));
+    rt.expect_send_simple(
+        expected_id_addr,
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&ext::paych::ConstructorParams {
+            from: Address::new_id(anne),
+            to: Address::new_id(bob),
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    let ret = exec_paych_and_verify(&rt, anne, anne, bob, 7).unwrap();
+    assert_eq!(expected_id_addr, ret.id_address);
+    assert!(ret.created);
+}
+
+#[test]
+fn payment_channel_address_derivation() {
+    let rt = construct_runtime();
+    let addr = payment_channel_address(&rt, 1001, 1002, 0).unwrap();
+    assert_eq!(addr, payment_channel_address(&rt, 1001, 1002, 0).unwrap());
+    assert_ne!(addr, payment_channel_address(&rt, 1001, 1002, 1).unwrap());
+    assert_ne!(addr, payment_channel_address(&rt, 1002, 1001, 0).unwrap());
+
+    let expected = rt.hash_blake2b(&RawBytes::serialize((1001u64, 1002u64, 0u64)).unwrap());
+    assert_eq!(Address::new_delegated(INIT_ACTOR_ID, &expected).unwrap(), addr);
+}
+
+#[test]
+fn create_payment_channel_requires_party() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+
+    let err = exec_paych_and_verify(&rt, 1003, 1001, 1002, 0).unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+
+    // Parties must be resolvable to derive the address.
+    rt.expect_validate_caller_any();
+    let params = ExecPaymentChannelParams {
+        from: Address::new_id(1001),
+        to: Address::new_actor(b"unknown"),
+        nonce: 0,
+    };
+    let err = rt
+        .call::<InitActor>(
+            Method::ExecPaymentChannelExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+    rt.verify();
+    check_state(&rt);
+}
+
+#[test]
+fn get_robust_address_of_created_actor() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+
+    let unique_address = Address::new_actor(b"multisig");
+    rt.new_actor_addr.replace(Some(unique_address));
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    rt.expect_create_actor(*MULTISIG_ACTOR_CODE_ID, 100, None);
+    rt.expect_send_simple(
+        Address::new_id(100),
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&fake_params).unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    exec_and_verify(&rt, *MULTISIG_ACTOR_CODE_ID, &fake_params).unwrap();
+
+    assert_eq!(unique_address, get_robust_address(&rt, 100).unwrap().robust_address);
+
+    // Singletons and IDs not assigned by exec have no robust address.
+    for id in [INIT_ACTOR_ID, 101] {
+        let err = get_robust_address(&rt, id).unwrap_err();
+        assert_eq!(ExitCode::USR_NOT_FOUND, err.exit_code());
+    }
+}
+
+#[test]
+fn migrate_state_written_before_robust_address_index() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+
+    rt.new_actor_addr.replace(Some(Address::new_actor(b"multisig")));
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    rt.expect_create_actor(*MULTISIG_ACTOR_CODE_ID, 100, None);
+    rt.expect_send_simple(
+        Address::new_id(100),
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&fake_params).unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    exec_and_verify(&rt, *MULTISIG_ACTOR_CODE_ID, &fake_params).unwrap();
+    let st: State = rt.get_state();
+    assert!(st.robust_address_map.is_some());
+
+    // Encode the state as it was before the robust address index was appended.
+    let mut encoded = fvm_ipld_encoding::to_vec(&State { robust_address_map: None, ..st }).unwrap();
+    assert_eq!(0x84, encoded[0]);
+    assert_eq!(Some(&0xf6), encoded.last());
+    encoded[0] = 0x83;
+    encoded.pop();
+    let root = rt.store.put(Code::Blake2b256, &Block::new(DAG_CBOR, &encoded)).unwrap();
+
+    let migrated = State::migrate(&rt.store, &root).unwrap();
+    assert_eq!(rt.get_state_root().unwrap(), migrated);
+    assert_eq!(migrated, State::migrate(&rt.store, &migrated).unwrap());
+}
+
+#[test]
+fn exec_batch_creates_actors_with_values() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+
+    let unique_address = Address::new_actor(b"multisig");
+    rt.new_actor_addr.replace(Some(unique_address));
+    let value = TokenAmount::from_atto(100);
+    rt.set_received(value.clone());
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    rt.expect_create_actor(*MULTISIG_ACTOR_CODE_ID, 100, None);
+    rt.expect_send_simple(
+        Address::new_id(100),
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&fake_params).unwrap(),
+        value.clone(),
+        None,
+        ExitCode::OK,
+    );
+
+    let ret =
+        exec_batch_and_verify(&rt, vec![batch_entry(*MULTISIG_ACTOR_CODE_ID, &fake_params, value)])
+            .unwrap();
+    assert_eq!(1, ret.results.len());
+    assert_eq!(Address::new_id(100), ret.results[0].id_address);
+    assert_eq!(unique_address, ret.results[0].robust_address);
+}
+
+#[test]
+fn exec_batch_fails_if_any_exec_fails() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+
+    rt.new_actor_addr.replace(Some(Address::new_actor(b"multisig")));
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    rt.expect_create_actor(*MULTISIG_ACTOR_CODE_ID, 100, None);
+    rt.expect_send_simple(
+        Address::new_id(100),
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&fake_params).unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+
+    // An account may not create a miner, so the second exec fails and with it the message.
+    let execs = vec![
+        batch_entry(*MULTISIG_ACTOR_CODE_ID, &fake_params, TokenAmount::zero()),
+        batch_entry(*MINER_ACTOR_CODE_ID, &fake_params, TokenAmount::zero()),
+    ];
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "failed to exec actor 1",
+        exec_batch_and_verify(&rt, execs),
+    );
+}
+
+#[test]
+fn exec_batch_rejects_invalid_batches() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    let entry = |value: u64| {
+        batch_entry(*MULTISIG_ACTOR_CODE_ID, &fake_params, TokenAmount::from_atto(value))
+    };
+
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "batch empty",
+        exec_batch_and_verify(&rt, vec![]),
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "exceeds maximum",
+        exec_batch_and_verify(&rt, (0..=EXEC_BATCH_MAX).map(|_| entry(0)).collect()),
+    );
+
+    // Entry values must sum to the value received.
+    rt.set_received(TokenAmount::from_atto(10));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "does not equal total value",
+        exec_batch_and_verify(&rt, vec![entry(4), entry(5)]),
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "negative value",
+        exec_batch_and_verify(
+            &rt,
+            vec![
+                entry(20),
+                batch_entry(*MULTISIG_ACTOR_CODE_ID, &fake_params, TokenAmount::from_atto(-10)),
+            ],
+        ),
+    );
+    check_state(&rt);
+}
+
 fn construct_and_verify(rt: &MockRuntime) {
     rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
@@ -451,6 +752,25 @@ where
     ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
 }
 
+fn batch_entry<S: Serialize>(code_cid: Cid, params: &S, value: TokenAmount) -> ExecBatchEntry {
+    ExecBatchEntry { code_cid, constructor_params: RawBytes::serialize(params).unwrap(), value }
+}
+
+fn exec_batch_and_verify(
+    rt: &MockRuntime,
+    execs: Vec<ExecBatchEntry>,
+) -> Result<ExecBatchReturn, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<InitActor>(
+        Method::ExecBatchExported as u64,
+        IpldBlock::serialize_cbor(&ExecBatchParams { execs }).unwrap(),
+    );
+
+    rt.verify();
+    check_state(rt);
+    ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
+}
+
 fn exec4_and_verify<S>(
     rt: &MockRuntime,
     namespace: ActorID,
@@ -476,3 +796,38 @@ where
     check_state(rt);
     ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
 }
+
+fn exec_paych_and_verify(
+    rt: &MockRuntime,
+    caller: ActorID,
+    from: ActorID,
+    to: ActorID,
+    nonce: u64,
+) -> Result<ExecPaymentChannelReturn, ActorError> {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(caller));
+    rt.expect_validate_caller_any();
+    let params =
+        ExecPaymentChannelParams { from: Address::new_id(from), to: Address::new_id(to), nonce };
+
+    let ret = rt.call::<InitActor>(
+        Method::ExecPaymentChannelExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+
+    rt.verify();
+    check_state(rt);
+    ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
+}
+
+fn get_robust_address(
+    rt: &MockRuntime,
+    actor_id: ActorID,
+) -> Result<GetRobustAddressReturn, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<InitActor>(
+        Method::GetRobustAddressExported as u64,
+        IpldBlock::serialize_cbor(&GetRobustAddressParams { actor_id }).unwrap(),
+    );
+    rt.verify();
+    ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
+}
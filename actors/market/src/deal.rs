@@ -2,16 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use cid::{Cid, Version};
+use fil_actors_runtime::{ActorError, actor_error};
 use fvm_ipld_encoding::BytesSer;
 use fvm_ipld_encoding::tuple::*;
+use fvm_shared::ActorID;
 use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::commcid::{FIL_COMMITMENT_UNSEALED, SHA2_256_TRUNC254_PADDED};
 use fvm_shared::crypto::signature::Signature;
+use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::SectorNumber;
 use ipld_core::ipld::Ipld;
+use multihash_codetable::{Code, MultihashDigest};
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use std::convert::{TryFrom, TryInto};
 
@@ -79,6 +83,17 @@ impl Label {
             Label::Bytes(b) => b.is_empty(),
         }
     }
+
+    /// Returns the key under which deals with this label are indexed:
+    /// the Blake2b-256 digest of the label's bytes (UTF-8 for a string label).
+    /// A string label and a bytes label with the same bytes share a key.
+    pub fn index_key(&self) -> Vec<u8> {
+        let bytes = match self {
+            Label::String(s) => s.as_bytes(),
+            Label::Bytes(b) => b.as_slice(),
+        };
+        Code::Blake2b256.digest(bytes).digest().to_vec()
+    }
 }
 
 /// Note: Deal Collateral is only released and returned to clients and miners
@@ -89,7 +104,7 @@ impl Label {
 /// minimal deals that last for a long time.
 /// Note: ClientCollateralPerEpoch may not be needed and removed pending future confirmation.
 /// There will be a Minimum value for both client and provider deal collateral.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize_tuple)]
 pub struct DealProposal {
     pub piece_cid: Cid,
     pub piece_size: PaddedPieceSize,
@@ -110,21 +125,72 @@ pub struct DealProposal {
 
     pub provider_collateral: TokenAmount,
     pub client_collateral: TokenAmount,
+
+    /// FRC-46 token in which the storage price is denominated and the storage fee paid
+    /// from the client's token escrow, or `None` for FIL.
+    /// Collateral is always denominated in FIL.
+    #[serde(default)]
+    pub payment_token: Option<Address>,
+}
+
+/// Serialize the proposal as a tuple, omitting the payment token if the fee is paid in FIL,
+/// so that the encoding (and so the CID and client signature) of such proposals is unchanged.
+impl Serialize for DealProposal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeTuple;
+        let len = if self.payment_token.is_some() { 12 } else { 11 };
+        let mut tuple = serializer.serialize_tuple(len)?;
+        tuple.serialize_element(&self.piece_cid)?;
+        tuple.serialize_element(&self.piece_size)?;
+        tuple.serialize_element(&self.verified_deal)?;
+        tuple.serialize_element(&self.client)?;
+        tuple.serialize_element(&self.provider)?;
+        tuple.serialize_element(&self.label)?;
+        tuple.serialize_element(&self.start_epoch)?;
+        tuple.serialize_element(&self.end_epoch)?;
+        tuple.serialize_element(&self.storage_price_per_epoch)?;
+        tuple.serialize_element(&self.provider_collateral)?;
+        tuple.serialize_element(&self.client_collateral)?;
+        if let Some(payment_token) = &self.payment_token {
+            tuple.serialize_element(payment_token)?;
+        }
+        tuple.end()
+    }
 }
 
 impl DealProposal {
     pub fn duration(&self) -> ChainEpoch {
         self.end_epoch - self.start_epoch
     }
+    /// Total storage fee, denominated in the payment token if any, else FIL.
     pub fn total_storage_fee(&self) -> TokenAmount {
         self.storage_price_per_epoch.clone() * self.duration() as u64
     }
+    /// FIL to be locked in the client's escrow: the client collateral,
+    /// plus the storage fee unless it is paid in a token.
     pub fn client_balance_requirement(&self) -> TokenAmount {
-        &self.client_collateral + self.total_storage_fee()
+        match self.payment_token {
+            Some(_) => self.client_collateral.clone(),
+            None => &self.client_collateral + self.total_storage_fee(),
+        }
     }
     pub fn provider_balance_requirement(&self) -> &TokenAmount {
         &self.provider_collateral
     }
+    /// ID of the token in which the storage fee is paid, or `None` for FIL.
+    /// The payment token of a published proposal is normalised to an ID address.
+    pub fn payment_token_id(&self) -> Result<Option<ActorID>, ActorError> {
+        self.payment_token
+            .map(|token| {
+                token.id().map_err(|_| {
+                    actor_error!(illegal_state, "payment token {} is not an ID address", token)
+                })
+            })
+            .transpose()
+    }
 }
 
 /// ClientDealProposal is a DealProposal signed by a client
@@ -134,6 +200,69 @@ pub struct ClientDealProposal {
     pub client_signature: Signature,
 }
 
+/// DealCancellation identifies a published deal which its client wishes to cancel.
+/// The deal's proposal CID binds the cancellation to the specific deal terms.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DealCancellation {
+    pub deal_id: DealID,
+    pub proposal: Cid,
+}
+
+/// ClientDealCancellation is a DealCancellation signed by the deal's client
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClientDealCancellation {
+    pub cancellation: DealCancellation,
+    /// The client's signature over the CBOR-encoded `cancellation`, prefixed with
+    /// SIGNATURE_DOMAIN_SEPARATION_CANCEL_DEALS.
+    pub client_signature: Signature,
+}
+
+/// Terms on which a provider accepts deals, published on chain so clients can discover them.
+/// Deals the policy does not accept are dropped when the provider publishes them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DealAcceptancePolicy {
+    /// Minimum storage price per epoch in FIL. Deals paid in another token have no minimum price.
+    pub min_price_per_epoch: TokenAmount,
+    /// Whether only verified deals are accepted.
+    pub verified_only: bool,
+    /// Largest accepted piece size, if limited.
+    pub max_piece_size: Option<PaddedPieceSize>,
+    /// Root of a HAMT set of the IDs of the clients whose deals are accepted,
+    /// committing to the list by its hash. If absent, deals from any client are accepted.
+    pub allowed_clients: Option<Cid>,
+}
+
+impl DealAcceptancePolicy {
+    /// Checks that a proposal meets the policy's terms, other than the allowed clients,
+    /// which must be checked against the set loaded from `allowed_clients`.
+    pub fn check(&self, proposal: &DealProposal) -> Result<(), ActorError> {
+        if proposal.payment_token.is_none()
+            && proposal.storage_price_per_epoch < self.min_price_per_epoch
+        {
+            return Err(actor_error!(
+                illegal_argument,
+                "storage price {} below provider minimum {}",
+                proposal.storage_price_per_epoch,
+                self.min_price_per_epoch
+            ));
+        }
+        if self.verified_only && !proposal.verified_deal {
+            return Err(actor_error!(illegal_argument, "provider accepts only verified deals"));
+        }
+        if let Some(max_piece_size) = self.max_piece_size {
+            if proposal.piece_size.0 > max_piece_size.0 {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "piece size {} exceeds provider maximum {}",
+                    proposal.piece_size.0,
+                    max_piece_size.0
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize_tuple, Deserialize_tuple)]
 pub struct DealState {
     // 0 if not yet included in proven sector (0 is also a valid sector number)
@@ -1,7 +1,11 @@
+use cid::Cid;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::{ActorError, EventBuilder};
 use fvm_shared::ActorID;
+use fvm_shared::address::Address;
 use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::SectorNumber;
 
 /// Indicates a deal has been published.
 pub fn deal_published(
@@ -18,17 +22,23 @@ pub fn deal_published(
     )
 }
 
-/// Indicates a deal has been activated.
+/// Indicates a deal has been activated in a sector.
 pub fn deal_activated(
     rt: &impl Runtime,
     deal_id: DealID,
     client: ActorID,
     provider: ActorID,
+    sector: SectorNumber,
+    piece_cid: &Cid,
+    verified: bool,
 ) -> Result<(), ActorError> {
     rt.emit_event(
         &EventBuilder::new()
             .typ("deal-activated")
             .with_parties(deal_id, client, provider)
+            .field_indexed("sector", &sector)
+            .field_indexed("piece-cid", piece_cid)
+            .field_indexed("verified", &verified)
             .build()?,
     )
 }
@@ -63,6 +73,118 @@ pub fn deal_completed(
     )
 }
 
+/// Indicates a published deal has been cancelled before activation, by its client
+/// or by agreement of client and provider.
+pub fn deal_cancelled(
+    rt: &impl Runtime,
+    deal_id: DealID,
+    client: ActorID,
+    provider: ActorID,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("deal-cancelled")
+            .with_parties(deal_id, client, provider)
+            .build()?,
+    )
+}
+
+/// Indicates escrow funds have been withdrawn to a beneficiary nominated by the escrow owner.
+pub fn balance_withdrawn_to_beneficiary(
+    rt: &impl Runtime,
+    account: ActorID,
+    beneficiary: &Address,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("beneficiary-withdrawal")
+            .field_indexed("account", &account)
+            .field_indexed("beneficiary", beneficiary)
+            .field_bigint("amount", amount.atto())
+            .build()?,
+    )
+}
+
+/// Indicates funds have been deposited into a provider's collateral pool.
+pub fn collateral_pool_deposited(
+    rt: &impl Runtime,
+    provider: ActorID,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("collateral-pool-deposit")
+            .field_indexed("provider", &provider)
+            .field_bigint("amount", amount.atto())
+            .build()?,
+    )
+}
+
+/// Indicates funds have been withdrawn from a provider's collateral pool.
+pub fn collateral_pool_withdrawn(
+    rt: &impl Runtime,
+    provider: ActorID,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("collateral-pool-withdrawal")
+            .field_indexed("provider", &provider)
+            .field_bigint("amount", amount.atto())
+            .build()?,
+    )
+}
+
+/// Indicates a provider's escrow has been topped up from its collateral pool
+/// to cover the collateral for deals being published.
+pub fn collateral_topped_up(
+    rt: &impl Runtime,
+    provider: ActorID,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("collateral-top-up")
+            .field_indexed("provider", &provider)
+            .field_bigint("amount", amount.atto())
+            .build()?,
+    )
+}
+
+/// Indicates a provider has set or removed its deal acceptance policy.
+pub fn deal_acceptance_policy_updated(
+    rt: &impl Runtime,
+    provider: ActorID,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("deal-acceptance-policy-updated")
+            .field_indexed("provider", &provider)
+            .build()?,
+    )
+}
+
+/// Indicates a client's withdrawal has left its escrow short of the amount required by
+/// a provider watching it: the client's locked funds plus the provider's threshold.
+pub fn client_balance_below_threshold(
+    rt: &impl Runtime,
+    client: ActorID,
+    provider: ActorID,
+    required: &TokenAmount,
+    threshold: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("client-balance-below-threshold")
+            .field_indexed("client", &client)
+            .field_indexed("provider", &provider)
+            .field_bigint("required", required.atto())
+            .field_bigint("threshold", threshold.atto())
+            .build()?,
+    )
+}
+
 trait WithParties {
     fn with_parties(self, id: DealID, client: ActorID, provider: ActorID) -> EventBuilder;
 }
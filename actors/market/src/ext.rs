@@ -1,17 +1,16 @@
 use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
-use fvm_shared::bigint::bigint_ser;
-use fvm_shared::econ::TokenAmount;
-use fvm_shared::sector::StoragePower;
-
-use fil_actors_runtime::reward::FilterEstimate;
 
 pub mod account {
     use super::*;
 
+    use fil_actors_runtime::BatchReturn;
+
     pub const AUTHENTICATE_MESSAGE_METHOD: u64 =
         frc42_dispatch::method_hash!("AuthenticateMessage");
+    pub const BATCH_AUTHENTICATE_MESSAGE_METHOD: u64 =
+        frc42_dispatch::method_hash!("BatchAuthenticateMessage");
 
     #[derive(Serialize_tuple, Deserialize_tuple)]
     pub struct AuthenticateMessageParams {
@@ -20,16 +19,28 @@ pub mod account {
         #[serde(with = "strict_bytes")]
         pub message: Vec<u8>,
     }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct BatchAuthenticateMessageParams {
+        pub messages: Vec<AuthenticateMessageParams>,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct BatchAuthenticateMessageReturn {
+        pub results: BatchReturn,
+    }
 }
 
 pub mod miner {
     use super::*;
-    use cid::Cid;
-    use fvm_ipld_encoding::RawBytes;
     use fvm_shared::MethodNum;
-    use fvm_shared::clock::ChainEpoch;
-    use fvm_shared::piece::PaddedPieceSize;
-    use fvm_shared::sector::SectorNumber;
+
+    pub use fil_actors_runtime::builtin::miner::{
+        PieceChange, PieceReturn, SectorChanges, SectorContentChangedParams,
+        SectorContentChangedReturn, SectorReturn,
+    };
 
     pub const CONTROL_ADDRESSES_METHOD: u64 = 2;
     pub const IS_CONTROLLING_ADDRESS_EXPORTED: u64 =
@@ -42,6 +53,8 @@ pub mod miner {
         pub owner: Address,
         pub worker: Address,
         pub control_addresses: Vec<Address>,
+        #[serde(default)]
+        pub control_address_roles: Vec<u8>,
     }
 
     #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -55,44 +68,6 @@ pub mod miner {
     pub struct IsControllingAddressParam {
         pub address: Address,
     }
-
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    #[serde(transparent)]
-    pub struct SectorContentChangedParams {
-        pub sectors: Vec<SectorChanges>,
-    }
-
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    pub struct SectorChanges {
-        pub sector: SectorNumber,
-        pub minimum_commitment_epoch: ChainEpoch,
-        pub added: Vec<PieceChange>,
-    }
-
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    pub struct PieceChange {
-        pub data: Cid,
-        pub size: PaddedPieceSize,
-        pub payload: RawBytes,
-    }
-
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    #[serde(transparent)]
-    pub struct SectorContentChangedReturn {
-        pub sectors: Vec<SectorReturn>,
-    }
-
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    #[serde(transparent)]
-    pub struct SectorReturn {
-        pub added: Vec<PieceReturn>,
-    }
-
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    #[serde(transparent)]
-    pub struct PieceReturn {
-        pub accepted: bool,
-    }
 }
 
 pub mod verifreg {
@@ -100,6 +75,7 @@ pub mod verifreg {
     use cid::Cid;
     use fil_actors_runtime::BatchReturn;
     use fvm_shared::ActorID;
+    use fvm_shared::bigint::{BigInt, bigint_ser};
     use fvm_shared::clock::ChainEpoch;
     use fvm_shared::piece::PaddedPieceSize;
 
@@ -138,6 +114,21 @@ pub mod verifreg {
         // IDs of new allocations created.
         pub new_allocations: Vec<AllocationID>,
     }
+
+    pub const RELEASE_ALLOCATIONS_METHOD: u64 = frc42_dispatch::method_hash!("ReleaseAllocations");
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct ReleaseAllocationsParams {
+        pub client: ActorID,
+        pub allocation_ids: Vec<AllocationID>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct ReleaseAllocationsReturn {
+        pub results: BatchReturn,
+        #[serde(with = "bigint_ser")]
+        pub datacap_recovered: BigInt,
+    }
 }
 
 pub mod datacap {
@@ -145,24 +136,16 @@ pub mod datacap {
     pub const TRANSFER_FROM_METHOD: u64 = frc42_dispatch::method_hash!("TransferFrom");
 }
 
+pub mod token {
+    pub const TRANSFER_METHOD: u64 = frc42_dispatch::method_hash!("Transfer");
+}
+
 pub mod reward {
     pub const THIS_EPOCH_REWARD_METHOD: u64 = 3;
 }
 
 pub mod power {
-    use super::*;
+    pub use fil_actors_runtime::builtin::power::CurrentTotalPowerReturn;
 
     pub const CURRENT_TOTAL_POWER_METHOD: u64 = 9;
-
-    #[derive(Serialize_tuple, Deserialize_tuple)]
-    pub struct CurrentTotalPowerReturn {
-        #[serde(with = "bigint_ser")]
-        pub raw_byte_power: StoragePower,
-        #[serde(with = "bigint_ser")]
-        pub quality_adj_power: StoragePower,
-        pub pledge_collateral: TokenAmount,
-        pub quality_adj_power_smoothed: FilterEstimate,
-        pub ramp_start_epoch: i64,
-        pub ramp_duration_epochs: u64,
-    }
 }
@@ -7,7 +7,11 @@ use std::collections::{BTreeMap, BTreeSet, HashSet};
 use cid::Cid;
 use cid::multihash::Multihash;
 use fil_actors_runtime::reward::ThisEpochRewardReturn;
-use frc46_token::token::types::{BalanceReturn, TransferFromParams, TransferFromReturn};
+use frc46_token::receiver::{FRC46_TOKEN_TYPE, FRC46TokenReceived};
+use frc46_token::token::types::{
+    BalanceReturn, TransferFromParams, TransferFromReturn, TransferParams,
+};
+use fvm_actor_utils::receiver::UniversalReceiverParams;
 use fvm_ipld_bitfield::BitField;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -17,12 +21,14 @@ use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
 use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::crypto::signature::Signature;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PieceInfo;
 use fvm_shared::sector::{RegisteredSealProof, SectorNumber, SectorSize, StoragePower};
 use fvm_shared::sys::SendFlags;
+use fvm_shared::version::NetworkVersion;
 use fvm_shared::{ActorID, METHOD_CONSTRUCTOR, METHOD_SEND};
 use integer_encoding::VarInt;
 use log::{info, warn};
@@ -37,7 +43,10 @@ use fil_actors_runtime::{
     DATACAP_TOKEN_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
     VERIFIED_REGISTRY_ACTOR_ADDR, actor_dispatch, actor_error, deserialize_block,
 };
-use fil_actors_runtime::{BatchReturnGen, FIRST_ACTOR_SPECIFIC_EXIT_CODE, extract_send_result};
+use fil_actors_runtime::{
+    BatchReturn, BatchReturnGen, FIRST_ACTOR_SPECIFIC_EXIT_CODE, MethodDeprecation,
+    extract_send_result,
+};
 
 use crate::balance_table::BalanceTable;
 use crate::ext::verifreg::{AllocationID, AllocationRequest};
@@ -69,6 +78,11 @@ pub const EX_DEAL_EXPIRED: ExitCode = ExitCode::new(FIRST_ACTOR_SPECIFIC_EXIT_CO
 // Indicates that information about a deal's activation is not yet available.
 pub const EX_DEAL_NOT_ACTIVATED: ExitCode = ExitCode::new(FIRST_ACTOR_SPECIFIC_EXIT_CODE + 1);
 
+// Network version from which internal method numbers duplicated by FRC-42 exports are rejected.
+// The methods remain available until then, giving callers a network upgrade's notice
+// to move to the exported methods.
+pub const INTERNAL_METHODS_SUNSET_VERSION: NetworkVersion = NetworkVersion::new(29);
+
 /// Market actor methods available
 #[derive(FromPrimitive)]
 #[repr(u64)]
@@ -99,7 +113,28 @@ pub enum Method {
     GetDealActivationExported = frc42_dispatch::method_hash!("GetDealActivation"),
     GetDealSectorExported = frc42_dispatch::method_hash!("GetDealSector"),
     SettleDealPaymentsExported = frc42_dispatch::method_hash!("SettleDealPayments"),
+    GetDealsForPieceExported = frc42_dispatch::method_hash!("GetDealsForPiece"),
+    GetDealsForLabelExported = frc42_dispatch::method_hash!("GetDealsForLabel"),
+    BatchGetDealActivationExported = frc42_dispatch::method_hash!("BatchGetDealActivation"),
+    BatchGetDealTermExported = frc42_dispatch::method_hash!("BatchGetDealTerm"),
+    BatchGetDealPartiesExported = frc42_dispatch::method_hash!("BatchGetDealParties"),
+    BatchGetDealVerifiedExported = frc42_dispatch::method_hash!("BatchGetDealVerified"),
+    ListProviderDealsExported = frc42_dispatch::method_hash!("ListProviderDeals"),
+    CancelStorageDealsExported = frc42_dispatch::method_hash!("CancelStorageDeals"),
+    SettleDealPaymentsPreviewExported = frc42_dispatch::method_hash!("SettleDealPaymentsPreview"),
+    DepositCollateralPoolExported = frc42_dispatch::method_hash!("DepositCollateralPool"),
+    WithdrawCollateralPoolExported = frc42_dispatch::method_hash!("WithdrawCollateralPool"),
+    SetDealAcceptancePolicyExported = frc42_dispatch::method_hash!("SetDealAcceptancePolicy"),
+    GetDealAcceptancePolicyExported = frc42_dispatch::method_hash!("GetDealAcceptancePolicy"),
+    ListProviderPendingDealsExported = frc42_dispatch::method_hash!("ListProviderPendingDeals"),
     SectorContentChangedExported = ext::miner::SECTOR_CONTENT_CHANGED,
+    MutuallyCancelDealsExported = frc42_dispatch::method_hash!("MutuallyCancelDeals"),
+    SetClientWithdrawalWatchExported = frc42_dispatch::method_hash!("SetClientWithdrawalWatch"),
+    UniversalReceiverHook = frc42_dispatch::method_hash!("Receive"),
+    WithdrawTokenBalanceExported = frc42_dispatch::method_hash!("WithdrawTokenBalance"),
+    GetTokenBalanceExported = frc42_dispatch::method_hash!("GetTokenBalance"),
+    PublishStorageDeals2Exported = frc42_dispatch::method_hash!("PublishStorageDeals2"),
+    GetDealSectorLocationExported = frc42_dispatch::method_hash!("GetDealSectorLocation"),
 }
 
 /// Market Actor
@@ -140,6 +175,8 @@ impl Actor {
 
     /// Attempt to withdraw the specified amount from the balance held in escrow.
     /// If less than the specified amount is available, yields the entire available balance.
+    /// The funds are sent to the escrow owner, or to the beneficiary if one is specified.
+    /// Providers watching the escrow are notified if its unlocked balance falls below their threshold.
     fn withdraw_balance(
         rt: &impl Runtime,
         params: WithdrawBalanceParams,
@@ -148,15 +185,185 @@ impl Actor {
             return Err(actor_error!(illegal_argument, "negative amount: {}", params.amount));
         }
 
+        let (nominal, recipient, approved) = escrow_address(rt, &params.provider_or_client)?;
+        let recipient = match params.beneficiary {
+            // only the recipient can direct funds elsewhere, so a provider's worker cannot
+            Some(beneficiary) => {
+                rt.validate_immediate_caller_is(std::iter::once(&recipient))?;
+                beneficiary
+            }
+            // for providers -> only corresponding owner or worker can withdraw
+            // for clients -> only the client i.e the recipient can withdraw
+            None => {
+                rt.validate_immediate_caller_is(&approved)?;
+                recipient
+            }
+        };
+
+        let (amount_extracted, uncovered_watches) = rt.transaction(|st: &mut State, rt| {
+            let ex = st.withdraw_balance_from_escrow_table(rt.store(), &nominal, &params.amount)?;
+            let uncovered = if ex.is_positive() {
+                st.uncovered_client_withdrawal_watches(rt.store(), nominal.id().unwrap())?
+            } else {
+                vec![]
+            };
+
+            Ok((ex, uncovered))
+        })?;
+
+        extract_send_result(rt.send_simple(
+            &recipient,
+            METHOD_SEND,
+            None,
+            amount_extracted.clone(),
+        ))?;
+
+        if params.beneficiary.is_some() {
+            emit::balance_withdrawn_to_beneficiary(
+                rt,
+                nominal.id().unwrap(),
+                &recipient,
+                &amount_extracted,
+            )?;
+        }
+
+        for (watch, required) in uncovered_watches {
+            emit::client_balance_below_threshold(
+                rt,
+                nominal.id().unwrap(),
+                watch.provider,
+                &required,
+                &watch.threshold,
+            )?;
+        }
+
+        Ok(WithdrawBalanceReturn { amount_withdrawn: amount_extracted })
+    }
+
+    /// Deposits FRC-46 tokens transferred to the market into escrow, from which the storage fees
+    /// of deals denominated in the token are paid.
+    /// The tokens are credited to the address given by `AddBalanceParams` in the transfer's
+    /// operator data, or to the sender if the operator data is empty.
+    fn universal_receiver_hook(
+        rt: &impl Runtime,
+        params: UniversalReceiverParams,
+    ) -> Result<(), ActorError> {
+        // Only tokens accepted for payment by the policy may be deposited.
+        rt.validate_immediate_caller_accept_any()?;
+        let token = rt.message().caller().id().unwrap();
+        if !rt.policy().market_payment_tokens.contains(&token) {
+            return Err(actor_error!(forbidden, "token {} is not accepted for payment", token));
+        }
+        let my_id = rt.message().receiver().id().unwrap();
+
+        let received = validate_tokens_received(&params, my_id)?;
+        if !received.amount.is_positive() {
+            return Err(actor_error!(
+                illegal_argument,
+                "token amount to add must be greater than zero was: {}",
+                received.amount
+            ));
+        }
+
+        let nominal = if received.operator_data.is_empty() {
+            Address::new_id(received.from)
+        } else {
+            let params: AddBalanceParams =
+                deserialize(&received.operator_data, "token deposit params")?;
+            escrow_address(rt, &params.provider_or_client)?.0
+        };
+
+        rt.transaction(|st: &mut State, rt| {
+            st.add_balance_to_token_escrow(rt.store(), token, &nominal, &received.amount)
+        })
+    }
+
+    /// Attempt to withdraw the specified amount of an FRC-46 token from the balance held in escrow.
+    /// If less than the specified amount is available, yields the entire available balance.
+    /// The tokens are transferred to the escrow owner.
+    fn withdraw_token_balance(
+        rt: &impl Runtime,
+        params: WithdrawTokenBalanceParams,
+    ) -> Result<WithdrawBalanceReturn, ActorError> {
+        if params.amount < TokenAmount::zero() {
+            return Err(actor_error!(illegal_argument, "negative amount: {}", params.amount));
+        }
+
+        let token = rt.resolve_address(&params.token).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve token address {}", params.token)
+        })?;
         let (nominal, recipient, approved) = escrow_address(rt, &params.provider_or_client)?;
         // for providers -> only corresponding owner or worker can withdraw
         // for clients -> only the client i.e the recipient can withdraw
         rt.validate_immediate_caller_is(&approved)?;
 
         let amount_extracted = rt.transaction(|st: &mut State, rt| {
-            let ex = st.withdraw_balance_from_escrow_table(rt.store(), &nominal, &params.amount)?;
+            st.withdraw_balance_from_token_escrow(rt.store(), token, &nominal, &params.amount)
+        })?;
+
+        if amount_extracted.is_positive() {
+            transfer_tokens(rt, token, &recipient, &amount_extracted)?;
+        }
+
+        Ok(WithdrawBalanceReturn { amount_withdrawn: amount_extracted })
+    }
+
+    /// Deposits the received value into a provider's collateral pool.
+    /// When the provider's escrow falls short of the collateral for deals being published,
+    /// the shortfall is moved from the pool into escrow rather than dropping the deals.
+    fn deposit_collateral_pool(
+        rt: &impl Runtime,
+        params: DepositCollateralPoolParams,
+    ) -> Result<(), ActorError> {
+        let msg_value = rt.message().value_received();
+
+        if msg_value <= TokenAmount::zero() {
+            return Err(actor_error!(
+                illegal_argument,
+                "amount to deposit must be greater than zero was: {}",
+                msg_value
+            ));
+        }
+
+        rt.validate_immediate_caller_accept_any()?;
 
-            Ok(ex)
+        let provider = rt.resolve_address(&params.provider).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.provider)
+        })?;
+        let code_id = rt
+            .get_actor_code_cid(&provider)
+            .ok_or_else(|| actor_error!(illegal_argument, "no code for address {}", provider))?;
+        if rt.resolve_builtin_actor_type(&code_id) != Some(Type::Miner) {
+            return Err(actor_error!(
+                illegal_argument,
+                "collateral pool provider {} is not a storage miner actor",
+                provider
+            ));
+        }
+
+        rt.transaction(|st: &mut State, rt| {
+            st.add_balance_to_collateral_pool(rt.store(), &Address::new_id(provider), &msg_value)
+        })?;
+
+        emit::collateral_pool_deposited(rt, provider, &msg_value)
+    }
+
+    /// Attempt to withdraw the specified amount from a provider's collateral pool.
+    /// If less than the specified amount is available, yields the entire pool balance.
+    /// The funds are sent to the provider's owner.
+    fn withdraw_collateral_pool(
+        rt: &impl Runtime,
+        params: WithdrawCollateralPoolParams,
+    ) -> Result<WithdrawCollateralPoolReturn, ActorError> {
+        if params.amount < TokenAmount::zero() {
+            return Err(actor_error!(illegal_argument, "negative amount: {}", params.amount));
+        }
+
+        let (nominal, recipient, approved) = escrow_address(rt, &params.provider)?;
+        rt.validate_immediate_caller_is(&approved)?;
+
+        let amount_extracted = rt.transaction(|st: &mut State, rt| {
+            st.withdraw_balance_from_collateral_pool(rt.store(), &nominal, &params.amount)
         })?;
 
         extract_send_result(rt.send_simple(
@@ -166,7 +373,161 @@ impl Actor {
             amount_extracted.clone(),
         ))?;
 
-        Ok(WithdrawBalanceReturn { amount_withdrawn: amount_extracted })
+        emit::collateral_pool_withdrawn(rt, nominal.id().unwrap(), &amount_extracted)?;
+
+        Ok(WithdrawCollateralPoolReturn { amount_withdrawn: amount_extracted })
+    }
+
+    /// Sets or removes the terms on which a provider accepts deals.
+    /// Deals published by the provider which the policy does not accept are dropped.
+    /// Must be called by the provider's owner or worker.
+    fn set_deal_acceptance_policy(
+        rt: &impl Runtime,
+        params: SetDealAcceptancePolicyParams,
+    ) -> Result<(), ActorError> {
+        let provider = rt.resolve_address(&params.provider).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.provider)
+        })?;
+        let code_id = rt
+            .get_actor_code_cid(&provider)
+            .ok_or_else(|| actor_error!(illegal_argument, "no code for address {}", provider))?;
+        if rt.resolve_builtin_actor_type(&code_id) != Some(Type::Miner) {
+            return Err(actor_error!(
+                illegal_argument,
+                "deal acceptance policy provider {} is not a storage miner actor",
+                provider
+            ));
+        }
+        let (owner, worker, _) = request_miner_control_addrs(rt, provider)?;
+        rt.validate_immediate_caller_is(&[owner, worker])?;
+
+        let policy = match params.policy {
+            Some(mut policy) => {
+                if policy.min_price_per_epoch.is_negative() {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "negative minimum price: {}",
+                        policy.min_price_per_epoch
+                    ));
+                }
+                if let Some(max_piece_size) = policy.max_piece_size {
+                    max_piece_size.validate().map_err(|e| {
+                        actor_error!(illegal_argument, "maximum piece size is invalid: {}", e)
+                    })?;
+                }
+                policy.allowed_clients.sort_unstable();
+                policy.allowed_clients.dedup();
+                if policy.allowed_clients.len() > detail::DEAL_ACCEPTANCE_MAX_ALLOWED_CLIENTS {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "policy can allow at most {} clients, has {}",
+                        detail::DEAL_ACCEPTANCE_MAX_ALLOWED_CLIENTS,
+                        policy.allowed_clients.len()
+                    ));
+                }
+                let allowed_clients = if policy.allowed_clients.is_empty() {
+                    None
+                } else {
+                    let mut allowed = AllowedClientsSet::empty(
+                        rt.store(),
+                        ALLOWED_CLIENTS_CONFIG,
+                        "allowed clients",
+                    );
+                    for client in &policy.allowed_clients {
+                        allowed.put(client)?;
+                    }
+                    Some(allowed.flush()?)
+                };
+                Some(DealAcceptancePolicy {
+                    min_price_per_epoch: policy.min_price_per_epoch,
+                    verified_only: policy.verified_only,
+                    max_piece_size: policy.max_piece_size,
+                    allowed_clients,
+                })
+            }
+            None => None,
+        };
+
+        rt.transaction(|st: &mut State, rt| {
+            st.set_deal_acceptance_policy(rt.store(), provider, policy)
+        })?;
+
+        emit::deal_acceptance_policy_updated(rt, provider)
+    }
+
+    /// Sets or removes a provider's watch on a client's escrow withdrawals.
+    /// While watching, the provider is notified by an event whenever the client withdraws
+    /// so as to leave less than the threshold of its escrow unlocked, e.g. so that it may
+    /// stop sealing deals the client can no longer fund.
+    /// Must be called by the provider's owner or worker. A watch may only be set by a
+    /// provider with a deal from the client, so unrelated providers cannot use up the
+    /// client's watches.
+    fn set_client_withdrawal_watch(
+        rt: &impl Runtime,
+        params: SetClientWithdrawalWatchParams,
+    ) -> Result<(), ActorError> {
+        let provider = rt.resolve_address(&params.provider).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.provider)
+        })?;
+        let code_id = rt
+            .get_actor_code_cid(&provider)
+            .ok_or_else(|| actor_error!(illegal_argument, "no code for address {}", provider))?;
+        if rt.resolve_builtin_actor_type(&code_id) != Some(Type::Miner) {
+            return Err(actor_error!(
+                illegal_argument,
+                "withdrawal watch provider {} is not a storage miner actor",
+                provider
+            ));
+        }
+        let (owner, worker, _) = request_miner_control_addrs(rt, provider)?;
+        rt.validate_immediate_caller_is(&[owner, worker])?;
+
+        let client = rt.resolve_address(&params.client).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.client)
+        })?;
+        if let Some(threshold) = &params.threshold {
+            if !threshold.is_positive() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "withdrawal watch threshold must be positive, was {}",
+                    threshold
+                ));
+            }
+        }
+
+        rt.transaction(|st: &mut State, rt| {
+            if params.threshold.is_some() {
+                let proposal = st
+                    .find_proposal(rt.store(), params.deal_id)?
+                    .ok_or_else(|| actor_error!(not_found, "no such deal {}", params.deal_id))?;
+                if proposal.provider != Address::new_id(provider)
+                    || proposal.client != Address::new_id(client)
+                {
+                    return Err(actor_error!(
+                        forbidden,
+                        "deal {} is not between provider {} and client {}",
+                        params.deal_id,
+                        provider,
+                        client
+                    ));
+                }
+            }
+            st.set_client_withdrawal_watch(rt.store(), client, provider, params.threshold)
+        })
+    }
+
+    /// Returns the terms on which a provider accepts deals, if it has published them.
+    fn get_deal_acceptance_policy(
+        rt: &impl Runtime,
+        params: GetDealAcceptancePolicyParams,
+    ) -> Result<GetDealAcceptancePolicyReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let provider = rt.resolve_address(&params.provider).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.provider)
+        })?;
+        let st: State = rt.state()?;
+        let policy = st.get_deal_acceptance_policy(rt.store(), provider)?;
+        Ok(GetDealAcceptancePolicyReturn { policy })
     }
 
     /// Returns the escrow balance and locked amount for an address.
@@ -191,11 +552,56 @@ impl Actor {
         Ok(GetBalanceReturn { balance, locked })
     }
 
+    /// Returns the escrow balance and locked amount of an FRC-46 token for an address.
+    fn get_token_balance(
+        rt: &impl Runtime,
+        params: GetTokenBalanceParams,
+    ) -> Result<GetBalanceReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let token = rt.resolve_address(&params.token).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve token address {}", params.token)
+        })?;
+        let nominal = rt.resolve_address(&params.account).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.account)
+        })?;
+
+        let st: State = rt.state()?;
+        let (balance, locked) =
+            st.get_token_balance(rt.store(), token, &Address::new_id(nominal))?;
+        Ok(GetBalanceReturn { balance, locked })
+    }
+
     /// Publish a new set of storage deals (not yet included in a sector).
+    /// Aborts if the verified registry rejects the datacap allocations for any client's deals.
     fn publish_storage_deals(
         rt: &impl Runtime,
         params: PublishStorageDealsParams,
     ) -> Result<PublishStorageDealsReturn, ActorError> {
+        let ret = Self::publish_deals(rt, params, false)?;
+        Ok(PublishStorageDealsReturn { ids: ret.ids, valid_deals: ret.valid_deals })
+    }
+
+    /// Publish a new set of storage deals, as for PublishStorageDeals, also returning the
+    /// result of allocating datacap for each verified deal.
+    /// Unlike PublishStorageDeals, a rejected datacap allocation does not abort the call.
+    /// When the verified registry rejects the allocations for a client's deals, each is
+    /// requested again alone, and only the verified deals whose allocation still fails are
+    /// dropped, with the rest of the batch published.
+    fn publish_storage_deals2(
+        rt: &impl Runtime,
+        params: PublishStorageDealsParams,
+    ) -> Result<PublishStorageDeals2Return, ActorError> {
+        Self::publish_deals(rt, params, true)
+    }
+
+    // Publishes deals for PublishStorageDeals and PublishStorageDeals2.
+    // If `retry_allocations` is set, a rejected datacap allocation drops the deals which
+    // cannot be allocated singly, otherwise it aborts.
+    fn publish_deals(
+        rt: &impl Runtime,
+        params: PublishStorageDealsParams,
+        retry_allocations: bool,
+    ) -> Result<PublishStorageDeals2Return, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         if params.deals.is_empty() {
             return Err(actor_error!(illegal_argument, "Empty deals parameter"));
@@ -218,34 +624,57 @@ impl Actor {
             ));
         }
 
-        let caller = rt.message().caller();
-        let caller_status: ext::miner::IsControllingAddressReturn =
-            deserialize_block(extract_send_result(rt.send_simple(
-                &Address::new_id(provider_id),
-                ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
-                IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam {
-                    address: caller,
-                })?,
-                TokenAmount::zero(),
-            ))?)?;
-        if !caller_status.is_controlling {
-            return Err(actor_error!(
-                forbidden,
-                "caller {} is not worker or control address of provider {}",
-                caller,
-                provider_id
-            ));
-        }
+        validate_caller_controls_provider(rt, provider_id)?;
         // Deals that passed `AuthenticateMessage` and other state-less checks.
         let mut validity_index: Vec<bool> = Vec::with_capacity(params.deals.len());
 
         let baseline_power = request_current_baseline_power(rt)?;
         let (network_raw_power, _) = request_current_network_power(rt)?;
 
-        // We perform these checks before loading state since the call to `AuthenticateMessage` could recurse
+        // Client addresses resolved to ID addresses, cached for the rest of the method.
+        let mut client_ids: BTreeMap<Address, Option<ActorID>> = BTreeMap::new();
+        // Indices of the deals proposed by each resolved client.
+        let mut client_deal_indices: BTreeMap<ActorID, Vec<usize>> = BTreeMap::new();
         for (di, deal) in params.deals.iter().enumerate() {
-            let valid = if let Err(e) = validate_deal(rt, deal, &network_raw_power, &baseline_power)
+            let client = deal.proposal.client;
+            if let Some(client_id) =
+                *client_ids.entry(client).or_insert_with(|| rt.resolve_address(&client))
             {
+                client_deal_indices.entry(client_id).or_default().push(di);
+            }
+        }
+
+        // Signatures of deals from an account client proposing more than one deal are
+        // authenticated with a single batch call to the client, which loads its key only once.
+        let mut authenticated: Vec<Option<bool>> = vec![None; params.deals.len()];
+        // We perform these checks before loading state since the call to `AuthenticateMessage` could recurse
+        for (di, deal) in params.deals.iter().enumerate() {
+            if authenticated[di].is_none() {
+                let batch = client_ids[&deal.proposal.client]
+                    .and_then(|client_id| {
+                        client_deal_indices.get(&client_id).map(|ids| (client_id, ids))
+                    })
+                    .filter(|(client_id, indices)| indices.len() > 1 && is_account(rt, *client_id));
+                if let Some((client_id, indices)) = batch {
+                    let results =
+                        batch_authenticate_deal_proposals(rt, client_id, indices, &params.deals);
+                    for (i, ok) in indices.iter().zip(results) {
+                        authenticated[*i] = Some(ok);
+                    }
+                } else {
+                    authenticated[di] = Some(match authenticate_deal_proposal(rt, deal) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            info!("invalid deal {}: {}", di, e);
+                            false
+                        }
+                    });
+                }
+            }
+
+            let valid = if authenticated[di] != Some(true) {
+                false
+            } else if let Err(e) = validate_deal(rt, deal, &network_raw_power, &baseline_power) {
                 info!("invalid deal {}: {}", di, e);
                 false
             } else {
@@ -256,20 +685,36 @@ impl Actor {
         }
 
         struct ValidDeal {
+            // Index of the deal in the parameters.
+            index: usize,
             proposal: DealProposal,
             serialized_proposal: RawBytes,
             cid: Cid,
         }
 
+        // Result of allocating datacap for each verified deal, by index in the parameters.
+        // Verified deals dropped before allocation is attempted fail as invalid.
+        let mut allocation_codes: BTreeMap<usize, ExitCode> = params
+            .deals
+            .iter()
+            .enumerate()
+            .filter(|(_, deal)| deal.proposal.verified_deal)
+            .map(|(di, _)| (di, ExitCode::USR_ILLEGAL_ARGUMENT))
+            .collect();
+
         // Deals that passed validation.
         let mut valid_deals: Vec<ValidDeal> = Vec::with_capacity(params.deals.len());
         // CIDs of valid proposals.
         let mut proposal_cid_lookup = BTreeSet::new();
         let mut total_client_lockup: BTreeMap<ActorID, TokenAmount> = BTreeMap::new();
+        // Storage fees to lock for each client in each payment token, by (client, token).
+        let mut total_client_token_lockup: BTreeMap<(ActorID, ActorID), TokenAmount> =
+            BTreeMap::new();
         // Client datacap balance remaining after allocations for deals processed so far.
         let mut client_datacap_remaining: BTreeMap<ActorID, TokenAmount> = BTreeMap::new();
-        // Verified allocation requests to make for each client, paired with the proposal CID.
-        let mut client_alloc_reqs: BTreeMap<ActorID, Vec<(Cid, AllocationRequest)>> =
+        // Verified allocation requests to make for each client,
+        // paired with the deal's index in the parameters and proposal CID.
+        let mut client_alloc_reqs: BTreeMap<ActorID, Vec<(usize, Cid, AllocationRequest)>> =
             BTreeMap::new();
         let mut total_provider_lockup = TokenAmount::zero();
 
@@ -277,6 +722,15 @@ impl Actor {
         let curr_epoch = rt.curr_epoch();
 
         let state: State = rt.state()?;
+        // Funds available to top up the provider's escrow, and the top-up required so far.
+        let provider_pool_balance =
+            state.get_collateral_pool_balance(rt.store(), &Address::new_id(provider_id))?;
+        let mut provider_top_up = TokenAmount::zero();
+        let acceptance_policy = state.get_deal_acceptance_policy(rt.store(), provider_id)?;
+        let allowed_clients = match acceptance_policy.as_ref().and_then(|p| p.allowed_clients) {
+            Some(root) => Some(state.load_allowed_clients(rt.store(), &root)?),
+            None => None,
+        };
 
         for (di, mut deal) in params.deals.into_iter().enumerate() {
             if !*validity_index.get(di).context_code(
@@ -295,7 +749,7 @@ impl Actor {
                 );
                 continue;
             }
-            let client_id = match rt.resolve_address(&deal.proposal.client) {
+            let client_id = match client_ids[&deal.proposal.client] {
                 Some(client) => client,
                 _ => {
                     info!(
@@ -306,6 +760,44 @@ impl Actor {
                 }
             };
 
+            // Resolve the token in which the storage fee is paid, if not FIL.
+            let payment_token_id = match deal.proposal.payment_token {
+                Some(token) => match rt.resolve_address(&token) {
+                    Some(token_id) if rt.policy().market_payment_tokens.contains(&token_id) => {
+                        Some(token_id)
+                    }
+                    Some(token_id) => {
+                        info!(
+                            "invalid deal {}: token {} is not accepted for payment",
+                            di, token_id
+                        );
+                        continue;
+                    }
+                    None => {
+                        info!(
+                            "invalid deal {}: failed to resolve proposal.payment_token address {}",
+                            di, token
+                        );
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            // drop deals the provider's acceptance policy rejects
+            if let Some(policy) = &acceptance_policy {
+                if let Err(e) = policy.check(&deal.proposal) {
+                    info!("invalid deal {}: {}", di, e);
+                    continue;
+                }
+            }
+            if let Some(allowed_clients) = &allowed_clients {
+                if !allowed_clients.has(&client_id)? {
+                    info!("invalid deal {}: client {} not allowed by provider", di, client_id);
+                    continue;
+                }
+            }
+
             // drop deals with insufficient lock up to cover costs
             let mut client_lockup =
                 total_client_lockup.get(&client_id).cloned().unwrap_or_default();
@@ -319,15 +811,39 @@ impl Actor {
                 continue;
             }
 
+            let client_token_lockup = match payment_token_id {
+                Some(token_id) => {
+                    let mut lockup = total_client_token_lockup
+                        .get(&(client_id, token_id))
+                        .cloned()
+                        .unwrap_or_default();
+                    lockup += deal.proposal.total_storage_fee();
+                    if !state.token_balance_covered(
+                        rt.store(),
+                        token_id,
+                        Address::new_id(client_id),
+                        &lockup,
+                    )? {
+                        info!(
+                            "invalid deal: {}: insufficient client token funds to cover storage fee",
+                            di
+                        );
+                        continue;
+                    }
+                    Some((token_id, lockup))
+                }
+                None => None,
+            };
+
             let mut provider_lockup = total_provider_lockup.clone();
             provider_lockup += &deal.proposal.provider_collateral;
-            let provider_balance_ok = state.balance_covered(
+            let provider_shortfall = state.balance_shortfall(
                 rt.store(),
                 Address::new_id(provider_id),
                 &provider_lockup,
             )?;
 
-            if !provider_balance_ok {
+            if provider_shortfall > provider_pool_balance {
                 info!("invalid deal: {}: insufficient provider funds to cover proposal cost", di);
                 continue;
             }
@@ -337,6 +853,7 @@ impl Actor {
             // Must happen after signature verification and before taking cid.
             deal.proposal.provider = Address::new_id(provider_id);
             deal.proposal.client = Address::new_id(client_id);
+            deal.proposal.payment_token = payment_token_id.map(Address::new_id);
 
             let serialized_proposal = serialize(&deal.proposal, "normalized deal proposal")
                 .context_code(ExitCode::USR_SERIALIZATION, "failed to serialize")?;
@@ -369,45 +886,100 @@ impl Actor {
                     TokenAmount::from_whole(deal.proposal.piece_size.0 as i64);
                 if remaining_datacap < piece_datacap_required {
                     client_datacap_remaining.insert(client_id, remaining_datacap);
+                    allocation_codes.insert(di, ExitCode::USR_INSUFFICIENT_FUNDS);
                     continue; // Drop the deal
                 }
                 client_datacap_remaining
                     .insert(client_id, remaining_datacap - piece_datacap_required);
-                client_alloc_reqs
-                    .entry(client_id)
-                    .or_default()
-                    .push((pcid, alloc_request_for_deal(&deal.proposal, rt.policy(), curr_epoch)));
+                client_alloc_reqs.entry(client_id).or_default().push((
+                    di,
+                    pcid,
+                    alloc_request_for_deal(&deal.proposal, rt.policy(), curr_epoch),
+                ));
             }
 
             total_provider_lockup = provider_lockup;
+            provider_top_up = provider_shortfall;
             total_client_lockup.insert(client_id, client_lockup);
+            if let Some((token_id, lockup)) = client_token_lockup {
+                total_client_token_lockup.insert((client_id, token_id), lockup);
+            }
             proposal_cid_lookup.insert(pcid);
-            valid_deals.push(ValidDeal { proposal: deal.proposal, serialized_proposal, cid: pcid });
+            valid_deals.push(ValidDeal {
+                index: di,
+                proposal: deal.proposal,
+                serialized_proposal,
+                cid: pcid,
+            });
             valid_input_bf.set(di as u64)
         }
 
         // Make datacap allocation requests by transferring datacap tokens, once per client.
         // Record the allocation ID for each deal proposal CID.
         let mut deal_allocation_ids: BTreeMap<Cid, AllocationID> = BTreeMap::new();
-        for (client_id, cids_and_reqs) in client_alloc_reqs.iter() {
-            let reqs: Vec<AllocationRequest> =
-                cids_and_reqs.iter().map(|(_, req)| req.clone()).collect();
-            let params = datacap_transfer_request(&Address::new_id(*client_id), reqs)?;
-            // A datacap transfer is all-or-nothing.
-            // We expect it to succeed because we checked the client's balance earlier.
-            let alloc_ids = transfer_from(rt, params)
-                .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
-                    format!("failed to transfer datacap from client {}", *client_id)
-                })?;
-            if alloc_ids.len() != cids_and_reqs.len() {
-                return Err(
-                    actor_error!(illegal_state; "datacap transfer returned {} allocation IDs for {} requests",
-                        alloc_ids.len(), cids_and_reqs.len()),
-                );
+        for (client_id, reqs) in client_alloc_reqs.iter() {
+            let batch = reqs.iter().map(|(_, _, req)| req.clone()).collect();
+            // A datacap transfer is all-or-nothing. If a batch of requests is rejected,
+            // either the publication aborts, or each request is retried alone so that only
+            // the deals which cannot be allocated are dropped.
+            let results: Vec<Result<AllocationID, ActorError>> =
+                match allocate_datacap(rt, *client_id, batch)? {
+                    Ok(alloc_ids) => alloc_ids.into_iter().map(Ok).collect(),
+                    Err(e) if !retry_allocations => {
+                        return Err(e).with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                            format!("failed to transfer datacap from client {}", *client_id)
+                        });
+                    }
+                    Err(e) if reqs.len() > 1 => {
+                        info!(
+                            "datacap transfer from client {} failed, retrying singly: {}",
+                            client_id, e
+                        );
+                        let mut results = Vec::with_capacity(reqs.len());
+                        for (_, _, req) in reqs.iter() {
+                            results.push(
+                                allocate_datacap(rt, *client_id, vec![req.clone()])?
+                                    .map(|alloc_ids| alloc_ids[0]),
+                            );
+                        }
+                        results
+                    }
+                    Err(e) => vec![Err(e)],
+                };
+            for ((di, cid, _), result) in reqs.iter().zip(results) {
+                match result {
+                    Ok(alloc_id) => {
+                        deal_allocation_ids.insert(*cid, alloc_id);
+                        allocation_codes.insert(*di, ExitCode::OK);
+                    }
+                    Err(e) => {
+                        info!("invalid deal {}: failed to allocate datacap: {}", di, e);
+                        allocation_codes.insert(*di, e.exit_code());
+                    }
+                }
             }
-            for ((cid, _), alloc_id) in cids_and_reqs.iter().zip(alloc_ids.iter()) {
-                deal_allocation_ids.insert(*cid, *alloc_id);
+        }
+
+        // Drop verified deals for which no allocation was made.
+        let deal_count = valid_deals.len();
+        valid_deals.retain(|valid_deal| {
+            let allocated = !valid_deal.proposal.verified_deal
+                || deal_allocation_ids.contains_key(&valid_deal.cid);
+            if !allocated {
+                valid_input_bf.unset(valid_deal.index as u64);
             }
+            allocated
+        });
+        if valid_deals.len() < deal_count {
+            // The remaining deals may need a smaller top-up of the provider's escrow.
+            let provider_lockup = valid_deals
+                .iter()
+                .fold(TokenAmount::zero(), |sum, d| sum + &d.proposal.provider_collateral);
+            provider_top_up = state.balance_shortfall(
+                rt.store(),
+                Address::new_id(provider_id),
+                &provider_lockup,
+            )?;
         }
 
         let valid_deal_count = valid_input_bf.len();
@@ -424,12 +996,21 @@ impl Actor {
         }
 
         let mut new_deal_ids = Vec::with_capacity(valid_deals.len());
+        let mut new_allocation_ids = Vec::with_capacity(valid_deals.len());
         rt.transaction(|st: &mut State, rt| {
             let mut pending_deals: Vec<Cid> = vec![];
             let mut deal_proposals: Vec<(DealID, DealProposal)> = vec![];
             let mut deals_by_epoch: Vec<(ChainEpoch, DealID)> = vec![];
             let mut pending_deal_allocation_ids: Vec<(DealID, AllocationID)> = vec![];
 
+            if provider_top_up.is_positive() {
+                st.top_up_escrow_from_collateral_pool(
+                    rt.store(),
+                    &Address::new_id(provider_id),
+                    &provider_top_up,
+                )?;
+            }
+
             // All storage dealProposals will be added in an atomic transaction; this operation will be unrolled if any of them fails.
             // This should only fail on programmer error because all expected invalid conditions should be filtered in the first set of checks.
             for valid_deal in valid_deals.iter() {
@@ -444,9 +1025,11 @@ impl Actor {
 
                 // Store verified allocation (if any) in the pending allocation IDs map.
                 // It will be removed when the deal is activated or expires.
-                if let Some(alloc_id) = deal_allocation_ids.get(&valid_deal.cid) {
-                    pending_deal_allocation_ids.push((deal_id, *alloc_id));
+                let alloc_id = deal_allocation_ids.get(&valid_deal.cid).copied();
+                if let Some(alloc_id) = alloc_id {
+                    pending_deal_allocation_ids.push((deal_id, alloc_id));
                 }
+                new_allocation_ids.push(alloc_id.unwrap_or(NO_ALLOCATION_ID));
 
                 // Randomize the first epoch for when the deal will be processed so an attacker isn't able to
                 // schedule too many deals for the same tick.
@@ -459,42 +1042,243 @@ impl Actor {
                     deal_id,
                 ));
 
-                new_deal_ids.push(deal_id);
+                new_deal_ids.push(deal_id);
+            }
+
+            st.put_pending_deals(rt.store(), &pending_deals)?;
+            st.put_deal_proposals(
+                rt.store(),
+                &deal_proposals,
+                rt.policy().market_index_deal_labels,
+            )?;
+            st.put_pending_deal_allocation_ids(rt.store(), &pending_deal_allocation_ids)?;
+            st.put_deals_by_epoch(rt.store(), &deals_by_epoch)?;
+            Ok(())
+        })?;
+
+        if provider_top_up.is_positive() {
+            emit::collateral_topped_up(rt, provider_id, &provider_top_up)?;
+        }
+
+        // notify clients, any failures cause the entire publish_storage_deals method to fail
+        // it's unsafe to ignore errors here, since that could be used to attack storage contract clients
+        // that might be unaware they're making storage deals
+        for (valid_deal, &deal_id) in valid_deals.iter().zip(&new_deal_ids) {
+            _ = extract_send_result(rt.send_simple(
+                &valid_deal.proposal.client,
+                MARKET_NOTIFY_DEAL_METHOD,
+                IpldBlock::serialize_cbor(&MarketNotifyDealParams {
+                    proposal: valid_deal.serialized_proposal.to_vec(),
+                    deal_id,
+                })?,
+                TokenAmount::zero(),
+            ))
+            .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+                format!("failed to notify deal with proposal cid {}", valid_deal.cid)
+            })?;
+
+            emit::deal_published(
+                rt,
+                valid_deal.proposal.client.id().unwrap(),
+                valid_deal.proposal.provider.id().unwrap(),
+                deal_id,
+            )?;
+        }
+
+        let mut allocation_results = BatchReturnGen::new(allocation_codes.len());
+        for code in allocation_codes.values() {
+            allocation_results.add(*code);
+        }
+        Ok(PublishStorageDeals2Return {
+            ids: new_deal_ids,
+            valid_deals: valid_input_bf,
+            allocation_ids: new_allocation_ids,
+            allocation_results: allocation_results.generate(),
+        })
+    }
+
+    /// Cancels published deals which have not yet been activated, on the authority of
+    /// each deal's client as evidenced by their signature over the cancellation.
+    /// The locked funds of both client and provider are released without penalty, and any
+    /// verified allocations for the deals are released, returning the datacap to the clients.
+    /// A deal cannot be cancelled once activated, or once its start epoch has passed
+    /// (after which it is subject to the penalty for missed activation).
+    /// Each cancellation succeeds or fails independently of the others.
+    fn cancel_storage_deals(
+        rt: &impl Runtime,
+        params: CancelStorageDealsParams,
+    ) -> Result<CancelStorageDealsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let curr_epoch = rt.curr_epoch();
+        let st: State = rt.state()?;
+        let proposals = st.load_proposals(rt.store())?;
+
+        let mut batch_gen = BatchReturnGen::new(params.cancellations.len());
+        let mut cancelled: Vec<(DealID, DealProposal, Cid)> = vec![];
+        let mut seen_deal_ids = BTreeSet::new();
+        for signed in &params.cancellations {
+            let deal_id = signed.cancellation.deal_id;
+            if !seen_deal_ids.insert(deal_id) {
+                info!("invalid cancellation: duplicate deal {}", deal_id);
+                batch_gen.add_fail(ExitCode::USR_ILLEGAL_ARGUMENT);
+                continue;
+            }
+            let proposal = match get_proposal(&proposals, deal_id, st.next_id) {
+                Ok(proposal) => proposal,
+                Err(e) => {
+                    info!("invalid cancellation of deal {}: {}", deal_id, e);
+                    batch_gen.add_fail(e.exit_code());
+                    continue;
+                }
+            };
+            let dcid = deal_cid(rt, &proposal)?;
+            if dcid != signed.cancellation.proposal {
+                info!(
+                    "invalid cancellation of deal {}: proposal cid {} does not match {}",
+                    deal_id, signed.cancellation.proposal, dcid
+                );
+                batch_gen.add_fail(ExitCode::USR_ILLEGAL_ARGUMENT);
+                continue;
+            }
+            if st.find_deal_state(rt.store(), deal_id)?.is_some() {
+                info!("invalid cancellation of deal {}: deal has been activated", deal_id);
+                batch_gen.add_fail(ExitCode::USR_FORBIDDEN);
+                continue;
+            }
+            if curr_epoch >= proposal.start_epoch {
+                info!(
+                    "invalid cancellation of deal {}: start epoch {} has passed",
+                    deal_id, proposal.start_epoch
+                );
+                batch_gen.add_fail(ExitCode::USR_FORBIDDEN);
+                continue;
+            }
+            if let Err(e) = authenticate_deal_cancellation(rt, &proposal.client, signed) {
+                info!("invalid cancellation of deal {}: {}", deal_id, e);
+                batch_gen.add_fail(ExitCode::USR_ILLEGAL_ARGUMENT);
+                continue;
+            }
+            cancelled.push((deal_id, proposal, dcid));
+            batch_gen.add_success();
+        }
+
+        let client_allocation_ids = rt.transaction(|st: &mut State, rt| {
+            let mut client_allocation_ids: BTreeMap<ActorID, Vec<AllocationID>> = BTreeMap::new();
+            for (deal_id, proposal, dcid) in &cancelled {
+                if let Some(id) = st.cancel_pending_deal(rt.store(), *deal_id, proposal, dcid)? {
+                    if id != NO_ALLOCATION_ID {
+                        client_allocation_ids
+                            .entry(proposal.client.id().unwrap())
+                            .or_default()
+                            .push(id);
+                    }
+                }
+            }
+            Ok(client_allocation_ids)
+        })?;
+
+        for (client, allocation_ids) in client_allocation_ids {
+            release_allocations(rt, client, allocation_ids)?;
+        }
+
+        for (deal_id, proposal, _) in &cancelled {
+            emit::deal_cancelled(
+                rt,
+                *deal_id,
+                proposal.client.id().unwrap(),
+                proposal.provider.id().unwrap(),
+            )?;
+        }
+
+        Ok(CancelStorageDealsReturn { results: batch_gen.generate() })
+    }
+
+    /// Cancels published deals which have not yet been activated, by agreement of the provider,
+    /// as the caller, and the client, by their signature over the CBOR-encoded list of deal IDs.
+    /// All deals must share the provider and client of the first deal. Unlike a cancellation by
+    /// the client alone, deals may be cancelled after their start epoch.
+    /// The locked funds of both parties are released without penalty, and any verified
+    /// allocations for the deals are released, returning the datacap to the client.
+    /// Each deal succeeds or fails independently of the others.
+    fn mutually_cancel_deals(
+        rt: &impl Runtime,
+        params: MutuallyCancelDealsParams,
+    ) -> Result<MutuallyCancelDealsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.deal_ids.is_empty() {
+            return Err(actor_error!(illegal_argument, "no deals to cancel"));
+        }
+        let st: State = rt.state()?;
+        let proposals = st.load_proposals(rt.store())?;
+
+        // The first deal identifies the parties, both of which must agree to the cancellation.
+        let first = get_proposal(&proposals, params.deal_ids[0], st.next_id)?;
+        let client = first.client;
+        let provider = first.provider;
+        validate_caller_controls_provider(rt, provider.id().unwrap())?;
+        authenticate_mutual_cancellation(rt, &client, &params)?;
+
+        let mut batch_gen = BatchReturnGen::new(params.deal_ids.len());
+        let mut cancelled: Vec<(DealID, DealProposal, Cid)> = vec![];
+        let mut seen_deal_ids = BTreeSet::new();
+        for deal_id in &params.deal_ids {
+            if !seen_deal_ids.insert(*deal_id) {
+                info!("invalid cancellation: duplicate deal {}", deal_id);
+                batch_gen.add_fail(ExitCode::USR_ILLEGAL_ARGUMENT);
+                continue;
+            }
+            let proposal = match get_proposal(&proposals, *deal_id, st.next_id) {
+                Ok(proposal) => proposal,
+                Err(e) => {
+                    info!("invalid cancellation of deal {}: {}", deal_id, e);
+                    batch_gen.add_fail(e.exit_code());
+                    continue;
+                }
+            };
+            if proposal.client != client || proposal.provider != provider {
+                info!(
+                    "invalid cancellation of deal {}: parties {}, {} do not match {}, {}",
+                    deal_id, proposal.client, proposal.provider, client, provider
+                );
+                batch_gen.add_fail(ExitCode::USR_FORBIDDEN);
+                continue;
+            }
+            if st.find_deal_state(rt.store(), *deal_id)?.is_some() {
+                info!("invalid cancellation of deal {}: deal has been activated", deal_id);
+                batch_gen.add_fail(ExitCode::USR_FORBIDDEN);
+                continue;
             }
+            let dcid = deal_cid(rt, &proposal)?;
+            cancelled.push((*deal_id, proposal, dcid));
+            batch_gen.add_success();
+        }
 
-            st.put_pending_deals(rt.store(), &pending_deals)?;
-            st.put_deal_proposals(rt.store(), &deal_proposals)?;
-            st.put_pending_deal_allocation_ids(rt.store(), &pending_deal_allocation_ids)?;
-            st.put_deals_by_epoch(rt.store(), &deals_by_epoch)?;
-            Ok(())
+        let allocation_ids = rt.transaction(|st: &mut State, rt| {
+            let mut allocation_ids = vec![];
+            for (deal_id, proposal, dcid) in &cancelled {
+                if let Some(id) = st.cancel_pending_deal(rt.store(), *deal_id, proposal, dcid)? {
+                    if id != NO_ALLOCATION_ID {
+                        allocation_ids.push(id);
+                    }
+                }
+            }
+            Ok(allocation_ids)
         })?;
 
-        // notify clients, any failures cause the entire publish_storage_deals method to fail
-        // it's unsafe to ignore errors here, since that could be used to attack storage contract clients
-        // that might be unaware they're making storage deals
-        for (valid_deal, &deal_id) in valid_deals.iter().zip(&new_deal_ids) {
-            _ = extract_send_result(rt.send_simple(
-                &valid_deal.proposal.client,
-                MARKET_NOTIFY_DEAL_METHOD,
-                IpldBlock::serialize_cbor(&MarketNotifyDealParams {
-                    proposal: valid_deal.serialized_proposal.to_vec(),
-                    deal_id,
-                })?,
-                TokenAmount::zero(),
-            ))
-            .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
-                format!("failed to notify deal with proposal cid {}", valid_deal.cid)
-            })?;
+        if !allocation_ids.is_empty() {
+            release_allocations(rt, client.id().unwrap(), allocation_ids)?;
+        }
 
-            emit::deal_published(
+        for (deal_id, proposal, _) in &cancelled {
+            emit::deal_cancelled(
                 rt,
-                valid_deal.proposal.client.id().unwrap(),
-                valid_deal.proposal.provider.id().unwrap(),
-                deal_id,
+                *deal_id,
+                proposal.client.id().unwrap(),
+                proposal.provider.id().unwrap(),
             )?;
         }
 
-        Ok(PublishStorageDealsReturn { ids: new_deal_ids, valid_deals: valid_input_bf })
+        Ok(MutuallyCancelDealsReturn { results: batch_gen.generate() })
     }
 
     /// Verify that a given set of storage deals is valid for a sector currently being PreCommitted
@@ -562,6 +1346,7 @@ impl Actor {
                 st.load_pending_deal_allocation_ids(rt.store())?;
 
             let mut deal_states: Vec<(DealID, DealState)> = vec![];
+            let mut activated_pending: Vec<(DealID, ChainEpoch)> = vec![];
             let mut batch_gen = BatchReturnGen::new(params.sectors.len());
             let mut activations: Vec<SectorDealActivation> = vec![];
             let mut activated_deals: HashSet<DealID> = HashSet::new();
@@ -625,6 +1410,7 @@ impl Actor {
                     });
 
                     // Prepare initial deal state.
+                    activated_pending.push((*deal_id, proposal.start_epoch));
                     deal_states.push((
                         *deal_id,
                         DealState {
@@ -651,6 +1437,9 @@ impl Actor {
                         *deal_id,
                         proposal.client.id().unwrap(),
                         proposal.provider.id().unwrap(),
+                        sector.sector_number,
+                        &proposal.piece_cid,
+                        proposal.verified_deal,
                     )?;
                 }
 
@@ -659,6 +1448,11 @@ impl Actor {
 
             st.put_deal_states(rt.store(), &deal_states)?;
             st.put_sector_deal_ids(rt.store(), miner_addr.id().unwrap(), &sectors_deals)?;
+            st.remove_provider_pending_deals(
+                rt.store(),
+                miner_addr.id().unwrap(),
+                &activated_pending,
+            )?;
             st.save_pending_deal_allocation_ids(&mut pending_deal_allocation_ids)?;
             Ok((activations, batch_gen.generate()))
         })?;
@@ -685,6 +1479,7 @@ impl Actor {
                 st.load_pending_deal_allocation_ids(rt.store())?;
 
             let mut deal_states: Vec<(DealID, DealState)> = vec![];
+            let mut activated_pending: Vec<(DealID, ChainEpoch)> = vec![];
             let mut activated_deals: HashSet<DealID> = HashSet::new();
             let mut sectors_deals: Vec<(SectorNumber, Vec<DealID>)> = vec![];
             let mut sectors_ret: Vec<ext::miner::SectorReturn> = vec![];
@@ -751,11 +1546,15 @@ impl Actor {
                         deal_id,
                         proposal.client.id().unwrap(),
                         proposal.provider.id().unwrap(),
+                        sector.sector,
+                        &proposal.piece_cid,
+                        proposal.verified_deal,
                     )?;
 
                     // Remove any verified allocation ID for the pending deal.
                     pending_deal_allocation_ids.delete(&deal_id)?;
 
+                    activated_pending.push((deal_id, proposal.start_epoch));
                     deal_states.push((
                         deal_id,
                         DealState {
@@ -775,6 +1574,11 @@ impl Actor {
             }
             st.put_deal_states(rt.store(), &deal_states)?;
             st.put_sector_deal_ids(rt.store(), miner_addr.id().unwrap(), &sectors_deals)?;
+            st.remove_provider_pending_deals(
+                rt.store(),
+                miner_addr.id().unwrap(),
+                &activated_pending,
+            )?;
             st.save_pending_deal_allocation_ids(&mut pending_deal_allocation_ids)?;
 
             assert_eq!(sectors_ret.len(), params.sectors.len(), "mismatched sector returns");
@@ -1145,34 +1949,7 @@ impl Actor {
     ) -> Result<GetDealActivationReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let st = rt.state::<State>()?;
-        let found = st.find_deal_state(rt.store(), params.id)?;
-        match found {
-            Some(state) => {
-                if state.slash_epoch != EPOCH_UNDEFINED {
-                    // Deal was terminated asynchronously
-                    // TODO: https://github.com/filecoin-project/builtin-actors/issues/1388
-                    Err(ActorError::unchecked(
-                        EX_DEAL_EXPIRED,
-                        format!("deal {} expired", params.id),
-                    ))
-                } else {
-                    // If we have state, the deal has been activated
-                    Ok(GetDealActivationReturn {
-                        activated: state.sector_start_epoch,
-                        terminated: state.slash_epoch,
-                    })
-                }
-            }
-            None => {
-                // Pass through exit codes if proposal doesn't exist.
-                let _ = st.get_proposal(rt.store(), params.id)?;
-                // Proposal was published but never activated.
-                Ok(GetDealActivationReturn {
-                    activated: EPOCH_UNDEFINED,
-                    terminated: EPOCH_UNDEFINED,
-                })
-            }
-        }
+        deal_activation(&st, rt.store(), params.id)
     }
 
     /// Fetches the sector in which a deal is stored.
@@ -1187,34 +1964,159 @@ impl Actor {
     ) -> Result<GetDealSectorReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let st = rt.state::<State>()?;
-        let found = st.find_deal_state(rt.store(), params.id)?;
-        match found {
-            Some(state) => {
-                // The deal has been activated and not yet finally settled.
-                if state.slash_epoch != EPOCH_UNDEFINED {
-                    // The deal has been terminated but not cleaned up.
-                    // Hide this internal state from caller and fail as if it had been cleaned up.
-                    // This will become an impossible state when deal termination is
-                    // processed immediately.
-                    // Remove with https://github.com/filecoin-project/builtin-actors/issues/1388.
-                    Err(ActorError::unchecked(
-                        EX_DEAL_EXPIRED,
-                        format!("deal {} expired", params.id),
-                    ))
-                } else {
-                    Ok(GetDealSectorReturn { sector: state.sector_number })
-                }
-            }
-            None => {
-                // Pass through exit codes if proposal doesn't exist.
-                let _ = st.get_proposal(rt.store(), params.id)?;
-                // Proposal was published but never activated.
-                Err(ActorError::unchecked(
-                    EX_DEAL_NOT_ACTIVATED,
-                    format!("deal {} not yet activated", params.id),
-                ))
-            }
+        let sector = deal_sector(&st, rt.store(), params.id)?;
+        Ok(GetDealSectorReturn { sector })
+    }
+
+    /// Fetches the sector in which a deal is stored, and the provider holding that sector.
+    /// This is available, and fails, as for GetDealSector.
+    fn get_deal_sector_location(
+        rt: &impl Runtime,
+        params: GetDealSectorLocationParams,
+    ) -> Result<GetDealSectorLocationReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st = rt.state::<State>()?;
+        let sector = deal_sector(&st, rt.store(), params.id)?;
+        let proposal = st.get_proposal(rt.store(), params.id)?;
+        Ok(GetDealSectorLocationReturn { sector, provider: proposal.provider.id().unwrap() })
+    }
+
+    /// Returns the IDs of all deal proposals for a piece.
+    /// A deal is included from when it is published until its proposal is removed from state
+    /// (after it completes, is terminated, or times out without activation).
+    fn get_deals_for_piece(
+        rt: &impl Runtime,
+        params: GetDealsForPieceParams,
+    ) -> Result<GetDealsForPieceReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st = rt.state::<State>()?;
+        let deal_ids = st.get_piece_deal_ids(rt.store(), &params.piece_cid)?;
+        Ok(GetDealsForPieceReturn { deal_ids })
+    }
+
+    /// Lists a page of the IDs of deal proposals with a label.
+    /// Deals are matched by label content, so a string label matches a bytes label with the
+    /// same bytes. Deals with an empty label are not indexed, so an empty label matches none.
+    /// A deal is included from when it is published until its proposal is removed from state.
+    /// Deals are not listed in ID order. A caller pages through all the deals by passing
+    /// the returned cursor to the next call, until no cursor is returned.
+    /// Fails if the policy does not enable the label index.
+    fn get_deals_for_label(
+        rt: &impl Runtime,
+        params: GetDealsForLabelParams,
+    ) -> Result<GetDealsForLabelReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if !rt.policy().market_index_deal_labels {
+            return Err(actor_error!(forbidden, "deal label index is not enabled"));
+        }
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let st = rt.state::<State>()?;
+        let (deal_ids, next_cursor) =
+            st.list_label_deal_ids(rt.store(), &params.label, params.cursor, params.limit)?;
+        Ok(GetDealsForLabelReturn { deal_ids, next_cursor })
+    }
+
+    /// Fetches activation state for a batch of deals.
+    /// Each deal succeeds or fails as for GetDealActivation, independently of the others.
+    fn batch_get_deal_activation(
+        rt: &impl Runtime,
+        params: BatchGetDealActivationParams,
+    ) -> Result<BatchGetDealActivationReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st = rt.state::<State>()?;
+        let (results, activations) =
+            batch_query_deals(&params.ids, |id| deal_activation(&st, rt.store(), id))?;
+        Ok(BatchGetDealActivationReturn { results, activations })
+    }
+
+    /// Returns the start epoch and duration (in epochs) of a batch of deal proposals.
+    fn batch_get_deal_term(
+        rt: &impl Runtime,
+        params: BatchGetDealTermParams,
+    ) -> Result<BatchGetDealTermReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st = rt.state::<State>()?;
+        let proposals = st.load_proposals(rt.store())?;
+        let (results, terms) = batch_query_deals(&params.ids, |id| {
+            let found = get_proposal(&proposals, id, st.next_id)?;
+            Ok(GetDealTermReturn { start: found.start_epoch, duration: found.duration() })
+        })?;
+        Ok(BatchGetDealTermReturn { results, terms })
+    }
+
+    /// Returns the client and provider of a batch of deal proposals.
+    fn batch_get_deal_parties(
+        rt: &impl Runtime,
+        params: BatchGetDealPartiesParams,
+    ) -> Result<BatchGetDealPartiesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st = rt.state::<State>()?;
+        let proposals = st.load_proposals(rt.store())?;
+        let (results, parties) = batch_query_deals(&params.ids, |id| {
+            let found = get_proposal(&proposals, id, st.next_id)?;
+            Ok(DealParties {
+                client: found.client.id().unwrap(),
+                provider: found.provider.id().unwrap(),
+            })
+        })?;
+        Ok(BatchGetDealPartiesReturn { results, parties })
+    }
+
+    /// Returns the verified flag for a batch of deal proposals.
+    fn batch_get_deal_verified(
+        rt: &impl Runtime,
+        params: BatchGetDealVerifiedParams,
+    ) -> Result<BatchGetDealVerifiedReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st = rt.state::<State>()?;
+        let proposals = st.load_proposals(rt.store())?;
+        let (results, verified) = batch_query_deals(&params.ids, |id| {
+            Ok(get_proposal(&proposals, id, st.next_id)?.verified_deal)
+        })?;
+        Ok(BatchGetDealVerifiedReturn { results, verified })
+    }
+
+    /// Lists a page of the IDs of a provider's deals which remain in state,
+    /// whether pending, active, or awaiting cleanup.
+    /// Deals are not listed in ID order. A caller pages through all the deals by passing
+    /// the returned cursor to the next call, until no cursor is returned.
+    fn list_provider_deals(
+        rt: &impl Runtime,
+        params: ListProviderDealsParams,
+    ) -> Result<ListProviderDealsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let st = rt.state::<State>()?;
+        let (deal_ids, next_cursor) =
+            st.list_provider_deal_ids(rt.store(), params.provider, params.cursor, params.limit)?;
+        Ok(ListProviderDealsReturn { deal_ids, next_cursor })
+    }
+
+    /// Lists a page of a provider's published deals which have not yet been activated,
+    /// ordered by start epoch and then by ID.
+    /// Deals leave the list when activated, or when they time out or are cancelled.
+    /// A caller pages through all the deals by passing the returned cursor to the next call,
+    /// until no cursor is returned.
+    fn list_provider_pending_deals(
+        rt: &impl Runtime,
+        params: ListProviderPendingDealsParams,
+    ) -> Result<ListProviderPendingDealsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
         }
+        let st = rt.state::<State>()?;
+        let (deals, next_cursor) = st.list_provider_pending_deals(
+            rt.store(),
+            params.provider,
+            params.cursor,
+            params.limit,
+        )?;
+        Ok(ListProviderPendingDealsReturn { deals, next_cursor })
     }
 
     fn settle_deal_payments(
@@ -1360,6 +2262,135 @@ impl Actor {
 
         Ok(SettleDealPaymentsReturn { results: batch_gen.generate(), settlements })
     }
+
+    /// Computes the payments which SettleDealPayments would transfer for a batch of deals
+    /// at the current epoch, without changing state.
+    fn settle_deal_payments_preview(
+        rt: &impl Runtime,
+        params: SettleDealPaymentsPreviewParams,
+    ) -> Result<SettleDealPaymentsPreviewReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let curr_epoch = rt.curr_epoch();
+        let st: State = rt.state()?;
+
+        let mut batch_gen = BatchReturnGen::new(params.deal_ids.len() as usize);
+        let mut settlements: Vec<DealSettlementSummary> = Vec::new();
+        let mut total_payment = TokenAmount::zero();
+        for deal_id in params.deal_ids.iter() {
+            let deal_proposal = match st.get_proposal(rt.store(), deal_id) {
+                Ok(prop) => prop,
+                Err(_) => {
+                    batch_gen.add_fail(EX_DEAL_EXPIRED);
+                    continue;
+                }
+            };
+            match st.preview_deal_settlement(rt.store(), deal_id, &deal_proposal, curr_epoch) {
+                Ok(settlement) => {
+                    total_payment += &settlement.payment;
+                    settlements.push(settlement);
+                    batch_gen.add_success();
+                }
+                // As in settlement, a deal marked for termination fails the whole batch.
+                Err(e) if e.exit_code() == ExitCode::USR_ILLEGAL_ARGUMENT => return Err(e),
+                Err(e) => batch_gen.add_fail(e.exit_code()),
+            }
+        }
+
+        Ok(SettleDealPaymentsPreviewReturn {
+            results: batch_gen.generate(),
+            settlements,
+            total_payment,
+        })
+    }
+}
+
+// Computes the activation state for a deal, as returned by GetDealActivation.
+fn deal_activation<BS: Blockstore>(
+    st: &State,
+    store: &BS,
+    id: DealID,
+) -> Result<GetDealActivationReturn, ActorError> {
+    let found = st.find_deal_state(store, id)?;
+    match found {
+        Some(state) => {
+            if state.slash_epoch != EPOCH_UNDEFINED {
+                // Deal was terminated asynchronously
+                // TODO: https://github.com/filecoin-project/builtin-actors/issues/1388
+                Err(ActorError::unchecked(EX_DEAL_EXPIRED, format!("deal {} expired", id)))
+            } else {
+                // If we have state, the deal has been activated
+                Ok(GetDealActivationReturn {
+                    activated: state.sector_start_epoch,
+                    terminated: state.slash_epoch,
+                })
+            }
+        }
+        None => {
+            // Pass through exit codes if proposal doesn't exist.
+            let _ = st.get_proposal(store, id)?;
+            // Proposal was published but never activated.
+            Ok(GetDealActivationReturn { activated: EPOCH_UNDEFINED, terminated: EPOCH_UNDEFINED })
+        }
+    }
+}
+
+// Returns the sector in which an activated deal is stored.
+fn deal_sector<BS: Blockstore>(
+    st: &State,
+    store: &BS,
+    id: DealID,
+) -> Result<SectorNumber, ActorError> {
+    let found = st.find_deal_state(store, id)?;
+    match found {
+        Some(state) => {
+            // The deal has been activated and not yet finally settled.
+            if state.slash_epoch != EPOCH_UNDEFINED {
+                // The deal has been terminated but not cleaned up.
+                // Hide this internal state from caller and fail as if it had been cleaned up.
+                // This will become an impossible state when deal termination is
+                // processed immediately.
+                // Remove with https://github.com/filecoin-project/builtin-actors/issues/1388.
+                Err(ActorError::unchecked(EX_DEAL_EXPIRED, format!("deal {} expired", id)))
+            } else {
+                Ok(state.sector_number)
+            }
+        }
+        None => {
+            // Pass through exit codes if proposal doesn't exist.
+            let _ = st.get_proposal(store, id)?;
+            // Proposal was published but never activated.
+            Err(ActorError::unchecked(
+                EX_DEAL_NOT_ACTIVATED,
+                format!("deal {} not yet activated", id),
+            ))
+        }
+    }
+}
+
+// Applies a per-deal query to a batch of deal IDs.
+// A deal that doesn't exist (yet) or has expired fails only that entry,
+// while any other error aborts the whole batch.
+fn batch_query_deals<T>(
+    ids: &[DealID],
+    mut query: impl FnMut(DealID) -> Result<T, ActorError>,
+) -> Result<(BatchReturn, Vec<T>), ActorError> {
+    let mut batch_gen = BatchReturnGen::new(ids.len());
+    let mut values = Vec::with_capacity(ids.len());
+    for &id in ids {
+        match query(id) {
+            Ok(v) => {
+                values.push(v);
+                batch_gen.add_success();
+            }
+            Err(e) => match e.exit_code() {
+                ExitCode::USR_NOT_FOUND | EX_DEAL_EXPIRED => {
+                    batch_gen.add_fail(e.exit_code());
+                }
+                _ => return Err(e),
+            },
+        }
+    }
+    Ok((batch_gen.generate(), values))
 }
 
 fn get_proposals<BS: Blockstore>(
@@ -1522,6 +2553,31 @@ fn datacap_transfer_request(
     })
 }
 
+/// Transfers datacap from a client to the verified registry to make a batch of allocations.
+/// Returns the new allocation IDs in request order, or the error with which the transfer failed.
+/// Fails outright only if a successful transfer returns an unexpected number of allocations.
+fn allocate_datacap(
+    rt: &impl Runtime,
+    client: ActorID,
+    reqs: Vec<AllocationRequest>,
+) -> Result<Result<Vec<AllocationID>, ActorError>, ActorError> {
+    let req_count = reqs.len();
+    let params = datacap_transfer_request(&Address::new_id(client), reqs)?;
+    let alloc_ids = match transfer_from(rt, params) {
+        Ok(alloc_ids) => alloc_ids,
+        Err(e) => return Ok(Err(e)),
+    };
+    if alloc_ids.len() != req_count {
+        return Err(actor_error!(
+            illegal_state,
+            "datacap transfer returned {} allocation IDs for {} requests",
+            alloc_ids.len(),
+            req_count
+        ));
+    }
+    Ok(Ok(alloc_ids))
+}
+
 // Invokes transfer_from on the data cap token actor.
 fn transfer_from(
     rt: &impl Runtime,
@@ -1542,6 +2598,51 @@ fn transfer_from(
     Ok(allocs.new_allocations)
 }
 
+// Invokes Transfer on an FRC-46 token actor to send tokens held by the market.
+fn transfer_tokens(
+    rt: &impl Runtime,
+    token: ActorID,
+    to: &Address,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    let params =
+        TransferParams { to: *to, amount: amount.clone(), operator_data: RawBytes::default() };
+    extract_send_result(rt.send_simple(
+        &Address::new_id(token),
+        ext::token::TRANSFER_METHOD,
+        IpldBlock::serialize_cbor(&params)?,
+        TokenAmount::zero(),
+    ))
+    .context(format!("failed to send transfer to token {} {:?}", token, params))?;
+    Ok(())
+}
+
+// Deserializes and validates a receiver hook payload, expecting only an FRC-46 transfer.
+fn validate_tokens_received(
+    params: &UniversalReceiverParams,
+    my_id: ActorID,
+) -> Result<FRC46TokenReceived, ActorError> {
+    if params.type_ != FRC46_TOKEN_TYPE {
+        return Err(actor_error!(
+            illegal_argument,
+            "invalid token type {}, expected {} (FRC-46)",
+            params.type_,
+            FRC46_TOKEN_TYPE
+        ));
+    }
+    let payload: FRC46TokenReceived = deserialize(&params.payload, "receiver hook payload")?;
+    // Payload to address must match receiving actor.
+    if payload.to != my_id {
+        return Err(actor_error!(
+            illegal_argument,
+            "token receiver expected to {}, was {}",
+            my_id,
+            payload.to
+        ));
+    }
+    Ok(payload)
+}
+
 // Invokes BalanceOf on the data cap token actor.
 fn balance_of(rt: &impl Runtime, owner: &Address) -> Result<TokenAmount, ActorError> {
     let params = IpldBlock::serialize_cbor(owner)?;
@@ -1619,8 +2720,6 @@ fn validate_deal(
     network_raw_power: &StoragePower,
     baseline_power: &StoragePower,
 ) -> Result<(), ActorError> {
-    deal_proposal_is_internally_valid(rt, deal)?;
-
     let proposal = &deal.proposal;
 
     if proposal.label.len() > detail::DEAL_MAX_LABEL_SIZE {
@@ -1651,14 +2750,15 @@ fn validate_deal(
         return Err(actor_error!(illegal_argument, "Deal start epoch has already elapsed."));
     };
 
-    let (min_dur, max_dur) = deal_duration_bounds(proposal.piece_size);
+    let (min_dur, max_dur) = deal_duration_bounds(rt.policy(), proposal.piece_size);
     if proposal.duration() < min_dur || proposal.duration() > max_dur {
         return Err(actor_error!(illegal_argument, "Deal duration out of bounds."));
     };
 
     let (min_price, max_price) =
-        deal_price_per_epoch_bounds(proposal.piece_size, proposal.duration());
-    if proposal.storage_price_per_epoch < min_price || &proposal.storage_price_per_epoch > max_price
+        deal_price_per_epoch_bounds(rt.policy(), proposal.piece_size, proposal.duration());
+    if &proposal.storage_price_per_epoch < min_price
+        || &proposal.storage_price_per_epoch > max_price
     {
         return Err(actor_error!(illegal_argument, "Storage price out of bounds."));
     };
@@ -1687,7 +2787,7 @@ fn validate_deal(
     Ok(())
 }
 
-fn deal_proposal_is_internally_valid(
+fn authenticate_deal_proposal(
     rt: &impl Runtime,
     proposal: &ClientDealProposal,
 ) -> Result<(), ActorError> {
@@ -1715,6 +2815,140 @@ fn deal_proposal_is_internally_valid(
     }
 }
 
+fn authenticate_deal_cancellation(
+    rt: &impl Runtime,
+    client: &Address,
+    cancellation: &ClientDealCancellation,
+) -> Result<(), ActorError> {
+    let cancellation_bytes = serialize(&cancellation.cancellation, "deal cancellation")?;
+    let message = [SIGNATURE_DOMAIN_SEPARATION_CANCEL_DEALS, cancellation_bytes.bytes()].concat();
+    authenticate_cancellation_message(rt, client, &cancellation.client_signature, message)
+}
+
+fn authenticate_mutual_cancellation(
+    rt: &impl Runtime,
+    client: &Address,
+    params: &MutuallyCancelDealsParams,
+) -> Result<(), ActorError> {
+    let deal_ids = serialize(&params.deal_ids, "deal ids")?;
+    let message = [SIGNATURE_DOMAIN_SEPARATION_MUTUALLY_CANCEL_DEALS, deal_ids.bytes()].concat();
+    authenticate_cancellation_message(rt, client, &params.client_signature, message)
+}
+
+// Releases a client's verified allocations for cancelled deals, returning the datacap to the client.
+// Allocations which fail to release are logged and left to expire.
+fn release_allocations(
+    rt: &impl Runtime,
+    client: ActorID,
+    allocation_ids: Vec<AllocationID>,
+) -> Result<(), ActorError> {
+    let ret: ext::verifreg::ReleaseAllocationsReturn =
+        deserialize_block(extract_send_result(rt.send_simple(
+            &VERIFIED_REGISTRY_ACTOR_ADDR,
+            ext::verifreg::RELEASE_ALLOCATIONS_METHOD,
+            IpldBlock::serialize_cbor(&ext::verifreg::ReleaseAllocationsParams {
+                client,
+                allocation_ids,
+            })?,
+            TokenAmount::zero(),
+        ))?)?;
+    if !ret.results.all_ok() {
+        info!("failed to release some allocations: {:?}", ret.results.codes());
+    }
+    Ok(())
+}
+
+// Authenticates a client's signature over a cancellation message with a call to the client account.
+fn authenticate_cancellation_message(
+    rt: &impl Runtime,
+    client: &Address,
+    signature: &Signature,
+    message: Vec<u8>,
+) -> Result<(), ActorError> {
+    if !extract_send_result(rt.send(
+        client,
+        ext::account::AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
+            signature: signature.bytes.clone(),
+            message,
+        })?,
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+    ))
+    .and_then(deserialize_block)
+    .context("cancellation authentication failed")?
+    {
+        Err(actor_error!(illegal_argument, "cancellation authentication failed"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Authenticates the proposals at `indices`, all from the account actor `client`,
+/// with a single call to the account.
+/// Returns whether each proposal was authenticated, in the same order as `indices`.
+/// A failure of the call as a whole fails all the proposals.
+fn batch_authenticate_deal_proposals(
+    rt: &impl Runtime,
+    client: ActorID,
+    indices: &[usize],
+    deals: &[ClientDealProposal],
+) -> Vec<bool> {
+    let authenticate = || -> Result<Vec<bool>, ActorError> {
+        let messages = indices
+            .iter()
+            .map(|i| {
+                let deal = &deals[*i];
+                Ok(ext::account::AuthenticateMessageParams {
+                    signature: deal.client_signature.bytes.clone(),
+                    message: serialize(&deal.proposal, "deal proposal")?.to_vec(),
+                })
+            })
+            .collect::<Result<Vec<_>, ActorError>>()?;
+        let ret: ext::account::BatchAuthenticateMessageReturn = extract_send_result(rt.send(
+            &Address::new_id(client),
+            ext::account::BATCH_AUTHENTICATE_MESSAGE_METHOD,
+            IpldBlock::serialize_cbor(&ext::account::BatchAuthenticateMessageParams { messages })?,
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+        ))
+        .and_then(deserialize_block)?;
+        if ret.results.size() != indices.len() {
+            return Err(actor_error!(
+                illegal_state,
+                "batch authentication returned {} results for {} messages",
+                ret.results.size(),
+                indices.len()
+            ));
+        }
+        Ok(ret.results.codes().iter().map(ExitCode::is_success).collect())
+    };
+
+    match authenticate() {
+        Ok(results) => {
+            for (i, ok) in indices.iter().zip(results.iter()) {
+                if !ok {
+                    info!("invalid deal {}: proposal authentication failed", i);
+                }
+            }
+            results
+        }
+        Err(e) => {
+            info!("invalid deals {:?}: proposal authentication failed: {}", indices, e);
+            vec![false; indices.len()]
+        }
+    }
+}
+
+/// Checks whether an actor is a built-in account actor.
+fn is_account(rt: &impl Runtime, id: ActorID) -> bool {
+    rt.get_actor_code_cid(&id)
+        .and_then(|code| rt.resolve_builtin_actor_type(&code))
+        .is_some_and(|t| t == Type::Account)
+}
+
 /// Compute a deal CID using the runtime.
 pub fn deal_cid(rt: &impl Runtime, proposal: &DealProposal) -> Result<Cid, ActorError> {
     let data = serialize(proposal, "deal proposal")?;
@@ -1730,6 +2964,30 @@ pub(crate) fn serialized_deal_cid(rt: &impl Runtime, data: &[u8]) -> Result<Cid,
     Ok(Cid::new_v1(DAG_CBOR, hash))
 }
 
+/// Checks that the immediate caller is the worker or a control address of the provider.
+fn validate_caller_controls_provider(
+    rt: &impl Runtime,
+    provider_id: ActorID,
+) -> Result<(), ActorError> {
+    let caller = rt.message().caller();
+    let caller_status: ext::miner::IsControllingAddressReturn =
+        deserialize_block(extract_send_result(rt.send_simple(
+            &Address::new_id(provider_id),
+            ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
+            IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam { address: caller })?,
+            TokenAmount::zero(),
+        ))?)?;
+    if !caller_status.is_controlling {
+        return Err(actor_error!(
+            forbidden,
+            "caller {} is not worker or control address of provider {}",
+            caller,
+            provider_id
+        ));
+    }
+    Ok(())
+}
+
 fn request_miner_control_addrs(
     rt: &impl Runtime,
     miner_id: ActorID,
@@ -1810,6 +3068,24 @@ pub fn sector_number_key(k: SectorNumber) -> BytesKey {
 impl ActorCode for Actor {
     type Methods = Method;
 
+    const DEPRECATED_METHODS: &'static [MethodDeprecation] = &[
+        MethodDeprecation {
+            method: Method::AddBalance as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::AddBalanceExported as u64,
+        },
+        MethodDeprecation {
+            method: Method::WithdrawBalance as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::WithdrawBalanceExported as u64,
+        },
+        MethodDeprecation {
+            method: Method::PublishStorageDeals as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::PublishStorageDealsExported as u64,
+        },
+    ];
+
     fn name() -> &'static str {
         "StorageMarket"
     }
@@ -1836,6 +3112,27 @@ impl ActorCode for Actor {
         GetDealActivationExported => get_deal_activation,
         GetDealSectorExported => get_deal_sector,
         SettleDealPaymentsExported => settle_deal_payments,
+        SettleDealPaymentsPreviewExported => settle_deal_payments_preview,
+        GetDealsForPieceExported => get_deals_for_piece,
+        GetDealsForLabelExported => get_deals_for_label,
+        BatchGetDealActivationExported => batch_get_deal_activation,
+        BatchGetDealTermExported => batch_get_deal_term,
+        BatchGetDealPartiesExported => batch_get_deal_parties,
+        BatchGetDealVerifiedExported => batch_get_deal_verified,
+        ListProviderDealsExported => list_provider_deals,
+        CancelStorageDealsExported => cancel_storage_deals,
+        DepositCollateralPoolExported => deposit_collateral_pool,
+        WithdrawCollateralPoolExported => withdraw_collateral_pool,
+        SetDealAcceptancePolicyExported => set_deal_acceptance_policy,
+        GetDealAcceptancePolicyExported => get_deal_acceptance_policy,
+        ListProviderPendingDealsExported => list_provider_pending_deals,
         SectorContentChangedExported => sector_content_changed,
+        MutuallyCancelDealsExported => mutually_cancel_deals,
+        SetClientWithdrawalWatchExported => set_client_withdrawal_watch,
+        UniversalReceiverHook => universal_receiver_hook,
+        WithdrawTokenBalanceExported => withdraw_token_balance,
+        GetTokenBalanceExported => get_token_balance,
+        PublishStorageDeals2Exported => publish_storage_deals2,
+        GetDealSectorLocationExported => get_deal_sector_location,
     }
 }
@@ -3,7 +3,6 @@
 
 use std::cmp::max;
 
-use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::Policy;
 use fvm_shared::bigint::{BigInt, Integer};
 use fvm_shared::clock::ChainEpoch;
@@ -16,6 +15,12 @@ use num_traits::Zero;
 pub mod detail {
     /// Maximum length of a deal label.
     pub const DEAL_MAX_LABEL_SIZE: usize = 256;
+
+    /// Maximum number of clients a deal acceptance policy may allow.
+    pub const DEAL_ACCEPTANCE_MAX_ALLOWED_CLIENTS: usize = 256;
+
+    /// Maximum number of providers which may watch a client's escrow withdrawals.
+    pub const CLIENT_WITHDRAWAL_MAX_WATCHES: usize = 32;
 }
 
 lazy_static! {
@@ -25,15 +30,19 @@ lazy_static! {
 }
 
 /// Bounds (inclusive) on deal duration.
-pub(super) fn deal_duration_bounds(_size: PaddedPieceSize) -> (ChainEpoch, ChainEpoch) {
-    (180 * EPOCHS_IN_DAY, 1278 * EPOCHS_IN_DAY)
+pub(super) fn deal_duration_bounds(
+    policy: &Policy,
+    _size: PaddedPieceSize,
+) -> (ChainEpoch, ChainEpoch) {
+    (policy.market_min_deal_duration, policy.market_max_deal_duration)
 }
 
-pub(super) fn deal_price_per_epoch_bounds(
+pub(super) fn deal_price_per_epoch_bounds<'a>(
+    policy: &'a Policy,
     _size: PaddedPieceSize,
     _duration: ChainEpoch,
-) -> (TokenAmount, &'static TokenAmount) {
-    (TokenAmount::zero(), &TOTAL_FILECOIN)
+) -> (&'a TokenAmount, &'static TokenAmount) {
+    (&policy.market_min_storage_price_per_epoch, &TOTAL_FILECOIN)
 }
 
 pub fn deal_provider_collateral_bounds(
@@ -26,7 +26,7 @@ use crate::ext::verifreg::AllocationID;
 
 use super::policy::*;
 use super::types::*;
-use super::{DealProposal, DealState, EX_DEAL_EXPIRED};
+use super::{DealAcceptancePolicy, DealProposal, DealState, EX_DEAL_EXPIRED, Label};
 
 pub enum Reason {
     ClientCollateral,
@@ -86,6 +86,75 @@ pub struct State {
     /// of multiple sectors all belonging to the same provider.
     /// HAMT[ActorID]HAMT[SectorNumber][]DealID
     pub provider_sectors: Cid,
+
+    /// Maps piece CIDs to the IDs of deal proposals for that piece.
+    /// Entries are added when a deal is published and removed with the proposal,
+    /// so this supports finding the deals for a piece without scanning all proposals.
+    /// Deals published before the index existed are not in it. Absent when the index is empty.
+    /// HAMT[Cid][]DealID
+    #[serde(default)]
+    pub piece_deals: Option<Cid>,
+
+    /// Maps providers to the IDs of their deal proposals.
+    /// Entries are added when a deal is published and removed with the proposal,
+    /// so this supports listing a provider's deals without replaying publication events.
+    /// Deals published before the index existed are not in it. Absent when the index is empty.
+    /// HAMT[ActorID]HAMT[DealID]
+    #[serde(default)]
+    pub provider_deals: Option<Cid>,
+
+    /// Funds deposited by providers to top up their escrow when it falls short of the
+    /// collateral for deals they publish, indexed by provider address.
+    /// These funds are not part of the escrow table until moved there by a top-up.
+    /// Absent when no provider has funds in the pool.
+    #[serde(default)]
+    pub collateral_pool: Option<Cid>,
+
+    /// Maps label index keys (see `Label::index_key`) to the IDs of deal proposals with that
+    /// label. Deals with an empty label are not indexed.
+    /// Entries are added when a deal is published, if the policy enables the index,
+    /// and removed with the proposal, so this supports finding deals by an identifier
+    /// encoded in their label. Absent when the index is empty.
+    /// HAMT[LabelKey]HAMT[DealID]
+    #[serde(default)]
+    pub label_deals: Option<Cid>,
+
+    /// Deal acceptance policies published by providers. Absent when no provider has a policy.
+    /// HAMT[ActorID]DealAcceptancePolicy
+    #[serde(default)]
+    pub deal_acceptance_policies: Option<Cid>,
+
+    /// Maps providers to the IDs of their published deals which have not yet been activated,
+    /// indexed by deal start epoch.
+    /// Entries are added when a deal is published and removed when it is activated,
+    /// times out or is cancelled, so this supports listing a provider's sealing backlog in order.
+    /// Deals published before the queue existed are not in it. Absent when no deals are queued.
+    /// HAMT[ActorID]AMT[ChainEpoch][]DealID
+    #[serde(default)]
+    pub provider_pending_deals: Option<Cid>,
+
+    /// Providers watching clients' escrow withdrawals, in ascending order of provider.
+    /// Absent when no client is watched.
+    /// HAMT[ActorID]Vec<ClientWithdrawalWatch>
+    #[serde(default)]
+    pub client_withdrawal_watches: Option<Cid>,
+
+    /// Escrow and locked balances of FRC-46 tokens in which storage fees are paid,
+    /// indexed by token actor ID. Entries are added when a token is first deposited.
+    /// Absent until a token is deposited.
+    /// HAMT[ActorID]TokenBalances
+    #[serde(default)]
+    pub token_balances: Option<Cid>,
+}
+
+/// Balance tables for a single FRC-46 token, structured as the market's FIL tables.
+/// Locked amounts in a token are storage fees for deals paid in that token.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct TokenBalances {
+    /// Total amount of the token held in escrow, indexed by actor address.
+    pub escrow_table: Cid,
+    /// Amount of the token locked, indexed by actor address.
+    pub locked_table: Cid,
 }
 
 pub type PendingProposalsSet<BS> = Set<BS, Cid>;
@@ -106,6 +175,37 @@ pub const PROVIDER_SECTORS_CONFIG: Config =
 pub type SectorDealsMap<BS> = Map2<BS, SectorNumber, Vec<DealID>>;
 pub const SECTOR_DEALS_CONFIG: Config = Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
 
+pub type PieceDealsMap<BS> = Map2<BS, Cid, Vec<DealID>>;
+pub const PIECE_DEALS_CONFIG: Config = Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type LabelDealsMultimap<BS> = SetMultimap<BS, Vec<u8>, DealID>;
+pub const LABEL_DEALS_CONFIG: SetMultimapConfig =
+    SetMultimapConfig { outer: DEFAULT_HAMT_CONFIG, inner: DEFAULT_HAMT_CONFIG };
+
+pub type DealAcceptancePoliciesMap<BS> = Map2<BS, ActorID, DealAcceptancePolicy>;
+pub const DEAL_ACCEPTANCE_POLICIES_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type AllowedClientsSet<BS> = Set<BS, ActorID>;
+pub const ALLOWED_CLIENTS_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type ProviderDealsMultimap<BS> = SetMultimap<BS, ActorID, DealID>;
+pub const PROVIDER_DEALS_CONFIG: SetMultimapConfig =
+    SetMultimapConfig { outer: DEFAULT_HAMT_CONFIG, inner: DEFAULT_HAMT_CONFIG };
+
+pub type ProviderPendingDealsMap<BS> = Map2<BS, ActorID, Cid>;
+pub const PROVIDER_PENDING_DEALS_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type ClientWithdrawalWatchesMap<BS> = Map2<BS, ActorID, Vec<ClientWithdrawalWatch>>;
+pub const CLIENT_WITHDRAWAL_WATCHES_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type TokenBalancesMap<BS> = Map2<BS, ActorID, TokenBalances>;
+pub const TOKEN_BALANCES_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
 impl State {
     pub fn new<BS: Blockstore>(store: &BS) -> Result<Self, ActorError> {
         let empty_proposals_array =
@@ -152,6 +252,14 @@ impl State {
             total_client_storage_fee: TokenAmount::default(),
             pending_deal_allocation_ids: empty_pending_deal_allocation_map,
             provider_sectors: empty_sector_deals_hamt,
+            piece_deals: None,
+            provider_deals: None,
+            collateral_pool: None,
+            label_deals: None,
+            deal_acceptance_policies: None,
+            provider_pending_deals: None,
+            client_withdrawal_watches: None,
+            token_balances: None,
         })
     }
 
@@ -276,13 +384,24 @@ impl State {
             .flush()
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush deal proposals")?;
 
+        if let Some(proposal) = &proposal {
+            self.remove_piece_deal_id(store, &proposal.piece_cid, deal_id)?;
+            if !proposal.label.is_empty() {
+                self.remove_label_deal_id(store, &proposal.label, deal_id)?;
+            }
+            self.remove_provider_deal_id(store, proposal.provider.id().unwrap(), deal_id)?;
+        }
+
         Ok(proposal)
     }
 
+    /// Stores newly published deal proposals and indexes them by piece and provider,
+    /// and by label if `index_labels` is set.
     pub fn put_deal_proposals<BS>(
         &mut self,
         store: &BS,
         new_deal_proposals: &[(DealID, DealProposal)],
+        index_labels: bool,
     ) -> Result<(), ActorError>
     where
         BS: Blockstore,
@@ -290,17 +409,633 @@ impl State {
         let mut deal_proposals = DealArray::load(&self.proposals, store)
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load deal proposal array")?;
 
+        let mut piece_deals = self.load_piece_deals(store)?;
+        let mut provider_deals = self.load_provider_deals(store)?;
+        let mut label_deals = self.load_label_deals(store)?;
+
         new_deal_proposals.iter().try_for_each(|(id, proposal)| -> Result<(), ActorError> {
             deal_proposals
                 .set(*id, proposal.clone())
                 .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set deal proposal")?;
+
+            let mut deal_ids = piece_deals
+                .get(&proposal.piece_cid)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to read piece deals")?
+                .cloned()
+                .unwrap_or_default();
+            deal_ids.push(*id);
+            piece_deals
+                .set(&proposal.piece_cid, deal_ids)
+                .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                    format!("failed to set piece deals for {}", proposal.piece_cid)
+                })?;
+            provider_deals.put(&proposal.provider.id().unwrap(), *id)?;
+
+            if index_labels && !proposal.label.is_empty() {
+                label_deals.put(&proposal.label.index_key(), *id)?;
+            }
             Ok(())
         })?;
 
         self.proposals = deal_proposals
             .flush()
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush deal proposals")?;
+        self.piece_deals = if piece_deals.is_empty() { None } else { Some(piece_deals.flush()?) };
+        self.provider_deals =
+            if provider_deals.is_empty() { None } else { Some(provider_deals.flush()?) };
+        if !label_deals.is_empty() {
+            self.label_deals = Some(label_deals.flush()?);
+        }
+
+        let mut pending_by_provider = BTreeMap::<ActorID, Vec<(DealID, ChainEpoch)>>::new();
+        for (id, proposal) in new_deal_proposals {
+            pending_by_provider
+                .entry(proposal.provider.id().unwrap())
+                .or_default()
+                .push((*id, proposal.start_epoch));
+        }
+        for (provider, deals) in pending_by_provider {
+            self.update_provider_pending_deals(store, provider, |queue| {
+                for (id, start_epoch) in deals {
+                    let mut deal_ids = queue
+                        .get(start_epoch as u64)
+                        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to read pending deals")?
+                        .cloned()
+                        .unwrap_or_default();
+                    deal_ids.push(id);
+                    queue
+                        .set(start_epoch as u64, deal_ids)
+                        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set pending deals")?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn load_piece_deals<BS>(&self, store: BS) -> Result<PieceDealsMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.piece_deals {
+            Some(root) => PieceDealsMap::load(store, root, PIECE_DEALS_CONFIG, "piece deals"),
+            None => Ok(PieceDealsMap::empty(store, PIECE_DEALS_CONFIG, "piece deals")),
+        }
+    }
+
+    /// Returns the IDs of all deal proposals for a piece, in publication order.
+    pub fn get_piece_deal_ids<BS>(
+        &self,
+        store: &BS,
+        piece_cid: &Cid,
+    ) -> Result<Vec<DealID>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let piece_deals = self.load_piece_deals(store)?;
+        let deal_ids = piece_deals
+            .get(piece_cid)
+            .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                format!("failed to read piece deals for {}", piece_cid)
+            })?
+            .cloned()
+            .unwrap_or_default();
+        Ok(deal_ids)
+    }
+
+    pub fn load_label_deals<BS>(&self, store: BS) -> Result<LabelDealsMultimap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.label_deals {
+            Some(root) => LabelDealsMultimap::load(store, root, LABEL_DEALS_CONFIG, "label deals"),
+            None => Ok(LabelDealsMultimap::empty(store, LABEL_DEALS_CONFIG, "label deals")),
+        }
+    }
+
+    /// Returns at most `limit` IDs of deal proposals with a label, starting at `cursor`
+    /// (or the beginning), along with the cursor from which to continue listing.
+    /// Deals are listed in the index's internal order, not by ID.
+    /// Deals with an empty label are not indexed, so are never returned.
+    pub fn list_label_deal_ids<BS>(
+        &self,
+        store: &BS,
+        label: &Label,
+        cursor: Option<DealID>,
+        limit: u64,
+    ) -> Result<(Vec<DealID>, Option<DealID>), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if label.is_empty() {
+            return Ok((vec![], None));
+        }
+        let label_deals = self.load_label_deals(store)?;
+        let deals = match label_deals.get(&label.index_key())? {
+            Some(deals) => deals,
+            None => return Ok((vec![], None)),
+        };
+        if let Some(cursor) = cursor {
+            if !deals.has(&cursor)? {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "cursor deal {} not found for label",
+                    cursor
+                ));
+            }
+        }
+
+        let mut deal_ids = Vec::new();
+        let (_, next) = deals.for_each_range(cursor.as_ref(), Some(limit as usize), |id| {
+            deal_ids.push(id);
+            Ok(())
+        })?;
+        Ok((deal_ids, next))
+    }
+
+    pub fn load_deal_acceptance_policies<BS>(
+        &self,
+        store: BS,
+    ) -> Result<DealAcceptancePoliciesMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.deal_acceptance_policies {
+            Some(root) => DealAcceptancePoliciesMap::load(
+                store,
+                root,
+                DEAL_ACCEPTANCE_POLICIES_CONFIG,
+                "deal acceptance policies",
+            ),
+            None => Ok(DealAcceptancePoliciesMap::empty(
+                store,
+                DEAL_ACCEPTANCE_POLICIES_CONFIG,
+                "deal acceptance policies",
+            )),
+        }
+    }
+
+    /// Returns a provider's deal acceptance policy, if it has published one.
+    pub fn get_deal_acceptance_policy<BS>(
+        &self,
+        store: &BS,
+        provider: ActorID,
+    ) -> Result<Option<DealAcceptancePolicy>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        if self.deal_acceptance_policies.is_none() {
+            return Ok(None);
+        }
+        let policies = self.load_deal_acceptance_policies(store)?;
+        Ok(policies.get(&provider)?.cloned())
+    }
+
+    /// Loads the set of clients allowed by a deal acceptance policy.
+    pub fn load_allowed_clients<BS>(
+        &self,
+        store: BS,
+        root: &Cid,
+    ) -> Result<AllowedClientsSet<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        AllowedClientsSet::load(store, root, ALLOWED_CLIENTS_CONFIG, "allowed clients")
+    }
+
+    /// Sets a provider's deal acceptance policy, or removes it if `None`.
+    pub fn set_deal_acceptance_policy<BS>(
+        &mut self,
+        store: &BS,
+        provider: ActorID,
+        policy: Option<DealAcceptancePolicy>,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut policies = self.load_deal_acceptance_policies(store)?;
+        match policy {
+            Some(policy) => {
+                policies.set(&provider, policy)?;
+            }
+            None => {
+                policies.delete(&provider)?;
+            }
+        }
+        self.deal_acceptance_policies =
+            if policies.is_empty() { None } else { Some(policies.flush()?) };
+        Ok(())
+    }
+
+    pub fn load_client_withdrawal_watches<BS>(
+        &self,
+        store: BS,
+    ) -> Result<ClientWithdrawalWatchesMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.client_withdrawal_watches {
+            Some(root) => ClientWithdrawalWatchesMap::load(
+                store,
+                root,
+                CLIENT_WITHDRAWAL_WATCHES_CONFIG,
+                "client withdrawal watches",
+            ),
+            None => Ok(ClientWithdrawalWatchesMap::empty(
+                store,
+                CLIENT_WITHDRAWAL_WATCHES_CONFIG,
+                "client withdrawal watches",
+            )),
+        }
+    }
+
+    /// Returns the providers watching a client's withdrawals, in ascending order of provider.
+    pub fn get_client_withdrawal_watches<BS>(
+        &self,
+        store: &BS,
+        client: ActorID,
+    ) -> Result<Vec<ClientWithdrawalWatch>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let watches = self.load_client_withdrawal_watches(store)?;
+        Ok(watches.get(&client)?.cloned().unwrap_or_default())
+    }
+
+    /// Sets the threshold at which a provider is notified of a client's withdrawals,
+    /// or stops the provider watching the client if `None`.
+    pub fn set_client_withdrawal_watch<BS>(
+        &mut self,
+        store: &BS,
+        client: ActorID,
+        provider: ActorID,
+        threshold: Option<TokenAmount>,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut watches = self.load_client_withdrawal_watches(store)?;
+        let mut client_watches = watches.get(&client)?.cloned().unwrap_or_default();
+        let existing = client_watches.binary_search_by_key(&provider, |w| w.provider);
+        match (threshold, existing) {
+            (Some(threshold), Ok(i)) => client_watches[i].threshold = threshold,
+            (Some(threshold), Err(i)) => {
+                if client_watches.len() >= detail::CLIENT_WITHDRAWAL_MAX_WATCHES {
+                    return Err(actor_error!(
+                        forbidden,
+                        "client {} already watched by {} providers",
+                        client,
+                        client_watches.len()
+                    ));
+                }
+                client_watches.insert(i, ClientWithdrawalWatch { provider, threshold });
+            }
+            (None, Ok(i)) => {
+                client_watches.remove(i);
+            }
+            (None, Err(_)) => return Ok(()),
+        }
+        if client_watches.is_empty() {
+            watches.delete(&client)?;
+        } else {
+            watches.set(&client, client_watches)?;
+        }
+        self.client_withdrawal_watches =
+            if watches.is_empty() { None } else { Some(watches.flush()?) };
+        Ok(())
+    }
+
+    /// Returns the watches on a client's withdrawals whose thresholds exceed the client's
+    /// unlocked escrow, along with the escrow each watch requires the client to hold:
+    /// its locked funds plus the watch's threshold.
+    pub fn uncovered_client_withdrawal_watches<BS>(
+        &self,
+        store: &BS,
+        client: ActorID,
+    ) -> Result<Vec<(ClientWithdrawalWatch, TokenAmount)>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let watches = self.get_client_withdrawal_watches(store, client)?;
+        if watches.is_empty() {
+            return Ok(vec![]);
+        }
+        let escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
+        let locked_table = BalanceTable::from_root(store, &self.locked_table, "locked table")?;
+        let escrow = escrow_table.get(&Address::new_id(client))?;
+        let locked = locked_table.get(&Address::new_id(client))?;
+        Ok(watches
+            .into_iter()
+            .filter_map(|watch| {
+                let required = &locked + &watch.threshold;
+                (escrow < required).then_some((watch, required))
+            })
+            .collect())
+    }
+
+    pub fn load_provider_deals<BS>(
+        &self,
+        store: BS,
+    ) -> Result<ProviderDealsMultimap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.provider_deals {
+            Some(root) => {
+                ProviderDealsMultimap::load(store, root, PROVIDER_DEALS_CONFIG, "provider deals")
+            }
+            None => {
+                Ok(ProviderDealsMultimap::empty(store, PROVIDER_DEALS_CONFIG, "provider deals"))
+            }
+        }
+    }
+
+    /// Returns at most `limit` IDs of a provider's deal proposals, starting at `cursor`
+    /// (or the beginning), along with the cursor from which to continue listing.
+    /// Deals are listed in the index's internal order, not by ID.
+    pub fn list_provider_deal_ids<BS>(
+        &self,
+        store: &BS,
+        provider: ActorID,
+        cursor: Option<DealID>,
+        limit: u64,
+    ) -> Result<(Vec<DealID>, Option<DealID>), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let provider_deals = self.load_provider_deals(store)?;
+        let deals = match provider_deals.get(&provider)? {
+            Some(deals) => deals,
+            None => return Ok((vec![], None)),
+        };
+        if let Some(cursor) = cursor {
+            if !deals.has(&cursor)? {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "cursor deal {} not found for provider {}",
+                    cursor,
+                    provider
+                ));
+            }
+        }
+
+        let mut deal_ids = Vec::new();
+        let (_, next) = deals.for_each_range(cursor.as_ref(), Some(limit as usize), |id| {
+            deal_ids.push(id);
+            Ok(())
+        })?;
+        Ok((deal_ids, next))
+    }
+
+    pub fn load_provider_pending_deals<BS>(
+        &self,
+        store: BS,
+    ) -> Result<ProviderPendingDealsMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.provider_pending_deals {
+            Some(root) => ProviderPendingDealsMap::load(
+                store,
+                root,
+                PROVIDER_PENDING_DEALS_CONFIG,
+                "provider pending deals",
+            ),
+            None => Ok(ProviderPendingDealsMap::empty(
+                store,
+                PROVIDER_PENDING_DEALS_CONFIG,
+                "provider pending deals",
+            )),
+        }
+    }
+
+    /// Removes deals from a provider's queue of deals awaiting activation,
+    /// given as pairs of deal ID and start epoch.
+    /// Deals missing from the queue are ignored.
+    pub fn remove_provider_pending_deals<BS>(
+        &mut self,
+        store: &BS,
+        provider: ActorID,
+        deals: &[(DealID, ChainEpoch)],
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if deals.is_empty() {
+            return Ok(());
+        }
+        self.update_provider_pending_deals(store, provider, |queue| {
+            for (id, start_epoch) in deals {
+                let key = *start_epoch as u64;
+                let deal_ids = match queue
+                    .get(key)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to read pending deals")?
+                {
+                    Some(deal_ids) if deal_ids.contains(id) => deal_ids,
+                    _ => continue,
+                };
+                let remaining: Vec<DealID> =
+                    deal_ids.iter().filter(|d| *d != id).cloned().collect();
+                if remaining.is_empty() {
+                    queue.delete(key).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        "failed to delete pending deals",
+                    )?;
+                } else {
+                    queue
+                        .set(key, remaining)
+                        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set pending deals")?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns at most `limit` of a provider's deals awaiting activation, ordered by start epoch
+    /// and then ID, starting at `cursor` (or the beginning), along with the cursor from which
+    /// to continue listing.
+    /// The cursor need not identify a deal still in the queue, so listing may continue after
+    /// the deal it names has been activated.
+    pub fn list_provider_pending_deals<BS>(
+        &self,
+        store: &BS,
+        provider: ActorID,
+        cursor: Option<PendingDeal>,
+        limit: u64,
+    ) -> Result<(Vec<PendingDeal>, Option<PendingDeal>), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let pending_deals = self.load_provider_pending_deals(store)?;
+        let root = match pending_deals.get(&provider)? {
+            Some(root) => root,
+            None => return Ok((vec![], None)),
+        };
+        let queue = PendingDealQueue::load(root, store)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load pending deal queue")?;
+
+        // Start at the cursor's epoch, skipping earlier deals at that epoch.
+        let start_at = cursor.map(|c| c.start_epoch as u64);
+        let mut deals = Vec::new();
+        let mut next = None;
+        queue
+            .for_each_while_ranged(start_at, None, |key, deal_ids| {
+                for deal_id in deal_ids {
+                    let deal = PendingDeal { start_epoch: key as ChainEpoch, deal_id: *deal_id };
+                    let before_cursor = cursor.is_some_and(|c| {
+                        (deal.start_epoch, deal.deal_id) < (c.start_epoch, c.deal_id)
+                    });
+                    if before_cursor {
+                        continue;
+                    }
+                    if deals.len() as u64 == limit {
+                        next = Some(deal);
+                        return Ok(false);
+                    }
+                    deals.push(deal);
+                }
+                Ok(true)
+            })
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate pending deal queue")?;
+        Ok((deals, next))
+    }
+
+    // Loads a provider's queue of deals awaiting activation, applies a modification,
+    // and stores the result, removing the provider's entry if the queue is left empty.
+    fn update_provider_pending_deals<BS, F>(
+        &mut self,
+        store: &BS,
+        provider: ActorID,
+        f: F,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+        F: FnOnce(&mut PendingDealQueue<BS>) -> Result<(), ActorError>,
+    {
+        let mut pending_deals = self.load_provider_pending_deals(store)?;
+        let mut queue = match pending_deals.get(&provider)? {
+            Some(root) => PendingDealQueue::load(root, store)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load pending deal queue")?,
+            None => PendingDealQueue::new_with_bit_width(store, PENDING_DEAL_QUEUE_AMT_BITWIDTH),
+        };
+        f(&mut queue)?;
 
+        if queue.count() == 0 {
+            pending_deals.delete(&provider)?;
+        } else {
+            let root = queue
+                .flush()
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush pending deal queue")?;
+            pending_deals.set(&provider, root)?;
+        }
+        self.provider_pending_deals =
+            if pending_deals.is_empty() { None } else { Some(pending_deals.flush()?) };
+        Ok(())
+    }
+
+    // Removes a deal from the label deals mapping.
+    // A missing deal, or a missing index, is ignored.
+    fn remove_label_deal_id<BS>(
+        &mut self,
+        store: &BS,
+        label: &Label,
+        deal_id: DealID,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if self.label_deals.is_none() {
+            return Ok(());
+        }
+        let key = label.index_key();
+        let mut label_deals = self.load_label_deals(store)?;
+        let others_remain = match label_deals.get(&key)? {
+            Some(mut deals) => {
+                if deals.delete(&deal_id)?.is_none() {
+                    return Ok(());
+                }
+                !deals.is_empty()
+            }
+            None => return Ok(()),
+        };
+        if others_remain {
+            label_deals.remove(&key, deal_id)?;
+        } else {
+            label_deals.remove_all(&key)?;
+        }
+        self.label_deals = if label_deals.is_empty() { None } else { Some(label_deals.flush()?) };
+        Ok(())
+    }
+
+    // Removes a deal from the provider deals mapping, dropping the provider when no deals remain.
+    // A missing deal is ignored.
+    fn remove_provider_deal_id<BS>(
+        &mut self,
+        store: &BS,
+        provider: ActorID,
+        deal_id: DealID,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if self.provider_deals.is_none() {
+            return Ok(());
+        }
+        let mut provider_deals = self.load_provider_deals(store)?;
+        let others_remain = match provider_deals.get(&provider)? {
+            Some(mut deals) => {
+                if deals.delete(&deal_id)?.is_none() {
+                    return Ok(());
+                }
+                !deals.is_empty()
+            }
+            None => return Ok(()),
+        };
+        if others_remain {
+            provider_deals.remove(&provider, deal_id)?;
+        } else {
+            provider_deals.remove_all(&provider)?;
+        }
+        self.provider_deals =
+            if provider_deals.is_empty() { None } else { Some(provider_deals.flush()?) };
+        Ok(())
+    }
+
+    // Removes a deal from the piece deals mapping.
+    // A missing deal is ignored.
+    fn remove_piece_deal_id<BS>(
+        &mut self,
+        store: &BS,
+        piece_cid: &Cid,
+        deal_id: DealID,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut piece_deals = self.load_piece_deals(store)?;
+        let existing_deal_ids = piece_deals
+            .get(piece_cid)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to read piece deals")?;
+        let new_deals: Vec<DealID> = match existing_deal_ids {
+            Some(existing) if existing.contains(&deal_id) => {
+                existing.iter().filter(|id| **id != deal_id).cloned().collect()
+            }
+            _ => return Ok(()),
+        };
+
+        if new_deals.is_empty() {
+            piece_deals.delete(piece_cid).with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                format!("failed to delete piece deals for {}", piece_cid)
+            })?;
+        } else {
+            piece_deals
+                .set(piece_cid, new_deals)
+                .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                    format!("failed to set piece deals for {}", piece_cid)
+                })?;
+        }
+        self.piece_deals = if piece_deals.is_empty() { None } else { Some(piece_deals.flush()?) };
         Ok(())
     }
 
@@ -421,86 +1156,277 @@ impl State {
     where
         BS: Blockstore,
     {
-        let mut deals_by_epoch = self.load_deal_ops(store)?;
-        new_deals_by_epoch.iter().try_for_each(|(epoch, deals)| -> Result<(), ActorError> {
-            deals_by_epoch.put_many(epoch, deals)?;
-            Ok(())
-        })?;
+        let mut deals_by_epoch = self.load_deal_ops(store)?;
+        new_deals_by_epoch.iter().try_for_each(|(epoch, deals)| -> Result<(), ActorError> {
+            deals_by_epoch.put_many(epoch, deals)?;
+            Ok(())
+        })?;
+
+        self.deal_ops_by_epoch = deals_by_epoch.flush()?;
+        Ok(())
+    }
+
+    pub fn get_deals_for_epoch<BS>(
+        &self,
+        store: &BS,
+        key: ChainEpoch,
+    ) -> Result<Vec<DealID>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut deal_ids = Vec::new();
+        let deals_by_epoch = self.load_deal_ops(store)?;
+        deals_by_epoch.for_each_in(&key, |deal_id| {
+            deal_ids.push(deal_id);
+            Ok(())
+        })?;
+
+        Ok(deal_ids)
+    }
+
+    pub fn remove_deals_by_epoch<BS>(
+        &mut self,
+        store: &BS,
+        epochs_to_remove: &[ChainEpoch],
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut deals_by_epoch = self.load_deal_ops(store)?;
+        epochs_to_remove.iter().try_for_each(|epoch| -> Result<(), ActorError> {
+            deals_by_epoch.remove_all(epoch)?;
+            Ok(())
+        })?;
+
+        self.deal_ops_by_epoch = deals_by_epoch.flush()?;
+        Ok(())
+    }
+
+    pub fn add_balance_to_escrow_table<BS>(
+        &mut self,
+        store: &BS,
+        addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
+        escrow_table.add(addr, amount)?;
+        self.escrow_table = escrow_table.root()?;
+        Ok(())
+    }
+
+    pub fn withdraw_balance_from_escrow_table<BS>(
+        &mut self,
+        store: &BS,
+        addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<TokenAmount, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
+        let locked_table = BalanceTable::from_root(store, &self.locked_table, "locked table")?;
+
+        let min_balance = locked_table.get(addr)?;
+        let ex = escrow_table.subtract_with_minimum(addr, amount, &min_balance)?;
+
+        self.escrow_table = escrow_table.root()?;
+        Ok(ex)
+    }
+
+    pub fn load_collateral_pool<BS>(&self, store: BS) -> Result<BalanceTable<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.collateral_pool {
+            Some(root) => BalanceTable::from_root(store, root, "collateral pool"),
+            None => Ok(BalanceTable::new(store, "collateral pool")),
+        }
+    }
+
+    fn save_collateral_pool<BS>(&mut self, pool: &mut BalanceTable<BS>) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        self.collateral_pool = if pool.0.is_empty() { None } else { Some(pool.root()?) };
+        Ok(())
+    }
+
+    pub fn add_balance_to_collateral_pool<BS>(
+        &mut self,
+        store: &BS,
+        addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut pool = self.load_collateral_pool(store)?;
+        pool.add(addr, amount)?;
+        self.save_collateral_pool(&mut pool)
+    }
+
+    pub fn withdraw_balance_from_collateral_pool<BS>(
+        &mut self,
+        store: &BS,
+        addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<TokenAmount, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut pool = self.load_collateral_pool(store)?;
+        let ex = pool.subtract_with_minimum(addr, amount, &TokenAmount::zero())?;
+        self.save_collateral_pool(&mut pool)?;
+        Ok(ex)
+    }
+
+    pub fn get_collateral_pool_balance<BS>(
+        &self,
+        store: &BS,
+        addr: &Address,
+    ) -> Result<TokenAmount, ActorError>
+    where
+        BS: Blockstore,
+    {
+        self.load_collateral_pool(store)?.get(addr)
+    }
+
+    /// Moves funds from an address's collateral pool balance into its escrow.
+    pub fn top_up_escrow_from_collateral_pool<BS>(
+        &mut self,
+        store: &BS,
+        addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut pool = self.load_collateral_pool(store)?;
+        pool.must_subtract(addr, amount)?;
+        self.save_collateral_pool(&mut pool)?;
+        self.add_balance_to_escrow_table(store, addr, amount)
+    }
 
-        self.deal_ops_by_epoch = deals_by_epoch.flush()?;
-        Ok(())
+    pub fn load_token_balances<BS>(&self, store: BS) -> Result<TokenBalancesMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        match &self.token_balances {
+            Some(root) => {
+                TokenBalancesMap::load(store, root, TOKEN_BALANCES_CONFIG, "token balances")
+            }
+            None => Ok(TokenBalancesMap::empty(store, TOKEN_BALANCES_CONFIG, "token balances")),
+        }
     }
 
-    pub fn get_deals_for_epoch<BS>(
+    /// Loads the escrow and locked tables of a token, which are empty if it was never deposited.
+    fn load_token_tables<'bs, BS>(
         &self,
-        store: &BS,
-        key: ChainEpoch,
-    ) -> Result<Vec<DealID>, ActorError>
+        store: &'bs BS,
+        token: ActorID,
+    ) -> Result<(BalanceTable<&'bs BS>, BalanceTable<&'bs BS>), ActorError>
     where
         BS: Blockstore,
     {
-        let mut deal_ids = Vec::new();
-        let deals_by_epoch = self.load_deal_ops(store)?;
-        deals_by_epoch.for_each_in(&key, |deal_id| {
-            deal_ids.push(deal_id);
-            Ok(())
-        })?;
-
-        Ok(deal_ids)
+        match self.load_token_balances(store)?.get(&token)? {
+            Some(tables) => Ok((
+                BalanceTable::from_root(store, &tables.escrow_table, "token escrow table")?,
+                BalanceTable::from_root(store, &tables.locked_table, "token locked table")?,
+            )),
+            None => Ok((
+                BalanceTable::new(store, "token escrow table"),
+                BalanceTable::new(store, "token locked table"),
+            )),
+        }
     }
 
-    pub fn remove_deals_by_epoch<BS>(
+    fn save_token_tables<BS>(
         &mut self,
         store: &BS,
-        epochs_to_remove: &[ChainEpoch],
+        token: ActorID,
+        escrow_table: &mut BalanceTable<&BS>,
+        locked_table: &mut BalanceTable<&BS>,
     ) -> Result<(), ActorError>
     where
         BS: Blockstore,
     {
-        let mut deals_by_epoch = self.load_deal_ops(store)?;
-        epochs_to_remove.iter().try_for_each(|epoch| -> Result<(), ActorError> {
-            deals_by_epoch.remove_all(epoch)?;
-            Ok(())
-        })?;
-
-        self.deal_ops_by_epoch = deals_by_epoch.flush()?;
+        let mut balances = self.load_token_balances(store)?;
+        balances.set(
+            &token,
+            TokenBalances {
+                escrow_table: escrow_table.root()?,
+                locked_table: locked_table.root()?,
+            },
+        )?;
+        self.token_balances = Some(balances.flush()?);
         Ok(())
     }
 
-    pub fn add_balance_to_escrow_table<BS>(
+    /// Returns the escrow balance and locked amount of a token for an address.
+    pub fn get_token_balance<BS>(
+        &self,
+        store: &BS,
+        token: ActorID,
+        addr: &Address,
+    ) -> Result<(TokenAmount, TokenAmount), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let (escrow_table, locked_table) = self.load_token_tables(store, token)?;
+        Ok((escrow_table.get(addr)?, locked_table.get(addr)?))
+    }
+
+    pub fn add_balance_to_token_escrow<BS>(
         &mut self,
         store: &BS,
+        token: ActorID,
         addr: &Address,
         amount: &TokenAmount,
     ) -> Result<(), ActorError>
     where
         BS: Blockstore,
     {
-        let mut escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
+        let (mut escrow_table, mut locked_table) = self.load_token_tables(store, token)?;
         escrow_table.add(addr, amount)?;
-        self.escrow_table = escrow_table.root()?;
-        Ok(())
+        self.save_token_tables(store, token, &mut escrow_table, &mut locked_table)
     }
 
-    pub fn withdraw_balance_from_escrow_table<BS>(
+    pub fn withdraw_balance_from_token_escrow<BS>(
         &mut self,
         store: &BS,
+        token: ActorID,
         addr: &Address,
         amount: &TokenAmount,
     ) -> Result<TokenAmount, ActorError>
     where
         BS: Blockstore,
     {
-        let mut escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
-        let locked_table = BalanceTable::from_root(store, &self.locked_table, "locked table")?;
-
+        let (mut escrow_table, mut locked_table) = self.load_token_tables(store, token)?;
         let min_balance = locked_table.get(addr)?;
         let ex = escrow_table.subtract_with_minimum(addr, amount, &min_balance)?;
-
-        self.escrow_table = escrow_table.root()?;
+        self.save_token_tables(store, token, &mut escrow_table, &mut locked_table)?;
         Ok(ex)
     }
 
+    // Return true when the escrow of a token for the input address can cover an additional
+    // lockup of amount_to_lock
+    pub fn token_balance_covered<BS>(
+        &self,
+        store: &BS,
+        token: ActorID,
+        addr: Address,
+        amount_to_lock: &TokenAmount,
+    ) -> Result<bool, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let (escrow_balance, prev_locked) = self.get_token_balance(store, token, &addr)?;
+        Ok((prev_locked + amount_to_lock) <= escrow_balance)
+    }
+
     pub fn load_pending_deals<BS>(&self, store: BS) -> Result<PendingProposalsSet<BS>, ActorError>
     where
         BS: Blockstore,
@@ -789,12 +1715,58 @@ impl State {
 
                 // delete pending deal allocation id (if present)
                 self.remove_pending_deal_allocation_id(store, deal_id)?;
+                self.remove_provider_pending_deals(
+                    store,
+                    deal_proposal.provider.id().unwrap(),
+                    &[(deal_id, deal_proposal.start_epoch)],
+                )?;
 
                 Ok(LoadDealState::ProposalExpired(slashed))
             }
         }
     }
 
+    /// Removes a published but un-activated deal cancelled by its client,
+    /// unlocking the client's and provider's funds in full.
+    /// The caller is responsible for checking that the deal has not been activated.
+    pub fn cancel_pending_deal<BS>(
+        &mut self,
+        store: &BS,
+        deal_id: DealID,
+        deal_proposal: &DealProposal,
+        dcid: &Cid,
+    ) -> Result<Option<AllocationID>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        self.process_deal_cancelled(store, deal_proposal)?;
+
+        self.remove_proposal(store, deal_id)?.ok_or_else(|| {
+            actor_error!(
+                illegal_state,
+                "failed to delete deal {} proposal: does not exist",
+                deal_id
+            )
+        })?;
+        self.remove_pending_deal(store, *dcid)?.ok_or_else(|| {
+            actor_error!(
+                illegal_state,
+                "failed to delete pending deal {}: cid {} does not exist",
+                deal_id,
+                dcid
+            )
+        })?;
+        // The allocation (if any) remains in the verified registry until it is released or expires,
+        // after which the client may reclaim the DataCap.
+        let allocation_id = self.remove_pending_deal_allocation_id(store, deal_id)?;
+        self.remove_provider_pending_deals(
+            store,
+            deal_proposal.provider.id().unwrap(),
+            &[(deal_id, deal_proposal.start_epoch)],
+        )?;
+        Ok(allocation_id)
+    }
+
     ////////////////////////////////////////////////////////////////////////////////
     // Deal state operations
     ////////////////////////////////////////////////////////////////////////////////
@@ -835,52 +1807,12 @@ impl State {
             self.remove_pending_deal(store, *deal_cid)?;
         }
 
-        // if the deal was ever updated, make sure it didn't happen in the future
-        if ever_updated && state.last_updated_epoch > epoch {
-            return Err(actor_error!(
-                illegal_state,
-                "deal updated at future epoch {}",
-                state.last_updated_epoch
-            ));
-        }
-
         // this is a safe no-op but can happen if a storage provider calls settle_deal_payments too early
-        if deal.start_epoch > epoch {
+        let Some(elapsed_payment) = deal_payment_due(state, deal, epoch)? else {
             return Ok((TokenAmount::zero(), TokenAmount::zero(), false, false));
-        }
-
-        let payment_end_epoch = if ever_slashed {
-            if epoch < state.slash_epoch {
-                return Err(actor_error!(
-                    illegal_state,
-                    "current epoch less than deal slash epoch {}",
-                    state.slash_epoch
-                ));
-            }
-            if state.slash_epoch > deal.end_epoch {
-                return Err(actor_error!(
-                    illegal_state,
-                    "deal slash epoch {} after deal end {}",
-                    state.slash_epoch,
-                    deal.end_epoch
-                ));
-            }
-            state.slash_epoch
-        } else {
-            std::cmp::min(deal.end_epoch, epoch)
-        };
-
-        let payment_start_epoch = if ever_updated && state.last_updated_epoch > deal.start_epoch {
-            state.last_updated_epoch
-        } else {
-            deal.start_epoch
         };
-
-        let num_epochs_elapsed = payment_end_epoch - payment_start_epoch;
-
-        let elapsed_payment = &deal.storage_price_per_epoch * num_epochs_elapsed;
         if elapsed_payment.is_positive() {
-            self.transfer_balance(store, &deal.client, &deal.provider, &elapsed_payment)?;
+            self.pay_storage_fee(store, deal, &elapsed_payment)?;
         }
 
         // TODO: remove handling of terminated deals *after* transition to synchronous deal termination
@@ -891,7 +1823,7 @@ impl State {
             let payment_remaining = deal_get_payment_remaining(deal, state.slash_epoch)?;
 
             // Unlock remaining storage fee
-            self.unlock_balance(store, &deal.client, &payment_remaining, Reason::ClientStorageFee)
+            self.unlock_storage_fee(store, deal, &payment_remaining)
                 .context("unlocking client storage fee")?;
 
             // Unlock client collateral
@@ -919,6 +1851,50 @@ impl State {
         Ok((TokenAmount::zero(), elapsed_payment, false, false))
     }
 
+    /// Computes the payment that settling a deal at the given epoch would transfer,
+    /// and whether the deal would complete, without modifying state.
+    /// Fails with EX_DEAL_EXPIRED for a deal which would instead time out,
+    /// and with USR_ILLEGAL_ARGUMENT for a deal marked for termination, which cannot be settled.
+    pub fn preview_deal_settlement<BS>(
+        &self,
+        store: &BS,
+        deal_id: DealID,
+        deal: &DealProposal,
+        epoch: ChainEpoch,
+    ) -> Result<DealSettlementSummary, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let state = match self.find_deal_state(store, deal_id)? {
+            Some(state) => state,
+            None if epoch < deal.start_epoch => {
+                return Ok(DealSettlementSummary {
+                    payment: TokenAmount::zero(),
+                    completed: false,
+                });
+            }
+            None => {
+                return Err(ActorError::unchecked(
+                    EX_DEAL_EXPIRED,
+                    format!("deal {} proposal expired before activation", deal_id),
+                ));
+            }
+        };
+        if state.slash_epoch != EPOCH_UNDEFINED {
+            return Err(actor_error!(
+                illegal_argument,
+                "deal {} is marked for termination and cannot be settled",
+                deal_id
+            ));
+        }
+        match deal_payment_due(&state, deal, epoch)? {
+            Some(payment) => {
+                Ok(DealSettlementSummary { payment, completed: epoch >= deal.end_epoch })
+            }
+            None => Ok(DealSettlementSummary { payment: TokenAmount::zero(), completed: false }),
+        }
+    }
+
     pub fn process_slashed_deal<BS>(
         &mut self,
         store: &BS,
@@ -934,14 +1910,14 @@ impl State {
         let num_epochs_elapsed = max(0, payment_end_epoch - payment_start_epoch);
         let total_payment = &proposal.storage_price_per_epoch * num_epochs_elapsed;
         if total_payment.is_positive() {
-            self.transfer_balance(store, &proposal.client, &proposal.provider, &total_payment)?;
+            self.pay_storage_fee(store, proposal, &total_payment)?;
         }
 
         // unlock client collateral and locked storage fee
         let payment_remaining = deal_get_payment_remaining(proposal, state.slash_epoch)?;
 
         // Unlock remaining storage fee
-        self.unlock_balance(store, &proposal.client, &payment_remaining, Reason::ClientStorageFee)
+        self.unlock_storage_fee(store, proposal, &payment_remaining)
             .context("unlocking client storage fee")?;
 
         // Unlock client collateral
@@ -972,13 +1948,8 @@ impl State {
     where
         BS: Blockstore,
     {
-        self.unlock_balance(
-            store,
-            &deal.client,
-            &deal.total_storage_fee(),
-            Reason::ClientStorageFee,
-        )
-        .context("unlocking client storage fee")?;
+        self.unlock_storage_fee(store, deal, &deal.total_storage_fee())
+            .context("unlocking client storage fee")?;
 
         self.unlock_balance(store, &deal.client, &deal.client_collateral, Reason::ClientCollateral)
             .context("unlocking client collateral")?;
@@ -996,6 +1967,33 @@ impl State {
         Ok(amount_slashed)
     }
 
+    /// Deal cancelled by the client before activation.
+    /// Unlock the storage fee and collaterals for both provider and client, without penalty.
+    fn process_deal_cancelled<BS>(
+        &mut self,
+        store: &BS,
+        deal: &DealProposal,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        self.unlock_storage_fee(store, deal, &deal.total_storage_fee())
+            .context("unlocking client storage fee")?;
+
+        self.unlock_balance(store, &deal.client, &deal.client_collateral, Reason::ClientCollateral)
+            .context("unlocking client collateral")?;
+
+        self.unlock_balance(
+            store,
+            &deal.provider,
+            deal.provider_balance_requirement(),
+            Reason::ProviderCollateral,
+        )
+        .context("unlocking deal provider balance")?;
+
+        Ok(())
+    }
+
     /// Normal expiration. Unlock collaterals for both miner and client.
     fn process_deal_expired<BS>(
         &mut self,
@@ -1048,6 +2046,25 @@ impl State {
         Ok((prev_locked + amount_to_lock) <= escrow_balance)
     }
 
+    // Return the amount by which the funds in escrow for the input address fall short of
+    // an additional lockup of amount_to_lock (zero if they cover it).
+    pub fn balance_shortfall<BS>(
+        &self,
+        store: &BS,
+        addr: Address,
+        amount_to_lock: &TokenAmount,
+    ) -> Result<TokenAmount, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
+        let locked_table = BalanceTable::from_root(store, &self.locked_table, "locked table")?;
+
+        let escrow_balance = escrow_table.get(&addr)?;
+        let prev_locked = locked_table.get(&addr)?;
+        Ok(max(prev_locked + amount_to_lock - escrow_balance, TokenAmount::zero()))
+    }
+
     fn maybe_lock_balance<BS>(
         &mut self,
         store: &BS,
@@ -1088,15 +2105,114 @@ impl State {
     {
         self.maybe_lock_balance(store, &proposal.client, &proposal.client_balance_requirement())
             .context("locking client funds")?;
+        if let Some(token) = proposal.payment_token_id()? {
+            self.maybe_lock_token_balance(
+                store,
+                token,
+                &proposal.client,
+                &proposal.total_storage_fee(),
+            )
+            .context("locking client token funds")?;
+        }
         self.maybe_lock_balance(store, &proposal.provider, &proposal.provider_collateral)
             .context("locking provider funds")?;
 
         self.total_client_locked_collateral += &proposal.client_collateral;
-        self.total_client_storage_fee += proposal.total_storage_fee();
+        if proposal.payment_token.is_none() {
+            self.total_client_storage_fee += proposal.total_storage_fee();
+        }
         self.total_provider_locked_collateral += &proposal.provider_collateral;
         Ok(())
     }
 
+    fn maybe_lock_token_balance<BS>(
+        &mut self,
+        store: &BS,
+        token: ActorID,
+        addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if amount.is_negative() {
+            return Err(actor_error!(illegal_state, "cannot lock negative amount {}", amount));
+        }
+
+        let (mut escrow_table, mut locked_table) = self.load_token_tables(store, token)?;
+        let prev_locked = locked_table.get(addr)?;
+        let escrow_balance = escrow_table.get(addr)?;
+        if &prev_locked + amount > escrow_balance {
+            return Err(actor_error!(insufficient_funds;
+                    "not enough balance of token {} to lock for addr{}: \
+                    escrow balance {} < prev locked {} + amount {}",
+                    token, addr, escrow_balance, prev_locked, amount));
+        }
+
+        locked_table.add(addr, amount)?;
+        self.save_token_tables(store, token, &mut escrow_table, &mut locked_table)
+    }
+
+    /// Unlocks part of a deal's storage fee in the client's escrow of FIL or its payment token.
+    fn unlock_storage_fee<BS>(
+        &mut self,
+        store: &BS,
+        proposal: &DealProposal,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let token = match proposal.payment_token_id()? {
+            Some(token) => token,
+            None => {
+                return self.unlock_balance(
+                    store,
+                    &proposal.client,
+                    amount,
+                    Reason::ClientStorageFee,
+                );
+            }
+        };
+        if amount.is_negative() {
+            return Err(actor_error!(illegal_state, "unlock negative amount: {}", amount));
+        }
+
+        let (mut escrow_table, mut locked_table) = self.load_token_tables(store, token)?;
+        locked_table.must_subtract(&proposal.client, amount).context("unlocking token balance")?;
+        self.save_token_tables(store, token, &mut escrow_table, &mut locked_table)
+    }
+
+    /// Pays part of a deal's storage fee, moving it from the client's locked escrow
+    /// of FIL or its payment token to the provider's available escrow.
+    fn pay_storage_fee<BS>(
+        &mut self,
+        store: &BS,
+        proposal: &DealProposal,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let token = match proposal.payment_token_id()? {
+            Some(token) => token,
+            None => {
+                return self.transfer_balance(store, &proposal.client, &proposal.provider, amount);
+            }
+        };
+        if amount.is_negative() {
+            return Err(actor_error!(illegal_state, "transfer negative amount: {}", amount));
+        }
+
+        let (mut escrow_table, mut locked_table) = self.load_token_tables(store, token)?;
+        escrow_table.must_subtract(&proposal.client, amount)?;
+        locked_table
+            .must_subtract(&proposal.client, amount)
+            .context("unlocking client token balance")?;
+        escrow_table.add(&proposal.provider, amount)?;
+        self.save_token_tables(store, token, &mut escrow_table, &mut locked_table)
+    }
+
     fn unlock_balance<BS>(
         &mut self,
         store: &BS,
@@ -1187,6 +2303,62 @@ pub enum LoadDealState {
     Loaded(DealState),
 }
 
+/// Computes the payment due to the provider of an active deal for the epochs since it was
+/// last updated, up to the given epoch (or its slash epoch, for a deal marked for termination).
+/// Returns None if the deal has not yet started.
+/// Both settlement and its preview compute payments with this, so they cannot disagree.
+pub fn deal_payment_due(
+    state: &DealState,
+    deal: &DealProposal,
+    epoch: ChainEpoch,
+) -> Result<Option<TokenAmount>, ActorError> {
+    let ever_updated = state.last_updated_epoch != EPOCH_UNDEFINED;
+    let ever_slashed = state.slash_epoch != EPOCH_UNDEFINED;
+
+    // if the deal was ever updated, make sure it didn't happen in the future
+    if ever_updated && state.last_updated_epoch > epoch {
+        return Err(actor_error!(
+            illegal_state,
+            "deal updated at future epoch {}",
+            state.last_updated_epoch
+        ));
+    }
+
+    if deal.start_epoch > epoch {
+        return Ok(None);
+    }
+
+    let payment_end_epoch = if ever_slashed {
+        if epoch < state.slash_epoch {
+            return Err(actor_error!(
+                illegal_state,
+                "current epoch less than deal slash epoch {}",
+                state.slash_epoch
+            ));
+        }
+        if state.slash_epoch > deal.end_epoch {
+            return Err(actor_error!(
+                illegal_state,
+                "deal slash epoch {} after deal end {}",
+                state.slash_epoch,
+                deal.end_epoch
+            ));
+        }
+        state.slash_epoch
+    } else {
+        std::cmp::min(deal.end_epoch, epoch)
+    };
+
+    let payment_start_epoch = if ever_updated && state.last_updated_epoch > deal.start_epoch {
+        state.last_updated_epoch
+    } else {
+        deal.start_epoch
+    };
+
+    let num_epochs_elapsed = payment_end_epoch - payment_start_epoch;
+    Ok(Some(&deal.storage_price_per_epoch * num_epochs_elapsed))
+}
+
 pub fn deal_get_payment_remaining(
     deal: &DealProposal,
     mut slash_epoch: ChainEpoch,
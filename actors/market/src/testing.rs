@@ -27,10 +27,12 @@ use fil_actors_runtime::{
 };
 
 use crate::ext::verifreg::AllocationID;
+use crate::policy::detail::{CLIENT_WITHDRAWAL_MAX_WATCHES, DEAL_ACCEPTANCE_MAX_ALLOWED_CLIENTS};
 use crate::{
     DEAL_OPS_BY_EPOCH_CONFIG, DealArray, DealMetaArray, DealOpsByEpoch, DealProposal,
-    PENDING_PROPOSALS_CONFIG, PROVIDER_SECTORS_CONFIG, PendingProposalsSet, ProviderSectorsMap,
-    SECTOR_DEALS_CONFIG, SectorDealsMap, State, balance_table::BalanceTable,
+    LABEL_DEALS_CONFIG, LabelDealsMultimap, PENDING_PROPOSALS_CONFIG, PROVIDER_SECTORS_CONFIG,
+    PendingDealQueue, PendingProposalsSet, ProviderSectorsMap, SECTOR_DEALS_CONFIG, SectorDealsMap,
+    State, balance_table::BalanceTable,
 };
 
 #[derive(Clone)]
@@ -43,6 +45,7 @@ pub struct DealSummary {
     pub last_update_epoch: ChainEpoch,
     pub slash_epoch: ChainEpoch,
     pub piece_cid: Option<Cid>,
+    pub label_key: Option<Vec<u8>>,
 }
 
 impl Default for DealSummary {
@@ -56,6 +59,7 @@ impl Default for DealSummary {
             last_update_epoch: -1,
             slash_epoch: -1,
             piece_cid: None,
+            label_key: None,
         }
     }
 }
@@ -127,6 +131,7 @@ pub fn check_state_invariants<BS: Blockstore>(
                         start_epoch: proposal.start_epoch,
                         end_epoch: proposal.end_epoch,
                         piece_cid: Some(proposal.piece_cid),
+                        label_key: (!proposal.label.is_empty()).then(|| proposal.label.index_key()),
                         ..Default::default()
                     },
                 );
@@ -142,6 +147,10 @@ pub fn check_state_invariants<BS: Blockstore>(
                     proposal.provider.protocol() == Protocol::ID,
                     "provider address for deal {deal_id} is not an ID address",
                 );
+                acc.require(
+                    proposal.payment_token.is_none_or(|t| t.protocol() == Protocol::ID),
+                    "payment token address for deal {deal_id} is not an ID address",
+                );
                 Ok(())
             });
             acc.require_no_error(ret, "error iterating proposals");
@@ -292,6 +301,262 @@ pub fn check_state_invariants<BS: Blockstore>(
         }
     }
 
+    // Piece->deal mapping
+    // Only proposals are indexed, each under its piece CID. Deals published before the index
+    // existed are not in it.
+    let mut indexed_piece_deals = BTreeSet::<DealID>::new();
+    match state.load_piece_deals(store) {
+        Ok(piece_deals) => {
+            if state.piece_deals.is_some() {
+                acc.require(!piece_deals.is_empty(), "empty piece deals not absent");
+            }
+            let ret = piece_deals.for_each(|piece_cid, deal_ids| {
+                acc.require(!deal_ids.is_empty(), format!("empty piece deals for {piece_cid}"));
+                for deal_id in deal_ids {
+                    acc.require(
+                        indexed_piece_deals.insert(*deal_id),
+                        format!("piece deal {deal_id} indexed more than once"),
+                    );
+                    if let Some(stats) = proposal_stats.get(deal_id) {
+                        acc.require(
+                            stats.piece_cid == Some(piece_cid),
+                            format!(
+                                "piece deal {deal_id} piece {piece_cid} does not match proposal piece {:?}",
+                                stats.piece_cid
+                            ),
+                        );
+                    } else {
+                        acc.add(format!("piece deal {deal_id} not found in proposals"));
+                    }
+                }
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating piece deals");
+        }
+        Err(e) => acc.add(format!("error loading piece deals: {e}")),
+    };
+
+    // Label->deal mapping
+    // When the index is present, every proposal with a non-empty label is indexed under its
+    // label key, and nothing else is.
+    if let Some(label_deals_root) = &state.label_deals {
+        let mut indexed_label_deals = BTreeSet::<DealID>::new();
+        match LabelDealsMultimap::load(store, label_deals_root, LABEL_DEALS_CONFIG, "label deals") {
+            Ok(label_deals) => {
+                let ret = label_deals.for_each(|label_key, _| {
+                    let mut count = 0;
+                    let ret = label_deals.for_each_in(&label_key, |deal_id| {
+                        count += 1;
+                        acc.require(
+                            indexed_label_deals.insert(deal_id),
+                            format!("label deal {deal_id} indexed more than once"),
+                        );
+                        if let Some(stats) = proposal_stats.get(&deal_id) {
+                            acc.require(
+                                stats.label_key.as_ref() == Some(&label_key),
+                                format!("label deal {deal_id} key does not match proposal label"),
+                            );
+                        } else {
+                            acc.add(format!("label deal {deal_id} not found in proposals"));
+                        }
+                        Ok(())
+                    });
+                    acc.require_no_error(ret, "error iterating label deal set");
+                    acc.require(count > 0, format!("empty label deals for {label_key:?}"));
+                    Ok(())
+                });
+                acc.require_no_error(ret, "error iterating label deals");
+            }
+            Err(e) => acc.add(format!("error loading label deals: {e}")),
+        };
+        for (id, stats) in proposal_stats.iter() {
+            if stats.label_key.is_some() {
+                acc.require(
+                    indexed_label_deals.contains(id),
+                    format!("deal {id} not found in label deals"),
+                );
+            }
+        }
+    }
+
+    // Deal acceptance policies
+    match state.load_deal_acceptance_policies(store) {
+        Ok(policies) => {
+            if state.deal_acceptance_policies.is_some() {
+                acc.require(!policies.is_empty(), "empty deal acceptance policies not absent");
+            }
+            let ret = policies.for_each(|provider, policy| {
+                acc.require(
+                    !policy.min_price_per_epoch.is_negative(),
+                    format!("provider {provider} policy has negative minimum price"),
+                );
+                if let Some(root) = &policy.allowed_clients {
+                    match state.load_allowed_clients(store, root) {
+                        Ok(allowed) => {
+                            let ret = allowed.collect_keys().map(|clients| {
+                                acc.require(
+                                    !clients.is_empty(),
+                                    format!("provider {provider} policy allowed clients empty"),
+                                );
+                                acc.require(
+                                    clients.len() <= DEAL_ACCEPTANCE_MAX_ALLOWED_CLIENTS,
+                                    format!("provider {provider} policy allows too many clients"),
+                                );
+                            });
+                            acc.require_no_error(ret, "error iterating allowed clients");
+                        }
+                        Err(e) => acc.add(format!(
+                            "error loading provider {provider} policy allowed clients: {e}"
+                        )),
+                    }
+                }
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating deal acceptance policies");
+        }
+        Err(e) => acc.add(format!("error loading deal acceptance policies: {e}")),
+    };
+
+    // Client withdrawal watches
+    match state.load_client_withdrawal_watches(store) {
+        Ok(watches) => {
+            if state.client_withdrawal_watches.is_some() {
+                acc.require(!watches.is_empty(), "empty client withdrawal watches not absent");
+            }
+            let ret = watches.for_each(|client, client_watches| {
+                acc.require(
+                    !client_watches.is_empty(),
+                    format!("client {client} has empty withdrawal watches"),
+                );
+                acc.require(
+                    client_watches.len() <= CLIENT_WITHDRAWAL_MAX_WATCHES,
+                    format!("client {client} has too many withdrawal watches"),
+                );
+                acc.require(
+                    client_watches.windows(2).all(|w| w[0].provider < w[1].provider),
+                    format!("client {client} withdrawal watches not strictly ascending"),
+                );
+                acc.require(
+                    client_watches.iter().all(|w| w.threshold.is_positive()),
+                    format!("client {client} withdrawal watch has non-positive threshold"),
+                );
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating client withdrawal watches");
+        }
+        Err(e) => acc.add(format!("error loading client withdrawal watches: {e}")),
+    };
+
+    // Token escrow and locked tables
+    match state.load_token_balances(store) {
+        Ok(token_balances) => {
+            if state.token_balances.is_some() {
+                acc.require(!token_balances.is_empty(), "empty token balances not absent");
+            }
+            let ret = token_balances.for_each(|token, tables| {
+                let escrow_table =
+                    BalanceTable::from_root(store, &tables.escrow_table, "token escrow table")?;
+                let lock_table =
+                    BalanceTable::from_root(store, &tables.locked_table, "token locked table")?;
+                lock_table.0.for_each(|address, locked_amount| {
+                    let escrow_amount = &escrow_table.get(&address)?;
+                    acc.require(escrow_amount >= locked_amount, format!("locked token {token} for {address}, {locked_amount}, greater than escrow amount, {escrow_amount}"));
+                    Ok(())
+                })
+            });
+            acc.require_no_error(ret, "error iterating token balances");
+        }
+        Err(e) => acc.add(format!("error loading token balances: {e}")),
+    };
+
+    // Provider->deal mapping
+    // Only proposals are indexed, each under its provider. Deals published before the index
+    // existed are not in it.
+    let mut indexed_provider_deals = BTreeSet::<DealID>::new();
+    match state.load_provider_deals(store) {
+        Ok(provider_deals) => {
+            if state.provider_deals.is_some() {
+                acc.require(!provider_deals.is_empty(), "empty provider deals not absent");
+            }
+            let ret = provider_deals.for_each(|provider: ActorID, _| {
+                provider_deals.for_each_in(&provider, |deal_id: DealID| {
+                    acc.require(
+                        indexed_provider_deals.insert(deal_id),
+                        format!("provider deal {deal_id} indexed more than once"),
+                    );
+                    if let Some(stats) = proposal_stats.get(&deal_id) {
+                        acc.require(
+                            stats.provider == Address::new_id(provider),
+                            format!(
+                                "provider deal {deal_id} provider {provider} does not match proposal provider {}",
+                                stats.provider
+                            ),
+                        );
+                    } else {
+                        acc.add(format!("provider deal {deal_id} not found in proposals"));
+                    }
+                    Ok(())
+                })
+            });
+            acc.require_no_error(ret, "error iterating provider deals");
+        }
+        Err(e) => acc.add(format!("error loading provider deals: {e}")),
+    };
+
+    // Provider->pending deal queue
+    // Every proposal not yet activated is queued under its provider and start epoch,
+    // and nothing else is.
+    let mut queued_deals = BTreeSet::<DealID>::new();
+    match state.load_provider_pending_deals(store) {
+        Ok(pending_deals) => {
+            let ret = pending_deals.for_each(|provider, root| {
+                let queue = PendingDealQueue::load(root, store)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load pending deals")?;
+                acc.require(queue.count() > 0, format!("provider {provider} has empty pending deals"));
+                queue
+                    .for_each(|start_epoch, deal_ids| {
+                        acc.require(
+                            !deal_ids.is_empty(),
+                            format!("provider {provider} has empty pending deals at {start_epoch}"),
+                        );
+                        for deal_id in deal_ids {
+                            acc.require(
+                                queued_deals.insert(*deal_id),
+                                format!("pending deal {deal_id} queued more than once"),
+                            );
+                            let Some(stats) = proposal_stats.get(deal_id) else {
+                                acc.add(format!("pending deal {deal_id} not found in proposals"));
+                                continue;
+                            };
+                            acc.require(
+                                stats.provider == Address::new_id(provider)
+                                    && stats.start_epoch == start_epoch as ChainEpoch,
+                                format!(
+                                    "pending deal {deal_id} queued for provider {provider} at {start_epoch} does not match proposal"
+                                ),
+                            );
+                            acc.require(
+                                stats.sector_start_epoch == EPOCH_UNDEFINED,
+                                format!("pending deal {deal_id} has been activated"),
+                            );
+                        }
+                        Ok(())
+                    })
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate pending deals")
+            });
+            acc.require_no_error(ret, "error iterating provider pending deals");
+        }
+        Err(e) => acc.add(format!("error loading provider pending deals: {e}")),
+    };
+    for (id, stats) in &proposal_stats {
+        if stats.sector_start_epoch == EPOCH_UNDEFINED {
+            acc.require(
+                queued_deals.contains(id),
+                format!("unactivated deal {id} not found in provider pending deals"),
+            );
+        }
+    }
+
     // pending proposals
     let mut pending_proposal_count = 0;
     match PendingProposalsSet::load(
@@ -344,14 +609,22 @@ pub fn check_state_invariants<BS: Blockstore>(
                 + &state.total_client_storage_fee;
             acc.require(locked_total == expected_lock_total, format!("locked total, {locked_total}, does not sum to provider locked, {}, client locked, {}, and client storage fee, {}", state.total_provider_locked_collateral, state.total_client_locked_collateral, state.total_client_storage_fee));
 
-            // assert escrow <= actor balance
+            // assert escrow + collateral pool <= actor balance
             // lock_table item <= escrow item and escrow_total <= balance implies lock_table total <= balance
+            let pool_total = state.load_collateral_pool(store).and_then(|pool| {
+                if state.collateral_pool.is_some() {
+                    acc.require(!pool.0.is_empty(), "empty collateral pool not absent");
+                }
+                pool.total()
+            });
+            acc.require_no_error(pool_total.as_ref(), "error calculating collateral pool total");
+            let pool_total = pool_total.unwrap_or_default();
             match escrow_table.total() {
                 Ok(escrow_total) => {
                     acc.require(
-                        &escrow_total <= balance,
+                        &escrow_total + &pool_total <= *balance,
                         format!(
-                            "escrow total, {escrow_total}, greater than actor balance, {balance}"
+                            "escrow total, {escrow_total}, plus collateral pool total, {pool_total}, greater than actor balance, {balance}"
                         ),
                     );
                     acc.require(escrow_total >= total_proposal_collateral, format!("escrow total, {escrow_total}, less than sum of proposal collateral, {total_proposal_collateral}"));
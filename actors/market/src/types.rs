@@ -12,6 +12,7 @@ use fvm_shared::ActorID;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::{BigInt, bigint_ser};
 use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::signature::Signature;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::piece::PaddedPieceSize;
@@ -19,10 +20,16 @@ use fvm_shared::piece::PaddedPieceSize;
 use crate::Label;
 use fvm_shared::sector::{RegisteredSealProof, SectorNumber};
 
-use super::deal::{ClientDealProposal, DealProposal, DealState};
+use super::deal::{
+    ClientDealCancellation, ClientDealProposal, DealAcceptancePolicy, DealProposal, DealState,
+};
 
 pub const PROPOSALS_AMT_BITWIDTH: u32 = 5;
 pub const STATES_AMT_BITWIDTH: u32 = 6;
+pub const PENDING_DEAL_QUEUE_AMT_BITWIDTH: u32 = 5;
+
+pub const SIGNATURE_DOMAIN_SEPARATION_CANCEL_DEALS: &[u8] = b"fil_canceldeals:";
+pub const SIGNATURE_DOMAIN_SEPARATION_MUTUALLY_CANCEL_DEALS: &[u8] = b"fil_mutuallycanceldeals:";
 
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
@@ -34,6 +41,10 @@ pub struct AddBalanceParams {
 pub struct WithdrawBalanceParams {
     pub provider_or_client: Address,
     pub amount: TokenAmount,
+    /// Address to receive the withdrawn funds in place of the escrow owner.
+    /// Only the escrow owner (the client, or a provider's owner) may nominate a beneficiary.
+    #[serde(default)]
+    pub beneficiary: Option<Address>,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
@@ -42,6 +53,84 @@ pub struct WithdrawBalanceReturn {
     pub amount_withdrawn: TokenAmount,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct WithdrawTokenBalanceParams {
+    /// The FRC-46 token to withdraw.
+    pub token: Address,
+    pub provider_or_client: Address,
+    pub amount: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct DepositCollateralPoolParams {
+    pub provider: Address,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct WithdrawCollateralPoolParams {
+    pub provider: Address,
+    pub amount: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct WithdrawCollateralPoolReturn {
+    pub amount_withdrawn: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct SetDealAcceptancePolicyParams {
+    pub provider: Address,
+    /// The new policy, or `None` to remove the provider's policy.
+    pub policy: Option<DealAcceptancePolicyParams>,
+}
+
+/// Terms of a deal acceptance policy as set by a provider.
+/// The allowed clients are stored as a set, and published in the policy by its root.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Default, Eq, PartialEq)]
+pub struct DealAcceptancePolicyParams {
+    pub min_price_per_epoch: TokenAmount,
+    pub verified_only: bool,
+    pub max_piece_size: Option<PaddedPieceSize>,
+    /// IDs of the clients whose deals are accepted, in any order.
+    /// If empty, deals from any client are accepted.
+    pub allowed_clients: Vec<ActorID>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetDealAcceptancePolicyParams {
+    pub provider: Address,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetDealAcceptancePolicyReturn {
+    /// The provider's policy, or `None` if it has not published one.
+    pub policy: Option<DealAcceptancePolicy>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct SetClientWithdrawalWatchParams {
+    pub provider: Address,
+    pub client: Address,
+    /// A deal between the provider and the client, which must not yet have been cleaned up.
+    /// Required to set a watch, and ignored when stopping one.
+    pub deal_id: DealID,
+    /// The escrow the client must retain in excess of its locked funds, or `None` to stop
+    /// watching the client.
+    pub threshold: Option<TokenAmount>,
+}
+
+/// A provider's request to be notified when a client's withdrawals leave less than a threshold
+/// of its escrow unlocked.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ClientWithdrawalWatch {
+    pub provider: ActorID,
+    pub threshold: TokenAmount,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
 pub struct GetBalanceParams {
@@ -54,6 +143,13 @@ pub struct GetBalanceReturn {
     pub locked: TokenAmount,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetTokenBalanceParams {
+    /// The FRC-46 token of which to get the balance.
+    pub token: Address,
+    pub account: Address,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq)] // Add Eq when BitField does
 pub struct OnMinerSectorsTerminateParams {
     pub epoch: ChainEpoch,
@@ -71,6 +167,45 @@ pub struct PublishStorageDealsReturn {
     pub valid_deals: BitField,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq)] // Add Eq when BitField does
+pub struct PublishStorageDeals2Return {
+    pub ids: Vec<DealID>,
+    pub valid_deals: BitField,
+    /// Verified allocation ID for each published deal, corresponding to `ids`.
+    /// NO_ALLOCATION_ID for deals which are not verified.
+    pub allocation_ids: Vec<AllocationID>,
+    /// Result of allocating datacap for each verified deal in the parameters, in order.
+    /// A verified deal is published only if its allocation succeeded.
+    pub allocation_results: BatchReturn,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CancelStorageDealsParams {
+    pub cancellations: Vec<ClientDealCancellation>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct CancelStorageDealsReturn {
+    /// Indicators of success or failure for each cancellation.
+    pub results: BatchReturn,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct MutuallyCancelDealsParams {
+    pub deal_ids: Vec<DealID>,
+    /// The client's signature over the CBOR-encoded `deal_ids`, prefixed with
+    /// SIGNATURE_DOMAIN_SEPARATION_MUTUALLY_CANCEL_DEALS.
+    pub client_signature: Signature,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct MutuallyCancelDealsReturn {
+    /// Indicators of success or failure for each deal.
+    pub results: BatchReturn,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 pub struct VerifyDealsForActivationParams {
     /// Deals to verify, grouped by sector.
@@ -143,6 +278,9 @@ pub type DealArray<'bs, BS> = Array<'bs, DealProposal, BS>;
 /// A specialization of a array to deals.
 pub type DealMetaArray<'bs, BS> = Array<'bs, DealState, BS>;
 
+/// A provider's deals awaiting activation, as arrays of deal IDs indexed by start epoch.
+pub type PendingDealQueue<'bs, BS> = Array<'bs, Vec<DealID>, BS>;
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 pub struct SectorDataSpec {
     pub deal_ids: Vec<DealID>,
@@ -248,6 +386,140 @@ pub struct GetDealSectorReturn {
     pub sector: SectorNumber,
 }
 
+pub type GetDealSectorLocationParams = DealQueryParams;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetDealSectorLocationReturn {
+    /// Sector number with the provider that has committed the deal.
+    pub sector: SectorNumber,
+    /// The provider (miner) holding the sector.
+    pub provider: ActorID,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct BatchDealQueryParams {
+    pub ids: Vec<DealID>,
+}
+
+pub type BatchGetDealActivationParams = BatchDealQueryParams;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct BatchGetDealActivationReturn {
+    /// Status of each deal queried.
+    pub results: BatchReturn,
+    /// Activation state for the deals that were successfully queried.
+    pub activations: Vec<GetDealActivationReturn>,
+}
+
+pub type BatchGetDealTermParams = BatchDealQueryParams;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct BatchGetDealTermReturn {
+    /// Status of each deal queried.
+    pub results: BatchReturn,
+    /// Terms for the deals that were successfully queried.
+    pub terms: Vec<GetDealTermReturn>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct DealParties {
+    pub client: ActorID,
+    pub provider: ActorID,
+}
+
+pub type BatchGetDealPartiesParams = BatchDealQueryParams;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct BatchGetDealPartiesReturn {
+    /// Status of each deal queried.
+    pub results: BatchReturn,
+    /// Client and provider for the deals that were successfully queried.
+    pub parties: Vec<DealParties>,
+}
+
+pub type BatchGetDealVerifiedParams = BatchDealQueryParams;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct BatchGetDealVerifiedReturn {
+    /// Status of each deal queried.
+    pub results: BatchReturn,
+    /// Verified flag for the deals that were successfully queried.
+    pub verified: Vec<bool>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetDealsForPieceParams {
+    pub piece_cid: Cid,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetDealsForPieceReturn {
+    /// IDs of the deal proposals for the piece, in publication order.
+    pub deal_ids: Vec<DealID>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetDealsForLabelParams {
+    pub label: Label,
+    /// Deal from which to continue listing, as returned by a previous call.
+    /// If absent, listing starts from the beginning.
+    pub cursor: Option<DealID>,
+    /// Maximum number of deal IDs to return. Must be positive.
+    pub limit: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetDealsForLabelReturn {
+    pub deal_ids: Vec<DealID>,
+    /// Cursor from which to continue listing, absent if there are no more deals.
+    pub next_cursor: Option<DealID>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListProviderDealsParams {
+    pub provider: ActorID,
+    /// Deal from which to continue listing, as returned by a previous call.
+    /// If absent, listing starts from the beginning.
+    pub cursor: Option<DealID>,
+    /// Maximum number of deal IDs to return. Must be positive.
+    pub limit: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListProviderDealsReturn {
+    pub deal_ids: Vec<DealID>,
+    /// Cursor from which to continue listing, absent if there are no more deals.
+    pub next_cursor: Option<DealID>,
+}
+
+/// A published deal awaiting activation, identified by its position in the provider's queue.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PendingDeal {
+    pub start_epoch: ChainEpoch,
+    pub deal_id: DealID,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListProviderPendingDealsParams {
+    pub provider: ActorID,
+    /// Position from which to continue listing, as returned by a previous call.
+    /// If absent, listing starts from the earliest start epoch.
+    pub cursor: Option<PendingDeal>,
+    /// Maximum number of deals to return. Must be positive.
+    pub limit: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListProviderPendingDealsReturn {
+    /// Deals ordered by start epoch, then by ID.
+    pub deals: Vec<PendingDeal>,
+    /// Cursor from which to continue listing, absent if there are no more deals.
+    pub next_cursor: Option<PendingDeal>,
+}
+
 // Interface market clients can implement to receive notifications from builtin market
 pub const MARKET_NOTIFY_DEAL_METHOD: u64 = frc42_dispatch::method_hash!("MarketNotifyDeal");
 
@@ -272,9 +544,22 @@ pub struct SettleDealPaymentsReturn {
     pub settlements: Vec<DealSettlementSummary>,
 }
 
+pub type SettleDealPaymentsPreviewParams = SettleDealPaymentsParams;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct SettleDealPaymentsPreviewReturn {
+    /// Indicators of success or failure for each deal
+    pub results: BatchReturn,
+    /// Projected results for the deals that would successfully settle
+    pub settlements: Vec<DealSettlementSummary>,
+    /// Sum of the projected payments
+    pub total_payment: TokenAmount,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 pub struct DealSettlementSummary {
-    /// Incremental amount of funds transferred from client to provider for deal payment
+    /// Incremental amount of funds transferred from client to provider for deal payment,
+    /// denominated in the deal's payment token if it has one
     pub payment: TokenAmount,
     /// Whether the deal has settled for the final time
     pub completed: bool,
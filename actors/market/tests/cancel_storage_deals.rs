@@ -0,0 +1,245 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::bigint::BigInt;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::signature::Signature;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
+use num_traits::Zero;
+
+use fil_actor_market::ext::account::{AUTHENTICATE_MESSAGE_METHOD, AuthenticateMessageParams};
+use fil_actor_market::ext::verifreg::{
+    RELEASE_ALLOCATIONS_METHOD, ReleaseAllocationsParams, ReleaseAllocationsReturn,
+};
+use fil_actor_market::{
+    Actor as MarketActor, CancelStorageDealsParams, CancelStorageDealsReturn,
+    ClientDealCancellation, DealCancellation, DealProposal, Method,
+    SIGNATURE_DOMAIN_SEPARATION_CANCEL_DEALS, deal_cid,
+};
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{BatchReturn, VERIFIED_REGISTRY_ACTOR_ADDR};
+use harness::*;
+
+mod harness;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+
+#[test]
+fn cancellation_unlocks_funds_without_penalty() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (deal_id, deal) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+
+    let client_before = get_balance(&rt, &CLIENT_ADDR);
+    let provider_before = get_balance(&rt, &addrs.provider);
+    assert_eq!(deal.client_balance_requirement(), client_before.locked);
+    assert_eq!(deal.provider_collateral, provider_before.locked);
+
+    let cancellation = signed_cancellation(&rt, deal_id, &deal);
+    expect_authenticate(&rt, deal.client, &cancellation, true);
+    expect_emitted(&rt, "deal-cancelled", deal_id, CLIENT_ID, PROVIDER_ID);
+    let ret = cancel_storage_deals(&rt, vec![cancellation]);
+    assert_eq!(vec![ExitCode::OK], ret.results.codes());
+
+    // Both parties retain their full balances, which are now unlocked.
+    let client_after = get_balance(&rt, &CLIENT_ADDR);
+    let provider_after = get_balance(&rt, &addrs.provider);
+    assert_eq!(client_before.balance, client_after.balance);
+    assert!(client_after.locked.is_zero());
+    assert_eq!(provider_before.balance, provider_after.balance);
+    assert!(provider_after.locked.is_zero());
+    assert_deal_deleted(&rt, deal_id, &deal, 0, true);
+    check_state(&rt);
+
+    // Cron removes the cancelled deal's scheduled op without slashing.
+    rt.set_epoch(process_epoch(START_EPOCH, deal_id));
+    cron_tick(&rt);
+    assert_deal_ops_clean(&rt);
+    assert_eq!(client_after, get_balance(&rt, &CLIENT_ADDR));
+    assert_eq!(provider_after, get_balance(&rt, &addrs.provider));
+    check_state(&rt);
+}
+
+#[test]
+fn cancellation_releases_pending_allocation() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal_id =
+        generate_and_publish_verified_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH, 1);
+    let deal = get_deal_proposal(&rt, deal_id);
+    let allocation_id = get_pending_deal_allocation(&rt, deal_id);
+
+    let cancellation = signed_cancellation(&rt, deal_id, &deal);
+    expect_authenticate(&rt, deal.client, &cancellation, true);
+    rt.expect_send_simple(
+        VERIFIED_REGISTRY_ACTOR_ADDR,
+        RELEASE_ALLOCATIONS_METHOD,
+        IpldBlock::serialize_cbor(&ReleaseAllocationsParams {
+            client: CLIENT_ID,
+            allocation_ids: vec![allocation_id],
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&ReleaseAllocationsReturn {
+            results: BatchReturn::ok(1),
+            datacap_recovered: BigInt::from(deal.piece_size.0),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    expect_emitted(&rt, "deal-cancelled", deal_id, CLIENT_ID, PROVIDER_ID);
+    let ret = cancel_storage_deals(&rt, vec![cancellation]);
+    assert_eq!(vec![ExitCode::OK], ret.results.codes());
+
+    assert_deal_deleted(&rt, deal_id, &deal, 0, true);
+    // State invariants require no pending allocation for a deal without a proposal.
+    check_state(&rt);
+}
+
+#[test]
+fn invalid_cancellations_fail_independently() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (id1, deal1) = generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+    let (id2, deal2) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH + 1);
+    let (id3, deal3) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH + 2);
+
+    let valid = signed_cancellation(&rt, id1, &deal1);
+    let duplicate = valid.clone();
+    let bad_signature = signed_cancellation(&rt, id2, &deal2);
+    let wrong_proposal = ClientDealCancellation {
+        cancellation: DealCancellation { deal_id: id3, proposal: deal_cid(&rt, &deal1).unwrap() },
+        client_signature: Signature::new_bls("does not matter".as_bytes().to_vec()),
+    };
+    let unknown = ClientDealCancellation {
+        cancellation: DealCancellation {
+            deal_id: id3 + 1,
+            proposal: deal_cid(&rt, &deal3).unwrap(),
+        },
+        client_signature: Signature::new_bls("does not matter".as_bytes().to_vec()),
+    };
+
+    expect_authenticate(&rt, deal1.client, &valid, true);
+    expect_authenticate(&rt, deal2.client, &bad_signature, false);
+    expect_emitted(&rt, "deal-cancelled", id1, CLIENT_ID, PROVIDER_ID);
+    let ret =
+        cancel_storage_deals(&rt, vec![valid, duplicate, bad_signature, wrong_proposal, unknown]);
+    assert_eq!(
+        vec![
+            ExitCode::OK,
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            ExitCode::USR_NOT_FOUND,
+        ],
+        ret.results.codes()
+    );
+
+    assert!(find_deal_proposal(&rt, id1).is_none());
+    assert_eq!(deal2, get_deal_proposal(&rt, id2));
+    assert_eq!(deal3, get_deal_proposal(&rt, id3));
+    let client = get_balance(&rt, &CLIENT_ADDR);
+    assert_eq!(
+        deal2.client_balance_requirement() + deal3.client_balance_requirement(),
+        client.locked
+    );
+    check_state(&rt);
+}
+
+#[test]
+fn cannot_cancel_activated_or_started_deal() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (activated_id, activated) = publish_and_activate_deal(
+        &rt,
+        CLIENT_ADDR,
+        &addrs,
+        1,
+        START_EPOCH,
+        END_EPOCH,
+        0,
+        END_EPOCH,
+    );
+    let (started_id, started) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH + 1);
+
+    // The activated deal cannot be cancelled even before its start epoch.
+    let ret = cancel_storage_deals(&rt, vec![signed_cancellation(&rt, activated_id, &activated)]);
+    assert_eq!(vec![ExitCode::USR_FORBIDDEN], ret.results.codes());
+
+    // Once the start epoch is reached, the un-activated deal is subject to timeout instead.
+    rt.set_epoch(START_EPOCH);
+    let ret = cancel_storage_deals(&rt, vec![signed_cancellation(&rt, started_id, &started)]);
+    assert_eq!(vec![ExitCode::USR_FORBIDDEN], ret.results.codes());
+
+    assert_eq!(activated, get_deal_proposal(&rt, activated_id));
+    assert_eq!(started, get_deal_proposal(&rt, started_id));
+    check_state(&rt);
+}
+
+fn signed_cancellation(
+    rt: &MockRuntime,
+    deal_id: DealID,
+    proposal: &DealProposal,
+) -> ClientDealCancellation {
+    ClientDealCancellation {
+        cancellation: DealCancellation { deal_id, proposal: deal_cid(rt, proposal).unwrap() },
+        client_signature: Signature::new_bls("does not matter".as_bytes().to_vec()),
+    }
+}
+
+fn expect_authenticate(
+    rt: &MockRuntime,
+    client: Address,
+    cancellation: &ClientDealCancellation,
+    valid: bool,
+) {
+    rt.expect_send(
+        client,
+        AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature: cancellation.client_signature.bytes.clone(),
+            message: [
+                SIGNATURE_DOMAIN_SEPARATION_CANCEL_DEALS,
+                &fvm_ipld_encoding::to_vec(&cancellation.cancellation).unwrap(),
+            ]
+            .concat(),
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+        IpldBlock::serialize_cbor(&valid).unwrap(),
+        ExitCode::OK,
+        None,
+    );
+}
+
+fn cancel_storage_deals(
+    rt: &MockRuntime,
+    cancellations: Vec<ClientDealCancellation>,
+) -> CancelStorageDealsReturn {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    rt.expect_validate_caller_any();
+    let ret: CancelStorageDealsReturn = rt
+        .call::<MarketActor>(
+            Method::CancelStorageDealsExported as u64,
+            IpldBlock::serialize_cbor(&CancelStorageDealsParams { cancellations }).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
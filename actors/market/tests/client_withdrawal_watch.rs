@@ -0,0 +1,175 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actor_market::{
+    Actor as MarketActor, ClientWithdrawalWatch, Method, SetClientWithdrawalWatchParams, State,
+};
+use fil_actors_runtime::EventBuilder;
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+
+use harness::*;
+
+mod harness;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+
+#[test]
+fn set_and_remove_watch() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal_id = publish_client_deal(&rt, &addrs);
+    assert!(get_watches(&rt).is_empty());
+
+    set_watch(&rt, &addrs, deal_id, Some(TokenAmount::from_atto(100)));
+    set_watch(&rt, &addrs, deal_id, Some(TokenAmount::from_atto(300)));
+    assert_eq!(
+        vec![ClientWithdrawalWatch {
+            provider: PROVIDER_ID,
+            threshold: TokenAmount::from_atto(300)
+        }],
+        get_watches(&rt)
+    );
+    check_state(&rt);
+
+    set_watch(&rt, &addrs, deal_id, None);
+    assert!(get_watches(&rt).is_empty());
+    // Removing an absent watch has no effect.
+    set_watch(&rt, &addrs, deal_id, None);
+    check_state(&rt);
+}
+
+#[test]
+fn set_rejects_invalid_watch() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal_id = publish_client_deal(&rt, &addrs);
+    let threshold = Some(TokenAmount::from_atto(100));
+
+    // Only the provider's owner or worker may watch on its behalf.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        call_set_watch(&rt, &addrs, CLIENT_ADDR, deal_id, threshold.clone()),
+    );
+    rt.verify();
+
+    for threshold in [TokenAmount::from_atto(0), TokenAmount::from_atto(-1)] {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+        expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+        rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            call_set_watch(&rt, &addrs, CLIENT_ADDR, deal_id, Some(threshold)),
+        );
+        rt.verify();
+    }
+
+    // The watch must be justified by a deal between the provider and the client.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    expect_abort(
+        ExitCode::USR_NOT_FOUND,
+        call_set_watch(&rt, &addrs, CLIENT_ADDR, deal_id + 1, threshold.clone()),
+    );
+    rt.verify();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        call_set_watch(&rt, &addrs, OWNER_ADDR, deal_id, threshold),
+    );
+    rt.verify();
+    assert!(get_watches(&rt).is_empty());
+}
+
+#[test]
+fn withdrawal_below_threshold_notifies_provider() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal_id = publish_client_deal(&rt, &addrs);
+    add_participant_funds(&rt, CLIENT_ADDR, TokenAmount::from_atto(500));
+
+    let threshold = TokenAmount::from_atto(300);
+    set_watch(&rt, &addrs, deal_id, Some(threshold.clone()));
+
+    // A withdrawal leaving at least the threshold unlocked is not reported.
+    let amount = TokenAmount::from_atto(100);
+    withdraw_client_balance(&rt, amount.clone(), amount, CLIENT_ADDR);
+
+    // The provider requires the client's locked funds plus the threshold.
+    let required = get_balance(&rt, &CLIENT_ADDR).locked + &threshold;
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("client-balance-below-threshold")
+            .field_indexed("client", &CLIENT_ID)
+            .field_indexed("provider", &PROVIDER_ID)
+            .field_bigint("required", required.atto())
+            .field_bigint("threshold", threshold.atto())
+            .build()
+            .unwrap(),
+    );
+    let amount = TokenAmount::from_atto(200);
+    withdraw_client_balance(&rt, amount.clone(), amount, CLIENT_ADDR);
+
+    // Once the provider stops watching, withdrawals are not reported.
+    set_watch(&rt, &addrs, deal_id, None);
+    let amount = TokenAmount::from_atto(200);
+    withdraw_client_balance(&rt, amount.clone(), amount, CLIENT_ADDR);
+    check_state(&rt);
+}
+
+fn publish_client_deal(rt: &MockRuntime, addrs: &MinerAddresses) -> DealID {
+    let deal = generate_deal_and_add_funds(rt, CLIENT_ADDR, addrs, START_EPOCH, END_EPOCH);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    publish_deals(rt, addrs, &[deal], TokenAmount::zero(), 1)[0]
+}
+
+fn call_set_watch(
+    rt: &MockRuntime,
+    addrs: &MinerAddresses,
+    client: Address,
+    deal_id: DealID,
+    threshold: Option<TokenAmount>,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.call::<MarketActor>(
+        Method::SetClientWithdrawalWatchExported as u64,
+        IpldBlock::serialize_cbor(&SetClientWithdrawalWatchParams {
+            provider: addrs.provider,
+            client,
+            deal_id,
+            threshold,
+        })
+        .unwrap(),
+    )
+}
+
+fn set_watch(
+    rt: &MockRuntime,
+    addrs: &MinerAddresses,
+    deal_id: DealID,
+    threshold: Option<TokenAmount>,
+) {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    expect_provider_control_address(rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    assert!(call_set_watch(rt, addrs, CLIENT_ADDR, deal_id, threshold).unwrap().is_none());
+    rt.verify();
+}
+
+fn get_watches(rt: &MockRuntime) -> Vec<ClientWithdrawalWatch> {
+    let st: State = rt.get_state();
+    st.get_client_withdrawal_watches(&rt.store, CLIENT_ID).unwrap()
+}
@@ -0,0 +1,197 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actor_market::{
+    Actor as MarketActor, DepositCollateralPoolParams, Method, State, WithdrawCollateralPoolParams,
+    WithdrawCollateralPoolReturn,
+};
+use fil_actors_runtime::EventBuilder;
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::METHOD_SEND;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use num_traits::Zero;
+
+use fil_actors_runtime::runtime::Runtime;
+use harness::*;
+
+mod harness;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+
+#[test]
+fn deposit_and_withdraw() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+
+    deposit_collateral_pool(&rt, &addrs, TokenAmount::from_atto(100));
+    deposit_collateral_pool(&rt, &addrs, TokenAmount::from_atto(20));
+    assert_eq!(TokenAmount::from_atto(120), get_pool_balance(&rt, PROVIDER_ADDR));
+    // Pooled funds are not part of the provider's escrow.
+    assert!(get_balance(&rt, &PROVIDER_ADDR).balance.is_zero());
+    check_state(&rt);
+
+    // Withdrawing more than the pool holds yields the whole pool balance.
+    let withdrawn = withdraw_collateral_pool(&rt, &addrs, TokenAmount::from_atto(50));
+    assert_eq!(TokenAmount::from_atto(50), withdrawn);
+    let withdrawn = withdraw_collateral_pool(&rt, &addrs, TokenAmount::from_atto(500));
+    assert_eq!(TokenAmount::from_atto(70), withdrawn);
+    assert!(get_pool_balance(&rt, PROVIDER_ADDR).is_zero());
+    check_state(&rt);
+}
+
+#[test]
+fn deposit_requires_storage_provider() {
+    let rt = setup();
+    rt.set_received(TokenAmount::from_atto(100));
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    rt.expect_validate_caller_any();
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<MarketActor>(
+            Method::DepositCollateralPoolExported as u64,
+            IpldBlock::serialize_cbor(&DepositCollateralPoolParams { provider: CLIENT_ADDR })
+                .unwrap(),
+        ),
+    );
+    rt.verify();
+    check_state(&rt);
+}
+
+#[test]
+fn withdraw_requires_provider_control_address() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    deposit_collateral_pool(&rt, &addrs, TokenAmount::from_atto(100));
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<MarketActor>(
+            Method::WithdrawCollateralPoolExported as u64,
+            IpldBlock::serialize_cbor(&WithdrawCollateralPoolParams {
+                provider: addrs.provider,
+                amount: TokenAmount::from_atto(100),
+            })
+            .unwrap(),
+        ),
+    );
+    rt.verify();
+    assert_eq!(TokenAmount::from_atto(100), get_pool_balance(&rt, PROVIDER_ADDR));
+    check_state(&rt);
+}
+
+#[test]
+fn publish_tops_up_provider_escrow_from_pool() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal1 = generate_deal_proposal(CLIENT_ADDR, addrs.provider, START_EPOCH, END_EPOCH);
+    let deal2 = generate_deal_proposal(CLIENT_ADDR, addrs.provider, START_EPOCH, END_EPOCH + 1);
+    let total_collateral = &deal1.provider_collateral + &deal2.provider_collateral;
+
+    // Escrow covers all but 5 of the collateral for the two deals.
+    let shortfall = TokenAmount::from_atto(5);
+    add_provider_funds(&rt, &total_collateral - &shortfall, &addrs);
+    add_participant_funds(
+        &rt,
+        CLIENT_ADDR,
+        deal1.client_balance_requirement() + deal2.client_balance_requirement(),
+    );
+    deposit_collateral_pool(&rt, &addrs, TokenAmount::from_atto(7));
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    expect_collateral_event(&rt, "collateral-top-up", &shortfall);
+    publish_deals(&rt, &addrs, &[deal1, deal2], TokenAmount::zero(), 1);
+
+    // Only the shortfall is moved into escrow, where it is locked.
+    let provider = get_balance(&rt, &PROVIDER_ADDR);
+    assert_eq!(total_collateral, provider.balance);
+    assert_eq!(total_collateral, provider.locked);
+    assert_eq!(TokenAmount::from_atto(2), get_pool_balance(&rt, PROVIDER_ADDR));
+    check_state(&rt);
+}
+
+#[test]
+fn publish_drops_deal_pool_cannot_cover() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal = generate_deal_proposal(CLIENT_ADDR, addrs.provider, START_EPOCH, END_EPOCH);
+    add_provider_funds(&rt, &deal.provider_collateral - TokenAmount::from_atto(5), &addrs);
+    add_participant_funds(&rt, CLIENT_ADDR, deal.client_balance_requirement());
+    deposit_collateral_pool(&rt, &addrs, TokenAmount::from_atto(4));
+
+    publish_deals_expect_abort(&rt, &addrs, deal, ExitCode::USR_ILLEGAL_ARGUMENT);
+
+    assert_eq!(TokenAmount::from_atto(4), get_pool_balance(&rt, PROVIDER_ADDR));
+    assert!(get_balance(&rt, &PROVIDER_ADDR).locked.is_zero());
+    check_state(&rt);
+}
+
+fn deposit_collateral_pool(rt: &MockRuntime, addrs: &MinerAddresses, amount: TokenAmount) {
+    rt.set_received(amount.clone());
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.owner);
+    rt.expect_validate_caller_any();
+    expect_collateral_event(rt, "collateral-pool-deposit", &amount);
+    assert!(
+        rt.call::<MarketActor>(
+            Method::DepositCollateralPoolExported as u64,
+            IpldBlock::serialize_cbor(&DepositCollateralPoolParams { provider: addrs.provider })
+                .unwrap(),
+        )
+        .unwrap()
+        .is_none()
+    );
+    rt.verify();
+    rt.add_balance(amount);
+}
+
+fn withdraw_collateral_pool(
+    rt: &MockRuntime,
+    addrs: &MinerAddresses,
+    amount: TokenAmount,
+) -> TokenAmount {
+    let expected = std::cmp::min(amount.clone(), get_pool_balance(rt, addrs.provider));
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    expect_provider_control_address(rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_send_simple(addrs.owner, METHOD_SEND, None, expected.clone(), None, ExitCode::OK);
+    expect_collateral_event(rt, "collateral-pool-withdrawal", &expected);
+    let ret: WithdrawCollateralPoolReturn = rt
+        .call::<MarketActor>(
+            Method::WithdrawCollateralPoolExported as u64,
+            IpldBlock::serialize_cbor(&WithdrawCollateralPoolParams {
+                provider: addrs.provider,
+                amount,
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.amount_withdrawn
+}
+
+fn get_pool_balance(rt: &MockRuntime, provider: Address) -> TokenAmount {
+    let st: State = rt.get_state();
+    st.get_collateral_pool_balance(rt.store(), &provider).unwrap()
+}
+
+fn expect_collateral_event(rt: &MockRuntime, typ: &str, amount: &TokenAmount) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ(typ)
+            .field_indexed("provider", &PROVIDER_ID)
+            .field_bigint("amount", amount.atto())
+            .build()
+            .unwrap(),
+    );
+}
@@ -0,0 +1,200 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actor_market::{
+    Actor as MarketActor, DealAcceptancePolicy, DealAcceptancePolicyParams,
+    GetDealAcceptancePolicyParams, GetDealAcceptancePolicyReturn, Method,
+    SetDealAcceptancePolicyParams, State,
+};
+use fil_actors_runtime::EventBuilder;
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::test_utils::*;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
+
+use harness::*;
+
+mod harness;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+
+#[test]
+fn set_and_get_policy() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    assert_eq!(None, get_policy(&rt));
+
+    let params = DealAcceptancePolicyParams {
+        min_price_per_epoch: TokenAmount::from_atto(5),
+        verified_only: true,
+        max_piece_size: Some(PaddedPieceSize(1 << 20)),
+        allowed_clients: vec![CLIENT_ID + 1, CLIENT_ID, CLIENT_ID + 1],
+    };
+    set_policy(&rt, &addrs, Some(params.clone()));
+
+    // Allowed clients are published as the root of a set, without duplicates.
+    let policy = get_policy(&rt).unwrap();
+    assert_eq!(params.min_price_per_epoch, policy.min_price_per_epoch);
+    assert_eq!(params.verified_only, policy.verified_only);
+    assert_eq!(params.max_piece_size, policy.max_piece_size);
+    let mut allowed = rt
+        .get_state::<State>()
+        .load_allowed_clients(rt.store(), &policy.allowed_clients.unwrap())
+        .unwrap()
+        .collect_keys()
+        .unwrap();
+    allowed.sort();
+    assert_eq!(vec![CLIENT_ID, CLIENT_ID + 1], allowed);
+    check_state(&rt);
+
+    // The same clients in another order commit to the same set.
+    let reordered =
+        DealAcceptancePolicyParams { allowed_clients: vec![CLIENT_ID, CLIENT_ID + 1], ..params };
+    set_policy(&rt, &addrs, Some(reordered));
+    assert_eq!(Some(policy), get_policy(&rt));
+
+    // Without allowed clients, the policy accepts any client.
+    set_policy(&rt, &addrs, Some(DealAcceptancePolicyParams::default()));
+    assert_eq!(Some(DealAcceptancePolicy::default()), get_policy(&rt));
+    check_state(&rt);
+
+    set_policy(&rt, &addrs, None);
+    assert_eq!(None, get_policy(&rt));
+    check_state(&rt);
+}
+
+#[test]
+fn set_requires_provider_control_address() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        call_set_policy(&rt, &addrs, Some(DealAcceptancePolicyParams::default())),
+    );
+    rt.verify();
+    assert_eq!(None, get_policy(&rt));
+}
+
+#[test]
+fn set_rejects_invalid_policy() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+
+    let invalid = [
+        DealAcceptancePolicyParams {
+            min_price_per_epoch: TokenAmount::from_atto(-1),
+            ..Default::default()
+        },
+        DealAcceptancePolicyParams {
+            max_piece_size: Some(PaddedPieceSize(1000)),
+            ..Default::default()
+        },
+        DealAcceptancePolicyParams { allowed_clients: (0..257).collect(), ..Default::default() },
+    ];
+    for policy in invalid {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+        expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+        rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+        expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, call_set_policy(&rt, &addrs, Some(policy)));
+        rt.verify();
+    }
+    assert_eq!(None, get_policy(&rt));
+}
+
+#[test]
+fn publish_drops_deals_policy_rejects() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal = generate_deal_and_add_funds(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+
+    let rejecting = [
+        DealAcceptancePolicyParams {
+            min_price_per_epoch: &deal.storage_price_per_epoch + TokenAmount::from_atto(1),
+            ..Default::default()
+        },
+        DealAcceptancePolicyParams { verified_only: true, ..Default::default() },
+        DealAcceptancePolicyParams {
+            max_piece_size: Some(PaddedPieceSize(deal.piece_size.0 / 2)),
+            ..Default::default()
+        },
+        DealAcceptancePolicyParams { allowed_clients: vec![CLIENT_ID + 1], ..Default::default() },
+    ];
+    for policy in rejecting {
+        set_policy(&rt, &addrs, Some(policy));
+        publish_deals_expect_abort(&rt, &addrs, deal.clone(), ExitCode::USR_ILLEGAL_ARGUMENT);
+    }
+
+    // A policy the deal meets lets it through.
+    set_policy(
+        &rt,
+        &addrs,
+        Some(DealAcceptancePolicyParams {
+            min_price_per_epoch: deal.storage_price_per_epoch.clone(),
+            verified_only: false,
+            max_piece_size: Some(deal.piece_size),
+            allowed_clients: vec![CLIENT_ID],
+        }),
+    );
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    publish_deals(&rt, &addrs, &[deal], TokenAmount::zero(), 1);
+    check_state(&rt);
+}
+
+fn call_set_policy(
+    rt: &MockRuntime,
+    addrs: &MinerAddresses,
+    policy: Option<DealAcceptancePolicyParams>,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.call::<MarketActor>(
+        Method::SetDealAcceptancePolicyExported as u64,
+        IpldBlock::serialize_cbor(&SetDealAcceptancePolicyParams {
+            provider: addrs.provider,
+            policy,
+        })
+        .unwrap(),
+    )
+}
+
+fn set_policy(
+    rt: &MockRuntime,
+    addrs: &MinerAddresses,
+    policy: Option<DealAcceptancePolicyParams>,
+) {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    expect_provider_control_address(rt, addrs.provider, addrs.owner, addrs.worker);
+    rt.expect_validate_caller_addr(vec![addrs.owner, addrs.worker]);
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("deal-acceptance-policy-updated")
+            .field_indexed("provider", &PROVIDER_ID)
+            .build()
+            .unwrap(),
+    );
+    assert!(call_set_policy(rt, addrs, policy).unwrap().is_none());
+    rt.verify();
+}
+
+fn get_policy(rt: &MockRuntime) -> Option<DealAcceptancePolicy> {
+    rt.expect_validate_caller_any();
+    let ret: GetDealAcceptancePolicyReturn = rt
+        .call::<MarketActor>(
+            Method::GetDealAcceptancePolicyExported as u64,
+            IpldBlock::serialize_cbor(&GetDealAcceptancePolicyParams { provider: PROVIDER_ADDR })
+                .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.policy
+}
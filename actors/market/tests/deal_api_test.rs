@@ -1,3 +1,4 @@
+use cid::Cid;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
 use fvm_shared::econ::TokenAmount;
@@ -6,17 +7,21 @@ use num_traits::Zero;
 use serde::de::DeserializeOwned;
 
 use fil_actor_market::{
-    Actor as MarketActor, DealQueryParams, EX_DEAL_EXPIRED, EX_DEAL_NOT_ACTIVATED,
-    GetDealActivationReturn, GetDealClientCollateralReturn, GetDealClientReturn,
-    GetDealDataCommitmentReturn, GetDealLabelReturn, GetDealProviderCollateralReturn,
-    GetDealProviderReturn, GetDealSectorReturn, GetDealTermReturn, GetDealTotalPriceReturn,
-    GetDealVerifiedReturn, Method,
+    Actor as MarketActor, BatchDealQueryParams, BatchGetDealActivationReturn,
+    BatchGetDealPartiesReturn, BatchGetDealTermReturn, BatchGetDealVerifiedReturn, DealParties,
+    DealQueryParams, EX_DEAL_EXPIRED, EX_DEAL_NOT_ACTIVATED, GetDealActivationReturn,
+    GetDealClientCollateralReturn, GetDealClientReturn, GetDealDataCommitmentReturn,
+    GetDealLabelReturn, GetDealProviderCollateralReturn, GetDealProviderReturn,
+    GetDealSectorLocationReturn, GetDealSectorReturn, GetDealTermReturn, GetDealTotalPriceReturn,
+    GetDealVerifiedReturn, GetDealsForLabelParams, GetDealsForLabelReturn, GetDealsForPieceParams,
+    GetDealsForPieceReturn, Label, ListProviderDealsParams, ListProviderDealsReturn,
+    ListProviderPendingDealsParams, ListProviderPendingDealsReturn, Method, PendingDeal, State,
 };
-use fil_actors_runtime::ActorError;
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::test_utils::{
-    ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort, expect_abort_contains_message,
+    ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort, expect_abort_contains_message, make_piece_cid,
 };
+use fil_actors_runtime::{ActorError, BatchReturn};
 use harness::*;
 
 mod harness;
@@ -113,6 +118,7 @@ fn activation() {
     assert_eq!(EPOCH_UNDEFINED, activation.activated);
     assert_eq!(EPOCH_UNDEFINED, activation.terminated);
     query_deal_fails(&rt, Method::GetDealSectorExported, id, EX_DEAL_NOT_ACTIVATED);
+    query_deal_fails(&rt, Method::GetDealSectorLocationExported, id, EX_DEAL_NOT_ACTIVATED);
 
     // activate the deal
     let activate_epoch = start_epoch - 2;
@@ -127,6 +133,10 @@ fn activation() {
         GetDealSectorReturn { sector: sector_number },
         query_deal(&rt, Method::GetDealSectorExported, id)
     );
+    assert_eq!(
+        GetDealSectorLocationReturn { sector: sector_number, provider: PROVIDER_ID },
+        query_deal(&rt, Method::GetDealSectorLocationExported, id)
+    );
 
     // terminate early
     let terminate_epoch = activate_epoch + 100;
@@ -143,6 +153,473 @@ fn activation() {
     // Non-existent deal is USR_NOT_FOUND
     query_deal_fails(&rt, Method::GetDealActivationExported, id + 1, ExitCode::USR_NOT_FOUND);
     query_deal_fails(&rt, Method::GetDealSectorExported, id + 1, ExitCode::USR_NOT_FOUND);
+    query_deal_fails(&rt, Method::GetDealSectorLocationExported, id + 1, ExitCode::USR_NOT_FOUND);
+}
+
+#[test]
+fn deals_for_piece() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 180 * EPOCHS_IN_DAY;
+    let publish_epoch = ChainEpoch::from(1);
+
+    let rt = setup();
+    rt.set_epoch(publish_epoch);
+    let next_allocation_id = 1;
+
+    // Two deals for the same piece, distinguished by their end epoch.
+    let proposal1 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    let proposal2 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch + 1,
+    );
+    assert_eq!(proposal1.piece_cid, proposal2.piece_cid);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    let ids = publish_deals(
+        &rt,
+        &MinerAddresses::default(),
+        &[proposal1.clone(), proposal2],
+        TokenAmount::zero(),
+        next_allocation_id,
+    );
+
+    let found = query_deals_for_piece(&rt, proposal1.piece_cid);
+    assert_eq!(ids, found.deal_ids);
+
+    // A piece with no deals has an empty result.
+    let other = query_deals_for_piece(&rt, make_piece_cid("other".as_bytes()));
+    assert!(other.deal_ids.is_empty());
+
+    // Terminating one deal removes it from the piece's deals.
+    let activate_epoch = start_epoch - 2;
+    rt.set_epoch(activate_epoch);
+    activate_deals(&rt, end_epoch + 1, PROVIDER_ADDR, activate_epoch, 7, &ids[..1]);
+    activate_deals(&rt, end_epoch + 1, PROVIDER_ADDR, activate_epoch, 8, &ids[1..]);
+    rt.set_epoch(activate_epoch + 100);
+    terminate_deals(&rt, PROVIDER_ADDR, &[7], &ids[..1]);
+
+    let found = query_deals_for_piece(&rt, proposal1.piece_cid);
+    assert_eq!(ids[1..].to_vec(), found.deal_ids);
+    check_state(&rt);
+}
+
+#[test]
+fn deals_for_label() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 180 * EPOCHS_IN_DAY;
+    let publish_epoch = ChainEpoch::from(1);
+
+    let mut rt = setup();
+    rt.policy.market_index_deal_labels = true;
+    rt.set_epoch(publish_epoch);
+    let next_allocation_id = 1;
+
+    // Two deals share a label, and a third has a different one.
+    let proposal1 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    let proposal2 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch + 1,
+    );
+    let mut proposal3 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch + 2,
+    );
+    proposal3.label = Label::String("other".to_string());
+    assert_eq!(proposal1.label, proposal2.label);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    let ids = publish_deals(
+        &rt,
+        &MinerAddresses::default(),
+        &[proposal1.clone(), proposal2, proposal3.clone()],
+        TokenAmount::zero(),
+        next_allocation_id,
+    );
+
+    let found = all_deals_for_label(&rt, proposal1.label.clone());
+    assert_eq!(ids[..2].to_vec(), found);
+    let found = all_deals_for_label(&rt, proposal3.label);
+    assert_eq!(ids[2..].to_vec(), found);
+
+    // Labels match by content, whether given as a string or bytes.
+    let found = all_deals_for_label(&rt, Label::Bytes("label".as_bytes().to_vec()));
+    assert_eq!(ids[..2].to_vec(), found);
+
+    // Unknown and empty labels have an empty result.
+    assert!(all_deals_for_label(&rt, Label::String("unknown".to_string())).is_empty());
+    assert!(all_deals_for_label(&rt, Label::String(String::new())).is_empty());
+
+    // A label's deals are listed in pages.
+    let page = query_deals_for_label(&rt, proposal1.label.clone(), None, 1).unwrap();
+    assert_eq!(1, page.deal_ids.len());
+    let cursor = page.next_cursor.unwrap();
+    let page = query_deals_for_label(&rt, proposal1.label.clone(), Some(cursor), 1).unwrap();
+    assert_eq!(vec![cursor], page.deal_ids);
+    assert!(page.next_cursor.is_none());
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        query_deals_for_label(&rt, proposal1.label.clone(), None, 0),
+    );
+
+    // Terminating one deal removes it from the label's deals.
+    let activate_epoch = start_epoch - 2;
+    rt.set_epoch(activate_epoch);
+    activate_deals(&rt, end_epoch + 2, PROVIDER_ADDR, activate_epoch, 7, &ids[..1]);
+    activate_deals(&rt, end_epoch + 2, PROVIDER_ADDR, activate_epoch, 8, &ids[1..]);
+    rt.set_epoch(activate_epoch + 100);
+    terminate_deals(&rt, PROVIDER_ADDR, &[7], &ids[..1]);
+
+    let found = all_deals_for_label(&rt, proposal1.label);
+    assert_eq!(ids[1..2].to_vec(), found);
+    check_state(&rt);
+}
+
+#[test]
+fn deals_for_label_requires_index() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 180 * EPOCHS_IN_DAY;
+
+    let rt = setup();
+    rt.set_epoch(ChainEpoch::from(1));
+    let proposal = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    publish_deals(&rt, &MinerAddresses::default(), &[proposal.clone()], TokenAmount::zero(), 1);
+
+    // Without the index, publishing leaves no label state and the query is refused.
+    assert!(rt.get_state::<State>().label_deals.is_none());
+    expect_abort(ExitCode::USR_FORBIDDEN, query_deals_for_label(&rt, proposal.label, None, 10));
+    check_state(&rt);
+}
+
+#[test]
+fn batch_queries() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 180 * EPOCHS_IN_DAY;
+    let publish_epoch = ChainEpoch::from(1);
+
+    let rt = setup();
+    rt.set_epoch(publish_epoch);
+    let next_allocation_id = 1;
+
+    let proposal1 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    let proposal2 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch + 1,
+    );
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    let ids = publish_deals(
+        &rt,
+        &MinerAddresses::default(),
+        &[proposal1.clone(), proposal2.clone()],
+        TokenAmount::zero(),
+        next_allocation_id,
+    );
+
+    let activate_epoch = start_epoch - 2;
+    rt.set_epoch(activate_epoch);
+    activate_deals(&rt, end_epoch + 1, PROVIDER_ADDR, activate_epoch, 7, &ids[..1]);
+
+    // The last deal doesn't exist.
+    let query_ids = vec![ids[0], ids[1], ids[1] + 1];
+    let expected_results = BatchReturn::of(&[ExitCode::OK, ExitCode::OK, ExitCode::USR_NOT_FOUND]);
+
+    let ret: BatchGetDealActivationReturn =
+        query_deals(&rt, Method::BatchGetDealActivationExported, &query_ids);
+    assert_eq!(expected_results, ret.results);
+    assert_eq!(
+        vec![
+            GetDealActivationReturn { activated: activate_epoch, terminated: EPOCH_UNDEFINED },
+            GetDealActivationReturn { activated: EPOCH_UNDEFINED, terminated: EPOCH_UNDEFINED },
+        ],
+        ret.activations
+    );
+
+    let ret: BatchGetDealTermReturn =
+        query_deals(&rt, Method::BatchGetDealTermExported, &query_ids);
+    assert_eq!(expected_results, ret.results);
+    assert_eq!(
+        vec![
+            GetDealTermReturn { start: start_epoch, duration: proposal1.duration() },
+            GetDealTermReturn { start: start_epoch, duration: proposal2.duration() },
+        ],
+        ret.terms
+    );
+
+    let ret: BatchGetDealPartiesReturn =
+        query_deals(&rt, Method::BatchGetDealPartiesExported, &query_ids);
+    assert_eq!(expected_results, ret.results);
+    let parties = DealParties { client: CLIENT_ADDR.id().unwrap(), provider: PROVIDER_ID };
+    assert_eq!(vec![parties.clone(), parties], ret.parties);
+
+    let ret: BatchGetDealVerifiedReturn =
+        query_deals(&rt, Method::BatchGetDealVerifiedExported, &query_ids);
+    assert_eq!(expected_results, ret.results);
+    assert_eq!(vec![false, false], ret.verified);
+
+    // An empty batch succeeds trivially.
+    let ret: BatchGetDealTermReturn = query_deals(&rt, Method::BatchGetDealTermExported, &[]);
+    assert_eq!(BatchReturn::empty(), ret.results);
+    assert!(ret.terms.is_empty());
+    check_state(&rt);
+}
+
+#[test]
+fn list_provider_deals() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 180 * EPOCHS_IN_DAY;
+    let publish_epoch = ChainEpoch::from(1);
+
+    let rt = setup();
+    rt.set_epoch(publish_epoch);
+    let next_allocation_id = 1;
+
+    let proposals: Vec<_> = (0..5)
+        .map(|i| {
+            generate_deal_and_add_funds(
+                &rt,
+                CLIENT_ADDR,
+                &MinerAddresses::default(),
+                start_epoch,
+                end_epoch + i,
+            )
+        })
+        .collect();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    let ids = publish_deals(
+        &rt,
+        &MinerAddresses::default(),
+        &proposals,
+        TokenAmount::zero(),
+        next_allocation_id,
+    );
+
+    // Page through the provider's deals.
+    let mut listed = list_all_provider_deals(&rt, PROVIDER_ID, 2);
+    listed.sort();
+    assert_eq!(ids, listed);
+
+    // A provider with no deals lists nothing.
+    let ret = query_provider_deals(&rt, PROVIDER_ID + 1000, None, 10).unwrap();
+    assert!(ret.deal_ids.is_empty());
+    assert_eq!(None, ret.next_cursor);
+
+    // The limit must be positive, and the cursor must be one of the provider's deals.
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, query_provider_deals(&rt, PROVIDER_ID, None, 0));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "not found for provider",
+        query_provider_deals(&rt, PROVIDER_ID, Some(ids[4] + 1), 10),
+    );
+
+    // Terminating a deal removes it from the provider's deals.
+    let activate_epoch = start_epoch - 2;
+    rt.set_epoch(activate_epoch);
+    activate_deals(&rt, end_epoch + 5, PROVIDER_ADDR, activate_epoch, 7, &ids[..1]);
+    activate_deals(&rt, end_epoch + 5, PROVIDER_ADDR, activate_epoch, 8, &ids[1..]);
+    rt.set_epoch(activate_epoch + 100);
+    terminate_deals(&rt, PROVIDER_ADDR, &[7], &ids[..1]);
+
+    let mut listed = list_all_provider_deals(&rt, PROVIDER_ID, 10);
+    listed.sort();
+    assert_eq!(ids[1..].to_vec(), listed);
+    check_state(&rt);
+}
+
+#[test]
+fn list_provider_pending_deals() {
+    let end_epoch = 200 * EPOCHS_IN_DAY;
+    let rt = setup();
+    rt.set_epoch(1);
+
+    let proposals: Vec<_> = [30, 10, 20, 10]
+        .iter()
+        .enumerate()
+        .map(|(i, start_epoch)| {
+            generate_deal_and_add_funds(
+                &rt,
+                CLIENT_ADDR,
+                &MinerAddresses::default(),
+                *start_epoch,
+                end_epoch + i as ChainEpoch,
+            )
+        })
+        .collect();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    let ids = publish_deals(&rt, &MinerAddresses::default(), &proposals, TokenAmount::zero(), 1);
+    let pending = |start_epoch, i: usize| PendingDeal { start_epoch, deal_id: ids[i] };
+
+    // Deals are listed by start epoch, then by ID.
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID, None, 3).unwrap();
+    assert_eq!(vec![pending(10, 1), pending(10, 3), pending(20, 2)], ret.deals);
+    assert_eq!(Some(pending(30, 0)), ret.next_cursor);
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID, ret.next_cursor, 3).unwrap();
+    assert_eq!(vec![pending(30, 0)], ret.deals);
+    assert_eq!(None, ret.next_cursor);
+
+    // Listing resumes from the cursor's position, even between queued epochs.
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID, Some(pending(15, 0)), 10).unwrap();
+    assert_eq!(vec![pending(20, 2), pending(30, 0)], ret.deals);
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID, Some(pending(10, 3)), 1).unwrap();
+    assert_eq!(vec![pending(10, 3)], ret.deals);
+    assert_eq!(Some(pending(20, 2)), ret.next_cursor);
+
+    // A provider with no pending deals lists nothing, and the limit must be positive.
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID + 1000, None, 10).unwrap();
+    assert!(ret.deals.is_empty());
+    assert_eq!(None, ret.next_cursor);
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        query_provider_pending_deals(&rt, PROVIDER_ID, None, 0),
+    );
+
+    // Activated deals leave the queue, and a cursor naming one remains usable.
+    rt.set_epoch(5);
+    activate_deals(&rt, end_epoch + 5, PROVIDER_ADDR, 5, 1, &ids[1..2]);
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID, Some(pending(10, 1)), 10).unwrap();
+    assert_eq!(vec![pending(10, 3), pending(20, 2), pending(30, 0)], ret.deals);
+    assert_eq!(None, ret.next_cursor);
+    check_state(&rt);
+
+    activate_deals(&rt, end_epoch + 5, PROVIDER_ADDR, 5, 2, &[ids[0], ids[2], ids[3]]);
+    let ret = query_provider_pending_deals(&rt, PROVIDER_ID, None, 10).unwrap();
+    assert!(ret.deals.is_empty());
+    assert!(rt.get_state::<State>().provider_pending_deals.is_none());
+    check_state(&rt);
+}
+
+fn query_provider_deals(
+    rt: &MockRuntime,
+    provider: u64,
+    cursor: Option<u64>,
+    limit: u64,
+) -> Result<ListProviderDealsReturn, ActorError> {
+    let params = ListProviderDealsParams { provider, cursor, limit };
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<MarketActor>(
+        Method::ListProviderDealsExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize().unwrap())
+}
+
+fn list_all_provider_deals(rt: &MockRuntime, provider: u64, limit: u64) -> Vec<u64> {
+    let mut deal_ids = vec![];
+    let mut cursor = None;
+    loop {
+        let ret = query_provider_deals(rt, provider, cursor, limit).unwrap();
+        assert!(ret.deal_ids.len() as u64 <= limit);
+        deal_ids.extend(ret.deal_ids);
+        cursor = ret.next_cursor;
+        if cursor.is_none() {
+            return deal_ids;
+        }
+    }
+}
+
+fn query_provider_pending_deals(
+    rt: &MockRuntime,
+    provider: u64,
+    cursor: Option<PendingDeal>,
+    limit: u64,
+) -> Result<ListProviderPendingDealsReturn, ActorError> {
+    let params = ListProviderPendingDealsParams { provider, cursor, limit };
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<MarketActor>(
+        Method::ListProviderPendingDealsExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize().unwrap())
+}
+
+fn query_deals<T: DeserializeOwned>(rt: &MockRuntime, method: Method, ids: &[u64]) -> T {
+    let params = BatchDealQueryParams { ids: ids.to_vec() };
+    rt.expect_validate_caller_any();
+    rt.call::<MarketActor>(method as u64, IpldBlock::serialize_cbor(&params).unwrap())
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap()
+}
+
+fn query_deals_for_piece(rt: &MockRuntime, piece_cid: Cid) -> GetDealsForPieceReturn {
+    let params = GetDealsForPieceParams { piece_cid };
+    rt.expect_validate_caller_any();
+    rt.call::<MarketActor>(
+        Method::GetDealsForPieceExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+    .unwrap()
+    .unwrap()
+    .deserialize()
+    .unwrap()
+}
+
+fn query_deals_for_label(
+    rt: &MockRuntime,
+    label: Label,
+    cursor: Option<u64>,
+    limit: u64,
+) -> Result<GetDealsForLabelReturn, ActorError> {
+    let params = GetDealsForLabelParams { label, cursor, limit };
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<MarketActor>(
+        Method::GetDealsForLabelExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize().unwrap())
+}
+
+// Lists all deals with a label a page at a time, sorted by ID.
+fn all_deals_for_label(rt: &MockRuntime, label: Label) -> Vec<u64> {
+    let mut deal_ids = vec![];
+    let mut cursor = None;
+    loop {
+        let ret = query_deals_for_label(rt, label.clone(), cursor, 1).unwrap();
+        assert!(ret.deal_ids.len() <= 1);
+        deal_ids.extend(ret.deal_ids);
+        cursor = ret.next_cursor;
+        if cursor.is_none() {
+            deal_ids.sort();
+            return deal_ids;
+        }
+    }
 }
 
 fn query_deal<T: DeserializeOwned>(rt: &MockRuntime, method: Method, id: u64) -> T {
@@ -23,7 +23,10 @@ use fvm_shared::{
 use num_traits::{FromPrimitive, Zero};
 use regex::Regex;
 
-use fil_actor_market::ext::account::{AUTHENTICATE_MESSAGE_METHOD, AuthenticateMessageParams};
+use fil_actor_market::ext::account::{
+    AUTHENTICATE_MESSAGE_METHOD, AuthenticateMessageParams, BATCH_AUTHENTICATE_MESSAGE_METHOD,
+    BatchAuthenticateMessageParams, BatchAuthenticateMessageReturn,
+};
 use fil_actor_market::ext::miner::{
     PieceChange, SectorChanges, SectorContentChangedParams, SectorContentChangedReturn,
 };
@@ -41,7 +44,8 @@ use fil_actor_market::{
     BatchActivateDealsParams, BatchActivateDealsResult, DealOpsByEpoch, PENDING_ALLOCATIONS_CONFIG,
     PENDING_PROPOSALS_CONFIG, PROVIDER_SECTORS_CONFIG, PendingDealAllocationsMap,
     PendingProposalsSet, ProviderSectorsMap, SECTOR_DEALS_CONFIG, SectorDealsMap,
-    SettleDealPaymentsParams, SettleDealPaymentsReturn, deal_cid, deal_get_payment_remaining,
+    SettleDealPaymentsParams, SettleDealPaymentsPreviewParams, SettleDealPaymentsPreviewReturn,
+    SettleDealPaymentsReturn, deal_cid, deal_get_payment_remaining,
 };
 use fil_actor_power::{CurrentTotalPowerReturn, Method as PowerMethod};
 use fil_actor_reward::Method as RewardMethod;
@@ -203,7 +207,12 @@ pub fn expect_get_control_addresses(
     worker: Address,
     controls: Vec<Address>,
 ) {
-    let result = GetControlAddressesReturnParams { owner, worker, control_addresses: controls };
+    let result = GetControlAddressesReturnParams {
+        owner,
+        worker,
+        control_addresses: controls,
+        control_address_roles: vec![],
+    };
 
     rt.expect_send_simple(
         provider,
@@ -294,7 +303,11 @@ pub fn withdraw_provider_balance(
     rt.expect_validate_caller_addr(vec![owner, worker]);
     expect_provider_control_address(rt, provider, owner, worker);
 
-    let params = WithdrawBalanceParams { provider_or_client: provider, amount: withdraw_amount };
+    let params = WithdrawBalanceParams {
+        provider_or_client: provider,
+        amount: withdraw_amount,
+        beneficiary: None,
+    };
 
     rt.expect_send_simple(owner, METHOD_SEND, None, expected_send.clone(), None, ExitCode::OK);
     let ret: WithdrawBalanceReturn = rt
@@ -325,7 +338,11 @@ pub fn withdraw_client_balance(
     rt.expect_send_simple(client, METHOD_SEND, None, expected_send.clone(), None, ExitCode::OK);
     rt.expect_validate_caller_addr(vec![client]);
 
-    let params = WithdrawBalanceParams { provider_or_client: client, amount: withdraw_amount };
+    let params = WithdrawBalanceParams {
+        provider_or_client: client,
+        amount: withdraw_amount,
+        beneficiary: None,
+    };
 
     let ret: WithdrawBalanceReturn = rt
         .call::<MarketActor>(
@@ -419,7 +436,7 @@ pub fn activate_deals_for(
             sector_type: RegisteredSealProof::StackedDRG8MiBV1,
         }],
         compute_cid,
-        expected_deal_activations,
+        &expected_deal_activations.iter().map(|id| (sector_number, *id)).collect::<Vec<_>>(),
     )
     .unwrap();
 
@@ -455,8 +472,10 @@ pub fn batch_activate_deals(
         })
         .collect();
 
-    let deal_ids =
-        sectors.iter().flat_map(|(_, _, deal_ids)| deal_ids).cloned().collect::<Vec<_>>();
+    let deal_ids = sectors
+        .iter()
+        .flat_map(|(sector_number, _, deal_ids)| deal_ids.iter().map(|id| (*sector_number, *id)))
+        .collect::<Vec<_>>();
 
     let ret =
         batch_activate_deals_raw(rt, provider, sectors_deals, compute_cid, &deal_ids).unwrap();
@@ -475,23 +494,16 @@ pub fn batch_activate_deals_raw(
     provider: Address,
     sectors_deals: Vec<SectorDeals>,
     compute_cid: bool,
-    expected_activated_deals: &[DealID],
+    expected_activated_deals: &[(SectorNumber, DealID)],
 ) -> Result<Option<IpldBlock>, ActorError> {
     rt.set_caller(*MINER_ACTOR_CODE_ID, provider);
     rt.expect_validate_caller_type(vec![Type::Miner]);
 
     let params = BatchActivateDealsParams { sectors: sectors_deals, compute_cid };
 
-    for deal_id in expected_activated_deals {
+    for (sector_number, deal_id) in expected_activated_deals {
         let dp = get_deal_proposal(rt, *deal_id);
-
-        expect_emitted(
-            rt,
-            "deal-activated",
-            *deal_id,
-            dp.client.id().unwrap(),
-            dp.provider.id().unwrap(),
-        );
+        expect_deal_activated(rt, *deal_id, &dp, *sector_number);
     }
     let ret = rt.call::<MarketActor>(
         Method::BatchActivateDeals as u64,
@@ -600,11 +612,7 @@ pub fn update_last_updated(rt: &MockRuntime, deal_id: DealID, new_last_updated:
 
 pub fn delete_deal_proposal(rt: &MockRuntime, deal_id: DealID) {
     let mut st: State = rt.get_state();
-    let mut deals = DealArray::load(&st.proposals, &rt.store).unwrap();
-    deals.delete(deal_id).unwrap();
-
-    let root = deals.flush().unwrap();
-    st.proposals = root;
+    st.remove_proposal(&rt.store, deal_id).unwrap();
     rt.replace_state(&st)
 }
 
@@ -744,6 +752,13 @@ pub fn publish_deals(
     let mut client_verified_deals: BTreeMap<ActorID, ClientVerifiedDeals> = BTreeMap::new();
     let mut alloc_id = next_allocation_id;
     let mut valid_deals = vec![];
+    // Account clients proposing more than one deal authenticate them in a single batch,
+    // made when the first of their deals is encountered.
+    let mut client_deal_counts: BTreeMap<Address, usize> = BTreeMap::new();
+    for deal in publish_deals {
+        *client_deal_counts.entry(deal.client).or_default() += 1;
+    }
+    let mut batch_authenticated: HashSet<Address> = HashSet::new();
     for deal in publish_deals {
         // create a client proposal with a valid signature
         let buf = RawBytes::serialize(deal.clone()).expect("failed to marshal deal proposal");
@@ -752,6 +767,29 @@ pub fn publish_deals(
             ClientDealProposal { proposal: deal.clone(), client_signature: sig.clone() };
         params.deals.push(client_proposal);
 
+        let client_count = client_deal_counts[&deal.client];
+        let client_is_account =
+            rt.actor_code_cids.borrow().get(&deal.client) == Some(&*ACCOUNT_ACTOR_CODE_ID);
+        if client_count > 1 && client_is_account {
+            if batch_authenticated.insert(deal.client) {
+                let messages: Vec<AuthenticateMessageParams> = publish_deals
+                    .iter()
+                    .filter(|d| d.client == deal.client)
+                    .map(|d| AuthenticateMessageParams {
+                        signature: "does not matter".as_bytes().to_vec(),
+                        message: RawBytes::serialize(d).unwrap().to_vec(),
+                    })
+                    .collect();
+                expect_batch_authenticate(
+                    rt,
+                    deal.client,
+                    messages,
+                    BatchReturn::ok(client_count as u32),
+                );
+            }
+            continue;
+        }
+
         // Expect an invocation of authenticate_message to verify the signature.
         rt.expect_send(
             deal.client,
@@ -904,6 +942,26 @@ pub fn publish_deals(
     ret.ids
 }
 
+/// Expects a single call to an account client to authenticate a batch of deal proposals.
+pub fn expect_batch_authenticate(
+    rt: &MockRuntime,
+    client: Address,
+    messages: Vec<AuthenticateMessageParams>,
+    results: BatchReturn,
+) {
+    rt.expect_send(
+        client,
+        BATCH_AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&BatchAuthenticateMessageParams { messages }).unwrap(),
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+        IpldBlock::serialize_cbor(&BatchAuthenticateMessageReturn { results }).unwrap(),
+        ExitCode::OK,
+        None,
+    );
+}
+
 pub fn publish_deals_expect_abort(
     rt: &MockRuntime,
     miner_addresses: &MinerAddresses,
@@ -998,6 +1056,30 @@ pub fn settle_deal_payments(
     res
 }
 
+pub fn settle_deal_payments_preview(
+    rt: &MockRuntime,
+    caller: Address,
+    deal_ids: &[DealID],
+) -> SettleDealPaymentsPreviewReturn {
+    let mut deal_id_bitfield = BitField::new();
+    for deal_id in deal_ids {
+        deal_id_bitfield.set(*deal_id);
+    }
+    let params = SettleDealPaymentsPreviewParams { deal_ids: deal_id_bitfield };
+    let params = IpldBlock::serialize_cbor(&params).unwrap();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_any();
+    let res = rt
+        .call::<MarketActor>(Method::SettleDealPaymentsPreviewExported as u64, params)
+        .unwrap()
+        .unwrap();
+    let res: SettleDealPaymentsPreviewReturn = res.deserialize().unwrap();
+
+    rt.verify();
+    res
+}
+
 pub fn settle_deal_payments_no_change(
     rt: &MockRuntime,
     caller: Address,
@@ -1122,6 +1204,29 @@ pub fn settle_deal_payments_expect_abort(
     rt.verify();
 }
 
+pub fn settle_deal_payments_preview_expect_abort(
+    rt: &MockRuntime,
+    caller: Address,
+    deal_ids: &[DealID],
+    expected_exit_code: ExitCode,
+) {
+    let mut deal_id_bitfield = BitField::new();
+    for deal_id in deal_ids {
+        deal_id_bitfield.set(*deal_id);
+    }
+    let params = SettleDealPaymentsPreviewParams { deal_ids: deal_id_bitfield };
+    let params = IpldBlock::serialize_cbor(&params).unwrap();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_any();
+    expect_abort(
+        expected_exit_code,
+        rt.call::<MarketActor>(Method::SettleDealPaymentsPreviewExported as u64, params),
+    );
+
+    rt.verify();
+}
+
 pub fn assert_deals_not_activated(rt: &MockRuntime, _epoch: ChainEpoch, deal_ids: &[DealID]) {
     let st: State = rt.get_state();
 
@@ -1428,6 +1533,7 @@ pub fn generate_and_publish_deal_for_piece(
         storage_price_per_epoch,
         provider_collateral,
         client_collateral,
+        payment_token: None,
     };
 
     // add funds
@@ -1498,6 +1604,7 @@ fn generate_deal_proposal_with_collateral(
         storage_price_per_epoch,
         provider_collateral,
         client_collateral,
+        payment_token: None,
     }
 }
 
@@ -1793,3 +1900,23 @@ pub fn expect_emitted(rt: &MockRuntime, typ: &str, id: DealID, client: ActorID,
             .unwrap(),
     );
 }
+
+pub fn expect_deal_activated(
+    rt: &MockRuntime,
+    id: DealID,
+    proposal: &DealProposal,
+    sector: SectorNumber,
+) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("deal-activated")
+            .field_indexed("id", &id)
+            .field_indexed("client", &proposal.client.id().unwrap())
+            .field_indexed("provider", &proposal.provider.id().unwrap())
+            .field_indexed("sector", &sector)
+            .field_indexed("piece-cid", &proposal.piece_cid)
+            .field_indexed("verified", &proposal.verified_deal)
+            .build()
+            .unwrap(),
+    );
+}
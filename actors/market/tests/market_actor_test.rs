@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::cell::RefCell;
+use std::cmp::min;
 use std::ops::Add;
 
 use frc46_token::token::types::{TransferFromParams, TransferFromReturn};
@@ -17,7 +18,8 @@ use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::{RegisteredSealProof, StoragePower};
 use fvm_shared::sys::SendFlags;
-use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND, MethodNum};
+use fvm_shared::version::NetworkVersion;
+use fvm_shared::{ActorID, METHOD_CONSTRUCTOR, METHOD_SEND, MethodNum};
 use num_traits::{FromPrimitive, Zero};
 use regex::Regex;
 
@@ -27,11 +29,12 @@ use fil_actor_market::ext::verifreg::{AllocationRequest, AllocationsResponse};
 use fil_actor_market::policy::detail::DEAL_MAX_LABEL_SIZE;
 use fil_actor_market::{
     Actor as MarketActor, BatchActivateDealsResult, ClientDealProposal, DEAL_OPS_BY_EPOCH_CONFIG,
-    DealArray, DealMetaArray, DealOpsByEpoch, EX_DEAL_EXPIRED, Label, MARKET_NOTIFY_DEAL_METHOD,
-    MarketNotifyDealParams, Method, PENDING_ALLOCATIONS_CONFIG, PENDING_PROPOSALS_CONFIG,
+    DealArray, DealMetaArray, DealOpsByEpoch, DealProposal, EX_DEAL_EXPIRED,
+    INTERNAL_METHODS_SUNSET_VERSION, Label, MARKET_NOTIFY_DEAL_METHOD, MarketNotifyDealParams,
+    Method, NO_ALLOCATION_ID, PENDING_ALLOCATIONS_CONFIG, PENDING_PROPOSALS_CONFIG,
     PROPOSALS_AMT_BITWIDTH, PendingDealAllocationsMap, PendingProposalsSet,
-    PublishStorageDealsParams, PublishStorageDealsReturn, STATES_AMT_BITWIDTH, SectorDeals, State,
-    WithdrawBalanceParams, ext,
+    PublishStorageDeals2Return, PublishStorageDealsParams, PublishStorageDealsReturn,
+    STATES_AMT_BITWIDTH, SectorDeals, State, WithdrawBalanceParams, ext,
 };
 use fil_actors_runtime::cbor::{deserialize, serialize};
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
@@ -39,7 +42,8 @@ use fil_actors_runtime::runtime::{Policy, Runtime};
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::{
     ActorError, BURNT_FUNDS_ACTOR_ADDR, BatchReturn, DATACAP_TOKEN_ACTOR_ADDR, DEFAULT_HAMT_CONFIG,
-    SYSTEM_ACTOR_ADDR, SetMultimap, SetMultimapConfig, VERIFIED_REGISTRY_ACTOR_ADDR,
+    ERR_METHOD_DEPRECATED, EventBuilder, MethodDeprecated, SYSTEM_ACTOR_ADDR, SetMultimap,
+    SetMultimapConfig, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use harness::*;
 
@@ -234,6 +238,7 @@ fn fails_if_withdraw_from_non_provider_funds_is_not_initiated_by_the_recipient()
     let params = WithdrawBalanceParams {
         provider_or_client: CLIENT_ADDR,
         amount: TokenAmount::from_atto(1u8),
+        beneficiary: None,
     };
 
     // caller is not the recipient
@@ -509,6 +514,7 @@ fn fails_with_a_negative_withdraw_amount() {
     let params = WithdrawBalanceParams {
         provider_or_client: PROVIDER_ADDR,
         amount: TokenAmount::from_atto(-1_i32),
+        beneficiary: None,
     };
 
     expect_abort(
@@ -536,6 +542,7 @@ fn fails_if_withdraw_from_provider_funds_is_not_initiated_by_the_owner_or_worker
     let params = WithdrawBalanceParams {
         provider_or_client: PROVIDER_ADDR,
         amount: TokenAmount::from_atto(1u8),
+        beneficiary: None,
     };
 
     // caller is not owner or worker
@@ -556,6 +563,92 @@ fn fails_if_withdraw_from_provider_funds_is_not_initiated_by_the_owner_or_worker
     check_state(&rt);
 }
 
+#[test]
+fn client_withdraws_to_beneficiary() {
+    let rt = setup();
+    let beneficiary = Address::new_id(909);
+    add_participant_funds(&rt, CLIENT_ADDR, TokenAmount::from_atto(20u8));
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    rt.expect_validate_caller_addr(vec![CLIENT_ADDR]);
+    let amount = TokenAmount::from_atto(15u8);
+    rt.expect_send_simple(beneficiary, METHOD_SEND, None, amount.clone(), None, ExitCode::OK);
+    expect_beneficiary_withdrawal(&rt, CLIENT_ID, beneficiary, &amount);
+    let params = WithdrawBalanceParams {
+        provider_or_client: CLIENT_ADDR,
+        amount: amount.clone(),
+        beneficiary: Some(beneficiary),
+    };
+    rt.call::<MarketActor>(
+        Method::WithdrawBalanceExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+
+    assert_eq!(TokenAmount::from_atto(5u8), get_balance(&rt, &CLIENT_ADDR).balance);
+    check_state(&rt);
+}
+
+#[test]
+fn only_provider_owner_can_withdraw_to_beneficiary() {
+    let rt = setup();
+    let beneficiary = Address::new_id(909);
+    let amount = TokenAmount::from_atto(20u8);
+    add_provider_funds(&rt, amount.clone(), &MinerAddresses::default());
+    let params = WithdrawBalanceParams {
+        provider_or_client: PROVIDER_ADDR,
+        amount: amount.clone(),
+        beneficiary: Some(beneficiary),
+    };
+
+    // the worker may withdraw to the owner, but not to a beneficiary
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    expect_provider_control_address(&rt, PROVIDER_ADDR, OWNER_ADDR, WORKER_ADDR);
+    rt.expect_validate_caller_addr(vec![OWNER_ADDR]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<MarketActor>(
+            Method::WithdrawBalance as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        ),
+    );
+    rt.verify();
+    assert_eq!(amount, get_balance(&rt, &PROVIDER_ADDR).balance);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, OWNER_ADDR);
+    expect_provider_control_address(&rt, PROVIDER_ADDR, OWNER_ADDR, WORKER_ADDR);
+    rt.expect_validate_caller_addr(vec![OWNER_ADDR]);
+    rt.expect_send_simple(beneficiary, METHOD_SEND, None, amount.clone(), None, ExitCode::OK);
+    expect_beneficiary_withdrawal(&rt, PROVIDER_ID, beneficiary, &amount);
+    rt.call::<MarketActor>(
+        Method::WithdrawBalance as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+
+    assert!(get_balance(&rt, &PROVIDER_ADDR).balance.is_zero());
+    check_state(&rt);
+}
+
+fn expect_beneficiary_withdrawal(
+    rt: &MockRuntime,
+    account: ActorID,
+    beneficiary: Address,
+    amount: &TokenAmount,
+) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("beneficiary-withdrawal")
+            .field_indexed("account", &account)
+            .field_indexed("beneficiary", &beneficiary)
+            .field_bigint("amount", amount.atto())
+            .build()
+            .unwrap(),
+    );
+}
+
 #[test]
 fn deal_starts_on_day_boundary() {
     let mut policy = Policy::default();
@@ -1060,6 +1153,68 @@ fn datacap_transfer_drops_deal_when_cap_insufficient() {
     check_state(&rt);
 }
 
+#[test]
+fn datacap_transfer_rejection_aborts_publish() {
+    let rt = setup();
+    let (deals, params) = expect_publish_until_datacap_transfer(&rt);
+    expect_datacap_transfer(&rt, &[&deals[0], &deals[1]], Err(ExitCode::USR_ILLEGAL_ARGUMENT));
+
+    expect_abort(
+        ExitCode::USR_ILLEGAL_STATE,
+        rt.call::<MarketActor>(
+            Method::PublishStorageDeals as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        ),
+    );
+    rt.verify();
+    check_state(&rt);
+}
+
+#[test]
+fn datacap_transfer_rejection_drops_only_rejected_deals() {
+    let rt = setup();
+    let (deals, params) = expect_publish_until_datacap_transfer(&rt);
+    // The batched transfer is rejected, so each deal's allocation is retried alone.
+    expect_datacap_transfer(&rt, &[&deals[0], &deals[1]], Err(ExitCode::USR_ILLEGAL_ARGUMENT));
+    expect_datacap_transfer(&rt, &[&deals[0]], Ok(vec![7]));
+    expect_datacap_transfer(&rt, &[&deals[1]], Err(ExitCode::USR_ILLEGAL_ARGUMENT));
+
+    let next_deal_id = rt.get_state::<State>().next_id;
+    for (deal_id, deal) in [(next_deal_id, &deals[0]), (next_deal_id + 1, &deals[2])] {
+        rt.expect_send_simple(
+            deal.client,
+            MARKET_NOTIFY_DEAL_METHOD,
+            IpldBlock::serialize_cbor(&MarketNotifyDealParams {
+                proposal: RawBytes::serialize(deal).unwrap().to_vec(),
+                deal_id,
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        expect_emitted(&rt, "deal-published", deal_id, 900, PROVIDER_ID);
+    }
+
+    let ret: PublishStorageDeals2Return = rt
+        .call::<MarketActor>(
+            Method::PublishStorageDeals2Exported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(vec![next_deal_id, next_deal_id + 1], ret.ids);
+    assert_eq!(vec![0, 2], ret.valid_deals.iter().collect::<Vec<u64>>());
+    assert_eq!(vec![7, NO_ALLOCATION_ID], ret.allocation_ids);
+    assert_eq!(vec![ExitCode::OK, ExitCode::USR_ILLEGAL_ARGUMENT], ret.allocation_results.codes());
+    assert_eq!(7, get_pending_deal_allocation(&rt, next_deal_id));
+    check_state(&rt);
+}
+
 #[test]
 fn publish_a_deal_after_activating_a_previous_deal_which_has_a_start_epoch_far_in_the_future() {
     let start_epoch = 1000;
@@ -2169,6 +2324,49 @@ fn add_balance_restricted_correctly() {
     rt.verify();
 }
 
+#[test]
+fn internal_methods_deprecated_at_sunset_version() {
+    let rt = setup();
+    let amount = TokenAmount::from_atto(1000);
+
+    // Before the sunset version, the internal method is dispatched as usual.
+    rt.set_network_version(NetworkVersion::new(u32::from(INTERNAL_METHODS_SUNSET_VERSION) - 1));
+    add_participant_funds(&rt, CLIENT_ADDR, amount.clone());
+
+    rt.set_network_version(INTERNAL_METHODS_SUNSET_VERSION);
+    rt.set_received(amount.clone());
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    let mut err = rt
+        .call::<MarketActor>(
+            Method::AddBalance as MethodNum,
+            IpldBlock::serialize_cbor(&CLIENT_ADDR).unwrap(),
+        )
+        .unwrap_err();
+    assert_eq!(ERR_METHOD_DEPRECATED, err.exit_code());
+    let data: MethodDeprecated = err.take_data().unwrap().deserialize().unwrap();
+    assert_eq!(
+        MethodDeprecated {
+            method: Method::AddBalance as MethodNum,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION.into(),
+            replacement: Method::AddBalanceExported as MethodNum,
+        },
+        data
+    );
+    rt.verify();
+
+    // The exported method remains available.
+    rt.expect_validate_caller_any();
+    rt.call::<MarketActor>(
+        Method::AddBalanceExported as MethodNum,
+        IpldBlock::serialize_cbor(&CLIENT_ADDR).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+    rt.add_balance(amount.clone());
+    assert_eq!(amount * 2, get_balance(&rt, &CLIENT_ADDR).balance);
+    check_state(&rt);
+}
+
 #[test]
 fn psd_restricted_correctly() {
     let rt = setup();
@@ -2286,3 +2484,135 @@ fn psd_restricted_correctly() {
     rt.verify();
     check_state(&rt);
 }
+
+// Generates three deals from one client, the first two verified, and expects their publication
+// up to the transfer of datacap for the verified deals.
+fn expect_publish_until_datacap_transfer(
+    rt: &MockRuntime,
+) -> (Vec<DealProposal>, PublishStorageDealsParams) {
+    let start_epoch = 42;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+    let client_addr = Address::new_id(900);
+    rt.set_epoch(start_epoch);
+
+    let deals: Vec<DealProposal> = (0..3)
+        .map(|i| {
+            let mut deal = generate_deal_and_add_funds(
+                rt,
+                client_addr,
+                &MinerAddresses::default(),
+                start_epoch,
+                end_epoch + i,
+            );
+            deal.verified_deal = i < 2;
+            deal
+        })
+        .collect();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    rt.expect_validate_caller_any();
+    expect_provider_is_control_address(rt, PROVIDER_ADDR, WORKER_ADDR, true);
+    expect_query_network_info(rt);
+    for deal in &deals {
+        rt.expect_send(
+            deal.client,
+            AUTHENTICATE_MESSAGE_METHOD,
+            IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+                signature: "does not matter".as_bytes().to_vec(),
+                message: RawBytes::serialize(deal).unwrap().to_vec(),
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+            AUTHENTICATE_MESSAGE_RESPONSE.clone(),
+            ExitCode::OK,
+            None,
+        );
+    }
+    rt.expect_send_simple(
+        DATACAP_TOKEN_ACTOR_ADDR,
+        ext::datacap::BALANCE_OF_METHOD,
+        IpldBlock::serialize_cbor(&client_addr).unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&TokenAmount::from_whole(deals[0].piece_size.0 * 10)).unwrap(),
+        ExitCode::OK,
+    );
+
+    let params = PublishStorageDealsParams {
+        deals: deals
+            .iter()
+            .map(|deal| ClientDealProposal {
+                proposal: deal.clone(),
+                client_signature: Signature::new_bls("does not matter".as_bytes().to_vec()),
+            })
+            .collect(),
+    };
+    (deals, params)
+}
+
+fn expect_datacap_transfer(
+    rt: &MockRuntime,
+    deals: &[&DealProposal],
+    result: Result<Vec<u64>, ExitCode>,
+) {
+    let policy = Policy::default();
+    let curr_epoch = *rt.epoch.borrow();
+    let allocations: Vec<AllocationRequest> = deals
+        .iter()
+        .map(|deal| AllocationRequest {
+            provider: deal.provider.id().unwrap(),
+            data: deal.piece_cid,
+            size: deal.piece_size,
+            term_min: deal.end_epoch - deal.start_epoch,
+            term_max: min(
+                deal.end_epoch - deal.start_epoch + policy.market_default_allocation_term_buffer,
+                policy.maximum_verified_allocation_term,
+            ),
+            expiration: min(
+                deal.start_epoch,
+                curr_epoch + policy.maximum_verified_allocation_expiration,
+            ),
+        })
+        .collect();
+    let amount = TokenAmount::from_whole(deals.iter().map(|deal| deal.piece_size.0).sum::<u64>());
+    let params = TransferFromParams {
+        from: deals[0].client,
+        to: VERIFIED_REGISTRY_ACTOR_ADDR,
+        amount: amount.clone(),
+        operator_data: serialize(
+            &ext::verifreg::AllocationRequests { allocations, extensions: vec![] },
+            "allocation requests",
+        )
+        .unwrap(),
+    };
+    let (ret, exit_code) = match result {
+        Ok(new_allocations) => (
+            IpldBlock::serialize_cbor(&TransferFromReturn {
+                from_balance: TokenAmount::zero(),
+                to_balance: amount,
+                allowance: TokenAmount::zero(),
+                recipient_data: serialize(
+                    &AllocationsResponse {
+                        allocation_results: BatchReturn::ok(new_allocations.len() as u32),
+                        extension_results: BatchReturn::empty(),
+                        new_allocations,
+                    },
+                    "allocations response",
+                )
+                .unwrap(),
+            })
+            .unwrap(),
+            ExitCode::OK,
+        ),
+        Err(code) => (None, code),
+    };
+    rt.expect_send_simple(
+        DATACAP_TOKEN_ACTOR_ADDR,
+        ext::datacap::TRANSFER_FROM_METHOD,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+        TokenAmount::zero(),
+        ret,
+        exit_code,
+    );
+}
@@ -0,0 +1,189 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::bigint::BigInt;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::signature::Signature;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
+use num_traits::Zero;
+
+use fil_actor_market::ext::account::{AUTHENTICATE_MESSAGE_METHOD, AuthenticateMessageParams};
+use fil_actor_market::ext::verifreg::{
+    RELEASE_ALLOCATIONS_METHOD, ReleaseAllocationsParams, ReleaseAllocationsReturn,
+};
+use fil_actor_market::{
+    Actor as MarketActor, Method, MutuallyCancelDealsParams, MutuallyCancelDealsReturn,
+    SIGNATURE_DOMAIN_SEPARATION_MUTUALLY_CANCEL_DEALS,
+};
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{ActorError, BatchReturn, VERIFIED_REGISTRY_ACTOR_ADDR};
+use harness::*;
+
+mod harness;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+
+#[test]
+fn mutual_cancellation_unlocks_funds_and_releases_allocations() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (id1, deal1) = generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+    let id2 =
+        generate_and_publish_verified_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH + 1, 1);
+    let deal2 = get_deal_proposal(&rt, id2);
+    let allocation_id = get_pending_deal_allocation(&rt, id2);
+
+    // Deals may be cancelled after their start epoch, so long as they are not activated.
+    rt.set_epoch(START_EPOCH + 1);
+    let provider_before = get_balance(&rt, &addrs.provider);
+
+    let deal_ids = vec![id1, id2];
+    expect_provider_is_control_address(&rt, addrs.provider, addrs.worker, true);
+    expect_authenticate(&rt, deal1.client, &deal_ids, true);
+    rt.expect_send_simple(
+        VERIFIED_REGISTRY_ACTOR_ADDR,
+        RELEASE_ALLOCATIONS_METHOD,
+        IpldBlock::serialize_cbor(&ReleaseAllocationsParams {
+            client: CLIENT_ID,
+            allocation_ids: vec![allocation_id],
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&ReleaseAllocationsReturn {
+            results: BatchReturn::ok(1),
+            datacap_recovered: BigInt::from(deal2.piece_size.0),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    expect_emitted(&rt, "deal-cancelled", id1, CLIENT_ID, PROVIDER_ID);
+    expect_emitted(&rt, "deal-cancelled", id2, CLIENT_ID, PROVIDER_ID);
+    let ret = mutually_cancel_deals(&rt, addrs.worker, deal_ids).unwrap();
+    assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.results.codes());
+
+    let client_after = get_balance(&rt, &CLIENT_ADDR);
+    let provider_after = get_balance(&rt, &addrs.provider);
+    assert!(client_after.locked.is_zero());
+    assert!(provider_after.locked.is_zero());
+    assert_eq!(provider_before.balance, provider_after.balance);
+    assert_deal_deleted(&rt, id1, &deal1, 0, true);
+    assert_deal_deleted(&rt, id2, &deal2, 0, true);
+    check_state(&rt);
+
+    // Cron removes the cancelled deals' scheduled ops without slashing.
+    rt.set_epoch(process_epoch(START_EPOCH, id1).max(process_epoch(START_EPOCH, id2)));
+    cron_tick(&rt);
+    assert_deal_ops_clean(&rt);
+    assert_eq!(provider_after, get_balance(&rt, &addrs.provider));
+    check_state(&rt);
+}
+
+#[test]
+fn invalid_deals_fail_independently() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (id1, deal1) = generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+    let (activated_id, activated) = publish_and_activate_deal(
+        &rt,
+        CLIENT_ADDR,
+        &addrs,
+        1,
+        START_EPOCH,
+        END_EPOCH + 1,
+        0,
+        END_EPOCH + 1,
+    );
+
+    let deal_ids = vec![id1, id1, activated_id, activated_id + 1];
+    expect_provider_is_control_address(&rt, addrs.provider, addrs.worker, true);
+    expect_authenticate(&rt, deal1.client, &deal_ids, true);
+    expect_emitted(&rt, "deal-cancelled", id1, CLIENT_ID, PROVIDER_ID);
+    let ret = mutually_cancel_deals(&rt, addrs.worker, deal_ids).unwrap();
+    assert_eq!(
+        vec![
+            ExitCode::OK,
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            ExitCode::USR_FORBIDDEN,
+            ExitCode::USR_NOT_FOUND,
+        ],
+        ret.results.codes()
+    );
+
+    assert!(find_deal_proposal(&rt, id1).is_none());
+    assert_eq!(activated, get_deal_proposal(&rt, activated_id));
+    check_state(&rt);
+}
+
+#[test]
+fn requires_provider_and_client_agreement() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (id, deal) = generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+
+    // The caller must control the provider.
+    expect_provider_is_control_address(&rt, addrs.provider, CLIENT_ADDR, false);
+    expect_abort(ExitCode::USR_FORBIDDEN, mutually_cancel_deals(&rt, CLIENT_ADDR, vec![id]));
+
+    // The client must have signed the cancellation.
+    expect_provider_is_control_address(&rt, addrs.provider, addrs.worker, true);
+    expect_authenticate(&rt, deal.client, &[id], false);
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        mutually_cancel_deals(&rt, addrs.worker, vec![id]),
+    );
+
+    assert_eq!(deal, get_deal_proposal(&rt, id));
+    check_state(&rt);
+}
+
+fn expect_authenticate(rt: &MockRuntime, client: Address, deal_ids: &[DealID], valid: bool) {
+    rt.expect_send(
+        client,
+        AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature: signature().bytes,
+            message: [
+                SIGNATURE_DOMAIN_SEPARATION_MUTUALLY_CANCEL_DEALS,
+                &fvm_ipld_encoding::to_vec(&deal_ids).unwrap(),
+            ]
+            .concat(),
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+        IpldBlock::serialize_cbor(&valid).unwrap(),
+        ExitCode::OK,
+        None,
+    );
+}
+
+fn signature() -> Signature {
+    Signature::new_bls("does not matter".as_bytes().to_vec())
+}
+
+fn mutually_cancel_deals(
+    rt: &MockRuntime,
+    caller: Address,
+    deal_ids: Vec<DealID>,
+) -> Result<MutuallyCancelDealsReturn, ActorError> {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<MarketActor>(
+        Method::MutuallyCancelDealsExported as u64,
+        IpldBlock::serialize_cbor(&MutuallyCancelDealsParams {
+            deal_ids,
+            client_signature: signature(),
+        })
+        .unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize().unwrap())
+}
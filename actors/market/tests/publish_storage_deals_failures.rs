@@ -7,6 +7,7 @@ use fil_actor_market::{
     MarketNotifyDealParams, Method, PublishStorageDealsParams, PublishStorageDealsReturn, State,
     ext,
 };
+use fil_actors_runtime::BatchReturn;
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::test_utils::*;
@@ -55,6 +56,56 @@ fn deal_duration_greater_than_max_deal_duration() {
     assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
 }
 
+#[test]
+fn deal_duration_bounds_follow_policy() {
+    let mut rt = setup();
+    rt.set_policy(Policy {
+        market_min_deal_duration: EPOCHS_IN_DAY,
+        market_max_deal_duration: 2 * EPOCHS_IN_DAY,
+        ..Policy::default()
+    });
+    let addrs = MinerAddresses::default();
+    let start_epoch = 10;
+
+    // A deal far shorter than the default minimum is accepted.
+    generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, start_epoch, start_epoch + EPOCHS_IN_DAY);
+
+    // A deal exceeding the policy maximum is rejected.
+    let deal = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &addrs,
+        start_epoch,
+        start_epoch + 2 * EPOCHS_IN_DAY + 1,
+    );
+    publish_deals_expect_abort(&rt, &addrs, deal, ExitCode::USR_ILLEGAL_ARGUMENT);
+    check_state(&rt);
+}
+
+#[test]
+fn price_per_epoch_less_than_policy_minimum() {
+    let mut rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal = generate_deal_and_add_funds(&rt, CLIENT_ADDR, &addrs, 10, 10 + 200 * EPOCHS_IN_DAY);
+    rt.set_policy(Policy {
+        market_min_storage_price_per_epoch: &deal.storage_price_per_epoch
+            + TokenAmount::from_atto(1),
+        ..Policy::default()
+    });
+    publish_deals_expect_abort(&rt, &addrs, deal, ExitCode::USR_ILLEGAL_ARGUMENT);
+    check_state(&rt);
+}
+
+#[test]
+fn structurally_invalid_piece_cid() {
+    for piece_cid in make_invalid_piece_cids(b"commp") {
+        let f = move |_rt: &MockRuntime, d: &mut DealProposal| {
+            d.piece_cid = piece_cid;
+        };
+        assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    }
+}
+
 #[test]
 fn negative_price_per_epoch() {
     let f = |_rt: &MockRuntime, d: &mut DealProposal| {
@@ -325,38 +376,15 @@ fn fail_when_deals_have_different_providers() {
     expect_provider_is_control_address(&rt, PROVIDER_ADDR, WORKER_ADDR, true);
     expect_query_network_info(&rt);
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
-    let authenticate_param1 = IpldBlock::serialize_cbor(&AuthenticateMessageParams {
-        signature: buf1.to_vec(),
-        message: buf1.to_vec(),
-    })
-    .unwrap();
-    let authenticate_param2 = IpldBlock::serialize_cbor(&AuthenticateMessageParams {
-        signature: buf2.to_vec(),
-        message: buf2.to_vec(),
-    })
-    .unwrap();
-
-    rt.expect_send(
-        deal1.client,
-        AUTHENTICATE_MESSAGE_METHOD,
-        authenticate_param1,
-        TokenAmount::zero(),
-        None,
-        SendFlags::READ_ONLY,
-        AUTHENTICATE_MESSAGE_RESPONSE.clone(),
-        ExitCode::OK,
-        None,
-    );
-    rt.expect_send(
-        deal2.client,
-        AUTHENTICATE_MESSAGE_METHOD,
-        authenticate_param2,
-        TokenAmount::zero(),
-        None,
-        SendFlags::READ_ONLY,
-        AUTHENTICATE_MESSAGE_RESPONSE.clone(),
-        ExitCode::OK,
-        None,
+    // Both deals are from the same account client, so are authenticated in one batch.
+    expect_batch_authenticate(
+        &rt,
+        CLIENT_ADDR,
+        vec![
+            AuthenticateMessageParams { signature: buf1.to_vec(), message: buf1.to_vec() },
+            AuthenticateMessageParams { signature: buf2.to_vec(), message: buf2.to_vec() },
+        ],
+        BatchReturn::ok(2),
     );
 
     // only valid deals are notified
@@ -400,6 +428,98 @@ fn fail_when_deals_have_different_providers() {
     check_state(&rt);
 }
 
+#[test]
+fn drops_deals_failing_batch_authentication() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+
+    let rt = setup();
+    let st: State = rt.get_state();
+    let next_deal_id = st.next_id;
+
+    let deal1 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    let deal2 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch + 1,
+    );
+
+    let buf1 = RawBytes::serialize(deal1.clone()).expect("failed to marshal deal proposal");
+    let buf2 = RawBytes::serialize(deal2.clone()).expect("failed to marshal deal proposal");
+    let params = PublishStorageDealsParams {
+        deals: vec![
+            ClientDealProposal {
+                proposal: deal1.clone(),
+                client_signature: Signature::new_bls(buf1.to_vec()),
+            },
+            ClientDealProposal {
+                proposal: deal2.clone(),
+                client_signature: Signature::new_bls(buf2.to_vec()),
+            },
+        ],
+    };
+
+    rt.expect_validate_caller_any();
+    expect_provider_is_control_address(&rt, PROVIDER_ADDR, WORKER_ADDR, true);
+    expect_query_network_info(&rt);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    // The first signature is rejected by the client.
+    expect_batch_authenticate(
+        &rt,
+        CLIENT_ADDR,
+        vec![
+            AuthenticateMessageParams { signature: buf1.to_vec(), message: buf1.to_vec() },
+            AuthenticateMessageParams { signature: buf2.to_vec(), message: buf2.to_vec() },
+        ],
+        BatchReturn::of(&[ExitCode::USR_ILLEGAL_ARGUMENT, ExitCode::OK]),
+    );
+
+    rt.expect_send_simple(
+        deal2.client,
+        MARKET_NOTIFY_DEAL_METHOD,
+        IpldBlock::serialize_cbor(&MarketNotifyDealParams {
+            proposal: buf2.to_vec(),
+            deal_id: next_deal_id,
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    expect_emitted(
+        &rt,
+        "deal-published",
+        next_deal_id,
+        deal2.client.id().unwrap(),
+        deal2.provider.id().unwrap(),
+    );
+
+    let psd_ret: PublishStorageDealsReturn = rt
+        .call::<MarketActor>(
+            Method::PublishStorageDeals as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+
+    let valid: Vec<u64> = psd_ret.valid_deals.bounded_iter(u64::MAX).unwrap().collect();
+    assert_eq!(vec![1], valid);
+    assert_eq!(vec![next_deal_id], psd_ret.ids);
+
+    rt.verify();
+    check_state(&rt);
+}
+
 #[test]
 fn fail_when_no_deals_in_params() {
     let rt = setup();
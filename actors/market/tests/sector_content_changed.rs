@@ -72,14 +72,8 @@ fn simple_one_sector() {
         minimum_commitment_epoch: END_EPOCH + 10,
         added: pieces,
     }];
-    for deal_id in deal_ids.iter().rev() {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for (deal_id, deal) in deal_ids.iter().zip(&deals).rev() {
+        harness::expect_deal_activated(&rt, *deal_id, deal, sno);
     }
     let ret = sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
     assert_eq!(1, ret.sectors.len());
@@ -125,14 +119,8 @@ fn simple_multiple_sectors() {
             added: pieces[2..3].to_vec(),
         },
     ];
-    for deal_id in deal_ids.iter() {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for ((deal_id, deal), sector) in deal_ids.iter().zip(&deals).zip([1, 1, 2]) {
+        harness::expect_deal_activated(&rt, *deal_id, deal, sector);
     }
     let ret = sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
     assert_eq!(3, ret.sectors.len());
@@ -159,14 +147,8 @@ fn new_deal_existing_sector() {
         minimum_commitment_epoch: END_EPOCH + 10,
         added: pieces[1..3].to_vec(),
     }];
-    for deal_id in deal_ids[1..3].iter() {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for (deal_id, deal) in deal_ids[1..3].iter().zip(&deals[1..3]) {
+        harness::expect_deal_activated(&rt, *deal_id, deal, 1);
     }
     sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
 
@@ -175,14 +157,8 @@ fn new_deal_existing_sector() {
         minimum_commitment_epoch: END_EPOCH + 10,
         added: pieces[0..1].to_vec(),
     }];
-    for deal_id in deal_ids[0..1].iter() {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for (deal_id, deal) in deal_ids[0..1].iter().zip(&deals[0..1]) {
+        harness::expect_deal_activated(&rt, *deal_id, deal, 1);
     }
     sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
 
@@ -280,14 +256,8 @@ fn failures_isolated() {
     ];
 
     // only first and last pieces emit an event
-    for deal_id in [deal_ids.first().unwrap(), deal_ids.last().unwrap()] {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for (i, sector) in [(0, 1), (3, 3)] {
+        harness::expect_deal_activated(&rt, deal_ids[i], &deals[i], sector);
     }
     let ret = sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
     assert_eq!(3, ret.sectors.len());
@@ -323,14 +293,8 @@ fn rejects_duplicates_in_same_sector() {
             added: vec![pieces[0].clone(), pieces[0].clone(), pieces[1].clone()],
         },
     ];
-    for deal_id in deal_ids.iter() {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for (deal_id, deal) in deal_ids.iter().zip(&deals) {
+        harness::expect_deal_activated(&rt, *deal_id, deal, 1);
     }
     let ret = sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
     assert_eq!(1, ret.sectors.len());
@@ -377,14 +341,8 @@ fn rejects_duplicates_across_sectors() {
             added: vec![pieces[0].clone(), pieces[1].clone(), pieces[2].clone()],
         },
     ];
-    for deal_id in deal_ids.iter() {
-        harness::expect_emitted(
-            &rt,
-            "deal-activated",
-            *deal_id,
-            CLIENT_ADDR.id().unwrap(),
-            MINER_ADDRESSES.provider.id().unwrap(),
-        );
+    for ((deal_id, deal), sector) in deal_ids.iter().zip(&deals).zip([1, 1, 2]) {
+        harness::expect_deal_activated(&rt, *deal_id, deal, sector);
     }
     let ret = sector_content_changed(&rt, PROVIDER_ADDR, changes).unwrap();
     assert_eq!(3, ret.sectors.len());
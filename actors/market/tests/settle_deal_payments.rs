@@ -5,6 +5,7 @@ use fvm_shared::error::ExitCode;
 use fil_actor_market::{DealSettlementSummary, EX_DEAL_EXPIRED};
 use fil_actors_runtime::BURNT_FUNDS_ACTOR_ADDR;
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::runtime::Runtime;
 use harness::*;
 
 mod harness;
@@ -289,3 +290,71 @@ fn batch_settlement_of_deals_allows_partial_success() {
             - unactivated_slashed)
     );
 }
+
+#[test]
+fn preview_matches_settlement_without_changing_state() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let sector_number = 7;
+    let settlement_epoch = END_EPOCH - 1;
+
+    let (continuing_id, continuing_proposal) = publish_and_activate_deal(
+        &rt,
+        CLIENT_ADDR,
+        &addrs,
+        sector_number,
+        START_EPOCH,
+        END_EPOCH,
+        0,
+        END_EPOCH,
+    );
+    let (finished_id, finished_proposal) = publish_and_activate_deal(
+        &rt,
+        CLIENT_ADDR,
+        &addrs,
+        sector_number,
+        START_EPOCH,
+        settlement_epoch,
+        0,
+        END_EPOCH,
+    );
+    let (unactivated_id, unactivated_proposal) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH + 2, END_EPOCH);
+    let deal_ids = [continuing_id, finished_id, unactivated_id, 9999];
+
+    rt.set_epoch(settlement_epoch);
+    let state_before = rt.get_state_root().unwrap();
+    let preview = settle_deal_payments_preview(&rt, addrs.provider, &deal_ids);
+    assert_eq!(state_before, rt.get_state_root().unwrap());
+
+    let continuing_payment = &continuing_proposal.storage_price_per_epoch
+        * (settlement_epoch - continuing_proposal.start_epoch);
+    let finished_payment = &finished_proposal.storage_price_per_epoch
+        * (settlement_epoch - finished_proposal.start_epoch);
+    assert_eq!(
+        &[ExitCode::OK, ExitCode::OK, EX_DEAL_EXPIRED, EX_DEAL_EXPIRED],
+        preview.results.codes().as_slice()
+    );
+    assert_eq!(
+        vec![
+            DealSettlementSummary { completed: false, payment: continuing_payment.clone() },
+            DealSettlementSummary { completed: true, payment: finished_payment.clone() },
+        ],
+        preview.settlements
+    );
+    assert_eq!(continuing_payment + finished_payment, preview.total_payment);
+
+    // Settling then transfers exactly the previewed amounts.
+    rt.expect_send_simple(
+        BURNT_FUNDS_ACTOR_ADDR,
+        METHOD_SEND,
+        None,
+        unactivated_proposal.provider_collateral.clone(),
+        None,
+        ExitCode::OK,
+    );
+    let ret = settle_deal_payments(&rt, addrs.provider, &deal_ids, &[finished_id], &[]);
+    assert_eq!(preview.results, ret.results);
+    assert_eq!(preview.settlements, ret.settlements);
+    check_state(&rt);
+}
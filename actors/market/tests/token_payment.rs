@@ -0,0 +1,327 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use frc46_token::receiver::{FRC46_TOKEN_TYPE, FRC46TokenReceived};
+use frc46_token::token::types::TransferParams;
+use fvm_actor_utils::receiver::UniversalReceiverParams;
+use fvm_ipld_encoding::RawBytes;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::ActorID;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use num_traits::Zero;
+
+use fil_actor_market::{
+    Actor as MarketActor, AddBalanceParams, DealAcceptancePolicy, DealProposal, GetBalanceReturn,
+    GetTokenBalanceParams, Method, WithdrawBalanceReturn, WithdrawTokenBalanceParams, ext,
+};
+use fil_actors_runtime::STORAGE_MARKET_ACTOR_ID;
+use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use harness::*;
+
+mod harness;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+
+const TOKEN_ID: ActorID = 300;
+const TOKEN_ADDR: Address = Address::new_id(TOKEN_ID);
+
+#[test]
+fn deposit_and_withdraw_tokens() {
+    let rt = setup_with_token();
+    let addrs = MinerAddresses::default();
+
+    // Tokens are credited to the sender, or the address named in the operator data.
+    deposit_tokens(&rt, CLIENT_ID, None, TokenAmount::from_atto(1000));
+    expect_provider_control_address(&rt, addrs.provider, addrs.owner, addrs.worker);
+    deposit_tokens(&rt, OWNER_ID, Some(addrs.provider), TokenAmount::from_atto(500));
+    assert_eq!(token_balance(&rt, CLIENT_ADDR).balance, TokenAmount::from_atto(1000));
+    assert_eq!(token_balance(&rt, addrs.provider).balance, TokenAmount::from_atto(500));
+    // FIL escrow is unaffected.
+    assert!(get_balance(&rt, &CLIENT_ADDR).balance.is_zero());
+
+    let withdrawn = withdraw_tokens(&rt, CLIENT_ADDR, TokenAmount::from_atto(400));
+    assert_eq!(TokenAmount::from_atto(400), withdrawn);
+    // Withdrawal is limited to the available balance.
+    let withdrawn = withdraw_tokens(&rt, CLIENT_ADDR, TokenAmount::from_atto(1000));
+    assert_eq!(TokenAmount::from_atto(600), withdrawn);
+    assert!(token_balance(&rt, CLIENT_ADDR).balance.is_zero());
+    check_state(&rt);
+}
+
+#[test]
+fn rejects_invalid_deposit() {
+    let rt = setup_with_token();
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, TOKEN_ADDR);
+    rt.expect_validate_caller_any();
+    let params = UniversalReceiverParams {
+        type_: FRC46_TOKEN_TYPE,
+        payload: serialize(&received(CLIENT_ID, 99, None, TokenAmount::from_atto(10)), "payload")
+            .unwrap(),
+    };
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, call_receive(&rt, params));
+    rt.reset();
+
+    rt.expect_validate_caller_any();
+    let params = UniversalReceiverParams {
+        type_: FRC46_TOKEN_TYPE,
+        payload: serialize(
+            &received(CLIENT_ID, STORAGE_MARKET_ACTOR_ID, None, TokenAmount::zero()),
+            "payload",
+        )
+        .unwrap(),
+    };
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, call_receive(&rt, params));
+    rt.reset();
+    check_state(&rt);
+}
+
+#[test]
+fn storage_fee_paid_in_token() {
+    let rt = setup_with_token();
+    let addrs = MinerAddresses::default();
+    let deal = token_deal(&addrs);
+    let fee = deal.total_storage_fee();
+    add_provider_funds(&rt, deal.provider_collateral.clone(), &addrs);
+    add_participant_funds(&rt, CLIENT_ADDR, deal.client_balance_requirement());
+    deposit_tokens(&rt, CLIENT_ID, None, fee.clone());
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    let deal_ids = publish_deals(&rt, &addrs, &[deal.clone()], TokenAmount::zero(), 1);
+    let deal_id = deal_ids[0];
+
+    // Only collateral is locked in FIL, and the fee in the token.
+    assert_eq!(deal.client_collateral, get_balance(&rt, &CLIENT_ADDR).locked);
+    assert_eq!(fee, token_balance(&rt, CLIENT_ADDR).locked);
+    check_state(&rt);
+
+    activate_deals(&rt, END_EPOCH + 10, addrs.provider, 0, 1, &deal_ids);
+    rt.set_epoch(START_EPOCH + 100);
+    let ret = settle_deal_payments(&rt, addrs.provider, &[deal_id], &[], &[]);
+    let payment = &deal.storage_price_per_epoch * 100;
+    assert_eq!(payment, ret.settlements[0].payment);
+
+    // The payment moves tokens to the provider, leaving FIL balances unchanged.
+    let client_tokens = token_balance(&rt, CLIENT_ADDR);
+    assert_eq!(&fee - &payment, client_tokens.balance);
+    assert_eq!(&fee - &payment, client_tokens.locked);
+    assert_eq!(payment, token_balance(&rt, addrs.provider).balance);
+    assert_eq!(deal.client_balance_requirement(), get_balance(&rt, &CLIENT_ADDR).balance);
+    assert_eq!(deal.provider_collateral, get_balance(&rt, &addrs.provider).balance);
+    check_state(&rt);
+}
+
+#[test]
+fn token_deal_dropped_without_token_funds() {
+    let rt = setup_with_token();
+    let addrs = MinerAddresses::default();
+    let deal = token_deal(&addrs);
+    add_provider_funds(&rt, deal.provider_collateral.clone(), &addrs);
+    // Enough FIL to cover the fee does not fund a deal paid in the token.
+    add_participant_funds(
+        &rt,
+        CLIENT_ADDR,
+        deal.client_collateral.clone() + deal.total_storage_fee(),
+    );
+    deposit_tokens(&rt, CLIENT_ID, None, deal.total_storage_fee() - TokenAmount::from_atto(1));
+
+    publish_deals_expect_abort(&rt, &addrs, deal, ExitCode::USR_ILLEGAL_ARGUMENT);
+    check_state(&rt);
+}
+
+#[test]
+fn tokens_not_accepted_for_payment_are_rejected() {
+    // No token is accepted by default.
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, TOKEN_ADDR);
+    rt.expect_validate_caller_any();
+    let params = UniversalReceiverParams {
+        type_: FRC46_TOKEN_TYPE,
+        payload: serialize(
+            &received(CLIENT_ID, STORAGE_MARKET_ACTOR_ID, None, TokenAmount::from_atto(10)),
+            "payload",
+        )
+        .unwrap(),
+    };
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "is not accepted for payment",
+        call_receive(&rt, params),
+    );
+    rt.reset();
+
+    // A deal paid in the token is dropped, even with funds in FIL.
+    let deal = token_deal(&addrs);
+    add_provider_funds(&rt, deal.provider_collateral.clone(), &addrs);
+    add_participant_funds(
+        &rt,
+        CLIENT_ADDR,
+        deal.client_collateral.clone() + deal.total_storage_fee(),
+    );
+    publish_deals_expect_abort(&rt, &addrs, deal, ExitCode::USR_ILLEGAL_ARGUMENT);
+    check_state(&rt);
+}
+
+#[test]
+fn provider_minimum_price_does_not_apply_to_token_deals() {
+    let addrs = MinerAddresses::default();
+    let deal = token_deal(&addrs);
+    let policy = DealAcceptancePolicy {
+        min_price_per_epoch: &deal.storage_price_per_epoch + TokenAmount::from_atto(1),
+        ..Default::default()
+    };
+    // The minimum is in FIL, so it is not compared with a price in another token.
+    policy.check(&deal).unwrap();
+
+    let fil_deal = DealProposal { payment_token: None, ..deal };
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "below provider minimum",
+        policy.check(&fil_deal),
+    );
+}
+
+// Sets up a market which accepts payment in the test token.
+fn setup_with_token() -> MockRuntime {
+    let mut rt = setup();
+    rt.policy.market_payment_tokens = vec![TOKEN_ID];
+    rt
+}
+
+#[test]
+fn fil_proposal_encoding_omits_payment_token() {
+    let addrs = MinerAddresses::default();
+    let fil_deal = generate_deal_proposal(CLIENT_ADDR, addrs.provider, START_EPOCH, END_EPOCH);
+    let token_deal = token_deal(&addrs);
+
+    // A proposal paid in FIL encodes as before the payment token was introduced.
+    let fil_bytes = serialize(&fil_deal, "proposal").unwrap();
+    assert_eq!(0x8b, fil_bytes.bytes()[0]); // array of 11 elements
+    let token_bytes = serialize(&token_deal, "proposal").unwrap();
+    assert_eq!(0x8c, token_bytes.bytes()[0]); // array of 12 elements
+
+    assert_eq!(fil_deal, fvm_ipld_encoding::from_slice::<DealProposal>(fil_bytes.bytes()).unwrap());
+    assert_eq!(
+        token_deal,
+        fvm_ipld_encoding::from_slice::<DealProposal>(token_bytes.bytes()).unwrap()
+    );
+}
+
+fn token_deal(addrs: &MinerAddresses) -> DealProposal {
+    DealProposal {
+        payment_token: Some(TOKEN_ADDR),
+        ..generate_deal_proposal(CLIENT_ADDR, addrs.provider, START_EPOCH, END_EPOCH)
+    }
+}
+
+fn received(
+    from: ActorID,
+    to: ActorID,
+    beneficiary: Option<Address>,
+    amount: TokenAmount,
+) -> FRC46TokenReceived {
+    let operator_data = match beneficiary {
+        Some(addr) => {
+            serialize(&AddBalanceParams { provider_or_client: addr }, "operator data").unwrap()
+        }
+        None => RawBytes::default(),
+    };
+    FRC46TokenReceived {
+        from,
+        to,
+        operator: from,
+        amount,
+        operator_data,
+        token_data: RawBytes::default(),
+    }
+}
+
+fn call_receive(
+    rt: &MockRuntime,
+    params: UniversalReceiverParams,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.call::<MarketActor>(
+        Method::UniversalReceiverHook as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+}
+
+fn deposit_tokens(
+    rt: &MockRuntime,
+    from: ActorID,
+    beneficiary: Option<Address>,
+    amount: TokenAmount,
+) {
+    rt.set_caller(*EVM_ACTOR_CODE_ID, TOKEN_ADDR);
+    rt.expect_validate_caller_any();
+    let params = UniversalReceiverParams {
+        type_: FRC46_TOKEN_TYPE,
+        payload: serialize(
+            &received(from, STORAGE_MARKET_ACTOR_ID, beneficiary, amount),
+            "payload",
+        )
+        .unwrap(),
+    };
+    assert!(call_receive(rt, params).unwrap().is_none());
+    rt.verify();
+}
+
+fn withdraw_tokens(rt: &MockRuntime, addr: Address, amount: TokenAmount) -> TokenAmount {
+    let expected = std::cmp::min(token_balance(rt, addr).balance, amount.clone());
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addr);
+    rt.expect_validate_caller_addr(vec![addr]);
+    rt.expect_send_simple(
+        TOKEN_ADDR,
+        ext::token::TRANSFER_METHOD,
+        IpldBlock::serialize_cbor(&TransferParams {
+            to: addr,
+            amount: expected,
+            operator_data: RawBytes::default(),
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    let ret: WithdrawBalanceReturn = rt
+        .call::<MarketActor>(
+            Method::WithdrawTokenBalanceExported as u64,
+            IpldBlock::serialize_cbor(&WithdrawTokenBalanceParams {
+                token: TOKEN_ADDR,
+                provider_or_client: addr,
+                amount,
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.amount_withdrawn
+}
+
+fn token_balance(rt: &MockRuntime, account: Address) -> GetBalanceReturn {
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<MarketActor>(
+            Method::GetTokenBalanceExported as u64,
+            IpldBlock::serialize_cbor(&GetTokenBalanceParams { token: TOKEN_ADDR, account })
+                .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
@@ -81,6 +81,13 @@ fn deal_scheduled_for_termination_cannot_be_settled_manually() {
         &[deal_id_1, slashed_deal],
         ExitCode::USR_ILLEGAL_ARGUMENT,
     );
+    // previewing the settlement fails in the same way
+    settle_deal_payments_preview_expect_abort(
+        &rt,
+        PROVIDER_ADDR,
+        &[deal_id_1, slashed_deal],
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+    );
 
     // advance cron to scheduled time and terminate it via cron
     rt.set_epoch(scheduled_epoch);
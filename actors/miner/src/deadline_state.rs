@@ -951,6 +951,23 @@ impl Deadline {
         fault_expiration_epoch: ChainEpoch,
         sectors: Cid,
     ) -> Result<(PowerPair, PowerPair), ActorError> {
+        self.process_deadline_end_with(store, quant, || Ok(fault_expiration_epoch), sectors)
+    }
+
+    /// Like process_deadline_end, but computes the fault expiration epoch only if
+    /// some partition missed its PoSt with faults to record.
+    pub fn process_deadline_end_with<BS, F>(
+        &mut self,
+        store: &BS,
+        quant: QuantSpec,
+        mut fault_expiration: F,
+        sectors: Cid,
+    ) -> Result<(PowerPair, PowerPair), ActorError>
+    where
+        BS: Blockstore,
+        F: FnMut() -> Result<ChainEpoch, ActorError>,
+    {
+        let mut fault_expiration_epoch = None;
         let mut partitions = self.partitions_amt(store).map_err(|e| {
             e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load partitions")
         })?;
@@ -987,7 +1004,12 @@ impl Deadline {
 
             // Ok, we actually need to process this partition. Make sure we save the partition state back.
             detected_any = true;
+            partition.post_stats.record(false);
 
+            let fault_expiration_epoch = match fault_expiration_epoch {
+                Some(epoch) => epoch,
+                None => *fault_expiration_epoch.insert(fault_expiration()?),
+            };
             let (part_power_delta, part_penalized_power, part_new_faulty_power) = partition
                 .record_missed_post(store, fault_expiration_epoch, quant)
                 .map_err(|e| {
@@ -1270,6 +1292,9 @@ impl Deadline {
                 })?;
 
             new_power_delta += &partition.activate_unproven();
+            if !partition.live_power.is_zero() {
+                partition.post_stats.record(true);
+            }
 
             // note: we do this first because `partition` is moved in the upcoming `partitions.set` call
             // At this point, the partition faults represents the expected faults for the proof, with new skipped
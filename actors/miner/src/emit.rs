@@ -1,6 +1,7 @@
 use cid::Cid;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::SectorNumber;
 
 /// Indicates a sector has been pre-committed.
@@ -47,6 +48,30 @@ pub fn sector_terminated(rt: &impl Runtime, sector: SectorNumber) -> Result<(),
     )
 }
 
+/// Indicates the miner's pledge collateral (initial pledge and locked rewards) has changed by
+/// some (non-zero) amount, positive when pledge is added and negative when released.
+pub fn pledge_changed(rt: &impl Runtime, delta: &TokenAmount) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new().typ("pledge-delta").field_bigint("delta", delta.atto()).build()?,
+    )
+}
+
+/// Indicates the miner's fee debt has changed by some (non-zero) amount,
+/// positive when debt is incurred and negative when repaid, to a new total.
+pub fn fee_debt_changed(
+    rt: &impl Runtime,
+    delta: &TokenAmount,
+    fee_debt: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("fee-debt")
+            .field_bigint("delta", delta.atto())
+            .field_bigint("fee-debt", fee_debt.atto())
+            .build()?,
+    )
+}
+
 trait WithSectorInfo {
     fn with_sector_info(
         self,
@@ -1,17 +1,15 @@
 use cid::Cid;
-use fvm_ipld_encoding::RawBytes;
+use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::ActorID;
 use fvm_shared::bigint::{BigInt, bigint_ser};
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::deal::DealID;
-use fvm_shared::econ::TokenAmount;
 use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::sector::RegisteredSealProof;
 use fvm_shared::sector::SectorNumber;
-use fvm_shared::sector::{RegisteredSealProof, StoragePower};
 
 use fil_actors_runtime::BatchReturn;
-use fil_actors_runtime::reward::FilterEstimate;
 
 pub mod account {
     pub const PUBKEY_ADDRESS_METHOD: u64 = 2;
@@ -85,38 +83,18 @@ pub mod market {
 }
 
 pub mod power {
-    use super::*;
-
     pub const UPDATE_CLAIMED_POWER_METHOD: u64 = 3;
     pub const ENROLL_CRON_EVENT_METHOD: u64 = 4;
     pub const UPDATE_PLEDGE_TOTAL_METHOD: u64 = 6;
     pub const SUBMIT_POREP_FOR_BULK_VERIFY_METHOD: u64 = 8;
     pub const CURRENT_TOTAL_POWER_METHOD: u64 = 9;
+    pub const GET_EXPERIMENTAL_SEAL_PROOFS_METHOD: u64 =
+        frc42_dispatch::method_hash!("GetExperimentalSealProofs");
 
-    #[derive(Serialize_tuple, Deserialize_tuple)]
-    pub struct CurrentTotalPowerReturn {
-        #[serde(with = "bigint_ser")]
-        pub raw_byte_power: StoragePower,
-        #[serde(with = "bigint_ser")]
-        pub quality_adj_power: StoragePower,
-        pub pledge_collateral: TokenAmount,
-        pub quality_adj_power_smoothed: FilterEstimate,
-        pub ramp_start_epoch: i64,
-        pub ramp_duration_epochs: u64,
-    }
-    #[derive(Serialize_tuple, Deserialize_tuple)]
-    pub struct EnrollCronEventParams {
-        pub event_epoch: ChainEpoch,
-        pub payload: RawBytes,
-    }
-
-    #[derive(Serialize_tuple, Deserialize_tuple)]
-    pub struct UpdateClaimedPowerParams {
-        #[serde(with = "bigint_ser")]
-        pub raw_byte_delta: StoragePower,
-        #[serde(with = "bigint_ser")]
-        pub quality_adjusted_delta: StoragePower,
-    }
+    pub use fil_actors_runtime::builtin::power::{
+        CurrentTotalPowerReturn, EnrollCronEventParams, GetExperimentalSealProofsReturn,
+        UpdateClaimedPowerParams,
+    };
 
     pub const MAX_MINER_PROVE_COMMITS_PER_EPOCH: usize = 200;
 }
@@ -152,6 +130,9 @@ pub mod verifreg {
         pub term_start: ChainEpoch,
         // ID of the provider's sector in which the data is committed.
         pub sector: SectorNumber,
+        // Commitment to the claim's immutable fields, empty for claims which predate it.
+        #[serde(default, with = "strict_bytes")]
+        pub commitment: Vec<u8>,
     }
     #[derive(Debug, Serialize_tuple, Deserialize_tuple)]
     pub struct GetClaimsParams {
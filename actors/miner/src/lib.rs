@@ -58,6 +58,7 @@ use fil_actors_runtime::{
 };
 pub use monies::*;
 pub use partition_state::*;
+pub use pledge_facility::*;
 pub use policy::*;
 pub use quantize::*;
 pub use sector_map::*;
@@ -91,6 +92,7 @@ pub mod ext;
 mod monies;
 mod notifications;
 mod partition_state;
+mod pledge_facility;
 mod policy;
 mod quantize;
 mod sector_map;
@@ -160,6 +162,36 @@ pub enum Method {
     GetMultiaddrsExported = frc42_dispatch::method_hash!("GetMultiaddrs"),
     MaxTerminationFeeExported = frc42_dispatch::method_hash!("MaxTerminationFee"),
     InitialPledgeExported = frc42_dispatch::method_hash!("InitialPledge"),
+    GetDeadlineTimingExported = frc42_dispatch::method_hash!("GetDeadlineTiming"),
+    OnClaimRevokedExported = frc42_dispatch::method_hash!("OnClaimRevoked"),
+    GetPendingBeneficiaryChangeExported =
+        frc42_dispatch::method_hash!("GetPendingBeneficiaryChange"),
+    GetSectorsExported = frc42_dispatch::method_hash!("GetSectors"),
+    GetDeadlineSummariesExported = frc42_dispatch::method_hash!("GetDeadlineSummaries"),
+    GetPartitionSummariesExported = frc42_dispatch::method_hash!("GetPartitionSummaries"),
+    ExtendSectorExpirationsByClassExported =
+        frc42_dispatch::method_hash!("ExtendSectorExpirationsByClass"),
+    AuthorizePledgeLenderExported = frc42_dispatch::method_hash!("AuthorizePledgeLender"),
+    DepositPledgeFacilityExported = frc42_dispatch::method_hash!("DepositPledgeFacility"),
+    WithdrawPledgeFacilityExported = frc42_dispatch::method_hash!("WithdrawPledgeFacility"),
+    GetPledgeFacilityExported = frc42_dispatch::method_hash!("GetPledgeFacility"),
+    GetPendingWorkerKeyChangeExported = frc42_dispatch::method_hash!("GetPendingWorkerKeyChange"),
+    CancelChangeWorkerAddressExported = frc42_dispatch::method_hash!("CancelChangeWorkerAddress"),
+    EstimateTerminationFeeExported = frc42_dispatch::method_hash!("EstimateTerminationFee"),
+    GetSectorEconomicsExported = frc42_dispatch::method_hash!("GetSectorEconomics"),
+    GetPartitionPoStStatsExported = frc42_dispatch::method_hash!("GetPartitionPoStStats"),
+    SetFaultTerminationThresholdExported =
+        frc42_dispatch::method_hash!("SetFaultTerminationThreshold"),
+    SimulatePenaltyExported = frc42_dispatch::method_hash!("SimulatePenalty"),
+    DeclareFaults2Exported = frc42_dispatch::method_hash!("DeclareFaults2"),
+    DeclareFaultsRecovered2Exported = frc42_dispatch::method_hash!("DeclareFaultsRecovered2"),
+    GetCompactablePartitionsExported = frc42_dispatch::method_hash!("GetCompactablePartitions"),
+    ChangeWorkerAddress2Exported = frc42_dispatch::method_hash!("ChangeWorkerAddress2"),
+    SetSectorMetadataExported = frc42_dispatch::method_hash!("SetSectorMetadata"),
+    GetSectorMetadataExported = frc42_dispatch::method_hash!("GetSectorMetadata"),
+    GetPowerBreakdownExported = frc42_dispatch::method_hash!("GetPowerBreakdown"),
+    GetAvailableBalanceBreakdownExported =
+        frc42_dispatch::method_hash!("GetAvailableBalanceBreakdown"),
 }
 
 pub const SECTOR_CONTENT_CHANGED: MethodNum = frc42_dispatch::method_hash!("SectorContentChanged");
@@ -248,7 +280,8 @@ impl Actor {
         Ok(())
     }
 
-    /// Returns the "controlling" addresses: the owner, the worker, and all control addresses
+    /// Returns the "controlling" addresses: the owner, the worker, and all control addresses,
+    /// along with the roles of the control addresses.
     fn control_addresses(rt: &impl Runtime) -> Result<GetControlAddressesReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let state: State = rt.state()?;
@@ -257,6 +290,7 @@ impl Actor {
             owner: info.owner,
             worker: info.worker,
             control_addresses: info.control_addresses,
+            control_address_roles: info.control_address_roles,
         })
     }
 
@@ -269,7 +303,9 @@ impl Actor {
     }
 
     /// Returns whether the provided address is "controlling".
-    /// The "controlling" addresses are the Owner, the Worker, and all Control Addresses.
+    /// The "controlling" addresses are the Owner, the Worker, and all Control Addresses
+    /// unrestricted by a role. Role-restricted control addresses may act only in their role,
+    /// so are not controlling.
     fn is_controlling_address(
         rt: &impl Runtime,
         params: IsControllingAddressParam,
@@ -281,8 +317,10 @@ impl Actor {
         };
         let state: State = rt.state()?;
         let info = get_miner_info(rt.store(), &state)?;
-        let is_controlling =
-            info.control_addresses.iter().chain(&[info.worker, info.owner]).any(|a| *a == input);
+        let is_controlling = info
+            .control_addresses_for(ControlAddressRole::Any)
+            .chain(&[info.worker, info.owner])
+            .any(|a| *a == input);
 
         Ok(IsControllingAddressReturn { is_controlling })
     }
@@ -308,6 +346,31 @@ impl Actor {
         Ok(GetAvailableBalanceReturn { available_balance })
     }
 
+    /// Returns the miner's balance broken down into the classes of funds that make it up,
+    /// as of the current epoch.
+    /// The vesting funds, pledge, pre-commit deposits, pledge facility and available balance
+    /// sum to the actor balance, with fee debt deducted from the available balance.
+    fn get_available_balance_breakdown(
+        rt: &impl Runtime,
+    ) -> Result<GetAvailableBalanceBreakdownReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let balance = rt.current_balance();
+        let available = state.get_available_balance(&balance).map_err(|e| {
+            actor_error!(illegal_state, "failed to calculate available balance: {}", e)
+        })?;
+        Ok(GetAvailableBalanceBreakdownReturn {
+            epoch: rt.curr_epoch(),
+            pledge_facility: state.pledge_facility_balance(),
+            balance,
+            available,
+            vesting: state.locked_funds,
+            initial_pledge: state.initial_pledge,
+            pre_commit_deposits: state.pre_commit_deposits,
+            fee_debt: state.fee_debt,
+        })
+    }
+
     /// Returns the funds vesting in this miner as a list of (vesting_epoch, vesting_amount) tuples.
     fn get_vesting_funds(rt: &impl Runtime) -> Result<GetVestingFundsReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
@@ -321,26 +384,78 @@ impl Actor {
         Ok(GetVestingFundsReturn { vesting_funds })
     }
 
+    /// Simulates applying a penalty at the current epoch, returning how much of the vesting
+    /// funds would be unlocked to pay it, how much would be burnt and the resulting fee debt.
+    /// State is not modified.
+    fn simulate_penalty(
+        rt: &impl Runtime,
+        params: SimulatePenaltyParams,
+    ) -> Result<SimulatePenaltyReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.penalty.is_negative() {
+            return Err(actor_error!(
+                illegal_argument,
+                "penalty must be non-negative, was {}",
+                params.penalty
+            ));
+        }
+        let state: State = rt.state()?;
+        let (vested_unlocked, unvested_unlocked, burnt, fee_debt) = state
+            .simulate_penalty(rt.store(), rt.curr_epoch(), &rt.current_balance(), &params.penalty)
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to simulate penalty")
+            })?;
+        Ok(SimulatePenaltyReturn { vested_unlocked, unvested_unlocked, burnt, fee_debt })
+    }
+
     /// Will ALWAYS overwrite the existing control addresses with the control addresses passed in the params.
     /// If an empty addresses vector is passed, the control addresses will be cleared.
     /// A worker change will be scheduled if the worker passed in the params is different from the existing worker.
     fn change_worker_address(
         rt: &impl Runtime,
         params: ChangeWorkerAddressParams,
+    ) -> Result<(), ActorError> {
+        let new_control_addresses = params
+            .new_control_addresses
+            .into_iter()
+            .map(|address| ControlAddressWithRole { address, role: ControlAddressRole::Any })
+            .collect();
+        Self::change_worker_address2(
+            rt,
+            ChangeWorkerAddress2Params { new_worker: params.new_worker, new_control_addresses },
+        )
+    }
+
+    /// As change_worker_address, but tags each control address with a role restricting
+    /// the methods it may call.
+    fn change_worker_address2(
+        rt: &impl Runtime,
+        params: ChangeWorkerAddress2Params,
     ) -> Result<(), ActorError> {
         check_control_addresses(rt.policy(), &params.new_control_addresses)?;
 
         let new_worker = Address::new_id(resolve_worker_address(rt, params.new_worker)?);
         let control_addresses: Vec<Address> = params
             .new_control_addresses
-            .into_iter()
-            .map(|address| {
-                rt.resolve_address(&address).ok_or_else(|| {
-                    actor_error!(illegal_argument, "unable to resolve control address: {}", address)
+            .iter()
+            .map(|control| {
+                rt.resolve_address(&control.address).ok_or_else(|| {
+                    actor_error!(
+                        illegal_argument,
+                        "unable to resolve control address: {}",
+                        control.address
+                    )
                 })
             })
             .map(|id_result| id_result.map(Address::new_id))
             .collect::<Result<_, _>>()?;
+        // Roles are only recorded if some control address is restricted.
+        let control_address_roles: Vec<ControlAddressRole> =
+            if params.new_control_addresses.iter().all(|c| c.role == ControlAddressRole::Any) {
+                vec![]
+            } else {
+                params.new_control_addresses.iter().map(|c| c.role).collect()
+            };
 
         rt.transaction(|state: &mut State, rt| {
             let mut info = get_miner_info(rt.store(), state)?;
@@ -350,6 +465,7 @@ impl Actor {
 
             // save the new control addresses
             info.control_addresses = control_addresses;
+            info.control_address_roles = control_address_roles;
 
             // save new_worker addr key change request
             if new_worker != info.worker && info.pending_worker_key.is_none() {
@@ -382,6 +498,77 @@ impl Actor {
         })
     }
 
+    /// Cancels a requested worker address change before it takes effect.
+    fn cancel_change_worker_address(rt: &impl Runtime) -> Result<(), ActorError> {
+        rt.transaction(|state: &mut State, rt| {
+            let mut info = get_miner_info(rt.store(), state)?;
+
+            // Only the Owner is allowed to change the worker.
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+
+            let pending = info
+                .pending_worker_key
+                .take()
+                .ok_or_else(|| actor_error!(illegal_argument, "no pending worker key change"))?;
+            if rt.curr_epoch() >= pending.effective_at {
+                return Err(actor_error!(
+                    forbidden,
+                    "worker key change to {} took effect at epoch {}",
+                    pending.new_worker,
+                    pending.effective_at
+                ));
+            }
+
+            state.save_info(rt.store(), &info).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "could not save miner info")
+            })?;
+
+            Ok(())
+        })
+    }
+
+    /// Sets or clears the number of epochs a sector may remain faulty before it is terminated.
+    /// The threshold applies to sectors faulted after it is set, and may not exceed the network's
+    /// fault max age.
+    fn set_fault_termination_threshold(
+        rt: &impl Runtime,
+        params: SetFaultTerminationThresholdParams,
+    ) -> Result<(), ActorError> {
+        let policy = rt.policy();
+        if let Some(threshold) = params.threshold {
+            if threshold < policy.wpost_proving_period || threshold > policy.fault_max_age {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "fault termination threshold {} must be between {} and {}",
+                    threshold,
+                    policy.wpost_proving_period,
+                    policy.fault_max_age
+                ));
+            }
+        }
+
+        rt.transaction(|state: &mut State, rt| {
+            let mut info = get_miner_info(rt.store(), state)?;
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+
+            info.fault_termination_threshold = params.threshold;
+            state.save_info(rt.store(), &info).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "could not save miner info")
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Returns the requested worker address change, if any, and the epoch from which it takes effect.
+    fn get_pending_worker_key_change(
+        rt: &impl Runtime,
+    ) -> Result<GetPendingWorkerKeyChangeReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &st)?;
+        Ok(GetPendingWorkerKeyChangeReturn { pending: info.pending_worker_key })
+    }
+
     /// Proposes or confirms a change of owner address.
     /// If invoked by the current owner, proposes a new owner address for confirmation. If the proposed address is the
     /// current owner address, revokes any existing proposal.
@@ -423,6 +610,7 @@ impl Actor {
                 }
                 // Cancel pending beneficiary term change when the owner changes
                 info.pending_beneficiary_term = None;
+                info.pending_beneficiary_expiration = None;
 
                 // Set the new owner address
                 info.owner = pending_address;
@@ -459,7 +647,8 @@ impl Actor {
             let mut info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             info.peer_id = params.new_id;
@@ -491,7 +680,8 @@ impl Actor {
             let mut info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             info.multi_address = params.new_multi_addrs;
@@ -548,7 +738,7 @@ impl Actor {
             }
         }
 
-        let post_result = rt.transaction(|state: &mut State, rt| {
+        let (post_result, fee_to_burn) = rt.transaction(|state: &mut State, rt| {
             let info = get_miner_info(rt.store(), state)?;
 
             let max_proof_size = info.window_post_proof_type.proof_size().map_err(|e| {
@@ -556,7 +746,8 @@ impl Actor {
             })?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::PoSt)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             // Make sure the miner is using the correct proof type.
@@ -658,6 +849,51 @@ impl Actor {
 
             let mut deadline = deadlines.load_deadline(rt.store(), params.deadline)?;
 
+            // Declare faults in the proven partitions recovered, as if by a prior DeclareFaultsRecovered,
+            // so that the proof restores their power.
+            let mut fee_to_burn = TokenAmount::zero();
+            if params.recover_faults {
+                if consensus_fault_active(&info, current_epoch) {
+                    return Err(actor_error!(
+                        forbidden,
+                        "recovery not allowed during active consensus fault"
+                    ));
+                }
+
+                // Verify unlocked funds cover both InitialPledgeRequirement and FeeDebt
+                // and repay fee debt now.
+                fee_to_burn = repay_debts_or_abort(rt, state)?;
+
+                let mut recoveries = PartitionSectorMap::default();
+                for post in &params.partitions {
+                    let partition = deadline.load_partition(rt.store(), post.index)?;
+                    recoveries.add(post.index, partition.faults.clone()).map_err(|e| {
+                        actor_error!(
+                            illegal_argument,
+                            "failed to process recoveries for partition {}: {}",
+                            post.index,
+                            e
+                        )
+                    })?;
+                }
+                deadline
+                    .declare_faults_recovered(
+                        rt.store(),
+                        &sectors,
+                        info.sector_size,
+                        &mut recoveries,
+                    )
+                    .map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!(
+                                "failed to declare recoveries for deadline {}",
+                                params.deadline
+                            ),
+                        )
+                    })?;
+            }
+
             // Record proven sectors/partitions, returning updates to power and the final set of sectors
             // proven/skipped.
             //
@@ -668,7 +904,7 @@ impl Actor {
             // While we could perform _all_ operations at the end of challenge window, we do as we can here to avoid
             // overloading cron.
             let policy = rt.policy();
-            let fault_expiration = current_deadline.last() + policy.fault_max_age;
+            let fault_expiration = current_deadline.last() + info.fault_max_age(policy);
             let post_result = deadline
                 .record_proven_sectors(
                     rt.store(),
@@ -748,7 +984,7 @@ impl Actor {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
             })?;
 
-            Ok(post_result)
+            Ok((post_result, fee_to_burn))
         })?;
 
         // Restore power for recovered sectors. Remove power for new faults.
@@ -756,6 +992,7 @@ impl Actor {
         // additional accounting state.
         // https://github.com/filecoin-project/specs-actors/issues/414
         request_update_power(rt, post_result.power_delta)?;
+        burn_funds(rt, fee_to_burn)?;
 
         let state: State = rt.state()?;
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
@@ -775,7 +1012,7 @@ impl Actor {
         let policy = rt.policy();
         let info = get_miner_info(store, &state)?;
         rt.validate_immediate_caller_is(
-            info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            info.control_addresses_for(ControlAddressRole::Any).chain(&[info.worker, info.owner]),
         )?;
 
         let sector_numbers = params.sector_numbers.validate().context_code(
@@ -895,7 +1132,7 @@ impl Actor {
         let info = get_miner_info(store, &state)?;
 
         rt.validate_immediate_caller_is(
-            info.control_addresses.iter().chain(&[info.owner, info.worker]),
+            info.control_addresses_for(ControlAddressRole::Any).chain(&[info.owner, info.worker]),
         )?;
 
         let mut sectors = Sectors::load(&store, &state.sectors)
@@ -1021,7 +1258,7 @@ impl Actor {
 
         // Validate parameters.
         rt.validate_immediate_caller_is(
-            info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            info.control_addresses_for(ControlAddressRole::Any).chain(&[info.worker, info.owner]),
         )?;
         if !params.sector_proofs.is_empty() {
             if !params.aggregate_proof.is_empty() {
@@ -1360,7 +1597,7 @@ impl Actor {
                 //
                 // However, some of these sectors may have been
                 // terminated. That's fine, we'll skip them.
-                let fault_expiration_epoch = target_deadline.last() + policy.fault_max_age;
+                let fault_expiration_epoch = target_deadline.last() + info.fault_max_age(policy);
                 let power_delta = dl_current
                     .record_faults(
                         rt.store(),
@@ -1406,6 +1643,7 @@ impl Actor {
                 // could end up receiving a substantial
                 // portion of their fee back as a reward.
                 let penalty_target = &penalty_base + &reward_target;
+                let prior_fee_debt = st.fee_debt.clone();
                 st.apply_penalty(&penalty_target)
                     .map_err(|e| actor_error!(illegal_state, "failed to apply penalty {}", e))?;
                 let (to_burn, total_unlocked) = st
@@ -1417,6 +1655,7 @@ impl Actor {
                     .map_err(|e| {
                         e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to pay debt")
                     })?;
+                emit_fee_debt_change(rt, &prior_fee_debt, &st.fee_debt)?;
 
                 // Now, move as much of the target reward as
                 // we can from the burn to the reward.
@@ -1488,6 +1727,7 @@ impl Actor {
         let challenge_earliest = curr_epoch - rt.policy().max_pre_commit_randomness_lookback;
         let mut sectors_deals = Vec::with_capacity(sectors.len());
         let mut sector_numbers = BitField::new();
+        let mut experimental_seal_proofs = None;
         for precommit in sectors.iter() {
             let set = sector_numbers.get(precommit.sector_number);
             if set {
@@ -1499,7 +1739,13 @@ impl Actor {
             }
             sector_numbers.set(precommit.sector_number);
 
-            if !can_pre_commit_seal_proof(rt.policy(), precommit.seal_proof) {
+            if !can_pre_commit_seal_proof(rt.policy(), precommit.seal_proof)
+                && !is_experimental_seal_proof(
+                    rt,
+                    &mut experimental_seal_proofs,
+                    precommit.seal_proof,
+                )?
+            {
                 return Err(actor_error!(
                     illegal_argument,
                     "unsupported seal proof type {}",
@@ -1591,8 +1837,7 @@ impl Actor {
             let info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses
-                    .iter()
+                info.control_addresses_for(ControlAddressRole::PreCommit)
                     .chain(&[info.worker, info.owner]),
             )?;
             let store = rt.store();
@@ -1724,7 +1969,7 @@ impl Actor {
 
         // Validate caller and parameters.
         rt.validate_immediate_caller_is(
-            info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            info.control_addresses_for(ControlAddressRole::Any).chain(&[info.worker, info.owner]),
         )?;
 
         // Load pre-commits, failing if any don't exist.
@@ -1990,7 +2235,7 @@ impl Actor {
         )?;
 
         rt.validate_immediate_caller_is(
-            info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            info.control_addresses_for(ControlAddressRole::Any).chain(&[info.worker, info.owner]),
         )?;
 
         if params.proving_deadline >= policy.wpost_period_deadlines {
@@ -2021,7 +2266,9 @@ impl Actor {
             ));
         }
 
-        if !can_prove_commit_ni_seal_proof(rt.policy(), params.seal_proof_type) {
+        if !can_prove_commit_ni_seal_proof(rt.policy(), params.seal_proof_type)
+            && !is_experimental_seal_proof(rt, &mut None, params.seal_proof_type)?
+        {
             return Err(actor_error!(
                 illegal_argument,
                 "unsupported seal proof type {}",
@@ -2113,11 +2360,16 @@ impl Actor {
 
         let (needs_cron, fee_to_burn) = rt.transaction(|state: &mut State, rt| {
             let current_balance = rt.current_balance();
-            let available_balance =
+            let mut available_balance =
                 state.get_unlocked_balance(&current_balance).with_context_code(
                     ExitCode::USR_ILLEGAL_STATE,
                     || "failed to calculate unlocked balance",
                 )?;
+            if available_balance < total_pledge {
+                // Cover the shortfall from the pledge facility, if one is available.
+                available_balance += state
+                    .draw_pledge_facility(rt.curr_epoch(), &(&total_pledge - &available_balance));
+            }
             if available_balance < total_pledge {
                 return Err(actor_error!(
                     insufficient_funds,
@@ -2205,6 +2457,152 @@ impl Actor {
         Ok(MaxTerminationFeeReturn { max_fee })
     }
 
+    /// Returns the penalty that terminating the given sectors would incur at the current epoch.
+    fn estimate_termination_fee(
+        rt: &impl Runtime,
+        params: EstimateTerminationFeeParams,
+    ) -> Result<EstimateTerminationFeeReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let sector_numbers = params
+            .sectors
+            .validate()
+            .context_code(ExitCode::USR_ILLEGAL_ARGUMENT, "failed to validate sectors")?;
+        let count = sector_numbers.len();
+        if count > rt.policy().addressed_sectors_max {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many sectors requested: {} > {}",
+                count,
+                rt.policy().addressed_sectors_max
+            ));
+        }
+
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        let sectors = Sectors::load(rt.store(), &state.sectors)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")?;
+        let sector_infos = sectors.load_sectors(&params.sectors)?;
+
+        let reward_smoothed = request_current_epoch_block_reward(rt)?.this_epoch_reward_smoothed;
+        let quality_adj_power_smoothed =
+            request_current_total_power(rt)?.quality_adj_power_smoothed;
+
+        // Priced as in early termination processing, with the sectors' age at the current epoch.
+        let mut fee = TokenAmount::zero();
+        for sector in &sector_infos {
+            let sector_power = qa_power_for_sector(info.sector_size, sector);
+            let fault_fee = pledge_penalty_for_continued_fault(
+                &reward_smoothed,
+                &quality_adj_power_smoothed,
+                &sector_power,
+            );
+            let sector_age = rt.curr_epoch() - sector.activation;
+            fee += pledge_penalty_for_termination(&sector.initial_pledge, sector_age, &fault_fee);
+        }
+
+        Ok(EstimateTerminationFeeReturn { fee })
+    }
+
+    /// Returns a sector's expected day reward at the current epoch, its daily fee and its pledge.
+    fn get_sector_economics(
+        rt: &impl Runtime,
+        params: GetSectorEconomicsParams,
+    ) -> Result<GetSectorEconomicsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+
+        if params.sector_number > MAX_SECTOR_NUMBER {
+            return Err(actor_error!(illegal_argument, "sector number out of range"));
+        }
+
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        let sector = state
+            .get_sector(rt.store(), params.sector_number)?
+            .ok_or_else(|| actor_error!(not_found, "sector {} not found", params.sector_number))?;
+
+        let reward_smoothed = request_current_epoch_block_reward(rt)?.this_epoch_reward_smoothed;
+        let quality_adj_power_smoothed =
+            request_current_total_power(rt)?.quality_adj_power_smoothed;
+        let expected_day_reward = expected_reward_for_power(
+            &reward_smoothed,
+            &quality_adj_power_smoothed,
+            &qa_power_for_sector(info.sector_size, &sector),
+            EPOCHS_IN_DAY,
+        );
+
+        Ok(GetSectorEconomicsReturn {
+            expected_day_reward,
+            daily_fee: sector.daily_fee,
+            initial_pledge: sector.initial_pledge,
+        })
+    }
+
+    /// Attaches metadata to sectors, such as external identifiers, for other actors to read.
+    /// Only the owner may set metadata, which is bounded in size by policy. The state written is
+    /// charged for as any other, through the VM's storage gas.
+    fn set_sector_metadata(
+        rt: &impl Runtime,
+        params: SetSectorMetadataParams,
+    ) -> Result<(), ActorError> {
+        let policy = rt.policy();
+        if params.entries.len() as u64 > policy.addressed_sectors_max {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many sector metadata entries {} > {}",
+                params.entries.len(),
+                policy.addressed_sectors_max
+            ));
+        }
+        for entry in &params.entries {
+            if entry.metadata.len() > policy.max_sector_metadata_size {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "metadata of sector {} too large: {} > {}",
+                    entry.sector_number,
+                    entry.metadata.len(),
+                    policy.max_sector_metadata_size
+                ));
+            }
+        }
+
+        rt.transaction(|state: &mut State, rt| {
+            let info = get_miner_info(rt.store(), state)?;
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+
+            let sectors = Sectors::load(rt.store(), &state.sectors)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors array")?;
+            let mut entries = Vec::with_capacity(params.entries.len());
+            for entry in params.entries {
+                if entry.sector_number > MAX_SECTOR_NUMBER {
+                    return Err(actor_error!(illegal_argument, "sector number out of range"));
+                }
+                sectors.must_get(entry.sector_number)?;
+                entries.push((entry.sector_number, entry.metadata));
+            }
+            state.set_sector_metadata(rt.store(), &entries)
+        })
+    }
+
+    /// Returns the metadata attached to a sector, empty if none.
+    fn get_sector_metadata(
+        rt: &impl Runtime,
+        params: GetSectorMetadataParams,
+    ) -> Result<GetSectorMetadataReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+
+        if params.sector_number > MAX_SECTOR_NUMBER {
+            return Err(actor_error!(illegal_argument, "sector number out of range"));
+        }
+
+        let state: State = rt.state()?;
+        state
+            .get_sector(rt.store(), params.sector_number)?
+            .ok_or_else(|| actor_error!(not_found, "sector {} not found", params.sector_number))?;
+        let metadata = state.get_sector_metadata(rt.store(), params.sector_number)?;
+
+        Ok(GetSectorMetadataReturn { metadata })
+    }
+
     /// Returns the miner's total initial pledge amount
     fn initial_pledge(rt: &impl Runtime) -> Result<InitialPledgeReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
@@ -2213,6 +2611,228 @@ impl Actor {
         Ok(InitialPledgeReturn { initial_pledge: state.initial_pledge })
     }
 
+    /// Returns the timing of the current and next WindowPoSt deadlines, including the epoch
+    /// whose beacon randomness (with the miner's address as entropy) seeds each challenge.
+    /// Deadlines are computed from the miner's proving period offset, so are defined
+    /// even before the miner's first proving period has begun.
+    fn get_deadline_timing(rt: &impl Runtime) -> Result<GetDeadlineTimingReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let policy = rt.policy();
+        let state: State = rt.state()?;
+
+        let current = state.deadline_info(policy, rt.curr_epoch());
+        let next = if current.index + 1 < policy.wpost_period_deadlines {
+            new_deadline_info(policy, current.period_start, current.index + 1, rt.curr_epoch())
+        } else {
+            new_deadline_info(policy, current.next_period_start(), 0, rt.curr_epoch())
+        };
+        Ok(GetDeadlineTimingReturn { current: current.into(), next: next.into() })
+    }
+
+    /// Returns the on-chain metadata of the requested sectors.
+    /// Sectors which don't exist (never committed, or terminated and removed) are omitted.
+    /// At most `addressed_sectors_max` sectors may be requested at once.
+    fn get_sectors(
+        rt: &impl Runtime,
+        params: GetSectorsParams,
+    ) -> Result<GetSectorsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let count = params.sectors.len();
+        if count > rt.policy().addressed_sectors_max {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many sectors requested: {} > {}",
+                count,
+                rt.policy().addressed_sectors_max
+            ));
+        }
+
+        let state: State = rt.state()?;
+        let sectors = Sectors::load(rt.store(), &state.sectors)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")?;
+        let mut found = Vec::new();
+        for sector_number in params.sectors.iter() {
+            if let Some(info) = sectors.get(sector_number)? {
+                found.push(SectorMetadata::from(&info));
+            }
+        }
+        Ok(GetSectorsReturn { sectors: found })
+    }
+
+    /// Returns a summary of the sectors and power in each WindowPoSt deadline,
+    /// along with the deadline's next challenge window.
+    fn get_deadline_summaries(rt: &impl Runtime) -> Result<GetDeadlineSummariesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let policy = rt.policy();
+        let state: State = rt.state()?;
+        let current = state.deadline_info(policy, rt.curr_epoch());
+        let deadlines = state.load_deadlines(rt.store())?;
+
+        let mut summaries = Vec::with_capacity(deadlines.due.len());
+        deadlines
+            .for_each(rt.store(), |index, deadline| {
+                let mut faulty_sectors = 0;
+                let mut recovering_sectors = 0;
+                let mut recovering_power = PowerPair::zero();
+                deadline.for_each(rt.store(), |_, partition| {
+                    faulty_sectors += partition.faults.len();
+                    recovering_sectors += partition.recoveries.len();
+                    recovering_power += &partition.recovering_power;
+                    Ok(())
+                })?;
+                let window =
+                    new_deadline_info(policy, current.period_start, index, rt.curr_epoch())
+                        .next_not_elapsed();
+                summaries.push(DeadlineSummary {
+                    index,
+                    partitions: deadline.partitions_amt(rt.store())?.count(),
+                    partitions_posted: deadline.partitions_posted.len(),
+                    live_sectors: deadline.live_sectors,
+                    faulty_sectors,
+                    recovering_sectors,
+                    live_power: deadline.live_power,
+                    faulty_power: deadline.faulty_power,
+                    recovering_power,
+                    next_window: window.into(),
+                });
+                Ok(())
+            })
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to summarize deadlines")
+            })?;
+        Ok(GetDeadlineSummariesReturn { deadlines: summaries })
+    }
+
+    /// Returns the active, faulty, recovering and unproven power of each WindowPoSt deadline,
+    /// summed over its partitions.
+    fn get_power_breakdown(rt: &impl Runtime) -> Result<GetPowerBreakdownReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let deadlines = state.load_deadlines(rt.store())?;
+
+        let mut breakdowns = Vec::with_capacity(deadlines.due.len());
+        deadlines
+            .for_each(rt.store(), |index, deadline| {
+                let mut breakdown = DeadlinePowerBreakdown {
+                    index,
+                    active: PowerPair::zero(),
+                    faulty: PowerPair::zero(),
+                    recovering: PowerPair::zero(),
+                    unproven: PowerPair::zero(),
+                };
+                deadline.for_each(rt.store(), |_, partition| {
+                    breakdown.active += &partition.active_power();
+                    breakdown.faulty += &partition.faulty_power;
+                    breakdown.recovering += &partition.recovering_power;
+                    breakdown.unproven += &partition.unproven_power;
+                    Ok(())
+                })?;
+                breakdowns.push(breakdown);
+                Ok(())
+            })
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to break down power")
+            })?;
+        Ok(GetPowerBreakdownReturn { deadlines: breakdowns })
+    }
+
+    /// Returns a summary of the sectors and power in each partition of a WindowPoSt deadline.
+    fn get_partition_summaries(
+        rt: &impl Runtime,
+        params: GetPartitionSummariesParams,
+    ) -> Result<GetPartitionSummariesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let deadline =
+            state.load_deadlines(rt.store())?.load_deadline(rt.store(), params.deadline)?;
+
+        let mut partitions = Vec::new();
+        deadline
+            .for_each(rt.store(), |index, partition| {
+                let posted = deadline.partitions_posted.get(index);
+                partitions.push(PartitionSummary::new(index, posted, partition));
+                Ok(())
+            })
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to summarize partitions")
+            })?;
+        Ok(GetPartitionSummariesReturn { partitions })
+    }
+
+    /// Returns the WindowPoSt outcome counts of a partition.
+    fn get_partition_post_stats(
+        rt: &impl Runtime,
+        params: GetPartitionPoStStatsParams,
+    ) -> Result<GetPartitionPoStStatsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let partition = state
+            .load_deadlines(rt.store())?
+            .load_deadline(rt.store(), params.deadline)?
+            .load_partition(rt.store(), params.partition)?;
+        Ok(GetPartitionPoStStatsReturn { stats: partition.post_stats })
+    }
+
+    /// Returns the partitions that CompactPartitions would currently accept in each deadline,
+    /// along with the number of terminated sectors compacting them would remove.
+    /// Only partitions with terminated sectors and without faulty or unproven sectors are listed,
+    /// and only for deadlines that are available for compaction at the current epoch.
+    fn get_compactable_partitions(
+        rt: &impl Runtime,
+    ) -> Result<GetCompactablePartitionsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let policy = rt.policy();
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        let proving_period_start = state.current_proving_period_start(policy, rt.curr_epoch());
+        let partition_limit =
+            load_partitions_sectors_max(policy, info.window_post_partition_sectors);
+
+        let mut compactable = Vec::new();
+        state
+            .load_deadlines(rt.store())?
+            .for_each(rt.store(), |index, deadline| {
+                if !deadline_available_for_compaction(
+                    policy,
+                    proving_period_start,
+                    index,
+                    rt.curr_epoch(),
+                ) || !deadline.early_terminations.is_empty()
+                {
+                    return Ok(());
+                }
+
+                let mut partitions = BitField::new();
+                let mut dead_sectors = 0;
+                deadline.for_each(rt.store(), |partition_idx, partition| {
+                    if partitions.len() < partition_limit
+                        && !partition.terminated.is_empty()
+                        && partition.faults.is_empty()
+                        && partition.unproven.is_empty()
+                    {
+                        partitions.set(partition_idx);
+                        dead_sectors += partition.terminated.len();
+                    }
+                    Ok(())
+                })?;
+                if !partitions.is_empty() {
+                    compactable.push(CompactableDeadline {
+                        deadline: index,
+                        partitions,
+                        dead_sectors,
+                    });
+                }
+                Ok(())
+            })
+            .map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    "failed to find compactable partitions",
+                )
+            })?;
+        Ok(GetCompactablePartitionsReturn { deadlines: compactable })
+    }
+
     fn check_sector_proven(
         rt: &impl Runtime,
         params: CheckSectorProvenParams,
@@ -2269,6 +2889,82 @@ impl Actor {
         )
     }
 
+    /// Extends every active sector of a class in the specified partitions to a new expiration,
+    /// selecting the sectors from state rather than requiring the caller to enumerate them.
+    /// Faulty, unproven and terminated sectors are skipped, as are sectors which already
+    /// expire at or after the new expiration.
+    fn extend_sector_expirations_by_class(
+        rt: &impl Runtime,
+        params: ExtendSectorExpirationsByClassParams,
+    ) -> Result<(), ActorError> {
+        let policy = rt.policy();
+        if params.partitions.is_empty() {
+            return Err(actor_error!(illegal_argument, "no partitions specified"));
+        }
+        if params.partitions.len() as u64 > policy.addressed_partitions_max {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many partitions specified: {} > {}",
+                params.partitions.len(),
+                policy.addressed_partitions_max
+            ));
+        }
+
+        let state: State = rt.state()?;
+        let deadlines = state.load_deadlines(rt.store())?;
+        let sectors = Sectors::load(rt.store(), &state.sectors).map_err(|e| {
+            e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors array")
+        })?;
+
+        let mut seen = BTreeSet::<(u64, u64)>::new();
+        let mut sector_count = 0;
+        let mut extensions = Vec::new();
+        for key in &params.partitions {
+            if key.deadline >= policy.wpost_period_deadlines {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "deadline {} not in range 0..{}",
+                    key.deadline,
+                    policy.wpost_period_deadlines
+                ));
+            }
+            if !seen.insert((key.deadline, key.partition)) {
+                return Err(actor_error!(illegal_argument, "duplicate partition {:?}", key));
+            }
+
+            let deadline = deadlines.load_deadline(rt.store(), key.deadline)?;
+            let partition = deadline.load_partition(rt.store(), key.partition)?;
+            let mut selected = BitField::new();
+            for sector in sectors.load_sectors(&partition.active_sectors())? {
+                if params.class.includes(&sector) && sector.expiration < params.new_expiration {
+                    selected.set(sector.sector_number);
+                }
+            }
+            if selected.is_empty() {
+                continue;
+            }
+            sector_count += selected.len();
+            extensions.push(ValidatedExpirationExtension {
+                deadline: key.deadline,
+                partition: key.partition,
+                sectors: selected,
+                new_expiration: params.new_expiration,
+            });
+        }
+        if sector_count > policy.addressed_sectors_max {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many sectors to extend: {} > {}",
+                sector_count,
+                policy.addressed_sectors_max
+            ));
+        }
+
+        // Selected sectors hold no verified data, so have no claims to check.
+        let inner = ExtendExpirationsInner { extensions, claims: Some(BTreeMap::new()) };
+        Self::extend_sector_expiration_inner(rt, inner, ExtensionKind::ExtendCommittment)
+    }
+
     fn extend_sector_expiration_inner(
         rt: &impl Runtime,
         inner: ExtendExpirationsInner,
@@ -2281,7 +2977,8 @@ impl Actor {
         let (power_delta, pledge_delta) = rt.transaction(|state: &mut State, rt| {
             let info = get_miner_info(rt.store(), state)?;
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             let mut deadlines =
@@ -2492,6 +3189,131 @@ impl Actor {
         Ok(())
     }
 
+    /// Removes the space of a verified claim revoked by the verified registry from the sector
+    /// holding it, reducing the sector's quality-adjusted power and daily fee.
+    /// The sector's pledge is unchanged.
+    /// Revocations from sectors which are no longer live are ignored, since they have no power.
+    /// A faulty sector cannot be revised until it recovers.
+    fn on_claim_revoked(rt: &impl Runtime, params: OnClaimRevokedParams) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&VERIFIED_REGISTRY_ACTOR_ADDR))?;
+
+        let power_delta = rt.transaction(|state: &mut State, rt| {
+            let info = get_miner_info(rt.store(), state)?;
+            let mut sectors = Sectors::load(rt.store(), &state.sectors).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors array")
+            })?;
+            let old_sector = match sectors.get(params.sector)? {
+                Some(sector) => sector,
+                None => return Ok(PowerPair::zero()),
+            };
+
+            let mut deadlines =
+                state.load_deadlines(rt.store()).map_err(|e| e.wrap("failed to load deadlines"))?;
+            let (deadline_idx, partition_idx) =
+                deadlines.find_sector(rt.store(), params.sector).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to find sector {}", params.sector),
+                    )
+                })?;
+            let mut deadline = deadlines.load_deadline(rt.store(), deadline_idx)?;
+            let mut partitions = deadline.partitions_amt(rt.store()).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to load partitions for deadline {}", deadline_idx),
+                )
+            })?;
+            let key = PartitionKey { deadline: deadline_idx, partition: partition_idx };
+            let mut partition = partitions
+                .get(partition_idx)
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to load partition {:?}", key),
+                    )
+                })?
+                .cloned()
+                .ok_or_else(|| actor_error!(illegal_state, "no such partition {:?}", key))?;
+
+            if partition.terminated.get(params.sector) {
+                return Ok(PowerPair::zero());
+            }
+            if partition.faults.get(params.sector) {
+                return Err(actor_error!(
+                    forbidden,
+                    "cannot revoke claim from faulty sector {}",
+                    params.sector
+                ));
+            }
+
+            let mut new_sector = old_sector.clone();
+            let duration = old_sector.expiration - old_sector.power_base_epoch;
+            let revoked_weight = BigInt::from(params.space) * duration;
+            new_sector.verified_deal_weight =
+                max(&old_sector.verified_deal_weight - revoked_weight, DealWeight::zero());
+            let old_qa_power = qa_power_for_sector(info.sector_size, &old_sector);
+            let new_qa_power = qa_power_for_sector(info.sector_size, &new_sector);
+            if !old_sector.daily_fee.is_zero() && old_qa_power != new_qa_power {
+                // adjust the daily_fee by the same proportion as the power changed
+                new_sector.daily_fee =
+                    daily_proof_fee_adjust(&old_sector.daily_fee, &old_qa_power, &new_qa_power);
+            }
+
+            sectors.store(vec![new_sector.clone()]).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to update sector {}", params.sector),
+                )
+            })?;
+
+            let quant = state.quant_spec_for_deadline(rt.policy(), deadline_idx);
+            let (partition_power_delta, partition_daily_fee_delta, unproven) = partition
+                .revise_sector(rt.store(), &old_sector, &new_sector, info.sector_size, quant)
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to revise sector {} in {:?}", params.sector, key),
+                    )
+                })?;
+            partitions.set(partition_idx, partition).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to save partition {:?}", key),
+                )
+            })?;
+
+            deadline.live_power += &partition_power_delta;
+            deadline.daily_fee += &partition_daily_fee_delta;
+            deadline.partitions = partitions.flush().map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to save partitions for deadline {}", deadline_idx),
+                )
+            })?;
+            deadlines.update_deadline(rt.policy(), rt.store(), deadline_idx, &deadline).map_err(
+                |e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to save deadline {}", deadline_idx),
+                    )
+                },
+            )?;
+
+            state.sectors = sectors.amt.flush().map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save sectors")
+            })?;
+            state.save_deadlines(rt.store(), deadlines).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
+            })?;
+
+            // An unproven sector's power is not yet active, and is activated (as revised)
+            // with its first proof.
+            if unproven { Ok(PowerPair::zero()) } else { Ok(partition_power_delta) }
+        })?;
+
+        request_update_power(rt, power_delta)
+    }
+
     /// Marks some sectors as terminated at the present epoch, earlier than their
     /// scheduled termination, and adds these sectors to the early termination queue.
     /// This method then processes up to AddressedSectorsMax sectors and
@@ -2548,7 +3370,8 @@ impl Actor {
             let info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             let store = rt.store();
@@ -2645,6 +3468,30 @@ impl Actor {
     }
 
     fn declare_faults(rt: &impl Runtime, params: DeclareFaultsParams) -> Result<(), ActorError> {
+        Self::declare_faults_batch(rt, params, true)?;
+        Ok(())
+    }
+
+    /// Declares faults as DeclareFaults, but processes each deadline independently.
+    /// A declaration fails, without aborting the message, if its deadline cannot accept
+    /// declarations (e.g. it is past its fault cutoff) or its faults cannot be recorded.
+    /// A failed deadline's changes are discarded, so declarations at the same deadline
+    /// succeed or fail together. Illegal state still aborts the whole message.
+    /// Returns a result for each declaration, in order.
+    fn declare_faults2(
+        rt: &impl Runtime,
+        params: DeclareFaultsParams,
+    ) -> Result<DeclarationsReturn, ActorError> {
+        let results = Self::declare_faults_batch(rt, params, false)?;
+        Ok(DeclarationsReturn { results })
+    }
+
+    fn declare_faults_batch(
+        rt: &impl Runtime,
+        params: DeclareFaultsParams,
+        all_or_nothing: bool,
+    ) -> Result<BatchReturn, ActorError> {
+        let declared_deadlines: Vec<u64> = params.faults.iter().map(|f| f.deadline).collect();
         let mut to_process = DeadlineSectorMap::new();
 
         for term in params.faults {
@@ -2671,78 +3518,48 @@ impl Actor {
                 })?;
         }
 
+        let mut failed_deadlines = BTreeMap::<u64, ExitCode>::new();
         let power_delta = rt.transaction(|state: &mut State, rt| {
             let info = get_miner_info(rt.store(), state)?;
 
-            rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
-            )?;
-
-            let store = rt.store();
-
-            let mut deadlines =
-                state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
-
-            let sectors = Sectors::load(store, &state.sectors).map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors array")
-            })?;
-
-            let mut new_fault_power_total = PowerPair::zero();
-            let curr_epoch = rt.curr_epoch();
-            for (deadline_idx, partition_map) in to_process.iter() {
-                let policy = rt.policy();
-                let target_deadline = declaration_deadline_info(
-                    policy,
-                    state.current_proving_period_start(policy, curr_epoch),
-                    deadline_idx,
-                    curr_epoch,
-                )
-                .map_err(|e| {
-                    actor_error!(
-                        illegal_argument,
-                        "invalid fault declaration deadline {}: {}",
-                        deadline_idx,
-                        e
-                    )
-                })?;
-
-                validate_fr_declaration_deadline(&target_deadline).map_err(|e| {
-                    actor_error!(
-                        illegal_argument,
-                        "failed fault declaration at deadline {}: {}",
-                        deadline_idx,
-                        e
-                    )
-                })?;
-
-                let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
-
-                let fault_expiration_epoch = target_deadline.last() + policy.fault_max_age;
-
-                let deadline_power_delta = deadline
-                    .record_faults(
-                        store,
-                        &sectors,
-                        info.sector_size,
-                        target_deadline.quant_spec(),
-                        fault_expiration_epoch,
-                        partition_map,
-                    )
-                    .map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            format!("failed to declare faults for deadline {}", deadline_idx),
-                        )
-                    })?;
-
-                deadlines.update_deadline(policy, store, deadline_idx, &deadline).map_err(|e| {
-                    e.downcast_default(
-                        ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to store deadline {} partitions", deadline_idx),
-                    )
-                })?;
+            rt.validate_immediate_caller_is(
+                info.control_addresses_for(ControlAddressRole::PoSt)
+                    .chain(&[info.worker, info.owner]),
+            )?;
+
+            let store = rt.store();
+
+            let mut deadlines =
+                state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
+
+            let sectors = Sectors::load(store, &state.sectors).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors array")
+            })?;
 
-                new_fault_power_total += &deadline_power_delta;
+            let mut new_fault_power_total = PowerPair::zero();
+            let curr_epoch = rt.curr_epoch();
+            for (deadline_idx, partition_map) in to_process.iter() {
+                let policy = rt.policy();
+                let result = declare_faults_at_deadline(
+                    policy,
+                    store,
+                    &info,
+                    &mut deadlines,
+                    &sectors,
+                    state.current_proving_period_start(policy, curr_epoch),
+                    curr_epoch,
+                    deadline_idx,
+                    partition_map,
+                );
+
+                match result {
+                    Ok(deadline_power_delta) => new_fault_power_total += &deadline_power_delta,
+                    Err(e) if !all_or_nothing && e.exit_code() != ExitCode::USR_ILLEGAL_STATE => {
+                        info!("failed to declare faults at deadline {}: {}", deadline_idx, e);
+                        failed_deadlines.insert(deadline_idx, e.exit_code());
+                    }
+                    Err(e) => return Err(e),
+                }
             }
 
             state.save_deadlines(store, deadlines).map_err(|e| {
@@ -2759,13 +3576,33 @@ impl Actor {
         request_update_power(rt, power_delta)?;
 
         // Payment of penalty for declared faults is deferred to the deadline cron.
-        Ok(())
+        Ok(declaration_results(&declared_deadlines, &failed_deadlines))
     }
 
     fn declare_faults_recovered(
         rt: &impl Runtime,
         params: DeclareFaultsRecoveredParams,
     ) -> Result<(), ActorError> {
+        Self::declare_faults_recovered_batch(rt, params, true)?;
+        Ok(())
+    }
+
+    /// Declares recoveries as DeclareFaultsRecovered, but processes each deadline independently,
+    /// as DeclareFaults2. Returns a result for each declaration, in order.
+    fn declare_faults_recovered2(
+        rt: &impl Runtime,
+        params: DeclareFaultsRecoveredParams,
+    ) -> Result<DeclarationsReturn, ActorError> {
+        let results = Self::declare_faults_recovered_batch(rt, params, false)?;
+        Ok(DeclarationsReturn { results })
+    }
+
+    fn declare_faults_recovered_batch(
+        rt: &impl Runtime,
+        params: DeclareFaultsRecoveredParams,
+        all_or_nothing: bool,
+    ) -> Result<BatchReturn, ActorError> {
+        let declared_deadlines: Vec<u64> = params.recoveries.iter().map(|r| r.deadline).collect();
         let mut to_process = DeadlineSectorMap::new();
 
         for term in params.recoveries {
@@ -2792,6 +3629,7 @@ impl Actor {
                 })?;
         }
 
+        let mut failed_deadlines = BTreeMap::<u64, ExitCode>::new();
         let fee_to_burn = rt.transaction(|state: &mut State, rt| {
             // Verify unlocked funds cover both InitialPledgeRequirement and FeeDebt
             // and repay fee debt now.
@@ -2800,7 +3638,8 @@ impl Actor {
             let info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::PoSt)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             if consensus_fault_active(&info, rt.curr_epoch()) {
@@ -2821,47 +3660,26 @@ impl Actor {
             let curr_epoch = rt.curr_epoch();
             for (deadline_idx, partition_map) in to_process.iter() {
                 let policy = rt.policy();
-                let target_deadline = declaration_deadline_info(
+                let result = declare_recoveries_at_deadline(
                     policy,
+                    store,
+                    &info,
+                    &mut deadlines,
+                    &sectors,
                     state.current_proving_period_start(policy, curr_epoch),
-                    deadline_idx,
                     curr_epoch,
-                )
-                .map_err(|e| {
-                    actor_error!(
-                        illegal_argument,
-                        "invalid recovery declaration deadline {}: {}",
-                        deadline_idx,
-                        e
-                    )
-                })?;
-
-                validate_fr_declaration_deadline(&target_deadline).map_err(|e| {
-                    actor_error!(
-                        illegal_argument,
-                        "failed recovery declaration at deadline {}: {}",
-                        deadline_idx,
-                        e
-                    )
-                })?;
-
-                let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
-
-                deadline
-                    .declare_faults_recovered(store, &sectors, info.sector_size, partition_map)
-                    .map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            format!("failed to declare recoveries for deadline {}", deadline_idx),
-                        )
-                    })?;
+                    deadline_idx,
+                    partition_map,
+                );
 
-                deadlines.update_deadline(policy, store, deadline_idx, &deadline).map_err(|e| {
-                    e.downcast_default(
-                        ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to store deadline {}", deadline_idx),
-                    )
-                })?;
+                match result {
+                    Ok(()) => {}
+                    Err(e) if !all_or_nothing && e.exit_code() != ExitCode::USR_ILLEGAL_STATE => {
+                        info!("failed to declare recoveries at deadline {}: {}", deadline_idx, e);
+                        failed_deadlines.insert(deadline_idx, e.exit_code());
+                    }
+                    Err(e) => return Err(e),
+                }
             }
 
             state.save_deadlines(store, deadlines).map_err(|e| {
@@ -2876,7 +3694,7 @@ impl Actor {
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
 
         // Power is not restored yet, but when the recovered sectors are successfully PoSted.
-        Ok(())
+        Ok(declaration_results(&declared_deadlines, &failed_deadlines))
     }
 
     /// Compacts a number of partitions at one deadline by removing terminated sectors, re-ordering the remaining sectors,
@@ -2907,7 +3725,8 @@ impl Actor {
             let info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             let store = rt.store();
@@ -2971,6 +3790,7 @@ impl Actor {
             sectors.delete_sectors(&dead).map_err(|e| {
                 e.wrap("failed to delete sectors removed during partition compaction")
             })?;
+            state.delete_sector_metadata(store, &dead)?;
             state.sectors = sectors.amt.flush().with_context_code(
                 ExitCode::USR_ILLEGAL_STATE,
                 || "failed to save sectors after compaction",
@@ -3039,7 +3859,8 @@ impl Actor {
             let info = get_miner_info(rt.store(), state)?;
 
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             state.allocate_sector_numbers(
@@ -3103,6 +3924,7 @@ impl Actor {
             pledge_delta_total -= &newly_vested;
             pledge_delta_total += &reward_to_lock;
 
+            let prior_fee_debt = st.fee_debt.clone();
             st.apply_penalty(&params.penalty)
                 .map_err(|e| actor_error!(illegal_state, "failed to apply penalty: {}", e))?;
 
@@ -3118,6 +3940,7 @@ impl Actor {
                 .map_err(|e| {
                     e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to repay penalty")
                 })?;
+            emit_fee_debt_change(rt, &prior_fee_debt, &st.fee_debt)?;
             pledge_delta_total -= &total_unlocked;
             Ok((pledge_delta_total, to_burn))
         })?;
@@ -3188,6 +4011,7 @@ impl Actor {
                 ));
             }
 
+            let prior_fee_debt = st.fee_debt.clone();
             st.apply_penalty(&fault_penalty).map_err(|e| {
                 actor_error!(illegal_state, format!("failed to apply penalty: {}", e))
             })?;
@@ -3202,6 +4026,7 @@ impl Actor {
                 .map_err(|e| {
                     e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to pay fees")
                 })?;
+            emit_fee_debt_change(rt, &prior_fee_debt, &st.fee_debt)?;
 
             pledge_delta -= total_unlocked;
 
@@ -3338,6 +4163,7 @@ impl Actor {
     /// Proposes or confirms a change of beneficiary address.
     /// A proposal must be submitted by the owner, and takes effect after approval of both the proposed beneficiary and current beneficiary,
     /// if applicable, any current beneficiary that has time and quota remaining.
+    /// A proposal not approved within the policy's beneficiary proposal lifetime expires, and can no longer be approved.
     //// See FIP-0029, https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0029.md
     fn change_beneficiary(
         rt: &impl Runtime,
@@ -3356,6 +4182,8 @@ impl Actor {
 
         rt.transaction(|state: &mut State, rt| {
             let mut info = get_miner_info(rt.store(), state)?;
+            // A proposal made before proposals expired gets its full lifetime from now.
+            info.migrate_pending_beneficiary_expiration(rt.policy(), rt.curr_epoch());
             if caller == info.owner {
                 // This is a ChangeBeneficiary proposal when the caller is Owner
                 if new_beneficiary != info.owner {
@@ -3397,7 +4225,16 @@ impl Actor {
                     pending_beneficiary_term.approved_by_beneficiary = true;
                 }
                 info.pending_beneficiary_term = Some(pending_beneficiary_term);
+                info.pending_beneficiary_expiration =
+                    Some(rt.curr_epoch() + rt.policy().beneficiary_proposal_lifetime);
             } else if let Some(pending_term) = &info.pending_beneficiary_term {
+                if info.pending_beneficiary_expired(rt.curr_epoch()) {
+                    return Err(actor_error!(
+                        forbidden,
+                        "changeBeneficiary proposal expired at epoch {}",
+                        info.pending_beneficiary_expiration.unwrap_or_default()
+                    ));
+                }
                 if caller != info.beneficiary && caller != pending_term.new_beneficiary {
                     return Err(actor_error!(
                         forbidden,
@@ -3456,6 +4293,7 @@ impl Actor {
                     info.beneficiary_term.expiration = pending_term.new_expiration;
                     // clear the pending proposal
                     info.pending_beneficiary_term = None;
+                    info.pending_beneficiary_expiration = None;
                 }
             }
 
@@ -3479,18 +4317,163 @@ impl Actor {
                 beneficiary: info.beneficiary,
                 term: info.beneficiary_term,
             },
-            proposed: info.pending_beneficiary_term,
+            proposed: info.live_pending_beneficiary_term(rt.curr_epoch()).cloned(),
+        })
+    }
+
+    /// Returns the pending beneficiary change proposal, if there is one that has not expired.
+    fn get_pending_beneficiary_change(
+        rt: &impl Runtime,
+    ) -> Result<GetPendingBeneficiaryChangeReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &st)?;
+        let proposed = info.live_pending_beneficiary_term(rt.curr_epoch()).cloned();
+        let proposal_expiration = proposed.as_ref().and(info.pending_beneficiary_expiration);
+        Ok(GetPendingBeneficiaryChangeReturn { proposed, proposal_expiration })
+    }
+
+    /// Authorizes a lender to fund a pledge facility, from which initial pledge shortfalls
+    /// are drawn when sectors are proven. Replaces the terms of any existing facility.
+    /// The lender may only be changed once the previous lender's deposits have been withdrawn.
+    fn authorize_pledge_lender(
+        rt: &impl Runtime,
+        params: AuthorizePledgeLenderParams,
+    ) -> Result<(), ActorError> {
+        if params.cap.is_negative() {
+            return Err(actor_error!(
+                illegal_argument,
+                "pledge facility cap {} must not be negative",
+                params.cap
+            ));
+        }
+        if params.expiration < rt.curr_epoch() {
+            return Err(actor_error!(
+                illegal_argument,
+                "pledge facility expiration {} is before current epoch {}",
+                params.expiration,
+                rt.curr_epoch()
+            ));
+        }
+        let lender = Address::new_id(rt.resolve_address(&params.lender).ok_or_else(|| {
+            actor_error!(illegal_argument, "unable to resolve address: {}", params.lender)
+        })?);
+
+        rt.transaction(|state: &mut State, rt| {
+            let info = get_miner_info(rt.store(), state)?;
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+
+            let facility = match state.pledge_facility.take() {
+                Some(mut facility) if facility.lender == lender => {
+                    facility.cap = params.cap;
+                    facility.expiration = params.expiration;
+                    facility
+                }
+                Some(facility) if facility.balance.is_positive() => {
+                    return Err(actor_error!(
+                        forbidden,
+                        "lender {} still has {} deposited in the pledge facility",
+                        facility.lender,
+                        facility.balance
+                    ));
+                }
+                _ => PledgeFacility::new(lender, params.cap, params.expiration),
+            };
+            state.pledge_facility = Some(facility);
+            Ok(())
         })
     }
 
+    /// Deposits the value sent into the pledge facility. Only the authorized lender may deposit,
+    /// and only while the facility is active.
+    fn deposit_pledge_facility(rt: &impl Runtime) -> Result<(), ActorError> {
+        let amount = rt.message().value_received();
+        if !amount.is_positive() {
+            return Err(actor_error!(
+                illegal_argument,
+                "pledge facility deposit {} must be positive",
+                amount
+            ));
+        }
+
+        let state: State = rt.transaction(|state: &mut State, rt| {
+            let facility = state
+                .pledge_facility
+                .as_mut()
+                .ok_or_else(|| actor_error!(forbidden, "no pledge facility authorized"))?;
+            rt.validate_immediate_caller_is(std::iter::once(&facility.lender))?;
+            if !facility.is_active(rt.curr_epoch()) {
+                return Err(actor_error!(
+                    forbidden,
+                    "pledge facility expired at epoch {}",
+                    facility.expiration
+                ));
+            }
+            facility.balance += &amount;
+            Ok(state.clone())
+        })?;
+
+        state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
+        Ok(())
+    }
+
+    /// Returns undrawn funds from the pledge facility to the lender.
+    /// Withdrawal is permitted at any time, including after the facility expires.
+    fn withdraw_pledge_facility(
+        rt: &impl Runtime,
+        params: WithdrawPledgeFacilityParams,
+    ) -> Result<WithdrawPledgeFacilityReturn, ActorError> {
+        if params.amount_requested.is_negative() {
+            return Err(actor_error!(
+                illegal_argument,
+                "negative fund requested for withdrawal: {}",
+                params.amount_requested
+            ));
+        }
+
+        let (lender, amount_withdrawn, state) = rt.transaction(|state: &mut State, rt| {
+            let facility = state
+                .pledge_facility
+                .as_mut()
+                .ok_or_else(|| actor_error!(forbidden, "no pledge facility authorized"))?;
+            rt.validate_immediate_caller_is(std::iter::once(&facility.lender))?;
+
+            let amount_withdrawn =
+                std::cmp::min(&facility.balance, &params.amount_requested).clone();
+            facility.balance -= &amount_withdrawn;
+            let lender = facility.lender;
+            Ok((lender, amount_withdrawn, state.clone()))
+        })?;
+
+        if amount_withdrawn.is_positive() {
+            extract_send_result(rt.send_simple(
+                &lender,
+                METHOD_SEND,
+                None,
+                amount_withdrawn.clone(),
+            ))?;
+        }
+
+        state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
+        Ok(WithdrawPledgeFacilityReturn { amount_withdrawn })
+    }
+
+    fn get_pledge_facility(rt: &impl Runtime) -> Result<GetPledgeFacilityReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        Ok(GetPledgeFacilityReturn { facility: st.pledge_facility })
+    }
+
     fn repay_debt(rt: &impl Runtime) -> Result<(), ActorError> {
         let (burn_amount, total_unlocked, state) = rt.transaction(|state: &mut State, rt| {
             let info = get_miner_info(rt.store(), state)?;
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses_for(ControlAddressRole::Any)
+                    .chain(&[info.worker, info.owner]),
             )?;
 
             // Repay as much fee debt as possible.
+            let prior_fee_debt = state.fee_debt.clone();
             let (burn_amount, total_unlocked) = state
                 .repay_partial_debt_in_priority_order(
                     rt.store(),
@@ -3500,6 +4483,7 @@ impl Actor {
                 .map_err(|e| {
                     e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to unlock fee debt")
                 })?;
+            emit_fee_debt_change(rt, &prior_fee_debt, &state.fee_debt)?;
 
             Ok((burn_amount, total_unlocked, state.clone()))
         })?;
@@ -4396,6 +5380,7 @@ fn process_early_terminations(
         }
 
         // Apply penalty (add to fee debt)
+        let prior_fee_debt = state.fee_debt.clone();
         state
             .apply_penalty(&total_penalty)
             .map_err(|e| actor_error!(illegal_state, "failed to apply penalty: {}", e))?;
@@ -4416,6 +5401,7 @@ fn process_early_terminations(
             .map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to repay penalty")
             })?;
+        emit_fee_debt_change(rt, &prior_fee_debt, &state.fee_debt)?;
 
         pledge_delta -= total_unlocked;
 
@@ -4482,6 +5468,7 @@ fn handle_proving_deadline(
                     "failed to expire pre-committed sectors",
                 )
             })?;
+        let prior_fee_debt = state.fee_debt.clone();
         state
             .apply_penalty(&deposit_to_burn)
             .map_err(|e| actor_error!(illegal_state, "failed to apply penalty: {}", e))?;
@@ -4550,6 +5537,7 @@ fn handle_proving_deadline(
             .map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to unlock penalty")
             })?;
+        emit_fee_debt_change(rt, &prior_fee_debt, &state.fee_debt)?;
 
         penalty_total = penalty;
         pledge_delta_total -= total_unlocked;
@@ -5183,6 +6171,32 @@ fn request_current_epoch_block_reward(
     )
 }
 
+/// Checks whether a seal proof type not accepted by policy has been registered with the power
+/// actor as experimental, on networks whose policy allows experimental seal proofs.
+/// The registered types are fetched from the power actor at most once, into `registered`.
+fn is_experimental_seal_proof(
+    rt: &impl Runtime,
+    registered: &mut Option<Vec<RegisteredSealProof>>,
+    proof: RegisteredSealProof,
+) -> Result<bool, ActorError> {
+    if !rt.policy().allow_experimental_seal_proofs {
+        return Ok(false);
+    }
+    if registered.is_none() {
+        let ret: ext::power::GetExperimentalSealProofsReturn = deserialize_block(
+            extract_send_result(rt.send_simple(
+                &STORAGE_POWER_ACTOR_ADDR,
+                ext::power::GET_EXPERIMENTAL_SEAL_PROOFS_METHOD,
+                None,
+                TokenAmount::zero(),
+            ))
+            .map_err(|e| e.wrap("failed to fetch experimental seal proofs"))?,
+        )?;
+        *registered = Some(ret.proofs);
+    }
+    Ok(registered.as_ref().is_some_and(|proofs| proofs.contains(&proof)))
+}
+
 /// Requests the current network total power and pledge from the power actor.
 fn request_current_total_power(
     rt: &impl Runtime,
@@ -5245,6 +6259,7 @@ fn burn_funds(rt: &impl Runtime, amount: TokenAmount) -> Result<(), ActorError>
 
 fn notify_pledge_changed(rt: &impl Runtime, pledge_delta: &TokenAmount) -> Result<(), ActorError> {
     if !pledge_delta.is_zero() {
+        emit::pledge_changed(rt, pledge_delta)?;
         extract_send_result(rt.send_simple(
             &STORAGE_POWER_ACTOR_ADDR,
             ext::power::UPDATE_PLEDGE_TOTAL_METHOD,
@@ -5345,6 +6360,136 @@ fn declaration_deadline_info(
     Ok(deadline)
 }
 
+/// Returns the result of each fault or recovery declaration, given the deadline of each
+/// declaration and the deadlines which failed.
+fn declaration_results(
+    declared_deadlines: &[u64],
+    failed_deadlines: &BTreeMap<u64, ExitCode>,
+) -> BatchReturn {
+    let mut batch_gen = BatchReturnGen::new(declared_deadlines.len());
+    for deadline in declared_deadlines {
+        batch_gen.add(*failed_deadlines.get(deadline).unwrap_or(&ExitCode::OK));
+    }
+    batch_gen.generate()
+}
+
+/// Records declared faults at a single deadline, returning the power of the newly faulty sectors.
+/// The deadline is written back to `deadlines` only once every partition has been updated.
+#[allow(clippy::too_many_arguments)]
+fn declare_faults_at_deadline<BS: Blockstore>(
+    policy: &Policy,
+    store: &BS,
+    info: &MinerInfo,
+    deadlines: &mut Deadlines,
+    sectors: &Sectors<'_, BS>,
+    proving_period_start: ChainEpoch,
+    curr_epoch: ChainEpoch,
+    deadline_idx: u64,
+    partition_map: &mut PartitionSectorMap,
+) -> Result<PowerPair, ActorError> {
+    let target_deadline = declaration_deadline_info(
+        policy,
+        proving_period_start,
+        deadline_idx,
+        curr_epoch,
+    )
+    .map_err(|e| {
+        actor_error!(illegal_argument, "invalid fault declaration deadline {}: {}", deadline_idx, e)
+    })?;
+
+    validate_fr_declaration_deadline(&target_deadline).map_err(|e| {
+        actor_error!(
+            illegal_argument,
+            "failed fault declaration at deadline {}: {}",
+            deadline_idx,
+            e
+        )
+    })?;
+
+    let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
+
+    let fault_expiration_epoch = target_deadline.last() + info.fault_max_age(policy);
+
+    let deadline_power_delta = deadline
+        .record_faults(
+            store,
+            sectors,
+            info.sector_size,
+            target_deadline.quant_spec(),
+            fault_expiration_epoch,
+            partition_map,
+        )
+        .map_err(|e| {
+            e.downcast_default(
+                ExitCode::USR_ILLEGAL_STATE,
+                format!("failed to declare faults for deadline {}", deadline_idx),
+            )
+        })?;
+
+    deadlines.update_deadline(policy, store, deadline_idx, &deadline).map_err(|e| {
+        e.downcast_default(
+            ExitCode::USR_ILLEGAL_STATE,
+            format!("failed to store deadline {} partitions", deadline_idx),
+        )
+    })?;
+
+    Ok(deadline_power_delta)
+}
+
+/// Records declared recoveries at a single deadline.
+/// The deadline is written back to `deadlines` only once every partition has been updated.
+#[allow(clippy::too_many_arguments)]
+fn declare_recoveries_at_deadline<BS: Blockstore>(
+    policy: &Policy,
+    store: &BS,
+    info: &MinerInfo,
+    deadlines: &mut Deadlines,
+    sectors: &Sectors<'_, BS>,
+    proving_period_start: ChainEpoch,
+    curr_epoch: ChainEpoch,
+    deadline_idx: u64,
+    partition_map: &mut PartitionSectorMap,
+) -> Result<(), ActorError> {
+    let target_deadline =
+        declaration_deadline_info(policy, proving_period_start, deadline_idx, curr_epoch).map_err(
+            |e| {
+                actor_error!(
+                    illegal_argument,
+                    "invalid recovery declaration deadline {}: {}",
+                    deadline_idx,
+                    e
+                )
+            },
+        )?;
+
+    validate_fr_declaration_deadline(&target_deadline).map_err(|e| {
+        actor_error!(
+            illegal_argument,
+            "failed recovery declaration at deadline {}: {}",
+            deadline_idx,
+            e
+        )
+    })?;
+
+    let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
+
+    deadline.declare_faults_recovered(store, sectors, info.sector_size, partition_map).map_err(
+        |e| {
+            e.downcast_default(
+                ExitCode::USR_ILLEGAL_STATE,
+                format!("failed to declare recoveries for deadline {}", deadline_idx),
+            )
+        },
+    )?;
+
+    deadlines.update_deadline(policy, store, deadline_idx, &deadline).map_err(|e| {
+        e.downcast_default(
+            ExitCode::USR_ILLEGAL_STATE,
+            format!("failed to store deadline {}", deadline_idx),
+        )
+    })
+}
+
 /// Checks that a fault or recovery declaration at a specific deadline is outside the exclusion window for the deadline.
 fn validate_fr_declaration_deadline(deadline: &DeadlineInfo) -> anyhow::Result<()> {
     if deadline.fault_cutoff_passed() {
@@ -5431,14 +6576,28 @@ fn process_pending_worker(
 /// almost always redundant since vesting is quantized to ~daily units.  Vesting
 /// will be at most one proving period old if computed in the cron callback.
 fn repay_debts_or_abort(rt: &impl Runtime, state: &mut State) -> Result<TokenAmount, ActorError> {
+    let prior_fee_debt = state.fee_debt.clone();
     let res = state.repay_debts(&rt.current_balance()).map_err(|e| {
         e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "unlocked balance can not repay fee debt")
     })?;
     info!("RepayDebtsOrAbort was called and succeeded");
+    emit_fee_debt_change(rt, &prior_fee_debt, &state.fee_debt)?;
     Ok(res)
 }
 
-fn check_control_addresses(policy: &Policy, control_addrs: &[Address]) -> Result<(), ActorError> {
+/// Emits an event if the fee debt has changed from a prior value to the current one.
+fn emit_fee_debt_change(
+    rt: &impl Runtime,
+    prior: &TokenAmount,
+    fee_debt: &TokenAmount,
+) -> Result<(), ActorError> {
+    if prior != fee_debt {
+        emit::fee_debt_changed(rt, &(fee_debt - prior), fee_debt)?;
+    }
+    Ok(())
+}
+
+fn check_control_addresses<T>(policy: &Policy, control_addrs: &[T]) -> Result<(), ActorError> {
     if control_addrs.len() > policy.max_control_addresses {
         return Err(actor_error!(
             illegal_argument,
@@ -5600,9 +6759,15 @@ fn activate_new_sector_infos(
             .add_pre_commit_deposit(&(-deposit_to_unlock))
             .map_err(|e| actor_error!(illegal_state, "failed to add precommit deposit: {}", e))?;
 
-        let unlocked_balance = state.get_unlocked_balance(&rt.current_balance()).map_err(|e| {
-            actor_error!(illegal_state, "failed to calculate unlocked balance: {}", e)
-        })?;
+        let mut unlocked_balance =
+            state.get_unlocked_balance(&rt.current_balance()).map_err(|e| {
+                actor_error!(illegal_state, "failed to calculate unlocked balance: {}", e)
+            })?;
+        if unlocked_balance < total_pledge {
+            // Cover the shortfall from the pledge facility, if one is available.
+            unlocked_balance +=
+                state.draw_pledge_facility(rt.curr_epoch(), &(&total_pledge - &unlocked_balance));
+        }
         if unlocked_balance < total_pledge {
             return Err(actor_error!(
                 insufficient_funds,
@@ -5747,6 +6912,18 @@ fn activate_sectors_pieces(
                     size: piece.size,
                 });
             }
+            if !piece.verified_allocation_parts.is_empty() {
+                validate_allocation_parts(piece, activation_info.sector_number)?;
+                // Each allocation is claimed by the piece, for the allocation's portion of it.
+                sector_claims.extend(piece.verified_allocation_parts.iter().map(|part| {
+                    ext::verifreg::AllocationClaim {
+                        client: part.client,
+                        allocation_id: part.id,
+                        data: piece.cid,
+                        size: part.size,
+                    }
+                }));
+            }
         }
         verified_claims.push(ext::verifreg::SectorAllocationClaims {
             sector: activation_info.sector_number,
@@ -5771,7 +6948,7 @@ fn activate_sectors_pieces(
             let mut unverified_space = BigInt::zero();
             let mut pieces = Vec::new();
             for piece in *sector_pieces {
-                if piece.verified_allocation_key.is_none() {
+                if !piece.is_verified() {
                     unverified_space += piece.size.0;
                 }
                 pieces.push((piece.cid, piece.size.0));
@@ -5788,6 +6965,43 @@ fn activate_sectors_pieces(
     Ok((claim_res.sector_results, activation_outputs))
 }
 
+// Checks that a piece's verified allocation parts exactly cover the piece.
+fn validate_allocation_parts(
+    piece: &PieceActivationManifest,
+    sector_number: SectorNumber,
+) -> Result<(), ActorError> {
+    if piece.verified_allocation_key.is_some() {
+        return Err(actor_error!(
+            illegal_argument,
+            "piece {} in sector {} specifies both an allocation key and allocation parts",
+            piece.cid,
+            sector_number
+        ));
+    }
+    if piece.verified_allocation_parts.iter().any(|part| part.size.0 == 0) {
+        return Err(actor_error!(
+            illegal_argument,
+            "piece {} in sector {} has an empty allocation part",
+            piece.cid,
+            sector_number
+        ));
+    }
+    let parts_size = piece
+        .verified_allocation_parts
+        .iter()
+        .try_fold(0u64, |acc, part| acc.checked_add(part.size.0));
+    if parts_size != Some(piece.size.0) {
+        return Err(actor_error!(
+            illegal_argument,
+            "allocation parts of piece {} in sector {} do not sum to piece size {}",
+            piece.cid,
+            sector_number,
+            piece.size.0
+        ));
+    }
+    Ok(())
+}
+
 /// Activates deals then claims allocations for any verified deals
 /// Deals and claims are grouped by sectors
 /// Successfully activated sectors have their DealSpaces returned
@@ -6019,6 +7233,32 @@ impl ActorCode for Actor {
         ProveCommitSectorsNI => prove_commit_sectors_ni,
         MaxTerminationFeeExported => max_termination_fee,
         InitialPledgeExported => initial_pledge,
+        GetDeadlineTimingExported => get_deadline_timing,
+        OnClaimRevokedExported => on_claim_revoked,
+        GetPendingBeneficiaryChangeExported => get_pending_beneficiary_change,
+        GetSectorsExported => get_sectors,
+        GetDeadlineSummariesExported => get_deadline_summaries,
+        GetPartitionSummariesExported => get_partition_summaries,
+        ExtendSectorExpirationsByClassExported => extend_sector_expirations_by_class,
+        AuthorizePledgeLenderExported => authorize_pledge_lender,
+        DepositPledgeFacilityExported => deposit_pledge_facility,
+        WithdrawPledgeFacilityExported => withdraw_pledge_facility,
+        GetPledgeFacilityExported => get_pledge_facility,
+        GetPendingWorkerKeyChangeExported => get_pending_worker_key_change,
+        CancelChangeWorkerAddressExported => cancel_change_worker_address,
+        EstimateTerminationFeeExported => estimate_termination_fee,
+        GetSectorEconomicsExported => get_sector_economics,
+        GetPartitionPoStStatsExported => get_partition_post_stats,
+        SetFaultTerminationThresholdExported => set_fault_termination_threshold,
+        SimulatePenaltyExported => simulate_penalty,
+        DeclareFaults2Exported => declare_faults2,
+        DeclareFaultsRecovered2Exported => declare_faults_recovered2,
+        GetCompactablePartitionsExported => get_compactable_partitions,
+        ChangeWorkerAddress2Exported => change_worker_address2,
+        SetSectorMetadataExported => set_sector_metadata,
+        GetSectorMetadataExported => get_sector_metadata,
+        GetPowerBreakdownExported => get_power_breakdown,
+        GetAvailableBalanceBreakdownExported => get_available_balance_breakdown,
     }
 }
 
@@ -63,6 +63,10 @@ pub struct Partition {
     pub faulty_power: PowerPair,
     /// Power of expected-to-recover sectors. RecoveringPower <= FaultyPower.
     pub recovering_power: PowerPair,
+    /// Outcomes of the partition's WindowPoSt challenges.
+    /// Zero for partitions stored before outcomes were recorded.
+    #[serde(default)]
+    pub post_stats: PoStStats,
 }
 
 impl Partition {
@@ -88,6 +92,7 @@ impl Partition {
             unproven_power: PowerPair::zero(),
             faulty_power: PowerPair::zero(),
             recovering_power: PowerPair::zero(),
+            post_stats: PoStStats::default(),
         })
     }
 
@@ -453,6 +458,51 @@ impl Partition {
         Ok((power_delta, pledge_delta, fee_delta))
     }
 
+    /// Replaces the info of a single sector whose power has been revised in place.
+    /// The sector must be live and not faulty, but may be unproven.
+    /// Returns the deltas to live power and daily fee, and whether the sector is unproven
+    /// (in which case its power is not yet active).
+    pub fn revise_sector<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        old_sector: &SectorOnChainInfo,
+        new_sector: &SectorOnChainInfo,
+        sector_size: SectorSize,
+        quant: QuantSpec,
+    ) -> anyhow::Result<(PowerPair, TokenAmount, bool)> {
+        let sector_number = old_sector.sector_number;
+        if !self.live_sectors().get(sector_number) || self.faults.get(sector_number) {
+            return Err(anyhow!("refusing to revise inactive sector {}", sector_number));
+        }
+
+        let mut expirations = ExpirationQueue::new(store, &self.expirations_epochs, quant)
+            .map_err(|e| e.downcast_wrap("failed to load sector expirations"))?;
+
+        let (_, _, power_delta, _, fee_delta) = expirations
+            .replace_sectors(
+                std::slice::from_ref(old_sector),
+                std::slice::from_ref(new_sector),
+                sector_size,
+            )
+            .map_err(|e| e.downcast_wrap("failed to replace sector expirations"))?;
+
+        self.expirations_epochs = expirations
+            .amt
+            .flush()
+            .map_err(|e| e.downcast_wrap("failed to save sector expirations"))?;
+
+        self.live_power += &power_delta;
+        let unproven = self.unproven.get(sector_number);
+        if unproven {
+            self.unproven_power += &power_delta;
+        }
+
+        // check invariants
+        self.validate_state()?;
+
+        Ok((power_delta, fee_delta, unproven))
+    }
+
     /// Record the epoch of any sectors expiring early, for termination fee calculation later.
     pub fn record_early_termination<BS: Blockstore>(
         &mut self,
@@ -828,6 +878,47 @@ impl Partition {
     }
 }
 
+/// Number of most recent WindowPoSt outcomes retained in [`PoStStats::recent`].
+pub const POST_STATS_RECENT_WINDOW: u64 = u64::BITS as u64;
+
+/// Counts of a partition's WindowPoSt outcomes, one per proving period in which the partition
+/// had live power to prove.
+/// A missed PoSt is not recorded while all of the partition's power is faulty and none is
+/// recovering, so that such partitions are not rewritten every proving period.
+/// A proof accepted optimistically and later successfully disputed still counts as a success.
+#[derive(Serialize_tuple, Deserialize_tuple, Eq, PartialEq, Clone, Debug, Default)]
+pub struct PoStStats {
+    /// Number of challenges for which a PoSt was accepted. Saturates at u64::MAX.
+    pub successes: u64,
+    /// Number of challenges missed. Saturates at u64::MAX.
+    pub failures: u64,
+    /// Outcomes of the most recent challenges, up to POST_STATS_RECENT_WINDOW, as a bit
+    /// history with the most recent outcome in the lowest bit. A set bit is a success.
+    pub recent: u64,
+}
+
+impl PoStStats {
+    /// Records the outcome of a challenge.
+    pub fn record(&mut self, success: bool) {
+        if success {
+            self.successes = self.successes.saturating_add(1);
+        } else {
+            self.failures = self.failures.saturating_add(1);
+        }
+        self.recent = (self.recent << 1) | success as u64;
+    }
+
+    /// Number of outcomes recorded in the recent history.
+    pub fn recent_count(&self) -> u64 {
+        self.successes.saturating_add(self.failures).min(POST_STATS_RECENT_WINDOW)
+    }
+
+    /// Number of successes in the recent history.
+    pub fn recent_successes(&self) -> u64 {
+        self.recent.count_ones() as u64
+    }
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Eq, PartialEq, Clone, Debug, Default)]
 // Value type for a pair of raw and QA power.
 pub struct PowerPair {
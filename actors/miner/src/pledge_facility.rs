@@ -0,0 +1,49 @@
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
+
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use num_traits::Zero;
+
+/// Funds deposited by a lender authorized by the owner, from which the miner draws
+/// when its unlocked balance falls short of the initial pledge for newly proven sectors.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct PledgeFacility {
+    /// The lender authorized to fund the facility, as an ID address.
+    pub lender: Address,
+    /// The total amount the miner may draw from the lender's deposits.
+    pub cap: TokenAmount,
+    /// The amount already drawn into the miner's balance.
+    pub drawn: TokenAmount,
+    /// The last epoch at which the facility may be funded or drawn from.
+    pub expiration: ChainEpoch,
+    /// The lender's deposited funds not yet drawn. Excluded from the miner's unlocked balance.
+    pub balance: TokenAmount,
+}
+
+impl PledgeFacility {
+    pub fn new(lender: Address, cap: TokenAmount, expiration: ChainEpoch) -> Self {
+        PledgeFacility {
+            lender,
+            cap,
+            drawn: TokenAmount::zero(),
+            expiration,
+            balance: TokenAmount::zero(),
+        }
+    }
+
+    /// Whether the facility may still be funded or drawn from.
+    pub fn is_active(&self, cur: ChainEpoch) -> bool {
+        cur <= self.expiration
+    }
+
+    /// The amount that may currently be drawn: the lesser of the deposited balance
+    /// and the remaining cap, or zero once the facility has expired.
+    pub fn available(&self, cur: ChainEpoch) -> TokenAmount {
+        if !self.is_active(cur) {
+            return TokenAmount::zero();
+        }
+        let remaining = (&self.cap - &self.drawn).max(TokenAmount::zero());
+        remaining.min(self.balance.clone())
+    }
+}
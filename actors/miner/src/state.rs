@@ -10,7 +10,7 @@ use cid::Cid;
 use fvm_ipld_bitfield::BitField;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
-use fvm_ipld_encoding::{BytesDe, CborStore, strict_bytes};
+use fvm_ipld_encoding::{BytesDe, CborStore, RawBytes, strict_bytes};
 use fvm_shared::address::Address;
 use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
 use fvm_shared::econ::TokenAmount;
@@ -30,6 +30,7 @@ use fil_actors_runtime::{
 
 use super::beneficiary::*;
 use super::deadlines::new_deadline_info;
+use super::pledge_facility::*;
 use super::policy::*;
 use super::types::*;
 use super::{
@@ -41,6 +42,10 @@ use super::{
 pub type PreCommitMap<BS> = Map2<BS, SectorNumber, SectorPreCommitOnChainInfo>;
 pub const PRECOMMIT_CONFIG: Config = Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
 
+pub type SectorMetadataMap<BS> = Map2<BS, SectorNumber, RawBytes>;
+pub const SECTOR_METADATA_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
 const PRECOMMIT_EXPIRY_AMT_BITWIDTH: u32 = 6;
 pub const SECTORS_AMT_BITWIDTH: u32 = 5;
 
@@ -111,6 +116,14 @@ pub struct State {
 
     // True when miner cron is active, false otherwise
     pub deadline_cron_active: bool,
+
+    // Pledge financing authorized by the owner, if any.
+    #[serde(default)]
+    pub pledge_facility: Option<PledgeFacility>,
+
+    // Metadata attached to sectors by the owner, if any.
+    #[serde(default)]
+    pub sector_metadata: Option<Cid>, // HAMT[SectorNumber]RawBytes
 }
 
 #[derive(PartialEq, Eq)]
@@ -183,6 +196,8 @@ impl State {
             early_terminations: BitField::new(),
             deadline_cron_active: false,
             pre_committed_sectors_cleanup: empty_precommits_cleanup_array,
+            pledge_facility: None,
+            sector_metadata: None,
         })
     }
 
@@ -204,6 +219,16 @@ impl State {
         Ok(())
     }
 
+    /// Re-encodes miner info written before fields were appended to it, such as the control
+    /// address roles, with those fields at their defaults (so every control address is
+    /// unrestricted). Returns whether the stored info changed.
+    pub fn migrate_info<BS: Blockstore>(&mut self, store: &BS) -> anyhow::Result<bool> {
+        let prior = self.info;
+        let info = self.get_info(store)?;
+        self.save_info(store, &info)?;
+        Ok(self.info != prior)
+    }
+
     /// Returns deadline calculations for the current (according to state) proving period.
     pub fn deadline_info(&self, policy: &Policy, current_epoch: ChainEpoch) -> DeadlineInfo {
         new_deadline_info_from_offset_and_epoch(policy, self.proving_period_start, current_epoch)
@@ -387,6 +412,61 @@ impl State {
         sectors.get(sector_num)
     }
 
+    /// Returns the metadata attached to a sector, empty if none.
+    pub fn get_sector_metadata<BS: Blockstore>(
+        &self,
+        store: &BS,
+        sector_num: SectorNumber,
+    ) -> Result<RawBytes, ActorError> {
+        let Some(root) = &self.sector_metadata else {
+            return Ok(RawBytes::default());
+        };
+        let metadata =
+            SectorMetadataMap::load(store, root, SECTOR_METADATA_CONFIG, "sector metadata")?;
+        Ok(metadata.get(&sector_num)?.cloned().unwrap_or_default())
+    }
+
+    /// Sets the metadata attached to sectors, removing it where the new metadata is empty.
+    pub fn set_sector_metadata<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        entries: &[(SectorNumber, RawBytes)],
+    ) -> Result<(), ActorError> {
+        let mut metadata = match &self.sector_metadata {
+            Some(root) => {
+                SectorMetadataMap::load(store, root, SECTOR_METADATA_CONFIG, "sector metadata")?
+            }
+            None => SectorMetadataMap::empty(store, SECTOR_METADATA_CONFIG, "sector metadata"),
+        };
+        for (sector_num, value) in entries {
+            if value.is_empty() {
+                metadata.delete(sector_num)?;
+            } else {
+                metadata.set(sector_num, value.clone())?;
+            }
+        }
+        self.sector_metadata = if metadata.is_empty() { None } else { Some(metadata.flush()?) };
+        Ok(())
+    }
+
+    /// Removes the metadata of sectors which are being deleted.
+    pub fn delete_sector_metadata<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        sector_nums: &BitField,
+    ) -> Result<(), ActorError> {
+        let Some(root) = &self.sector_metadata else {
+            return Ok(());
+        };
+        let mut metadata =
+            SectorMetadataMap::load(store, root, SECTOR_METADATA_CONFIG, "sector metadata")?;
+        for sector_num in sector_nums.iter() {
+            metadata.delete(&sector_num)?;
+        }
+        self.sector_metadata = if metadata.is_empty() { None } else { Some(metadata.flush()?) };
+        Ok(())
+    }
+
     pub fn for_each_sector<BS: Blockstore, F>(&self, store: &BS, mut f: F) -> anyhow::Result<()>
     where
         F: FnMut(&SectorOnChainInfo) -> anyhow::Result<()>,
@@ -926,6 +1006,31 @@ impl State {
         Ok((unlocked_unvested, total_unlocked))
     }
 
+    /// Computes the outcome of applying a penalty at the current epoch and repaying the resulting
+    /// fee debt with `repay_partial_debt_in_priority_order`, on a copy of the state.
+    /// Returns the amounts of vested and unvested funds unlocked, the amount burnt,
+    /// and the fee debt remaining.
+    pub fn simulate_penalty<BS: Blockstore>(
+        &self,
+        store: &BS,
+        current_epoch: ChainEpoch,
+        curr_balance: &TokenAmount,
+        penalty: &TokenAmount,
+    ) -> anyhow::Result<(TokenAmount, TokenAmount, TokenAmount, TokenAmount)> {
+        let mut st = self.clone();
+        st.apply_penalty(penalty)?;
+        // Repayment unlocks all vested funds before any unvested funds, so unlocking the vested
+        // funds first separates the two without changing the outcome.
+        let unlocked_vested = if st.fee_debt.is_zero() {
+            TokenAmount::zero()
+        } else {
+            st.unlock_vested_funds(store, current_epoch)?
+        };
+        let (burnt, unlocked_unvested) =
+            st.repay_partial_debt_in_priority_order(store, current_epoch, curr_balance)?;
+        Ok((unlocked_vested, unlocked_unvested, burnt, st.fee_debt))
+    }
+
     /// Unlocks all vesting funds that have vested before the provided epoch.
     /// Returns the amount unlocked.
     pub fn unlock_vested_funds<BS: Blockstore>(
@@ -950,9 +1055,13 @@ impl State {
     }
 
     /// Unclaimed funds that are not locked -- includes funds used to cover initial pledge requirement.
+    /// Excludes undrawn funds held in the pledge facility.
     pub fn get_unlocked_balance(&self, actor_balance: &TokenAmount) -> anyhow::Result<TokenAmount> {
-        let unlocked_balance =
-            actor_balance - &self.locked_funds - &self.pre_commit_deposits - &self.initial_pledge;
+        let unlocked_balance = actor_balance
+            - &self.locked_funds
+            - &self.pre_commit_deposits
+            - &self.initial_pledge
+            - self.pledge_facility_balance();
         if unlocked_balance.is_negative() {
             return Err(anyhow!("negative unlocked balance {}", unlocked_balance));
         }
@@ -983,7 +1092,15 @@ impl State {
             return Err(anyhow!("fee debt is negative: {}", self.fee_debt));
         }
 
-        let min_balance = &self.pre_commit_deposits + &self.locked_funds + &self.initial_pledge;
+        let facility_balance = self.pledge_facility_balance();
+        if facility_balance.is_negative() {
+            return Err(anyhow!("pledge facility balance is negative: {}", facility_balance));
+        }
+
+        let min_balance = &self.pre_commit_deposits
+            + &self.locked_funds
+            + &self.initial_pledge
+            + facility_balance;
         if balance < &min_balance {
             return Err(anyhow!("balance {} below minimum {}", balance, min_balance));
         }
@@ -991,6 +1108,27 @@ impl State {
         Ok(())
     }
 
+    /// Undrawn funds deposited into the pledge facility.
+    pub fn pledge_facility_balance(&self) -> TokenAmount {
+        self.pledge_facility.as_ref().map(|f| f.balance.clone()).unwrap_or_default()
+    }
+
+    /// Draws up to `amount` from the pledge facility into the unlocked balance,
+    /// returning the amount drawn.
+    pub fn draw_pledge_facility(
+        &mut self,
+        curr_epoch: ChainEpoch,
+        amount: &TokenAmount,
+    ) -> TokenAmount {
+        let Some(facility) = self.pledge_facility.as_mut() else {
+            return TokenAmount::zero();
+        };
+        let drawn = facility.available(curr_epoch).min(amount.clone()).max(TokenAmount::zero());
+        facility.balance -= &drawn;
+        facility.drawn += &drawn;
+        drawn
+    }
+
     /// pre-commit expiry
     pub fn quant_spec_every_deadline(&self, policy: &Policy) -> QuantSpec {
         QuantSpec { unit: policy.wpost_challenge_window, offset: self.proving_period_start }
@@ -1118,10 +1256,15 @@ impl State {
         let quant = quant_spec_for_deadline(policy, &dl_info);
 
         // Detect and penalize missing proofs.
-        let fault_expiration = dl_info.last() + policy.fault_max_age;
-
+        // The miner's fault termination threshold is read only if there are faults to schedule.
+        let fault_expiration = || {
+            let info = self.get_info(store).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load miner info")
+            })?;
+            Ok(dl_info.last() + info.fault_max_age(policy))
+        };
         let (mut power_delta, detected_faulty_power) =
-            deadline.process_deadline_end(store, quant, fault_expiration, self.sectors)?;
+            deadline.process_deadline_end_with(store, quant, fault_expiration, self.sectors)?;
 
         // Capture deadline's faulty power after new faults have been detected, but before it is
         // dropped along with faulty sectors expiring this round.
@@ -1256,6 +1399,21 @@ pub struct MinerInfo {
 
     /// A proposal new beneficiary message for this miner
     pub pending_beneficiary_term: Option<PendingBeneficiaryChange>,
+
+    /// Owner-configured number of epochs a sector may remain faulty before it is terminated,
+    /// if less than the network's fault max age. Applies to sectors faulted after it is set.
+    #[serde(default)]
+    pub fault_termination_threshold: Option<ChainEpoch>,
+
+    /// Roles restricting the methods each control address may call, by position.
+    /// Empty if every control address may act in any role.
+    #[serde(default)]
+    pub control_address_roles: Vec<ControlAddressRole>,
+
+    /// The last epoch at which the pending beneficiary change may be approved.
+    /// None for a proposal made before proposals expired, until it is migrated.
+    #[serde(default)]
+    pub pending_beneficiary_expiration: Option<ChainEpoch>,
 }
 
 impl MinerInfo {
@@ -1291,6 +1449,63 @@ impl MinerInfo {
             window_post_partition_sectors,
             consensus_fault_elapsed: EPOCH_UNDEFINED,
             pending_owner_address: None,
+            fault_termination_threshold: None,
+            control_address_roles: vec![],
+            pending_beneficiary_expiration: None,
         })
     }
+
+    /// The role of the control address at an index.
+    pub fn control_address_role(&self, index: usize) -> ControlAddressRole {
+        self.control_address_roles.get(index).copied().unwrap_or_default()
+    }
+
+    /// The control addresses permitted to call a method requiring a role: those with the role
+    /// itself and those unrestricted.
+    pub fn control_addresses_for(
+        &self,
+        role: ControlAddressRole,
+    ) -> impl Iterator<Item = &Address> + '_ {
+        self.control_addresses.iter().enumerate().filter_map(move |(i, address)| {
+            let granted = self.control_address_role(i);
+            (granted == ControlAddressRole::Any || granted == role).then_some(address)
+        })
+    }
+
+    /// Whether the pending beneficiary change has expired and can no longer be approved.
+    pub fn pending_beneficiary_expired(&self, cur: ChainEpoch) -> bool {
+        self.pending_beneficiary_term.is_some()
+            && self.pending_beneficiary_expiration.is_some_and(|expiration| cur > expiration)
+    }
+
+    /// The pending beneficiary change, if it has not expired.
+    pub fn live_pending_beneficiary_term(
+        &self,
+        cur: ChainEpoch,
+    ) -> Option<&PendingBeneficiaryChange> {
+        self.pending_beneficiary_term.as_ref().filter(|_| !self.pending_beneficiary_expired(cur))
+    }
+
+    /// Sets the expiration of a pending beneficiary change proposed before proposals expired,
+    /// giving it the full proposal lifetime from the upgrade epoch.
+    /// Returns whether the info changed.
+    pub fn migrate_pending_beneficiary_expiration(
+        &mut self,
+        policy: &Policy,
+        upgrade_epoch: ChainEpoch,
+    ) -> bool {
+        if self.pending_beneficiary_term.is_none() || self.pending_beneficiary_expiration.is_some()
+        {
+            return false;
+        }
+        self.pending_beneficiary_expiration =
+            Some(upgrade_epoch + policy.beneficiary_proposal_lifetime);
+        true
+    }
+
+    /// The number of epochs after which a newly faulty sector is terminated.
+    pub fn fault_max_age(&self, policy: &Policy) -> ChainEpoch {
+        self.fault_termination_threshold
+            .map_or(policy.fault_max_age, |threshold| threshold.min(policy.fault_max_age))
+    }
 }
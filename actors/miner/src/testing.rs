@@ -1,7 +1,8 @@
 use crate::{
     BitFieldQueue, Deadline, ExpirationQueue, MinerInfo, NO_QUANTIZATION, PRECOMMIT_CONFIG,
-    Partition, PowerPair, PreCommitMap, QuantSpec, SectorOnChainInfo, SectorOnChainInfoFlags,
-    Sectors, State, daily_fee_for_sectors, power_for_sectors,
+    Partition, PowerPair, PreCommitMap, QuantSpec, SECTOR_METADATA_CONFIG, SectorMetadataMap,
+    SectorOnChainInfo, SectorOnChainInfoFlags, Sectors, State, daily_fee_for_sectors,
+    power_for_sectors,
 };
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{DealWeight, MessageAccumulator};
@@ -105,6 +106,26 @@ pub fn check_state_invariants<BS: Blockstore>(
         Err(e) => acc.add(format!("error loading sectors: {e}")),
     };
 
+    if let Some(root) = &state.sector_metadata {
+        match SectorMetadataMap::load(store, root, SECTOR_METADATA_CONFIG, "sector metadata") {
+            Ok(metadata) => {
+                let ret = metadata.for_each(|sector_number, value| {
+                    acc.require(
+                        all_sectors.contains_key(&sector_number),
+                        format!("metadata for missing sector {sector_number}"),
+                    );
+                    acc.require(
+                        !value.is_empty(),
+                        format!("empty metadata for sector {sector_number}"),
+                    );
+                    Ok(())
+                });
+                acc.require_no_error(ret, "error iterating sector metadata");
+            }
+            Err(e) => acc.add(format!("error loading sector metadata: {e}")),
+        }
+    }
+
     // check deadlines
     acc.require(
         state.current_deadline < policy.wpost_period_deadlines,
@@ -193,6 +214,15 @@ fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
             format!("control address {} is not an ID address", address),
         )
     });
+    acc.require(
+        info.control_address_roles.is_empty()
+            || info.control_address_roles.len() == info.control_addresses.len(),
+        format!(
+            "{} control address roles for {} control addresses",
+            info.control_address_roles.len(),
+            info.control_addresses.len()
+        ),
+    );
 
     if let Some(pending_worker_key) = info.pending_worker_key {
         acc.require(
@@ -272,7 +302,7 @@ fn check_miner_balances<BS: Blockstore>(
         format!("miner fee debt is less than zero: {}", state.fee_debt),
     );
 
-    acc.require(!(balance - &state.locked_funds - &state.pre_commit_deposits - &state.initial_pledge).is_negative(), format!("miner balance {balance} is less than sum of locked funds ({}), precommit deposit ({}) and initial pledge ({})", state.locked_funds, state.pre_commit_deposits, state.initial_pledge));
+    acc.require(!(balance - &state.locked_funds - &state.pre_commit_deposits - &state.initial_pledge - state.pledge_facility_balance()).is_negative(), format!("miner balance {balance} is less than sum of locked funds ({}), precommit deposit ({}), initial pledge ({}) and pledge facility balance ({})", state.locked_funds, state.pre_commit_deposits, state.initial_pledge, state.pledge_facility_balance()));
 
     // locked funds must be sum of vesting table and vesting table payments must be quantized
     let mut vesting_sum = TokenAmount::zero();
@@ -3,6 +3,7 @@
 
 use cid::Cid;
 use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::repr::*;
 use fvm_ipld_encoding::{BytesDe, strict_bytes};
 use fvm_ipld_encoding::{RawBytes, tuple::*};
 use fvm_shared::ActorID;
@@ -17,8 +18,13 @@ use fvm_shared::sector::{
     PoStProof, RegisteredAggregateProof, RegisteredPoStProof, RegisteredSealProof,
     RegisteredUpdateProof, SectorNumber, SectorSize, StoragePower,
 };
+use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 
+pub use fil_actors_runtime::builtin::miner::{
+    PieceChange, PieceReturn, SectorChanges, SectorContentChangedParams,
+    SectorContentChangedReturn, SectorReturn,
+};
 use fil_actors_runtime::reward::FilterEstimate;
 use fil_actors_runtime::{BatchReturn, DealWeight};
 
@@ -27,6 +33,8 @@ use crate::ext::verifreg::AllocationID;
 use crate::ext::verifreg::ClaimID;
 
 use super::beneficiary::*;
+use super::pledge_facility::*;
+use super::{DeadlineInfo, Partition, PowerPair};
 
 pub type CronEvent = i64;
 
@@ -52,7 +60,7 @@ pub struct CronEventPayload {
     pub event_type: i64,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize_tuple, Deserialize_tuple)]
 pub struct PartitionKey {
     pub deadline: u64,
     pub partition: u64,
@@ -63,6 +71,9 @@ pub struct GetControlAddressesReturn {
     pub owner: Address,
     pub worker: Address,
     pub control_addresses: Vec<Address>,
+    /// Roles of the control addresses, by position. Empty if every control address is unrestricted.
+    #[serde(default)]
+    pub control_address_roles: Vec<ControlAddressRole>,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -71,6 +82,31 @@ pub struct ChangeWorkerAddressParams {
     pub new_control_addresses: Vec<Address>,
 }
 
+/// The methods a control address is permitted to call on behalf of the miner.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum ControlAddressRole {
+    /// Any method open to control addresses.
+    #[default]
+    Any = 0,
+    /// Only WindowPoSt submission and fault and recovery declarations.
+    PoSt = 1,
+    /// Only sector pre-commitment.
+    PreCommit = 2,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct ControlAddressWithRole {
+    pub address: Address,
+    pub role: ControlAddressRole,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ChangeWorkerAddress2Params {
+    pub new_worker: Address,
+    pub new_control_addresses: Vec<ControlAddressWithRole>,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
 pub struct ChangeOwnerAddressParams {
@@ -127,6 +163,11 @@ pub struct SubmitWindowedPoStParams {
     pub chain_commit_epoch: ChainEpoch,
     /// The ticket randomness on the chain at the `chain_commit_epoch` on the chain this post is committed to.
     pub chain_commit_rand: Randomness,
+    /// Whether to declare all faulty sectors in the proven partitions as recovered before recording the proof.
+    /// Faulty sectors which are not to be recovered must then be listed as skipped.
+    /// Absent, and so false, in submissions encoded before it was added.
+    #[serde(default)]
+    pub recover_faults: bool,
 }
 
 // Deprecated as of FIP 0084 -- kept for legacy testing
@@ -200,6 +241,18 @@ pub struct PieceActivationManifest {
     pub verified_allocation_key: Option<VerifiedAllocationKey>,
     // Synchronous notifications to be sent to other actors after activation.
     pub notify: Vec<DataActivationNotification>,
+    // Identifies several verified allocations to be claimed, each by a portion of the piece,
+    // for a piece larger than any one allocation. The portions must exactly cover the piece.
+    // Mutually exclusive with verified_allocation_key. Absent from manifests encoded before it was added.
+    #[serde(default)]
+    pub verified_allocation_parts: Vec<VerifiedAllocationPart>,
+}
+
+impl PieceActivationManifest {
+    /// Whether the piece's space is claimed by one or more verified allocations.
+    pub fn is_verified(&self) -> bool {
+        self.verified_allocation_key.is_some() || !self.verified_allocation_parts.is_empty()
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
@@ -208,6 +261,14 @@ pub struct VerifiedAllocationKey {
     pub id: AllocationID,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct VerifiedAllocationPart {
+    pub client: ActorID,
+    pub id: AllocationID,
+    // Size of the portion of the piece claimed, which must equal the allocation's size.
+    pub size: PaddedPieceSize,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
 pub struct DataActivationNotification {
     // Actor to be notified.
@@ -281,6 +342,34 @@ impl From<&ExpirationExtension> for ExpirationExtension2 {
     }
 }
 
+/// A class of sectors, selected by their content.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum SectorClass {
+    /// Committed-capacity sectors, which hold no deals or verified data.
+    CommittedCapacity = 0,
+}
+
+impl SectorClass {
+    /// Whether a sector belongs to this class.
+    pub fn includes(&self, sector: &SectorOnChainInfo) -> bool {
+        match self {
+            SectorClass::CommittedCapacity => {
+                sector.deal_weight.is_zero() && sector.verified_deal_weight.is_zero()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ExtendSectorExpirationsByClassParams {
+    /// The class of sectors to extend.
+    pub class: SectorClass,
+    /// Partitions whose sectors of the class are extended. Each may appear only once.
+    pub partitions: Vec<PartitionKey>,
+    pub new_expiration: ChainEpoch,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct TerminateSectorsParams {
     pub terminations: Vec<TerminationDeclaration>,
@@ -333,6 +422,13 @@ pub struct RecoveryDeclaration {
     pub sectors: BitField,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct DeclarationsReturn {
+    /// The result of each fault or recovery declaration, in order.
+    pub results: BatchReturn,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct CompactPartitionsParams {
     pub deadline: u64,
@@ -365,13 +461,19 @@ pub struct WithdrawBalanceReturn {
     pub amount_withdrawn: TokenAmount,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct WorkerKeyChange {
     /// Must be an ID address
     pub new_worker: Address,
     pub effective_at: ChainEpoch,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetPendingWorkerKeyChangeReturn {
+    pub pending: Option<WorkerKeyChange>,
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
 pub struct PreCommitSectorParams {
     pub seal_proof: RegisteredSealProof,
@@ -590,6 +692,42 @@ pub struct GetBeneficiaryReturn {
     pub proposed: Option<PendingBeneficiaryChange>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetPendingBeneficiaryChangeReturn {
+    pub proposed: Option<PendingBeneficiaryChange>,
+    /// The last epoch at which the proposal may be approved.
+    /// None if there is no proposal, or it was made before proposals expired and has not been migrated.
+    pub proposal_expiration: Option<ChainEpoch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AuthorizePledgeLenderParams {
+    /// The address permitted to fund the pledge facility.
+    pub lender: Address,
+    /// The total amount the miner may draw from the facility.
+    pub cap: TokenAmount,
+    /// The last epoch at which the facility may be funded or drawn from.
+    pub expiration: ChainEpoch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct WithdrawPledgeFacilityParams {
+    pub amount_requested: TokenAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct WithdrawPledgeFacilityReturn {
+    pub amount_withdrawn: TokenAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetPledgeFacilityReturn {
+    pub facility: Option<PledgeFacility>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct GetOwnerReturn {
     pub owner: Address,
@@ -620,11 +758,50 @@ pub struct GetAvailableBalanceReturn {
     pub available_balance: TokenAmount,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetAvailableBalanceBreakdownReturn {
+    /// The epoch at which the breakdown was computed.
+    pub epoch: ChainEpoch,
+    /// The actor's total balance.
+    pub balance: TokenAmount,
+    /// The balance available for withdrawal, net of fee debt.
+    /// Can go negative if the miner is in debt.
+    pub available: TokenAmount,
+    /// Rewards locked in the vesting schedule.
+    pub vesting: TokenAmount,
+    /// Initial pledge locked for committed sectors.
+    pub initial_pledge: TokenAmount,
+    /// Deposits locked for pre-committed sectors.
+    pub pre_commit_deposits: TokenAmount,
+    /// Funds held in the pledge facility.
+    pub pledge_facility: TokenAmount,
+    /// Fees owed by the miner, deducted from the available balance.
+    pub fee_debt: TokenAmount,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct GetVestingFundsReturn {
     pub vesting_funds: Vec<(ChainEpoch, TokenAmount)>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct SimulatePenaltyParams {
+    pub penalty: TokenAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SimulatePenaltyReturn {
+    /// Vested funds that would be unlocked into the available balance.
+    pub vested_unlocked: TokenAmount,
+    /// Unvested funds that would be unlocked early to pay the penalty.
+    pub unvested_unlocked: TokenAmount,
+    /// The amount that would be burnt, including any existing fee debt.
+    pub burnt: TokenAmount,
+    /// The fee debt that would remain unpaid.
+    pub fee_debt: TokenAmount,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct GetPeerIDReturn {
     #[serde(with = "strict_bytes")]
@@ -636,81 +813,311 @@ pub struct GetMultiaddrsReturn {
     pub multi_addrs: Vec<BytesDe>,
 }
 
-// Notification of change committed to one or more sectors.
-// The relevant state must be already committed so the receiver can observe any impacts
-// at the sending miner actor.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct OnClaimRevokedParams {
+    pub sector: SectorNumber,
+    /// The (padded) verified space revoked from the sector.
+    pub space: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct MaxTerminationFeeParams {
+    #[serde(with = "bigint_ser")]
+    pub power: StoragePower,
+    pub initial_pledge: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
-pub struct SectorContentChangedParams {
-    // Distinct sectors with changed content.
-    pub sectors: Vec<SectorChanges>,
+pub struct MaxTerminationFeeReturn {
+    pub max_fee: TokenAmount,
 }
 
-// Description of changes to one sector's content.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-pub struct SectorChanges {
-    // Identifier of sector being updated.
-    pub sector: SectorNumber,
-    // Minimum epoch until which the data is committed to the sector.
-    // Note the sector may later be extended without necessarily another notification.
-    pub minimum_commitment_epoch: ChainEpoch,
-    // Information about some pieces added to (or retained in) the sector.
-    // This may be only a subset of sector content.
-    // Inclusion here does not mean the piece was definitely absent previously.
-    // Exclusion here does not mean a piece has been removed since a prior notification.
-    pub added: Vec<PieceChange>,
-}
-
-// Description of a piece of data committed to a sector.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-pub struct PieceChange {
-    pub data: Cid,
-    pub size: PaddedPieceSize,
-    // A receiver-specific identifier.
-    // E.g. an encoded deal ID which the provider claims this piece satisfies.
-    pub payload: RawBytes,
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct EstimateTerminationFeeParams {
+    /// The live sectors whose termination to price.
+    pub sectors: BitField,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct EstimateTerminationFeeReturn {
+    /// The penalty for terminating the sectors at the current epoch.
+    pub fee: TokenAmount,
 }
 
-// For each piece in each sector, the notifee returns an exit code and
-// (possibly-empty) result data.
-// The miner actor will pass through results to its caller.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
-pub struct SectorContentChangedReturn {
-    // A result for each sector that was notified, in the same order.
-    pub sectors: Vec<SectorReturn>,
+pub struct GetSectorEconomicsParams {
+    pub sector_number: SectorNumber,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetSectorEconomicsReturn {
+    /// The sector's expected reward over one day, projected from the current network
+    /// reward and power estimates.
+    pub expected_day_reward: TokenAmount,
+    /// The fee payable per day for the sector, before any cap relative to block reward.
+    pub daily_fee: TokenAmount,
+    /// The pledge locked for the sector.
+    pub initial_pledge: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
-pub struct SectorReturn {
-    // A result for each piece for the sector that was notified, in the same order.
-    pub added: Vec<PieceReturn>,
+pub struct InitialPledgeReturn {
+    pub initial_pledge: TokenAmount,
+}
+
+/// Timing of a WindowPoSt deadline, as needed to schedule proof generation and submission.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DeadlineTiming {
+    /// Index of the deadline within the proving period.
+    pub index: u64,
+    /// Epoch whose beacon randomness seeds the deadline's WindowPoSt challenge.
+    pub challenge: ChainEpoch,
+    /// First epoch from which a proof may be submitted.
+    pub open: ChainEpoch,
+    /// First epoch from which a proof may no longer be submitted.
+    pub close: ChainEpoch,
+    /// First epoch at which a fault declaration for the deadline is rejected.
+    pub fault_cutoff: ChainEpoch,
+}
+
+impl From<DeadlineInfo> for DeadlineTiming {
+    fn from(di: DeadlineInfo) -> Self {
+        Self {
+            index: di.index,
+            challenge: di.challenge,
+            open: di.open,
+            close: di.close,
+            fault_cutoff: di.fault_cutoff,
+        }
+    }
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetDeadlineTimingReturn {
+    /// The deadline containing the current epoch.
+    pub current: DeadlineTiming,
+    /// The deadline following the current one, which may be in the next proving period.
+    pub next: DeadlineTiming,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
-pub struct PieceReturn {
-    // Indicates whether the receiver accepted the notification.
-    // The caller is free to ignore this, but may chose to abort and roll back.
-    pub accepted: bool,
+pub struct GetSectorsParams {
+    /// Numbers of the sectors to query.
+    /// A contiguous range of sector numbers is encoded compactly as a single run.
+    pub sectors: BitField,
 }
 
+/// The on-chain metadata of a sector, as returned by GetSectors.
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
-pub struct MaxTerminationFeeParams {
+pub struct SectorMetadata {
+    pub sector_number: SectorNumber,
+    /// Epoch during which the sector proof was accepted.
+    pub activation: ChainEpoch,
+    /// Epoch during which the sector expires.
+    pub expiration: ChainEpoch,
+    /// Integral of active deals over sector lifetime.
     #[serde(with = "bigint_ser")]
-    pub power: StoragePower,
+    pub deal_weight: DealWeight,
+    /// Integral of active verified deals over sector lifetime.
+    #[serde(with = "bigint_ser")]
+    pub verified_deal_weight: DealWeight,
+    /// Pledge collected to commit the sector.
     pub initial_pledge: TokenAmount,
 }
 
+impl From<&SectorOnChainInfo> for SectorMetadata {
+    fn from(info: &SectorOnChainInfo) -> Self {
+        Self {
+            sector_number: info.sector_number,
+            activation: info.activation,
+            expiration: info.expiration,
+            deal_weight: info.deal_weight.clone(),
+            verified_deal_weight: info.verified_deal_weight.clone(),
+            initial_pledge: info.initial_pledge.clone(),
+        }
+    }
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
-pub struct MaxTerminationFeeReturn {
-    pub max_fee: TokenAmount,
+pub struct GetSectorsReturn {
+    /// Metadata of the queried sectors that exist, in ascending sector number order.
+    pub sectors: Vec<SectorMetadata>,
+}
+
+/// A summary of the sectors and power in a WindowPoSt deadline, as returned by GetDeadlineSummaries.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct DeadlineSummary {
+    /// Index of the deadline within the proving period.
+    pub index: u64,
+    /// Number of partitions in the deadline.
+    pub partitions: u64,
+    /// Number of partitions proven so far in the deadline's current challenge window.
+    pub partitions_posted: u64,
+    /// Number of non-terminated sectors (including faulty and unproven).
+    pub live_sectors: u64,
+    /// Number of faulty sectors, including those declared recovering.
+    pub faulty_sectors: u64,
+    /// Number of faulty sectors declared recovering.
+    pub recovering_sectors: u64,
+    /// Power of non-terminated sectors (including faulty and unproven).
+    pub live_power: PowerPair,
+    pub faulty_power: PowerPair,
+    pub recovering_power: PowerPair,
+    /// The deadline's current challenge window, or its next one if the current has elapsed.
+    pub next_window: DeadlineTiming,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
-pub struct InitialPledgeReturn {
-    pub initial_pledge: TokenAmount,
+pub struct GetDeadlineSummariesReturn {
+    /// Summaries of all deadlines, in index order.
+    pub deadlines: Vec<DeadlineSummary>,
+}
+
+/// The power of a WindowPoSt deadline by sector status, as returned by GetPowerBreakdown.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct DeadlinePowerBreakdown {
+    /// Index of the deadline within the proving period.
+    pub index: u64,
+    /// Power of sectors which are proven and not faulty.
+    pub active: PowerPair,
+    /// Power of faulty sectors, including those declared recovering.
+    pub faulty: PowerPair,
+    /// Power of faulty sectors declared recovering.
+    pub recovering: PowerPair,
+    /// Power of sectors yet to be proven by a first WindowPoSt.
+    pub unproven: PowerPair,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetPowerBreakdownReturn {
+    /// Power breakdowns of all deadlines, in index order.
+    pub deadlines: Vec<DeadlinePowerBreakdown>,
+}
+
+/// A summary of the sectors and power in a partition, as returned by GetPartitionSummaries.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct PartitionSummary {
+    /// Index of the partition within its deadline.
+    pub index: u64,
+    /// Whether the partition has been proven in the deadline's current challenge window.
+    pub posted: bool,
+    /// Number of non-terminated sectors (including faulty and unproven).
+    pub live_sectors: u64,
+    /// Number of sectors yet to be proven by a first WindowPoSt.
+    pub unproven_sectors: u64,
+    /// Number of faulty sectors, including those declared recovering.
+    pub faulty_sectors: u64,
+    /// Number of faulty sectors declared recovering.
+    pub recovering_sectors: u64,
+    /// Number of terminated sectors not yet removed from the partition.
+    pub terminated_sectors: u64,
+    /// Power of non-terminated sectors (including faulty and unproven).
+    pub live_power: PowerPair,
+    pub unproven_power: PowerPair,
+    pub faulty_power: PowerPair,
+    pub recovering_power: PowerPair,
+}
+
+impl PartitionSummary {
+    pub fn new(index: u64, posted: bool, partition: &Partition) -> Self {
+        Self {
+            index,
+            posted,
+            live_sectors: partition.live_sectors().len(),
+            unproven_sectors: partition.unproven.len(),
+            faulty_sectors: partition.faults.len(),
+            recovering_sectors: partition.recoveries.len(),
+            terminated_sectors: partition.terminated.len(),
+            live_power: partition.live_power.clone(),
+            unproven_power: partition.unproven_power.clone(),
+            faulty_power: partition.faulty_power.clone(),
+            recovering_power: partition.recovering_power.clone(),
+        }
+    }
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetPartitionSummariesParams {
+    /// Index of the deadline whose partitions to summarize.
+    pub deadline: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetPartitionSummariesReturn {
+    /// Summaries of the deadline's partitions, in index order.
+    pub partitions: Vec<PartitionSummary>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct SetFaultTerminationThresholdParams {
+    /// Epochs a sector may remain faulty before termination, or None for the network default.
+    pub threshold: Option<ChainEpoch>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetPartitionPoStStatsParams {
+    pub deadline: u64,
+    pub partition: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetPartitionPoStStatsReturn {
+    pub stats: PoStStats,
+}
+
+/// A deadline's partitions that may be compacted, as returned by GetCompactablePartitions.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CompactableDeadline {
+    /// Index of the deadline.
+    pub deadline: u64,
+    /// Partitions eligible for compaction, no more than CompactPartitions accepts in one call.
+    pub partitions: BitField,
+    /// Number of terminated sectors that compacting the partitions would remove.
+    pub dead_sectors: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetCompactablePartitionsReturn {
+    /// Deadlines with at least one compactable partition, in index order.
+    pub deadlines: Vec<CompactableDeadline>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct SectorMetadataEntry {
+    pub sector_number: SectorNumber,
+    /// The metadata to attach to the sector, replacing any previous value.
+    /// Empty metadata removes it.
+    pub metadata: RawBytes,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct SetSectorMetadataParams {
+    pub entries: Vec<SectorMetadataEntry>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetSectorMetadataParams {
+    pub sector_number: SectorNumber,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetSectorMetadataReturn {
+    pub metadata: RawBytes,
 }
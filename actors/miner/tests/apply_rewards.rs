@@ -1,13 +1,13 @@
 use fil_actor_miner::{
-    Actor, ApplyRewardParams, Method, QuantSpec, REWARD_VESTING_SPEC, locked_reward_from_reward,
+    Actor, ApplyRewardParams, Method, QuantSpec, REWARD_VESTING_SPEC, SimulatePenaltyParams,
+    SimulatePenaltyReturn, locked_reward_from_reward,
 };
-use fil_actor_power::Method as PowerMethod;
+use fil_actors_runtime::ActorError;
 use fil_actors_runtime::BURNT_FUNDS_ACTOR_ADDR;
 use fil_actors_runtime::REWARD_ACTOR_ADDR;
-use fil_actors_runtime::STORAGE_POWER_ACTOR_ADDR;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::runtime::RuntimePolicy;
-use fil_actors_runtime::test_utils::REWARD_ACTOR_CODE_ID;
+use fil_actors_runtime::test_utils::{MockRuntime, REWARD_ACTOR_CODE_ID, expect_abort};
 
 use fvm_shared::METHOD_SEND;
 use fvm_shared::bigint::Zero;
@@ -130,6 +130,9 @@ fn penalty_is_partially_burnt_and_stored_as_fee_debt() {
     // zero here since all reward goes to debt
     // so do not expect pledge update
 
+    // fee debt =  penalty - reward - initial balance = 3*amt - 2*amt = amt
+    expect_fee_debt_change(&rt, &TokenAmount::zero(), &amt);
+
     // burn initial balance + reward = 2*amt
     let expect_burnt = 2 * &amt;
     rt.expect_send_simple(
@@ -190,15 +193,9 @@ fn rewards_pay_back_fee_debt() {
     let pledge_delta = &remaining_locked;
     rt.set_caller(*REWARD_ACTOR_CODE_ID, REWARD_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![REWARD_ACTOR_ADDR]);
-    // expect pledge update
-    rt.expect_send_simple(
-        STORAGE_POWER_ACTOR_ADDR,
-        PowerMethod::UpdatePledgeTotal as u64,
-        IpldBlock::serialize_cbor(pledge_delta).unwrap(),
-        TokenAmount::zero(),
-        None,
-        ExitCode::OK,
-    );
+    // expect fee debt repayment, then pledge update
+    expect_fee_debt_change(&rt, &init_fee_debt, &-&init_fee_debt);
+    expect_update_pledge(&rt, pledge_delta);
 
     let expect_burnt = st.fee_debt;
     rt.expect_send_simple(
@@ -228,3 +225,62 @@ fn rewards_pay_back_fee_debt() {
     assert_eq!(1, acc.len());
     assert!(acc.messages().first().unwrap().contains("DeadlineCronActive == false"));
 }
+
+#[test]
+fn simulate_penalty_leaves_state_unchanged() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let rwd = TokenAmount::from_atto(600_000);
+    rt.add_balance(rwd.clone());
+    h.apply_rewards(&rt, rwd, TokenAmount::zero());
+    let locked = h.get_locked_funds(&rt);
+
+    // A penalty smaller than the locked funds is paid from unvested funds.
+    let penalty = TokenAmount::from_atto(300_000);
+    let ret = simulate_penalty(&rt, &penalty).unwrap();
+    assert!(ret.vested_unlocked.is_zero());
+    assert_eq!(penalty, ret.unvested_unlocked);
+    assert_eq!(penalty, ret.burnt);
+    assert!(ret.fee_debt.is_zero());
+    assert_eq!(locked, h.get_locked_funds(&rt));
+
+    // A penalty exceeding the balance leaves fee debt.
+    let balance = rt.get_balance();
+    let ret = simulate_penalty(&rt, &(&balance + TokenAmount::from_atto(1))).unwrap();
+    assert_eq!(locked, ret.unvested_unlocked);
+    assert_eq!(balance, ret.burnt);
+    assert_eq!(TokenAmount::from_atto(1), ret.fee_debt);
+
+    // Once all funds have vested, they are unlocked regardless of the penalty.
+    let st = h.get_state(&rt);
+    let last_vest = st.vesting_funds.load(&rt.store).unwrap().last().unwrap().epoch;
+    rt.set_epoch(last_vest + 1);
+    let ret = simulate_penalty(&rt, &penalty).unwrap();
+    assert_eq!(locked, ret.vested_unlocked);
+    assert!(ret.unvested_unlocked.is_zero());
+    assert_eq!(penalty, ret.burnt);
+
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        simulate_penalty(&rt, &TokenAmount::from_atto(-1)),
+    );
+    let st = h.get_state(&rt);
+    assert_eq!(locked, st.locked_funds);
+    assert!(st.fee_debt.is_zero());
+}
+
+fn simulate_penalty(
+    rt: &MockRuntime,
+    penalty: &TokenAmount,
+) -> Result<SimulatePenaltyReturn, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<Actor>(
+        Method::SimulatePenaltyExported as u64,
+        IpldBlock::serialize_cbor(&SimulatePenaltyParams { penalty: penalty.clone() }).unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize().unwrap())
+}
@@ -1,4 +1,7 @@
-use fil_actor_miner::{Actor, BeneficiaryTerm, GetBeneficiaryReturn, Method};
+use fil_actor_miner::{
+    Actor, BeneficiaryTerm, GetBeneficiaryReturn, GetPendingBeneficiaryChangeReturn, Method, State,
+};
+use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::test_utils::{
     EVM_ACTOR_CODE_ID, MockRuntime, expect_abort, expect_abort_contains_message,
 };
@@ -443,6 +446,119 @@ fn successfully_get_beneficiary() {
     assert_eq!(beneficiary_return.active.term, info.beneficiary_term);
 }
 
+#[test]
+fn expired_proposal_cannot_be_approved() {
+    let (mut h, rt) = setup();
+    let lifetime = rt.policy.beneficiary_proposal_lifetime;
+    let first_beneficiary_id = Address::new_id(999);
+    let beneficiary_change = BeneficiaryChange::new(
+        first_beneficiary_id,
+        TokenAmount::from_atto(100),
+        ChainEpoch::from(200) + lifetime,
+    );
+    let proposal_epoch = *rt.epoch.borrow();
+    h.change_beneficiary(&rt, h.owner, &beneficiary_change, None).unwrap();
+    let pending = get_pending_beneficiary_change(&rt);
+    assert_eq!(beneficiary_change, BeneficiaryChange::from_pending(&pending.proposed.unwrap()));
+    let expiration = proposal_epoch + lifetime;
+    assert_eq!(Some(expiration), pending.proposal_expiration);
+
+    // The proposal can be approved up to its expiration epoch, but not after.
+    rt.set_epoch(expiration + 1);
+    assert_eq!(None, h.get_beneficiary(&rt).unwrap().proposed);
+    let pending = get_pending_beneficiary_change(&rt);
+    assert_eq!(None, pending.proposed);
+    assert_eq!(None, pending.proposal_expiration);
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "proposal expired",
+        h.change_beneficiary(&rt, first_beneficiary_id, &beneficiary_change, None),
+    );
+    assert_eq!(h.owner, h.get_beneficiary(&rt).unwrap().active.beneficiary);
+
+    // A new proposal from the owner replaces the expired one.
+    h.change_beneficiary(&rt, h.owner, &beneficiary_change, None).unwrap();
+    h.change_beneficiary(
+        &rt,
+        first_beneficiary_id,
+        &beneficiary_change,
+        Some(first_beneficiary_id),
+    )
+    .unwrap();
+    assert_eq!(None, get_pending_beneficiary_change(&rt).proposed);
+    assert_eq!(None, h.get_info(&rt).pending_beneficiary_expiration);
+    h.check_state(&rt);
+}
+
+#[test]
+fn proposal_without_expiration_gets_full_lifetime_when_next_touched() {
+    let (mut h, rt) = setup();
+    let lifetime = rt.policy.beneficiary_proposal_lifetime;
+    let first_beneficiary_id = Address::new_id(999);
+    let beneficiary_change =
+        BeneficiaryChange::new(first_beneficiary_id, TokenAmount::from_atto(100), 200);
+    h.change_beneficiary(&rt, h.owner, &beneficiary_change, None).unwrap();
+
+    // Remove the expiration, as for a proposal made before proposals expired.
+    let mut st: State = rt.get_state();
+    let mut info = st.get_info(rt.store()).unwrap();
+    info.pending_beneficiary_expiration = None;
+    st.save_info(rt.store(), &info).unwrap();
+    rt.replace_state(&st);
+
+    // It does not expire until migrated.
+    let touched_epoch = *rt.epoch.borrow() + 2 * lifetime;
+    rt.set_epoch(touched_epoch);
+    let pending = get_pending_beneficiary_change(&rt);
+    assert!(pending.proposed.is_some());
+    assert_eq!(None, pending.proposal_expiration);
+
+    // Approval by the nominee migrates it and then approves it.
+    h.change_beneficiary(
+        &rt,
+        first_beneficiary_id,
+        &beneficiary_change,
+        Some(first_beneficiary_id),
+    )
+    .unwrap();
+    assert_eq!(first_beneficiary_id, h.get_beneficiary(&rt).unwrap().active.beneficiary);
+    assert_eq!(None, h.get_info(&rt).pending_beneficiary_expiration);
+    h.check_state(&rt);
+}
+
+#[test]
+fn migration_sets_expiration_from_upgrade_epoch() {
+    let (mut h, rt) = setup();
+    let lifetime = rt.policy.beneficiary_proposal_lifetime;
+    let beneficiary_change =
+        BeneficiaryChange::new(Address::new_id(999), TokenAmount::from_atto(100), 200);
+    h.change_beneficiary(&rt, h.owner, &beneficiary_change, None).unwrap();
+
+    let mut info = h.get_info(&rt);
+    info.pending_beneficiary_expiration = None;
+    let upgrade_epoch = 1000;
+    assert!(info.migrate_pending_beneficiary_expiration(&rt.policy, upgrade_epoch));
+    assert_eq!(Some(upgrade_epoch + lifetime), info.pending_beneficiary_expiration);
+    assert!(!info.pending_beneficiary_expired(upgrade_epoch + lifetime));
+    assert!(info.pending_beneficiary_expired(upgrade_epoch + lifetime + 1));
+
+    // A second run leaves the expiration alone.
+    assert!(!info.migrate_pending_beneficiary_expiration(&rt.policy, upgrade_epoch + 1));
+    assert_eq!(Some(upgrade_epoch + lifetime), info.pending_beneficiary_expiration);
+}
+
+fn get_pending_beneficiary_change(rt: &MockRuntime) -> GetPendingBeneficiaryChangeReturn {
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<Actor>(Method::GetPendingBeneficiaryChangeExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
+
 #[test]
 fn get_beneficiary_correctly_restricted() {
     let (h, rt) = setup();
@@ -1,9 +1,14 @@
 use fil_actor_account::Method as AccountMethod;
-use fil_actor_miner::{Actor, ChangeWorkerAddressParams, Method};
+use fil_actor_miner::{
+    Actor, ChangePeerIDParams, ChangeWorkerAddress2Params, ChangeWorkerAddressParams,
+    ControlAddressRole, ControlAddressWithRole, GetControlAddressesReturn,
+    GetPendingWorkerKeyChangeReturn, IsControllingAddressParam, IsControllingAddressReturn, Method,
+    WorkerKeyChange,
+};
 use fil_actors_runtime::{
     runtime::RuntimePolicy,
     test_utils::{
-        ACCOUNT_ACTOR_CODE_ID, MINER_ACTOR_CODE_ID, MockRuntime, expect_abort,
+        ACCOUNT_ACTOR_CODE_ID, MINER_ACTOR_CODE_ID, MockRuntime, TestIdAllocator, expect_abort,
         expect_abort_contains_message, new_bls_addr,
     },
 };
@@ -12,7 +17,10 @@ use fvm_shared::{address::Address, econ::TokenAmount, error::ExitCode};
 mod util;
 
 use fil_actors_runtime::test_utils::EVM_ACTOR_CODE_ID;
+use fvm_ipld_blockstore::{Block, Blockstore};
+use fvm_ipld_encoding::DAG_CBOR;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use multihash_codetable::Code;
 
 use itertools::Itertools;
 use num_traits::Zero;
@@ -77,13 +85,58 @@ fn successfully_change_only_the_worker_address() {
 }
 
 #[test]
-fn change_and_confirm_worker_address_restricted_correctly() {
+fn cancel_pending_worker_address_change() {
     let (h, rt) = setup();
+    let new_worker = Address::new_id(999);
+    assert_eq!(None, get_pending_worker_key_change(&rt));
 
-    let original_control_addresses = h.control_addrs.clone();
+    h.change_worker_address(&rt, new_worker, h.control_addrs.clone()).unwrap();
+    let effective_at = *rt.epoch.borrow() + rt.policy().worker_key_change_delay;
+    assert_eq!(
+        Some(WorkerKeyChange { new_worker, effective_at }),
+        get_pending_worker_key_change(&rt)
+    );
+
+    // Only the owner may cancel.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(Method::CancelChangeWorkerAddressExported as u64, None),
+    );
+    rt.verify();
+
+    cancel_change_worker_address(&h, &rt).unwrap();
+    assert_eq!(None, get_pending_worker_key_change(&rt));
+
+    // Nothing left to cancel, and the worker is unchanged once the delay passes.
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, cancel_change_worker_address(&h, &rt));
+    rt.set_epoch(effective_at);
+    h.confirm_change_worker_address(&rt).unwrap();
+    assert_eq!(h.worker, h.get_info(&rt).worker);
+    h.check_state(&rt);
+}
+
+#[test]
+fn cannot_cancel_effective_worker_address_change() {
+    let (h, rt) = setup();
     let new_worker = Address::new_id(999);
 
-    rt.set_address_actor_type(new_worker, *ACCOUNT_ACTOR_CODE_ID);
+    h.change_worker_address(&rt, new_worker, h.control_addrs.clone()).unwrap();
+    rt.set_epoch(*rt.epoch.borrow() + rt.policy().worker_key_change_delay);
+
+    expect_abort(ExitCode::USR_FORBIDDEN, cancel_change_worker_address(&h, &rt));
+    assert_eq!(new_worker, get_pending_worker_key_change(&rt).unwrap().new_worker);
+    h.check_state(&rt);
+}
+
+#[test]
+fn change_and_confirm_worker_address_restricted_correctly() {
+    let (h, rt) = setup();
+
+    let original_control_addresses = h.control_addrs.clone();
+    let ids = TestIdAllocator::default();
+    let new_worker = ids.new_account(&rt);
 
     let params = IpldBlock::serialize_cbor(&ChangeWorkerAddressParams {
         new_worker,
@@ -186,11 +239,10 @@ fn change_cannot_be_overridden() {
 fn successfully_resolve_and_change_only_control_addresses() {
     let (h, rt) = setup();
 
-    let (control_address_1, control_address_2) = (Address::new_id(555), Address::new_id(556));
+    let ids = TestIdAllocator::default();
+    let (control_address_1, control_address_2) = (ids.new_account(&rt), ids.new_account(&rt));
     let control_address_2_non_id = new_bls_addr(42);
     rt.add_id_address(control_address_2_non_id, control_address_2);
-    rt.set_address_actor_type(control_address_1, *ACCOUNT_ACTOR_CODE_ID);
-    rt.set_address_actor_type(control_address_2, *ACCOUNT_ACTOR_CODE_ID);
 
     h.change_worker_address(&rt, h.worker, vec![control_address_1, control_address_2_non_id])
         .unwrap();
@@ -208,10 +260,9 @@ fn successfully_resolve_and_change_only_control_addresses() {
 fn successfully_change_both_worker_and_control_addresses() {
     let (h, rt) = setup();
 
-    let new_worker = Address::new_id(999);
-    let (control_address_1, control_address_2) = (Address::new_id(5001), Address::new_id(5002));
-    rt.set_address_actor_type(control_address_1, *ACCOUNT_ACTOR_CODE_ID);
-    rt.set_address_actor_type(control_address_2, *ACCOUNT_ACTOR_CODE_ID);
+    let ids = TestIdAllocator::default();
+    let new_worker = ids.new_account(&rt);
+    let (control_address_1, control_address_2) = (ids.new_account(&rt), ids.new_account(&rt));
 
     let current_epoch = 5;
     rt.set_epoch(current_epoch);
@@ -328,8 +379,7 @@ fn fails_if_new_worker_address_does_not_have_a_code() {
 fn fails_if_new_worker_is_not_account_actor() {
     let (h, rt) = setup();
 
-    let new_worker = Address::new_id(999);
-    rt.set_address_actor_type(new_worker, *MINER_ACTOR_CODE_ID);
+    let new_worker = TestIdAllocator::default().new_actor(&rt, *MINER_ACTOR_CODE_ID);
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
 
     let params = ChangeWorkerAddressParams { new_worker, new_control_addresses: Vec::new() };
@@ -347,8 +397,7 @@ fn fails_if_new_worker_is_not_account_actor() {
 fn fails_when_caller_is_not_the_owner() {
     let (h, rt) = setup();
 
-    let new_worker = Address::new_id(999);
-    rt.set_address_actor_type(new_worker, *ACCOUNT_ACTOR_CODE_ID);
+    let new_worker = TestIdAllocator::default().new_account(&rt);
     rt.expect_validate_caller_addr(vec![h.owner]);
     rt.expect_send_simple(
         new_worker,
@@ -370,3 +419,215 @@ fn fails_when_caller_is_not_the_owner() {
 
     h.check_state(&rt);
 }
+
+#[test]
+fn restricted_control_address_may_only_act_in_its_role() {
+    let (h, rt) = setup();
+
+    let hot = h.control_addrs[0];
+    let cold = h.control_addrs[1];
+    change_worker_address2(
+        &h,
+        &rt,
+        vec![
+            ControlAddressWithRole { address: hot, role: ControlAddressRole::PoSt },
+            ControlAddressWithRole { address: cold, role: ControlAddressRole::Any },
+        ],
+    )
+    .unwrap();
+
+    let info = h.get_info(&rt);
+    assert_eq!(vec![hot, cold], info.control_addresses);
+    assert_eq!(vec![ControlAddressRole::PoSt, ControlAddressRole::Any], info.control_address_roles);
+    assert_eq!(
+        vec![&hot, &cold],
+        info.control_addresses_for(ControlAddressRole::PoSt).collect_vec()
+    );
+    assert_eq!(vec![&cold], info.control_addresses_for(ControlAddressRole::Any).collect_vec());
+
+    // The PoSt-only address may not change the peer ID.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, hot);
+    rt.expect_validate_caller_addr(vec![cold, h.worker, h.owner]);
+    let params = ChangePeerIDParams { new_id: vec![1, 2, 3] };
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(Method::ChangePeerID as u64, IpldBlock::serialize_cbor(&params).unwrap()),
+    );
+    rt.verify();
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn restricted_control_address_is_not_controlling() {
+    let (h, rt) = setup();
+
+    let hot = h.control_addrs[0];
+    let cold = h.control_addrs[1];
+    change_worker_address2(
+        &h,
+        &rt,
+        vec![
+            ControlAddressWithRole { address: hot, role: ControlAddressRole::PoSt },
+            ControlAddressWithRole { address: cold, role: ControlAddressRole::Any },
+        ],
+    )
+    .unwrap();
+
+    // Only unrestricted control addresses control the miner, e.g. for the market.
+    assert!(!is_controlling_address(&rt, hot));
+    assert!(is_controlling_address(&rt, cold));
+    assert!(is_controlling_address(&rt, h.worker));
+
+    // The roles are reported with the control addresses.
+    rt.expect_validate_caller_any();
+    let ret: GetControlAddressesReturn = rt
+        .call::<Actor>(Method::ControlAddresses as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(vec![hot, cold], ret.control_addresses);
+    assert_eq!(vec![ControlAddressRole::PoSt, ControlAddressRole::Any], ret.control_address_roles);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn migrate_info_written_before_control_address_roles() {
+    let (h, rt) = setup();
+
+    let mut st = h.get_state(&rt);
+    let info = st.get_info(&rt.store).unwrap();
+
+    // Encode the info as it was before the fault termination threshold, control address roles
+    // and pending beneficiary expiration were appended, all at their defaults here.
+    let mut encoded = fvm_ipld_encoding::to_vec(&info).unwrap();
+    assert_eq!(0x91, encoded[0]);
+    assert_eq!([0xf6, 0x80, 0xf6], encoded[encoded.len() - 3..]);
+    encoded[0] = 0x8e;
+    encoded.truncate(encoded.len() - 3);
+    st.info = rt.store.put(Code::Blake2b256, &Block::new(DAG_CBOR, &encoded)).unwrap();
+
+    assert_eq!(info, st.get_info(&rt.store).unwrap());
+    assert!(st.migrate_info(&rt.store).unwrap());
+    assert_eq!(h.get_state(&rt).info, st.info);
+    assert!(!st.migrate_info(&rt.store).unwrap());
+}
+
+#[test]
+fn change_worker_address_clears_control_address_roles() {
+    let (h, rt) = setup();
+
+    change_worker_address2(
+        &h,
+        &rt,
+        vec![ControlAddressWithRole {
+            address: h.control_addrs[0],
+            role: ControlAddressRole::PreCommit,
+        }],
+    )
+    .unwrap();
+    assert_eq!(vec![ControlAddressRole::PreCommit], h.get_info(&rt).control_address_roles);
+
+    h.change_worker_address(&rt, h.worker, vec![h.control_addrs[0]]).unwrap();
+    let info = h.get_info(&rt);
+    assert!(info.control_address_roles.is_empty());
+    assert_eq!(
+        vec![&h.control_addrs[0]],
+        info.control_addresses_for(ControlAddressRole::PreCommit).collect_vec()
+    );
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn change_worker_address2_fails_if_too_many_control_addresses() {
+    let (h, rt) = setup();
+
+    let control_addresses = (0..=rt.policy().max_control_addresses)
+        .map(|i| ControlAddressWithRole {
+            address: Address::new_id(i as u64),
+            role: ControlAddressRole::PoSt,
+        })
+        .collect_vec();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    let params = ChangeWorkerAddress2Params {
+        new_worker: h.worker,
+        new_control_addresses: control_addresses,
+    };
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<Actor>(
+            Method::ChangeWorkerAddress2Exported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        ),
+    );
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+fn change_worker_address2(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+    new_control_addresses: Vec<ControlAddressWithRole>,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.set_address_actor_type(h.worker, *ACCOUNT_ACTOR_CODE_ID);
+    rt.expect_send_simple(
+        h.worker,
+        AccountMethod::PubkeyAddress as u64,
+        None,
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&h.worker_key).unwrap(),
+        ExitCode::OK,
+    );
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    let params = ChangeWorkerAddress2Params { new_worker: h.worker, new_control_addresses };
+    let ret = rt.call::<Actor>(
+        Method::ChangeWorkerAddress2Exported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+fn is_controlling_address(rt: &MockRuntime, address: Address) -> bool {
+    rt.expect_validate_caller_any();
+    let ret: IsControllingAddressReturn = rt
+        .call::<Actor>(
+            Method::IsControllingAddressExported as u64,
+            IpldBlock::serialize_cbor(&IsControllingAddressParam { address }).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.is_controlling
+}
+
+fn cancel_change_worker_address(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    let ret = rt.call::<Actor>(Method::CancelChangeWorkerAddressExported as u64, None);
+    rt.verify();
+    ret
+}
+
+fn get_pending_worker_key_change(rt: &MockRuntime) -> Option<WorkerKeyChange> {
+    rt.expect_validate_caller_any();
+    let ret: GetPendingWorkerKeyChangeReturn = rt
+        .call::<Actor>(Method::GetPendingWorkerKeyChangeExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.pending
+}
@@ -1,6 +1,6 @@
 use fil_actor_miner::{
-    State, new_deadline_info, pledge_penalty_for_continued_fault, pledge_penalty_for_termination,
-    qa_power_for_sector,
+    Actor, CompactableDeadline, GetCompactablePartitionsReturn, Method, State, new_deadline_info,
+    pledge_penalty_for_continued_fault, pledge_penalty_for_termination, qa_power_for_sector,
 };
 use fil_actors_runtime::{
     runtime::{Runtime, RuntimePolicy},
@@ -102,6 +102,60 @@ fn compacting_a_partition_with_both_live_and_dead_sectors_removes_dead_sectors_r
     h.check_state(&rt);
 }
 
+#[test]
+fn get_compactable_partitions_lists_partitions_with_dead_sectors() {
+    let (mut h, rt) = setup();
+    rt.set_epoch(200);
+
+    let sectors_info = h.commit_and_prove_sectors(
+        &rt,
+        2,
+        DEFAULT_SECTOR_EXPIRATION,
+        vec![vec![10], vec![20]],
+        true,
+    );
+    h.advance_and_submit_posts(&rt, &sectors_info);
+
+    // Nothing to compact without terminated sectors.
+    assert!(get_compactable_partitions(&rt).deadlines.is_empty());
+
+    let terminated_sector = &sectors_info[0];
+    let sector_size = terminated_sector.seal_proof.sector_size().unwrap();
+    let sector_age = *rt.epoch.borrow() - terminated_sector.activation;
+    let fault_fee = pledge_penalty_for_continued_fault(
+        &h.epoch_reward_smooth,
+        &h.epoch_qa_power_smooth,
+        &qa_power_for_sector(sector_size, terminated_sector),
+    );
+    let expected_fee =
+        pledge_penalty_for_termination(&terminated_sector.initial_pledge, sector_age, &fault_fee);
+    h.terminate_sectors(
+        &rt,
+        &bitfield_from_slice(&[terminated_sector.sector_number]),
+        expected_fee,
+    );
+
+    let target_epoch = *rt.epoch.borrow() + rt.policy().wpost_dispute_window;
+    h.advance_to_epoch_with_cron(&rt, target_epoch);
+
+    let ret = get_compactable_partitions(&rt);
+    assert_eq!(
+        vec![CompactableDeadline {
+            deadline: 0,
+            partitions: bitfield_from_slice(&[0]),
+            dead_sectors: 1
+        }],
+        ret.deadlines
+    );
+
+    // The hint can be passed straight to CompactPartitions, after which nothing remains.
+    let hint = &ret.deadlines[0];
+    h.compact_partitions(&rt, hint.deadline, hint.partitions.clone()).unwrap();
+    assert!(get_compactable_partitions(&rt).deadlines.is_empty());
+
+    h.check_state(&rt);
+}
+
 #[test]
 fn fail_to_compact_partitions_with_faults() {
     let (mut h, rt) = setup();
@@ -268,3 +322,15 @@ fn fails_if_partition_count_is_above_limit() {
 
     h.check_state(&rt);
 }
+
+fn get_compactable_partitions(rt: &MockRuntime) -> GetCompactablePartitionsReturn {
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<Actor>(Method::GetCompactablePartitionsExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
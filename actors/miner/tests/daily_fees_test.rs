@@ -5,20 +5,21 @@ use num_traits::Signed;
 
 use fil_actor_market::{ActivatedDeal, NO_ALLOCATION_ID};
 use fil_actor_miner::{
-    Actor, ApplyRewardParams, DeadlineInfo, Method, PoStPartition, SectorOnChainInfo,
-    daily_fee_for_sectors, daily_proof_fee, expected_reward_for_power,
-    pledge_penalty_for_continued_fault, pledge_penalty_for_termination, power_for_sectors,
-    qa_power_for_sector,
+    Actor, ApplyRewardParams, DeadlineInfo, GetSectorEconomicsParams, GetSectorEconomicsReturn,
+    Method, PoStPartition, SectorOnChainInfo, daily_fee_for_sectors, daily_proof_fee,
+    expected_reward_for_power, pledge_penalty_for_continued_fault, pledge_penalty_for_termination,
+    power_for_sectors, qa_power_for_sector,
 };
 use fil_actors_runtime::reward::FilterEstimate;
-use fil_actors_runtime::test_utils::{MockRuntime, REWARD_ACTOR_CODE_ID};
-use fil_actors_runtime::{BURNT_FUNDS_ACTOR_ADDR, EPOCHS_IN_DAY, REWARD_ACTOR_ADDR};
+use fil_actors_runtime::test_utils::{MockRuntime, REWARD_ACTOR_CODE_ID, expect_abort};
+use fil_actors_runtime::{ActorError, BURNT_FUNDS_ACTOR_ADDR, EPOCHS_IN_DAY, REWARD_ACTOR_ADDR};
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::METHOD_SEND;
 use fvm_shared::bigint::{BigInt, Zero};
 use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::sector::SectorNumber;
 use fvm_shared::{clock::ChainEpoch, econ::TokenAmount};
 
 use test_case::test_case;
@@ -91,6 +92,7 @@ fn fee_paid_at_deadline() {
             None,
             ExitCode::OK,
         );
+        expect_fee_debt_change(&rt, &daily_fee, &-&daily_fee);
         let params = ApplyRewardParams { reward: daily_fee.clone(), penalty: TokenAmount::zero() };
         rt.call::<Actor>(Method::ApplyRewards as u64, IpldBlock::serialize_cbor(&params).unwrap())
             .unwrap();
@@ -390,6 +392,52 @@ fn fees_proportional_to_qap() {
     );
 }
 
+#[test]
+fn sector_economics_getter() {
+    let (mut h, rt) = setup();
+    rt.set_circulating_supply(TokenAmount::from_whole(500_000_000));
+    h.epoch_reward_smooth = FilterEstimate::new(BigInt::from(1e16 as u64), BigInt::zero());
+
+    let sector =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION, vec![], true)[0].clone();
+    assert!(sector.daily_fee.is_positive());
+
+    h.expect_query_network_info(&rt);
+    let ret = get_sector_economics(&rt, sector.sector_number).unwrap();
+    let expected_day_reward = expected_reward_for_power(
+        &h.epoch_reward_smooth,
+        &h.epoch_qa_power_smooth,
+        &qa_power_for_sector(h.sector_size, &sector),
+        EPOCHS_IN_DAY,
+    );
+    assert!(expected_day_reward.is_positive());
+    assert_eq!(
+        GetSectorEconomicsReturn {
+            expected_day_reward,
+            daily_fee: sector.daily_fee.clone(),
+            initial_pledge: sector.initial_pledge.clone(),
+        },
+        ret
+    );
+
+    // Unknown sectors have no economics.
+    expect_abort(ExitCode::USR_NOT_FOUND, get_sector_economics(&rt, sector.sector_number + 1));
+    h.check_state(&rt);
+}
+
+fn get_sector_economics(
+    rt: &MockRuntime,
+    sector_number: SectorNumber,
+) -> Result<GetSectorEconomicsReturn, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<Actor>(
+        Method::GetSectorEconomicsExported as u64,
+        IpldBlock::serialize_cbor(&GetSectorEconomicsParams { sector_number }).unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize().unwrap())
+}
+
 fn setup() -> (ActorHarness, MockRuntime) {
     let h = ActorHarness::new(PERIOD_OFFSET);
     let rt = h.new_runtime();
@@ -1,12 +1,14 @@
 use fil_actor_miner::testing::{DeadlineStateSummary, check_deadline_state_invariants};
 use fil_actor_miner::{
-    Deadline, PowerPair, QuantSpec, REWARD_VESTING_SPEC, SectorOnChainInfo, daily_fee_for_sectors,
+    Actor, Deadline, GetPartitionPoStStatsParams, GetPartitionPoStStatsReturn, Method, PoStStats,
+    PowerPair, QuantSpec, REWARD_VESTING_SPEC, SectorOnChainInfo, daily_fee_for_sectors,
     pledge_penalty_for_continued_fault, power_for_sectors,
 };
 use fil_actors_runtime::runtime::RuntimePolicy;
 use fil_actors_runtime::test_utils::MockRuntime;
 use fil_actors_runtime::{EPOCHS_IN_DAY, EPOCHS_IN_HOUR, MessageAccumulator};
 use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::bigint::Zero;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
@@ -210,6 +212,7 @@ fn sector_expires_and_repays_fee_debt() {
             power_delta: Some(power_delta),
             burnt_funds: initial_pledge.clone(),
             pledge_delta: initial_pledge.neg(),
+            fee_debt_delta: initial_pledge.neg(),
             ..CronConfig::default()
         },
     );
@@ -318,6 +321,82 @@ fn detects_and_penalizes_faults() {
     h.check_state(&rt);
 }
 
+#[test]
+fn records_partition_post_stats() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let sectors =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    h.advance_and_submit_posts(&rt, &sectors);
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+
+    let st = h.get_state(&rt);
+    let (dl_idx, p_idx) = st.find_sector(&rt.store, sectors[0].sector_number).unwrap();
+    let stats = get_partition_post_stats(&rt, dl_idx, p_idx);
+    assert_eq!(PoStStats { successes: 1, failures: 0, recent: 0b1 }, stats);
+
+    // Miss the next PoSt, and the sector is detected faulty.
+    let mut dl_info = h.deadline(&rt);
+    while dl_info.index != dl_idx {
+        dl_info = h.advance_deadline(&rt, CronConfig::default());
+    }
+    let mut fee_payable = daily_fee_for_sectors(&sectors);
+    h.advance_deadline(
+        &rt,
+        CronConfig {
+            burnt_funds: fee_payable.clone(),
+            pledge_delta: -fee_payable.clone(),
+            power_delta: Some(power_for_sectors(h.sector_size, &sectors).neg()),
+            ..Default::default()
+        },
+    );
+    let stats = get_partition_post_stats(&rt, dl_idx, p_idx);
+    assert_eq!(PoStStats { successes: 1, failures: 1, recent: 0b10 }, stats);
+
+    // Missing again while the whole partition is faulty and not recovering is not recorded.
+    dl_info = h.advance_deadline(&rt, CronConfig::default());
+    while dl_info.index != dl_idx {
+        dl_info = h.advance_deadline(&rt, CronConfig::default());
+    }
+    fee_payable += pledge_penalty_for_continued_fault(
+        &h.epoch_reward_smooth,
+        &h.epoch_qa_power_smooth,
+        &power_for_sectors(h.sector_size, &sectors).qa,
+    );
+    h.advance_deadline(
+        &rt,
+        CronConfig {
+            burnt_funds: fee_payable.clone(),
+            pledge_delta: -fee_payable,
+            ..Default::default()
+        },
+    );
+    let stats = get_partition_post_stats(&rt, dl_idx, p_idx);
+    assert_eq!(PoStStats { successes: 1, failures: 1, recent: 0b10 }, stats);
+    assert_eq!(2, stats.recent_count());
+    assert_eq!(1, stats.recent_successes());
+    h.check_state(&rt);
+}
+
+fn get_partition_post_stats(rt: &MockRuntime, deadline: u64, partition: u64) -> PoStStats {
+    rt.expect_validate_caller_any();
+    let ret: GetPartitionPoStStatsReturn = rt
+        .call::<Actor>(
+            Method::GetPartitionPoStStatsExported as u64,
+            IpldBlock::serialize_cbor(&GetPartitionPoStStatsParams { deadline, partition })
+                .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.stats
+}
+
 #[test]
 fn test_cron_run_trigger_faults() {
     let mut h = ActorHarness::new(PERIOD_OFFSET);
@@ -1,9 +1,18 @@
 use fil_actor_miner::daily_fee_for_sectors;
+use fil_actor_miner::new_deadline_info;
 use fil_actor_miner::pledge_penalty_for_continued_fault;
 use fil_actor_miner::power_for_sectors;
+use fil_actor_miner::{Actor, FaultDeclaration, Method, SetFaultTerminationThresholdParams};
+use fil_actors_runtime::ActorError;
+use fil_actors_runtime::test_utils::{ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort};
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use std::ops::Neg;
 
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
 
 use num_traits::Zero;
 
@@ -66,3 +75,128 @@ fn declare_fault_pays_fee_at_window_post() {
     );
     h.check_state(&rt);
 }
+
+fn set_fault_termination_threshold(
+    rt: &MockRuntime,
+    caller: Address,
+    owner: Address,
+    threshold: Option<ChainEpoch>,
+) -> Result<Option<IpldBlock>, ActorError> {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_addr(vec![owner]);
+    let ret = rt.call::<Actor>(
+        Method::SetFaultTerminationThresholdExported as u64,
+        IpldBlock::serialize_cbor(&SetFaultTerminationThresholdParams { threshold }).unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+#[test]
+fn fault_termination_threshold_validation() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    h.construct_and_verify(&rt);
+    let period = rt.policy.wpost_proving_period;
+
+    // Only the owner may set the threshold.
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        set_fault_termination_threshold(&rt, h.worker, h.owner, Some(3 * period)),
+    );
+
+    // The threshold must be at least a proving period and at most the network fault max age.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<Actor>(
+            Method::SetFaultTerminationThresholdExported as u64,
+            IpldBlock::serialize_cbor(&SetFaultTerminationThresholdParams {
+                threshold: Some(period - 1),
+            })
+            .unwrap(),
+        ),
+    );
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<Actor>(
+            Method::SetFaultTerminationThresholdExported as u64,
+            IpldBlock::serialize_cbor(&SetFaultTerminationThresholdParams {
+                threshold: Some(rt.policy.fault_max_age + 1),
+            })
+            .unwrap(),
+        ),
+    );
+
+    set_fault_termination_threshold(&rt, h.owner, h.owner, Some(3 * period)).unwrap();
+    assert_eq!(Some(3 * period), h.get_info(&rt).fault_termination_threshold);
+    set_fault_termination_threshold(&rt, h.owner, h.owner, None).unwrap();
+    assert_eq!(None, h.get_info(&rt).fault_termination_threshold);
+    h.check_state(&rt);
+}
+
+#[test]
+fn declared_fault_expires_at_owner_threshold() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    let all_sectors =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    h.advance_and_submit_posts(&rt, &all_sectors);
+
+    let threshold = 3 * rt.policy.wpost_proving_period;
+    set_fault_termination_threshold(&rt, h.owner, h.owner, Some(threshold)).unwrap();
+
+    let st = h.get_state(&rt);
+    let (dl_idx, _) = st.find_sector(&rt.store, all_sectors[0].sector_number).unwrap();
+    let target =
+        new_deadline_info(&rt.policy, st.current_proving_period_start, dl_idx, *rt.epoch.borrow())
+            .next_not_elapsed();
+    h.declare_faults(&rt, &all_sectors);
+
+    // The faulty sector is scheduled to expire early at the owner's threshold rather than
+    // the network's fault max age.
+    let (_, partition) = h.find_sector(&rt, all_sectors[0].sector_number);
+    let expirations = h.collect_partition_expirations(&rt, &partition);
+    let early = &expirations[&(target.last() + threshold)].early_sectors;
+    assert!(early.get(all_sectors[0].sector_number));
+    assert!(!expirations.contains_key(&(target.last() + rt.policy.fault_max_age)));
+    h.check_state(&rt);
+}
+
+#[test]
+fn batched_declarations_fail_per_deadline() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    let all_sectors =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    h.advance_and_submit_posts(&rt, &all_sectors);
+
+    let st = h.get_state(&rt);
+    let (dl_idx, p_idx) = st.find_sector(&rt.store, all_sectors[0].sector_number).unwrap();
+    let current = h.current_deadline(&rt);
+    assert_ne!(dl_idx, current.index);
+
+    // The current deadline is past its fault cutoff, but the sector's deadline is not.
+    let faults = vec![
+        FaultDeclaration {
+            deadline: current.index,
+            partition: 0,
+            sectors: BitField::try_from_bits([all_sectors[0].sector_number + 1]).unwrap(),
+        },
+        FaultDeclaration {
+            deadline: dl_idx,
+            partition: p_idx,
+            sectors: BitField::try_from_bits([all_sectors[0].sector_number]).unwrap(),
+        },
+    ];
+    let ret = h.declare_faults2(&rt, faults, power_for_sectors(h.sector_size, &all_sectors).neg());
+    assert_eq!(vec![ExitCode::USR_ILLEGAL_ARGUMENT, ExitCode::OK], ret.results.codes());
+
+    let (_, partition) = h.find_sector(&rt, all_sectors[0].sector_number);
+    assert!(partition.faults.get(all_sectors[0].sector_number));
+    h.check_state(&rt);
+}
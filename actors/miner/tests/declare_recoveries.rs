@@ -1,3 +1,4 @@
+use fil_actor_miner::RecoveryDeclaration;
 use fil_actor_miner::daily_fee_for_sectors;
 use fil_actor_miner::pledge_penalty_for_continued_fault;
 use fil_actor_miner::power_for_sectors;
@@ -75,7 +76,10 @@ fn recovery_must_pay_back_fee_debt() {
         &h.epoch_qa_power_smooth,
         &ongoing_pwr.qa,
     );
-    h.advance_deadline(&rt, CronConfig { burnt_funds: daily_fee, ..Default::default() });
+    h.advance_deadline(
+        &rt,
+        CronConfig { burnt_funds: daily_fee, fee_debt_delta: ff.clone(), ..Default::default() },
+    );
 
     st = h.get_state(&rt);
     assert_eq!(ff, st.fee_debt);
@@ -154,6 +158,74 @@ fn recovery_fails_during_active_consensus_fault() {
     h.check_state(&rt);
 }
 
+#[test]
+fn batched_recoveries_fail_per_deadline() {
+    let (mut h, rt) = setup();
+    let one_sector = h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+
+    // advance to first proving period and submit so we'll have time to declare the fault next cycle
+    h.advance_and_submit_posts(&rt, &one_sector);
+    h.declare_faults(&rt, &one_sector);
+
+    let st = h.get_state(&rt);
+    let (dl_idx, p_idx) = st.find_sector(&rt.store, one_sector[0].sector_number).unwrap();
+    let current = h.current_deadline(&rt);
+    assert_ne!(dl_idx, current.index);
+
+    // The current deadline is past its fault cutoff, but the sector's deadline is not.
+    let recoveries = vec![
+        RecoveryDeclaration {
+            deadline: current.index,
+            partition: 0,
+            sectors: BitField::try_from_bits([one_sector[0].sector_number + 1]).unwrap(),
+        },
+        RecoveryDeclaration {
+            deadline: dl_idx,
+            partition: p_idx,
+            sectors: BitField::try_from_bits([one_sector[0].sector_number]).unwrap(),
+        },
+    ];
+    let ret = h.declare_recoveries2(&rt, recoveries);
+    assert_eq!(vec![ExitCode::USR_ILLEGAL_ARGUMENT, ExitCode::OK], ret.results.codes());
+
+    let dl = h.get_deadline(&rt, dl_idx);
+    let p = dl.load_partition(&rt.store, p_idx).unwrap();
+    assert_eq!(p.faults, p.recoveries);
+    h.check_state(&rt);
+}
+
+#[test]
+fn batched_recovery_of_sectors_outside_partition_leaves_deadline_unchanged() {
+    let (mut h, rt) = setup();
+    let one_sector = h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+
+    h.advance_and_submit_posts(&rt, &one_sector);
+    h.declare_faults(&rt, &one_sector);
+
+    let st = h.get_state(&rt);
+    let (dl_idx, p_idx) = st.find_sector(&rt.store, one_sector[0].sector_number).unwrap();
+    let dl_before = h.get_deadline(&rt, dl_idx);
+
+    // The second sector is not in the partition, so the whole deadline is rejected.
+    let recoveries = vec![RecoveryDeclaration {
+        deadline: dl_idx,
+        partition: p_idx,
+        sectors: BitField::try_from_bits([
+            one_sector[0].sector_number,
+            one_sector[0].sector_number + 1,
+        ])
+        .unwrap(),
+    }];
+    let ret = h.declare_recoveries2(&rt, recoveries);
+    assert_eq!(vec![ExitCode::USR_ILLEGAL_ARGUMENT], ret.results.codes());
+
+    let dl = h.get_deadline(&rt, dl_idx);
+    assert_eq!(dl_before.partitions, dl.partitions);
+    let p = dl.load_partition(&rt.store, p_idx).unwrap();
+    assert!(p.recoveries.is_empty());
+    h.check_state(&rt);
+}
+
 fn setup() -> (ActorHarness, MockRuntime) {
     let h = ActorHarness::new(PERIOD_OFFSET);
     let rt = h.new_runtime();
@@ -0,0 +1,72 @@
+use fil_actor_miner::ext::power::{
+    GET_EXPERIMENTAL_SEAL_PROOFS_METHOD, GetExperimentalSealProofsReturn,
+};
+use fil_actors_runtime::STORAGE_POWER_ACTOR_ADDR;
+use fil_actors_runtime::runtime::ProofSet;
+use fil_actors_runtime::test_utils::{MockRuntime, expect_abort_contains_message};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::RegisteredSealProof;
+use num_traits::Zero;
+
+mod util;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+// Not accepted by the default policy.
+const EXPERIMENTAL_PROOF: RegisteredSealProof = RegisteredSealProof::StackedDRG2KiBV1P1;
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    h.set_proof_type(EXPERIMENTAL_PROOF);
+    let mut rt = h.new_runtime();
+    rt.policy.valid_pre_commit_proof_type = ProofSet::default_precommit_seal_proofs();
+    rt.policy.allow_experimental_seal_proofs = true;
+    rt.set_balance(BIG_BALANCE.clone());
+    rt.set_epoch(PERIOD_OFFSET + 1);
+    h.construct_and_verify(&rt);
+    (h, rt)
+}
+
+fn expect_query_experimental_seal_proofs(rt: &MockRuntime, proofs: Vec<RegisteredSealProof>) {
+    rt.expect_send_simple(
+        STORAGE_POWER_ACTOR_ADDR,
+        GET_EXPERIMENTAL_SEAL_PROOFS_METHOD,
+        None,
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&GetExperimentalSealProofsReturn { proofs }).unwrap(),
+        ExitCode::OK,
+    );
+}
+
+#[test]
+fn pre_commit_with_registered_experimental_proof() {
+    let (h, rt) = setup();
+    let expiration = h.deadline(&rt).period_end()
+        + DEFAULT_SECTOR_EXPIRATION as i64 * rt.policy.wpost_proving_period;
+    let params = h.make_pre_commit_params(100, PERIOD_OFFSET, expiration, vec![]);
+
+    expect_query_experimental_seal_proofs(&rt, vec![EXPERIMENTAL_PROOF]);
+    let precommit = h.pre_commit_sector_and_get(&rt, params, PreCommitConfig::default(), true);
+    assert_eq!(EXPERIMENTAL_PROOF, precommit.info.seal_proof);
+    h.check_state(&rt);
+}
+
+#[test]
+fn pre_commit_rejects_unregistered_experimental_proof() {
+    let (h, rt) = setup();
+    let expiration = h.deadline(&rt).period_end()
+        + DEFAULT_SECTOR_EXPIRATION as i64 * rt.policy.wpost_proving_period;
+    let params = h.make_pre_commit_params(100, PERIOD_OFFSET, expiration, vec![]);
+
+    expect_query_experimental_seal_proofs(&rt, vec![RegisteredSealProof::StackedDRG8MiBV1P1]);
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "unsupported seal proof type",
+        h.pre_commit_sector(&rt, params, PreCommitConfig::default(), true),
+    );
+    rt.reset();
+    h.check_state(&rt);
+}
@@ -1,13 +1,20 @@
 use fil_actor_miner::{
-    Actor, GetAvailableBalanceReturn, GetOwnerReturn, GetSectorSizeReturn,
-    IsControllingAddressParam, IsControllingAddressReturn, Method,
+    Actor, DeadlinePowerBreakdown, DeadlineTiming, GetAvailableBalanceBreakdownReturn,
+    GetAvailableBalanceReturn, GetDeadlineSummariesReturn, GetDeadlineTimingReturn, GetOwnerReturn,
+    GetPartitionSummariesParams, GetPartitionSummariesReturn, GetPowerBreakdownReturn,
+    GetSectorSizeReturn, GetSectorsParams, GetSectorsReturn, IsControllingAddressParam,
+    IsControllingAddressReturn, Method, PartitionSummary, PowerPair, SectorMetadata,
+    power_for_sectors,
 };
 use fil_actors_runtime::INIT_ACTOR_ADDR;
 use fil_actors_runtime::runtime::policy_constants::MAX_SECTOR_NUMBER;
-use fil_actors_runtime::test_utils::EVM_ACTOR_CODE_ID;
+use fil_actors_runtime::test_utils::{EVM_ACTOR_CODE_ID, MockRuntime, expect_abort};
+use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
+use fvm_shared::error::ExitCode;
 use fvm_shared::{clock::ChainEpoch, econ::TokenAmount};
+use num_traits::Zero;
 use std::ops::Sub;
 
 mod util;
@@ -94,6 +101,222 @@ fn info_getters() {
     h.check_state(&rt);
 }
 
+#[test]
+fn deadline_timing_getter() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+    // Within a proving period, the next deadline immediately follows the current one.
+    let dl_info = h.get_state(&rt).deadline_info(&rt.policy, *rt.epoch.borrow());
+    let ret = get_deadline_timing(&rt);
+    assert_eq!(DeadlineTiming::from(dl_info), ret.current);
+    assert_eq!(dl_info.index + 1, ret.next.index);
+    assert_eq!(dl_info.close, ret.next.open);
+    assert_eq!(ret.next.open + rt.policy.wpost_challenge_window, ret.next.close);
+    assert_eq!(ret.next.open - rt.policy.wpost_challenge_lookback, ret.next.challenge);
+    assert_eq!(ret.next.open - rt.policy.fault_declaration_cutoff, ret.next.fault_cutoff);
+
+    // The deadline after the last in a proving period is the first of the next period.
+    let last = rt.policy.wpost_period_deadlines - 1;
+    rt.set_epoch(dl_info.period_start + last as ChainEpoch * rt.policy.wpost_challenge_window);
+    let ret = get_deadline_timing(&rt);
+    assert_eq!(last, ret.current.index);
+    assert_eq!(0, ret.next.index);
+    assert_eq!(dl_info.next_period_start(), ret.next.open);
+    assert_eq!(ret.current.close, ret.next.open);
+
+    h.check_state(&rt);
+}
+
+fn get_deadline_timing(rt: &MockRuntime) -> GetDeadlineTimingReturn {
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<Actor>(Method::GetDeadlineTimingExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
+
+#[test]
+fn sectors_getter() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    rt.set_epoch(1);
+    h.construct_and_verify(&rt);
+    let sectors =
+        h.commit_and_prove_sectors(&rt, 3, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+    // A range covering the committed sectors and beyond returns only those that exist.
+    let first = sectors[0].sector_number;
+    let ret = get_sectors(&rt, BitField::try_from_bits(first..first + 10).unwrap()).unwrap();
+    let expected: Vec<SectorMetadata> = sectors.iter().map(SectorMetadata::from).collect();
+    assert_eq!(expected, ret.sectors);
+
+    let ret = get_sectors(&rt, BitField::try_from_bits([sectors[1].sector_number]).unwrap());
+    assert_eq!(vec![SectorMetadata::from(&sectors[1])], ret.unwrap().sectors);
+
+    let too_many = rt.policy.addressed_sectors_max;
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        get_sectors(&rt, BitField::try_from_bits(0..too_many + 1).unwrap()),
+    );
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+fn get_sectors(
+    rt: &MockRuntime,
+    sectors: BitField,
+) -> Result<GetSectorsReturn, fil_actors_runtime::ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<Actor>(
+        Method::GetSectorsExported as u64,
+        IpldBlock::serialize_cbor(&GetSectorsParams { sectors }).unwrap(),
+    )?;
+    rt.verify();
+    Ok(ret.unwrap().deserialize().unwrap())
+}
+
+#[test]
+fn deadline_and_partition_summary_getters() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    let sectors =
+        h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    h.advance_and_submit_posts(&rt, &sectors);
+    h.declare_faults(&rt, &sectors[..1]);
+    let (dl_idx, p_idx) =
+        h.get_state(&rt).find_sector(&rt.store, sectors[0].sector_number).unwrap();
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+    let ret = get_deadline_summaries(&rt);
+    assert_eq!(rt.policy.wpost_period_deadlines, ret.deadlines.len() as u64);
+    let curr_epoch = *rt.epoch.borrow();
+    for (i, summary) in ret.deadlines.iter().enumerate() {
+        assert_eq!(i as u64, summary.index);
+        assert_eq!(summary.index, summary.next_window.index);
+        assert!(summary.next_window.close > curr_epoch);
+    }
+    assert_eq!(2, ret.deadlines.iter().map(|d| d.live_sectors).sum::<u64>());
+
+    let deadline = h.get_deadline(&rt, dl_idx);
+    let summary = &ret.deadlines[dl_idx as usize];
+    assert_eq!(1, summary.faulty_sectors);
+    assert_eq!(0, summary.recovering_sectors);
+    assert_eq!(power_for_sectors(h.sector_size, &sectors[..1]), summary.faulty_power);
+    assert_eq!(deadline.live_power, summary.live_power);
+
+    let ret = get_partition_summaries(&rt, dl_idx).unwrap();
+    let (_, partition) = h.get_deadline_and_partition(&rt, dl_idx, p_idx);
+    let posted = deadline.partitions_posted.get(p_idx);
+    assert_eq!(PartitionSummary::new(p_idx, posted, &partition), ret.partitions[p_idx as usize]);
+    assert_eq!(1, ret.partitions[p_idx as usize].faulty_sectors);
+
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        get_partition_summaries(&rt, rt.policy.wpost_period_deadlines),
+    );
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+fn get_deadline_summaries(rt: &MockRuntime) -> GetDeadlineSummariesReturn {
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<Actor>(Method::GetDeadlineSummariesExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
+
+fn get_partition_summaries(
+    rt: &MockRuntime,
+    deadline: u64,
+) -> Result<GetPartitionSummariesReturn, fil_actors_runtime::ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<Actor>(
+        Method::GetPartitionSummariesExported as u64,
+        IpldBlock::serialize_cbor(&GetPartitionSummariesParams { deadline }).unwrap(),
+    )?;
+    rt.verify();
+    Ok(ret.unwrap().deserialize().unwrap())
+}
+
+#[test]
+fn power_breakdown_getter() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    let sectors =
+        h.commit_and_prove_sectors(&rt, 3, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+    // Newly committed sectors are unproven.
+    let total = |ret: &GetPowerBreakdownReturn, f: fn(&DeadlinePowerBreakdown) -> &PowerPair| {
+        ret.deadlines.iter().fold(PowerPair::zero(), |acc, d| &acc + f(d))
+    };
+    let ret = get_power_breakdown(&rt);
+    assert_eq!(rt.policy.wpost_period_deadlines, ret.deadlines.len() as u64);
+    assert_eq!(power_for_sectors(h.sector_size, &sectors), total(&ret, |d| &d.unproven));
+    assert!(total(&ret, |d| &d.active).is_zero());
+
+    // Proven sectors become active, and declared faults and recoveries are reported.
+    h.advance_and_submit_posts(&rt, &sectors);
+    h.declare_faults(&rt, &sectors[..2]);
+    let (dl_idx, p_idx) =
+        h.get_state(&rt).find_sector(&rt.store, sectors[0].sector_number).unwrap();
+    h.declare_recoveries(
+        &rt,
+        dl_idx,
+        p_idx,
+        BitField::try_from_bits([sectors[0].sector_number]).unwrap(),
+        TokenAmount::zero(),
+    )
+    .unwrap();
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+    let ret = get_power_breakdown(&rt);
+    assert!(total(&ret, |d| &d.unproven).is_zero());
+    assert_eq!(power_for_sectors(h.sector_size, &sectors[2..]), total(&ret, |d| &d.active));
+    assert_eq!(power_for_sectors(h.sector_size, &sectors[..2]), total(&ret, |d| &d.faulty));
+    assert_eq!(power_for_sectors(h.sector_size, &sectors[..1]), total(&ret, |d| &d.recovering));
+
+    let (_, partition) = h.get_deadline_and_partition(&rt, dl_idx, p_idx);
+    let breakdown = &ret.deadlines[dl_idx as usize];
+    assert_eq!(dl_idx, breakdown.index);
+    assert_eq!(partition.active_power(), breakdown.active);
+    assert_eq!(partition.faulty_power, breakdown.faulty);
+    h.check_state(&rt);
+}
+
+fn get_power_breakdown(rt: &MockRuntime) -> GetPowerBreakdownReturn {
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<Actor>(Method::GetPowerBreakdownExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
+
 #[test]
 fn collateral_getters() {
     let h = ActorHarness::new(PERIOD_OFFSET);
@@ -142,5 +365,31 @@ fn collateral_getters() {
     // let's be sure we're not vacuously testing this method
     assert_eq!(actor_balance.sub(sector.initial_pledge), available_balance_ret.available_balance);
 
+    // The breakdown accounts for the whole balance.
+    rt.expect_validate_caller_any();
+    let breakdown: GetAvailableBalanceBreakdownReturn = rt
+        .call::<Actor>(Method::GetAvailableBalanceBreakdownExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(prove_commit_epoch, breakdown.epoch);
+    assert_eq!(actor_balance, breakdown.balance);
+    assert_eq!(available_balance_ret.available_balance, breakdown.available);
+    assert_eq!(sector.initial_pledge, breakdown.initial_pledge);
+    assert!(breakdown.pre_commit_deposits.is_zero());
+    assert!(breakdown.fee_debt.is_zero());
+    assert_eq!(
+        actor_balance,
+        &breakdown.available
+            + &breakdown.fee_debt
+            + &breakdown.vesting
+            + &breakdown.initial_pledge
+            + &breakdown.pre_commit_deposits
+            + &breakdown.pledge_facility
+    );
+
     h.check_state(&rt);
 }
@@ -1,17 +1,22 @@
 use fil_actor_market::ActivatedDeal;
 use fil_actor_miner::ext::verifreg::Claim as FILPlusClaim;
 use fil_actor_miner::{
-    ExpirationExtension, ExpirationExtension2, ExtendSectorExpiration2Params,
-    ExtendSectorExpirationParams, PoStPartition, SectorClaim, SectorOnChainInfo, State,
-    daily_proof_fee, power_for_sector, seal_proof_sector_maximum_lifetime,
+    Actor, ExpirationExtension, ExpirationExtension2, ExtendSectorExpiration2Params,
+    ExtendSectorExpirationParams, ExtendSectorExpirationsByClassParams, Method, PartitionKey,
+    PoStPartition, SectorClaim, SectorClass, SectorOnChainInfo, State, daily_proof_fee,
+    power_for_sector, seal_proof_sector_maximum_lifetime,
 };
 use fil_actors_runtime::DealWeight;
 use fil_actors_runtime::{
     EPOCHS_IN_DAY, actor_error,
     runtime::{Runtime, RuntimePolicy},
-    test_utils::{MockRuntime, expect_abort_contains_message, make_piece_cid},
+    test_utils::{
+        ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort, expect_abort_contains_message,
+        make_piece_cid,
+    },
 };
 use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
@@ -1119,6 +1124,73 @@ fn update_expiration2_drop_claims_failure_cases() {
     claim1.sector = old_sector.sector_number;
 }
 
+#[test]
+fn extends_committed_capacity_sectors_by_class() {
+    let (mut h, rt) = setup();
+    h.construct_and_verify(&rt);
+
+    // The first sector holds verified data, the others are committed capacity.
+    let mut pcc = ProveCommitConfig::empty();
+    pcc.add_activated_deals(
+        h.next_sector_no,
+        vec![test_activated_deal(h.sector_size as u64 / 2, 1)],
+    );
+    let sectors = h.commit_and_prove_sectors_with_cfgs(
+        &rt,
+        3,
+        DEFAULT_SECTOR_EXPIRATION as u64,
+        vec![vec![0], vec![], vec![]],
+        true,
+        pcc,
+    );
+    h.advance_and_submit_posts(&rt, &sectors);
+
+    let state: State = rt.get_state();
+    let partitions: Vec<PartitionKey> = sectors
+        .iter()
+        .map(|s| {
+            let (deadline, partition) = state.find_sector(rt.store(), s.sector_number).unwrap();
+            PartitionKey { deadline, partition }
+        })
+        .unique()
+        .collect();
+    let new_expiration = sectors[1].expiration + 42 * rt.policy().wpost_proving_period;
+    let params = ExtendSectorExpirationsByClassParams {
+        class: SectorClass::CommittedCapacity,
+        partitions: partitions.clone(),
+        new_expiration,
+    };
+
+    // Naming a partition twice is rejected.
+    let mut duplicated = params.clone();
+    duplicated.partitions.push(partitions[0]);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, extend_sectors_by_class(&rt, duplicated));
+    rt.reset();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    extend_sectors_by_class(&rt, params).unwrap();
+    rt.verify();
+
+    // Only the committed-capacity sectors are extended.
+    assert_eq!(sectors[0], h.get_sector(&rt, sectors[0].sector_number));
+    for sector in &sectors[1..] {
+        assert_eq!(new_expiration, h.get_sector(&rt, sector.sector_number).expiration);
+    }
+    h.check_state(&rt);
+}
+
+fn extend_sectors_by_class(
+    rt: &MockRuntime,
+    params: ExtendSectorExpirationsByClassParams,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.call::<Actor>(
+        Method::ExtendSectorExpirationsByClassExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+}
+
 fn commit_sector_verified_deals(
     verified_deals: &[ActivatedDeal],
     h: &mut ActorHarness,
@@ -1205,5 +1277,6 @@ fn make_claim(
         term_max: new_expiration - sector.activation,
         term_start: sector.activation,
         sector: sector.sector_number,
+        commitment: vec![],
     }
 }
@@ -1,6 +1,6 @@
 use fil_actor_miner::{
-    DeadlineInfo, State, VestSpec, max_prove_commit_duration, pre_commit_deposit_for_power,
-    qa_power_max,
+    CompactCommD, DeadlineInfo, State, VestSpec, max_prove_commit_duration,
+    pre_commit_deposit_for_power, qa_power_max,
 };
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::policy_constants::MAX_SECTOR_NUMBER;
@@ -165,6 +165,51 @@ mod miner_actor_test_commitment {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn structurally_invalid_commitments_rejected() {
+        let period_offset = ChainEpoch::from(100);
+
+        let h = ActorHarness::new(period_offset);
+        let rt = h.new_runtime();
+        rt.set_balance(BIG_BALANCE.clone());
+        rt.set_received(TokenAmount::zero());
+
+        let precommit_epoch = period_offset + 1;
+        rt.set_epoch(precommit_epoch);
+        h.construct_and_verify(&rt);
+        let deadline = h.deadline(&rt);
+        let challenge_epoch = precommit_epoch - 1;
+
+        for sealed_cid in make_invalid_sealed_cids(b"commr") {
+            let mut precommit_params =
+                h.make_pre_commit_params(101, challenge_epoch, deadline.period_end(), vec![]);
+            precommit_params.sealed_cid = sealed_cid;
+            let ret =
+                h.pre_commit_sector(&rt, precommit_params, util::PreCommitConfig::default(), false);
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "sealed CID had wrong prefix",
+                ret,
+            );
+            rt.reset();
+        }
+
+        for unsealed_cid in make_invalid_piece_cids(b"commd") {
+            let mut precommit_params =
+                h.make_pre_commit_params(101, challenge_epoch, deadline.period_end(), vec![]);
+            precommit_params.unsealed_cid = CompactCommD::of(unsealed_cid);
+            let ret =
+                h.pre_commit_sector(&rt, precommit_params, util::PreCommitConfig::default(), false);
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "unsealed CID had wrong prefix",
+                ret,
+            );
+            rt.reset();
+        }
+        h.check_state(&rt);
+    }
+
     #[test]
     fn invalid_pre_commit_rejected() {
         let period_offset = ChainEpoch::from(100);
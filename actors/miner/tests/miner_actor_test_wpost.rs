@@ -127,6 +127,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -148,6 +149,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -176,6 +178,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -201,6 +204,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -222,6 +226,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -247,6 +252,7 @@ fn invalid_submissions() {
             proofs: Vec::new(),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -272,6 +278,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(RegisteredPoStProof::StackedDRGWindow8MiBV1P1),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -297,6 +304,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(RegisteredPoStProof::StackedDRGWindow64GiBV1P1),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -324,6 +332,7 @@ fn invalid_submissions() {
             proofs,
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -349,6 +358,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(b"123456789012345678901234567890123".to_vec()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -375,6 +385,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge + rt.policy.wpost_proving_period / 2,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -403,6 +414,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge - 1,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -428,6 +440,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: *rt.epoch.borrow(),
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -453,6 +466,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -478,6 +492,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         h.submit_window_post_raw(
             &rt,
@@ -547,6 +562,7 @@ fn duplicate_proof_rejected() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(commit_rand.clone().into()),
+        recover_faults: false,
     };
 
     h.expect_query_network_info(&rt);
@@ -648,6 +664,7 @@ fn duplicate_proof_rejected_with_many_partitions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -768,6 +785,76 @@ fn successful_recoveries_recover_power() {
     h.check_state(&rt);
 }
 
+#[test]
+fn post_recovers_faults_without_declaration() {
+    let period_offset = ChainEpoch::from(100);
+    let precommit_epoch = ChainEpoch::from(1);
+
+    let mut h = ActorHarness::new(period_offset);
+    h.set_proof_type(RegisteredSealProof::StackedDRG2KiBV1P1);
+
+    let rt = h.new_runtime();
+    rt.epoch.replace(precommit_epoch);
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    h.construct_and_verify(&rt);
+
+    let infos = h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+    let pwr = miner::power_for_sectors(h.sector_size, &infos[..1]);
+
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+
+    // Submit first PoSt to ensure we are sufficiently early to add a fault
+    h.advance_and_submit_posts(&rt, &infos);
+
+    // advance deadline and declare both sectors faulty
+    h.advance_deadline(&rt, CronConfig::empty());
+    h.declare_faults(&rt, &infos);
+
+    // advance to epoch when submitPoSt is due, without declaring recovery
+    let (dlidx, pidx) = h.get_state(&rt).find_sector(&rt.store, infos[0].sector_number).unwrap();
+    let mut dlinfo = h.deadline(&rt);
+    while dlinfo.index != dlidx {
+        dlinfo = h.advance_deadline(&rt, CronConfig::empty());
+    }
+
+    // Submit PoSt recovering the first sector and skipping the second.
+    // Power should return for the recovered sector only.
+    let params = miner::SubmitWindowedPoStParams {
+        deadline: dlinfo.index,
+        partitions: vec![miner::PoStPartition {
+            index: pidx,
+            skipped: make_bitfield(&[infos[1].sector_number]),
+        }],
+        proofs: make_post_proofs(h.window_post_proof_type),
+        chain_commit_epoch: dlinfo.challenge,
+        chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        recover_faults: true,
+    };
+    h.submit_window_post_raw(
+        &rt,
+        &dlinfo,
+        infos.clone(),
+        params,
+        PoStConfig::with_expected_power_delta(&pwr),
+    )
+    .unwrap();
+    rt.verify();
+
+    // The recovered sector is no longer faulty, the skipped one remains faulty and not recovering.
+    let (deadline, partition) = h.find_sector(&rt, infos[0].sector_number);
+    assert_eq!(miner::power_for_sectors(h.sector_size, &infos[1..]), deadline.faulty_power);
+    assert_bitfield_equals(&partition.faults, &[infos[1].sector_number]);
+    assert!(partition.recoveries.is_empty());
+
+    // We restored power, so we should not have recorded a post.
+    let deadline = h.get_deadline(&rt, dlidx);
+    assert_bitfield_equals(&deadline.partitions_posted, &[pidx]);
+    let posts = amt_to_vec::<miner::WindowedPoSt>(&rt, &deadline.optimistic_post_submissions);
+    assert!(posts.is_empty());
+    h.check_state(&rt);
+}
+
 #[test]
 fn skipped_faults_adjust_power() {
     let period_offset = ChainEpoch::from(100);
@@ -837,6 +924,7 @@ fn skipped_faults_adjust_power() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        recover_faults: false,
     };
 
     // Now all sectors are faulty so there's nothing to prove.
@@ -905,6 +993,7 @@ fn skipping_all_sectors_in_a_partition_rejected() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        recover_faults: false,
     };
     let result = h.submit_window_post_raw(&rt, &dlinfo, infos.clone(), params, PoStConfig::empty());
     expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, result);
@@ -1016,6 +1105,7 @@ fn skipping_a_fault_from_the_wrong_partition_is_an_error() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        recover_faults: false,
     };
     let result = h.submit_window_post_raw(&rt, &dlinfo, infos, params, PoStConfig::empty());
     expect_abort_contains_message(
@@ -1322,6 +1412,7 @@ fn bad_post_fails_when_verified() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        recover_faults: false,
     };
     let result = h.submit_window_post_raw(&rt, &dlinfo, infos, params, post_config);
     expect_abort_contains_message(
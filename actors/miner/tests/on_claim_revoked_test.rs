@@ -0,0 +1,135 @@
+use fil_actor_miner::{
+    Method, OnClaimRevokedParams, SectorOnChainInfo, State, daily_proof_fee_adjust,
+    power_for_sector, qa_power_for_sector,
+};
+use fil_actor_power::UpdateClaimedPowerParams;
+use fil_actors_runtime::test_utils::{
+    ACCOUNT_ACTOR_CODE_ID, MockRuntime, VERIFREG_ACTOR_CODE_ID, expect_abort,
+};
+use fil_actors_runtime::{DealWeight, STORAGE_POWER_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::MethodNum;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::RegisteredSealProof;
+use num_traits::{Signed, Zero};
+
+mod util;
+use util::*;
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let mut h = ActorHarness::new(100);
+    h.set_proof_type(RegisteredSealProof::StackedDRG512MiBV1);
+    let rt = h.new_runtime();
+    rt.balance.replace(BIG_BALANCE.clone());
+    rt.set_epoch(1);
+    h.construct_and_verify(&rt);
+    (h, rt)
+}
+
+// Commits and proves a sector half filled with verified data.
+fn commit_half_verified_sector(h: &mut ActorHarness, rt: &MockRuntime) -> SectorOnChainInfo {
+    let mut pcc = ProveCommitConfig::empty();
+    pcc.add_activated_deals(
+        h.next_sector_no,
+        vec![test_activated_deal(h.sector_size as u64 / 2, 1)],
+    );
+    let sector = h.commit_and_prove_sectors_with_cfgs(
+        rt,
+        1,
+        DEFAULT_SECTOR_EXPIRATION,
+        vec![vec![0]],
+        true,
+        pcc,
+    )[0]
+    .clone();
+    h.advance_and_submit_posts(rt, &[sector.clone()]);
+    sector
+}
+
+fn on_claim_revoked(
+    rt: &MockRuntime,
+    params: OnClaimRevokedParams,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+    let ret = rt.call::<fil_actor_miner::Actor>(
+        Method::OnClaimRevokedExported as MethodNum,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+#[test]
+fn revocation_removes_verified_power() {
+    let (mut h, rt) = setup();
+    let old_sector = commit_half_verified_sector(&mut h, &rt);
+    let space = h.sector_size as u64 / 2;
+
+    let mut expected = old_sector.clone();
+    expected.verified_deal_weight = DealWeight::zero();
+    expected.daily_fee = daily_proof_fee_adjust(
+        &old_sector.daily_fee,
+        &qa_power_for_sector(h.sector_size, &old_sector),
+        &qa_power_for_sector(h.sector_size, &expected),
+    );
+    let power_delta =
+        power_for_sector(h.sector_size, &expected) - power_for_sector(h.sector_size, &old_sector);
+    assert!(power_delta.qa.is_negative());
+    rt.expect_send_simple(
+        STORAGE_POWER_ACTOR_ADDR,
+        fil_actor_miner::ext::power::UPDATE_CLAIMED_POWER_METHOD,
+        IpldBlock::serialize_cbor(&UpdateClaimedPowerParams {
+            raw_byte_delta: power_delta.raw,
+            quality_adjusted_delta: power_delta.qa,
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    on_claim_revoked(&rt, OnClaimRevokedParams { sector: old_sector.sector_number, space })
+        .unwrap();
+
+    let new_sector = h.get_sector(&rt, old_sector.sector_number);
+    assert_eq!(expected, new_sector);
+    assert!(new_sector.daily_fee < old_sector.daily_fee);
+    let state: State = rt.get_state();
+    let (dlidx, _) = state.find_sector(rt.store(), old_sector.sector_number).unwrap();
+    assert_eq!(new_sector.daily_fee, h.get_deadline(&rt, dlidx).daily_fee);
+    h.check_state(&rt);
+}
+
+#[test]
+fn revocation_of_unknown_sector_is_ignored() {
+    let (mut h, rt) = setup();
+    let sector = commit_half_verified_sector(&mut h, &rt);
+
+    on_claim_revoked(&rt, OnClaimRevokedParams { sector: sector.sector_number + 1, space: 1 })
+        .unwrap();
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+#[test]
+fn requires_verified_registry_caller() {
+    let (mut h, rt) = setup();
+    let sector = commit_half_verified_sector(&mut h, &rt);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<fil_actor_miner::Actor>(
+            Method::OnClaimRevokedExported as MethodNum,
+            IpldBlock::serialize_cbor(&OnClaimRevokedParams {
+                sector: sector.sector_number,
+                space: h.sector_size as u64 / 2,
+            })
+            .unwrap(),
+        ),
+    );
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
@@ -0,0 +1,253 @@
+use fil_actor_miner::{
+    Actor, AuthorizePledgeLenderParams, GetPledgeFacilityReturn, Method, PledgeFacility,
+    WithdrawPledgeFacilityParams, WithdrawPledgeFacilityReturn, max_prove_commit_duration,
+};
+use fil_actors_runtime::reward::FilterEstimate;
+use fil_actors_runtime::test_utils::{ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::METHOD_SEND;
+use fvm_shared::address::Address;
+use fvm_shared::bigint::{BigInt, Zero};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+
+mod util;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+const DEFAULT_SECTOR_EXPIRATION: ChainEpoch = 220;
+const LENDER: Address = Address::new_id(1001);
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.balance.replace(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    (h, rt)
+}
+
+fn authorize(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+    caller: Address,
+    cap: TokenAmount,
+    expiration: ChainEpoch,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    let ret = rt.call::<Actor>(
+        Method::AuthorizePledgeLenderExported as u64,
+        IpldBlock::serialize_cbor(&AuthorizePledgeLenderParams { lender: LENDER, cap, expiration })
+            .unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+fn deposit(rt: &MockRuntime, caller: Address, amount: TokenAmount) {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.add_balance(amount.clone());
+    rt.set_received(amount);
+    rt.expect_validate_caller_addr(vec![LENDER]);
+    rt.call::<Actor>(Method::DepositPledgeFacilityExported as u64, None).unwrap();
+    rt.verify();
+    rt.set_received(TokenAmount::zero());
+}
+
+fn withdraw(rt: &MockRuntime, requested: TokenAmount, expected: TokenAmount) {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, LENDER);
+    rt.expect_validate_caller_addr(vec![LENDER]);
+    if expected.is_positive() {
+        rt.expect_send_simple(LENDER, METHOD_SEND, None, expected.clone(), None, ExitCode::OK);
+    }
+    let ret: WithdrawPledgeFacilityReturn = rt
+        .call::<Actor>(
+            Method::WithdrawPledgeFacilityExported as u64,
+            IpldBlock::serialize_cbor(&WithdrawPledgeFacilityParams {
+                amount_requested: requested,
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(expected, ret.amount_withdrawn);
+}
+
+fn get_facility(rt: &MockRuntime) -> Option<PledgeFacility> {
+    rt.expect_validate_caller_any();
+    let ret: GetPledgeFacilityReturn = rt
+        .call::<Actor>(Method::GetPledgeFacilityExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.facility
+}
+
+#[test]
+fn authorize_deposit_and_withdraw() {
+    let (h, rt) = setup();
+    assert_eq!(None, get_facility(&rt));
+
+    // Only the owner may authorize a lender.
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        authorize(&h, &rt, h.worker, TokenAmount::from_whole(10), 1000),
+    );
+    authorize(&h, &rt, h.owner, TokenAmount::from_whole(10), 1000).unwrap();
+    assert_eq!(
+        Some(PledgeFacility::new(LENDER, TokenAmount::from_whole(10), 1000)),
+        get_facility(&rt)
+    );
+
+    deposit(&rt, LENDER, TokenAmount::from_whole(4));
+    let facility = get_facility(&rt).unwrap();
+    assert_eq!(TokenAmount::from_whole(4), facility.balance);
+    assert_eq!(TokenAmount::from_whole(4), facility.available(*rt.epoch.borrow()));
+
+    // Deposited funds are not available to the miner until drawn.
+    let st = h.get_state(&rt);
+    assert_eq!(BIG_BALANCE.clone(), st.get_unlocked_balance(&rt.balance.borrow()).unwrap());
+
+    // Withdrawal is capped at the deposited balance.
+    withdraw(&rt, TokenAmount::from_whole(5), TokenAmount::from_whole(4));
+    assert!(get_facility(&rt).unwrap().balance.is_zero());
+    h.check_state(&rt);
+}
+
+#[test]
+fn deposit_requires_lender_and_active_facility() {
+    let (h, rt) = setup();
+    let expiration = *rt.epoch.borrow() + 10;
+
+    // No facility yet.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, LENDER);
+    rt.set_received(TokenAmount::from_whole(1));
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(Method::DepositPledgeFacilityExported as u64, None),
+    );
+    rt.reset();
+
+    authorize(&h, &rt, h.owner, TokenAmount::from_whole(10), expiration).unwrap();
+
+    // Only the lender may deposit.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    rt.set_received(TokenAmount::from_whole(1));
+    rt.expect_validate_caller_addr(vec![LENDER]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(Method::DepositPledgeFacilityExported as u64, None),
+    );
+    rt.verify();
+
+    // No deposits after expiration.
+    rt.set_epoch(expiration + 1);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, LENDER);
+    rt.set_received(TokenAmount::from_whole(1));
+    rt.expect_validate_caller_addr(vec![LENDER]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(Method::DepositPledgeFacilityExported as u64, None),
+    );
+    rt.verify();
+    rt.set_received(TokenAmount::zero());
+    h.check_state(&rt);
+}
+
+#[test]
+fn lender_cannot_be_replaced_while_funded() {
+    let (h, rt) = setup();
+    authorize(&h, &rt, h.owner, TokenAmount::from_whole(10), 1000).unwrap();
+    deposit(&rt, LENDER, TokenAmount::from_whole(1));
+
+    let other = Address::new_id(1002);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(
+            Method::AuthorizePledgeLenderExported as u64,
+            IpldBlock::serialize_cbor(&AuthorizePledgeLenderParams {
+                lender: other,
+                cap: TokenAmount::from_whole(10),
+                expiration: 1000,
+            })
+            .unwrap(),
+        ),
+    );
+    rt.verify();
+
+    // Once the lender has withdrawn, the owner may authorize another.
+    withdraw(&rt, TokenAmount::from_whole(1), TokenAmount::from_whole(1));
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    rt.call::<Actor>(
+        Method::AuthorizePledgeLenderExported as u64,
+        IpldBlock::serialize_cbor(&AuthorizePledgeLenderParams {
+            lender: other,
+            cap: TokenAmount::from_whole(10),
+            expiration: 1000,
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+    assert_eq!(other, get_facility(&rt).unwrap().lender);
+    h.check_state(&rt);
+}
+
+#[test]
+fn prove_commit_draws_pledge_shortfall_from_facility() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.balance.replace(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    // Coerce the pledge requirement to exceed the pre-commit deposit.
+    rt.set_circulating_supply(TokenAmount::from_whole(100_000_000));
+    h.epoch_reward_smooth = FilterEstimate::new(BigInt::from(1e15 as u64), BigInt::zero());
+
+    let sectors =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+
+    let expiration = DEFAULT_SECTOR_EXPIRATION * rt.policy.wpost_proving_period + PERIOD_OFFSET - 1;
+    let precommit_epoch = *rt.epoch.borrow() + 1;
+    rt.set_epoch(precommit_epoch);
+    let params =
+        h.make_pre_commit_params(h.next_sector_no, *rt.epoch.borrow() - 1, expiration, vec![]);
+    let precommit = h.pre_commit_sector_and_get(&rt, params, PreCommitConfig::default(), false);
+    assert!(sectors[0].initial_pledge > precommit.pre_commit_deposit);
+
+    // Set balance to exactly cover locked funds, then fund a facility with the new pledge.
+    let st = h.get_state(&rt);
+    rt.balance.replace(&st.pre_commit_deposits + &st.initial_pledge + &st.locked_funds);
+    let cap = st.initial_pledge.clone();
+    authorize(&h, &rt, h.owner, cap.clone(), expiration).unwrap();
+    deposit(&rt, LENDER, cap.clone() * 2);
+
+    rt.set_epoch(
+        precommit_epoch + max_prove_commit_duration(&rt.policy, h.seal_proof_type).unwrap() - 1,
+    );
+    h.deprecated_sector_commit(
+        &rt,
+        &vec![],
+        h.make_prove_commit_params(h.next_sector_no),
+        ProveCommitConfig::empty(),
+    )
+    .unwrap();
+
+    // Only the shortfall is drawn: the new pledge less the unlocked pre-commit deposit.
+    let new_pledge = &h.get_state(&rt).initial_pledge - &st.initial_pledge;
+    let shortfall = new_pledge - &precommit.pre_commit_deposit;
+    assert!(shortfall.is_positive());
+    let facility = get_facility(&rt).unwrap();
+    assert_eq!(shortfall, facility.drawn);
+    assert_eq!(cap * 2 - &shortfall, facility.balance);
+    h.check_state(&rt);
+}
@@ -2,13 +2,14 @@ use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::deal::DealID;
 use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, clock::ChainEpoch};
 
 use fil_actor_miner::ext::verifreg::AllocationID;
 use fil_actor_miner::{
     ERR_NOTIFICATION_RECEIVER_ABORTED, ERR_NOTIFICATION_REJECTED, ProveCommitSectors3Params,
-    SectorActivationManifest,
+    SectorActivationManifest, VerifiedAllocationPart,
 };
 use fil_actors_runtime::EPOCHS_IN_DAY;
 use fil_actors_runtime::test_utils::{MockRuntime, expect_abort_contains_message};
@@ -205,6 +206,37 @@ fn reject_mismatched_commd() {
     );
 }
 
+#[test]
+fn reject_mismatched_allocation_parts() {
+    let (h, rt, mut activations) = setup_precommits(&[(0, 0, 0)]);
+    // Parts covering only half the piece.
+    let half = PaddedPieceSize(activations[0].pieces[0].size.0 / 2);
+    activations[0].pieces[0].verified_allocation_parts =
+        vec![VerifiedAllocationPart { client: CLIENT_ID, id: 1, size: half }];
+
+    let cfg = ProveCommitSectors3Config::default();
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "do not sum to piece size",
+        h.prove_commit_sectors3(&rt, &activations, false, false, false, cfg),
+    );
+}
+
+#[test]
+fn reject_allocation_key_and_parts() {
+    let (h, rt, mut activations) = setup_precommits(&[(CLIENT_ID, 1, 0)]);
+    let size = activations[0].pieces[0].size;
+    activations[0].pieces[0].verified_allocation_parts =
+        vec![VerifiedAllocationPart { client: CLIENT_ID, id: 2, size }];
+
+    let cfg = ProveCommitSectors3Config::default();
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "both an allocation key and allocation parts",
+        h.prove_commit_sectors3(&rt, &activations, false, false, false, cfg),
+    );
+}
+
 #[test]
 fn reject_required_claim_failure() {
     let (h, rt, activations) = setup_precommits(&[(0, 0, 0), (CLIENT_ID, 1, 0)]);
@@ -1,12 +1,13 @@
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, clock::ChainEpoch};
 
 use fil_actor_miner::ext::verifreg::{AllocationClaim, SectorAllocationClaims};
 use fil_actor_miner::{
     DataActivationNotification, PieceChange, ProveCommitSectors3Return, SectorChanges,
-    SectorOnChainInfo, SectorPreCommitInfo,
+    SectorOnChainInfo, SectorPreCommitInfo, VerifiedAllocationPart,
 };
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::test_utils::MockRuntime;
@@ -301,6 +302,65 @@ fn multiple_notifs_for_piece() {
     h.check_state(&rt);
 }
 
+#[test]
+fn piece_claims_multiple_allocations() {
+    let (h, mut rt) = setup_basic();
+    let piece_size = h.sector_size as u64;
+    let precommits = precommit_sectors(&mut rt, &h, &[&[piece_size]]);
+    let sno = precommits[0].sector_number;
+
+    // A single full-size piece backed by allocations for half and two quarters of it.
+    let mut manifests = vec![make_activation_manifest(sno, &[(piece_size, 0, 0, 0)])];
+    let parts = vec![
+        VerifiedAllocationPart {
+            client: CLIENT_ID,
+            id: 1000,
+            size: PaddedPieceSize(piece_size / 2),
+        },
+        VerifiedAllocationPart {
+            client: CLIENT_ID,
+            id: 1001,
+            size: PaddedPieceSize(piece_size / 4),
+        },
+        VerifiedAllocationPart {
+            client: CLIENT_ID + 1,
+            id: 1002,
+            size: PaddedPieceSize(piece_size / 4),
+        },
+    ];
+    manifests[0].pieces[0].verified_allocation_parts = parts.clone();
+
+    let cfg = ProveCommitSectors3Config::default();
+    let (result, claims, notifications) =
+        h.prove_commit_sectors3(&rt, &manifests, true, true, false, cfg).unwrap();
+    assert_commit_result(&[ExitCode::OK], &result);
+    let sector = h.get_sector(&rt, sno);
+
+    // Each allocation is claimed by the same piece, for its portion.
+    let piece_cid = manifests[0].pieces[0].cid;
+    assert_eq!(
+        vec![SectorAllocationClaims {
+            sector: sno,
+            expiry: sector.expiration,
+            claims: parts
+                .iter()
+                .map(|p| AllocationClaim {
+                    client: p.client,
+                    allocation_id: p.id,
+                    data: piece_cid,
+                    size: p.size,
+                })
+                .collect(),
+        }],
+        claims
+    );
+    assert!(notifications.is_empty());
+
+    // The whole piece is verified.
+    verify_weights(&rt, &h, sno, 0, piece_size);
+    h.check_state(&rt);
+}
+
 #[test]
 fn expired_precommit_dropped_batch() {
     let (h, mut rt) = setup_basic();
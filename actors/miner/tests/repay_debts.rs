@@ -80,6 +80,7 @@ fn repay_debt_restricted_correctly() {
     rt.add_balance(fee_debt.clone());
     rt.set_received(fee_debt.clone());
 
+    expect_fee_debt_change(&rt, &fee_debt, &-&fee_debt);
     rt.expect_send_simple(BURNT_FUNDS_ACTOR_ADDR, METHOD_SEND, None, fee_debt, None, ExitCode::OK);
 
     rt.call::<Actor>(Method::RepayDebtExported as u64, None).unwrap();
@@ -0,0 +1,137 @@
+use fil_actor_miner::{
+    Actor, GetSectorMetadataParams, GetSectorMetadataReturn, Method, SectorMetadataEntry,
+    SetSectorMetadataParams,
+};
+use fil_actors_runtime::ActorError;
+use fil_actors_runtime::test_utils::{
+    ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort, expect_abort_contains_message,
+};
+use fvm_ipld_encoding::RawBytes;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::SectorNumber;
+
+mod util;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+const DEFAULT_SECTOR_EXPIRATION: u64 = 220;
+
+fn setup() -> (ActorHarness, MockRuntime, Vec<SectorNumber>) {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.balance.replace(BIG_BALANCE.clone());
+    rt.set_epoch(1);
+    h.construct_and_verify(&rt);
+    let sectors = h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+    (h, rt, sectors.iter().map(|s| s.sector_number).collect())
+}
+
+fn set_sector_metadata(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+    caller: Address,
+    entries: &[(SectorNumber, &[u8])],
+) -> Result<(), ActorError> {
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|(sector_number, metadata)| SectorMetadataEntry {
+            sector_number: *sector_number,
+            metadata: RawBytes::new(metadata.to_vec()),
+        })
+        .collect();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_addr(vec![h.owner]);
+    let ret = rt.call::<Actor>(
+        Method::SetSectorMetadataExported as u64,
+        IpldBlock::serialize_cbor(&SetSectorMetadataParams { entries }).unwrap(),
+    );
+    if ret.is_err() {
+        rt.reset();
+        return ret.map(|_| ());
+    }
+    rt.verify();
+    Ok(())
+}
+
+fn get_sector_metadata(
+    rt: &MockRuntime,
+    sector_number: SectorNumber,
+) -> Result<RawBytes, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<Actor>(
+        Method::GetSectorMetadataExported as u64,
+        IpldBlock::serialize_cbor(&GetSectorMetadataParams { sector_number }).unwrap(),
+    );
+    if ret.is_err() {
+        rt.reset();
+        return ret.map(|_| RawBytes::default());
+    }
+    rt.verify();
+    let ret: GetSectorMetadataReturn = ret.unwrap().unwrap().deserialize().unwrap();
+    Ok(ret.metadata)
+}
+
+#[test]
+fn owner_sets_and_clears_metadata() {
+    let (h, rt, sectors) = setup();
+    assert!(get_sector_metadata(&rt, sectors[0]).unwrap().is_empty());
+
+    set_sector_metadata(&h, &rt, h.owner, &[(sectors[0], b"deal-1234"), (sectors[1], b"x")])
+        .unwrap();
+    assert_eq!(b"deal-1234".to_vec(), get_sector_metadata(&rt, sectors[0]).unwrap().to_vec());
+    assert_eq!(b"x".to_vec(), get_sector_metadata(&rt, sectors[1]).unwrap().to_vec());
+
+    // Metadata is replaced, and removed when empty.
+    set_sector_metadata(&h, &rt, h.owner, &[(sectors[0], b"deal-5678"), (sectors[1], b"")])
+        .unwrap();
+    assert_eq!(b"deal-5678".to_vec(), get_sector_metadata(&rt, sectors[0]).unwrap().to_vec());
+    assert!(get_sector_metadata(&rt, sectors[1]).unwrap().is_empty());
+    h.check_state(&rt);
+
+    set_sector_metadata(&h, &rt, h.owner, &[(sectors[0], b"")]).unwrap();
+    assert!(h.get_state(&rt).sector_metadata.is_none());
+    h.check_state(&rt);
+}
+
+#[test]
+fn rejects_invalid_metadata() {
+    let (h, rt, sectors) = setup();
+
+    // Only the owner may set metadata.
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        set_sector_metadata(&h, &rt, h.worker, &[(sectors[0], b"deal")]),
+    );
+
+    // Metadata is bounded by policy.
+    let oversized = vec![1u8; rt.policy.max_sector_metadata_size + 1];
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "too large",
+        rt.call::<Actor>(
+            Method::SetSectorMetadataExported as u64,
+            IpldBlock::serialize_cbor(&SetSectorMetadataParams {
+                entries: vec![SectorMetadataEntry {
+                    sector_number: sectors[0],
+                    metadata: RawBytes::new(oversized),
+                }],
+            })
+            .unwrap(),
+        ),
+    );
+    rt.reset();
+
+    // Metadata can only be attached to existing sectors.
+    expect_abort(
+        ExitCode::USR_NOT_FOUND,
+        set_sector_metadata(&h, &rt, h.owner, &[(sectors[1] + 100, b"deal")]),
+    );
+    expect_abort(ExitCode::USR_NOT_FOUND, get_sector_metadata(&rt, sectors[1] + 100));
+
+    assert!(h.get_state(&rt).sector_metadata.is_none());
+    h.check_state(&rt);
+}
@@ -1,18 +1,19 @@
 use fil_actor_miner::{
     Actor, CRON_EVENT_PROCESS_EARLY_TERMINATIONS, CronEventPayload, DeferredCronEventParams,
-    ExpirationExtension2, ExtendSectorExpiration2Params, MaxTerminationFeeParams,
-    MaxTerminationFeeReturn, Method, SectorOnChainInfo, State,
-    TERM_FEE_MAX_FAULT_FEE_MULTIPLE_DENOM, TERM_FEE_MAX_FAULT_FEE_MULTIPLE_NUM,
-    TERM_FEE_PLEDGE_MULTIPLE_DENOM, TERM_FEE_PLEDGE_MULTIPLE_NUM, TerminateSectorsParams,
-    TerminationDeclaration, pledge_penalty_for_continued_fault, pledge_penalty_for_termination,
-    power_for_sector, qa_power_for_sector,
+    EstimateTerminationFeeParams, EstimateTerminationFeeReturn, ExpirationExtension2,
+    ExtendSectorExpiration2Params, MaxTerminationFeeParams, MaxTerminationFeeReturn, Method,
+    SectorOnChainInfo, State, TERM_FEE_MAX_FAULT_FEE_MULTIPLE_DENOM,
+    TERM_FEE_MAX_FAULT_FEE_MULTIPLE_NUM, TERM_FEE_PLEDGE_MULTIPLE_DENOM,
+    TERM_FEE_PLEDGE_MULTIPLE_NUM, TerminateSectorsParams, TerminationDeclaration,
+    pledge_penalty_for_continued_fault, pledge_penalty_for_termination, power_for_sector,
+    qa_power_for_sector,
 };
 use fil_actors_runtime::{
     BURNT_FUNDS_ACTOR_ADDR, BatchReturn, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
     SYSTEM_ACTOR_ADDR,
     reward::FilterEstimate,
     runtime::Runtime,
-    test_utils::{ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort_contains_message},
+    test_utils::{ACCOUNT_ACTOR_CODE_ID, MockRuntime, expect_abort, expect_abort_contains_message},
 };
 use fvm_ipld_bitfield::BitField;
 use fvm_shared::{
@@ -27,7 +28,6 @@ use fil_actor_market::{ActivatedDeal, NO_ALLOCATION_ID};
 use fil_actor_miner::ext::market::{
     ON_MINER_SECTORS_TERMINATE_METHOD, OnMinerSectorsTerminateParams,
 };
-use fil_actor_miner::ext::power::UPDATE_PLEDGE_TOTAL_METHOD;
 use fil_actors_runtime::test_utils::POWER_ACTOR_CODE_ID;
 use fvm_ipld_encoding::RawBytes;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -274,14 +274,7 @@ fn owner_cannot_terminate_if_market_fails() {
         ExitCode::OK,
     );
 
-    rt.expect_send_simple(
-        STORAGE_POWER_ACTOR_ADDR,
-        UPDATE_PLEDGE_TOTAL_METHOD,
-        IpldBlock::serialize_cbor(&(-sector.clone().initial_pledge)).unwrap(),
-        TokenAmount::zero(),
-        None,
-        ExitCode::OK,
-    );
+    expect_update_pledge(&rt, &-&sector.initial_pledge);
 
     let sectors_bf = BitField::try_from_bits([sector.sector_number]).unwrap();
     rt.expect_send_simple(
@@ -433,3 +426,44 @@ fn max_termination_fee_returns_correct_results() {
 
     h.check_state(&rt);
 }
+
+#[test]
+fn estimate_termination_fee_matches_termination() {
+    let (mut h, rt) = setup();
+    h.epoch_reward_smooth = FilterEstimate::new(BigInt::from(1e12 as u64), BigInt::zero());
+
+    let sectors = h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, Vec::new(), true);
+    for _ in 0..10 {
+        h.advance_and_submit_posts(&rt, &sectors);
+    }
+
+    let expected_fee = sectors
+        .iter()
+        .fold(TokenAmount::zero(), |acc, s| acc + calc_expected_fee_for_termination(&h, &rt, s));
+    let bf = bitfield_from_slice(&sectors.iter().map(|s| s.sector_number).collect::<Vec<u64>>());
+
+    h.expect_query_network_info(&rt);
+    let fee = estimate_termination_fee(&rt, bf.clone()).unwrap();
+    assert_eq!(expected_fee, fee);
+
+    // Unknown sectors can't be priced.
+    let unknown = bitfield_from_slice(&[sectors[1].sector_number + 1]);
+    expect_abort(ExitCode::USR_NOT_FOUND, estimate_termination_fee(&rt, unknown));
+
+    // Termination at the same epoch charges the estimate.
+    h.terminate_sectors(&rt, &bf, fee);
+    h.check_state(&rt);
+}
+
+fn estimate_termination_fee(
+    rt: &MockRuntime,
+    sectors: BitField,
+) -> Result<TokenAmount, fil_actors_runtime::ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<Actor>(
+        Method::EstimateTerminationFeeExported as MethodNum,
+        IpldBlock::serialize_cbor(&EstimateTerminationFeeParams { sectors }).unwrap(),
+    );
+    rt.verify();
+    Ok(ret?.unwrap().deserialize::<EstimateTerminationFeeReturn>().unwrap().fee)
+}
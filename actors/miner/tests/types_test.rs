@@ -9,13 +9,14 @@ mod serialization {
 
     use fil_actor_miner::{
         Deadline, ExpirationSet, PowerPair, ProveCommitSectorsNIParams, SectorNIActivationInfo,
-        SectorOnChainInfo, SectorOnChainInfoFlags,
+        SectorOnChainInfo, SectorOnChainInfoFlags, SubmitWindowedPoStParams,
     };
     use fvm_ipld_bitfield::BitField;
     use fvm_ipld_bitfield::iter::Ranges;
     use fvm_ipld_encoding::ipld_block::IpldBlock;
     use fvm_shared::bigint::BigInt;
     use fvm_shared::econ::TokenAmount;
+    use fvm_shared::randomness::Randomness;
     use fvm_shared::sector::{RegisteredAggregateProof, RegisteredSealProof};
     use num_traits::Zero;
 
@@ -203,6 +204,32 @@ mod serialization {
         }
     }
 
+    #[test]
+    fn submit_windowed_post_params() {
+        let params = SubmitWindowedPoStParams {
+            deadline: 0,
+            partitions: vec![],
+            proofs: vec![],
+            chain_commit_epoch: 10,
+            chain_commit_rand: Randomness(vec![1]),
+            recover_faults: true,
+        };
+        // [0,[],[],10,[AQ],true]
+        let encoded = IpldBlock::serialize_cbor(&params).unwrap().unwrap();
+        assert_eq!(encoded.data, &hex!("860080800a4101f5")[..]);
+
+        // recover_faults is absent from submissions encoded before it was added
+        // [0,[],[],10,[AQ]]
+        let decoded: SubmitWindowedPoStParams = IpldBlock::deserialize(&IpldBlock {
+            codec: 0x71,
+            data: hex!("850080800a4101").to_vec(),
+        })
+        .unwrap();
+        assert_eq!(10, decoded.chain_commit_epoch);
+        assert_eq!(Randomness(vec![1]), decoded.chain_commit_rand);
+        assert!(!decoded.recover_faults);
+    }
+
     #[test]
     fn expiration_set() {
         // ExpirationSet's fields are all bytes or byte tuples
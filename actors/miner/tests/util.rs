@@ -51,22 +51,22 @@ use fil_actor_miner::{
     CRON_EVENT_PROVING_DEADLINE, ChangeBeneficiaryParams, ChangeMultiaddrsParams,
     ChangePeerIDParams, ChangeWorkerAddressParams, CheckSectorProvenParams, CompactCommD,
     CompactPartitionsParams, CompactSectorNumbersParams, CronEventPayload,
-    DataActivationNotification, Deadline, DeadlineInfo, Deadlines, DeclareFaultsParams,
-    DeclareFaultsRecoveredParams, DeferredCronEventParams, DisputeWindowedPoStParams,
-    ExpirationQueue, ExpirationSet, ExtendSectorExpiration2Params, ExtendSectorExpirationParams,
-    FaultDeclaration, GetAvailableBalanceReturn, GetBeneficiaryReturn, GetControlAddressesReturn,
-    GetMultiaddrsReturn, GetPeerIDReturn, Method, Method as MinerMethod,
-    MinerConstructorParams as ConstructorParams, MinerInfo, NO_QUANTIZATION, Partition,
-    PendingBeneficiaryChange, PieceActivationManifest, PieceChange, PieceReturn, PoStPartition,
-    PowerPair, PreCommitSectorBatchParams, PreCommitSectorBatchParams2, PreCommitSectorParams,
-    ProveCommitAggregateParams, ProveCommitSectorParams, ProveCommitSectors3Params,
-    ProveCommitSectors3Return, QuantSpec, RecoveryDeclaration, ReportConsensusFaultParams,
-    SECTOR_CONTENT_CHANGED, SECTORS_AMT_BITWIDTH, SectorActivationManifest, SectorChanges,
-    SectorContentChangedParams, SectorContentChangedReturn, SectorOnChainInfo, SectorPreCommitInfo,
-    SectorPreCommitOnChainInfo, SectorReturn, SectorUpdateManifest, Sectors, State,
-    SubmitWindowedPoStParams, TerminateSectorsParams, TerminationDeclaration,
-    VerifiedAllocationKey, WindowedPoSt, WithdrawBalanceParams, WithdrawBalanceReturn,
-    consensus_fault_penalty, ext,
+    DataActivationNotification, Deadline, DeadlineInfo, Deadlines, DeclarationsReturn,
+    DeclareFaultsParams, DeclareFaultsRecoveredParams, DeferredCronEventParams,
+    DisputeWindowedPoStParams, ExpirationQueue, ExpirationSet, ExtendSectorExpiration2Params,
+    ExtendSectorExpirationParams, FaultDeclaration, GetAvailableBalanceReturn,
+    GetBeneficiaryReturn, GetControlAddressesReturn, GetMultiaddrsReturn, GetPeerIDReturn, Method,
+    Method as MinerMethod, MinerConstructorParams as ConstructorParams, MinerInfo, NO_QUANTIZATION,
+    Partition, PendingBeneficiaryChange, PieceActivationManifest, PieceChange, PieceReturn,
+    PoStPartition, PowerPair, PreCommitSectorBatchParams, PreCommitSectorBatchParams2,
+    PreCommitSectorParams, ProveCommitAggregateParams, ProveCommitSectorParams,
+    ProveCommitSectors3Params, ProveCommitSectors3Return, QuantSpec, RecoveryDeclaration,
+    ReportConsensusFaultParams, SECTOR_CONTENT_CHANGED, SECTORS_AMT_BITWIDTH,
+    SectorActivationManifest, SectorChanges, SectorContentChangedParams,
+    SectorContentChangedReturn, SectorOnChainInfo, SectorPreCommitInfo, SectorPreCommitOnChainInfo,
+    SectorReturn, SectorUpdateManifest, Sectors, State, SubmitWindowedPoStParams,
+    TerminateSectorsParams, TerminationDeclaration, VerifiedAllocationKey, WindowedPoSt,
+    WithdrawBalanceParams, WithdrawBalanceReturn, consensus_fault_penalty, ext,
     ext::market::ON_MINER_SECTORS_TERMINATE_METHOD,
     ext::power::UPDATE_CLAIMED_POWER_METHOD,
     ext::verifreg::{
@@ -635,7 +635,8 @@ impl ActorHarness {
         let state = self.get_state(rt);
 
         if state.fee_debt.is_positive() {
-            expect_burn(rt, state.fee_debt);
+            expect_fee_debt_change(rt, &state.fee_debt, &-&state.fee_debt);
+            expect_burn(rt, state.fee_debt.clone());
         }
 
         if first_for_miner {
@@ -866,12 +867,6 @@ impl ActorHarness {
             );
         });
 
-        params.sectors.iter().filter(|s| !failed_sectors.contains(&s.sector_number)).for_each(
-            |s| {
-                expect_sector_event(rt, "sector-activated", &s.sealing_number, None, &vec![]);
-            },
-        );
-
         let seal_verify_info = params
             .sectors
             .iter()
@@ -893,13 +888,12 @@ impl ActorHarness {
         let sector_pledge = self.initial_pledge_for_power(rt, &qa_sector_power);
         let total_pledge = BigInt::from(expected_success_count) * sector_pledge;
 
-        rt.expect_send_simple(
-            STORAGE_POWER_ACTOR_ADDR,
-            PowerMethod::UpdatePledgeTotal as u64,
-            IpldBlock::serialize_cbor(&total_pledge).unwrap(),
-            TokenAmount::zero(),
-            None,
-            ExitCode::OK,
+        expect_update_pledge(rt, &total_pledge);
+
+        params.sectors.iter().filter(|s| !failed_sectors.contains(&s.sector_number)).for_each(
+            |s| {
+                expect_sector_event(rt, "sector-activated", &s.sealing_number, None, &vec![]);
+            },
         );
 
         if first_for_miner {
@@ -1320,7 +1314,7 @@ impl ActorHarness {
             let mut deal_size = DealWeight::zero();
             let mut verified_size = DealWeight::zero();
             for piece in &sa.pieces {
-                if piece.verified_allocation_key.is_some() {
+                if piece.is_verified() {
                     verified_size += piece.size.0 * duration as u64;
                 } else {
                     deal_size += piece.size.0 * duration as u64;
@@ -1518,7 +1512,7 @@ impl ActorHarness {
             let mut deal_size = DealWeight::zero();
             let mut verified_size = DealWeight::zero();
             for piece in &sup.pieces {
-                if piece.verified_allocation_key.is_some() {
+                if piece.is_verified() {
                     verified_size += piece.size.0 * duration as u64;
                 } else {
                     deal_size += piece.size.0 * duration as u64;
@@ -1685,6 +1679,7 @@ impl ActorHarness {
         rt.expect_validate_caller_addr(vec![STORAGE_POWER_ACTOR_ADDR]);
         expect_update_power(rt, cfg.power_delta.unwrap_or_else(PowerPair::zero));
         expect_burn(rt, cfg.burnt_funds);
+        expect_fee_debt_change(rt, &self.get_state(rt).fee_debt, &cfg.fee_debt_delta);
         expect_update_pledge(rt, &cfg.pledge_delta);
 
         // Re-enrollment for next period.
@@ -1727,6 +1722,7 @@ impl ActorHarness {
             proofs: make_post_proofs(self.window_post_proof_type),
             chain_commit_epoch: deadline.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            recover_faults: false,
         };
         self.submit_window_post_raw(rt, deadline, infos, params, cfg).unwrap();
         rt.verify();
@@ -1762,9 +1758,15 @@ impl ActorHarness {
         for p in &params.partitions {
             let maybe_partition = dln.load_partition(&rt.store, p.index);
             if let Ok(partition) = maybe_partition {
-                let expected_faults = &partition.faults - &partition.recoveries;
+                // all faults are declared recovered first if requested
+                let recoveries = if params.recover_faults {
+                    partition.faults.clone()
+                } else {
+                    partition.recoveries.clone()
+                };
+                let expected_faults = &partition.faults - &recoveries;
                 all_ignored |= &(&expected_faults | &p.skipped);
-                all_recovered |= &(&partition.recoveries - &p.skipped);
+                all_recovered |= &(&recoveries - &p.skipped);
             }
         }
         let optimistic = all_recovered.is_empty();
@@ -2111,8 +2113,10 @@ impl ActorHarness {
             let burnt_funds = daily_fee.clone().clamp(TokenAmount::zero(), available_to_burn);
             // advance_deadline() will update our pledge_delta with immediately_vesting
             // so we don't need to do it here
+            let fee_debt_delta = &daily_fee - &burnt_funds;
             let pledge_delta = -std::cmp::min(unvested, daily_fee);
-            let cfg = CronConfig { burnt_funds, pledge_delta, ..Default::default() };
+            let cfg =
+                CronConfig { burnt_funds, pledge_delta, fee_debt_delta, ..Default::default() };
             dlinfo = self.advance_deadline(rt, cfg);
         }
     }
@@ -2139,6 +2143,29 @@ impl ActorHarness {
         expected_delta
     }
 
+    pub fn declare_faults2(
+        &self,
+        rt: &MockRuntime,
+        faults: Vec<FaultDeclaration>,
+        expected_delta: PowerPair,
+    ) -> DeclarationsReturn {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+        rt.expect_validate_caller_addr(self.caller_addrs());
+        expect_update_power(rt, expected_delta);
+
+        let ret = rt
+            .call::<Actor>(
+                Method::DeclareFaults2Exported as u64,
+                IpldBlock::serialize_cbor(&DeclareFaultsParams { faults }).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret
+    }
+
     pub fn declare_recoveries(
         &self,
         rt: &MockRuntime,
@@ -2151,6 +2178,7 @@ impl ActorHarness {
         rt.expect_validate_caller_addr(self.caller_addrs());
 
         if expected_debt_repaid.is_positive() {
+            expect_fee_debt_change(rt, &self.get_state(rt).fee_debt, &-&expected_debt_repaid);
             expect_burn(rt, expected_debt_repaid.clone());
         }
 
@@ -2170,6 +2198,27 @@ impl ActorHarness {
         ret
     }
 
+    pub fn declare_recoveries2(
+        &self,
+        rt: &MockRuntime,
+        recoveries: Vec<RecoveryDeclaration>,
+    ) -> DeclarationsReturn {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+        rt.expect_validate_caller_addr(self.caller_addrs());
+
+        let ret = rt
+            .call::<Actor>(
+                Method::DeclareFaultsRecovered2Exported as u64,
+                IpldBlock::serialize_cbor(&DeclareFaultsRecoveredParams { recoveries }).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret
+    }
+
     pub fn continued_fault_penalty(&self, sectors: &[SectorOnChainInfo]) -> TokenAmount {
         let pwr = power_for_sectors(self.sector_size, sectors);
         pledge_penalty_for_continued_fault(
@@ -2459,12 +2508,13 @@ impl ActorHarness {
 
         rt.add_balance(value.clone());
         rt.set_received(value.clone());
+        let total_repaid = expected_repaid_from_vest + expected_repaid_from_balance;
+        expect_fee_debt_change(rt, &self.get_state(rt).fee_debt, &-&total_repaid);
         if expected_repaid_from_vest > &TokenAmount::zero() {
             let pledge_delta = expected_repaid_from_vest.neg();
             expect_update_pledge(rt, &pledge_delta);
         }
 
-        let total_repaid = expected_repaid_from_vest + expected_repaid_from_balance;
         if total_repaid.is_positive() {
             expect_burn(rt, total_repaid.clone());
         }
@@ -2497,6 +2547,7 @@ impl ActorHarness {
         }
 
         if expected_debt_repaid.is_positive() {
+            expect_fee_debt_change(rt, &self.get_state(rt).fee_debt, &expected_debt_repaid.neg());
             expect_burn(rt, expected_debt_repaid.clone());
         }
 
@@ -2894,6 +2945,20 @@ pub fn expect_event(rt: &MockRuntime, typ: &str, sector: &SectorNumber) {
     );
 }
 
+/// Expects a fee debt event if the fee debt changes by a (non-zero) delta from a prior amount.
+pub fn expect_fee_debt_change(rt: &MockRuntime, prior: &TokenAmount, delta: &TokenAmount) {
+    if !delta.is_zero() {
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("fee-debt")
+                .field_bigint("delta", delta.atto())
+                .field_bigint("fee-debt", (prior + delta).atto())
+                .build()
+                .unwrap(),
+        );
+    }
+}
+
 #[allow(dead_code)]
 pub struct PoStConfig {
     pub chain_randomness: Option<[u8; RANDOMNESS_LENGTH]>,
@@ -3034,6 +3099,7 @@ pub struct CronConfig {
     pub power_delta: Option<PowerPair>,
     pub pledge_delta: TokenAmount, // Expected change in miner's pledge
     pub burnt_funds: TokenAmount, // Expected burnt funds, through penalties, fee debt repayments and daily fees
+    pub fee_debt_delta: TokenAmount, // Expected change in fee debt
 }
 
 #[allow(dead_code)]
@@ -3045,6 +3111,7 @@ impl CronConfig {
             power_delta: None,
             pledge_delta: TokenAmount::zero(),
             burnt_funds: TokenAmount::zero(),
+            fee_debt_delta: TokenAmount::zero(),
         }
     }
 }
@@ -3345,6 +3412,7 @@ pub fn make_piece_manifest(
         } else {
             vec![]
         },
+        verified_allocation_parts: vec![],
     }
 }
 
@@ -3384,13 +3452,20 @@ pub fn make_piece_specs_from_configs(
 pub fn claims_from_pieces(pieces: &[PieceActivationManifest]) -> Vec<AllocationClaim> {
     pieces
         .iter()
-        .filter_map(|p| {
-            p.verified_allocation_key.as_ref().map(|a| AllocationClaim {
+        .flat_map(|p| {
+            let key_claim = p.verified_allocation_key.as_ref().map(|a| AllocationClaim {
                 client: a.client,
                 allocation_id: a.id,
                 data: p.cid,
                 size: p.size,
-            })
+            });
+            let part_claims = p.verified_allocation_parts.iter().map(|a| AllocationClaim {
+                client: a.client,
+                allocation_id: a.id,
+                data: p.cid,
+                size: a.size,
+            });
+            key_claim.into_iter().chain(part_claims)
         })
         .collect()
 }
@@ -3609,8 +3684,15 @@ fn expect_validate_precommits(
     Ok((seal_rands, seal_int_rands))
 }
 
-fn expect_update_pledge(rt: &MockRuntime, pledge_delta: &TokenAmount) {
+pub fn expect_update_pledge(rt: &MockRuntime, pledge_delta: &TokenAmount) {
     if !pledge_delta.is_zero() {
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("pledge-delta")
+                .field_bigint("delta", pledge_delta.atto())
+                .build()
+                .unwrap(),
+        );
         rt.expect_send_simple(
             STORAGE_POWER_ACTOR_ADDR,
             PowerMethod::UpdatePledgeTotal as u64,
@@ -0,0 +1,81 @@
+// A namespace for helpers that build and emit multisig events.
+
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::error::ExitCode;
+use fvm_shared::{ActorID, MethodNum};
+
+use crate::{Transaction, TxnID};
+
+/// Indicates a signer has proposed a transaction.
+/// The proposal is also the proposer's approval, indicated by a following approval event.
+pub fn txn_proposed(
+    rt: &impl Runtime,
+    id: TxnID,
+    proposer: ActorID,
+    txn: &Transaction,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("txn-proposed")
+            .field_indexed("id", &id)
+            .field_indexed("proposer", &proposer)
+            .field("to", &txn.to)
+            .field_bigint("value", txn.value.atto())
+            .field("method", &txn.method)
+            .build()?,
+    )
+}
+
+/// Indicates a signer has approved a pending transaction.
+/// The count of approvals includes this one.
+pub fn txn_approved(
+    rt: &impl Runtime,
+    id: TxnID,
+    approver: ActorID,
+    approvals: u64,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("txn-approved")
+            .field_indexed("id", &id)
+            .field_indexed("approver", &approver)
+            .field("approvals", &approvals)
+            .build()?,
+    )
+}
+
+/// Indicates the proposer of a pending transaction has cancelled it.
+pub fn txn_cancelled(rt: &impl Runtime, id: TxnID, proposer: ActorID) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("txn-cancelled")
+            .field_indexed("id", &id)
+            .field_indexed("proposer", &proposer)
+            .build()?,
+    )
+}
+
+/// Indicates an approved transaction has been executed and removed from the pending set.
+/// The executor is the signer whose proposal or approval caused the execution.
+/// The exit code is that of the transaction's message, which may have failed.
+pub fn txn_executed(
+    rt: &impl Runtime,
+    id: TxnID,
+    executor: ActorID,
+    code: ExitCode,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("txn-executed")
+            .field_indexed("id", &id)
+            .field_indexed("executor", &executor)
+            .field("code", &code.value())
+            .build()?,
+    )
+}
+
+/// Indicates an expired pending transaction has been pruned without being executed.
+pub fn txn_pruned(rt: &impl Runtime, id: TxnID) -> Result<(), ActorError> {
+    rt.emit_event(&EventBuilder::new().typ("txn-pruned").field_indexed("id", &id).build()?)
+}
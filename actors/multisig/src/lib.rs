@@ -7,11 +7,11 @@ use fvm_actor_utils::receiver::UniversalReceiverParams;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::RawBytes;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::METHOD_CONSTRUCTOR;
 use fvm_shared::MethodNum;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
 use num_derive::FromPrimitive;
 use num_traits::Zero;
 
@@ -19,8 +19,8 @@ use fil_actors_runtime::FIRST_EXPORTED_METHOD_NUMBER;
 use fil_actors_runtime::cbor::serialize_vec;
 use fil_actors_runtime::runtime::{ActorCode, Primitives, Runtime};
 use fil_actors_runtime::{
-    ActorContext, ActorError, AsActorError, INIT_ACTOR_ADDR, actor_dispatch, actor_error,
-    extract_send_result, resolve_to_actor_id,
+    ActorContext, ActorError, AsActorError, BatchReturnGen, INIT_ACTOR_ADDR, actor_dispatch,
+    actor_error, extract_send_result, resolve_to_actor_id,
 };
 
 pub use self::state::*;
@@ -29,6 +29,7 @@ pub use self::types::*;
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+mod emit;
 mod state;
 pub mod testing;
 mod types;
@@ -48,6 +49,12 @@ pub enum Method {
     LockBalance = 9,
     // Method numbers derived from FRC-0042 standards
     UniversalReceiverHook = frc42_dispatch::method_hash!("Receive"),
+    ProposalHashExported = frc42_dispatch::method_hash!("ProposalHash"),
+    PruneExpiredExported = frc42_dispatch::method_hash!("PruneExpired"),
+    ApproveBatchExported = frc42_dispatch::method_hash!("ApproveBatch"),
+    SetSpendingLimitExported = frc42_dispatch::method_hash!("SetSpendingLimit"),
+    ListPendingTransactionsExported = frc42_dispatch::method_hash!("ListPendingTransactions"),
+    SwapSignerAtomicExported = frc42_dispatch::method_hash!("SwapSignerAtomic"),
 }
 
 /// Multisig Actor
@@ -107,6 +114,7 @@ impl Actor {
             next_tx_id: Default::default(),
             start_epoch: Default::default(),
             unlock_duration: Default::default(),
+            spending_limits: Vec::new(),
         };
 
         if params.unlock_duration != 0 {
@@ -133,8 +141,18 @@ impl Actor {
                 params.value
             ));
         }
+        if let Some(expiration) = params.expiration {
+            if expiration < rt.curr_epoch() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "expiration {} must not be before the current epoch {}",
+                    expiration,
+                    rt.curr_epoch()
+                ));
+            }
+        }
 
-        let (txn_id, txn) = rt.transaction(|st: &mut State, rt| {
+        let (txn_id, txn, within_limit) = rt.transaction(|st: &mut State, rt| {
             if !st.is_signer(&proposer) {
                 return Err(actor_error!(forbidden, "{} is not a signer", proposer));
             }
@@ -154,20 +172,79 @@ impl Actor {
                 method: params.method,
                 params: params.params,
                 approved: Vec::new(),
+                expiration: params.expiration,
             };
 
             ptx.set(&t_id, txn.clone())?;
             st.pending_txs = ptx.flush()?;
-            Ok((t_id, txn))
+
+            // A plain transfer within the proposer's spending allowance needs no other approval.
+            let within_limit = st.num_approvals_threshold > 1
+                && txn.method == METHOD_SEND
+                && txn.value.is_positive()
+                && st.consume_spending_allowance(&proposer, &txn.value, rt.curr_epoch());
+            Ok((t_id, txn, within_limit))
         })?;
 
-        let (applied, ret, code) = Self::approve_transaction(rt, txn_id, txn)?;
+        emit::txn_proposed(rt, txn_id, proposer.id().unwrap(), &txn)?;
+        let (mut applied, mut ret, mut code) = Self::approve_transaction(rt, txn_id, txn.clone())?;
+        if !applied && within_limit {
+            let st: State = rt.state()?;
+            (applied, ret, code) = execute_transaction(rt, &st, txn_id, &txn)?;
+        }
         Ok(ProposeReturn { txn_id, applied, code, ret })
     }
 
     /// Multisig actor approve function
     pub fn approve(rt: &impl Runtime, params: TxnIDParams) -> Result<ApproveReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
+        Self::approve_pending(rt, params)
+    }
+
+    /// Approves a batch of pending transactions, executing each which reaches the threshold.
+    /// Each approval which fails, e.g. for a transaction which does not exist or whose proposal
+    /// hash does not match, is reported in the result rather than aborting the whole batch.
+    /// A failure to access the actor's state aborts the whole batch.
+    pub fn approve_batch(
+        rt: &impl Runtime,
+        params: ApproveBatchParams,
+    ) -> Result<ApproveBatchReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let approver: Address = rt.message().caller();
+        let st: State = rt.state()?;
+        if !st.is_signer(&approver) {
+            return Err(actor_error!(forbidden; "{} is not a signer", approver));
+        }
+        if params.approvals.len() > APPROVE_BATCH_MAX {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many transactions to approve: {} > {}",
+                params.approvals.len(),
+                APPROVE_BATCH_MAX
+            ));
+        }
+
+        let mut results = BatchReturnGen::new(params.approvals.len());
+        let mut approvals = Vec::new();
+        for txn in params.approvals {
+            match Self::approve_pending(rt, txn) {
+                Ok(ret) => {
+                    results.add_success();
+                    approvals.push(ret);
+                }
+                Err(e) if e.exit_code() == ExitCode::USR_ILLEGAL_STATE => return Err(e),
+                Err(e) => {
+                    results.add_fail(e.exit_code());
+                }
+            }
+        }
+        Ok(ApproveBatchReturn { results: results.generate(), approvals })
+    }
+
+    fn approve_pending(
+        rt: &impl Runtime,
+        params: TxnIDParams,
+    ) -> Result<ApproveReturn, ActorError> {
         let approver: Address = rt.message().caller();
 
         let id = params.id;
@@ -183,6 +260,14 @@ impl Actor {
             )?;
 
             let txn = get_transaction(rt, &ptx, params.id, params.proposal_hash)?;
+            if txn.is_expired(rt.curr_epoch()) {
+                return Err(actor_error!(
+                    forbidden,
+                    "transaction {} expired at epoch {}",
+                    params.id,
+                    txn.expiration.unwrap()
+                ));
+            }
 
             // Go implementation holds reference to state after transaction so state must be cloned
             // to match to handle possible exit code inconsistency
@@ -237,7 +322,9 @@ impl Actor {
 
             st.pending_txs = ptx.flush()?;
             Ok(())
-        })
+        })?;
+
+        emit::txn_cancelled(rt, params.id, caller_addr.id().unwrap())
     }
 
     /// Multisig actor function to add signers to multisig
@@ -308,6 +395,7 @@ impl Actor {
             st.purge_approvals(rt.store(), &Address::new_id(resolved_old_signer))
                 .context("failed to purge approvals of removed signer")?;
             st.signers.retain(|s| s != &Address::new_id(resolved_old_signer));
+            st.set_spending_limit(Address::new_id(resolved_old_signer), None);
 
             Ok(())
         })?;
@@ -338,12 +426,95 @@ impl Actor {
             st.signers.push(Address::new_id(to_resolved));
 
             st.purge_approvals(rt.store(), &Address::new_id(from_resolved))?;
+            st.set_spending_limit(Address::new_id(from_resolved), None);
             Ok(())
         })?;
 
         Ok(())
     }
 
+    /// Multisig actor function to replace a signer, either transferring its approvals of
+    /// pending transactions to the new signer or removing them, so that no approval is left
+    /// bound to a key which is no longer a signer.
+    /// The old signer's spending limit is removed in either case.
+    pub fn swap_signer_atomic(
+        rt: &impl Runtime,
+        params: SwapSignerAtomicParams,
+    ) -> Result<(), ActorError> {
+        let receiver = rt.message().receiver();
+        rt.validate_immediate_caller_is(std::iter::once(&receiver))?;
+        let from = Address::new_id(resolve_to_actor_id(rt, &params.from, false)?);
+        let to = Address::new_id(resolve_to_actor_id(rt, &params.to, true)?);
+
+        rt.transaction(|st: &mut State, rt| {
+            if !st.is_signer(&from) {
+                return Err(actor_error!(forbidden; "{} is not a signer", from));
+            }
+            // Swapping to an existing signer would reduce the number of distinct signers,
+            // and with it the approvals available to meet the threshold.
+            if st.is_signer(&to) {
+                return Err(actor_error!(illegal_argument; "{} is already a signer", to));
+            }
+            if (st.signers.len() as u64) < st.num_approvals_threshold {
+                return Err(actor_error!(
+                    illegal_state,
+                    "signers {} below threshold {}",
+                    st.signers.len(),
+                    st.num_approvals_threshold
+                ));
+            }
+
+            // Replace the signer in place, preserving the order of signers.
+            st.signers.iter_mut().filter(|s| **s == from).for_each(|s| *s = to);
+
+            if params.rebind_approvals {
+                st.rebind_approvals(rt.store(), &from, &to)
+                    .context("failed to rebind approvals of swapped signer")?;
+            } else {
+                st.purge_approvals(rt.store(), &from)
+                    .context("failed to purge approvals of swapped signer")?;
+            }
+            st.set_spending_limit(from, None);
+            Ok(())
+        })
+    }
+
+    /// Sets or removes a signer's limit on the value it may send without the approval of
+    /// other signers.
+    pub fn set_spending_limit(
+        rt: &impl Runtime,
+        params: SetSpendingLimitParams,
+    ) -> Result<(), ActorError> {
+        let receiver = rt.message().receiver();
+        rt.validate_immediate_caller_is(std::iter::once(&receiver))?;
+        let signer = Address::new_id(resolve_to_actor_id(rt, &params.signer, false)?);
+
+        if let Some(limit) = &params.limit {
+            if limit.window <= 0 {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "spending limit window must be positive, was {}",
+                    limit.window
+                ));
+            }
+            if !limit.amount.is_positive() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "spending limit amount must be positive, was {}",
+                    limit.amount
+                ));
+            }
+        }
+
+        rt.transaction(|st: &mut State, _| {
+            if !st.is_signer(&signer) {
+                return Err(actor_error!(forbidden, "{} is not a signer", signer));
+            }
+            st.set_spending_limit(signer, params.limit);
+            Ok(())
+        })
+    }
+
     /// Multisig actor function to change number of approvals needed
     pub fn change_num_approvals_threshold(
         rt: &impl Runtime,
@@ -424,6 +595,12 @@ impl Actor {
             Ok(st.clone())
         })?;
 
+        emit::txn_approved(
+            rt,
+            tx_id,
+            rt.message().caller().id().unwrap(),
+            txn.approved.len() as u64,
+        )?;
         execute_transaction_if_approved(rt, &st, tx_id, &txn)
     }
 
@@ -436,6 +613,78 @@ impl Actor {
         Ok(())
     }
 
+    /// Computes the hash of a proposal with the given fields, as expected when approving
+    /// or cancelling it, so that offline signers can check the hash they approve.
+    pub fn proposal_hash(
+        rt: &impl Runtime,
+        params: ProposalHashParams,
+    ) -> Result<ProposalHashReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let requester = rt.resolve_address(&params.requester).ok_or_else(|| {
+            actor_error!(not_found, "failed to resolve requester address {}", params.requester)
+        })?;
+
+        let txn = Transaction {
+            to: params.to,
+            value: params.value,
+            method: params.method,
+            params: params.params,
+            approved: vec![Address::new_id(requester)],
+            expiration: params.expiration,
+        };
+        let proposal_hash = compute_proposal_hash(&txn, rt)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to compute proposal hash")?;
+        Ok(ProposalHashReturn { proposal_hash: proposal_hash.to_vec() })
+    }
+
+    /// Removes expired pending transactions, which can no longer be approved.
+    /// Anyone may prune them.
+    pub fn prune_expired(rt: &impl Runtime) -> Result<PruneExpiredReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let pruned = rt.transaction(|st: &mut State, rt| {
+            st.prune_expired(rt.store(), rt.curr_epoch()).context("failed to prune transactions")
+        })?;
+        for id in &pruned {
+            emit::txn_pruned(rt, *id)?;
+        }
+        Ok(PruneExpiredReturn { pruned })
+    }
+
+    /// Lists a page of pending transactions, summarising each with a digest of its parameters.
+    /// Transactions are listed in ID order. A caller pages through all the transactions
+    /// by passing the returned cursor to the next call, until no cursor is returned.
+    pub fn list_pending_transactions(
+        rt: &impl Runtime,
+        params: ListPendingTransactionsParams,
+    ) -> Result<ListPendingTransactionsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let st: State = rt.state()?;
+        let (txns, next_cursor) =
+            st.list_pending_transactions(rt.store(), params.cursor, params.limit)?;
+        let transactions = txns
+            .into_iter()
+            .map(|(id, txn)| {
+                let proposer = *txn.approved.first().ok_or_else(|| {
+                    actor_error!(illegal_state, "pending transaction {} has no approvals", id.0)
+                })?;
+                Ok(PendingTransactionSummary {
+                    id,
+                    proposer,
+                    to: txn.to,
+                    value: txn.value,
+                    method: txn.method,
+                    params_digest: rt.hash_blake2b(txn.params.bytes()).to_vec(),
+                    approvals: txn.approved.len() as u64,
+                    expiration: txn.expiration,
+                })
+            })
+            .collect::<Result<_, ActorError>>()?;
+        Ok(ListPendingTransactionsReturn { transactions, next_cursor })
+    }
+
     pub fn fallback(
         rt: &impl Runtime,
         method: MethodNum,
@@ -456,47 +705,56 @@ fn execute_transaction_if_approved(
     txn_id: TxnID,
     txn: &Transaction,
 ) -> Result<(bool, RawBytes, ExitCode), ActorError> {
-    let mut out = RawBytes::default();
-    let mut code = ExitCode::OK;
-    let mut applied = false;
     let threshold_met = txn.approved.len() as u64 >= st.num_approvals_threshold;
     if threshold_met {
-        st.check_available(rt.current_balance(), &txn.value, rt.curr_epoch())?;
-
-        match extract_send_result(rt.send_simple(
-            &txn.to,
-            txn.method,
-            txn.params.clone().into(),
-            txn.value.clone(),
-        )) {
-            Ok(Some(r)) => {
+        execute_transaction(rt, st, txn_id, txn)
+    } else {
+        Ok((false, RawBytes::default(), ExitCode::OK))
+    }
+}
+
+/// Executes a pending transaction and removes it from the pending set.
+fn execute_transaction(
+    rt: &impl Runtime,
+    st: &State,
+    txn_id: TxnID,
+    txn: &Transaction,
+) -> Result<(bool, RawBytes, ExitCode), ActorError> {
+    let mut out = RawBytes::default();
+    let mut code = ExitCode::OK;
+    st.check_available(rt.current_balance(), &txn.value, rt.curr_epoch())?;
+
+    match extract_send_result(rt.send_simple(
+        &txn.to,
+        txn.method,
+        txn.params.clone().into(),
+        txn.value.clone(),
+    )) {
+        Ok(Some(r)) => {
+            out = RawBytes::new(r.data);
+        }
+        Err(mut e) => {
+            if let Some(r) = e.take_data() {
                 out = RawBytes::new(r.data);
             }
-            Err(mut e) => {
-                if let Some(r) = e.take_data() {
-                    out = RawBytes::new(r.data);
-                }
 
-                code = e.exit_code();
-            }
-            _ => {}
+            code = e.exit_code();
         }
-        applied = true;
-
-        rt.transaction(|st: &mut State, rt| {
-            let mut ptx = PendingTxnMap::load(
-                rt.store(),
-                &st.pending_txs,
-                PENDING_TXN_CONFIG,
-                "pending txns",
-            )?;
-            ptx.delete(&txn_id)?;
-            st.pending_txs = ptx.flush()?;
-            Ok(())
-        })?;
+        _ => {}
     }
 
-    Ok((applied, out, code))
+    rt.transaction(|st: &mut State, rt| {
+        let mut ptx =
+            PendingTxnMap::load(rt.store(), &st.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+        ptx.delete(&txn_id)?;
+        st.pending_txs = ptx.flush()?;
+        Ok(())
+    })?;
+
+    // Execution is always triggered by a signer's proposal or approval.
+    emit::txn_executed(rt, txn_id, rt.message().caller().id().unwrap(), code)?;
+
+    Ok((true, out, code))
 }
 
 fn get_transaction<'m, BS, RT>(
@@ -532,15 +790,31 @@ where
 
 /// Computes a digest of a proposed transaction. This digest is used to confirm identity
 /// of the transaction associated with an ID, which might change under chain re-orgs.
+/// A transaction's expiration is part of the digest, if it has one.
 pub fn compute_proposal_hash(txn: &Transaction, sys: &dyn Primitives) -> anyhow::Result<[u8; 32]> {
-    let proposal_hash = ProposalHashData {
-        requester: txn.approved.first(),
-        to: &txn.to,
-        value: &txn.value,
-        method: &txn.method,
-        params: &txn.params,
+    let data = match &txn.expiration {
+        None => serialize_vec(
+            &ProposalHashData {
+                requester: txn.approved.first(),
+                to: &txn.to,
+                value: &txn.value,
+                method: &txn.method,
+                params: &txn.params,
+            },
+            "proposal hash",
+        )?,
+        Some(expiration) => serialize_vec(
+            &ExpiringProposalHashData {
+                requester: txn.approved.first(),
+                to: &txn.to,
+                value: &txn.value,
+                method: &txn.method,
+                params: &txn.params,
+                expiration,
+            },
+            "proposal hash",
+        )?,
     };
-    let data = serialize_vec(&proposal_hash, "proposal hash")?;
     Ok(sys.hash_blake2b(&data))
 }
 
@@ -562,6 +836,12 @@ impl ActorCode for Actor {
       ChangeNumApprovalsThreshold => change_num_approvals_threshold,
       LockBalance => lock_balance,
       UniversalReceiverHook => universal_receiver_hook,
+      ProposalHashExported => proposal_hash,
+      PruneExpiredExported => prune_expired,
+      ApproveBatchExported => approve_batch,
+      SetSpendingLimitExported => set_spending_limit,
+      ListPendingTransactionsExported => list_pending_transactions,
+      SwapSignerAtomicExported => swap_signer_atomic,
       _ => fallback,
     }
 }
@@ -1,6 +1,8 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::BTreeMap;
+
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
@@ -15,7 +17,7 @@ use num_traits::Zero;
 use fil_actors_runtime::{ActorError, Config, DEFAULT_HAMT_CONFIG, Map2, actor_error};
 
 use super::TxnID;
-use super::types::Transaction;
+use super::types::{SignerSpending, SpendingLimit, Transaction};
 
 pub type PendingTxnMap<BS> = Map2<BS, TxnID, Transaction>;
 pub const PENDING_TXN_CONFIG: Config = DEFAULT_HAMT_CONFIG;
@@ -33,6 +35,10 @@ pub struct State {
     pub unlock_duration: ChainEpoch,
 
     pub pending_txs: Cid,
+
+    /// Limits on the value signers may send without the approval of others.
+    #[serde(default)]
+    pub spending_limits: Vec<SignerSpending>,
 }
 
 impl State {
@@ -41,6 +47,53 @@ impl State {
         self.signers.contains(address)
     }
 
+    /// Returns a signer's spending limit and recent spending, if it has a limit.
+    pub fn spending_limit(&self, signer: &Address) -> Option<&SignerSpending> {
+        self.spending_limits.iter().find(|s| &s.signer == signer)
+    }
+
+    /// Sets or removes a signer's spending limit.
+    /// Spending already recorded in the current window counts against a replacement limit
+    /// with the same window.
+    pub fn set_spending_limit(&mut self, signer: Address, limit: Option<SpendingLimit>) {
+        let Some(limit) = limit else {
+            self.spending_limits.retain(|s| s.signer != signer);
+            return;
+        };
+        if let Some(s) = self.spending_limits.iter_mut().find(|s| s.signer == signer) {
+            s.limit = limit;
+        } else {
+            self.spending_limits.push(SignerSpending {
+                signer,
+                limit,
+                window_start: 0,
+                spent: TokenAmount::zero(),
+            });
+        }
+    }
+
+    /// Records a signer spending a value at an epoch, returning whether the value was within
+    /// the allowance remaining in the window containing the epoch.
+    /// Nothing is recorded if the signer has no limit or the value exceeds its allowance.
+    pub fn consume_spending_allowance(
+        &mut self,
+        signer: &Address,
+        value: &TokenAmount,
+        epoch: ChainEpoch,
+    ) -> bool {
+        let Some(s) = self.spending_limits.iter_mut().find(|s| &s.signer == signer) else {
+            return false;
+        };
+        let window_start = epoch - epoch.rem_euclid(s.limit.window);
+        let spent = if s.window_start == window_start { &s.spent + value } else { value.clone() };
+        if spent > s.limit.amount {
+            return false;
+        }
+        s.window_start = window_start;
+        s.spent = spent;
+        true
+    }
+
     /// Set locked amount in multisig state.
     pub fn set_locked(
         &mut self,
@@ -107,6 +160,96 @@ impl State {
         Ok(())
     }
 
+    /// Transfers a signer's approvals of pending transactions to another signer.
+    /// An approval is dropped rather than duplicated if the other signer has already approved.
+    pub fn rebind_approvals<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        from: &Address,
+        to: &Address,
+    ) -> Result<(), ActorError> {
+        let mut txns =
+            PendingTxnMap::load(store, &self.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+
+        let mut txn_ids_to_rebind = IndexMap::new();
+        txns.for_each(|tx_id, txn: &Transaction| {
+            if txn.approved.contains(from) {
+                txn_ids_to_rebind.insert(tx_id, txn.clone());
+            }
+            Ok(())
+        })?;
+
+        for (tx_id, mut txn) in txn_ids_to_rebind {
+            if txn.approved.contains(to) {
+                txn.approved.retain(|approver| approver != from);
+            } else {
+                // Replace in place, so the proposer keeps its position as the first approver.
+                txn.approved.iter_mut().filter(|a| *a == from).for_each(|a| *a = *to);
+            }
+            txns.set(&tx_id, txn)?;
+        }
+
+        self.pending_txs = txns.flush()?;
+        Ok(())
+    }
+
+    /// Removes pending transactions which have expired at an epoch, returning their IDs in
+    /// ascending order.
+    pub fn prune_expired<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        epoch: ChainEpoch,
+    ) -> Result<Vec<TxnID>, ActorError> {
+        let mut txns =
+            PendingTxnMap::load(store, &self.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+
+        let mut expired = Vec::new();
+        txns.for_each(|tx_id, txn: &Transaction| {
+            if txn.is_expired(epoch) {
+                expired.push(tx_id);
+            }
+            Ok(())
+        })?;
+        expired.sort_by_key(|tx_id| tx_id.0);
+
+        for tx_id in &expired {
+            txns.delete(tx_id)?;
+        }
+
+        self.pending_txs = txns.flush()?;
+        Ok(expired)
+    }
+
+    /// Lists up to `limit` pending transactions in ID order, starting after the cursor
+    /// transaction, and returns the last listed transaction as the cursor to continue from,
+    /// if any remain. The cursor need not be pending, e.g. once it has been executed.
+    /// Transactions are keyed by ID hash, so every transaction is read to select a page.
+    pub fn list_pending_transactions<BS: Blockstore>(
+        &self,
+        store: &BS,
+        cursor: Option<TxnID>,
+        limit: u64,
+    ) -> Result<(Vec<(TxnID, Transaction)>, Option<TxnID>), ActorError> {
+        let txns =
+            PendingTxnMap::load(store, &self.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+        let mut page = BTreeMap::new();
+        let mut more = false;
+        txns.for_each(|id, txn| {
+            if cursor.is_some_and(|c| id.0 <= c.0) {
+                return Ok(());
+            }
+            page.insert(id.0, txn.clone());
+            if page.len() as u64 > limit {
+                page.pop_last();
+                more = true;
+            }
+            Ok(())
+        })?;
+
+        let next_cursor = if more { page.last_key_value().map(|(id, _)| TxnID(*id)) } else { None };
+        Ok((page.into_iter().map(|(id, txn)| (TxnID(id), txn)).collect(), next_cursor))
+    }
+
     pub(crate) fn check_available(
         &self,
         balance: TokenAmount,
@@ -49,6 +49,18 @@ pub fn check_state_invariants<BS: Blockstore>(
     // create lookup to test transaction approvals are multisig signers
     let signers = HashSet::<&Address>::from_iter(state.signers.iter());
 
+    // test spending limits
+    let mut limited = HashSet::<&Address>::new();
+    for spending in &state.spending_limits {
+        let signer = &spending.signer;
+        acc.require(signers.contains(signer), format!("spending limit for non-signer {signer}"));
+        acc.require(limited.insert(signer), format!("duplicate spending limit for {signer}"));
+        acc.require(
+            spending.limit.window > 0,
+            format!("non-positive spending window {} for {signer}", spending.limit.window),
+        );
+    }
+
     // test pending transactions
     let mut max_tx_id = TxnID(-1);
     let mut pending_tx_count = 0u64;
@@ -10,14 +10,17 @@ use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
-use fil_actors_runtime::MapKey;
+use fil_actors_runtime::{BatchReturn, MapKey};
 
 /// SignersMax is the maximum number of signers allowed in a multisig. If more
 /// are required, please use a combining tree of multisigs.
 pub const SIGNERS_MAX: usize = 256;
 
+/// Maximum number of transactions which may be approved by a single ApproveBatch message.
+pub const APPROVE_BATCH_MAX: usize = 64;
+
 /// Transaction ID type
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Hash, Eq, PartialEq, PartialOrd)]
 #[serde(transparent)]
@@ -40,7 +43,7 @@ impl Display for TxnID {
 }
 
 /// Transaction type used in multisig actor
-#[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize_tuple)]
 pub struct Transaction {
     pub to: Address,
     pub value: TokenAmount,
@@ -48,6 +51,38 @@ pub struct Transaction {
     pub params: RawBytes,
 
     pub approved: Vec<Address>,
+    /// Last epoch at which the transaction may be approved, after which it may be pruned.
+    #[serde(default)]
+    pub expiration: Option<ChainEpoch>,
+}
+
+/// Serialize the transaction as a tuple, omitting the expiration if there is none,
+/// so that the encoding of such transactions is unchanged.
+impl Serialize for Transaction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeTuple;
+        let len = if self.expiration.is_some() { 6 } else { 5 };
+        let mut tuple = serializer.serialize_tuple(len)?;
+        tuple.serialize_element(&self.to)?;
+        tuple.serialize_element(&self.value)?;
+        tuple.serialize_element(&self.method)?;
+        tuple.serialize_element(&self.params)?;
+        tuple.serialize_element(&self.approved)?;
+        if let Some(expiration) = &self.expiration {
+            tuple.serialize_element(expiration)?;
+        }
+        tuple.end()
+    }
+}
+
+impl Transaction {
+    /// Whether the transaction has expired, and so can no longer be approved, at an epoch.
+    pub fn is_expired(&self, epoch: ChainEpoch) -> bool {
+        self.expiration.is_some_and(|expiration| epoch > expiration)
+    }
 }
 
 /// Data for a BLAKE2B-256 to be attached to methods referencing proposals via TXIDs.
@@ -65,6 +100,40 @@ pub struct ProposalHashData<'a> {
     pub params: &'a RawBytes,
 }
 
+/// Data for the proposal hash of a transaction with an expiration.
+/// The expiration follows the fields of ProposalHashData, so the hash of a transaction
+/// without an expiration is unchanged.
+#[derive(Serialize_tuple, Debug)]
+pub struct ExpiringProposalHashData<'a> {
+    pub requester: Option<&'a Address>,
+    pub to: &'a Address,
+    pub value: &'a TokenAmount,
+    pub method: &'a MethodNum,
+    pub params: &'a RawBytes,
+    pub expiration: &'a ChainEpoch,
+}
+
+/// Fields of a proposal for which to compute the proposal hash.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalHashParams {
+    /// The proposing signer, which is resolved to an ID address as recorded with the proposal.
+    pub requester: Address,
+    pub to: Address,
+    pub value: TokenAmount,
+    pub method: MethodNum,
+    pub params: RawBytes,
+    /// The proposal's expiration, if any.
+    #[serde(default)]
+    pub expiration: Option<ChainEpoch>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ProposalHashReturn {
+    #[serde(with = "strict_bytes")]
+    pub proposal_hash: Vec<u8>,
+}
+
 /// Constructor parameters for multisig actor.
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
@@ -82,6 +151,9 @@ pub struct ProposeParams {
     pub value: TokenAmount,
     pub method: MethodNum,
     pub params: RawBytes,
+    /// Last epoch at which the transaction may be approved. None for no expiration.
+    #[serde(default)]
+    pub expiration: Option<ChainEpoch>,
 }
 
 /// Propose method call return.
@@ -109,8 +181,32 @@ pub struct TxnIDParams {
     pub proposal_hash: Vec<u8>,
 }
 
+/// ApproveBatch method call parameters.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct ApproveBatchParams {
+    pub approvals: Vec<TxnIDParams>,
+}
+
+/// ApproveBatch method call return.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, PartialEq, Eq)]
+pub struct ApproveBatchReturn {
+    /// The outcome of each approval, in the order requested.
+    pub results: BatchReturn,
+    /// The return of each successful approval, in the order requested.
+    pub approvals: Vec<ApproveReturn>,
+}
+
+/// PruneExpired method call return.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct PruneExpiredReturn {
+    /// IDs of the expired transactions removed, in ascending order.
+    pub pruned: Vec<TxnID>,
+}
+
 /// Parameters for approve and cancel multisig functions.
-#[derive(Serialize_tuple, Deserialize_tuple)]
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, PartialEq, Eq)]
 pub struct ApproveReturn {
     /// Applied indicates if the transaction was applied as opposed to proposed but not applied
     /// due to lack of approvals
@@ -143,6 +239,78 @@ pub struct SwapSignerParams {
     pub to: Address,
 }
 
+/// Atomic swap signer multisig method params
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct SwapSignerAtomicParams {
+    pub from: Address,
+    pub to: Address,
+    /// Whether the old signer's approvals of pending transactions are transferred to the new
+    /// signer. Otherwise they are removed, as by SwapSigner.
+    pub rebind_approvals: bool,
+}
+
+/// A signer's allowance to send value without the approval of other signers.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct SpendingLimit {
+    /// Length of the window over which the amount may be spent, in epochs.
+    /// Windows are aligned to multiples of their length.
+    pub window: ChainEpoch,
+    /// Maximum value the signer may send within a single window.
+    pub amount: TokenAmount,
+}
+
+/// A signer's spending limit, and its spending in the most recent window in which it spent.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct SignerSpending {
+    pub signer: Address,
+    pub limit: SpendingLimit,
+    pub window_start: ChainEpoch,
+    pub spent: TokenAmount,
+}
+
+/// SetSpendingLimit method call parameters.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct SetSpendingLimitParams {
+    pub signer: Address,
+    /// The signer's new limit, or None to remove its limit.
+    pub limit: Option<SpendingLimit>,
+}
+
+/// ListPendingTransactions method call parameters.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ListPendingTransactionsParams {
+    /// Transaction after which to continue listing, as returned by a previous call.
+    /// It need no longer be pending. If absent, listing starts from the beginning.
+    pub cursor: Option<TxnID>,
+    /// Maximum number of transactions to return. Must be positive.
+    pub limit: u64,
+}
+
+/// A summary of a pending transaction, sufficient to present it for approval.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct PendingTransactionSummary {
+    pub id: TxnID,
+    /// The signer which proposed the transaction, or the earliest remaining approver
+    /// if the proposer has since been removed.
+    pub proposer: Address,
+    pub to: Address,
+    pub value: TokenAmount,
+    pub method: MethodNum,
+    /// BLAKE2B-256 digest of the transaction's parameters.
+    #[serde(with = "strict_bytes")]
+    pub params_digest: Vec<u8>,
+    /// The number of signers which have approved the transaction, including the proposer.
+    pub approvals: u64,
+    pub expiration: Option<ChainEpoch>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ListPendingTransactionsReturn {
+    pub transactions: Vec<PendingTransactionSummary>,
+    /// Cursor after which to continue listing, absent if there are no more transactions.
+    pub next_cursor: Option<TxnID>,
+}
+
 /// Propose method call parameters
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ChangeNumApprovalsThresholdParams {
@@ -1,7 +1,8 @@
 use fil_actor_multisig::testing::check_state_invariants;
 use fil_actor_multisig::{
-    Actor as MultisigActor, ConstructorParams, Method, ProposeReturn, SIGNERS_MAX, State,
-    Transaction, TxnID, TxnIDParams, compute_proposal_hash,
+    Actor as MultisigActor, ConstructorParams, Method, ProposalHashParams, ProposalHashReturn,
+    ProposeParams, ProposeReturn, SIGNERS_MAX, State, Transaction, TxnID, TxnIDParams,
+    compute_proposal_hash,
 };
 use fil_actors_runtime::FIRST_EXPORTED_METHOD_NUMBER;
 use fil_actors_runtime::cbor::serialize;
@@ -575,6 +576,7 @@ fn test_simple_propose() {
         method: METHOD_SEND,
         params: RawBytes::default(),
         approved: vec![anne],
+        expiration: None,
     };
     let expect_txns = vec![(TxnID(0), txn0)];
     h.assert_transactions(&rt, expect_txns);
@@ -734,6 +736,79 @@ fn test_fail_propose_from_non_signer() {
     check_state(&rt);
 }
 
+#[test]
+fn test_lifecycle_events() {
+    let msig = Address::new_id(1000);
+    let rt = construct_runtime(msig);
+    let h = util::ActorHarness::new();
+
+    let anne = Address::new_id(101);
+    let bob = Address::new_id(102);
+    let chuck = Address::new_id(103);
+    let fake_params = RawBytes::from([99u8; 3].to_vec());
+    let send_value = TokenAmount::from_atto(10u8);
+    let fake_method = 42u64;
+    rt.set_balance(TokenAmount::from_atto(10u8));
+    rt.set_received(TokenAmount::zero());
+    h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+    let propose_params = ProposeParams {
+        to: chuck,
+        value: send_value.clone(),
+        method: fake_method,
+        params: fake_params.clone(),
+        expiration: None,
+    };
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+    rt.expect_validate_caller_any();
+    rt.expect_emitted_event(util::proposed_event(TxnID(0), anne, chuck, &send_value, fake_method));
+    rt.expect_emitted_event(util::approved_event(TxnID(0), anne, 1));
+    rt.call::<MultisigActor>(
+        Method::Propose as u64,
+        IpldBlock::serialize_cbor(&propose_params).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+
+    // The executed event carries the exit code of the failed transaction.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+    rt.expect_validate_caller_any();
+    rt.expect_send_simple(
+        chuck,
+        fake_method,
+        to_ipld_block(fake_params.clone()),
+        send_value.clone(),
+        None,
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+    );
+    rt.expect_emitted_event(util::approved_event(TxnID(0), bob, 2));
+    rt.expect_emitted_event(util::executed_event(TxnID(0), bob, ExitCode::USR_ILLEGAL_ARGUMENT));
+    let params = TxnIDParams { id: TxnID(0), proposal_hash: Vec::<u8>::new() };
+    rt.call::<MultisigActor>(Method::Approve as u64, IpldBlock::serialize_cbor(&params).unwrap())
+        .unwrap();
+    rt.verify();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+    rt.expect_validate_caller_any();
+    rt.expect_emitted_event(util::proposed_event(TxnID(1), anne, chuck, &send_value, fake_method));
+    rt.expect_emitted_event(util::approved_event(TxnID(1), anne, 1));
+    rt.call::<MultisigActor>(
+        Method::Propose as u64,
+        IpldBlock::serialize_cbor(&propose_params).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+
+    rt.expect_validate_caller_any();
+    rt.expect_emitted_event(util::cancelled_event(TxnID(1), anne));
+    let params = TxnIDParams { id: TxnID(1), proposal_hash: Vec::<u8>::new() };
+    rt.call::<MultisigActor>(Method::Cancel as u64, IpldBlock::serialize_cbor(&params).unwrap())
+        .unwrap();
+    rt.verify();
+    h.assert_transactions(&rt, vec![]);
+    check_state(&rt);
+}
+
 // AddSigner
 #[test]
 fn test_add_signer() {
@@ -1145,6 +1220,7 @@ fn test_swap_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration: None,
                 },
             ),
             (
@@ -1155,6 +1231,7 @@ fn test_swap_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration: None,
                 },
             ),
         ],
@@ -1231,6 +1308,7 @@ fn test_remove_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration: None,
                 },
             ),
             (
@@ -1241,6 +1319,7 @@ fn test_remove_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration: None,
                 },
             ),
         ],
@@ -1306,6 +1385,7 @@ mod approval_tests {
             method: fake_method,
             params: fake_params.clone(),
             approved: vec![anne],
+            expiration: None,
         };
         h.assert_transactions(&rt, vec![(TxnID(0), expect_txn)]);
 
@@ -1392,6 +1472,7 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params.clone(),
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -1442,6 +1523,7 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -1479,6 +1561,7 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -1512,7 +1595,8 @@ mod approval_tests {
                 value: send_value,
                 method: fake_method,
                 params: fake_params,
-                approved: vec![bob], //mismatch
+                approved: vec![bob], //mismatch,
+                expiration: None,
             },
             &rt,
         )
@@ -1551,6 +1635,7 @@ mod approval_tests {
             method: fake_method,
             params: fake_params.clone(),
             approved: vec![anne],
+            expiration: None,
         };
         h.assert_transactions(&rt, vec![(TxnID(0), expect_txn)]);
 
@@ -1605,6 +1690,7 @@ mod approval_tests {
             ExitCode::OK,
         );
         rt.expect_validate_caller_any();
+        util::expect_approval_events(&rt, TxnID(0));
         let params = TxnIDParams { id: TxnID(0), proposal_hash: Vec::<u8>::new() };
         rt.call::<MultisigActor>(
             Method::Approve as u64,
@@ -1648,6 +1734,7 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -1714,6 +1801,7 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -1949,6 +2037,7 @@ mod cancel_tests {
                     method: fake_method,
                     params: RawBytes::default(),
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -1990,6 +2079,7 @@ mod cancel_tests {
                     method: fake_method,
                     params: RawBytes::default(),
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -2032,6 +2122,7 @@ mod cancel_tests {
                     method: fake_method,
                     params: RawBytes::default(),
                     approved: vec![anne],
+                    expiration: None,
                 },
             )],
         );
@@ -2083,7 +2174,8 @@ mod cancel_tests {
             value: send_value,
             method: fake_method,
             params: RawBytes::default(),
-            approved: vec![bob], // anne's approval is gone
+            approved: vec![bob], // anne's approval is gone,
+            expiration: None,
         };
         let new_proposal_hash = compute_proposal_hash(&new_tx, &rt).unwrap();
         h.assert_transactions(&rt, vec![(TxnID(0), new_tx)]);
@@ -2486,3 +2578,756 @@ fn test_fallback() {
 
     rt.verify();
 }
+
+#[test]
+fn proposal_hash_matches_proposal() {
+    let msig = Address::new_id(1000);
+    let anne = Address::new_id(101);
+    let bob = Address::new_id(102);
+    let chuck = Address::new_id(103);
+    let anne_non_id = Address::new_bls(&[1u8; BLS_PUB_LEN]).unwrap();
+
+    let rt = construct_runtime(msig);
+    rt.add_id_address(anne_non_id, anne);
+    let h = util::ActorHarness::new();
+    h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+    let params = RawBytes::from(vec![1, 2, 3, 4]);
+    let method = 42;
+    let value = TokenAmount::from_atto(10u8);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+    let proposed_hash = h.propose_ok(&rt, chuck, value.clone(), method, params.clone());
+
+    // The hash is computed for the requester's ID address, as recorded with the proposal.
+    let hash_params = ProposalHashParams {
+        requester: anne_non_id,
+        to: chuck,
+        value: value.clone(),
+        method,
+        params: params.clone(),
+        expiration: None,
+    };
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_any();
+    let ret: ProposalHashReturn = rt
+        .call::<MultisigActor>(
+            Method::ProposalHashExported as u64,
+            IpldBlock::serialize_cbor(&hash_params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(proposed_hash.to_vec(), ret.proposal_hash);
+
+    // The previewed hash is accepted for approval.
+    rt.set_balance(value.clone());
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+    rt.expect_send_simple(chuck, method, to_ipld_block(params), value, None, ExitCode::OK);
+    h.approve_ok(&rt, TxnID(0), ret.proposal_hash.try_into().unwrap());
+
+    // An unknown requester cannot have made a proposal.
+    let hash_params = ProposalHashParams {
+        requester: Address::new_bls(&[9u8; BLS_PUB_LEN]).unwrap(),
+        ..hash_params
+    };
+    rt.expect_validate_caller_any();
+    expect_abort(
+        ExitCode::USR_NOT_FOUND,
+        rt.call::<MultisigActor>(
+            Method::ProposalHashExported as u64,
+            IpldBlock::serialize_cbor(&hash_params).unwrap(),
+        ),
+    );
+    rt.verify();
+    check_state(&rt);
+}
+
+mod approve_batch_tests {
+    use super::*;
+    use fil_actor_multisig::{APPROVE_BATCH_MAX, ApproveReturn};
+    use fil_actors_runtime::BatchReturn;
+    use fvm_ipld_encoding::DAG_CBOR;
+
+    #[test]
+    fn approves_batch_reporting_each_outcome() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        let send_value = TokenAmount::from_atto(10u8);
+        rt.set_balance(send_value.clone() * 3);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        let hashes: Vec<[u8; 32]> = [42, 43, 44]
+            .into_iter()
+            .map(|method| h.propose_ok(&rt, chuck, send_value.clone(), method, RawBytes::default()))
+            .collect();
+
+        // The first and last transactions are executed, the last failing to apply.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        rt.expect_emitted_event(util::approved_event(TxnID(0), bob, 2));
+        rt.expect_send_simple(chuck, 42, None, send_value.clone(), None, ExitCode::OK);
+        rt.expect_emitted_event(util::executed_event(TxnID(0), bob, ExitCode::OK));
+        rt.expect_emitted_event(util::approved_event(TxnID(2), bob, 2));
+        rt.expect_send_simple(chuck, 44, None, send_value.clone(), None, ExitCode::USR_UNSPECIFIED);
+        rt.expect_emitted_event(util::executed_event(TxnID(2), bob, ExitCode::USR_UNSPECIFIED));
+        let ret = h
+            .approve_batch(
+                &rt,
+                &[
+                    (TxnID(0), hashes[0]),
+                    (TxnID(1), hashes[0]),
+                    (TxnID(5), [0; 32]),
+                    (TxnID(2), hashes[2]),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            BatchReturn::of(&[
+                ExitCode::OK,
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                ExitCode::USR_NOT_FOUND,
+                ExitCode::OK
+            ]),
+            ret.results
+        );
+        assert_eq!(
+            vec![
+                ApproveReturn { applied: true, code: ExitCode::OK, ret: RawBytes::default() },
+                ApproveReturn {
+                    applied: true,
+                    code: ExitCode::USR_UNSPECIFIED,
+                    ret: RawBytes::default()
+                },
+            ],
+            ret.approvals
+        );
+
+        // Only the transaction with a mismatched hash remains pending.
+        h.assert_transactions(
+            &rt,
+            vec![(
+                TxnID(1),
+                Transaction {
+                    to: chuck,
+                    value: send_value,
+                    method: 43,
+                    params: RawBytes::default(),
+                    approved: vec![anne],
+                    expiration: None,
+                },
+            )],
+        );
+        check_state(&rt);
+    }
+
+    #[test]
+    fn non_signer_cannot_approve_batch() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        let hash = h.propose_ok(&rt, chuck, TokenAmount::zero(), 42, RawBytes::default());
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, chuck);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "is not a signer",
+            h.approve_batch(&rt, &[(TxnID(0), hash)]),
+        );
+        check_state(&rt);
+    }
+
+    #[test]
+    fn approve_batch_rejects_too_many_approvals() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        let approvals: Vec<(TxnID, [u8; 32])> =
+            (0..=APPROVE_BATCH_MAX as i64).map(|id| (TxnID(id), [0; 32])).collect();
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "too many transactions to approve",
+            h.approve_batch(&rt, &approvals),
+        );
+
+        // A full batch is accepted, reporting each missing transaction.
+        let ret = h.approve_batch(&rt, &approvals[..APPROVE_BATCH_MAX]).unwrap();
+        assert_eq!(BatchReturn::of(&[ExitCode::USR_NOT_FOUND; APPROVE_BATCH_MAX]), ret.results);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn approve_batch_aborts_on_illegal_state() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        let hash = h.propose_ok(&rt, chuck, TokenAmount::zero(), 42, RawBytes::default());
+
+        // The pending transactions cannot be loaded.
+        let mut st: State = rt.get_state();
+        st.pending_txs = make_cid_sha(b"missing", DAG_CBOR);
+        rt.replace_state(&st);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        expect_abort(ExitCode::USR_ILLEGAL_STATE, h.approve_batch(&rt, &[(TxnID(0), hash)]));
+    }
+}
+
+mod spending_limit_tests {
+    use super::*;
+    use fil_actor_multisig::SpendingLimit;
+
+    const WINDOW: ChainEpoch = 100;
+
+    fn limit(amount: u64) -> SpendingLimit {
+        SpendingLimit { window: WINDOW, amount: TokenAmount::from_atto(amount) }
+    }
+
+    #[test]
+    fn signer_spends_within_limit_without_approval() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+        rt.set_balance(TokenAmount::from_atto(100u8));
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+        h.set_spending_limit(&rt, bob, Some(limit(15))).unwrap();
+
+        // A transfer within the allowance is executed on proposal.
+        rt.set_epoch(WINDOW + 10);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        let value = TokenAmount::from_atto(10u8);
+        rt.expect_send_simple(chuck, METHOD_SEND, None, value.clone(), None, ExitCode::OK);
+        let ret: ProposeReturn = h
+            .propose(&rt, chuck, value.clone(), METHOD_SEND, RawBytes::default())
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        assert!(ret.applied);
+
+        // A transfer exceeding the allowance remaining in the window awaits approval,
+        // as does any other method, and any proposal by a signer without a limit.
+        h.propose(&rt, chuck, value.clone(), METHOD_SEND, RawBytes::default()).unwrap();
+        h.propose(&rt, chuck, TokenAmount::from_atto(1u8), 42, RawBytes::default()).unwrap();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose(&rt, chuck, TokenAmount::from_atto(1u8), METHOD_SEND, RawBytes::default())
+            .unwrap();
+        let st: State = rt.get_state();
+        let spending = st.spending_limit(&bob).unwrap();
+        assert_eq!((WINDOW, value.clone()), (spending.window_start, spending.spent.clone()));
+
+        // The allowance is renewed in the next window.
+        rt.set_epoch(2 * WINDOW);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        rt.expect_send_simple(chuck, METHOD_SEND, None, value.clone(), None, ExitCode::OK);
+        h.propose(&rt, chuck, value, METHOD_SEND, RawBytes::default()).unwrap();
+        let st: State = rt.get_state();
+        assert_eq!(2 * WINDOW, st.spending_limit(&bob).unwrap().window_start);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn set_spending_limit_validation() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        // Only the multisig itself may set limits.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        expect_abort(ExitCode::USR_FORBIDDEN, h.set_spending_limit(&rt, anne, Some(limit(1))));
+        rt.reset();
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "is not a signer",
+            h.set_spending_limit(&rt, chuck, Some(limit(1))),
+        );
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "window must be positive",
+            h.set_spending_limit(&rt, bob, Some(SpendingLimit { window: 0, ..limit(1) })),
+        );
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "amount must be positive",
+            h.set_spending_limit(&rt, bob, Some(limit(0))),
+        );
+
+        // Limits may be removed explicitly, and are removed with their signer.
+        h.set_spending_limit(&rt, anne, Some(limit(1))).unwrap();
+        h.set_spending_limit(&rt, bob, Some(limit(1))).unwrap();
+        h.set_spending_limit(&rt, anne, None).unwrap();
+        h.remove_signer(&rt, bob, true).unwrap();
+        let st: State = rt.get_state();
+        assert!(st.spending_limits.is_empty());
+        check_state(&rt);
+    }
+}
+
+mod expiration_tests {
+    use super::*;
+
+    #[test]
+    fn expired_transaction_cannot_be_approved() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        let send_value = TokenAmount::from_atto(10u8);
+        let fake_params = RawBytes::from(vec![1, 2, 3, 4]);
+        let fake_method = 42;
+        let txns: Vec<(TxnID, Transaction)> = [110, 120]
+            .into_iter()
+            .enumerate()
+            .map(|(i, expiration)| {
+                let txn = Transaction {
+                    to: chuck,
+                    value: send_value.clone(),
+                    method: fake_method,
+                    params: fake_params.clone(),
+                    approved: vec![anne],
+                    expiration: Some(expiration),
+                };
+                (TxnID(i as i64), txn)
+            })
+            .collect();
+
+        rt.set_epoch(100);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        for (_, txn) in &txns {
+            h.propose_expiring(
+                &rt,
+                chuck,
+                send_value.clone(),
+                fake_method,
+                fake_params.clone(),
+                txn.expiration,
+            )
+            .unwrap();
+        }
+        h.assert_transactions(&rt, txns.clone());
+
+        // A transaction may be approved up to and including its expiration epoch.
+        rt.set_epoch(111);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        let proposal_hash = compute_proposal_hash(&txns[0].1, &rt).unwrap();
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "transaction 0 expired at epoch 110",
+            h.approve(&rt, TxnID(0), proposal_hash),
+        );
+
+        rt.set_epoch(120);
+        rt.set_balance(send_value.clone());
+        rt.expect_send_simple(
+            chuck,
+            fake_method,
+            to_ipld_block(fake_params),
+            send_value,
+            None,
+            ExitCode::OK,
+        );
+        let proposal_hash = compute_proposal_hash(&txns[1].1, &rt).unwrap();
+        h.approve_ok(&rt, TxnID(1), proposal_hash);
+        h.assert_transactions(&rt, vec![txns[0].clone()]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn anyone_can_prune_expired_transactions() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        rt.set_epoch(100);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        for expiration in [Some(110), None, Some(120)] {
+            h.propose_expiring(
+                &rt,
+                chuck,
+                TokenAmount::zero(),
+                42,
+                RawBytes::default(),
+                expiration,
+            )
+            .unwrap();
+        }
+
+        // Nothing has expired yet.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, chuck);
+        rt.set_epoch(110);
+        h.prune_expired(&rt, vec![]);
+
+        rt.set_epoch(115);
+        h.prune_expired(&rt, vec![TxnID(0)]);
+        rt.set_epoch(1000);
+        h.prune_expired(&rt, vec![TxnID(2)]);
+
+        // The transaction without expiration remains pending.
+        h.assert_transactions(
+            &rt,
+            vec![(
+                TxnID(1),
+                Transaction {
+                    to: chuck,
+                    value: TokenAmount::zero(),
+                    method: 42,
+                    params: RawBytes::default(),
+                    approved: vec![anne],
+                    expiration: None,
+                },
+            )],
+        );
+        check_state(&rt);
+    }
+
+    #[test]
+    fn propose_rejects_past_expiration() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        rt.set_epoch(100);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            h.propose_expiring(&rt, chuck, TokenAmount::zero(), 42, RawBytes::default(), Some(99)),
+        );
+        h.propose_expiring(&rt, chuck, TokenAmount::zero(), 42, RawBytes::default(), Some(100))
+            .unwrap();
+        check_state(&rt);
+    }
+
+    #[test]
+    fn proposal_hash_includes_expiration() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        let txn = Transaction {
+            to: chuck,
+            value: TokenAmount::zero(),
+            method: 42,
+            params: RawBytes::default(),
+            approved: vec![anne],
+            expiration: Some(110),
+        };
+        rt.set_epoch(100);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_expiring(&rt, chuck, TokenAmount::zero(), 42, RawBytes::default(), Some(110))
+            .unwrap();
+
+        // A hash computed for another expiration, or none, does not match the proposal.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        for expiration in [None, Some(120)] {
+            let other_hash =
+                compute_proposal_hash(&Transaction { expiration, ..txn.clone() }, &rt).unwrap();
+            expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.approve(&rt, TxnID(0), other_hash));
+        }
+
+        // The hash previewed with the expiration matches.
+        let hash_params = ProposalHashParams {
+            requester: anne,
+            to: chuck,
+            value: TokenAmount::zero(),
+            method: 42,
+            params: RawBytes::default(),
+            expiration: Some(110),
+        };
+        rt.expect_validate_caller_any();
+        let ret: ProposalHashReturn = rt
+            .call::<MultisigActor>(
+                Method::ProposalHashExported as u64,
+                IpldBlock::serialize_cbor(&hash_params).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        assert_eq!(compute_proposal_hash(&txn, &rt).unwrap().to_vec(), ret.proposal_hash);
+
+        rt.expect_send_simple(chuck, 42, None, TokenAmount::zero(), None, ExitCode::OK);
+        h.approve_ok(&rt, TxnID(0), ret.proposal_hash.try_into().unwrap());
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn transaction_encoding_omits_absent_expiration() {
+        let txn = Transaction {
+            to: Address::new_id(103),
+            value: TokenAmount::zero(),
+            method: 42,
+            params: RawBytes::default(),
+            approved: vec![Address::new_id(101)],
+            expiration: None,
+        };
+        let expiring = Transaction { expiration: Some(110), ..txn.clone() };
+
+        // A transaction without an expiration encodes as before the expiration was introduced.
+        let bytes = fvm_ipld_encoding::to_vec(&txn).unwrap();
+        assert_eq!(0x85, bytes[0]); // array of 5 elements
+        let expiring_bytes = fvm_ipld_encoding::to_vec(&expiring).unwrap();
+        assert_eq!(0x86, expiring_bytes[0]); // array of 6 elements
+
+        assert_eq!(txn, fvm_ipld_encoding::from_slice::<Transaction>(&bytes).unwrap());
+        assert_eq!(
+            expiring,
+            fvm_ipld_encoding::from_slice::<Transaction>(&expiring_bytes).unwrap()
+        );
+    }
+}
+
+mod list_pending_tests {
+    use super::*;
+    use fil_actor_multisig::PendingTransactionSummary;
+
+    #[test]
+    fn lists_pending_transactions_in_pages() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 3, 0, 0, vec![anne, bob, chuck]);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        let params: Vec<RawBytes> = (0..3u8).map(|i| RawBytes::new(vec![i; 4])).collect();
+        let hashes: Vec<[u8; 32]> = params
+            .iter()
+            .map(|p| h.propose_ok(&rt, chuck, TokenAmount::from_atto(5), 42, p.clone()))
+            .collect();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        assert!(h.approve(&rt, TxnID(1), hashes[1]).is_ok());
+
+        let first = h.list_pending_transactions(&rt, None, 2).unwrap();
+        assert_eq!(2, first.transactions.len());
+        assert_eq!(Some(TxnID(1)), first.next_cursor);
+        let second = h.list_pending_transactions(&rt, first.next_cursor, 2).unwrap();
+        assert_eq!(1, second.transactions.len());
+        assert_eq!(None, second.next_cursor);
+
+        let listed = [first.transactions, second.transactions].concat();
+        let expected: Vec<PendingTransactionSummary> = params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| PendingTransactionSummary {
+                id: TxnID(i as i64),
+                proposer: anne,
+                to: chuck,
+                value: TokenAmount::from_atto(5),
+                method: 42,
+                params_digest: blake2b_256(p.bytes()).to_vec(),
+                approvals: if i == 1 { 2 } else { 1 },
+                expiration: None,
+            })
+            .collect();
+        assert_eq!(expected, listed);
+
+        // A single page may hold all transactions.
+        let all = h.list_pending_transactions(&rt, None, 10).unwrap();
+        assert_eq!(expected, all.transactions);
+        assert_eq!(None, all.next_cursor);
+
+        // Listing resumes after a cursor which is no longer pending.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.cancel(&rt, TxnID(1), hashes[1]).unwrap();
+        let resumed = h.list_pending_transactions(&rt, Some(TxnID(1)), 2).unwrap();
+        assert_eq!(expected[2..], resumed.transactions);
+        assert_eq!(None, resumed.next_cursor);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn list_pending_transactions_validation() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        let empty = h.list_pending_transactions(&rt, None, 1).unwrap();
+        assert!(empty.transactions.is_empty());
+        assert_eq!(None, empty.next_cursor);
+
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "limit must be positive",
+            h.list_pending_transactions(&rt, None, 0),
+        );
+        // A cursor need not be a pending transaction.
+        let empty = h.list_pending_transactions(&rt, Some(TxnID(7)), 1).unwrap();
+        assert!(empty.transactions.is_empty());
+        assert_eq!(None, empty.next_cursor);
+    }
+}
+
+mod swap_signer_atomic_tests {
+    use super::*;
+
+    fn txn(chuck: Address, approved: Vec<Address>) -> Transaction {
+        Transaction {
+            to: chuck,
+            value: TokenAmount::zero(),
+            method: METHOD_SEND,
+            params: RawBytes::default(),
+            approved,
+            expiration: None,
+        }
+    }
+
+    #[test]
+    fn rebinds_approvals_to_new_signer() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let darlene = Address::new_id(104);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 3, 0, 0, vec![anne, bob, chuck]);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        let hash0 = h.propose_ok(&rt, chuck, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        h.approve_ok(&rt, TxnID(0), hash0);
+        let hash1 = h.propose_ok(&rt, chuck, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.approve_ok(&rt, TxnID(1), hash1);
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+        h.swap_signers_atomic(&rt, anne, darlene, true).unwrap();
+
+        // Darlene takes anne's place among the signers and in each transaction's approvals,
+        // including as proposer.
+        let st: State = rt.get_state();
+        assert_eq!(vec![darlene, bob, chuck], st.signers);
+        h.assert_transactions(
+            &rt,
+            vec![
+                (TxnID(0), txn(chuck, vec![darlene, bob])),
+                (TxnID(1), txn(chuck, vec![bob, darlene])),
+            ],
+        );
+        check_state(&rt);
+
+        // The rebound proposal is now darlene's, and may be cancelled by darlene.
+        let rebound_hash = compute_proposal_hash(&txn(chuck, vec![darlene, bob]), &rt).unwrap();
+        assert_ne!(hash0, rebound_hash);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, darlene);
+        h.cancel(&rt, TxnID(0), rebound_hash).unwrap();
+        h.assert_transactions(&rt, vec![(TxnID(1), txn(chuck, vec![bob, darlene]))]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn clears_approvals_of_old_signer() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let darlene = Address::new_id(104);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 3, 0, 0, vec![anne, bob, chuck]);
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        let hash0 = h.propose_ok(&rt, chuck, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        h.approve_ok(&rt, TxnID(0), hash0);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_ok(&rt, chuck, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+        h.swap_signers_atomic(&rt, anne, darlene, false).unwrap();
+
+        // Anne's approvals are removed, deleting anne's solo proposal.
+        let st: State = rt.get_state();
+        assert_eq!(vec![darlene, bob, chuck], st.signers);
+        h.assert_transactions(&rt, vec![(TxnID(0), txn(chuck, vec![bob]))]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn swap_signer_atomic_validation() {
+        let msig = Address::new_id(100);
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        let chuck = Address::new_id(103);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "is already a signer",
+            h.swap_signers_atomic(&rt, anne, bob, true),
+        );
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "is not a signer",
+            h.swap_signers_atomic(&rt, chuck, Address::new_id(104), true),
+        );
+
+        // Only the multisig itself may swap signers.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        expect_abort(ExitCode::USR_FORBIDDEN, h.swap_signers_atomic(&rt, anne, chuck, true));
+
+        let st: State = rt.get_state();
+        assert_eq!(vec![anne, bob], st.signers);
+        check_state(&rt);
+    }
+}
@@ -1,20 +1,23 @@
 use fil_actor_multisig::{
-    Actor, AddSignerParams, ApproveReturn, ConstructorParams, Method, PENDING_TXN_CONFIG,
-    PendingTxnMap, ProposeParams, ProposeReturn, RemoveSignerParams, State, SwapSignerParams,
-    Transaction, TxnID, TxnIDParams, compute_proposal_hash,
+    Actor, AddSignerParams, ApproveBatchParams, ApproveBatchReturn, ApproveReturn,
+    ConstructorParams, ListPendingTransactionsParams, ListPendingTransactionsReturn, Method,
+    PENDING_TXN_CONFIG, PendingTxnMap, ProposeParams, ProposeReturn, PruneExpiredReturn,
+    RemoveSignerParams, SetSpendingLimitParams, SpendingLimit, State, SwapSignerAtomicParams,
+    SwapSignerParams, Transaction, TxnID, TxnIDParams, compute_proposal_hash,
 };
 use fil_actor_multisig::{ChangeNumApprovalsThresholdParams, LockBalanceParams};
-use fil_actors_runtime::ActorError;
 use fil_actors_runtime::INIT_ACTOR_ADDR;
 use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{ActorError, EventBuilder};
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::event::ActorEvent;
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::MethodNum;
 use fvm_shared::error::ExitCode;
+use fvm_shared::{METHOD_SEND, MethodNum};
 
 #[derive(Default)]
 pub struct ActorHarness {}
@@ -77,6 +80,22 @@ impl ActorHarness {
         ret
     }
 
+    pub fn set_spending_limit(
+        &self,
+        rt: &MockRuntime,
+        signer: Address,
+        limit: Option<SpendingLimit>,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_addr(vec![rt.receiver]);
+        let params = SetSpendingLimitParams { signer, limit };
+        let ret = rt.call::<Actor>(
+            Method::SetSpendingLimitExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        rt.verify();
+        ret
+    }
+
     pub fn swap_signers(
         &self,
         rt: &MockRuntime,
@@ -91,6 +110,23 @@ impl ActorHarness {
         ret
     }
 
+    pub fn swap_signers_atomic(
+        &self,
+        rt: &MockRuntime,
+        old_signer: Address,
+        new_signer: Address,
+        rebind_approvals: bool,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_addr(vec![rt.receiver]);
+        let params = SwapSignerAtomicParams { from: old_signer, to: new_signer, rebind_approvals };
+        let ret = rt.call::<Actor>(
+            Method::SwapSignerAtomicExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        rt.verify();
+        ret
+    }
+
     pub fn propose_ok(
         &self,
         rt: &MockRuntime,
@@ -102,7 +138,14 @@ impl ActorHarness {
         let ret = self.propose(rt, to, value.clone(), method, params.clone());
         ret.unwrap().unwrap().deserialize::<ProposeReturn>().unwrap();
         // compute proposal hash
-        let txn = Transaction { to, value, method, params, approved: vec![*rt.caller.borrow()] };
+        let txn = Transaction {
+            to,
+            value,
+            method,
+            params,
+            approved: vec![*rt.caller.borrow()],
+            expiration: None,
+        };
         compute_proposal_hash(&txn, rt).unwrap()
     }
 
@@ -122,13 +165,37 @@ impl ActorHarness {
         value: TokenAmount,
         method: MethodNum,
         params: RawBytes,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        self.propose_expiring(rt, to, value, method, params, None)
+    }
+
+    pub fn propose_expiring(
+        &self,
+        rt: &MockRuntime,
+        to: Address,
+        value: TokenAmount,
+        method: MethodNum,
+        params: RawBytes,
+        expiration: Option<ChainEpoch>,
     ) -> Result<Option<IpldBlock>, ActorError> {
         rt.expect_validate_caller_any();
-        let propose_params = ProposeParams { to, value, method, params };
+        let st: State = rt.get_state();
+        let proposer = *rt.caller.borrow();
+        rt.expect_emitted_event(proposed_event(st.next_tx_id, proposer, to, &value, method));
+        rt.expect_emitted_event(approved_event(st.next_tx_id, proposer, 1));
+        // A transfer within the proposer's spending allowance is executed without other approvals.
+        let within_limit = method == METHOD_SEND
+            && value.is_positive()
+            && st.clone().consume_spending_allowance(&proposer, &value, *rt.epoch.borrow());
+        if st.num_approvals_threshold <= 1 || within_limit {
+            expect_executed_event(rt, st.next_tx_id);
+        }
+        let propose_params = ProposeParams { to, value, method, params, expiration };
         let ret = rt.call::<Actor>(
             Method::Propose as u64,
             IpldBlock::serialize_cbor(&propose_params).unwrap(),
         );
+        discard_events_on_abort(rt, &ret);
         rt.verify();
         ret
     }
@@ -140,16 +207,41 @@ impl ActorHarness {
         proposal_hash: [u8; 32],
     ) -> Result<Option<IpldBlock>, ActorError> {
         rt.expect_validate_caller_any();
+        expect_approval_events(rt, txn_id);
         let approve_params =
             TxnIDParams { id: txn_id, proposal_hash: Vec::<u8>::from(proposal_hash) };
         let ret = rt.call::<Actor>(
             Method::Approve as u64,
             IpldBlock::serialize_cbor(&approve_params).unwrap(),
         );
+        discard_events_on_abort(rt, &ret);
         rt.verify();
         ret
     }
 
+    // Approves a batch of transactions. Expectations of the approvals' events and sends
+    // are left to the caller, as they depend on the outcome of each approval.
+    pub fn approve_batch(
+        &self,
+        rt: &MockRuntime,
+        approvals: &[(TxnID, [u8; 32])],
+    ) -> Result<ApproveBatchReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ApproveBatchParams {
+            approvals: approvals
+                .iter()
+                .map(|(id, hash)| TxnIDParams { id: *id, proposal_hash: hash.to_vec() })
+                .collect(),
+        };
+        let ret = rt.call::<Actor>(
+            Method::ApproveBatchExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        discard_events_on_abort(rt, &ret);
+        rt.verify();
+        ret.map(|r| r.unwrap().deserialize().unwrap())
+    }
+
     pub fn cancel(
         &self,
         rt: &MockRuntime,
@@ -157,16 +249,49 @@ impl ActorHarness {
         proposal_hash: [u8; 32],
     ) -> Result<Option<IpldBlock>, ActorError> {
         rt.expect_validate_caller_any();
+        rt.expect_emitted_event(cancelled_event(txn_id, *rt.caller.borrow()));
         let cancel_params =
             TxnIDParams { id: txn_id, proposal_hash: Vec::<u8>::from(proposal_hash) };
         let ret = rt.call::<Actor>(
             Method::Cancel as u64,
             IpldBlock::serialize_cbor(&cancel_params).unwrap(),
         );
+        discard_events_on_abort(rt, &ret);
         rt.verify();
         ret
     }
 
+    pub fn prune_expired(&self, rt: &MockRuntime, expect_pruned: Vec<TxnID>) {
+        rt.expect_validate_caller_any();
+        for id in &expect_pruned {
+            rt.expect_emitted_event(pruned_event(*id));
+        }
+        let ret: PruneExpiredReturn = rt
+            .call::<Actor>(Method::PruneExpiredExported as u64, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        assert_eq!(expect_pruned, ret.pruned);
+    }
+
+    pub fn list_pending_transactions(
+        &self,
+        rt: &MockRuntime,
+        cursor: Option<TxnID>,
+        limit: u64,
+    ) -> Result<ListPendingTransactionsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ListPendingTransactionsParams { cursor, limit };
+        let ret = rt.call::<Actor>(
+            Method::ListPendingTransactionsExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        rt.verify();
+        ret.map(|r| r.unwrap().deserialize().unwrap())
+    }
+
     pub fn lock_balance(
         &self,
         rt: &MockRuntime,
@@ -219,3 +344,89 @@ impl ActorHarness {
         assert_eq!(expect_txns, actual_txns);
     }
 }
+
+pub fn proposed_event(
+    txn_id: TxnID,
+    proposer: Address,
+    to: Address,
+    value: &TokenAmount,
+    method: MethodNum,
+) -> ActorEvent {
+    EventBuilder::new()
+        .typ("txn-proposed")
+        .field_indexed("id", &txn_id)
+        .field_indexed("proposer", &proposer.id().unwrap())
+        .field("to", &to)
+        .field_bigint("value", value.atto())
+        .field("method", &method)
+        .build()
+        .unwrap()
+}
+
+pub fn approved_event(txn_id: TxnID, approver: Address, approvals: u64) -> ActorEvent {
+    EventBuilder::new()
+        .typ("txn-approved")
+        .field_indexed("id", &txn_id)
+        .field_indexed("approver", &approver.id().unwrap())
+        .field("approvals", &approvals)
+        .build()
+        .unwrap()
+}
+
+pub fn cancelled_event(txn_id: TxnID, proposer: Address) -> ActorEvent {
+    EventBuilder::new()
+        .typ("txn-cancelled")
+        .field_indexed("id", &txn_id)
+        .field_indexed("proposer", &proposer.id().unwrap())
+        .build()
+        .unwrap()
+}
+
+pub fn executed_event(txn_id: TxnID, executor: Address, code: ExitCode) -> ActorEvent {
+    EventBuilder::new()
+        .typ("txn-executed")
+        .field_indexed("id", &txn_id)
+        .field_indexed("executor", &executor.id().unwrap())
+        .field("code", &code.value())
+        .build()
+        .unwrap()
+}
+
+pub fn pruned_event(txn_id: TxnID) -> ActorEvent {
+    EventBuilder::new().typ("txn-pruned").field_indexed("id", &txn_id).build().unwrap()
+}
+
+// Expects the execution of a transaction by the caller, with the exit code of the next
+// expected send.
+pub fn expect_executed_event(rt: &MockRuntime, txn_id: TxnID) {
+    let code = rt.expectations.borrow().expect_sends.front().map_or(ExitCode::OK, |s| s.exit_code);
+    rt.expect_emitted_event(executed_event(txn_id, *rt.caller.borrow(), code));
+}
+
+// Expects the caller's approval of a pending transaction, and the transaction's execution
+// if the approvals then meet the threshold.
+// A transaction already meeting the threshold is executed without recording the approval.
+pub fn expect_approval_events(rt: &MockRuntime, txn_id: TxnID) {
+    let st: State = rt.get_state();
+    let ptx =
+        PendingTxnMap::load(&rt.store, &st.pending_txs, PENDING_TXN_CONFIG, "pending").unwrap();
+    let Some(txn) = ptx.get(&txn_id).unwrap() else {
+        return;
+    };
+    let mut approvals = txn.approved.len() as u64;
+    if approvals < st.num_approvals_threshold {
+        approvals += 1;
+        rt.expect_emitted_event(approved_event(txn_id, *rt.caller.borrow(), approvals));
+    }
+    if approvals >= st.num_approvals_threshold {
+        expect_executed_event(rt, txn_id);
+    }
+}
+
+// Events emitted by an aborted call are discarded along with its state changes,
+// so expectations of events the call did not reach are dropped.
+pub fn discard_events_on_abort(rt: &MockRuntime, ret: &Result<Option<IpldBlock>, ActorError>) {
+    if ret.is_err() {
+        rt.expectations.borrow_mut().expect_emitted_events.clear();
+    }
+}
@@ -0,0 +1,65 @@
+// A namespace for helpers that build and emit payment channel events.
+
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::ActorID;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+
+/// Indicates a voucher has been redeemed, updating its lane to the voucher's nonce.
+/// The amount is the voucher's addition to the balance to send, net of amounts already redeemed.
+pub fn voucher_redeemed(
+    rt: &impl Runtime,
+    lane: u64,
+    nonce: u64,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("voucher-redeemed")
+            .field_indexed("lane", &lane)
+            .field("nonce", &nonce)
+            .field_bigint("amount", amount.atto())
+            .build()?,
+    )
+}
+
+/// Indicates a party has begun settling the channel, which may be collected from an epoch.
+/// The amount to send is the total redeemed at the time, which later vouchers may increase.
+pub fn channel_settling(
+    rt: &impl Runtime,
+    from: ActorID,
+    to: ActorID,
+    settling_at: ChainEpoch,
+    to_send: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("channel-settling")
+            .field_indexed("from", &from)
+            .field_indexed("to", &to)
+            .field("settling-at", &settling_at)
+            .field_bigint("to-send", to_send.atto())
+            .build()?,
+    )
+}
+
+/// Indicates a settled channel has been collected, paying the recipient and refunding
+/// the remaining balance to the owner.
+pub fn channel_collected(
+    rt: &impl Runtime,
+    from: ActorID,
+    to: ActorID,
+    paid: &TokenAmount,
+    refunded: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("channel-collected")
+            .field_indexed("from", &from)
+            .field_indexed("to", &to)
+            .field_bigint("paid", paid.atto())
+            .field_bigint("refunded", refunded.atto())
+            .build()?,
+    )
+}
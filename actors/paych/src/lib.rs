@@ -1,12 +1,15 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::BTreeSet;
+
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
     ActorContext, ActorDowncast, ActorError, Array, actor_dispatch, actor_error, deserialize_block,
     extract_send_result, resolve_to_actor_id,
 };
+use fvm_ipld_bitfield::BitField;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::CBOR;
 use fvm_shared::address::Address;
@@ -19,12 +22,13 @@ use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
 use num_derive::FromPrimitive;
 use num_traits::Zero;
 
-pub use self::state::{LaneState, Merge, State};
+pub use self::state::{LaneState, Merge, State, Watchtower};
 pub use self::types::*;
 
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+mod emit;
 pub mod ext;
 mod state;
 pub mod testing;
@@ -40,6 +44,12 @@ pub enum Method {
     UpdateChannelState = 2,
     Settle = 3,
     Collect = 4,
+    // Method numbers derived from FRC-0042 standards
+    PruneLanesExported = frc42_dispatch::method_hash!("PruneLanes"),
+    CloseLanesExported = frc42_dispatch::method_hash!("CloseLanes"),
+    UpdateChannelStateBatchExported = frc42_dispatch::method_hash!("UpdateChannelStateBatch"),
+    GetLaneStatesExported = frc42_dispatch::method_hash!("GetLaneStates"),
+    SetWatchtowerExported = frc42_dispatch::method_hash!("SetWatchtower"),
 }
 
 pub const ERR_CHANNEL_STATE_UPDATE_AFTER_SETTLED: ExitCode = ExitCode::new(32);
@@ -75,197 +85,107 @@ impl Actor {
     ) -> Result<(), ActorError> {
         let st: State = rt.state()?;
 
-        rt.validate_immediate_caller_is([st.from, st.to].iter())?;
-        let signer = if rt.message().caller() == st.from { st.to } else { st.from };
-        let sv = params.sv;
-
-        // Pull signature from signed voucher
-        let sig = &sv
-            .signature
-            .as_ref()
-            .ok_or_else(|| actor_error!(illegal_argument, "voucher has no signature"))?
-            .bytes;
-
-        if st.settling_at != 0 && rt.curr_epoch() >= st.settling_at {
-            return Err(ActorError::unchecked(
-                ERR_CHANNEL_STATE_UPDATE_AFTER_SETTLED,
-                "no vouchers can be processed after settling at epoch".to_string(),
-            ));
-        }
-
-        if params.secret.len() > MAX_SECRET_SIZE {
-            return Err(actor_error!(illegal_argument, "secret must be at most 256 bytes long"));
-        }
-
-        // Generate unsigned bytes
-        let sv_bz = sv.signing_bytes().map_err(|e| {
-            ActorError::serialization(format!("failed to serialized SignedVoucher: {}", e))
-        })?;
-
-        // Validate signature
-
-        if !extract_send_result(rt.send(
-            &signer,
-            ext::account::AUTHENTICATE_MESSAGE_METHOD,
-            IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
-                signature: sig.to_vec(),
-                message: sv_bz,
-            })?,
-            TokenAmount::zero(),
-            None,
-            SendFlags::READ_ONLY,
-        ))
-        .and_then(deserialize_block)
-        .context("proposal authentication failed")?
-        {
-            return Err(actor_error!(illegal_argument, "voucher sig authentication failed"));
-        }
+        let signer = validate_voucher_submitter(rt, &st)?;
+        validate_voucher(rt, &st, &signer, &params)?;
 
-        let pch_addr = rt.message().receiver();
-        let svpch_id = rt.resolve_address(&sv.channel_addr).ok_or_else(|| {
-            actor_error!(
-                illegal_argument,
-                "voucher payment channel address {} does not resolve to an ID address",
-                sv.channel_addr
-            )
+        let delta = rt.transaction(|st: &mut State, rt| {
+            let mut l_states = Array::load(&st.lane_states, rt.store()).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load lane states")
+            })?;
+            let delta = redeem_voucher(st, &mut l_states, &params.sv, &rt.current_balance())?;
+            st.lane_states = l_states.flush().map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save lanes")
+            })?;
+            Ok(delta)
         })?;
-        if pch_addr != Address::new_id(svpch_id) {
-            return Err(actor_error!(illegal_argument;
-                    "voucher payment channel address {} does not match receiver {}",
-                    svpch_id, pch_addr));
-        }
+        emit::voucher_redeemed(rt, params.sv.lane, params.sv.nonce, &delta)
+    }
 
-        if rt.curr_epoch() < sv.time_lock_min {
-            return Err(actor_error!(illegal_argument; "cannot use this voucher yet"));
-        }
+    /// Redeems a batch of vouchers, each for a different lane, in a single state update.
+    /// Every voucher is validated as by UpdateChannelState, and vouchers are applied in order.
+    /// If any voucher fails, none are redeemed.
+    /// Returns the amount each voucher added to the channel's balance to send.
+    pub fn update_channel_state_batch(
+        rt: &impl Runtime,
+        params: UpdateChannelStateBatchParams,
+    ) -> Result<UpdateChannelStateBatchReturn, ActorError> {
+        let st: State = rt.state()?;
 
-        if sv.time_lock_max != 0 && rt.curr_epoch() > sv.time_lock_max {
-            return Err(actor_error!(illegal_argument; "this voucher has expired"));
-        }
+        let signer = validate_voucher_submitter(rt, &st)?;
 
-        if sv.amount.is_negative() {
-            return Err(actor_error!(illegal_argument;
-                    "voucher amount must be non-negative, was {}", sv.amount));
+        if params.updates.is_empty() {
+            return Err(actor_error!(illegal_argument, "no vouchers to redeem"));
         }
-
-        if !sv.secret_pre_image.is_empty() {
-            let hashed_secret: &[u8] = &rt.hash_blake2b(&params.secret);
-            if hashed_secret != sv.secret_pre_image.as_slice() {
-                return Err(actor_error!(illegal_argument; "incorrect secret"));
+        let mut lanes = BTreeSet::new();
+        for update in &params.updates {
+            if !lanes.insert(update.sv.lane) {
+                return Err(actor_error!(illegal_argument;
+                    "batch contains more than one voucher for lane {}", update.sv.lane));
             }
         }
-
-        if let Some(extra) = &sv.extra {
-            extract_send_result(rt.send_simple(
-                &extra.actor,
-                extra.method,
-                Some(IpldBlock { codec: CBOR, data: extra.data.to_vec() }),
-                TokenAmount::zero(),
-            ))
-            .map_err(|e| e.wrap("spend voucher verification failed"))?;
+        for update in &params.updates {
+            validate_voucher(rt, &st, &signer, update)?;
         }
 
-        rt.transaction(|st: &mut State, rt| {
+        let ret = rt.transaction(|st: &mut State, rt| {
             let mut l_states = Array::load(&st.lane_states, rt.store()).map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load lane states")
             })?;
-
-            // Find the voucher lane, create and insert it in sorted order if necessary.
-            let lane_id = sv.lane;
-            let lane_state = find_lane(&l_states, lane_id)?;
-
-            let mut lane_state = if let Some(state) = lane_state {
-                if state.nonce >= sv.nonce {
-                    return Err(actor_error!(illegal_argument;
-                        "voucher has an outdated nonce, existing: {}, voucher: {}, cannot redeem",
-                        state.nonce, sv.nonce));
-                }
-                state.clone()
-            } else {
-                LaneState::default()
-            };
-
-            // The next section actually calculates the payment amounts to update
-            // the payment channel state
-            // 1. (optional) sum already redeemed value of all merging lanes
-            let mut redeemed_from_others = TokenAmount::zero();
-            for merge in sv.merges {
-                if merge.lane == sv.lane {
-                    return Err(actor_error!(illegal_argument;
-                        "voucher cannot merge lanes into it's own lane"));
-                }
-                let mut other_ls = find_lane(&l_states, merge.lane)?
-                    .ok_or_else(|| {
-                        actor_error!(illegal_argument;
-                        "voucher specifies invalid merge lane {}", merge.lane)
-                    })?
-                    .clone();
-
-                if other_ls.nonce >= merge.nonce {
-                    return Err(actor_error!(illegal_argument;
-                            "merged lane in voucher has outdated nonce, cannot redeem"));
-                }
-
-                redeemed_from_others += &other_ls.redeemed;
-                other_ls.nonce = merge.nonce;
-                l_states.set(merge.lane, other_ls).map_err(|e| {
-                    e.downcast_default(
-                        ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to store lane {}", merge.lane),
-                    )
-                })?;
+            let balance = rt.current_balance();
+            let mut redeemed = Vec::with_capacity(params.updates.len());
+            for update in &params.updates {
+                let delta = redeem_voucher(st, &mut l_states, &update.sv, &balance)?;
+                redeemed.push(VoucherRedemption { lane: update.sv.lane, delta });
             }
+            st.lane_states = l_states.flush().map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save lanes")
+            })?;
+            Ok(UpdateChannelStateBatchReturn { redeemed })
+        })?;
+        for (update, redemption) in params.updates.iter().zip(&ret.redeemed) {
+            emit::voucher_redeemed(rt, update.sv.lane, update.sv.nonce, &redemption.delta)?;
+        }
+        Ok(ret)
+    }
 
-            // 2. To prevent double counting, remove already redeemed amounts (from
-            // voucher or other lanes) from the voucher amount
-            lane_state.nonce = sv.nonce;
-            let balance_delta = &sv.amount - (redeemed_from_others + &lane_state.redeemed);
-
-            // 3. set new redeemed value for merged-into lane
-            lane_state.redeemed = sv.amount;
-
-            // 4. check operation validity
-            let new_send_balance = balance_delta + &st.to_send;
-
-            if new_send_balance < TokenAmount::zero() {
-                return Err(actor_error!(illegal_argument;
-                    "voucher would leave channel balance negative"));
-            }
+    /// Authorizes a watchtower to submit vouchers on behalf of the calling party, or revokes
+    /// an authorization the caller previously granted.
+    /// A watchtower's vouchers must be signed by the caller's counterparty, as the caller's would.
+    pub fn set_watchtower(
+        rt: &impl Runtime,
+        params: SetWatchtowerParams,
+    ) -> Result<(), ActorError> {
+        let st: State = rt.state()?;
+        rt.validate_immediate_caller_is([st.from, st.to].iter())?;
+        let principal = rt.message().caller();
+        let watchtower = resolve_to_actor_id(rt, &params.watchtower, true).map(Address::new_id)?;
+        if watchtower == st.from || watchtower == st.to {
+            return Err(actor_error!(illegal_argument;
+                "channel party {} cannot be a watchtower", watchtower));
+        }
 
-            if new_send_balance > rt.current_balance() {
-                return Err(actor_error!(illegal_argument;
-                    "not enough funds in channel to cover voucher"));
+        rt.transaction(|st: &mut State, _| {
+            if !params.authorized {
+                st.watchtowers.retain(|w| w.address != watchtower || w.principal != principal);
+                return Ok(());
             }
-
-            // 5. add new redemption ToSend
-            st.to_send = new_send_balance;
-
-            // update channel settlingAt and MinSettleHeight if delayed by voucher
-            if sv.min_settle_height != 0 {
-                if st.settling_at != 0 && st.settling_at < sv.min_settle_height {
-                    st.settling_at = sv.min_settle_height;
-                }
-                if st.min_settle_height < sv.min_settle_height {
-                    st.min_settle_height = sv.min_settle_height;
+            match st.watchtowers.iter().find(|w| w.address == watchtower).map(|w| w.principal) {
+                Some(p) if p != principal => Err(actor_error!(forbidden;
+                    "watchtower {} is authorized by the counterparty", watchtower)),
+                Some(_) => Ok(()),
+                None if st.watchtowers.len() >= MAX_WATCHTOWERS => Err(actor_error!(
+                    illegal_argument;
+                    "channel already has the maximum of {} watchtowers", MAX_WATCHTOWERS)),
+                None => {
+                    st.watchtowers.push(Watchtower { address: watchtower, principal });
+                    Ok(())
                 }
             }
-
-            l_states.set(lane_id, lane_state).map_err(|e| {
-                e.downcast_default(
-                    ExitCode::USR_ILLEGAL_STATE,
-                    format!("failed to store lane {}", lane_id),
-                )
-            })?;
-
-            st.lane_states = l_states.flush().map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save lanes")
-            })?;
-            Ok(())
         })
     }
 
     pub fn settle(rt: &impl Runtime) -> Result<(), ActorError> {
-        rt.transaction(|st: &mut State, rt| {
+        let st = rt.transaction(|st: &mut State, rt| {
             rt.validate_immediate_caller_is([st.from, st.to].iter())?;
 
             if st.settling_at != 0 {
@@ -277,8 +197,15 @@ impl Actor {
                 st.settling_at = st.min_settle_height;
             }
 
-            Ok(())
-        })
+            Ok(st.clone())
+        })?;
+        emit::channel_settling(
+            rt,
+            st.from.id().unwrap(),
+            st.to.id().unwrap(),
+            st.settling_at,
+            &st.to_send,
+        )
     }
 
     pub fn collect(rt: &impl Runtime) -> Result<(), ActorError> {
@@ -290,17 +217,343 @@ impl Actor {
         }
 
         // send ToSend to `to`
-        extract_send_result(rt.send_simple(&st.to, METHOD_SEND, None, st.to_send))
+        extract_send_result(rt.send_simple(&st.to, METHOD_SEND, None, st.to_send.clone()))
             .map_err(|e| e.wrap("Failed to send funds to `to` address"))?;
 
         // return remaining balance back to the "from" address.
-        extract_send_result(rt.send_simple(&st.from, METHOD_SEND, None, rt.current_balance()))
+        let refund = rt.current_balance();
+        extract_send_result(rt.send_simple(&st.from, METHOD_SEND, None, refund.clone()))
             .map_err(|e| e.wrap("Failed to send funds to `from` address"))?;
 
+        emit::channel_collected(
+            rt,
+            st.from.id().unwrap(),
+            st.to.id().unwrap(),
+            &st.to_send,
+            &refund,
+        )?;
         rt.delete_actor()?;
 
         Ok(())
     }
+
+    /// Closes lanes so that no further voucher can be redeemed on them, either directly or by
+    /// merging. Closed lanes may later be pruned by either party.
+    pub fn close_lanes(rt: &impl Runtime, params: CloseLanesParams) -> Result<(), ActorError> {
+        rt.transaction(|st: &mut State, rt| {
+            rt.validate_immediate_caller_is(std::iter::once(&st.to))?;
+            validate_lanes(&params.lanes)?;
+            st.closed_lanes |= &params.lanes;
+            Ok(())
+        })
+    }
+
+    /// Removes lanes from state, adding their redeemed amounts to the channel's pruned total.
+    /// The payee may prune any lanes, closing them first.
+    /// The payer may only prune lanes which the payee has closed.
+    pub fn prune_lanes(
+        rt: &impl Runtime,
+        params: PruneLanesParams,
+    ) -> Result<PruneLanesReturn, ActorError> {
+        rt.transaction(|st: &mut State, rt| {
+            rt.validate_immediate_caller_is([st.from, st.to].iter())?;
+            validate_lanes(&params.lanes)?;
+            if params.lanes.len() > MAX_PRUNED_LANES {
+                return Err(actor_error!(illegal_argument;
+                    "cannot prune more than {} lanes", MAX_PRUNED_LANES));
+            }
+
+            if rt.message().caller() == st.to {
+                st.closed_lanes |= &params.lanes;
+            } else if !st.closed_lanes.contains_all(&params.lanes) {
+                return Err(actor_error!(forbidden; "payer can only prune closed lanes"));
+            }
+
+            let mut l_states =
+                Array::<LaneState, _>::load(&st.lane_states, rt.store()).map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load lane states")
+                })?;
+
+            let mut pruned = 0;
+            let mut redeemed = TokenAmount::zero();
+            for lane_id in params.lanes.iter() {
+                let deleted = l_states.delete(lane_id).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to delete lane {}", lane_id),
+                    )
+                })?;
+                if let Some(lane) = deleted {
+                    pruned += 1;
+                    redeemed += &lane.redeemed;
+                }
+            }
+
+            st.lane_states = l_states.flush().map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save lanes")
+            })?;
+            st.pruned_redeemed += &redeemed;
+
+            Ok(PruneLanesReturn { pruned, redeemed })
+        })
+    }
+
+    /// Returns the nonce and redeemed amount of the channel's lanes in ascending order of lane ID,
+    /// starting from a cursor. Pruned lanes are not listed.
+    pub fn get_lane_states(
+        rt: &impl Runtime,
+        params: GetLaneStatesParams,
+    ) -> Result<GetLaneStatesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let st: State = rt.state()?;
+        let l_states = Array::<LaneState, _>::load(&st.lane_states, rt.store()).map_err(|e| {
+            e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load lane states")
+        })?;
+
+        let mut lanes = Vec::new();
+        let mut next_cursor = None;
+        l_states
+            .for_each_while_ranged(params.cursor, None, |lane, state| {
+                if lanes.len() as u64 == params.limit {
+                    next_cursor = Some(lane);
+                    return Ok(false);
+                }
+                lanes.push(LaneStateSummary {
+                    lane,
+                    nonce: state.nonce,
+                    redeemed: state.redeemed.clone(),
+                });
+                Ok(true)
+            })
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to iterate lanes")
+            })?;
+        Ok(GetLaneStatesReturn { lanes, next_cursor })
+    }
+}
+
+/// Validates that the caller may submit vouchers, as a party to the channel or a watchtower
+/// authorized by one, and returns the counterparty whose signature the vouchers must carry.
+fn validate_voucher_submitter(rt: &impl Runtime, st: &State) -> Result<Address, ActorError> {
+    let submitters: Vec<Address> =
+        [st.from, st.to].into_iter().chain(st.watchtowers.iter().map(|w| w.address)).collect();
+    rt.validate_immediate_caller_is(submitters.iter())?;
+    let caller = rt.message().caller();
+    let principal =
+        st.watchtowers.iter().find(|w| w.address == caller).map_or(caller, |w| w.principal);
+    Ok(if principal == st.from { st.to } else { st.from })
+}
+
+/// Checks a voucher submitted with an update to the channel state, including its signature
+/// by the other party to the channel and any extra verification it specifies.
+fn validate_voucher(
+    rt: &impl Runtime,
+    st: &State,
+    signer: &Address,
+    params: &UpdateChannelStateParams,
+) -> Result<(), ActorError> {
+    let sv = &params.sv;
+
+    // Pull signature from signed voucher
+    let sig = &sv
+        .signature
+        .as_ref()
+        .ok_or_else(|| actor_error!(illegal_argument, "voucher has no signature"))?
+        .bytes;
+
+    if st.settling_at != 0 && rt.curr_epoch() >= st.settling_at {
+        return Err(ActorError::unchecked(
+            ERR_CHANNEL_STATE_UPDATE_AFTER_SETTLED,
+            "no vouchers can be processed after settling at epoch".to_string(),
+        ));
+    }
+
+    if params.secret.len() > MAX_SECRET_SIZE {
+        return Err(actor_error!(illegal_argument, "secret must be at most 256 bytes long"));
+    }
+
+    // Generate unsigned bytes
+    let sv_bz = sv.signing_bytes().map_err(|e| {
+        ActorError::serialization(format!("failed to serialized SignedVoucher: {}", e))
+    })?;
+
+    // Validate signature
+
+    if !extract_send_result(rt.send(
+        signer,
+        ext::account::AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
+            signature: sig.to_vec(),
+            message: sv_bz,
+        })?,
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+    ))
+    .and_then(deserialize_block)
+    .context("proposal authentication failed")?
+    {
+        return Err(actor_error!(illegal_argument, "voucher sig authentication failed"));
+    }
+
+    let pch_addr = rt.message().receiver();
+    let svpch_id = rt.resolve_address(&sv.channel_addr).ok_or_else(|| {
+        actor_error!(
+            illegal_argument,
+            "voucher payment channel address {} does not resolve to an ID address",
+            sv.channel_addr
+        )
+    })?;
+    if pch_addr != Address::new_id(svpch_id) {
+        return Err(actor_error!(illegal_argument;
+                "voucher payment channel address {} does not match receiver {}",
+                svpch_id, pch_addr));
+    }
+
+    if rt.curr_epoch() < sv.time_lock_min {
+        return Err(actor_error!(illegal_argument; "cannot use this voucher yet"));
+    }
+
+    if sv.time_lock_max != 0 && rt.curr_epoch() > sv.time_lock_max {
+        return Err(actor_error!(illegal_argument; "this voucher has expired"));
+    }
+
+    if sv.amount.is_negative() {
+        return Err(actor_error!(illegal_argument;
+                "voucher amount must be non-negative, was {}", sv.amount));
+    }
+
+    if !sv.secret_pre_image.is_empty() {
+        let hashed_secret: &[u8] = &rt.hash_blake2b(&params.secret);
+        if hashed_secret != sv.secret_pre_image.as_slice() {
+            return Err(actor_error!(illegal_argument; "incorrect secret"));
+        }
+    }
+
+    if let Some(extra) = &sv.extra {
+        extract_send_result(rt.send_simple(
+            &extra.actor,
+            extra.method,
+            Some(IpldBlock { codec: CBOR, data: extra.data.to_vec() }),
+            TokenAmount::zero(),
+        ))
+        .map_err(|e| e.wrap("spend voucher verification failed"))?;
+    }
+    Ok(())
+}
+
+/// Applies a validated voucher to the lane states, adding the amount it newly redeems to the
+/// channel's balance to send, which may not exceed the channel's balance.
+/// Returns the change in the balance to send.
+fn redeem_voucher<BS: Blockstore>(
+    st: &mut State,
+    l_states: &mut Array<LaneState, BS>,
+    sv: &SignedVoucher,
+    balance: &TokenAmount,
+) -> Result<TokenAmount, ActorError> {
+    // Find the voucher lane, create and insert it in sorted order if necessary.
+    let lane_id = sv.lane;
+    if st.closed_lanes.get(lane_id) {
+        return Err(actor_error!(illegal_argument;
+            "voucher lane {} has been closed", lane_id));
+    }
+    let lane_state = find_lane(l_states, lane_id)?;
+
+    let mut lane_state = if let Some(state) = lane_state {
+        if state.nonce >= sv.nonce {
+            return Err(actor_error!(illegal_argument;
+                "voucher has an outdated nonce, existing: {}, voucher: {}, cannot redeem",
+                state.nonce, sv.nonce));
+        }
+        state.clone()
+    } else {
+        LaneState::default()
+    };
+
+    // The next section actually calculates the payment amounts to update
+    // the payment channel state
+    // 1. (optional) sum already redeemed value of all merging lanes
+    let mut redeemed_from_others = TokenAmount::zero();
+    for merge in &sv.merges {
+        if merge.lane == sv.lane {
+            return Err(actor_error!(illegal_argument;
+                "voucher cannot merge lanes into it's own lane"));
+        }
+        if st.closed_lanes.get(merge.lane) {
+            return Err(actor_error!(illegal_argument;
+                "voucher merges closed lane {}", merge.lane));
+        }
+        let mut other_ls = find_lane(l_states, merge.lane)?
+            .ok_or_else(|| {
+                actor_error!(illegal_argument;
+                "voucher specifies invalid merge lane {}", merge.lane)
+            })?
+            .clone();
+
+        if other_ls.nonce >= merge.nonce {
+            return Err(actor_error!(illegal_argument;
+                    "merged lane in voucher has outdated nonce, cannot redeem"));
+        }
+
+        redeemed_from_others += &other_ls.redeemed;
+        other_ls.nonce = merge.nonce;
+        l_states.set(merge.lane, other_ls).map_err(|e| {
+            e.downcast_default(
+                ExitCode::USR_ILLEGAL_STATE,
+                format!("failed to store lane {}", merge.lane),
+            )
+        })?;
+    }
+
+    // 2. To prevent double counting, remove already redeemed amounts (from
+    // voucher or other lanes) from the voucher amount
+    lane_state.nonce = sv.nonce;
+    let balance_delta = &sv.amount - (redeemed_from_others + &lane_state.redeemed);
+
+    // 3. set new redeemed value for merged-into lane
+    lane_state.redeemed = sv.amount.clone();
+
+    // 4. check operation validity
+    let new_send_balance = &balance_delta + &st.to_send;
+
+    if new_send_balance < TokenAmount::zero() {
+        return Err(actor_error!(illegal_argument;
+            "voucher would leave channel balance negative"));
+    }
+
+    if &new_send_balance > balance {
+        return Err(actor_error!(illegal_argument;
+            "not enough funds in channel to cover voucher"));
+    }
+
+    // 5. add new redemption ToSend
+    st.to_send = new_send_balance;
+
+    // update channel settlingAt and MinSettleHeight if delayed by voucher
+    if sv.min_settle_height != 0 {
+        if st.settling_at != 0 && st.settling_at < sv.min_settle_height {
+            st.settling_at = sv.min_settle_height;
+        }
+        if st.min_settle_height < sv.min_settle_height {
+            st.min_settle_height = sv.min_settle_height;
+        }
+    }
+
+    l_states.set(lane_id, lane_state).map_err(|e| {
+        e.downcast_default(ExitCode::USR_ILLEGAL_STATE, format!("failed to store lane {}", lane_id))
+    })?;
+
+    Ok(balance_delta)
+}
+
+fn validate_lanes(lanes: &BitField) -> Result<(), ActorError> {
+    if lanes.last().is_some_and(|last| last > MAX_LANE) {
+        return Err(actor_error!(illegal_argument; "maximum lane ID is 2^63-1"));
+    }
+    Ok(())
 }
 
 #[inline]
@@ -332,5 +585,10 @@ impl ActorCode for Actor {
         UpdateChannelState => update_channel_state,
         Settle => settle,
         Collect => collect,
+        PruneLanesExported => prune_lanes,
+        CloseLanesExported => close_lanes,
+        UpdateChannelStateBatchExported => update_channel_state_batch,
+        GetLaneStatesExported => get_lane_states,
+        SetWatchtowerExported => set_watchtower,
     }
 }
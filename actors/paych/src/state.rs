@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use cid::Cid;
+use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 
@@ -25,6 +26,16 @@ pub struct State {
     pub min_settle_height: ChainEpoch,
     /// Collections of lane states for the channel, maintained in ID order.
     pub lane_states: Cid, // AMT<LaneState>
+    /// Lanes which the payee has closed. Vouchers for closed lanes can't be redeemed,
+    /// and closed lanes may be pruned from `lane_states` by either party.
+    #[serde(default)]
+    pub closed_lanes: BitField,
+    /// Total amount redeemed by pruned lanes.
+    #[serde(default)]
+    pub pruned_redeemed: TokenAmount,
+    /// Addresses authorized by a party to submit vouchers on its behalf.
+    #[serde(default)]
+    pub watchtowers: Vec<Watchtower>,
 }
 
 impl State {
@@ -36,10 +47,22 @@ impl State {
             settling_at: 0,
             min_settle_height: 0,
             lane_states: empty_arr_cid,
+            closed_lanes: BitField::new(),
+            pruned_redeemed: TokenAmount::default(),
+            watchtowers: Vec::new(),
         }
     }
 }
 
+/// An address authorized to submit vouchers to the channel on behalf of one of its parties,
+/// such as a service redeeming vouchers while the recipient is offline.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct Watchtower {
+    pub address: Address,
+    /// The party, `from` or `to`, which authorized the watchtower.
+    pub principal: Address,
+}
+
 /// The Lane state tracks the latest (highest) voucher nonce used to merge the lane
 /// as well as the amount it has already redeemed.
 #[derive(Default, Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
@@ -5,9 +5,10 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_shared::{address::Protocol, econ::TokenAmount};
 use num_traits::Zero;
 
-use crate::{LaneState, State};
+use crate::{LaneState, MAX_WATCHTOWERS, State};
 
 pub struct StateSummary {
+    /// Total redeemed across all lanes, including those pruned.
     pub redeemed: TokenAmount,
 }
 
@@ -36,6 +37,35 @@ pub fn check_state_invariants<BS: Blockstore>(
         ),
     );
 
+    acc.require(
+        state.watchtowers.len() <= MAX_WATCHTOWERS,
+        format!("channel has {} watchtowers", state.watchtowers.len()),
+    );
+    for (i, w) in state.watchtowers.iter().enumerate() {
+        acc.require(
+            w.address.protocol() == Protocol::ID,
+            format!("watchtower address is not ID address {}", w.address),
+        );
+        acc.require(
+            w.address != state.from && w.address != state.to,
+            format!("watchtower {} is a channel party", w.address),
+        );
+        acc.require(
+            w.principal == state.from || w.principal == state.to,
+            format!("watchtower {} principal {} is not a channel party", w.address, w.principal),
+        );
+        acc.require(
+            state.watchtowers[..i].iter().all(|o| o.address != w.address),
+            format!("watchtower {} is authorized more than once", w.address),
+        );
+    }
+
+    acc.require(
+        !state.pruned_redeemed.is_negative(),
+        format!("pruned lanes redeemed is negative {}", state.pruned_redeemed),
+    );
+    redeemed += &state.pruned_redeemed;
+
     match Amt::<LaneState, _>::load(&state.lane_states, store) {
         Ok(lanes) => {
             let ret = lanes.for_each(|i, lane| {
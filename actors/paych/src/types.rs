@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use fil_actors_runtime::network::EPOCHS_IN_HOUR;
+use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::tuple::*;
 use fvm_ipld_encoding::{Error, RawBytes, strict_bytes, to_vec};
 use fvm_shared::MethodNum;
@@ -22,6 +23,12 @@ pub const MAX_SECRET_SIZE: usize = 256;
 
 pub const LANE_STATES_AMT_BITWIDTH: u32 = 3;
 
+/// Maximum number of watchtowers authorized for a channel.
+pub const MAX_WATCHTOWERS: usize = 8;
+
+/// Maximum number of lanes which may be pruned in a single call.
+pub const MAX_PRUNED_LANES: u64 = 1024;
+
 /// Constructor parameters for payment channel actor
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
@@ -126,3 +133,76 @@ impl From<SignedVoucher> for UpdateChannelStateParams {
         UpdateChannelStateParams { secret: vec![], sv }
     }
 }
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct UpdateChannelStateBatchParams {
+    /// Vouchers to redeem, each for a different lane, applied in order.
+    pub updates: Vec<UpdateChannelStateParams>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct UpdateChannelStateBatchReturn {
+    /// The result of each voucher, in the order submitted.
+    pub redeemed: Vec<VoucherRedemption>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct VoucherRedemption {
+    /// The lane of the voucher.
+    pub lane: u64,
+    /// The amount the voucher added to the channel's balance to send.
+    pub delta: TokenAmount,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct CloseLanesParams {
+    /// Lanes to close.
+    pub lanes: BitField,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct PruneLanesParams {
+    /// Lanes to remove from state.
+    pub lanes: BitField,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct PruneLanesReturn {
+    /// Number of lanes removed.
+    pub pruned: u64,
+    /// Total amount redeemed by the removed lanes.
+    pub redeemed: TokenAmount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetLaneStatesParams {
+    /// Lane from which to continue listing, as returned by a previous call.
+    /// If absent, listing starts from the lowest lane.
+    pub cursor: Option<u64>,
+    /// Maximum number of lanes to return. Must be positive.
+    pub limit: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct LaneStateSummary {
+    pub lane: u64,
+    /// Nonce of the latest voucher to update or merge the lane.
+    pub nonce: u64,
+    /// Total amount redeemed by the lane.
+    pub redeemed: TokenAmount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetLaneStatesReturn {
+    pub lanes: Vec<LaneStateSummary>,
+    /// Cursor from which to continue listing, absent if there are no more lanes.
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SetWatchtowerParams {
+    /// Address to submit vouchers on the caller's behalf.
+    pub watchtower: Address,
+    /// Whether to authorize the watchtower, or revoke an authorization.
+    pub authorized: bool,
+}
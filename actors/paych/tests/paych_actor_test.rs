@@ -9,15 +9,19 @@ use derive_builder::Builder;
 use fil_actor_paych::ext::account::{AUTHENTICATE_MESSAGE_METHOD, AuthenticateMessageParams};
 use fil_actor_paych::testing::check_state_invariants;
 use fil_actor_paych::{
-    Actor as PaychActor, ConstructorParams, LaneState, MAX_LANE, Merge, Method, ModVerifyParams,
-    SETTLE_DELAY, SignedVoucher, State as PState, UpdateChannelStateParams,
+    Actor as PaychActor, CloseLanesParams, ConstructorParams, GetLaneStatesParams,
+    GetLaneStatesReturn, LaneState, LaneStateSummary, MAX_LANE, Merge, Method, ModVerifyParams,
+    PruneLanesParams, PruneLanesReturn, SETTLE_DELAY, SetWatchtowerParams, SignedVoucher,
+    State as PState, UpdateChannelStateBatchParams, UpdateChannelStateBatchReturn,
+    UpdateChannelStateParams, VoucherRedemption, Watchtower,
 };
 
-use fil_actors_runtime::INIT_ACTOR_ADDR;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{EventBuilder, INIT_ACTOR_ADDR};
 use fvm_ipld_amt::Amt;
+use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::RawBytes;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::METHOD_CONSTRUCTOR;
@@ -75,6 +79,55 @@ fn check_state(rt: &MockRuntime) {
     acc.assert_empty();
 }
 
+// Expects the event for redeeming a voucher, whose amount is net of that already redeemed
+// by its lane and any lanes it merges, as of the current state.
+fn expect_voucher_redeemed(rt: &MockRuntime, sv: &SignedVoucher) {
+    let st: PState = rt.get_state();
+    let lanes: Amt<LaneState, _> = Amt::load(&st.lane_states, &rt.store).unwrap();
+    let mut redeemed =
+        lanes.get(sv.lane).unwrap().map(|ls| ls.redeemed.clone()).unwrap_or_default();
+    for merge in &sv.merges {
+        redeemed += &lanes.get(merge.lane).unwrap().unwrap().redeemed;
+    }
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("voucher-redeemed")
+            .field_indexed("lane", &sv.lane)
+            .field("nonce", &sv.nonce)
+            .field_bigint("amount", (&sv.amount - redeemed).atto())
+            .build()
+            .unwrap(),
+    );
+}
+
+fn expect_channel_settling(rt: &MockRuntime, settling_at: ChainEpoch) {
+    let st: PState = rt.get_state();
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("channel-settling")
+            .field_indexed("from", &st.from.id().unwrap())
+            .field_indexed("to", &st.to.id().unwrap())
+            .field("settling-at", &settling_at)
+            .field_bigint("to-send", st.to_send.atto())
+            .build()
+            .unwrap(),
+    );
+}
+
+fn expect_channel_collected(rt: &MockRuntime, paid: &TokenAmount, refunded: &TokenAmount) {
+    let st: PState = rt.get_state();
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("channel-collected")
+            .field_indexed("from", &st.from.id().unwrap())
+            .field_indexed("to", &st.to.id().unwrap())
+            .field_bigint("paid", paid.atto())
+            .field_bigint("refunded", refunded.atto())
+            .build()
+            .unwrap(),
+    );
+}
+
 mod paych_constructor {
     use fil_actors_runtime::runtime::builtins::Type;
     use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
@@ -402,6 +455,7 @@ mod create_lane_tests {
             }
 
             if test_case.exp_exit_code.is_success() {
+                expect_voucher_redeemed(&rt, &sv);
                 call(
                     &rt,
                     Method::UpdateChannelState as u64,
@@ -447,6 +501,7 @@ mod update_channel_state_redeem {
         let payer_addr = Address::new_id(PAYER_ID);
 
         expect_authenticate_message(&rt, payer_addr, sv.clone(), ExitCode::OK);
+        expect_voucher_redeemed(&rt, &sv);
 
         call(
             &rt,
@@ -463,6 +518,8 @@ mod update_channel_state_redeem {
             settling_at: state.settling_at,
             min_settle_height: state.min_settle_height,
             lane_states: construct_lane_state_amt(&rt, vec![exp_ls]),
+            closed_lanes: BitField::new(),
+            pruned_redeemed: TokenAmount::zero(),
         };
         verify_state(&rt, Some(1), exp_state);
     }
@@ -485,6 +542,7 @@ mod update_channel_state_redeem {
         let payer_addr = Address::new_id(PAYER_ID);
 
         expect_authenticate_message(&rt, payer_addr, sv.clone(), ExitCode::OK);
+        expect_voucher_redeemed(&rt, &sv);
 
         call(
             &rt,
@@ -571,6 +629,7 @@ mod merge_tests {
         sv.merges = vec![Merge { lane: 1, nonce: merge_nonce }];
         let payee_addr = Address::new_id(PAYEE_ID);
         expect_authenticate_message(&rt, payee_addr, sv.clone(), ExitCode::OK);
+        expect_voucher_redeemed(&rt, &sv);
 
         call(
             &rt,
@@ -707,6 +766,7 @@ mod update_channel_state_extra {
     #[test]
     fn extra_call_succeed() {
         let (rt, sv) = construct_runtime(ExitCode::OK);
+        expect_voucher_redeemed(&rt, &sv);
         call(
             &rt,
             Method::UpdateChannelState as u64,
@@ -737,6 +797,7 @@ fn update_channel_settling() {
     let state: PState = rt.get_state();
     rt.expect_validate_caller_addr(vec![state.from, state.to]);
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+    expect_channel_settling(&rt, SETTLE_DELAY + 10);
     call(&rt, Method::Settle as u64, None);
 
     let exp_settling_at = SETTLE_DELAY + 10;
@@ -770,6 +831,7 @@ fn update_channel_settling() {
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
 
         expect_authenticate_message(&rt, state.to, ucp.sv.clone(), ExitCode::OK);
+        expect_voucher_redeemed(&rt, &ucp.sv);
 
         call(&rt, Method::UpdateChannelState as u64, IpldBlock::serialize_cbor(&ucp).unwrap());
         let new_state: PState = rt.get_state();
@@ -792,6 +854,7 @@ mod secret_preimage {
         let ucp = UpdateChannelStateParams::from(sv.clone());
 
         expect_authenticate_message(&rt, state.to, sv, ExitCode::OK);
+        expect_voucher_redeemed(&rt, &ucp.sv);
 
         call(&rt, Method::UpdateChannelState as u64, IpldBlock::serialize_cbor(&ucp).unwrap());
 
@@ -826,6 +889,420 @@ mod secret_preimage {
     }
 }
 
+mod prune_lanes {
+    use super::*;
+
+    fn prune_lanes(rt: &MockRuntime, caller: Address, lanes: &[u64]) -> PruneLanesReturn {
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        let params =
+            PruneLanesParams { lanes: BitField::try_from_bits(lanes.iter().copied()).unwrap() };
+        let ret = call(
+            rt,
+            Method::PruneLanesExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .deserialize()
+        .unwrap();
+        rt.verify();
+        ret
+    }
+
+    fn close_lanes(rt: &MockRuntime, lanes: &[u64]) {
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+        rt.expect_validate_caller_addr(vec![state.to]);
+        let params =
+            CloseLanesParams { lanes: BitField::try_from_bits(lanes.iter().copied()).unwrap() };
+        call(rt, Method::CloseLanesExported as u64, IpldBlock::serialize_cbor(&params).unwrap());
+        rt.verify();
+    }
+
+    fn redeemed(rt: &MockRuntime) -> TokenAmount {
+        let (summary, acc) = check_state_invariants(&rt.get_state(), rt.store(), &rt.get_balance());
+        acc.assert_empty();
+        summary.redeemed
+    }
+
+    #[test]
+    fn payee_prunes_lanes_conserving_redeemed() {
+        let (rt, _) = require_create_channel_with_lanes(3);
+        let before: PState = rt.get_state();
+        let redeemed_before = redeemed(&rt);
+
+        let ret = prune_lanes(&rt, Address::new_id(PAYEE_ID), &[0, 2]);
+        assert_eq!(2, ret.pruned);
+        // Lanes 0 and 2 redeemed 1 and 3 respectively.
+        assert_eq!(TokenAmount::from_atto(4), ret.redeemed);
+
+        let state: PState = rt.get_state();
+        assert_eq!(vec![0, 2], state.closed_lanes.iter().collect::<Vec<_>>());
+        assert_eq!(ret.redeemed, state.pruned_redeemed);
+        assert_eq!(before.to_send, state.to_send);
+        assert_lane_states_length(&rt, &state.lane_states, 1);
+        assert_eq!(redeemed_before, redeemed(&rt));
+
+        // Pruning lanes again removes nothing.
+        let ret = prune_lanes(&rt, Address::new_id(PAYEE_ID), &[0, 2]);
+        assert_eq!(0, ret.pruned);
+        assert_eq!(TokenAmount::zero(), ret.redeemed);
+        assert_eq!(redeemed_before, redeemed(&rt));
+    }
+
+    #[test]
+    fn pruned_lane_cannot_be_redeemed() {
+        let (rt, mut sv) = require_create_channel_with_lanes(2);
+        prune_lanes(&rt, Address::new_id(PAYEE_ID), &[0]);
+
+        // A fresh voucher for the pruned lane would otherwise pay out its redeemed amount again.
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        sv.lane = 0;
+        sv.nonce = 100;
+        expect_authenticate_message(&rt, state.from, sv.clone(), ExitCode::OK);
+        expect_abort(
+            &rt,
+            Method::UpdateChannelState as u64,
+            IpldBlock::serialize_cbor(&UpdateChannelStateParams::from(sv)).unwrap(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+        check_state(&rt);
+    }
+
+    #[test]
+    fn closed_lane_cannot_be_merged() {
+        let (rt, mut sv) = require_create_channel_with_lanes(2);
+        close_lanes(&rt, &[0]);
+
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        sv.lane = 1;
+        sv.nonce = 100;
+        sv.amount = TokenAmount::from_atto(10);
+        sv.merges = vec![Merge { lane: 0, nonce: 100 }];
+        expect_authenticate_message(&rt, state.from, sv.clone(), ExitCode::OK);
+        expect_abort(
+            &rt,
+            Method::UpdateChannelState as u64,
+            IpldBlock::serialize_cbor(&UpdateChannelStateParams::from(sv)).unwrap(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+        check_state(&rt);
+    }
+
+    #[test]
+    fn payer_prunes_only_closed_lanes() {
+        let (rt, _) = require_create_channel_with_lanes(3);
+        let payer = Address::new_id(PAYER_ID);
+        let state: PState = rt.get_state();
+
+        // Lane 0 is open, so the payer cannot prune it.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, payer);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::PruneLanesExported as u64,
+            IpldBlock::serialize_cbor(&PruneLanesParams {
+                lanes: BitField::try_from_bits([0]).unwrap(),
+            })
+            .unwrap(),
+            ExitCode::USR_FORBIDDEN,
+        );
+        rt.verify();
+
+        // Only the payee can close lanes.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, payer);
+        rt.expect_validate_caller_addr(vec![state.to]);
+        expect_abort(
+            &rt,
+            Method::CloseLanesExported as u64,
+            IpldBlock::serialize_cbor(&CloseLanesParams {
+                lanes: BitField::try_from_bits([0]).unwrap(),
+            })
+            .unwrap(),
+            ExitCode::USR_FORBIDDEN,
+        );
+        rt.verify();
+
+        // An open lane doesn't block pruning of closed lanes after it.
+        close_lanes(&rt, &[0, 2]);
+        let redeemed_before = redeemed(&rt);
+        let ret = prune_lanes(&rt, payer, &[2]);
+        assert_eq!(1, ret.pruned);
+        assert_eq!(TokenAmount::from_atto(3), ret.redeemed);
+        assert_eq!(redeemed_before, redeemed(&rt));
+
+        let ret = prune_lanes(&rt, payer, &[0]);
+        assert_eq!(1, ret.pruned);
+        assert_eq!(TokenAmount::from_atto(1), ret.redeemed);
+        assert_eq!(redeemed_before, redeemed(&rt));
+        let state: PState = rt.get_state();
+        assert_lane_states_length(&rt, &state.lane_states, 1);
+    }
+}
+
+mod update_channel_state_batch {
+    use super::*;
+
+    fn voucher(template: &SignedVoucher, lane: u64, nonce: u64, amount: i64) -> SignedVoucher {
+        SignedVoucher { lane, nonce, amount: TokenAmount::from_atto(amount), ..template.clone() }
+    }
+
+    fn batch_params(vouchers: &[SignedVoucher]) -> Option<IpldBlock> {
+        let updates = vouchers.iter().cloned().map(UpdateChannelStateParams::from).collect();
+        IpldBlock::serialize_cbor(&UpdateChannelStateBatchParams { updates }).unwrap()
+    }
+
+    #[test]
+    fn redeems_vouchers_for_several_lanes() {
+        let (rt, sv) = require_create_channel_with_lanes(2);
+        let before: PState = rt.get_state();
+
+        // The payee redeems an update to lane 0 and a voucher for a new lane together.
+        let vouchers = [voucher(&sv, 0, 5, 10), voucher(&sv, 2, 1, 7)];
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, before.to);
+        rt.expect_validate_caller_addr(vec![before.from, before.to]);
+        for v in &vouchers {
+            expect_authenticate_message(&rt, before.from, v.clone(), ExitCode::OK);
+            expect_voucher_redeemed(&rt, v);
+        }
+        let ret: UpdateChannelStateBatchReturn =
+            call(&rt, Method::UpdateChannelStateBatchExported as u64, batch_params(&vouchers))
+                .unwrap()
+                .deserialize()
+                .unwrap();
+        rt.verify();
+
+        // Lane 0 had redeemed 1.
+        assert_eq!(
+            vec![
+                VoucherRedemption { lane: 0, delta: TokenAmount::from_atto(9) },
+                VoucherRedemption { lane: 2, delta: TokenAmount::from_atto(7) },
+            ],
+            ret.redeemed
+        );
+        let state: PState = rt.get_state();
+        assert_eq!(&before.to_send + TokenAmount::from_atto(16), state.to_send);
+        assert_lane_states_length(&rt, &state.lane_states, 3);
+        let ls = get_lane_state(&rt, &state.lane_states, 0);
+        assert_eq!((5, TokenAmount::from_atto(10)), (ls.nonce, ls.redeemed));
+        check_state(&rt);
+    }
+
+    #[test]
+    fn batch_is_atomic() {
+        let (rt, sv) = require_create_channel_with_lanes(2);
+        let before: PState = rt.get_state();
+        let root = *rt.state.borrow();
+
+        // The second voucher's nonce is outdated, so neither is redeemed.
+        let vouchers = [voucher(&sv, 0, 5, 10), voucher(&sv, 1, 1, 7)];
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, before.to);
+        rt.expect_validate_caller_addr(vec![before.from, before.to]);
+        for v in &vouchers {
+            expect_authenticate_message(&rt, before.from, v.clone(), ExitCode::OK);
+        }
+        expect_abort(
+            &rt,
+            Method::UpdateChannelStateBatchExported as u64,
+            batch_params(&vouchers),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+        assert_eq!(root, *rt.state.borrow());
+        check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_empty_batch_or_repeated_lane() {
+        let (rt, sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+
+        for vouchers in [vec![], vec![voucher(&sv, 0, 5, 10), voucher(&sv, 0, 6, 11)]] {
+            rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+            rt.expect_validate_caller_addr(vec![state.from, state.to]);
+            expect_abort(
+                &rt,
+                Method::UpdateChannelStateBatchExported as u64,
+                batch_params(&vouchers),
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+            );
+            rt.verify();
+        }
+        check_state(&rt);
+    }
+}
+
+mod get_lane_states {
+    use super::*;
+
+    fn get_lane_states(
+        rt: &MockRuntime,
+        cursor: Option<u64>,
+        limit: u64,
+    ) -> Result<GetLaneStatesReturn, fil_actors_runtime::ActorError> {
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<PaychActor>(
+            Method::GetLaneStatesExported as u64,
+            IpldBlock::serialize_cbor(&GetLaneStatesParams { cursor, limit }).unwrap(),
+        );
+        rt.verify();
+        ret.map(|r| r.unwrap().deserialize().unwrap())
+    }
+
+    fn summary(lane: u64, nonce: u64, redeemed: i64) -> LaneStateSummary {
+        LaneStateSummary { lane, nonce, redeemed: TokenAmount::from_atto(redeemed) }
+    }
+
+    #[test]
+    fn lists_lanes_in_pages() {
+        // Lane i is created with nonce i + 1, redeeming i + 1.
+        let (rt, _) = require_create_channel_with_lanes(3);
+
+        let first = get_lane_states(&rt, None, 2).unwrap();
+        assert_eq!(vec![summary(0, 1, 1), summary(1, 2, 2)], first.lanes);
+        assert_eq!(Some(2), first.next_cursor);
+        let second = get_lane_states(&rt, first.next_cursor, 2).unwrap();
+        assert_eq!(vec![summary(2, 3, 3)], second.lanes);
+        assert_eq!(None, second.next_cursor);
+
+        // A single page may hold all lanes.
+        let all = get_lane_states(&rt, None, 10).unwrap();
+        assert_eq!([first.lanes, second.lanes].concat(), all.lanes);
+        assert_eq!(None, all.next_cursor);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn get_lane_states_validation() {
+        let (rt, _) = require_create_channel_with_lanes(1);
+
+        // A cursor beyond the last lane lists nothing.
+        let ret = get_lane_states(&rt, Some(5), 1).unwrap();
+        assert!(ret.lanes.is_empty());
+        assert_eq!(None, ret.next_cursor);
+
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "limit must be positive",
+            get_lane_states(&rt, None, 0),
+        );
+    }
+}
+
+mod watchtower {
+    use super::*;
+
+    const WATCHTOWER: Address = Address::new_id(104);
+
+    fn set_watchtower_params(authorized: bool) -> Option<IpldBlock> {
+        IpldBlock::serialize_cbor(&SetWatchtowerParams { watchtower: WATCHTOWER, authorized })
+            .unwrap()
+    }
+
+    fn set_watchtower(rt: &MockRuntime, caller: Address, authorized: bool) {
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        call(rt, Method::SetWatchtowerExported as u64, set_watchtower_params(authorized));
+        rt.verify();
+    }
+
+    fn construct_runtime() -> (MockRuntime, SignedVoucher) {
+        let (rt, sv) = require_create_channel_with_lanes(1);
+        rt.actor_code_cids.borrow_mut().insert(WATCHTOWER, *ACCOUNT_ACTOR_CODE_ID);
+        (rt, sv)
+    }
+
+    #[test]
+    fn watchtower_redeems_voucher_for_payee() {
+        let (rt, sv) = construct_runtime();
+        let state: PState = rt.get_state();
+        set_watchtower(&rt, state.to, true);
+        assert_eq!(
+            vec![Watchtower { address: WATCHTOWER, principal: state.to }],
+            rt.get_state::<PState>().watchtowers
+        );
+
+        // The watchtower submits a voucher signed by the payer, as the payee would.
+        let sv = SignedVoucher { amount: TokenAmount::from_atto(10), ..sv };
+        let params =
+            IpldBlock::serialize_cbor(&UpdateChannelStateParams::from(sv.clone())).unwrap();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WATCHTOWER);
+        rt.expect_validate_caller_addr(vec![state.from, state.to, WATCHTOWER]);
+        expect_voucher_redeemed(&rt, &sv);
+        expect_authenticate_message(&rt, state.from, sv, ExitCode::OK);
+        call(&rt, Method::UpdateChannelState as u64, params.clone());
+        rt.verify();
+        let ls = get_lane_state(&rt, &rt.get_state::<PState>().lane_states, 0);
+        assert_eq!(TokenAmount::from_atto(10), ls.redeemed);
+        check_state(&rt);
+
+        // Once revoked, the watchtower may no longer submit vouchers.
+        set_watchtower(&rt, state.to, false);
+        assert!(rt.get_state::<PState>().watchtowers.is_empty());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WATCHTOWER);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(&rt, Method::UpdateChannelState as u64, params, ExitCode::USR_FORBIDDEN);
+        rt.verify();
+        check_state(&rt);
+    }
+
+    #[test]
+    fn set_watchtower_validation() {
+        let (rt, _) = construct_runtime();
+        let state: PState = rt.get_state();
+
+        // Only a channel party may authorize a watchtower.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WATCHTOWER);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::SetWatchtowerExported as u64,
+            set_watchtower_params(true),
+            ExitCode::USR_FORBIDDEN,
+        );
+        rt.verify();
+
+        // A party cannot be a watchtower.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::SetWatchtowerExported as u64,
+            IpldBlock::serialize_cbor(&SetWatchtowerParams {
+                watchtower: state.from,
+                authorized: true,
+            })
+            .unwrap(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+
+        // A watchtower acts for only one party, and only that party may revoke it.
+        set_watchtower(&rt, state.to, true);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::SetWatchtowerExported as u64,
+            set_watchtower_params(true),
+            ExitCode::USR_FORBIDDEN,
+        );
+        rt.verify();
+        set_watchtower(&rt, state.from, false);
+        assert_eq!(1, rt.get_state::<PState>().watchtowers.len());
+        check_state(&rt);
+    }
+}
+
 mod actor_settle {
     use super::*;
 
@@ -838,6 +1315,7 @@ mod actor_settle {
         let mut state: PState = rt.get_state();
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_channel_settling(&rt, EP + SETTLE_DELAY);
 
         call(&rt, Method::Settle as u64, None);
 
@@ -855,6 +1333,7 @@ mod actor_settle {
         let state: PState = rt.get_state();
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_channel_settling(&rt, EP + SETTLE_DELAY);
         call(&rt, Method::Settle as u64, None);
 
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
@@ -872,6 +1351,7 @@ mod actor_settle {
 
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
         expect_authenticate_message(&rt, state.to, sv, ExitCode::OK);
+        expect_voucher_redeemed(&rt, &ucp.sv);
 
         call(&rt, Method::UpdateChannelState as u64, IpldBlock::serialize_cbor(&ucp).unwrap());
 
@@ -882,6 +1362,7 @@ mod actor_settle {
         // Settle.
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_channel_settling(&rt, ucp.sv.min_settle_height);
         call(&rt, Method::Settle as u64, None);
 
         state = rt.get_state();
@@ -898,6 +1379,7 @@ mod actor_settle {
         let mut state: PState = rt.get_state();
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
         rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_channel_settling(&rt, EP + SETTLE_DELAY);
 
         call(&rt, Method::Settle as u64, None);
 
@@ -928,6 +1410,7 @@ mod actor_collect {
         // Settle.
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, st.from);
         rt.expect_validate_caller_addr(vec![st.from, st.to]);
+        expect_channel_settling(&rt, SETTLE_DELAY + curr_epoch);
         call(&rt, Method::Settle as u64, None);
         check_state(&rt);
 
@@ -949,14 +1432,16 @@ mod actor_collect {
         );
 
         rt.expect_validate_caller_addr(vec![st.from, st.to]);
+        let refund = &*rt.balance.borrow() - &st.to_send;
         rt.expect_send_simple(
             st.from,
             METHOD_SEND,
             Default::default(),
-            &*rt.balance.borrow() - &st.to_send,
+            refund.clone(),
             Default::default(),
             ExitCode::OK,
         );
+        expect_channel_collected(&rt, &st.to_send, &refund);
         rt.expect_delete_actor();
         let res = call(&rt, Method::Collect as u64, None);
         assert!(res.is_none());
@@ -994,6 +1479,7 @@ mod actor_collect {
             if !tc.dont_settle {
                 rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
                 rt.expect_validate_caller_addr(vec![state.from, state.to]);
+                expect_channel_settling(&rt, SETTLE_DELAY + *rt.epoch.borrow());
                 call(&rt, Method::Settle as u64, None);
                 state = rt.get_state();
                 assert_eq!(state.settling_at, SETTLE_DELAY + *rt.epoch.borrow());
@@ -1084,6 +1570,7 @@ fn require_add_new_lane(rt: &MockRuntime, param: LaneParams) -> SignedVoucher {
     rt.expect_validate_caller_addr(vec![param.from, param.to]);
 
     expect_authenticate_message(rt, payee_addr, sv.clone(), ExitCode::OK);
+    expect_voucher_redeemed(rt, &sv);
 
     call(
         rt,
@@ -0,0 +1,28 @@
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::ActorID;
+
+use crate::ConsensusMinimumCrossing;
+
+/// Indicates a miner has been created and its claim registered.
+pub fn miner_added(rt: &impl Runtime, miner: ActorID) -> Result<(), ActorError> {
+    rt.emit_event(&EventBuilder::new().typ("miner-added").field_indexed("miner", &miner).build()?)
+}
+
+/// Indicates a miner's claim has been removed, along with any power it held.
+pub fn miner_removed(rt: &impl Runtime, miner: ActorID) -> Result<(), ActorError> {
+    rt.emit_event(&EventBuilder::new().typ("miner-removed").field_indexed("miner", &miner).build()?)
+}
+
+/// Indicates a miner's raw byte power has reached or fallen below the consensus minimum.
+pub fn consensus_minimum_crossed(
+    rt: &impl Runtime,
+    miner: ActorID,
+    crossing: ConsensusMinimumCrossing,
+) -> Result<(), ActorError> {
+    let typ = match crossing {
+        ConsensusMinimumCrossing::Above => "miner-above-consensus-minimum",
+        ConsensusMinimumCrossing::Below => "miner-below-consensus-minimum",
+    };
+    rt.emit_event(&EventBuilder::new().typ(typ).field_indexed("miner", &miner).build()?)
+}
@@ -4,6 +4,8 @@ use fvm_ipld_encoding::{BytesDe, strict_bytes};
 
 use fvm_shared::METHOD_CONSTRUCTOR;
 use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::RegisteredPoStProof;
 use num_derive::FromPrimitive;
 
@@ -35,7 +37,9 @@ pub mod init {
 pub mod miner {
     use super::*;
 
+    pub const CONTROL_ADDRESSES_METHOD: u64 = 2;
     pub const ON_DEFERRED_CRON_EVENT_METHOD: u64 = 12;
+    pub const GET_BENEFICIARY_METHOD: u64 = frc42_dispatch::method_hash!("GetBeneficiary");
 
     #[derive(Serialize_tuple, Deserialize_tuple)]
     pub struct MinerConstructorParams {
@@ -55,6 +59,43 @@ pub mod miner {
         pub reward_smoothed: FilterEstimate,
         pub quality_adj_power_smoothed: FilterEstimate,
     }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct GetControlAddressesReturn {
+        pub owner: Address,
+        pub worker: Address,
+        pub control_addresses: Vec<Address>,
+        #[serde(default)]
+        pub control_address_roles: Vec<u8>,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct BeneficiaryTerm {
+        pub quota: TokenAmount,
+        pub used_quota: TokenAmount,
+        pub expiration: ChainEpoch,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct ActiveBeneficiary {
+        pub beneficiary: Address,
+        pub term: BeneficiaryTerm,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct PendingBeneficiaryChange {
+        pub new_beneficiary: Address,
+        pub new_quota: TokenAmount,
+        pub new_expiration: ChainEpoch,
+        pub approved_by_beneficiary: bool,
+        pub approved_by_nominee: bool,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct GetBeneficiaryReturn {
+        pub active: ActiveBeneficiary,
+        pub proposed: Option<PendingBeneficiaryChange>,
+    }
 }
 
 pub mod reward {
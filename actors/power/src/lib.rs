@@ -1,12 +1,14 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use fil_actors_runtime::reward::ThisEpochRewardReturn;
-use fvm_ipld_encoding::RawBytes;
+use fil_actors_runtime::reward::{EpochRewardNotification, ThisEpochRewardReturn};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::{CborStore, RawBytes};
+use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
 use fvm_shared::{METHOD_CONSTRUCTOR, MethodNum};
 use log::{debug, error};
 use num_derive::FromPrimitive;
@@ -16,8 +18,9 @@ use ext::init;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
-    ActorDowncast, ActorError, CRON_ACTOR_ADDR, INIT_ACTOR_ADDR, Multimap, REWARD_ACTOR_ADDR,
-    SYSTEM_ACTOR_ADDR, actor_dispatch, actor_error, deserialize_block, extract_send_result,
+    ActorDowncast, ActorError, AsActorError, BatchReturnGen, CRON_ACTOR_ADDR, INIT_ACTOR_ADDR,
+    Multimap, REWARD_ACTOR_ADDR, RecordingBlockstore, SYSTEM_ACTOR_ADDR, actor_dispatch,
+    actor_error, deserialize_block, extract_send_result,
 };
 
 pub use self::policy::*;
@@ -27,6 +30,7 @@ pub use self::types::*;
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+mod emit;
 #[doc(hidden)]
 pub mod ext;
 mod policy;
@@ -57,6 +61,19 @@ pub enum Method {
     MinerCountExported = frc42_dispatch::method_hash!("MinerCount"),
     MinerConsensusCountExported = frc42_dispatch::method_hash!("MinerConsensusCount"),
     MinerPowerExported = frc42_dispatch::method_hash!("MinerPower"),
+    MinerClaimProofExported = frc42_dispatch::method_hash!("MinerClaimProof"),
+    BatchGetMinerInfoExported = frc42_dispatch::method_hash!("BatchGetMinerInfo"),
+    ConsensusMinPowerExported = frc42_dispatch::method_hash!("ConsensusMinPower"),
+    ListMinersExported = frc42_dispatch::method_hash!("ListMiners"),
+    MinerCountsExported = frc42_dispatch::method_hash!("MinerCounts"),
+    GetMinerPowerBatchExported = frc42_dispatch::method_hash!("GetMinerPowerBatch"),
+    GetPowerHistoryExported = frc42_dispatch::method_hash!("GetPowerHistory"),
+    CreateMinersExported = frc42_dispatch::method_hash!("CreateMiners"),
+    GetCronQueueStatsExported = frc42_dispatch::method_hash!("GetCronQueueStats"),
+    NotifyEpochRewardExported = frc42_dispatch::method_hash!("NotifyEpochReward"),
+    RegisterExperimentalSealProofsExported =
+        frc42_dispatch::method_hash!("RegisterExperimentalSealProofs"),
+    GetExperimentalSealProofsExported = frc42_dispatch::method_hash!("GetExperimentalSealProofs"),
 }
 
 pub const ERR_TOO_MANY_PROVE_COMMITS: ExitCode = ExitCode::new(32);
@@ -82,55 +99,65 @@ impl Actor {
     ) -> Result<CreateMinerReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let value = rt.message().value_received();
+        create_miner_actor(rt, params, value)
+    }
 
-        let constructor_params = RawBytes::serialize(ext::miner::MinerConstructorParams {
-            owner: params.owner,
-            worker: params.worker,
-            window_post_proof_type: params.window_post_proof_type,
-            peer_id: params.peer,
-            multi_addresses: params.multiaddrs,
-            control_addresses: Default::default(),
-        })?;
-
-        let miner_actor_code_cid = rt.get_code_cid_for_type(Type::Miner);
-        let ext::init::ExecReturn { id_address, robust_address } =
-            deserialize_block(extract_send_result(rt.send_simple(
-                &INIT_ACTOR_ADDR,
-                ext::init::EXEC_METHOD,
-                IpldBlock::serialize_cbor(&init::ExecParams {
-                    code_cid: miner_actor_code_cid,
-                    constructor_params,
-                })?,
-                value,
-            ))?)?;
-
-        let window_post_proof_type = params.window_post_proof_type;
-        rt.transaction(|st: &mut State, rt| {
-            let mut claims = st.load_claims(rt.store())?;
-            set_claim(
-                &mut claims,
-                &id_address,
-                Claim {
-                    window_post_proof_type,
-                    quality_adj_power: Default::default(),
-                    raw_byte_power: Default::default(),
-                },
-            )?;
-            st.miner_count += 1;
+    /// Creates a batch of miners with a shared owner, sending each the value specified for it.
+    /// Miners are created in order, so the addresses assigned to them are determined by the
+    /// message and the order of the parameters. The batch fails if any miner cannot be created.
+    fn create_miners(
+        rt: &impl Runtime,
+        params: CreateMinersParams,
+    ) -> Result<CreateMinersReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.miners.is_empty() {
+            return Err(actor_error!(illegal_argument, "no miners to create"));
+        }
+        if params.miners.len() > CREATE_MINERS_MAX_BATCH {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many miners to create: {} > {}",
+                params.miners.len(),
+                CREATE_MINERS_MAX_BATCH
+            ));
+        }
+        if let Some(entry) = params.miners.iter().find(|m| m.value.is_negative()) {
+            return Err(actor_error!(
+                illegal_argument,
+                "negative value for miner with worker {}: {}",
+                entry.worker,
+                entry.value
+            ));
+        }
+        let total: TokenAmount = params.miners.iter().map(|m| &m.value).sum();
+        let value = rt.message().value_received();
+        if total != value {
+            return Err(actor_error!(
+                illegal_argument,
+                "miner values total {} but {} was sent",
+                total,
+                value
+            ));
+        }
 
-            st.update_stats_for_new_miner(rt.policy(), window_post_proof_type).map_err(|e| {
-                actor_error!(
-                    illegal_state,
-                    "failed to update power stats for new miner {}: {}",
-                    &id_address,
-                    e
+        let miners = params
+            .miners
+            .into_iter()
+            .map(|entry| {
+                create_miner_actor(
+                    rt,
+                    CreateMinerParams {
+                        owner: params.owner,
+                        worker: entry.worker,
+                        window_post_proof_type: entry.window_post_proof_type,
+                        peer: entry.peer,
+                        multiaddrs: entry.multiaddrs,
+                    },
+                    entry.value,
                 )
-            })?;
-
-            st.save_claims(&mut claims)?;
-            Ok(())
-        })?;
-        Ok(CreateMinerReturn { id_address, robust_address })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(CreateMinersReturn { miners })
     }
 
     /// Adds or removes claimed power for the calling actor.
@@ -142,10 +169,10 @@ impl Actor {
         rt.validate_immediate_caller_type(std::iter::once(&Type::Miner))?;
         let miner_addr = rt.message().caller();
 
-        rt.transaction(|st: &mut State, rt| {
+        let crossing = rt.transaction(|st: &mut State, rt| {
             let mut claims = st.load_claims(rt.store())?;
 
-            st.add_to_claim(
+            let crossing = st.add_to_claim(
                 rt.policy(),
                 &mut claims,
                 &miner_addr,
@@ -154,8 +181,13 @@ impl Actor {
             )?;
 
             st.save_claims(&mut claims)?;
-            Ok(())
-        })
+            Ok(crossing)
+        })?;
+
+        if let Some(crossing) = crossing {
+            emit::consensus_minimum_crossed(rt, miner_addr.id().unwrap(), crossing)?;
+        }
+        Ok(())
     }
 
     fn enroll_cron_event(
@@ -201,25 +233,35 @@ impl Actor {
     fn on_epoch_tick_end(rt: &impl Runtime) -> Result<(), ActorError> {
         rt.validate_immediate_caller_is(std::iter::once(&CRON_ACTOR_ADDR))?;
 
-        let rewret: ThisEpochRewardReturn = deserialize_block(
-            extract_send_result(rt.send_simple(
-                &REWARD_ACTOR_ADDR,
-                ext::reward::Method::ThisEpochReward as MethodNum,
-                None,
-                TokenAmount::zero(),
-            ))
-            .map_err(|e| e.wrap("failed to check epoch baseline power"))?,
-        )?;
+        // Use the reward pushed by the reward actor at the last tick, querying it only if
+        // none was.
+        let st: State = rt.state()?;
+        let rewret: ThisEpochRewardReturn = match st.last_epoch_reward {
+            Some(notification) => notification.reward,
+            None => deserialize_block(
+                extract_send_result(rt.send_simple(
+                    &REWARD_ACTOR_ADDR,
+                    ext::reward::Method::ThisEpochReward as MethodNum,
+                    None,
+                    TokenAmount::zero(),
+                ))
+                .map_err(|e| e.wrap("failed to check epoch baseline power"))?,
+            )?,
+        };
 
         Self::process_deferred_cron_events(rt, rewret)?;
 
-        let this_epoch_raw_byte_power = rt.transaction(|st: &mut State, _| {
+        let this_epoch_raw_byte_power = rt.transaction(|st: &mut State, rt| {
             let (raw_byte_power, qa_power) = st.current_total_power();
             st.this_epoch_pledge_collateral = st.total_pledge_collateral.clone();
             st.this_epoch_quality_adj_power = qa_power;
             st.this_epoch_raw_byte_power = raw_byte_power;
             // Can assume delta is one since cron is invoked every epoch.
             st.update_smoothed_estimate(1);
+            st.record_power_checkpoint(rt.store(), rt.curr_epoch())?;
+            // The reward actor pushes the updated reward during the update below.
+            // Should that notification fail, the next tick queries the reward instead.
+            st.last_epoch_reward = None;
 
             Ok(IpldBlock::serialize_cbor(&BigIntSer(&st.this_epoch_raw_byte_power))?)
         })?;
@@ -236,6 +278,69 @@ impl Actor {
         Ok(())
     }
 
+    /// Records the epoch reward pushed by the reward actor after each update of its network KPI,
+    /// for use at the next epoch tick.
+    fn notify_epoch_reward(
+        rt: &impl Runtime,
+        params: EpochRewardNotification,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&REWARD_ACTOR_ADDR))?;
+        rt.transaction(|st: &mut State, _| {
+            st.last_epoch_reward = Some(params);
+            Ok(())
+        })
+    }
+
+    /// Registers seal proof types as experimental, so that miners accept them for new sectors.
+    /// This is permitted only on networks whose policy allows experimental seal proofs, and
+    /// only to the governor named by that policy. Registration cannot be revoked.
+    fn register_experimental_seal_proofs(
+        rt: &impl Runtime,
+        params: RegisterExperimentalSealProofsParams,
+    ) -> Result<(), ActorError> {
+        let policy = rt.policy();
+        let governor = policy.experimental_seal_proofs_governor.map(Address::new_id);
+        rt.validate_immediate_caller_is(governor.iter())?;
+        if !policy.allow_experimental_seal_proofs {
+            return Err(actor_error!(forbidden, "experimental seal proofs are not enabled"));
+        }
+        for proof in &params.proofs {
+            if policy.valid_pre_commit_proof_type.contains(*proof)
+                || policy.valid_prove_commit_ni_proof_type.contains(*proof)
+            {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "seal proof type {} is already supported",
+                    i64::from(*proof)
+                ));
+            }
+        }
+        rt.transaction(|st: &mut State, _| {
+            for proof in params.proofs {
+                if !st.experimental_seal_proofs.contains(&proof) {
+                    st.experimental_seal_proofs.push(proof);
+                }
+            }
+            if st.experimental_seal_proofs.len() > MAX_EXPERIMENTAL_SEAL_PROOFS {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "cannot register more than {} experimental seal proof types",
+                    MAX_EXPERIMENTAL_SEAL_PROOFS
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the seal proof types registered as experimental.
+    fn get_experimental_seal_proofs(
+        rt: &impl Runtime,
+    ) -> Result<GetExperimentalSealProofsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        Ok(GetExperimentalSealProofsReturn { proofs: st.experimental_seal_proofs })
+    }
+
     fn update_pledge_total(
         rt: &impl Runtime,
         params: UpdatePledgeTotalParams,
@@ -319,6 +424,36 @@ impl Actor {
         Ok(MinerConsensusCountReturn { miner_consensus_count: st.miner_above_min_power_count })
     }
 
+    /// Returns the number of registered miners, split by whether their raw power meets the
+    /// consensus minimum.
+    fn miner_counts(rt: &impl Runtime) -> Result<MinerCountsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+
+        Ok(MinerCountsReturn {
+            miner_count: st.miner_count,
+            above_consensus_minimum: st.miner_above_min_power_count,
+            below_consensus_minimum: st.miner_count - st.miner_above_min_power_count,
+        })
+    }
+
+    /// Lists a page of registered miners with their power claims, in ID order.
+    /// A caller pages through all the miners by passing the returned cursor to the next call,
+    /// until no cursor is returned. Miners deleted between calls do not interrupt paging.
+    fn list_miners(
+        rt: &impl Runtime,
+        params: ListMinersParams,
+    ) -> Result<ListMinersReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let st: State = rt.state()?;
+        let (miners, next_cursor) =
+            st.list_miners(rt.policy(), rt.store(), params.cursor, params.limit)?;
+        Ok(ListMinersReturn { miners, next_cursor })
+    }
+
     /// Returns the miner's quality-adjusted and raw power
     fn miner_power(
         rt: &impl Runtime,
@@ -340,6 +475,166 @@ impl Actor {
         }
     }
 
+    /// Returns the miner's claim together with the state blocks which prove it
+    /// (or its absence) against the current state root.
+    /// The proof may be checked with `verify_miner_claim`.
+    fn miner_claim_proof(
+        rt: &impl Runtime,
+        params: MinerClaimProofParams,
+    ) -> Result<MinerClaimProofReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state_root = rt.get_state_root()?;
+
+        let store = RecordingBlockstore::new(rt.store());
+        let st: State = store
+            .get_cbor(&state_root)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load state")?
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "state not found")?;
+        let claim = st.miner_power(&store, &Address::new_id(params.miner))?;
+
+        Ok(MinerClaimProofReturn { state_root, claim, proof: store.into_proof() })
+    }
+
+    /// Returns the recent checkpoints of total network power recorded by cron, oldest first.
+    /// A checkpoint is recorded at the first cron tick of each POWER_CHECKPOINT_INTERVAL epochs,
+    /// and the last POWER_CHECKPOINT_HISTORY checkpoints are retained.
+    fn get_power_history(rt: &impl Runtime) -> Result<GetPowerHistoryReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        Ok(GetPowerHistoryReturn { checkpoints: st.power_history(rt.store())? })
+    }
+
+    /// Returns the number of events in the cron event queue, the number queued for each
+    /// epoch in a range, and the backlog of proofs awaiting batch verification, so the
+    /// pressure on cron processing can be monitored.
+    fn get_cron_queue_stats(
+        rt: &impl Runtime,
+        params: GetCronQueueStatsParams,
+    ) -> Result<GetCronQueueStatsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.end_epoch < params.start_epoch {
+            return Err(actor_error!(
+                illegal_argument,
+                "end epoch {} before start epoch {}",
+                params.end_epoch,
+                params.start_epoch
+            ));
+        }
+        if params.end_epoch - params.start_epoch > CRON_QUEUE_STATS_MAX_EPOCHS {
+            return Err(actor_error!(
+                illegal_argument,
+                "epoch range {}..{} exceeds maximum of {} epochs",
+                params.start_epoch,
+                params.end_epoch,
+                CRON_QUEUE_STATS_MAX_EPOCHS
+            ));
+        }
+        let st: State = rt.state()?;
+        let buckets = st.cron_queue_buckets(rt.store(), params.start_epoch, params.end_epoch)?;
+        Ok(GetCronQueueStatsReturn {
+            first_cron_epoch: st.first_cron_epoch,
+            total_events: st.cron_event_count,
+            buckets,
+            proof_validation_backlog: st.proof_validation_backlog(rt.store())?,
+        })
+    }
+
+    /// Returns the raw and quality-adjusted power of a batch of miners.
+    /// Each miner fails independently of the others, with USR_NOT_FOUND if it is not
+    /// registered with the power actor.
+    fn get_miner_power_batch(
+        rt: &impl Runtime,
+        params: GetMinerPowerBatchParams,
+    ) -> Result<GetMinerPowerBatchReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.miners.len() > GET_MINER_POWER_MAX_BATCH {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many miners to query: {} > {}",
+                params.miners.len(),
+                GET_MINER_POWER_MAX_BATCH
+            ));
+        }
+        let st: State = rt.state()?;
+        let claims = st.load_claims(rt.store())?;
+
+        let mut batch_gen = BatchReturnGen::new(params.miners.len());
+        let mut powers = Vec::new();
+        for miner in params.miners {
+            match claims.get(&Address::new_id(miner))? {
+                Some(claim) => {
+                    batch_gen.add_success();
+                    powers.push(MinerPowerReturn {
+                        raw_byte_power: claim.raw_byte_power.clone(),
+                        quality_adj_power: claim.quality_adj_power.clone(),
+                    });
+                }
+                None => batch_gen.add_fail(ExitCode::USR_NOT_FOUND),
+            }
+        }
+        Ok(GetMinerPowerBatchReturn { results: batch_gen.generate(), powers })
+    }
+
+    /// Returns the control addresses and active beneficiary of a batch of miners,
+    /// fetched from each miner with read-only calls.
+    /// Each miner fails independently of the others, with USR_NOT_FOUND if it is not
+    /// registered with the power actor, or the exit code of a failed call to it.
+    fn batch_get_miner_info(
+        rt: &impl Runtime,
+        params: BatchGetMinerInfoParams,
+    ) -> Result<BatchGetMinerInfoReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.miners.len() > GET_MINER_INFO_MAX_BATCH {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many miners to query: {} > {}",
+                params.miners.len(),
+                GET_MINER_INFO_MAX_BATCH
+            ));
+        }
+        let st: State = rt.state()?;
+
+        let mut batch_gen = BatchReturnGen::new(params.miners.len());
+        let mut infos = Vec::new();
+        for miner in params.miners {
+            let miner_addr = Address::new_id(miner);
+            if st.miner_power(rt.store(), &miner_addr)?.is_none() {
+                batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                continue;
+            }
+            match request_miner_control_info(rt, &miner_addr) {
+                Ok(info) => {
+                    batch_gen.add_success();
+                    infos.push(info);
+                }
+                Err(e) => {
+                    debug!("failed to get info for miner {}: {}", miner, e);
+                    batch_gen.add_fail(e.exit_code());
+                }
+            }
+        }
+        Ok(BatchGetMinerInfoReturn { results: batch_gen.generate(), infos })
+    }
+
+    /// Returns the minimum raw power a miner must hold to participate in consensus, for each
+    /// window PoSt proof type accepted by new miners at the current network version.
+    fn consensus_min_power(rt: &impl Runtime) -> Result<ConsensusMinPowerReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let policy = rt.policy();
+
+        let thresholds = WINDOW_POST_PROOF_TYPES
+            .iter()
+            .filter(|proof| policy.valid_post_proof_type.contains(**proof))
+            .map(|&window_post_proof_type| {
+                let min_power = consensus_miner_min_power(policy, window_post_proof_type)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to get minimum power")?;
+                Ok(ConsensusMinPower { window_post_proof_type, min_power })
+            })
+            .collect::<Result<_, ActorError>>()?;
+
+        Ok(ConsensusMinPowerReturn { network_version: rt.network_version(), thresholds })
+    }
+
     fn process_deferred_cron_events(
         rt: &impl Runtime,
         rewret: ThisEpochRewardReturn,
@@ -370,6 +665,7 @@ impl Actor {
                 if epoch_events.is_empty() {
                     continue;
                 }
+                st.cron_event_count = st.cron_event_count.saturating_sub(epoch_events.len() as u64);
 
                 for evt in epoch_events.into_iter() {
                     let miner_has_claim = claims.contains_key(&evt.miner_addr)?;
@@ -420,29 +716,143 @@ impl Actor {
         }
 
         if !failed_miner_crons.is_empty() {
-            rt.transaction(|st: &mut State, rt| {
+            let removed = rt.transaction(|st: &mut State, rt| {
                 let mut claims = st.load_claims(rt.store())?;
+                let mut removed = Vec::new();
 
                 // Remove power and leave miner frozen
                 for miner_addr in failed_miner_crons {
-                    if let Err(e) = st.delete_claim(rt.policy(), &mut claims, &miner_addr) {
-                        error!(
-                            "failed to delete claim for miner {} after\
-                            failing on deferred cron event: {}",
-                            miner_addr, e
-                        );
-                        continue;
+                    let had_claim = claims.contains_key(&miner_addr)?;
+                    let crossing = match st.delete_claim(rt.policy(), &mut claims, &miner_addr) {
+                        Ok(crossing) => crossing,
+                        Err(e) => {
+                            error!(
+                                "failed to delete claim for miner {} after\
+                                failing on deferred cron event: {}",
+                                miner_addr, e
+                            );
+                            continue;
+                        }
+                    };
+                    if had_claim {
+                        removed.push((miner_addr, crossing));
                     }
                     st.miner_count -= 1
                 }
                 st.save_claims(&mut claims)?;
-                Ok(())
+                Ok(removed)
             })?;
+
+            for (miner_addr, crossing) in removed {
+                let miner_id = miner_addr.id().unwrap();
+                if let Some(crossing) = crossing {
+                    emit::consensus_minimum_crossed(rt, miner_id, crossing)?;
+                }
+                emit::miner_removed(rt, miner_id)?;
+            }
         }
         Ok(())
     }
 }
 
+/// Creates a miner actor with the init actor, sending it `value`, and registers its claim.
+fn create_miner_actor(
+    rt: &impl Runtime,
+    params: CreateMinerParams,
+    value: TokenAmount,
+) -> Result<CreateMinerReturn, ActorError> {
+    let constructor_params = RawBytes::serialize(ext::miner::MinerConstructorParams {
+        owner: params.owner,
+        worker: params.worker,
+        window_post_proof_type: params.window_post_proof_type,
+        peer_id: params.peer,
+        multi_addresses: params.multiaddrs,
+        control_addresses: Default::default(),
+    })?;
+
+    let miner_actor_code_cid = rt.get_code_cid_for_type(Type::Miner);
+    let ext::init::ExecReturn { id_address, robust_address } =
+        deserialize_block(extract_send_result(rt.send_simple(
+            &INIT_ACTOR_ADDR,
+            ext::init::EXEC_METHOD,
+            IpldBlock::serialize_cbor(&init::ExecParams {
+                code_cid: miner_actor_code_cid,
+                constructor_params,
+            })?,
+            value,
+        ))?)?;
+
+    let window_post_proof_type = params.window_post_proof_type;
+    let above_minimum = rt.transaction(|st: &mut State, rt| {
+        let mut claims = st.load_claims(rt.store())?;
+        set_claim(
+            &mut claims,
+            &id_address,
+            Claim {
+                window_post_proof_type,
+                quality_adj_power: Default::default(),
+                raw_byte_power: Default::default(),
+            },
+        )?;
+        st.miner_count += 1;
+
+        let above_minimum =
+            st.update_stats_for_new_miner(rt.policy(), window_post_proof_type).map_err(|e| {
+                actor_error!(
+                    illegal_state,
+                    "failed to update power stats for new miner {}: {}",
+                    &id_address,
+                    e
+                )
+            })?;
+
+        st.save_claims(&mut claims)?;
+        Ok(above_minimum)
+    })?;
+
+    let miner_id = id_address.id().unwrap();
+    emit::miner_added(rt, miner_id)?;
+    if above_minimum {
+        emit::consensus_minimum_crossed(rt, miner_id, ConsensusMinimumCrossing::Above)?;
+    }
+    Ok(CreateMinerReturn { id_address, robust_address })
+}
+
+/// Fetches a miner's control addresses and active beneficiary with read-only calls to it.
+fn request_miner_control_info(
+    rt: &impl Runtime,
+    miner: &Address,
+) -> Result<MinerControlInfo, ActorError> {
+    let addrs: ext::miner::GetControlAddressesReturn =
+        deserialize_block(extract_send_result(rt.send(
+            miner,
+            ext::miner::CONTROL_ADDRESSES_METHOD,
+            None,
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+        ))?)?;
+    let beneficiary: ext::miner::GetBeneficiaryReturn =
+        deserialize_block(extract_send_result(rt.send(
+            miner,
+            ext::miner::GET_BENEFICIARY_METHOD,
+            None,
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+        ))?)?;
+    let active = beneficiary.active;
+    Ok(MinerControlInfo {
+        owner: addrs.owner,
+        worker: addrs.worker,
+        control_addresses: addrs.control_addresses,
+        beneficiary: active.beneficiary,
+        beneficiary_quota: active.term.quota,
+        beneficiary_used_quota: active.term.used_quota,
+        beneficiary_expiration: active.term.expiration,
+    })
+}
+
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -463,5 +873,17 @@ impl ActorCode for Actor {
         MinerCountExported => miner_count,
         MinerConsensusCountExported => miner_consensus_count,
         MinerPowerExported => miner_power,
+        MinerClaimProofExported => miner_claim_proof,
+        BatchGetMinerInfoExported => batch_get_miner_info,
+        ConsensusMinPowerExported => consensus_min_power,
+        ListMinersExported => list_miners,
+        MinerCountsExported => miner_counts,
+        GetMinerPowerBatchExported => get_miner_power_batch,
+        GetPowerHistoryExported => get_power_history,
+        CreateMinersExported => create_miners,
+        GetCronQueueStatsExported => get_cron_queue_stats,
+        NotifyEpochRewardExported => notify_epoch_reward,
+        RegisterExperimentalSealProofsExported => register_experimental_seal_proofs,
+        GetExperimentalSealProofsExported => get_experimental_seal_proofs,
     }
 }
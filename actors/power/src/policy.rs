@@ -1,6 +1,9 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use fil_actors_runtime::network::{EPOCHS_IN_DAY, EPOCHS_IN_HOUR};
+use fvm_shared::clock::ChainEpoch;
+
 /// Minimum power of an individual miner to meet the threshold for leader election.
 pub const CONSENSUS_MINER_MIN_MINERS: i64 = 4;
 
@@ -11,3 +14,26 @@ pub const CONSENSUS_MINER_MIN_MINERS: i64 = 4;
 ///
 /// To support onboarding 1EiB/year, we need to allow at least 32 prove commits per epoch.
 pub const MAX_MINER_PROVE_COMMITS_PER_EPOCH: u64 = 200;
+
+/// Maximum number of miners which may be created by a single CreateMiners message.
+pub const CREATE_MINERS_MAX_BATCH: usize = 32;
+
+/// Maximum number of miners whose power may be queried by a single GetMinerPowerBatch message.
+pub const GET_MINER_POWER_MAX_BATCH: usize = 1000;
+
+/// Maximum number of miners whose info may be queried by a single BatchGetMinerInfo message.
+/// Each miner costs two calls to it, so this is much smaller than the power batch limit.
+pub const GET_MINER_INFO_MAX_BATCH: usize = 32;
+
+/// Epochs between checkpoints of total network power, recorded by cron.
+pub const POWER_CHECKPOINT_INTERVAL: ChainEpoch = EPOCHS_IN_HOUR;
+
+/// Number of power checkpoints retained, the oldest being overwritten by each new checkpoint.
+/// Hourly checkpoints are retained for a week.
+pub const POWER_CHECKPOINT_HISTORY: u64 = 7 * 24;
+
+/// Maximum number of epochs of the cron event queue which may be inspected in one query.
+pub const CRON_QUEUE_STATS_MAX_EPOCHS: ChainEpoch = EPOCHS_IN_DAY;
+
+/// Maximum number of seal proof types which may be registered as experimental.
+pub const MAX_EXPERIMENTAL_SEAL_PROOFS: usize = 16;
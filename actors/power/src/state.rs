@@ -1,13 +1,14 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::BTreeMap;
 use std::ops::Neg;
 
 use anyhow::anyhow;
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
-use fvm_ipld_encoding::RawBytes;
 use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{CborStore, RawBytes};
 use fvm_ipld_hamt::BytesKey;
 use fvm_shared::ActorID;
 use fvm_shared::address::Address;
@@ -15,21 +16,26 @@ use fvm_shared::bigint::bigint_ser;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::sector::{RegisteredPoStProof, StoragePower};
+use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, SealVerifyInfo, StoragePower};
 use integer_encoding::VarInt;
 use lazy_static::lazy_static;
+use multihash_codetable::Code;
 use num_traits::Signed;
 
 use fil_actors_runtime::builtin::reward::smooth::{
     AlphaBetaFilter, DEFAULT_ALPHA, DEFAULT_BETA, FilterEstimate,
 };
+use fil_actors_runtime::reward::EpochRewardNotification;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
-    ActorContext, ActorDowncast, ActorError, AsActorError, Config, DEFAULT_HAMT_CONFIG, Map2,
-    Multimap, actor_error,
+    ActorContext, ActorDowncast, ActorError, Array, AsActorError, Config, DEFAULT_HAMT_CONFIG,
+    HAMT_BIT_WIDTH, Map2, Multimap, ProofBlock, actor_error, proof_blockstore,
 };
 
-use super::CONSENSUS_MINER_MIN_MINERS;
+use super::{
+    CONSENSUS_MINER_MIN_MINERS, CronQueueBucket, ListedMiner, POWER_CHECKPOINT_HISTORY,
+    POWER_CHECKPOINT_INTERVAL,
+};
 
 lazy_static! {
     /// genesis power in bytes = 750,000 GiB
@@ -40,6 +46,8 @@ lazy_static! {
 
 pub const CRON_QUEUE_HAMT_BITWIDTH: u32 = 6;
 pub const CRON_QUEUE_AMT_BITWIDTH: u32 = 6;
+pub const POWER_HISTORY_AMT_BITWIDTH: u32 = 5;
+pub const PROOF_VALIDATION_BATCH_AMT_BITWIDTH: u32 = 4;
 
 pub type ClaimsMap<BS> = Map2<BS, Address, Claim>;
 pub const CLAIMS_CONFIG: Config = DEFAULT_HAMT_CONFIG;
@@ -89,6 +97,56 @@ pub struct State {
 
     // Deprecated as of FIP 0084
     pub proof_validation_batch: Option<Cid>,
+
+    /// Recent checkpoints of total power, as a ring buffer indexed by checkpoint interval.
+    /// Absent until the first checkpoint is recorded.
+    #[serde(default)]
+    pub power_history: Option<Cid>, // AMT[u64]PowerCheckpoint
+    /// Epoch of the most recent power checkpoint, absent until the first is recorded.
+    #[serde(default)]
+    pub last_power_checkpoint: Option<ChainEpoch>,
+
+    /// Number of events in the cron event queue.
+    #[serde(default)]
+    pub cron_event_count: u64,
+
+    /// The epoch reward pushed by the reward actor at the last epoch tick, used in place of
+    /// querying it. Absent if no notification was received since the last tick.
+    #[serde(default)]
+    pub last_epoch_reward: Option<EpochRewardNotification>,
+
+    /// Seal proof types registered as experimental, which miners accept for new sectors
+    /// where the policy allows them.
+    #[serde(default)]
+    pub experimental_seal_proofs: Vec<RegisteredSealProof>,
+}
+
+// The state schema before the power history was added.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+struct StateV0 {
+    #[serde(with = "bigint_ser")]
+    total_raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    total_bytes_committed: StoragePower,
+    #[serde(with = "bigint_ser")]
+    total_quality_adj_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    total_qa_bytes_committed: StoragePower,
+    total_pledge_collateral: TokenAmount,
+    #[serde(with = "bigint_ser")]
+    this_epoch_raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    this_epoch_quality_adj_power: StoragePower,
+    this_epoch_pledge_collateral: TokenAmount,
+    this_epoch_qa_power_smoothed: FilterEstimate,
+    miner_count: i64,
+    miner_above_min_power_count: i64,
+    ramp_start_epoch: i64,
+    ramp_duration_epochs: u64,
+    cron_event_queue: Cid,
+    first_cron_epoch: ChainEpoch,
+    claims: Cid,
+    proof_validation_batch: Option<Cid>,
 }
 
 impl State {
@@ -108,6 +166,52 @@ impl State {
         })
     }
 
+    /// Re-encodes power state written before the power history was added, with the history
+    /// and the fields appended after it at their initial values, and seeds the cron event count
+    /// from the queue. Returns the root of the migrated state.
+    pub fn migrate<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Cid> {
+        let mut st = match store.get_cbor::<State>(root) {
+            Ok(Some(st)) => st,
+            _ => {
+                let old: StateV0 = store
+                    .get_cbor(root)?
+                    .ok_or_else(|| anyhow!("power state {} not found", root))?;
+                State {
+                    total_raw_byte_power: old.total_raw_byte_power,
+                    total_bytes_committed: old.total_bytes_committed,
+                    total_quality_adj_power: old.total_quality_adj_power,
+                    total_qa_bytes_committed: old.total_qa_bytes_committed,
+                    total_pledge_collateral: old.total_pledge_collateral,
+                    this_epoch_raw_byte_power: old.this_epoch_raw_byte_power,
+                    this_epoch_quality_adj_power: old.this_epoch_quality_adj_power,
+                    this_epoch_pledge_collateral: old.this_epoch_pledge_collateral,
+                    this_epoch_qa_power_smoothed: old.this_epoch_qa_power_smoothed,
+                    miner_count: old.miner_count,
+                    miner_above_min_power_count: old.miner_above_min_power_count,
+                    ramp_start_epoch: old.ramp_start_epoch,
+                    ramp_duration_epochs: old.ramp_duration_epochs,
+                    cron_event_queue: old.cron_event_queue,
+                    first_cron_epoch: old.first_cron_epoch,
+                    claims: old.claims,
+                    proof_validation_batch: old.proof_validation_batch,
+                    ..Default::default()
+                }
+            }
+        };
+        let events = Multimap::from_root(
+            store,
+            &st.cron_event_queue,
+            CRON_QUEUE_HAMT_BITWIDTH,
+            CRON_QUEUE_AMT_BITWIDTH,
+        )?;
+        st.cron_event_count = 0;
+        events.for_all::<_, CronEvent>(|_, epoch_events| {
+            st.cron_event_count += epoch_events.count();
+            Ok(())
+        })?;
+        Ok(store.put_cbor(&st, Code::Blake2b256)?)
+    }
+
     pub fn into_total_locked(self) -> TokenAmount {
         self.total_pledge_collateral
     }
@@ -125,22 +229,133 @@ impl State {
             format!("no claim for actor: {}", miner)
         })?;
 
-        let miner_nominal_power = claim.raw_byte_power.clone();
+        let meets_minimum = self.claim_meets_consensus_minimum(policy, claim)?;
+        Ok((claim.raw_byte_power.clone(), meets_minimum))
+    }
+
+    /// Checks whether a miner's claim meets minimum consensus power.
+    fn claim_meets_consensus_minimum(
+        &self,
+        policy: &Policy,
+        claim: &Claim,
+    ) -> Result<bool, ActorError> {
+        let miner_nominal_power = &claim.raw_byte_power;
         let miner_min_power = consensus_miner_min_power(policy, claim.window_post_proof_type)
             .context_code(
                 ExitCode::USR_ILLEGAL_STATE,
                 "could not get miner min power from proof type: {}",
             )?;
 
-        if miner_nominal_power >= miner_min_power {
+        if *miner_nominal_power >= miner_min_power {
             // If miner is larger than min power requirement, valid
-            Ok((miner_nominal_power, true))
+            Ok(true)
         } else if self.miner_above_min_power_count >= CONSENSUS_MINER_MIN_MINERS {
             // if min consensus miners requirement met, return false
-            Ok((miner_nominal_power, false))
+            Ok(false)
         } else {
             // if fewer miners than consensus minimum, return true if non-zero power
-            Ok((miner_nominal_power.clone(), miner_nominal_power.is_positive()))
+            Ok(miner_nominal_power.is_positive())
+        }
+    }
+
+    /// Lists up to `limit` miners with their claims in ID order, starting after the cursor
+    /// miner, and returns the last listed miner as the cursor to continue from, if any remain.
+    /// The cursor need not be a registered miner.
+    /// Claims are keyed by address hash, so every claim is read to select a page.
+    pub fn list_miners<BS: Blockstore>(
+        &self,
+        policy: &Policy,
+        s: &BS,
+        cursor: Option<ActorID>,
+        limit: u64,
+    ) -> Result<(Vec<ListedMiner>, Option<ActorID>), ActorError> {
+        let claims = self.load_claims(s)?;
+        let mut page = BTreeMap::new();
+        let mut more = false;
+        claims.for_each(|a, claim| {
+            let miner = miner_id(&a)?;
+            if cursor.is_some_and(|c| miner <= c) {
+                return Ok(());
+            }
+            page.insert(miner, claim.clone());
+            if page.len() as u64 > limit {
+                page.pop_last();
+                more = true;
+            }
+            Ok(())
+        })?;
+
+        let next_cursor = if more { page.last_key_value().map(|(miner, _)| *miner) } else { None };
+        let miners = page
+            .into_iter()
+            .map(|(miner, claim)| {
+                let meets_consensus_minimum = self.claim_meets_consensus_minimum(policy, &claim)?;
+                Ok(ListedMiner { miner, claim, meets_consensus_minimum })
+            })
+            .collect::<Result<_, ActorError>>()?;
+        Ok((miners, next_cursor))
+    }
+
+    /// Records the current total power as a checkpoint, unless one has already been recorded
+    /// in the checkpoint interval containing the epoch.
+    /// The checkpoint replaces that recorded POWER_CHECKPOINT_HISTORY intervals earlier.
+    pub fn record_power_checkpoint<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        epoch: ChainEpoch,
+    ) -> Result<(), ActorError> {
+        let interval = epoch.div_euclid(POWER_CHECKPOINT_INTERVAL);
+        if self
+            .last_power_checkpoint
+            .is_some_and(|last| last.div_euclid(POWER_CHECKPOINT_INTERVAL) == interval)
+        {
+            return Ok(());
+        }
+        let slot = interval.rem_euclid(POWER_CHECKPOINT_HISTORY as i64) as u64;
+        let mut history = self.load_power_history(store)?;
+        history
+            .set(
+                slot,
+                PowerCheckpoint {
+                    epoch,
+                    raw_byte_power: self.this_epoch_raw_byte_power.clone(),
+                    quality_adj_power: self.this_epoch_quality_adj_power.clone(),
+                },
+            )
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set power checkpoint")?;
+        self.power_history = Some(
+            history
+                .flush()
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush power history")?,
+        );
+        self.last_power_checkpoint = Some(epoch);
+        Ok(())
+    }
+
+    /// Returns the recorded power checkpoints, oldest first.
+    pub fn power_history<BS: Blockstore>(
+        &self,
+        store: &BS,
+    ) -> Result<Vec<PowerCheckpoint>, ActorError> {
+        let mut checkpoints = Vec::new();
+        self.load_power_history(store)?
+            .for_each(|_, checkpoint| {
+                checkpoints.push(checkpoint.clone());
+                Ok(())
+            })
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate power history")?;
+        checkpoints.sort_by_key(|c| c.epoch);
+        Ok(checkpoints)
+    }
+
+    fn load_power_history<'bs, BS: Blockstore>(
+        &self,
+        store: &'bs BS,
+    ) -> Result<Array<'bs, PowerCheckpoint, BS>, ActorError> {
+        match &self.power_history {
+            Some(root) => Array::load(root, store)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load power history"),
+            None => Ok(Array::new_with_bit_width(store, POWER_HISTORY_AMT_BITWIDTH)),
         }
     }
 
@@ -160,7 +375,7 @@ impl State {
         miner: &Address,
         power: &StoragePower,
         qa_power: &StoragePower,
-    ) -> Result<(), ActorError> {
+    ) -> Result<Option<ConsensusMinimumCrossing>, ActorError> {
         let old_claim = claims
             .get(miner)?
             .ok_or_else(|| actor_error!(not_found, "no claim for actor {}", miner))?;
@@ -179,14 +394,17 @@ impl State {
                 .exit_code(ExitCode::USR_ILLEGAL_STATE)?;
         let prev_below: bool = old_claim.raw_byte_power < min_power;
         let still_below: bool = new_claim.raw_byte_power < min_power;
+        let mut crossing = None;
 
         if prev_below && !still_below {
             // Just passed min miner size
+            crossing = Some(ConsensusMinimumCrossing::Above);
             self.miner_above_min_power_count += 1;
             self.total_quality_adj_power += &new_claim.quality_adj_power;
             self.total_raw_byte_power += &new_claim.raw_byte_power;
         } else if !prev_below && still_below {
             // just went below min miner size
+            crossing = Some(ConsensusMinimumCrossing::Below);
             self.miner_above_min_power_count -= 1;
             self.total_quality_adj_power = self
                 .total_quality_adj_power
@@ -224,7 +442,8 @@ impl State {
             ));
         }
 
-        set_claim(claims, miner, new_claim)
+        set_claim(claims, miner, new_claim)?;
+        Ok(crossing)
     }
 
     pub fn load_claims<BS: Blockstore>(&self, s: BS) -> Result<ClaimsMap<BS>, ActorError> {
@@ -256,9 +475,65 @@ impl State {
         events.add(epoch_key(epoch), event).map_err(|e| {
             e.downcast_wrap(format!("failed to store cron event at epoch {}", epoch))
         })?;
+        self.cron_event_count += 1;
         Ok(())
     }
 
+    /// Returns the number of events queued for each epoch in `[start, end)` with any.
+    pub fn cron_queue_buckets<BS: Blockstore>(
+        &self,
+        store: &BS,
+        start: ChainEpoch,
+        end: ChainEpoch,
+    ) -> Result<Vec<CronQueueBucket>, ActorError> {
+        let events = Multimap::from_root(
+            store,
+            &self.cron_event_queue,
+            CRON_QUEUE_HAMT_BITWIDTH,
+            CRON_QUEUE_AMT_BITWIDTH,
+        )
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load cron events")?;
+
+        let mut buckets = Vec::new();
+        for epoch in start..end {
+            let epoch_events = events
+                .get::<CronEvent>(&epoch_key(epoch))
+                .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                    format!("failed to load cron events at {}", epoch)
+                })?;
+            if let Some(epoch_events) = epoch_events {
+                buckets.push(CronQueueBucket { epoch, events: epoch_events.count() });
+            }
+        }
+        Ok(buckets)
+    }
+
+    /// Returns the number of seal proofs awaiting batch verification by cron.
+    /// Batch verification was removed by FIP 0084, since when the backlog is always empty.
+    pub fn proof_validation_backlog<BS: Blockstore>(&self, store: &BS) -> Result<u64, ActorError> {
+        let Some(root) = &self.proof_validation_batch else {
+            return Ok(0);
+        };
+        let batch = Multimap::from_root(
+            store,
+            root,
+            HAMT_BIT_WIDTH,
+            PROOF_VALIDATION_BATCH_AMT_BITWIDTH,
+        )
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load proof validation batch")?;
+        let mut backlog = 0;
+        batch
+            .for_all::<_, SealVerifyInfo>(|_, proofs| {
+                backlog += proofs.count();
+                Ok(())
+            })
+            .context_code(
+                ExitCode::USR_ILLEGAL_STATE,
+                "failed to iterate proof validation batch",
+            )?;
+        Ok(backlog)
+    }
+
     pub fn current_total_power(&self) -> (StoragePower, StoragePower) {
         if self.miner_above_min_power_count < CONSENSUS_MINER_MIN_MINERS {
             (self.total_bytes_committed.clone(), self.total_qa_bytes_committed.clone())
@@ -279,17 +554,19 @@ impl State {
 
     /// Update stats on new miner creation. This is currently just used to update the miner count
     /// when new added miner starts above the minimum.
+    /// Returns whether the new miner starts above the minimum.
     pub(super) fn update_stats_for_new_miner(
         &mut self,
         policy: &Policy,
         window_post_proof: RegisteredPoStProof,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         let min_power = consensus_miner_min_power(policy, window_post_proof)?;
 
-        if !min_power.is_positive() {
+        let above = !min_power.is_positive();
+        if above {
             self.miner_above_min_power_count += 1;
         }
-        Ok(())
+        Ok(above)
     }
 
     /// Validates that miner has
@@ -327,21 +604,22 @@ impl State {
         policy: &Policy,
         claims: &mut ClaimsMap<BS>,
         miner: &Address,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Option<ConsensusMinimumCrossing>> {
         let (rbp, qap) = match claims.get(miner)? {
             None => {
-                return Ok(());
+                return Ok(None);
             }
             Some(claim) => (claim.raw_byte_power.clone(), claim.quality_adj_power.clone()),
         };
 
         // Subtract from stats to remove power
-        self.add_to_claim(policy, claims, miner, &rbp.neg(), &qap.neg())
+        let crossing = self
+            .add_to_claim(policy, claims, miner, &rbp.neg(), &qap.neg())
             .context("subtract miner power before deleting claim")?;
         claims
             .delete(miner)?
             .ok_or_else(|| anyhow!("failed to delete claim for {miner}: doesn't exist"))?;
-        Ok(())
+        Ok(crossing)
     }
 }
 
@@ -383,6 +661,31 @@ pub fn set_claim<BS: Blockstore>(
     Ok(())
 }
 
+/// Verifies a proof of a miner's claim against a power actor state root, as returned by
+/// the MinerClaimProof method.
+/// Returns the proven claim, or None if the proof shows the miner has no claim.
+pub fn verify_miner_claim(
+    state_root: &Cid,
+    miner: ActorID,
+    proof: &[ProofBlock],
+) -> Result<Option<Claim>, ActorError> {
+    let store = proof_blockstore(proof)?;
+    let st: State = store
+        .get_cbor(state_root)
+        .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+            format!("failed to load state {} from proof", state_root)
+        })?
+        .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+            format!("proof is missing state root {}", state_root)
+        })?;
+    st.miner_power(&store, &Address::new_id(miner))
+        .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || "proof is incomplete for claim")
+}
+
+fn miner_id(a: &Address) -> Result<ActorID, ActorError> {
+    a.id().context_code(ExitCode::USR_ILLEGAL_STATE, "claim key is not an ID address")
+}
+
 pub fn epoch_key(e: ChainEpoch) -> BytesKey {
     let bz = e.encode_var_vec();
     bz.into()
@@ -400,6 +703,25 @@ pub struct Claim {
     pub quality_adj_power: StoragePower,
 }
 
+/// Total network power at an epoch.
+#[derive(Debug, Serialize_tuple, Deserialize_tuple, Clone, PartialEq, Eq)]
+pub struct PowerCheckpoint {
+    pub epoch: ChainEpoch,
+    #[serde(with = "bigint_ser")]
+    pub raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub quality_adj_power: StoragePower,
+}
+
+/// A change in whether a miner's raw byte power meets the consensus minimum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsensusMinimumCrossing {
+    /// The miner's power has reached the minimum.
+    Above,
+    /// The miner's power has fallen below the minimum.
+    Below,
+}
+
 #[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
 pub struct CronEvent {
     pub miner_addr: Address,
@@ -9,7 +9,7 @@ use fil_actors_runtime::{MessageAccumulator, Multimap, parse_uint_key, runtime::
 
 use crate::{
     CLAIMS_CONFIG, CRON_QUEUE_AMT_BITWIDTH, CRON_QUEUE_HAMT_BITWIDTH, Claim, ClaimsMap, CronEvent,
-    State, consensus_miner_min_power,
+    MAX_EXPERIMENTAL_SEAL_PROOFS, State, consensus_miner_min_power,
 };
 
 pub struct MinerCronEvent {
@@ -50,6 +50,10 @@ pub fn check_state_invariants<BS: Blockstore>(
         !state.total_qa_bytes_committed.is_negative(),
         format!("total qa power committed is negative {}", state.total_qa_bytes_committed),
     );
+    acc.require(
+        state.experimental_seal_proofs.len() <= MAX_EXPERIMENTAL_SEAL_PROOFS,
+        format!("{} experimental seal proofs registered", state.experimental_seal_proofs.len()),
+    );
 
     acc.require(
         state.total_raw_byte_power <= state.total_quality_adj_power,
@@ -93,6 +97,7 @@ fn check_cron_invariants<BS: Blockstore>(
     acc: &MessageAccumulator,
 ) -> CronEventsByAddress {
     let mut cron_events_by_address = CronEventsByAddress::new();
+    let mut event_count = 0;
 
     match Multimap::from_root(
         store,
@@ -119,6 +124,7 @@ fn check_cron_invariants<BS: Blockstore>(
                 );
                 events
                     .for_each(|_, event| {
+                        event_count += 1;
                         cron_events_by_address.entry(event.miner_addr).or_default().push(
                             MinerCronEvent { epoch, payload: event.callback_payload.clone() },
                         );
@@ -129,6 +135,13 @@ fn check_cron_invariants<BS: Blockstore>(
                     })
             });
             acc.require_no_error(ret, "error iterating cron tasks");
+            acc.require(
+                event_count == state.cron_event_count,
+                format!(
+                    "cron event count {} does not match {} queued events",
+                    state.cron_event_count, event_count
+                ),
+            );
         }
         Err(e) => acc.add(format!("error loading cron event queue: {e}")),
     }
@@ -1,18 +1,27 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use fil_actors_runtime::reward::FilterEstimate;
+use cid::Cid;
+use fil_actors_runtime::{BatchReturn, ProofBlock};
 use fvm_ipld_encoding::tuple::*;
-use fvm_ipld_encoding::{BytesDe, RawBytes, strict_bytes};
+use fvm_ipld_encoding::{BytesDe, strict_bytes};
 use fvm_shared::ActorID;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
-use fvm_shared::sector::{RegisteredPoStProof, StoragePower};
+use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, StoragePower};
+use fvm_shared::version::NetworkVersion;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{Claim, PowerCheckpoint};
+
+pub use fil_actors_runtime::builtin::power::{
+    CurrentTotalPowerReturn, EnrollCronEventParams, GetExperimentalSealProofsReturn,
+    UpdateClaimedPowerParams,
+};
+
 pub type SectorTermination = i64;
 
 /// Implicit termination after all deals expire
@@ -41,17 +50,30 @@ pub struct CreateMinerReturn {
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
-pub struct UpdateClaimedPowerParams {
-    #[serde(with = "bigint_ser")]
-    pub raw_byte_delta: StoragePower,
-    #[serde(with = "bigint_ser")]
-    pub quality_adjusted_delta: StoragePower,
+pub struct CreateMinersParams {
+    /// Owner of every miner created.
+    pub owner: Address,
+    /// Miners to create, in order.
+    pub miners: Vec<CreateMinersEntry>,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
-pub struct EnrollCronEventParams {
-    pub event_epoch: ChainEpoch,
-    pub payload: RawBytes,
+pub struct CreateMinersEntry {
+    pub worker: Address,
+    pub window_post_proof_type: RegisteredPoStProof,
+    #[serde(with = "strict_bytes")]
+    pub peer: Vec<u8>,
+    pub multiaddrs: Vec<BytesDe>,
+    /// Funds sent to the new miner, out of the value sent with the message.
+    /// The values of all entries must sum to the value sent.
+    pub value: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct CreateMinersReturn {
+    /// Addresses of the created miners, in the order of the parameters.
+    pub miners: Vec<CreateMinerReturn>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
@@ -60,18 +82,6 @@ pub struct UpdatePledgeTotalParams {
     pub pledge_delta: TokenAmount,
 }
 
-#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
-pub struct CurrentTotalPowerReturn {
-    #[serde(with = "bigint_ser")]
-    pub raw_byte_power: StoragePower,
-    #[serde(with = "bigint_ser")]
-    pub quality_adj_power: StoragePower,
-    pub pledge_collateral: TokenAmount,
-    pub quality_adj_power_smoothed: FilterEstimate,
-    pub ramp_start_epoch: i64,
-    pub ramp_duration_epochs: u64,
-}
-
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
 pub struct NetworkRawPowerReturn {
@@ -106,6 +116,65 @@ pub struct MinerPowerReturn {
     pub quality_adj_power: StoragePower,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetMinerPowerBatchParams {
+    pub miners: Vec<ActorID>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetMinerPowerBatchReturn {
+    /// Status of each miner queried.
+    pub results: BatchReturn,
+    /// Power of the miners that were successfully queried.
+    pub powers: Vec<MinerPowerReturn>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct BatchGetMinerInfoParams {
+    pub miners: Vec<ActorID>,
+}
+
+/// A miner's control addresses and active beneficiary.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct MinerControlInfo {
+    pub owner: Address,
+    pub worker: Address,
+    pub control_addresses: Vec<Address>,
+    pub beneficiary: Address,
+    /// The total amount the beneficiary can withdraw.
+    pub beneficiary_quota: TokenAmount,
+    /// The amount of quota the beneficiary has already withdrawn.
+    pub beneficiary_used_quota: TokenAmount,
+    /// The epoch at which the beneficiary's rights expire and revert to the owner.
+    pub beneficiary_expiration: ChainEpoch,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct BatchGetMinerInfoReturn {
+    /// Status of each miner queried.
+    pub results: BatchReturn,
+    /// Information for the miners that were successfully queried.
+    pub infos: Vec<MinerControlInfo>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct MinerClaimProofParams {
+    pub miner: ActorID,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct MinerClaimProofReturn {
+    /// The power actor state root against which the proof is made.
+    pub state_root: Cid,
+    /// The miner's claim, or None if the miner has no claim.
+    pub claim: Option<Claim>,
+    /// The blocks traversed from the state root to the claim (or to its absence).
+    pub proof: Vec<ProofBlock>,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 #[serde(transparent)]
 pub struct MinerCountReturn {
@@ -117,3 +186,104 @@ pub struct MinerCountReturn {
 pub struct MinerConsensusCountReturn {
     pub miner_consensus_count: i64,
 }
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListMinersParams {
+    /// Miner after which to continue listing, as returned by a previous call.
+    /// If absent, listing starts from the lowest miner ID.
+    pub cursor: Option<ActorID>,
+    /// Maximum number of miners to return. Must be positive.
+    pub limit: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListedMiner {
+    pub miner: ActorID,
+    pub claim: Claim,
+    /// Whether the miner's raw power meets the consensus minimum for its proof type.
+    pub meets_consensus_minimum: bool,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ListMinersReturn {
+    pub miners: Vec<ListedMiner>,
+    /// Cursor from which to continue listing, absent if there are no more miners.
+    pub next_cursor: Option<ActorID>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct MinerCountsReturn {
+    /// The number of registered miners.
+    pub miner_count: i64,
+    /// The number of miners with raw power meeting the consensus minimum.
+    pub above_consensus_minimum: i64,
+    /// The number of miners with raw power below the consensus minimum, including those
+    /// with no power.
+    pub below_consensus_minimum: i64,
+}
+
+/// The window PoSt proof types for which consensus minimum power may be reported.
+pub const WINDOW_POST_PROOF_TYPES: &[RegisteredPoStProof] = &[
+    RegisteredPoStProof::StackedDRGWindow2KiBV1P1,
+    RegisteredPoStProof::StackedDRGWindow8MiBV1P1,
+    RegisteredPoStProof::StackedDRGWindow512MiBV1P1,
+    RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+    RegisteredPoStProof::StackedDRGWindow64GiBV1P1,
+];
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ConsensusMinPower {
+    pub window_post_proof_type: RegisteredPoStProof,
+    #[serde(with = "bigint_ser")]
+    pub min_power: StoragePower,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct ConsensusMinPowerReturn {
+    /// The network version at which the thresholds apply.
+    pub network_version: NetworkVersion,
+    /// The minimum raw power for consensus, for each window PoSt proof type accepted for new miners.
+    pub thresholds: Vec<ConsensusMinPower>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetCronQueueStatsParams {
+    /// First epoch for which to count queued events.
+    pub start_epoch: ChainEpoch,
+    /// Epoch after the last for which to count queued events.
+    pub end_epoch: ChainEpoch,
+}
+
+/// The number of cron events queued for an epoch.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CronQueueBucket {
+    pub epoch: ChainEpoch,
+    pub events: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetCronQueueStatsReturn {
+    /// First epoch which cron will next process.
+    pub first_cron_epoch: ChainEpoch,
+    /// Number of events in the queue, across all epochs.
+    pub total_events: u64,
+    /// Number of events queued for each epoch in the requested range with any, in epoch order.
+    pub buckets: Vec<CronQueueBucket>,
+    /// Number of seal proofs awaiting batch verification. Always zero since FIP 0084 removed
+    /// batch verification.
+    pub proof_validation_backlog: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetPowerHistoryReturn {
+    /// Checkpoints of total network power, oldest first.
+    pub checkpoints: Vec<PowerCheckpoint>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct RegisterExperimentalSealProofsParams {
+    /// Seal proof types to register. Those already registered are ignored.
+    pub proofs: Vec<RegisteredSealProof>,
+}
@@ -13,7 +13,7 @@ use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, StoragePower};
 use lazy_static::lazy_static;
-use num_traits::Zero;
+use num_traits::{Signed, Zero};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 
@@ -27,12 +27,13 @@ use fil_actor_power::ext::reward::UPDATE_NETWORK_KPI;
 use fil_actor_power::testing::check_state_invariants;
 use fil_actor_power::{
     Claim, CreateMinerParams, CreateMinerReturn, CurrentTotalPowerReturn, Method, State,
-    UpdateClaimedPowerParams, ext,
+    UpdateClaimedPowerParams, consensus_miner_min_power, ext,
 };
 use fil_actor_power::{CronEvent, MinerConsensusCountReturn};
 use fil_actor_power::{MinerCountReturn, epoch_key};
-use fil_actors_runtime::REWARD_ACTOR_ADDR;
-use fil_actors_runtime::builtin::reward::{FilterEstimate, ThisEpochRewardReturn};
+use fil_actors_runtime::builtin::reward::{
+    EpochRewardNotification, FilterEstimate, ThisEpochRewardReturn,
+};
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::runtime::RuntimePolicy;
 use fil_actors_runtime::runtime::builtins::Type;
@@ -44,6 +45,7 @@ use fil_actors_runtime::{
     ActorError, INIT_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
 };
 use fil_actors_runtime::{CRON_ACTOR_ADDR, DEFAULT_HAMT_CONFIG};
+use fil_actors_runtime::{EventBuilder, REWARD_ACTOR_ADDR};
 use fil_actors_runtime::{Map2, MapKey, Multimap};
 
 use crate::PowerActor;
@@ -156,6 +158,10 @@ impl Harness {
             IpldBlock::serialize_cbor(&create_miner_ret).unwrap(),
             ExitCode::OK,
         );
+        expect_miner_event(rt, "miner-added", miner);
+        if !consensus_miner_min_power(&rt.policy, window_post_proof_type).unwrap().is_positive() {
+            expect_miner_event(rt, "miner-above-consensus-minimum", miner);
+        }
         let params = CreateMinerParams {
             owner: *owner,
             worker: *worker,
@@ -314,6 +320,16 @@ impl Harness {
             raw_byte_delta: raw_delta.clone(),
             quality_adjusted_delta: qa_delta.clone(),
         };
+        let min_power =
+            consensus_miner_min_power(&rt.policy, prev_cl.window_post_proof_type).unwrap();
+        let prev_below = prev_cl.raw_byte_power < min_power;
+        let now_below = &prev_cl.raw_byte_power + raw_delta < min_power;
+        if prev_below && !now_below {
+            expect_miner_event(rt, "miner-above-consensus-minimum", &miner);
+        } else if !prev_below && now_below {
+            expect_miner_event(rt, "miner-below-consensus-minimum", &miner);
+        }
+
         rt.set_caller(*MINER_ACTOR_CODE_ID, miner);
         rt.expect_validate_caller_type(vec![Type::Miner]);
         rt.call::<PowerActor>(
@@ -385,13 +401,32 @@ impl Harness {
         );
     }
 
+    pub fn notify_epoch_reward(
+        &self,
+        rt: &MockRuntime,
+        notification: &EpochRewardNotification,
+    ) -> Result<(), ActorError> {
+        rt.expect_validate_caller_addr(vec![REWARD_ACTOR_ADDR]);
+        let ret = rt.call::<PowerActor>(
+            Method::NotifyEpochRewardExported as u64,
+            IpldBlock::serialize_cbor(notification).unwrap(),
+        )?;
+        assert!(ret.is_none());
+        rt.verify();
+        Ok(())
+    }
+
     pub fn on_epoch_tick_end(
         &self,
         rt: &MockRuntime,
         current_epoch: ChainEpoch,
         expected_raw_power: &StoragePower,
     ) {
-        self.expect_query_network_info(rt);
+        // The reward actor is queried only if it has not pushed a reward.
+        let state: State = rt.get_state();
+        if state.last_epoch_reward.is_none() {
+            self.expect_query_network_info(rt);
+        }
         // expect power sends to reward actor
         rt.expect_send_simple(
             REWARD_ACTOR_ADDR,
@@ -428,6 +463,13 @@ where
     Ok(ret_keys)
 }
 
+/// Expects the power actor to emit an event of a type concerning a miner.
+pub fn expect_miner_event(rt: &MockRuntime, typ: &str, miner: &Address) {
+    rt.expect_emitted_event(
+        EventBuilder::new().typ(typ).field_indexed("miner", &miner.id().unwrap()).build().unwrap(),
+    );
+}
+
 pub fn verify_empty_map(rt: &MockRuntime, key: Cid) {
     let map =
         Map2::<_, Vec<u8>, Vec<u8>>::load(&rt.store, &key, DEFAULT_HAMT_CONFIG, "empty?").unwrap();
@@ -1,27 +1,48 @@
 use fil_actor_power::ext::init::{EXEC_METHOD, ExecParams};
-use fil_actor_power::ext::miner::MinerConstructorParams;
+use fil_actor_power::ext::miner::{
+    ActiveBeneficiary, BeneficiaryTerm, CONTROL_ADDRESSES_METHOD, GET_BENEFICIARY_METHOD,
+    GetBeneficiaryReturn, GetControlAddressesReturn, MinerConstructorParams,
+};
+use fil_actors_runtime::runtime::EMPTY_ARR_CID;
+use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::test_utils::{
-    ACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID, MINER_ACTOR_CODE_ID, SYSTEM_ACTOR_CODE_ID,
-    expect_abort, expect_abort_contains_message,
+    ACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID, MINER_ACTOR_CODE_ID, MockRuntime,
+    SYSTEM_ACTOR_CODE_ID, expect_abort, expect_abort_contains_message,
 };
-use fil_actors_runtime::{INIT_ACTOR_ADDR, runtime::Policy};
-use fvm_ipld_encoding::{BytesDe, RawBytes};
-use fvm_shared::MethodNum;
+use fil_actors_runtime::{HAMT_BIT_WIDTH, INIT_ACTOR_ADDR, Multimap, runtime::Policy};
+use fvm_ipld_blockstore::{Block, Blockstore};
+use fvm_ipld_encoding::{BytesDe, DAG_CBOR, RawBytes};
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::sector::{RegisteredPoStProof, StoragePower};
+use fvm_shared::randomness::Randomness;
+use fvm_shared::sector::{
+    RegisteredPoStProof, RegisteredSealProof, SealVerifyInfo, SectorID, StoragePower,
+};
+use fvm_shared::sys::SendFlags;
+use fvm_shared::version::NetworkVersion;
+use fvm_shared::{ActorID, MethodNum};
+use multihash_codetable::Code;
 use num_traits::Zero;
 use std::ops::Neg;
 
 use fil_actor_power::{
-    Actor as PowerActor, Actor, CONSENSUS_MINER_MIN_MINERS, CreateMinerParams, CreateMinerReturn,
-    EnrollCronEventParams, Method, MinerPowerParams, MinerPowerReturn, MinerRawPowerParams,
-    MinerRawPowerReturn, NetworkRawPowerReturn, State, UpdateClaimedPowerParams,
-    consensus_miner_min_power,
+    Actor as PowerActor, Actor, BatchGetMinerInfoParams, BatchGetMinerInfoReturn,
+    CONSENSUS_MINER_MIN_MINERS, CREATE_MINERS_MAX_BATCH, CRON_QUEUE_STATS_MAX_EPOCHS,
+    ConsensusMinPowerReturn, CreateMinerParams, CreateMinerReturn, CreateMinersEntry,
+    CreateMinersParams, CreateMinersReturn, CronQueueBucket, EnrollCronEventParams,
+    GET_MINER_INFO_MAX_BATCH, GET_MINER_POWER_MAX_BATCH, GetCronQueueStatsParams,
+    GetCronQueueStatsReturn, GetExperimentalSealProofsReturn, GetMinerPowerBatchParams,
+    GetMinerPowerBatchReturn, GetPowerHistoryReturn, ListMinersParams, ListMinersReturn,
+    MAX_EXPERIMENTAL_SEAL_PROOFS, Method, MinerClaimProofParams, MinerClaimProofReturn,
+    MinerControlInfo, MinerCountsReturn, MinerPowerParams, MinerPowerReturn, MinerRawPowerParams,
+    MinerRawPowerReturn, NetworkRawPowerReturn, POWER_CHECKPOINT_HISTORY,
+    POWER_CHECKPOINT_INTERVAL, PROOF_VALIDATION_BATCH_AMT_BITWIDTH, PowerCheckpoint,
+    RegisterExperimentalSealProofsParams, State, UpdateClaimedPowerParams, WINDOW_POST_PROOF_TYPES,
+    consensus_miner_min_power, verify_miner_claim,
 };
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -134,6 +155,121 @@ fn create_miner_given_send_to_init_actor_fails_should_fail() {
     h.check_state(&rt);
 }
 
+#[test]
+fn create_miners() {
+    let (h, rt) = setup();
+    let workers = [Address::new_id(301), Address::new_id(302)];
+    let created = [
+        CreateMinerReturn { id_address: Address::new_id(201), robust_address: *ACTOR },
+        CreateMinerReturn {
+            id_address: Address::new_id(202),
+            robust_address: Address::new_actor(b"actor2"),
+        },
+    ];
+    let entries: Vec<_> = workers
+        .iter()
+        .enumerate()
+        .map(|(i, worker)| CreateMinersEntry {
+            worker: *worker,
+            window_post_proof_type: RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+            peer: format!("miner{}", i).into_bytes(),
+            multiaddrs: vec![],
+            value: TokenAmount::from_atto(10 * (i + 1)),
+        })
+        .collect();
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *OWNER);
+    rt.set_received(TokenAmount::from_atto(30));
+    rt.set_balance(TokenAmount::from_atto(30));
+    rt.expect_validate_caller_any();
+    // Miners are created in order, each with its own worker and value.
+    for (entry, ret) in entries.iter().zip(created.iter()) {
+        let init_params = ExecParams {
+            code_cid: *MINER_ACTOR_CODE_ID,
+            constructor_params: RawBytes::serialize(MinerConstructorParams {
+                owner: *OWNER,
+                worker: entry.worker,
+                control_addresses: vec![],
+                window_post_proof_type: entry.window_post_proof_type,
+                peer_id: entry.peer.clone(),
+                multi_addresses: vec![],
+            })
+            .unwrap(),
+        };
+        rt.expect_send_simple(
+            INIT_ACTOR_ADDR,
+            EXEC_METHOD,
+            IpldBlock::serialize_cbor(&init_params).unwrap(),
+            entry.value.clone(),
+            IpldBlock::serialize_cbor(ret).unwrap(),
+            ExitCode::OK,
+        );
+        expect_miner_event(&rt, "miner-added", &ret.id_address);
+    }
+    let ret: CreateMinersReturn = rt
+        .call::<PowerActor>(
+            Method::CreateMinersExported as MethodNum,
+            IpldBlock::serialize_cbor(&CreateMinersParams { owner: *OWNER, miners: entries })
+                .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(created.to_vec(), ret.miners);
+    for miner in created.iter() {
+        assert!(h.get_claim(&rt, &miner.id_address).is_some());
+    }
+    let st: State = rt.get_state();
+    assert_eq!(2, st.miner_count);
+    h.check_state(&rt);
+}
+
+#[test]
+fn create_miners_rejects_invalid_batch() {
+    let (h, rt) = setup();
+    let entry = CreateMinersEntry {
+        worker: *OWNER,
+        window_post_proof_type: RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        peer: vec![],
+        multiaddrs: vec![],
+        value: TokenAmount::from_atto(10),
+    };
+    let cases = [
+        (vec![], TokenAmount::zero(), "no miners"),
+        (
+            vec![entry.clone(); CREATE_MINERS_MAX_BATCH + 1],
+            TokenAmount::from_atto(10 * (CREATE_MINERS_MAX_BATCH as u64 + 1)),
+            "too many miners",
+        ),
+        (vec![entry.clone()], TokenAmount::from_atto(5), "miner values total"),
+        (
+            vec![CreateMinersEntry { value: TokenAmount::from_atto(-1), ..entry }],
+            TokenAmount::from_atto(-1),
+            "negative value",
+        ),
+    ];
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *OWNER);
+    for (miners, value, msg) in cases {
+        rt.set_received(value);
+        rt.expect_validate_caller_any();
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            msg,
+            rt.call::<PowerActor>(
+                Method::CreateMinersExported as MethodNum,
+                IpldBlock::serialize_cbor(&CreateMinersParams { owner: *OWNER, miners }).unwrap(),
+            ),
+        );
+        rt.verify();
+    }
+    let st: State = rt.get_state();
+    assert_eq!(0, st.miner_count);
+    h.check_state(&rt);
+}
+
 #[test]
 fn claimed_power_given_caller_is_not_storage_miner_should_fail() {
     let (h, rt) = setup();
@@ -273,6 +409,114 @@ fn enroll_cron_epoch_multiple_events() {
     h.check_state(&rt);
 }
 
+#[test]
+fn cron_queue_stats() {
+    let (mut h, rt) = setup();
+    let miner2 = Address::new_id(501);
+    h.create_miner_basic(&rt, *OWNER, *OWNER, *MINER).unwrap();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, miner2).unwrap();
+
+    h.enroll_cron_event(&rt, 1, &MINER, &RawBytes::default()).unwrap();
+    h.enroll_cron_event(&rt, 1, &MINER, &RawBytes::default()).unwrap();
+    h.enroll_cron_event(&rt, 3, &miner2, &RawBytes::default()).unwrap();
+
+    let get_cron_queue_stats = |rt: &MockRuntime, start_epoch, end_epoch| {
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<Actor>(
+            Method::GetCronQueueStatsExported as u64,
+            IpldBlock::serialize_cbor(&GetCronQueueStatsParams { start_epoch, end_epoch }).unwrap(),
+        );
+        rt.verify();
+        ret.map(|r| r.unwrap().deserialize::<GetCronQueueStatsReturn>().unwrap())
+    };
+
+    let stats = get_cron_queue_stats(&rt, 0, 5).unwrap();
+    assert_eq!(0, stats.first_cron_epoch);
+    assert_eq!(3, stats.total_events);
+    assert_eq!(0, stats.proof_validation_backlog);
+    assert_eq!(
+        vec![CronQueueBucket { epoch: 1, events: 2 }, CronQueueBucket { epoch: 3, events: 1 }],
+        stats.buckets
+    );
+    // Only epochs in the range are counted.
+    assert_eq!(
+        vec![CronQueueBucket { epoch: 3, events: 1 }],
+        get_cron_queue_stats(&rt, 2, 4).unwrap().buckets
+    );
+
+    // Processed events leave the queue. The first miner's events are skipped as it has no claim.
+    h.delete_claim(&rt, &MINER);
+    h.on_epoch_tick_end(&rt, 2, &StoragePower::zero());
+    let stats = get_cron_queue_stats(&rt, 0, 5).unwrap();
+    assert_eq!(3, stats.first_cron_epoch);
+    assert_eq!(1, stats.total_events);
+    assert_eq!(vec![CronQueueBucket { epoch: 3, events: 1 }], stats.buckets);
+
+    // The range must be ordered and bounded.
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, get_cron_queue_stats(&rt, 5, 4));
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        get_cron_queue_stats(&rt, 0, CRON_QUEUE_STATS_MAX_EPOCHS + 1),
+    );
+    h.check_state(&rt);
+}
+
+#[test]
+fn cron_tick_with_understated_event_count() {
+    let (mut h, rt) = setup();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, *MINER).unwrap();
+    h.enroll_cron_event(&rt, 1, &MINER, &RawBytes::default()).unwrap();
+    h.delete_claim(&rt, &MINER);
+
+    // State which has not been migrated counts no events, though some are queued.
+    let mut st: State = rt.get_state();
+    st.cron_event_count = 0;
+    rt.replace_state(&st);
+
+    h.on_epoch_tick_end(&rt, 2, &StoragePower::zero());
+    let st: State = rt.get_state();
+    assert_eq!(0, st.cron_event_count);
+}
+
+#[test]
+fn cron_queue_stats_reports_proof_validation_backlog() {
+    let (mut h, rt) = setup();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, *MINER).unwrap();
+
+    // Proofs left awaiting batch verification from before FIP 0084 are counted.
+    let mut batch = Multimap::new(&rt.store, HAMT_BIT_WIDTH, PROOF_VALIDATION_BATCH_AMT_BITWIDTH);
+    for number in 0..2 {
+        let info = SealVerifyInfo {
+            registered_proof: RegisteredSealProof::StackedDRG32GiBV1P1,
+            sector_id: SectorID { miner: MINER.id().unwrap(), number },
+            deal_ids: vec![],
+            randomness: Randomness(vec![]),
+            interactive_randomness: Randomness(vec![]),
+            proof: vec![],
+            sealed_cid: EMPTY_ARR_CID,
+            unsealed_cid: EMPTY_ARR_CID,
+        };
+        batch.add(MINER.to_bytes().into(), info).unwrap();
+    }
+    let mut st: State = rt.get_state();
+    st.proof_validation_batch = Some(batch.root().unwrap());
+    rt.replace_state(&st);
+
+    rt.expect_validate_caller_any();
+    let stats: GetCronQueueStatsReturn = rt
+        .call::<Actor>(
+            Method::GetCronQueueStatsExported as u64,
+            IpldBlock::serialize_cbor(&GetCronQueueStatsParams { start_epoch: 0, end_epoch: 1 })
+                .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(2, stats.proof_validation_backlog);
+}
+
 #[test]
 fn enroll_cron_epoch_before_current_epoch() {
     let (mut h, rt) = setup();
@@ -628,6 +872,436 @@ fn get_network_and_miner_power() {
     h.check_state(&rt);
 }
 
+#[test]
+fn consensus_min_power_lists_enabled_proof_types() {
+    let (h, rt) = setup();
+    rt.set_network_version(NetworkVersion::V21);
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_any();
+    let ret: ConsensusMinPowerReturn = rt
+        .call::<Actor>(Method::ConsensusMinPowerExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(NetworkVersion::V21, ret.network_version);
+    let enabled: Vec<_> = WINDOW_POST_PROOF_TYPES
+        .iter()
+        .filter(|proof| rt.policy.valid_post_proof_type.contains(**proof))
+        .copied()
+        .collect();
+    assert!(!enabled.is_empty());
+    assert_eq!(
+        enabled,
+        ret.thresholds.iter().map(|t| t.window_post_proof_type).collect::<Vec<_>>()
+    );
+    for threshold in ret.thresholds {
+        assert_eq!(
+            consensus_miner_min_power(&rt.policy, threshold.window_post_proof_type).unwrap(),
+            threshold.min_power
+        );
+    }
+    h.check_state(&rt);
+}
+
+#[test]
+fn register_experimental_seal_proofs() {
+    let (h, mut rt) = setup();
+    let governor = Address::new_id(1234);
+    let register = |rt: &MockRuntime, caller: Address, proofs: Vec<RegisteredSealProof>| {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+        rt.expect_validate_caller_addr(
+            rt.policy.experimental_seal_proofs_governor.map(Address::new_id).into_iter().collect(),
+        );
+        let ret = rt.call::<Actor>(
+            Method::RegisterExperimentalSealProofsExported as u64,
+            IpldBlock::serialize_cbor(&RegisterExperimentalSealProofsParams { proofs }).unwrap(),
+        );
+        rt.verify();
+        ret
+    };
+    let experimental = RegisteredSealProof::Invalid(100);
+
+    // Registration is refused unless the policy allows it and names a governor.
+    expect_abort(ExitCode::USR_FORBIDDEN, register(&rt, governor, vec![experimental]));
+    rt.reset();
+    rt.set_policy(Policy { allow_experimental_seal_proofs: true, ..Policy::default() });
+    expect_abort(ExitCode::USR_FORBIDDEN, register(&rt, governor, vec![experimental]));
+    rt.reset();
+    rt.set_policy(Policy {
+        allow_experimental_seal_proofs: false,
+        experimental_seal_proofs_governor: Some(governor.id().unwrap()),
+        ..Policy::default()
+    });
+    expect_abort(ExitCode::USR_FORBIDDEN, register(&rt, governor, vec![experimental]));
+    rt.reset();
+
+    // Only the governor may register types.
+    rt.set_policy(Policy {
+        allow_experimental_seal_proofs: true,
+        experimental_seal_proofs_governor: Some(governor.id().unwrap()),
+        ..Policy::default()
+    });
+    expect_abort(ExitCode::USR_FORBIDDEN, register(&rt, Address::new_id(1235), vec![experimental]));
+    rt.reset();
+
+    register(
+        &rt,
+        governor,
+        vec![experimental, RegisteredSealProof::StackedDRG8MiBV1, experimental],
+    )
+    .unwrap();
+    rt.expect_validate_caller_any();
+    let ret: GetExperimentalSealProofsReturn = rt
+        .call::<Actor>(Method::GetExperimentalSealProofsExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(vec![experimental, RegisteredSealProof::StackedDRG8MiBV1], ret.proofs);
+
+    // Types already accepted by policy cannot be registered.
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "already supported",
+        register(&rt, governor, vec![RegisteredSealProof::StackedDRG32GiBV1P1]),
+    );
+    rt.reset();
+
+    // The number of registered types is bounded.
+    let too_many =
+        (0..MAX_EXPERIMENTAL_SEAL_PROOFS as i64).map(|i| RegisteredSealProof::Invalid(200 + i));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "cannot register more than",
+        register(&rt, governor, too_many.collect()),
+    );
+    rt.reset();
+    h.check_state(&rt);
+}
+
+#[test]
+fn miner_claim_proof_verifies_against_state_root() {
+    let power_unit = &consensus_miner_min_power(
+        &Policy::default(),
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+    )
+    .unwrap();
+
+    let (mut h, rt) = setup();
+
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER1).unwrap();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER2).unwrap();
+    h.update_claimed_power(&rt, MINER1, power_unit, &(power_unit * 2));
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    let claim_proof = |miner: ActorID| -> MinerClaimProofReturn {
+        rt.expect_validate_caller_any();
+        let ret = rt
+            .call::<Actor>(
+                Method::MinerClaimProofExported as u64,
+                IpldBlock::serialize_cbor(&MinerClaimProofParams { miner }).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret
+    };
+
+    // A claim is proven against the current state root.
+    let ret = claim_proof(MINER1.id().unwrap());
+    assert_eq!(rt.get_state_root().unwrap(), ret.state_root);
+    let claim = ret.claim.clone().unwrap();
+    assert_eq!(power_unit, &claim.raw_byte_power);
+    assert_eq!(&(power_unit * 2), &claim.quality_adj_power);
+    assert_eq!(
+        Some(claim),
+        verify_miner_claim(&ret.state_root, MINER1.id().unwrap(), &ret.proof).unwrap()
+    );
+
+    // An incomplete proof is rejected.
+    assert_eq!(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        verify_miner_claim(&ret.state_root, MINER1.id().unwrap(), &ret.proof[1..])
+            .unwrap_err()
+            .exit_code()
+    );
+
+    // A tampered block is rejected.
+    let mut tampered = ret.proof.clone();
+    tampered.last_mut().unwrap().data.push(0);
+    assert_eq!(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        verify_miner_claim(&ret.state_root, MINER1.id().unwrap(), &tampered)
+            .unwrap_err()
+            .exit_code()
+    );
+
+    // Absence of a claim is also proven.
+    let unknown = 999;
+    let ret = claim_proof(unknown);
+    assert_eq!(None, ret.claim);
+    assert_eq!(None, verify_miner_claim(&ret.state_root, unknown, &ret.proof).unwrap());
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn batch_get_miner_info() {
+    let (mut h, rt) = setup();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER1).unwrap();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER2).unwrap();
+
+    let worker = Address::new_id(102);
+    let control = Address::new_id(103);
+    let beneficiary = Address::new_id(104);
+    expect_miner_read(
+        &rt,
+        MINER1,
+        CONTROL_ADDRESSES_METHOD,
+        IpldBlock::serialize_cbor(&GetControlAddressesReturn {
+            owner: *OWNER,
+            worker,
+            control_addresses: vec![control],
+            control_address_roles: vec![],
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    expect_miner_read(
+        &rt,
+        MINER1,
+        GET_BENEFICIARY_METHOD,
+        IpldBlock::serialize_cbor(&GetBeneficiaryReturn {
+            active: ActiveBeneficiary {
+                beneficiary,
+                term: BeneficiaryTerm {
+                    quota: TokenAmount::from_whole(10),
+                    used_quota: TokenAmount::from_whole(3),
+                    expiration: 1000,
+                },
+            },
+            proposed: None,
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    // A failed call to one miner fails only that miner.
+    expect_miner_read(&rt, MINER2, CONTROL_ADDRESSES_METHOD, None, ExitCode::USR_FORBIDDEN);
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_any();
+    let ret: BatchGetMinerInfoReturn = rt
+        .call::<Actor>(
+            Method::BatchGetMinerInfoExported as u64,
+            IpldBlock::serialize_cbor(&BatchGetMinerInfoParams {
+                miners: vec![MINER1.id().unwrap(), MINER2.id().unwrap(), 999],
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(
+        vec![ExitCode::OK, ExitCode::USR_FORBIDDEN, ExitCode::USR_NOT_FOUND],
+        ret.results.codes()
+    );
+    assert_eq!(
+        vec![MinerControlInfo {
+            owner: *OWNER,
+            worker,
+            control_addresses: vec![control],
+            beneficiary,
+            beneficiary_quota: TokenAmount::from_whole(10),
+            beneficiary_used_quota: TokenAmount::from_whole(3),
+            beneficiary_expiration: 1000,
+        }],
+        ret.infos
+    );
+
+    rt.expect_validate_caller_any();
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "too many miners",
+        rt.call::<Actor>(
+            Method::BatchGetMinerInfoExported as u64,
+            IpldBlock::serialize_cbor(&BatchGetMinerInfoParams {
+                miners: vec![MINER1.id().unwrap(); GET_MINER_INFO_MAX_BATCH + 1],
+            })
+            .unwrap(),
+        ),
+    );
+    rt.verify();
+    h.check_state(&rt);
+}
+
+#[test]
+fn get_miner_power_batch() {
+    let (mut h, rt) = setup();
+    let power_unit = &StoragePower::from(1_000_000);
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER1).unwrap();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER2).unwrap();
+    h.update_claimed_power(&rt, MINER1, power_unit, &(power_unit * 2));
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_any();
+    let ret: GetMinerPowerBatchReturn = rt
+        .call::<Actor>(
+            Method::GetMinerPowerBatchExported as u64,
+            IpldBlock::serialize_cbor(&GetMinerPowerBatchParams {
+                miners: vec![MINER1.id().unwrap(), 999, MINER2.id().unwrap()],
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(vec![ExitCode::OK, ExitCode::USR_NOT_FOUND, ExitCode::OK], ret.results.codes());
+    assert_eq!(
+        vec![
+            MinerPowerReturn {
+                raw_byte_power: power_unit.clone(),
+                quality_adj_power: power_unit * 2
+            },
+            MinerPowerReturn {
+                raw_byte_power: StoragePower::zero(),
+                quality_adj_power: StoragePower::zero()
+            },
+        ],
+        ret.powers
+    );
+
+    rt.expect_validate_caller_any();
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "too many miners",
+        rt.call::<Actor>(
+            Method::GetMinerPowerBatchExported as u64,
+            IpldBlock::serialize_cbor(&GetMinerPowerBatchParams {
+                miners: vec![MINER1.id().unwrap(); GET_MINER_POWER_MAX_BATCH + 1],
+            })
+            .unwrap(),
+        ),
+    );
+    rt.verify();
+    h.check_state(&rt);
+}
+
+#[test]
+fn list_miners_and_counts() {
+    let power_unit = &consensus_miner_min_power(
+        &Policy::default(),
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+    )
+    .unwrap();
+
+    let (mut h, rt) = setup();
+    for miner in [MINER1, MINER2, MINER3] {
+        h.create_miner_basic(&rt, *OWNER, *OWNER, miner).unwrap();
+    }
+    h.update_claimed_power(&rt, MINER2, power_unit, &(power_unit * 2));
+    // Below the minimum power, but counted while there are too few miners above it.
+    h.update_claimed_power(&rt, MINER3, &(power_unit / 2), &(power_unit / 2));
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    let list_miners = |cursor: Option<ActorID>, limit: u64| {
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<Actor>(
+            Method::ListMinersExported as u64,
+            IpldBlock::serialize_cbor(&ListMinersParams { cursor, limit }).unwrap(),
+        );
+        rt.verify();
+        ret.map(|r| r.unwrap().deserialize::<ListMinersReturn>().unwrap())
+    };
+
+    // Page through all miners in ID order.
+    let mut listed = vec![];
+    let mut cursor = None;
+    loop {
+        let ret = list_miners(cursor, 2).unwrap();
+        assert!(ret.miners.len() <= 2);
+        listed.extend(ret.miners);
+        cursor = ret.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(
+        vec![MINER1.id().unwrap(), MINER2.id().unwrap(), MINER3.id().unwrap()],
+        listed.iter().map(|m| m.miner).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![false, true, true],
+        listed.iter().map(|m| m.meets_consensus_minimum).collect::<Vec<_>>()
+    );
+    assert_eq!(power_unit, &listed[1].claim.raw_byte_power);
+    assert_eq!(&(power_unit * 2), &listed[1].claim.quality_adj_power);
+
+    // Listing resumes after a cursor which is not a registered miner.
+    let ret = list_miners(Some(MINER1.id().unwrap() - 1), 2).unwrap();
+    assert_eq!(
+        vec![MINER1.id().unwrap(), MINER2.id().unwrap()],
+        ret.miners.iter().map(|m| m.miner).collect::<Vec<_>>()
+    );
+    assert_eq!(Some(MINER2.id().unwrap()), ret.next_cursor);
+    let ret = list_miners(Some(999), 2).unwrap();
+    assert!(ret.miners.is_empty());
+    assert_eq!(None, ret.next_cursor);
+
+    // The limit must be positive.
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, list_miners(None, 0));
+
+    rt.expect_validate_caller_any();
+    let counts: MinerCountsReturn = rt
+        .call::<Actor>(Method::MinerCountsExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(
+        MinerCountsReturn {
+            miner_count: 3,
+            above_consensus_minimum: 1,
+            below_consensus_minimum: 2
+        },
+        counts
+    );
+    h.check_state(&rt);
+}
+
+fn expect_miner_read(
+    rt: &MockRuntime,
+    miner: Address,
+    method: MethodNum,
+    ret: Option<IpldBlock>,
+    exit_code: ExitCode,
+) {
+    rt.expect_send(
+        miner,
+        method,
+        None,
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+        ret,
+        exit_code,
+        None,
+    );
+}
+
 #[test]
 fn given_no_miner_claim_update_pledge_total_should_abort() {
     let (mut h, rt) = setup();
@@ -661,7 +1335,11 @@ mod cron_tests {
         miner::{DeferredCronEventParams, ON_DEFERRED_CRON_EVENT_METHOD},
         reward::UPDATE_NETWORK_KPI,
     };
-    use fil_actors_runtime::{CRON_ACTOR_ADDR, REWARD_ACTOR_ADDR, test_utils::CRON_ACTOR_CODE_ID};
+    use fil_actors_runtime::reward::{
+        EpochRewardNotification, FilterEstimate, ThisEpochRewardReturn,
+    };
+    use fil_actors_runtime::test_utils::{CRON_ACTOR_CODE_ID, REWARD_ACTOR_CODE_ID};
+    use fil_actors_runtime::{CRON_ACTOR_ADDR, REWARD_ACTOR_ADDR};
     use fvm_shared::bigint::BigInt;
 
     const OWNER: Address = Address::new_id(103);
@@ -692,6 +1370,67 @@ mod cron_tests {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn uses_epoch_reward_pushed_by_reward_actor() {
+        let (mut h, rt) = setup();
+        let miner = Address::new_id(101);
+        h.create_miner_basic(&rt, OWNER, OWNER, miner).unwrap();
+        let payload = vec![0x01, 0x03];
+        h.enroll_cron_event(&rt, 2, &miner, &RawBytes::from(payload.clone())).unwrap();
+
+        // Only the reward actor may push a reward.
+        let notification = EpochRewardNotification {
+            epoch: 2,
+            reward: ThisEpochRewardReturn {
+                this_epoch_reward_smoothed: FilterEstimate::new(BigInt::from(7), BigInt::zero()),
+                this_epoch_baseline_power: StoragePower::from(1 << 20),
+            },
+        };
+        rt.set_caller(*MINER_ACTOR_CODE_ID, miner);
+        expect_abort(ExitCode::USR_FORBIDDEN, h.notify_epoch_reward(&rt, &notification));
+        rt.reset();
+
+        rt.set_caller(*REWARD_ACTOR_CODE_ID, REWARD_ACTOR_ADDR);
+        h.notify_epoch_reward(&rt, &notification).unwrap();
+        let state: State = rt.get_state();
+        assert_eq!(Some(notification.clone()), state.last_epoch_reward);
+
+        // The tick passes the pushed reward to miners without querying the reward actor.
+        rt.set_epoch(2);
+        rt.expect_validate_caller_addr(vec![CRON_ACTOR_ADDR]);
+        rt.expect_send_simple(
+            miner,
+            ON_DEFERRED_CRON_EVENT_METHOD,
+            IpldBlock::serialize_cbor(&DeferredCronEventParams {
+                event_payload: payload,
+                reward_smoothed: notification.reward.this_epoch_reward_smoothed,
+                quality_adj_power_smoothed: state.this_epoch_qa_power_smoothed,
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        rt.expect_send_simple(
+            REWARD_ACTOR_ADDR,
+            RewardMethod::UpdateNetworkKPI as u64,
+            IpldBlock::serialize_cbor(&BigIntSer(&StoragePower::zero())).unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
+        rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
+        rt.verify();
+
+        // The tick discards the pushed reward. With no new reward pushed during the tick,
+        // as if the notification failed, the next tick queries the reward actor.
+        let state: State = rt.get_state();
+        assert_eq!(None, state.last_epoch_reward);
+        h.on_epoch_tick_end(&rt, 3, &StoragePower::zero());
+        h.check_state(&rt);
+    }
+
     #[test]
     fn amount_sent_to_reward_actor_and_state_change() {
         let (mut h, rt) = setup();
@@ -732,6 +1471,84 @@ mod cron_tests {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn records_power_checkpoints() {
+        let (mut h, rt) = setup();
+        let power_unit = &StoragePower::from(1 << 20);
+        let miner = Address::new_id(101);
+        h.create_miner_basic(&rt, OWNER, OWNER, miner).unwrap();
+        h.update_claimed_power(&rt, miner, power_unit, &(power_unit * 2));
+        h.on_epoch_tick_end(&rt, 0, power_unit);
+
+        // Only the first tick in each interval records a checkpoint.
+        h.update_claimed_power(&rt, miner, power_unit, power_unit);
+        h.on_epoch_tick_end(&rt, 1, &(power_unit * 2));
+        let next = POWER_CHECKPOINT_INTERVAL + 5;
+        h.on_epoch_tick_end(&rt, next, &(power_unit * 2));
+        assert_eq!(
+            vec![
+                PowerCheckpoint {
+                    epoch: 0,
+                    raw_byte_power: power_unit.clone(),
+                    quality_adj_power: power_unit * 2,
+                },
+                PowerCheckpoint {
+                    epoch: next,
+                    raw_byte_power: power_unit * 2,
+                    quality_adj_power: power_unit * 3,
+                },
+            ],
+            get_power_history(&rt)
+        );
+
+        // Once the history is full, each checkpoint replaces the oldest.
+        let wrapped = POWER_CHECKPOINT_INTERVAL * POWER_CHECKPOINT_HISTORY as i64;
+        h.on_epoch_tick_end(&rt, wrapped, &(power_unit * 2));
+        assert_eq!(
+            vec![next, wrapped],
+            get_power_history(&rt).iter().map(|c| c.epoch).collect::<Vec<_>>()
+        );
+        let st: State = rt.get_state();
+        assert_eq!(Some(wrapped), st.last_power_checkpoint);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn migrate_state_written_before_power_history() {
+        let (mut h, rt) = setup();
+        let miner = Address::new_id(101);
+        h.create_miner_basic(&rt, OWNER, OWNER, miner).unwrap();
+        h.enroll_cron_event(&rt, 3, &miner, &RawBytes::default()).unwrap();
+        h.enroll_cron_event(&rt, 4, &miner, &RawBytes::default()).unwrap();
+        let st: State = rt.get_state();
+        assert_eq!(2, st.cron_event_count);
+
+        // Encode the state as it was before the power history, last checkpoint, cron event
+        // count, last epoch reward and experimental seal proofs were appended.
+        let mut encoded = fvm_ipld_encoding::to_vec(&st).unwrap();
+        assert_eq!(0x96, encoded[0]);
+        assert_eq!([0xf6, 0xf6, 0x02, 0xf6, 0x80], encoded[encoded.len() - 5..]);
+        encoded[0] = 0x91;
+        encoded.truncate(encoded.len() - 5);
+        let root = rt.store.put(Code::Blake2b256, &Block::new(DAG_CBOR, &encoded)).unwrap();
+
+        let migrated = State::migrate(&rt.store, &root).unwrap();
+        assert_eq!(rt.get_state_root().unwrap(), migrated);
+        assert_eq!(migrated, State::migrate(&rt.store, &migrated).unwrap());
+    }
+
+    fn get_power_history(rt: &MockRuntime) -> Vec<PowerCheckpoint> {
+        rt.expect_validate_caller_any();
+        let ret: GetPowerHistoryReturn = rt
+            .call::<PowerActor>(Method::GetPowerHistoryExported as u64, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret.checkpoints
+    }
+
     #[test]
     fn event_scheduled_in_null_round_called_next_round() {
         let (mut h, rt) = setup();
@@ -1007,6 +1824,9 @@ mod cron_tests {
             None,
             ExitCode::OK,
         );
+        // the failed miner drops below the consensus minimum as it is removed
+        expect_miner_event(&rt, "miner-below-consensus-minimum", &miner1);
+        expect_miner_event(&rt, "miner-removed", &miner1);
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
 
@@ -1091,6 +1911,7 @@ fn create_miner_restricted_correctly() {
         IpldBlock::serialize_cbor(&create_miner_ret).unwrap(),
         ExitCode::OK,
     );
+    expect_miner_event(&rt, "miner-added", &MINER);
 
     let ret: CreateMinerReturn = rt
         .call::<PowerActor>(Method::CreateMinerExported as MethodNum, params)
@@ -0,0 +1,29 @@
+// A namespace for helpers that build and emit reward events.
+
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::ActorID;
+use fvm_shared::econ::TokenAmount;
+
+/// Indicates a block reward has been paid out for a miner's winning tickets.
+/// The reward includes the gas reward. If the miner failed to accept the reward it is not
+/// credited, the reward is burnt, and the penalty is not applied.
+pub fn block_reward_awarded(
+    rt: &impl Runtime,
+    miner: ActorID,
+    reward: &TokenAmount,
+    gas_reward: &TokenAmount,
+    penalty: &TokenAmount,
+    credited: bool,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("block-reward-awarded")
+            .field_indexed("miner", &miner)
+            .field_bigint("reward", reward.atto())
+            .field_bigint("gas-reward", gas_reward.atto())
+            .field_bigint("penalty", penalty.atto())
+            .field("credited", &credited)
+            .build()?,
+    )
+}
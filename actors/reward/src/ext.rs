@@ -12,3 +12,7 @@ pub mod miner {
         pub penalty: TokenAmount,
     }
 }
+
+pub mod subscriber {
+    pub const NOTIFY_EPOCH_REWARD_METHOD: u64 = frc42_dispatch::method_hash!("NotifyEpochReward");
+}
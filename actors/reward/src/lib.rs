@@ -13,6 +13,7 @@ use fvm_shared::econ::TokenAmount;
 use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
 use log::{error, warn};
 use num_derive::FromPrimitive;
+use num_traits::Zero;
 
 pub use self::logic::*;
 pub use self::state::{Reward, State, VestingFunction};
@@ -21,6 +22,7 @@ pub use self::types::*;
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+mod emit;
 pub(crate) mod expneg;
 mod logic;
 mod state;
@@ -36,6 +38,9 @@ pub mod ext;
 /// PenaltyMultiplier is the factor miner penalties are scaled up by
 pub const PENALTY_MULTIPLIER: u64 = 3;
 
+/// Builtin actors pushed the epoch reward each time it is computed.
+pub const EPOCH_REWARD_SUBSCRIBERS: &[Address] = &[STORAGE_POWER_ACTOR_ADDR];
+
 /// Reward actor methods available
 #[derive(FromPrimitive)]
 #[repr(u64)]
@@ -44,6 +49,8 @@ pub enum Method {
     AwardBlockReward = 2,
     ThisEpochReward = 3,
     UpdateNetworkKPI = 4,
+    // Method numbers derived from FRC-0042 standards
+    GetRewardEstimateExported = frc42_dispatch::method_hash!("GetRewardEstimate"),
 }
 
 /// Reward Actor
@@ -55,7 +62,7 @@ impl Actor {
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
 
         if let Some(power) = params.power.map(|v| v.0) {
-            rt.create(&State::new(power))?;
+            rt.create(&State::new(rt.policy(), power))?;
             Ok(())
         } else {
             Err(actor_error!(illegal_argument, "argument should not be nil"))
@@ -143,13 +150,17 @@ impl Actor {
         }
 
         // if this fails, we can assume the miner is responsible and avoid failing here.
-        let reward_params = ext::miner::ApplyRewardParams { reward: total_reward.clone(), penalty };
+        let reward_params = ext::miner::ApplyRewardParams {
+            reward: total_reward.clone(),
+            penalty: penalty.clone(),
+        };
         let res = extract_send_result(rt.send_simple(
             &Address::new_id(miner_id),
             ext::miner::APPLY_REWARDS_METHOD,
             IpldBlock::serialize_cbor(&reward_params)?,
             total_reward.clone(),
         ));
+        let credited = res.is_ok();
         if let Err(e) = res {
             error!(
                 "failed to send ApplyRewards call to the miner actor with funds {}, code: {:?}",
@@ -160,7 +171,7 @@ impl Actor {
                 &BURNT_FUNDS_ACTOR_ADDR,
                 METHOD_SEND,
                 None,
-                total_reward,
+                total_reward.clone(),
             ));
             if let Err(e) = res {
                 error!(
@@ -170,7 +181,14 @@ impl Actor {
             }
         }
 
-        Ok(())
+        emit::block_reward_awarded(
+            rt,
+            miner_id,
+            &total_reward,
+            &params.gas_reward,
+            &penalty,
+            credited,
+        )
     }
 
     /// The award value used for the current epoch, updated at the end of an epoch
@@ -179,9 +197,21 @@ impl Actor {
     fn this_epoch_reward(rt: &impl Runtime) -> Result<ThisEpochRewardReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let st: State = rt.state()?;
-        Ok(ThisEpochRewardReturn {
-            this_epoch_baseline_power: st.this_epoch_baseline_power,
+        Ok(st.this_epoch_reward_return())
+    }
+
+    /// Returns the current epoch's block reward together with the baseline power and
+    /// smoothed reward estimate from which rewards are projected.
+    fn get_reward_estimate(rt: &impl Runtime) -> Result<GetRewardEstimateReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        Ok(GetRewardEstimateReturn {
+            epoch: st.epoch,
+            this_epoch_reward: st.this_epoch_reward,
             this_epoch_reward_smoothed: st.this_epoch_reward_smoothed,
+            this_epoch_baseline_power: st.this_epoch_baseline_power,
+            effective_baseline_power: st.effective_baseline_power,
+            effective_network_time: st.effective_network_time,
         })
     }
 
@@ -198,19 +228,31 @@ impl Actor {
             .ok_or_else(|| actor_error!(illegal_argument, "argument cannot be None"))?
             .0;
 
-        rt.transaction(|st: &mut State, rt| {
-            let prev = st.epoch;
-            // if there were null runs catch up the computation until
-            // st.Epoch == rt.CurrEpoch()
-            while st.epoch < rt.curr_epoch() {
-                // Update to next epoch to process null rounds
-                st.update_to_next_epoch(&curr_realized_power);
-            }
-
-            st.update_to_next_epoch_with_reward(&curr_realized_power);
-            st.update_smoothed_estimates(st.epoch - prev);
-            Ok(())
+        let notification = rt.transaction(|st: &mut State, rt| {
+            st.update_network_kpi(rt.policy(), rt.curr_epoch(), &curr_realized_power);
+            Ok(st.epoch_reward_notification())
         })?;
+
+        // Push the new reward to subscribers, sparing each a query to this actor.
+        // A subscriber failing to accept it does not abort the update. Subscribers discard
+        // the previous reward before requesting the update, so a subscriber that missed
+        // the notification queries the reward instead.
+        let params = IpldBlock::serialize_cbor(&notification)?;
+        for subscriber in EPOCH_REWARD_SUBSCRIBERS {
+            let res = extract_send_result(rt.send_simple(
+                subscriber,
+                ext::subscriber::NOTIFY_EPOCH_REWARD_METHOD,
+                params.clone(),
+                TokenAmount::zero(),
+            ));
+            if let Err(e) = res {
+                error!(
+                    "failed to notify {} of epoch reward, code: {:?}",
+                    subscriber,
+                    e.exit_code()
+                );
+            }
+        }
         Ok(())
     }
 }
@@ -227,5 +269,6 @@ impl ActorCode for Actor {
         AwardBlockReward => award_block_reward,
         ThisEpochReward => this_epoch_reward,
         UpdateNetworkKPI => update_network_kpi,
+        GetRewardEstimateExported => get_reward_estimate,
     }
 }
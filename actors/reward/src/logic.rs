@@ -1,8 +1,6 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::str::FromStr;
-
 use fvm_shared::bigint::{BigInt, Integer};
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
@@ -11,26 +9,25 @@ use lazy_static::lazy_static;
 
 use super::expneg::expneg;
 use fil_actors_runtime::builtin::reward::math::PRECISION;
+use fil_actors_runtime::runtime::Policy;
 
 lazy_static! {
     /// Floor(e^(ln[1 + 100%] / epochsInYear) * 2^128
     /// Q.128 formatted number such that f(epoch) = baseExponent^epoch grows 100% in one
-    /// year of epochs.
-    /// Calculation here: https://www.wolframalpha.com/input?i=IntegerPart%5BExp%5BLog%5B1%2B100%25%5D%2F%28%28365+days%29%2F%2830+seconds%29%29%5D*2%5E128%5D
-    pub static ref BASELINE_EXPONENT: StoragePower =
-        StoragePower::from_str("340282591298641078465964189926313473653").unwrap();
+    /// year of epochs. Mainnet value of the policy's baseline exponent.
+    pub static ref BASELINE_EXPONENT: StoragePower = Policy::default().reward_baseline_exponent;
 
     // 2.5057116798121726 EiB
-    pub static ref BASELINE_INITIAL_VALUE: StoragePower = StoragePower::from(2_888_888_880_000_000_000u128);
+    pub static ref BASELINE_INITIAL_VALUE: StoragePower =
+        Policy::default().reward_baseline_initial_value;
 
     /// 1EiB
-    pub static ref INIT_BASELINE_POWER: StoragePower =
-    ((BASELINE_INITIAL_VALUE.clone() << (2*PRECISION)) / &*BASELINE_EXPONENT) >> PRECISION;
+    pub static ref INIT_BASELINE_POWER: StoragePower = init_baseline_power(&Policy::default());
 
     /// 330M for mainnet
-    pub(super) static ref SIMPLE_TOTAL: TokenAmount = TokenAmount::from_whole(330_000_000);
+    pub static ref SIMPLE_TOTAL: TokenAmount = Policy::default().reward_simple_total;
     /// 770M for mainnet
-    pub(super) static ref BASELINE_TOTAL: TokenAmount = TokenAmount::from_whole(770_000_000);
+    pub static ref BASELINE_TOTAL: TokenAmount = Policy::default().reward_baseline_total;
     /// expLamSubOne = e^lambda - 1
     /// for Q.128: int(expLamSubOne * 2^128)
     static ref EXP_LAM_SUB_ONE: BigInt = BigInt::from(37396273494747879394193016954629u128);
@@ -42,8 +39,15 @@ lazy_static! {
 
 /// Compute BaselinePower(t) from BaselinePower(t-1) with an additional multiplication
 /// of the base exponent.
-pub(crate) fn baseline_power_from_prev(prev_power: &StoragePower) -> StoragePower {
-    (prev_power * &*BASELINE_EXPONENT) >> PRECISION
+pub(crate) fn baseline_power_from_prev(policy: &Policy, prev_power: &StoragePower) -> StoragePower {
+    (prev_power * &policy.reward_baseline_exponent) >> PRECISION
+}
+
+/// Computes the baseline power of the epoch before the first, such that the first epoch's
+/// baseline power is the policy's initial value.
+pub(crate) fn init_baseline_power(policy: &Policy) -> StoragePower {
+    ((&policy.reward_baseline_initial_value << (2 * PRECISION)) / &policy.reward_baseline_exponent)
+        >> PRECISION
 }
 
 /// Computes RewardTheta which is is precise fractional value of effectiveNetworkTime.
@@ -113,9 +117,11 @@ mod tests {
 
     use super::*;
     use num::BigRational;
+    use num::Signed;
     use num::ToPrimitive;
     use std::fs;
     use std::ops::Shl;
+    use std::str::FromStr;
 
     // Converted from: https://github.com/filecoin-project/specs-actors/blob/d56b240af24517443ce1f8abfbdab7cb22d331f1/actors/builtin/reward/reward_logic_test.go#L18
     // x => x/(2^128)
@@ -169,6 +175,7 @@ mod tests {
         let step = BigInt::from(5000_i64).shl(u128::BITS) - BigInt::from(77_777_777_777_i64); // offset from full integers
         let delta = BigInt::from(1_i64).shl(u128::BITS) - BigInt::from(33_333_333_333_i64); // offset from full integers
 
+        let policy = Policy::default();
         let mut prev_theta = BigInt::from(0i64);
         let mut theta = delta;
 
@@ -177,8 +184,8 @@ mod tests {
             0,
             BigInt::from(0i64),
             BigInt::from(0i64),
-            &SIMPLE_TOTAL,
-            &BASELINE_TOTAL,
+            &policy.reward_simple_total,
+            &policy.reward_baseline_total,
         );
 
         for _ in 0..512 {
@@ -186,8 +193,8 @@ mod tests {
                 0,
                 prev_theta.clone(),
                 theta.clone(),
-                &SIMPLE_TOTAL,
-                &BASELINE_TOTAL,
+                &policy.reward_simple_total,
+                &policy.reward_baseline_total,
             );
             reward -= &simple;
 
@@ -216,6 +223,7 @@ mod tests {
     // Converted from: https://github.com/filecoin-project/specs-actors/blob/d56b240af24517443ce1f8abfbdab7cb22d331f1/actors/builtin/reward/reward_logic_test.go#L70
     #[test]
     fn test_simple_reward() {
+        let policy = Policy::default();
         let mut b = String::from("x, y\n");
         for i in 0..512 {
             let x: i64 = i * 5000;
@@ -223,8 +231,8 @@ mod tests {
                 x,
                 BigInt::from(0i64),
                 BigInt::from(0i64),
-                &SIMPLE_TOTAL,
-                &BASELINE_TOTAL,
+                &policy.reward_simple_total,
+                &policy.reward_baseline_total,
             );
 
             let x_str = &x.to_string();
@@ -246,13 +254,14 @@ mod tests {
     // Converted from: https://github.com/filecoin-project/specs-actors/blob/d56b240af24517443ce1f8abfbdab7cb22d331f1/actors/builtin/reward/reward_logic_test.go#L82
     #[test]
     fn test_baseline_reward_growth() {
-        fn baseline_in_years(start: StoragePower, x: ChainEpoch) -> StoragePower {
+        let policy = Policy::default();
+        let baseline_in_years = |start: StoragePower, x: ChainEpoch| -> StoragePower {
             let mut baseline = start;
             for _ in 0..(x * EPOCHS_IN_YEAR) {
-                baseline = baseline_power_from_prev(&baseline);
+                baseline = baseline_power_from_prev(&policy, &baseline);
             }
             baseline
-        }
+        };
 
         struct GrowthTestCase {
             start_val: StoragePower,
@@ -269,7 +278,10 @@ mod tests {
             // PiB
             GrowthTestCase { start_val: StoragePower::from(1i64 << 50), err_bound: 1e-8 },
             // EiB
-            GrowthTestCase { start_val: BASELINE_INITIAL_VALUE.clone(), err_bound: 1e-8 },
+            GrowthTestCase {
+                start_val: policy.reward_baseline_initial_value.clone(),
+                err_bound: 1e-8,
+            },
             // ZiB
             GrowthTestCase { start_val: StoragePower::from(1u128 << 70), err_bound: 1e-8 },
             // non power of 2 ~ 1 EiB
@@ -295,4 +307,60 @@ mod tests {
             assert!(perr < case.err_bound);
         }
     }
+
+    #[test]
+    fn default_policy_matches_mainnet() {
+        let policy = Policy::default();
+        assert_eq!(
+            StoragePower::from_str("340282591298641078465964189926313473653").unwrap(),
+            policy.reward_baseline_exponent
+        );
+        assert_eq!(
+            StoragePower::from(2_888_888_880_000_000_000u128),
+            policy.reward_baseline_initial_value
+        );
+        assert_eq!(TokenAmount::from_whole(330_000_000), policy.reward_simple_total);
+        assert_eq!(TokenAmount::from_whole(770_000_000), policy.reward_baseline_total);
+        // The baseline power of the epoch preceding the first.
+        assert_eq!(StoragePower::from(2_888_886_975_106_048_385u128), init_baseline_power(&policy));
+        assert_eq!(*INIT_BASELINE_POWER, init_baseline_power(&policy));
+
+        // The mainnet epoch zero reward and baseline follow from the default policy.
+        let st = crate::State::new(&policy, StoragePower::from(0));
+        assert_eq!(TokenAmount::from_atto(36_266_264_293_777_134_739i128), st.this_epoch_reward);
+        assert_eq!(&*BASELINE_INITIAL_VALUE - 1, st.this_epoch_baseline_power);
+        assert_eq!(*BASELINE_INITIAL_VALUE, st.effective_baseline_power);
+    }
+
+    #[test]
+    fn custom_policy_scales_rewards() {
+        let mut policy = Policy::default();
+        let theta = BigInt::from(1_000_i64).shl(u128::BITS);
+        let mainnet = compute_reward(
+            10,
+            BigInt::from(0i64),
+            theta.clone(),
+            &policy.reward_simple_total,
+            &policy.reward_baseline_total,
+        );
+
+        policy.reward_simple_total = TokenAmount::from_whole(660_000_000);
+        policy.reward_baseline_total = TokenAmount::from_whole(1_540_000_000);
+        let doubled = compute_reward(
+            10,
+            BigInt::from(0i64),
+            theta,
+            &policy.reward_simple_total,
+            &policy.reward_baseline_total,
+        );
+        // Rounding of each component may differ by one atto.
+        assert!((doubled.atto() - mainnet.atto() * 2).abs() <= BigInt::from(2));
+
+        // A larger exponent grows the baseline faster.
+        let start = policy.reward_baseline_initial_value.clone();
+        let mainnet_next = baseline_power_from_prev(&policy, &start);
+        policy.reward_baseline_exponent =
+            &policy.reward_baseline_exponent + BigInt::from(1u64).shl(100);
+        assert!(baseline_power_from_prev(&policy, &start) > mainnet_next);
+    }
 }
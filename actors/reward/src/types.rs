@@ -1,10 +1,13 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use fil_actors_runtime::builtin::reward::FilterEstimate;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
-use fvm_shared::bigint::bigint_ser::BigIntDe;
+use fvm_shared::bigint::bigint_ser::{self, BigIntDe};
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::StoragePower;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
@@ -27,3 +30,23 @@ pub use fil_actors_runtime::builtin::reward::ThisEpochRewardReturn;
 pub struct UpdateNetworkKPIParams {
     pub curr_realized_power: Option<BigIntDe>,
 }
+
+/// Reward conditions for the current epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetRewardEstimateReturn {
+    /// The epoch for which the reward was computed, by the update at the end of the
+    /// preceding non-null epoch.
+    pub epoch: ChainEpoch,
+    /// The reward for winning a block in this epoch, before multiplying by win count.
+    pub this_epoch_reward: TokenAmount,
+    /// The smoothed estimate of the reward per epoch.
+    pub this_epoch_reward_smoothed: FilterEstimate,
+    /// The baseline power for this epoch.
+    #[serde(with = "bigint_ser")]
+    pub this_epoch_baseline_power: StoragePower,
+    /// The baseline power at the effective network time.
+    #[serde(with = "bigint_ser")]
+    pub effective_baseline_power: StoragePower,
+    /// The epoch at which the cumulative baseline reached the cumulative realized power.
+    pub effective_network_time: ChainEpoch,
+}
@@ -4,15 +4,17 @@
 use std::cell::RefCell;
 
 use fil_actor_reward::{
-    Actor as RewardActor, AwardBlockRewardParams, BASELINE_INITIAL_VALUE, Method,
-    PENALTY_MULTIPLIER, State, ThisEpochRewardReturn, ext,
+    Actor as RewardActor, AwardBlockRewardParams, BASELINE_INITIAL_VALUE, EPOCH_REWARD_SUBSCRIBERS,
+    GetRewardEstimateReturn, Method, PENALTY_MULTIPLIER, State, ThisEpochRewardReturn, ext,
 };
-use fil_actors_runtime::EXPECTED_LEADERS_PER_EPOCH;
+use fil_actors_runtime::reward::EpochRewardNotification;
+use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::{
     ActorError, BURNT_FUNDS_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
     SYSTEM_ACTOR_ADDR,
 };
+use fil_actors_runtime::{EXPECTED_LEADERS_PER_EPOCH, EventBuilder};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
@@ -22,7 +24,7 @@ use fvm_shared::error::ExitCode;
 use fvm_shared::sector::StoragePower;
 use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
 use lazy_static::lazy_static;
-use num_traits::FromPrimitive;
+use num_traits::{FromPrimitive, Zero};
 
 lazy_static! {
     static ref EPOCH_ZERO_REWARD: TokenAmount =
@@ -75,6 +77,24 @@ mod construction_tests {
         let state: State = rt.get_state();
         assert_eq!(reward, state.this_epoch_reward);
     }
+
+    #[test]
+    fn construct_with_custom_reward_policy() {
+        let policy = Policy {
+            reward_baseline_initial_value: StoragePower::from(1_u128 << 50),
+            reward_simple_total: TokenAmount::from_whole(100_000_000),
+            reward_baseline_total: TokenAmount::from_whole(200_000_000),
+            ..Default::default()
+        };
+        let rt = construct_with_policy(policy.clone(), &StoragePower::zero());
+
+        let state: State = rt.get_state();
+        assert_eq!(policy.reward_simple_total, state.simple_total);
+        assert_eq!(policy.reward_baseline_total, state.baseline_total);
+        assert_eq!(policy.reward_baseline_initial_value, state.effective_baseline_power);
+        assert!(state.this_epoch_baseline_power < StoragePower::from(1_u128 << 50));
+        assert!(state.this_epoch_reward < *EPOCH_ZERO_REWARD);
+    }
 }
 
 mod test_award_block_reward {
@@ -160,17 +180,18 @@ mod test_award_block_reward {
         let miner_penalty = PENALTY_MULTIPLIER * &penalty;
         let params = IpldBlock::serialize_cbor(&ext::miner::ApplyRewardParams {
             reward: expected_reward.clone(),
-            penalty: miner_penalty,
+            penalty: miner_penalty.clone(),
         })
         .unwrap();
         rt.expect_send_simple(
             *WINNER,
             ext::miner::APPLY_REWARDS_METHOD,
             params,
-            expected_reward,
+            expected_reward.clone(),
             None,
             ExitCode::OK,
         );
+        expect_reward_event(&rt, *WINNER, &expected_reward, &gas_reward, &miner_penalty, true);
         let inner_params = IpldBlock::serialize_cbor(&AwardBlockRewardParams {
             miner: *WINNER,
             penalty,
@@ -196,17 +217,25 @@ mod test_award_block_reward {
         let miner_penalty = PENALTY_MULTIPLIER * &penalty;
         let params = IpldBlock::serialize_cbor(&ext::miner::ApplyRewardParams {
             reward: small_reward.clone(),
-            penalty: miner_penalty,
+            penalty: miner_penalty.clone(),
         })
         .unwrap();
         rt.expect_send_simple(
             *WINNER,
             ext::miner::APPLY_REWARDS_METHOD,
             params,
-            small_reward,
+            small_reward.clone(),
             None,
             ExitCode::OK,
         );
+        expect_reward_event(
+            &rt,
+            *WINNER,
+            &small_reward,
+            &TokenAmount::zero(),
+            &miner_penalty,
+            true,
+        );
 
         let inner_params = IpldBlock::serialize_cbor(&AwardBlockRewardParams {
             miner: *WINNER,
@@ -281,10 +310,19 @@ mod test_award_block_reward {
             BURNT_FUNDS_ACTOR_ADDR,
             METHOD_SEND,
             None,
-            expected_reward,
+            expected_reward.clone(),
             None,
             ExitCode::OK,
         );
+        // The reward is reported as not credited to the miner.
+        expect_reward_event(
+            &rt,
+            *WINNER,
+            &expected_reward,
+            &TokenAmount::zero(),
+            &TokenAmount::zero(),
+            false,
+        );
 
         let inner_params = IpldBlock::serialize_cbor(&AwardBlockRewardParams {
             miner: *WINNER,
@@ -314,6 +352,37 @@ mod test_this_epoch_reward {
         assert_eq!(state.this_epoch_baseline_power, resp.this_epoch_baseline_power);
         assert_eq!(state.this_epoch_reward_smoothed, resp.this_epoch_reward_smoothed);
     }
+
+    #[test]
+    fn get_reward_estimate_after_kpi_update() {
+        let power = StoragePower::from_i128(1 << 50).unwrap();
+        let rt = construct_and_verify(&power);
+        rt.epoch.replace(5);
+        update_network_kpi(&rt, &power);
+
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1000));
+        rt.expect_validate_caller_any();
+        let resp: GetRewardEstimateReturn = rt
+            .call::<RewardActor>(Method::GetRewardEstimateExported as u64, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+
+        let state: State = rt.get_state();
+        assert_eq!(
+            GetRewardEstimateReturn {
+                epoch: 6,
+                this_epoch_reward: state.this_epoch_reward,
+                this_epoch_reward_smoothed: state.this_epoch_reward_smoothed,
+                this_epoch_baseline_power: state.this_epoch_baseline_power,
+                effective_baseline_power: state.effective_baseline_power,
+                effective_network_time: state.effective_network_time,
+            },
+            resp
+        );
+    }
 }
 
 #[test]
@@ -327,11 +396,31 @@ fn test_successive_kpi_updates() {
     }
 }
 
+#[test]
+fn kpi_update_succeeds_if_subscriber_rejects_notification() {
+    let power = StoragePower::from_i128(1 << 50).unwrap();
+    let rt = construct_and_verify(&power);
+
+    rt.epoch.replace(1);
+    let notification = expect_update_network_kpi(&rt, &power, ExitCode::USR_FORBIDDEN);
+    assert!(call_update_network_kpi(&rt, &power).unwrap().is_none());
+    rt.verify();
+
+    // The reward is updated regardless.
+    let state: State = rt.get_state();
+    assert_eq!(notification, state.epoch_reward_notification());
+}
+
 fn construct_and_verify(curr_power: &StoragePower) -> MockRuntime {
+    construct_with_policy(Policy::default(), curr_power)
+}
+
+fn construct_with_policy(policy: Policy, curr_power: &StoragePower) -> MockRuntime {
     let rt = MockRuntime {
         receiver: REWARD_ACTOR_ADDR,
         caller: RefCell::new(SYSTEM_ACTOR_ADDR),
         caller_type: RefCell::new(*SYSTEM_ACTOR_CODE_ID),
+        policy,
         ..Default::default()
     };
     rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
@@ -363,7 +452,7 @@ fn award_block_reward(
         ext::miner::APPLY_REWARDS_METHOD,
         IpldBlock::serialize_cbor(&ext::miner::ApplyRewardParams {
             reward: expected_payment.clone(),
-            penalty: miner_penalty,
+            penalty: miner_penalty.clone(),
         })
         .unwrap(),
         expected_payment.clone(),
@@ -376,11 +465,12 @@ fn award_block_reward(
             BURNT_FUNDS_ACTOR_ADDR,
             METHOD_SEND,
             None,
-            expected_payment,
+            expected_payment.clone(),
             None,
             ExitCode::OK,
         );
     }
+    expect_reward_event(rt, miner, &expected_payment, &gas_reward, &miner_penalty, true);
 
     let params = IpldBlock::serialize_cbor(&AwardBlockRewardParams {
         miner,
@@ -396,6 +486,27 @@ fn award_block_reward(
     Ok(serialized_bytes)
 }
 
+fn expect_reward_event(
+    rt: &MockRuntime,
+    miner: Address,
+    reward: &TokenAmount,
+    gas_reward: &TokenAmount,
+    penalty: &TokenAmount,
+    credited: bool,
+) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("block-reward-awarded")
+            .field_indexed("miner", &miner.id().unwrap())
+            .field_bigint("reward", reward.atto())
+            .field_bigint("gas-reward", gas_reward.atto())
+            .field_bigint("penalty", penalty.atto())
+            .field("credited", &credited)
+            .build()
+            .unwrap(),
+    );
+}
+
 fn this_epoch_reward(rt: &MockRuntime) -> ThisEpochRewardReturn {
     rt.expect_validate_caller_any();
     let serialized_result = rt.call::<RewardActor>(Method::ThisEpochReward as u64, None).unwrap();
@@ -405,10 +516,44 @@ fn this_epoch_reward(rt: &MockRuntime) -> ThisEpochRewardReturn {
 }
 
 fn update_network_kpi(rt: &MockRuntime, curr_raw_power: &StoragePower) {
+    let notification = expect_update_network_kpi(rt, curr_raw_power, ExitCode::OK);
+    assert!(call_update_network_kpi(rt, curr_raw_power).is_ok());
+    rt.verify();
+
+    let state: State = rt.get_state();
+    assert_eq!(notification, state.epoch_reward_notification());
+}
+
+// Expects the validation and subscriber notifications of a KPI update, returning the expected
+// notification.
+fn expect_update_network_kpi(
+    rt: &MockRuntime,
+    curr_raw_power: &StoragePower,
+    notify_exit: ExitCode,
+) -> EpochRewardNotification {
     rt.set_caller(*POWER_ACTOR_CODE_ID, STORAGE_POWER_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![STORAGE_POWER_ACTOR_ADDR]);
 
+    let mut expected: State = rt.get_state();
+    expected.update_network_kpi(&rt.policy, *rt.epoch.borrow(), curr_raw_power);
+    let notification = expected.epoch_reward_notification();
+    for subscriber in EPOCH_REWARD_SUBSCRIBERS {
+        rt.expect_send_simple(
+            *subscriber,
+            ext::subscriber::NOTIFY_EPOCH_REWARD_METHOD,
+            IpldBlock::serialize_cbor(&notification).unwrap(),
+            TokenAmount::zero(),
+            None,
+            notify_exit,
+        );
+    }
+    notification
+}
+
+fn call_update_network_kpi(
+    rt: &MockRuntime,
+    curr_raw_power: &StoragePower,
+) -> Result<Option<IpldBlock>, ActorError> {
     let params = IpldBlock::serialize_cbor(&(BigIntSer(curr_raw_power))).unwrap();
-    assert!(rt.call::<RewardActor>(Method::UpdateNetworkKPI as u64, params).is_ok());
-    rt.verify();
+    rt.call::<RewardActor>(Method::UpdateNetworkKPI as u64, params)
 }
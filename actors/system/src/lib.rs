@@ -4,10 +4,11 @@ use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::CborStore;
 use fvm_ipld_encoding::tuple::*;
-use fvm_shared::METHOD_CONSTRUCTOR;
 use fvm_shared::error::ExitCode;
+use fvm_shared::{METHOD_CONSTRUCTOR, MethodNum};
 use num_derive::FromPrimitive;
 
+use fil_actors_runtime::method_names::{builtin_type_by_name, method_names};
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
     ActorContext, ActorError, AsActorError, SYSTEM_ACTOR_ADDR, actor_dispatch, actor_error,
@@ -21,6 +22,26 @@ fil_actors_runtime::wasm_trampoline!(Actor);
 #[repr(u64)]
 pub enum Method {
     Constructor = METHOD_CONSTRUCTOR,
+    GetMethodNamesExported = frc42_dispatch::method_hash!("GetMethodNames"),
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetMethodNamesParams {
+    /// The builtin actor type name, as in the builtin actor registry.
+    pub actor_name: String,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct MethodName {
+    pub method: MethodNum,
+    pub name: String,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetMethodNamesReturn {
+    pub methods: Vec<MethodName>,
 }
 
 /// System actor state.
@@ -62,6 +83,22 @@ impl Actor {
         rt.create(&state)?;
         Ok(())
     }
+
+    /// Returns the method numbers and names of a builtin actor type.
+    pub fn get_method_names(
+        rt: &impl Runtime,
+        params: GetMethodNamesParams,
+    ) -> Result<GetMethodNamesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let typ = builtin_type_by_name(&params.actor_name).ok_or_else(|| {
+            actor_error!(illegal_argument, "unknown builtin actor type {}", params.actor_name)
+        })?;
+        let methods = method_names(typ)
+            .iter()
+            .map(|(method, name)| MethodName { method: *method, name: name.to_string() })
+            .collect();
+        Ok(GetMethodNamesReturn { methods })
+    }
 }
 
 impl ActorCode for Actor {
@@ -73,6 +110,7 @@ impl ActorCode for Actor {
 
     actor_dispatch! {
         Constructor => constructor,
+        GetMethodNamesExported => get_method_names,
     }
 }
 
@@ -80,12 +118,17 @@ impl ActorCode for Actor {
 mod tests {
     use std::cell::RefCell;
 
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
     use fvm_shared::MethodNum;
+    use fvm_shared::address::Address;
+    use fvm_shared::error::ExitCode;
 
     use fil_actors_runtime::SYSTEM_ACTOR_ADDR;
-    use fil_actors_runtime::test_utils::{MockRuntime, SYSTEM_ACTOR_CODE_ID};
+    use fil_actors_runtime::test_utils::{
+        EVM_ACTOR_CODE_ID, MockRuntime, SYSTEM_ACTOR_CODE_ID, expect_abort,
+    };
 
-    use crate::{Actor, Method, State};
+    use crate::{Actor, GetMethodNamesParams, GetMethodNamesReturn, Method, MethodName, State};
 
     pub fn new_runtime() -> MockRuntime {
         MockRuntime {
@@ -107,4 +150,48 @@ mod tests {
         let builtin_actors = state.get_builtin_actors(&rt.store).unwrap();
         assert!(builtin_actors.is_empty());
     }
+
+    #[test]
+    fn get_method_names() {
+        let rt = new_runtime();
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1000));
+
+        rt.expect_validate_caller_any();
+        let ret: GetMethodNamesReturn = rt
+            .call::<Actor>(
+                Method::GetMethodNamesExported as MethodNum,
+                IpldBlock::serialize_cbor(&GetMethodNamesParams {
+                    actor_name: "system".to_string(),
+                })
+                .unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        assert_eq!(
+            vec![
+                MethodName { method: Method::Constructor as MethodNum, name: "Constructor".into() },
+                MethodName {
+                    method: Method::GetMethodNamesExported as MethodNum,
+                    name: "GetMethodNames".into()
+                },
+            ],
+            ret.methods
+        );
+
+        rt.expect_validate_caller_any();
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            rt.call::<Actor>(
+                Method::GetMethodNamesExported as MethodNum,
+                IpldBlock::serialize_cbor(&GetMethodNamesParams {
+                    actor_name: "unknown".to_string(),
+                })
+                .unwrap(),
+            ),
+        );
+        rt.verify();
+    }
 }
@@ -6,7 +6,6 @@ use cid::Cid;
 use fil_actors_runtime::EventBuilder;
 use fil_actors_runtime::runtime::Runtime;
 use fvm_shared::ActorID;
-use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::clock::ChainEpoch;
 
 /// Indicates a new value for a verifier's datacap balance.
@@ -21,13 +20,60 @@ pub fn verifier_balance(
     let mut event: EventBuilder = EventBuilder::new()
         .typ("verifier-balance")
         .field_indexed("verifier", &verifier)
-        .field("balance", &BigIntSer(new_balance));
+        .field_bigint("balance", new_balance);
     if let Some(client) = client {
         event = event.field_indexed("client", &client);
     }
     rt.emit_event(&event.build()?)
 }
 
+/// Indicates a verifier has granted datacap to a client.
+/// The total is all datacap granted by the verifier so far, including this grant.
+/// Values are in datacap whole units (not TokenAmount).
+pub fn datacap_granted(
+    rt: &impl Runtime,
+    verifier: ActorID,
+    client: ActorID,
+    amount: &DataCap,
+    total: &DataCap,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("datacap-granted")
+            .field_indexed("verifier", &verifier)
+            .field_indexed("client", &client)
+            .field_bigint("amount", amount)
+            .field_bigint("total", total)
+            .build()?,
+    )
+}
+
+/// Indicates an observer verifier has been registered, or its metadata replaced.
+/// An observer has no allowance and cannot grant datacap.
+pub fn observer_verifier_added(
+    rt: &impl Runtime,
+    verifier: ActorID,
+    metadata: &str,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("observer-verifier-added")
+            .field_indexed("verifier", &verifier)
+            .field("metadata", metadata)
+            .build()?,
+    )
+}
+
+/// Indicates an observer verifier has been removed, or promoted to a verifier with an allowance.
+pub fn observer_verifier_removed(rt: &impl Runtime, verifier: ActorID) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("observer-verifier-removed")
+            .field_indexed("verifier", &verifier)
+            .build()?,
+    )
+}
+
 /// Indicates a new allocation has been made.
 pub fn allocation(
     rt: &impl Runtime,
@@ -104,6 +150,36 @@ pub fn claim_removed(rt: &impl Runtime, id: ClaimID, claim: &Claim) -> Result<()
     )
 }
 
+/// Indicates a claim has been disputed by its client.
+pub fn claim_disputed(rt: &impl Runtime, id: ClaimID, claim: &Claim) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("claim-disputed")
+            .with_parties(id, claim.client, claim.provider)
+            .with_piece(&claim.data, claim.size.0)
+            .field_indexed("sector", &claim.sector)
+            .build()?,
+    )
+}
+
+/// Indicates a claim dispute has been resolved.
+/// An upheld dispute revokes the claim.
+pub fn claim_dispute_resolved(
+    rt: &impl Runtime,
+    id: ClaimID,
+    provider: ActorID,
+    upheld: bool,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("claim-dispute-resolved")
+            .field_indexed("id", &id)
+            .field_indexed("provider", &provider)
+            .field("upheld", &upheld)
+            .build()?,
+    )
+}
+
 // Private helpers //
 trait WithParties {
     fn with_parties(self, id: AllocationID, client: ActorID, provider: ActorID) -> EventBuilder;
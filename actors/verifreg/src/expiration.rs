@@ -1,6 +1,6 @@
 use crate::{Allocation, Claim};
 use fil_actors_runtime::{
-    ActorError, AsActorError, BatchReturn, BatchReturnGen, MapMap, parse_uint_key,
+    ActorError, AsActorError, BatchReturn, BatchReturnGen, MapMap, actor_error, parse_uint_key,
 };
 use fvm_ipld_blockstore::Blockstore;
 use fvm_shared::ActorID;
@@ -52,6 +52,48 @@ where
     Ok(found_ids)
 }
 
+// Finds the expired items among at most `max` items in a collection for some owner,
+// starting at the item keyed `start` (or the beginning), in the collection's internal order.
+// Returns those items' keys, and the key at which to resume if any items remain.
+pub fn find_expired_ranged<T, BS>(
+    collection: &mut MapMap<BS, T, ActorID, u64>,
+    owner: ActorID,
+    start: Option<u64>,
+    max: u64,
+    curr_epoch: ChainEpoch,
+) -> Result<(Vec<u64>, Option<u64>), ActorError>
+where
+    T: Expires + Serialize + DeserializeOwned + Clone + PartialEq,
+    BS: Blockstore,
+{
+    if let Some(start) = start {
+        let found = collection
+            .get(owner, start)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load allocation/claim")?;
+        if found.is_none() {
+            return Err(actor_error!(not_found, "no item {} for {} to start from", start, owner));
+        }
+    }
+
+    let mut found_ids = Vec::<u64>::new();
+    let max = usize::try_from(max).unwrap_or(usize::MAX);
+    let (_, next) = collection
+        .for_each_in_ranged(owner, start, Some(max), |key, record| {
+            if curr_epoch >= record.expiration() {
+                let id = parse_uint_key(key)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to parse uint key")?;
+                found_ids.push(id);
+            }
+            Ok(())
+        })
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate over allocations/claims")?;
+    let next = next
+        .map(|key| parse_uint_key(&key))
+        .transpose()
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to parse uint key")?;
+    Ok((found_ids, next))
+}
+
 // Checks each candidate item from the collection for expiration.
 // Returns a batch return with OK for expired items, and FORBIDDEN for non-expired.
 pub fn check_expired<T, BS>(
@@ -1,7 +1,6 @@
 use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
-use fvm_shared::address::Address;
 
 pub mod account {
     use super::*;
@@ -19,8 +18,7 @@ pub mod account {
 }
 
 pub mod datacap {
-    use super::*;
-    use fvm_shared::econ::TokenAmount;
+    pub use fil_actors_runtime::builtin::datacap::{DestroyParams, MintParams};
 
     #[repr(u64)]
     pub enum Method {
@@ -30,17 +28,18 @@ pub mod datacap {
         Transfer = frc42_dispatch::method_hash!("Transfer"),
         Burn = frc42_dispatch::method_hash!("Burn"),
     }
+}
 
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    pub struct MintParams {
-        pub to: Address,
-        pub amount: TokenAmount,
-        pub operators: Vec<Address>,
-    }
+pub mod miner {
+    use super::*;
+    use fvm_shared::sector::SectorNumber;
+
+    pub const ON_CLAIM_REVOKED_METHOD: u64 = frc42_dispatch::method_hash!("OnClaimRevoked");
 
-    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
-    pub struct DestroyParams {
-        pub owner: Address,
-        pub amount: TokenAmount,
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct OnClaimRevokedParams {
+        pub sector: SectorNumber,
+        // The (padded) verified space revoked from the sector.
+        pub space: u64,
     }
 }
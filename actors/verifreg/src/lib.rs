@@ -11,9 +11,11 @@ use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::sys::SendFlags;
+use fvm_shared::version::NetworkVersion;
 use fvm_shared::{ActorID, METHOD_CONSTRUCTOR};
 use log::info;
 use num_derive::FromPrimitive;
@@ -24,18 +26,22 @@ use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Policy, Runtime};
 use fil_actors_runtime::{ActorContext, AsActorError, BatchReturnGen};
 use fil_actors_runtime::{
-    ActorError, BatchReturn, DATACAP_TOKEN_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
-    SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR, actor_dispatch, actor_error,
-    deserialize_block, extract_send_result, resolve_to_actor_id,
+    ActorError, BatchReturn, DATACAP_TOKEN_ACTOR_ADDR, MethodDeprecation, NonNegativeTokenAmount,
+    STORAGE_MARKET_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR, actor_dispatch,
+    actor_error, deserialize_block, extract_send_result, resolve_to_actor_id,
 };
 
+use crate::expiration::Expires;
 use crate::ext::datacap::{DestroyParams, MintParams};
 use crate::state::{
-    DATACAP_MAP_CONFIG, DataCapMap, REMOVE_DATACAP_PROPOSALS_CONFIG, RemoveDataCapProposalMap,
+    ClaimDispute, DATACAP_MAP_CONFIG, DataCapMap, REMOVE_DATACAP_PROPOSALS_CONFIG,
+    RemoveDataCapProposalMap,
 };
 
 pub use self::state::Allocation;
 pub use self::state::Claim;
+pub use self::state::ObserverVerifier;
+pub use self::state::ProviderHistory;
 pub use self::state::State;
 pub use self::types::*;
 
@@ -72,9 +78,24 @@ pub enum Method {
     GetClaimsExported = frc42_dispatch::method_hash!("GetClaims"),
     ExtendClaimTermsExported = frc42_dispatch::method_hash!("ExtendClaimTerms"),
     RemoveExpiredClaimsExported = frc42_dispatch::method_hash!("RemoveExpiredClaims"),
+    DisputeClaimExported = frc42_dispatch::method_hash!("DisputeClaim"),
+    ResolveClaimDisputeExported = frc42_dispatch::method_hash!("ResolveClaimDispute"),
+    GetVerifregPolicyExported = frc42_dispatch::method_hash!("GetVerifregPolicy"),
     UniversalReceiverHook = frc42_dispatch::method_hash!("Receive"),
+    ReleaseAllocationsExported = frc42_dispatch::method_hash!("ReleaseAllocations"),
+    GetClaimTotalsExported = frc42_dispatch::method_hash!("GetClaimTotals"),
+    AddObserverVerifierExported = frc42_dispatch::method_hash!("AddObserverVerifier"),
+    RemoveObserverVerifierExported = frc42_dispatch::method_hash!("RemoveObserverVerifier"),
+    ListObserverVerifiersExported = frc42_dispatch::method_hash!("ListObserverVerifiers"),
+    GetProviderHistoryExported = frc42_dispatch::method_hash!("GetProviderHistory"),
+    RemoveExpiredClaimsLimitedExported = frc42_dispatch::method_hash!("RemoveExpiredClaimsLimited"),
 }
 
+// Network version from which internal method numbers duplicated by FRC-42 exports are rejected.
+// The methods remain available until then, giving callers a network upgrade's notice
+// to move to the exported methods.
+pub const INTERNAL_METHODS_SUNSET_VERSION: NetworkVersion = NetworkVersion::new(29);
+
 pub struct Actor;
 
 impl Actor {
@@ -90,12 +111,12 @@ impl Actor {
         let st = State::new(rt.store(), Address::new_id(id_addr))
             .context("failed to create verifreg state")?;
 
-        rt.create(&st)?;
+        rt.create_versioned(&st)?;
         Ok(())
     }
 
     pub fn add_verifier(rt: &impl Runtime, params: AddVerifierParams) -> Result<(), ActorError> {
-        if params.allowance < rt.policy().minimum_verified_allocation_size {
+        if *params.allowance < rt.policy().minimum_verified_allocation_size {
             return Err(actor_error!(
                 illegal_argument,
                 "Allowance {} below minimum deal size for add verifier {}",
@@ -107,7 +128,7 @@ impl Actor {
         let verifier = resolve_to_actor_id(rt, &params.address, true)?;
         let verifier_addr = Address::new_id(verifier);
 
-        let st: State = rt.state()?;
+        let st: State = rt.versioned_state()?;
         rt.validate_immediate_caller_is(std::iter::once(&st.root_key))?;
 
         // Disallow root as a verifier.
@@ -126,12 +147,18 @@ impl Actor {
         }
 
         // Store the new verifier and allowance (over-writing).
-        rt.transaction(|st: &mut State, rt| {
+        // An observer verifier assigned an allowance is no longer an observer.
+        let promoted = rt.versioned_transaction(|st: &mut State, rt| {
             st.put_verifier(rt.store(), &verifier_addr, &params.allowance)
-                .context("failed to add verifier")
+                .context("failed to add verifier")?;
+            st.remove_observer_verifier(rt.store(), verifier)
         })?;
 
-        emit::verifier_balance(rt, verifier, &params.allowance, None)
+        emit::verifier_balance(rt, verifier, &params.allowance, None)?;
+        if promoted {
+            emit::observer_verifier_removed(rt, verifier)?;
+        }
+        Ok(())
     }
 
     pub fn remove_verifier(
@@ -141,7 +168,7 @@ impl Actor {
         let verifier = resolve_to_actor_id(rt, &params.verifier, false)?;
         let verifier_addr = Address::new_id(verifier);
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             rt.validate_immediate_caller_is(std::iter::once(&st.root_key))?;
             st.remove_verifier(rt.store(), &verifier_addr).context("failed to remove verifier")
         })?;
@@ -149,6 +176,107 @@ impl Actor {
         emit::verifier_balance(rt, verifier, &DataCap::zero(), None)
     }
 
+    /// Registers an observer verifier: a notary approved by the root key, listed and visible
+    /// to event subscribers, but with no allowance and so unable to grant datacap.
+    /// Registering an existing observer replaces its metadata.
+    /// Adding the observer as a verifier with an allowance ends its observer registration.
+    pub fn add_observer_verifier(
+        rt: &impl Runtime,
+        params: AddObserverVerifierParams,
+    ) -> Result<(), ActorError> {
+        if params.metadata.len() > OBSERVER_VERIFIER_METADATA_MAX_LEN {
+            return Err(actor_error!(
+                illegal_argument,
+                "observer verifier metadata length {} exceeds maximum {}",
+                params.metadata.len(),
+                OBSERVER_VERIFIER_METADATA_MAX_LEN
+            ));
+        }
+
+        let verifier = resolve_to_actor_id(rt, &params.address, true)?;
+        let verifier_addr = Address::new_id(verifier);
+
+        let st: State = rt.versioned_state()?;
+        rt.validate_immediate_caller_is(std::iter::once(&st.root_key))?;
+
+        if verifier_addr == st.root_key {
+            return Err(actor_error!(
+                illegal_argument,
+                "Rootkey cannot be added as observer verifier"
+            ));
+        }
+
+        // Disallow existing clients as observers, as for verifiers.
+        let token_balance = balance(rt, &verifier_addr)?;
+        if token_balance.is_positive() {
+            return Err(actor_error!(
+                illegal_argument,
+                "verified client {} cannot become an observer verifier",
+                verifier_addr
+            ));
+        }
+
+        rt.versioned_transaction(|st: &mut State, rt| {
+            if st.get_verifier_cap(rt.store(), &verifier_addr)?.is_some() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "verifier {} cannot become an observer verifier",
+                    verifier_addr
+                ));
+            }
+            let observer =
+                ObserverVerifier { metadata: params.metadata.clone(), registered: rt.curr_epoch() };
+            st.put_observer_verifier(rt.store(), verifier, observer)
+                .context("failed to add observer verifier")
+        })?;
+
+        emit::observer_verifier_added(rt, verifier, &params.metadata)
+    }
+
+    pub fn remove_observer_verifier(
+        rt: &impl Runtime,
+        params: RemoveObserverVerifierParams,
+    ) -> Result<(), ActorError> {
+        let verifier = resolve_to_actor_id(rt, &params.verifier, false)?;
+
+        rt.versioned_transaction(|st: &mut State, rt| {
+            rt.validate_immediate_caller_is(std::iter::once(&st.root_key))?;
+            if !st.remove_observer_verifier(rt.store(), verifier)? {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "observer verifier {} not found",
+                    verifier
+                ));
+            }
+            Ok(())
+        })?;
+
+        emit::observer_verifier_removed(rt, verifier)
+    }
+
+    /// Returns a page of registered observer verifiers, starting from a cursor.
+    pub fn list_observer_verifiers(
+        rt: &impl Runtime,
+        params: ListObserverVerifiersParams,
+    ) -> Result<ListObserverVerifiersReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let st: State = rt.versioned_state()?;
+        let (listed, next_cursor) =
+            st.list_observer_verifiers(rt.store(), params.cursor, params.limit)?;
+        let observers = listed
+            .into_iter()
+            .map(|(verifier, observer)| ObserverVerifierInfo {
+                verifier,
+                metadata: observer.metadata,
+                registered: observer.registered,
+            })
+            .collect();
+        Ok(ListObserverVerifiersReturn { observers, next_cursor })
+    }
+
     pub fn add_verified_client(
         rt: &impl Runtime,
         params: AddVerifiedClientParams,
@@ -156,7 +284,7 @@ impl Actor {
         // The caller will be verified by checking table below
         rt.validate_immediate_caller_accept_any()?;
 
-        if params.allowance < rt.policy().minimum_verified_allocation_size {
+        if *params.allowance < rt.policy().minimum_verified_allocation_size {
             return Err(actor_error!(
                 illegal_argument,
                 "allowance {} below MinVerifiedDealSize for add verified client {}",
@@ -168,20 +296,39 @@ impl Actor {
         let client_id = resolve_to_actor_id(rt, &params.address, true)?;
         let client = Address::new_id(client_id);
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             if client == st.root_key {
                 return Err(actor_error!(illegal_argument, "root cannot be added as client"));
             }
 
             // Validate caller is one of the verifiers, i.e. has an allowance (even if zero).
+            // Observer verifiers have no allowance.
             let verifier_addr = rt.message().caller();
-            let verifier_cap =
-                st.get_verifier_cap(rt.store(), &verifier_addr)?.ok_or_else(|| {
-                    actor_error!(not_found, "caller {} is not a verifier", verifier_addr)
-                })?;
+            let verifier_cap = match st.get_verifier_cap(rt.store(), &verifier_addr)? {
+                Some(cap) => cap,
+                None if st
+                    .get_observer_verifier(rt.store(), verifier_addr.id().unwrap())?
+                    .is_some() =>
+                {
+                    return Err(actor_error!(
+                        forbidden,
+                        "observer verifier {} cannot grant datacap",
+                        verifier_addr
+                    ));
+                }
+                None => {
+                    return Err(actor_error!(
+                        not_found,
+                        "caller {} is not a verifier",
+                        verifier_addr
+                    ));
+                }
+            };
 
-            // Disallow existing verifiers as clients.
-            if st.get_verifier_cap(rt.store(), &client)?.is_some() {
+            // Disallow existing verifiers, including observers, as clients.
+            if st.get_verifier_cap(rt.store(), &client)?.is_some()
+                || st.get_observer_verifier(rt.store(), client_id)?.is_some()
+            {
                 return Err(actor_error!(
                     illegal_argument,
                     "verifier {} cannot be added as a verified client",
@@ -190,7 +337,7 @@ impl Actor {
             }
 
             // Compute new verifier allowance.
-            if verifier_cap < params.allowance {
+            if verifier_cap < *params.allowance {
                 return Err(actor_error!(
                     illegal_argument,
                     "add more DataCap {} for client than allocated {}",
@@ -200,15 +347,25 @@ impl Actor {
             }
 
             // Reduce verifier's cap.
-            let new_verifier_cap = verifier_cap - &params.allowance;
+            let new_verifier_cap = verifier_cap - &*params.allowance;
             st.put_verifier(rt.store(), &verifier_addr, &new_verifier_cap)
                 .context("failed to update verifier allowance")?;
 
+            let total_granted =
+                st.add_verifier_grant(rt.store(), &verifier_addr, &params.allowance)?;
+
             emit::verifier_balance(
                 rt,
                 verifier_addr.id().unwrap(),
                 &new_verifier_cap,
                 Some(client.id().unwrap()),
+            )?;
+            emit::datacap_granted(
+                rt,
+                verifier_addr.id().unwrap(),
+                client.id().unwrap(),
+                &params.allowance,
+                &total_granted,
             )
         })?;
 
@@ -242,7 +399,7 @@ impl Actor {
             ));
         }
 
-        let (verifier_1_id, verifier_2_id) = rt.transaction(|st: &mut State, rt| {
+        let (verifier_1_id, verifier_2_id) = rt.versioned_transaction(|st: &mut State, rt| {
             rt.validate_immediate_caller_is(std::iter::once(&st.root_key))?;
 
             if params.verified_client_to_remove == VERIFIED_REGISTRY_ACTOR_ADDR {
@@ -297,7 +454,7 @@ impl Actor {
 
         // Burn the client's data cap tokens.
         let balance = balance(rt, &client).context("failed to fetch balance")?;
-        let burnt = std::cmp::min(balance, params.data_cap_amount_to_remove);
+        let burnt = std::cmp::min(balance, params.data_cap_amount_to_remove.into_inner());
         destroy(rt, &client, &burnt)
             .context(format!("failed to destroy {} from allowance for {}", &burnt, &client))?;
 
@@ -321,7 +478,7 @@ impl Actor {
         let mut considered = Vec::<ClaimID>::new();
         let mut recovered_datacap = DataCap::zero();
         let recovered_datacap = rt
-            .transaction(|st: &mut State, rt| {
+            .versioned_transaction(|st: &mut State, rt| {
                 let mut allocs = st.load_allocs(rt.store())?;
 
                 let to_remove: Vec<&AllocationID>;
@@ -376,6 +533,56 @@ impl Actor {
         })
     }
 
+    /// Called by the storage market actor to remove unexpired allocations it made on behalf of
+    /// a client whose deals have been cancelled, returning the datacap to the client.
+    /// Each allocation is removed independently, failing with USR_NOT_FOUND if the client
+    /// has no such allocation.
+    pub fn release_allocations(
+        rt: &impl Runtime,
+        params: ReleaseAllocationsParams,
+    ) -> Result<ReleaseAllocationsReturn, ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&STORAGE_MARKET_ACTOR_ADDR))?;
+        let mut batch_gen = BatchReturnGen::new(params.allocation_ids.len());
+        let recovered_datacap = rt
+            .versioned_transaction(|st: &mut State, rt| {
+                let mut allocs = st.load_allocs(rt.store())?;
+                let mut recovered_datacap = DataCap::zero();
+                for id in &params.allocation_ids {
+                    let removed = allocs.remove(params.client, *id).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to remove allocation {}", id),
+                    )?;
+                    match removed {
+                        Some(existing) => {
+                            emit::allocation_removed(rt, *id, &existing)?;
+                            recovered_datacap += existing.size.0;
+                            batch_gen.add_success();
+                        }
+                        None => {
+                            info!("no allocation {} for client {}", id, params.client);
+                            batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                        }
+                    }
+                }
+                st.save_allocs(&mut allocs)?;
+                Ok(recovered_datacap)
+            })
+            .context("state transaction failed")?;
+
+        // Transfer the recovered datacap back to the client.
+        transfer(rt, params.client, &recovered_datacap).with_context(|| {
+            format!(
+                "failed to transfer released datacap {} back to client {}",
+                &recovered_datacap, params.client
+            )
+        })?;
+
+        Ok(ReleaseAllocationsReturn {
+            results: batch_gen.generate(),
+            datacap_recovered: recovered_datacap,
+        })
+    }
+
     /// Called by storage provider actor to claim allocations for data provably committed to storage.
     /// For each allocation claim, the registry checks that the provided piece CID
     /// and size match that of the allocation.
@@ -397,7 +604,7 @@ impl Actor {
         let mut sector_results: Vec<SectorClaimSummary> = vec![];
         let mut total_claimed_space = DataCap::zero();
 
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let mut claims = st.load_claims(rt.store())?;
             let mut allocs = st.load_allocs(rt.store())?;
 
@@ -420,19 +627,20 @@ impl Actor {
                             batch_gen.add_fail(ExitCode::USR_FORBIDDEN);
                             continue 'sectors;
                         }
-                        sector_new_claims.push((
-                            claim.allocation_id,
-                            Claim {
-                                provider,
-                                client: alloc.client,
-                                data: alloc.data,
-                                size: alloc.size,
-                                term_min: alloc.term_min,
-                                term_max: alloc.term_max,
-                                term_start: rt.curr_epoch(),
-                                sector: sector.sector,
-                            },
-                        ));
+                        let mut new_claim = Claim {
+                            provider,
+                            client: alloc.client,
+                            data: alloc.data,
+                            size: alloc.size,
+                            term_min: alloc.term_min,
+                            term_max: alloc.term_max,
+                            term_start: rt.curr_epoch(),
+                            sector: sector.sector,
+                            commitment: vec![],
+                        };
+                        new_claim.commitment =
+                            rt.hash(SupportedHashes::Sha2_256, &new_claim.commitment_preimage());
+                        sector_new_claims.push((claim.allocation_id, new_claim));
                     } else {
                         info!("no allocation {} for client {}", claim.allocation_id, claim.client);
                         batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
@@ -495,7 +703,7 @@ impl Actor {
     ) -> Result<GetClaimsReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let mut batch_gen = BatchReturnGen::new(params.claim_ids.len());
-        let st: State = rt.state()?;
+        let st: State = rt.versioned_state()?;
         let mut st_claims = st.load_claims(rt.store())?;
         let mut claims = Vec::new();
         for id in params.claim_ids {
@@ -515,6 +723,63 @@ impl Actor {
         Ok(GetClaimsReturn { batch_info: batch_gen.generate(), claims })
     }
 
+    /// Returns aggregate totals over all of a provider's claims, without returning the claims
+    /// themselves, which may be too many to list in one call.
+    pub fn get_claim_totals(
+        rt: &impl Runtime,
+        params: GetClaimTotalsParams,
+    ) -> Result<GetClaimTotalsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.expiring_within < 0 {
+            return Err(actor_error!(
+                illegal_argument,
+                "negative expiry window {}",
+                params.expiring_within
+            ));
+        }
+        let expiring_by = rt.curr_epoch().saturating_add(params.expiring_within);
+        let st: State = rt.versioned_state()?;
+        let mut claims = st.load_claims(rt.store())?;
+        let mut totals = GetClaimTotalsReturn { count: 0, total_size: 0, expiring_size: 0 };
+        claims
+            .for_each_in(params.provider, |_, claim: &Claim| {
+                totals.count += 1;
+                totals.total_size += claim.size.0;
+                if claim.expiration() <= expiring_by {
+                    totals.expiring_size += claim.size.0;
+                }
+                Ok(())
+            })
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate claims")?;
+        Ok(totals)
+    }
+
+    /// Returns the summary of a provider's claims which have expired and been removed,
+    /// a durable record of the verified data it has stored.
+    pub fn get_provider_history(
+        rt: &impl Runtime,
+        params: GetProviderHistoryParams,
+    ) -> Result<ProviderHistory, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.versioned_state()?;
+        st.get_provider_history(rt.store(), params.provider)
+    }
+
+    /// Returns the verified registry policy values in effect on this network,
+    /// which differ between networks.
+    pub fn get_verifreg_policy(rt: &impl Runtime) -> Result<GetVerifregPolicyReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let policy = rt.policy();
+        Ok(GetVerifregPolicyReturn {
+            minimum_allocation_size: policy.minimum_verified_allocation_size.clone(),
+            minimum_allocation_term: policy.minimum_verified_allocation_term,
+            maximum_allocation_term: policy.maximum_verified_allocation_term,
+            maximum_allocation_expiration: policy.maximum_verified_allocation_expiration,
+            end_of_life_claim_drop_period: policy.end_of_life_claim_drop_period,
+            claim_dispute_window: policy.verified_claim_dispute_window,
+        })
+    }
+
     /// Extends the maximum term of some claims up to the largest value they could have been
     /// originally allocated.
     /// Callable only by the claims' client.
@@ -531,7 +796,7 @@ impl Actor {
         let caller_id = rt.message().caller().id().unwrap();
         let term_limit = rt.policy().maximum_verified_allocation_term;
         let mut batch_gen = BatchReturnGen::new(params.terms.len());
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let mut st_claims = st.load_claims(rt.store())?;
             for term in params.terms {
                 // Confirm the new term limit is allowed.
@@ -565,7 +830,7 @@ impl Actor {
                         continue;
                     }
 
-                    let new_claim = Claim { term_max: term.term_max, ..*claim };
+                    let new_claim = Claim { term_max: term.term_max, ..claim.clone() };
                     st_claims.put(term.provider, term.claim_id, new_claim.clone()).context_code(
                         ExitCode::USR_ILLEGAL_STATE,
                         "HAMT put failure storing new claims",
@@ -595,8 +860,9 @@ impl Actor {
         let curr_epoch = rt.curr_epoch();
         let mut batch_ret = BatchReturn::empty();
         let mut considered = Vec::<ClaimID>::new();
-        rt.transaction(|st: &mut State, rt| {
+        rt.versioned_transaction(|st: &mut State, rt| {
             let mut claims = st.load_claims(rt.store())?;
+            let mut removed_claims = Vec::new();
             let to_remove: Vec<&ClaimID>;
             if params.claim_ids.is_empty() {
                 // Find all expired claims for the provider.
@@ -624,9 +890,11 @@ impl Actor {
                     .unwrap();
 
                 emit::claim_removed(rt, *id, &removed)?;
+                removed_claims.push(removed);
             }
 
             st.save_claims(&mut claims)?;
+            st.record_expired_claims(rt.store(), params.provider, &removed_claims)?;
             Ok(())
         })
         .context("state transaction failed")?;
@@ -634,6 +902,163 @@ impl Actor {
         Ok(RemoveExpiredClaimsReturn { considered, results: batch_ret })
     }
 
+    /// Removes a provider's expired claims, found by the actor so that the caller need not
+    /// enumerate claim IDs. At most `limit` of the provider's claims are examined, starting at
+    /// the cursor (or the beginning), in the claim map's internal order.
+    /// Returns the IDs of the removed claims, and the cursor from which to continue if any of
+    /// the provider's claims remain to be examined.
+    pub fn remove_expired_claims_limited(
+        rt: &impl Runtime,
+        params: RemoveExpiredClaimsLimitedParams,
+    ) -> Result<RemoveExpiredClaimsLimitedReturn, ActorError> {
+        // Since the claims are expired, this is safe to be called by anyone.
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 {
+            return Err(actor_error!(illegal_argument, "limit must be positive"));
+        }
+        let curr_epoch = rt.curr_epoch();
+        rt.versioned_transaction(|st: &mut State, rt| {
+            let mut claims = st.load_claims(rt.store())?;
+            let (removed, next_cursor) = expiration::find_expired_ranged(
+                &mut claims,
+                params.provider,
+                params.cursor,
+                params.limit,
+                curr_epoch,
+            )?;
+
+            let mut removed_claims = Vec::with_capacity(removed.len());
+            for id in &removed {
+                let claim = claims
+                    .remove(params.provider, *id)
+                    .context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to remove claim {}", id),
+                    )?
+                    .unwrap(); // The claim was just found to be expired.
+
+                emit::claim_removed(rt, *id, &claim)?;
+                removed_claims.push(claim);
+            }
+
+            st.save_claims(&mut claims)?;
+            st.record_expired_claims(rt.store(), params.provider, &removed_claims)?;
+            Ok(RemoveExpiredClaimsLimitedReturn { removed, next_cursor })
+        })
+        .context("state transaction failed")
+    }
+
+    /// Disputes a claim (e.g. for committing the wrong piece), pending resolution by the root key.
+    /// Callable only by the claim's client, within the policy's dispute window after the claim
+    /// was made. Disputes are disabled when the window is zero.
+    pub fn dispute_claim(rt: &impl Runtime, params: DisputeClaimParams) -> Result<(), ActorError> {
+        // Permissions are checked against the claim.
+        rt.validate_immediate_caller_accept_any()?;
+        let caller_id = rt.message().caller().id().unwrap();
+        let window = rt.policy().verified_claim_dispute_window;
+        if window <= 0 {
+            return Err(actor_error!(forbidden, "claim disputes are disabled"));
+        }
+        let curr_epoch = rt.curr_epoch();
+        rt.versioned_transaction(|st: &mut State, rt| {
+            let mut claims = st.load_claims(rt.store())?;
+            let claim = state::get_claim(&mut claims, params.provider, params.claim_id)?
+                .ok_or_else(|| {
+                    actor_error!(
+                        not_found,
+                        "no claim {} for provider {}",
+                        params.claim_id,
+                        params.provider
+                    )
+                })?;
+            if claim.client != caller_id {
+                return Err(actor_error!(
+                    forbidden,
+                    "client {} for claim {} does not match caller {}",
+                    claim.client,
+                    params.claim_id,
+                    caller_id
+                ));
+            }
+            if curr_epoch >= claim.term_start + window {
+                return Err(actor_error!(
+                    forbidden,
+                    "dispute window for claim {} closed at {}",
+                    params.claim_id,
+                    claim.term_start + window
+                ));
+            }
+
+            let mut disputes = st.load_claim_disputes(rt.store())?;
+            let dispute = ClaimDispute { provider: params.provider, epoch: curr_epoch };
+            if !disputes.set_if_absent(&params.claim_id, dispute)? {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "claim {} is already disputed",
+                    params.claim_id
+                ));
+            }
+            st.save_claim_disputes(&mut disputes)?;
+
+            emit::claim_disputed(rt, params.claim_id, claim)
+        })
+        .context("state transaction failed")
+    }
+
+    /// Resolves a claim dispute. Callable only by the root key.
+    /// An upheld dispute revokes the claim, restores its datacap to the client, and removes
+    /// the claimed space from the provider's sector.
+    /// A dispute of a claim that no longer exists is simply dropped.
+    pub fn resolve_claim_dispute(
+        rt: &impl Runtime,
+        params: ResolveClaimDisputeParams,
+    ) -> Result<(), ActorError> {
+        let (provider, revoked) = rt
+            .versioned_transaction(|st: &mut State, rt| {
+                rt.validate_immediate_caller_is(std::iter::once(&st.root_key))?;
+
+                let mut disputes = st.load_claim_disputes(rt.store())?;
+                let dispute = disputes.delete(&params.claim_id)?.ok_or_else(|| {
+                    actor_error!(not_found, "no dispute for claim {}", params.claim_id)
+                })?;
+                st.save_claim_disputes(&mut disputes)?;
+
+                let mut revoked = None;
+                if params.upheld {
+                    let mut claims = st.load_claims(rt.store())?;
+                    revoked = claims.remove(dispute.provider, params.claim_id).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to remove claim {}", params.claim_id),
+                    )?;
+                    st.save_claims(&mut claims)?;
+                    if let Some(claim) = &revoked {
+                        emit::claim_removed(rt, params.claim_id, claim)?;
+                    }
+                }
+                emit::claim_dispute_resolved(rt, params.claim_id, dispute.provider, params.upheld)?;
+                Ok((dispute.provider, revoked))
+            })
+            .context("state transaction failed")?;
+
+        if let Some(claim) = revoked {
+            // Restore the datacap spent on the claim, burned when it was made.
+            mint(rt, &Address::new_id(claim.client), &DataCap::from(claim.size.0), vec![])?;
+            extract_send_result(rt.send_simple(
+                &Address::new_id(provider),
+                ext::miner::ON_CLAIM_REVOKED_METHOD,
+                IpldBlock::serialize_cbor(&ext::miner::OnClaimRevokedParams {
+                    sector: claim.sector,
+                    space: claim.size.0,
+                })?,
+                TokenAmount::zero(),
+            ))
+            .with_context(|| {
+                format!("failed to revoke claim {} from provider {}", params.claim_id, provider)
+            })?;
+        }
+        Ok(())
+    }
+
     // Receives data cap tokens (only) and creates allocations according to one or more
     // allocation requests specified in the transfer's operator data.
     // The token amount received must exactly correspond to the sum of the requested allocation sizes.
@@ -654,6 +1079,18 @@ impl Actor {
         let tokens_received = validate_tokens_received(&params, my_id)?;
         let client = tokens_received.from;
 
+        // A verifier may top up its allowance with datacap, tagged as such in the operator data.
+        if let Ok(VerifierTopUpRequest { operation: VERIFIER_TOP_UP_OPERATION }) =
+            tokens_received.operator_data.deserialize::<VerifierTopUpRequest>()
+        {
+            top_up_verifier_allowance(rt, client, &tokens_received.amount)?;
+            return Ok(AllocationsResponse {
+                allocation_results: BatchReturn::empty(),
+                extension_results: BatchReturn::empty(),
+                new_allocations: vec![],
+            });
+        }
+
         // Extract and validate allocation request from the operator data.
         let reqs: AllocationRequests =
             deserialize(&tokens_received.operator_data, "allocation requests")?;
@@ -678,7 +1115,7 @@ impl Actor {
             datacap_total += DataCap::from(req.size.0);
         }
 
-        let st: State = rt.state()?;
+        let st: State = rt.versioned_state()?;
         let mut claims = st.load_claims(rt.store())?;
         let mut updated_claims = Vec::<(ClaimID, Claim)>::new();
         let mut extension_total = DataCap::zero();
@@ -694,7 +1131,7 @@ impl Actor {
             validate_claim_extension(req, claim, policy, curr_epoch)?;
             // The claim's client is not changed to be the address of the token sender.
             // It remains the original allocation client.
-            updated_claims.push((req.claim, Claim { term_max: req.term_max, ..*claim }));
+            updated_claims.push((req.claim, Claim { term_max: req.term_max, ..claim.clone() }));
             datacap_total += DataCap::from(claim.size.0);
             extension_total += DataCap::from(claim.size.0);
         }
@@ -719,7 +1156,7 @@ impl Actor {
         let extension_results = BatchReturn::ok(updated_claims.len() as u32);
 
         // Save new allocations and updated claims.
-        let ids = rt.transaction(|st: &mut State, rt| {
+        let ids = rt.versioned_transaction(|st: &mut State, rt| {
             let ids = st.insert_allocations(rt.store(), client, new_allocs.clone())?;
 
             for (id, alloc) in ids.iter().zip(new_allocs.iter()) {
@@ -739,6 +1176,38 @@ impl Actor {
     }
 }
 
+// Adds received datacap tokens to a verifier's allowance, burning the tokens.
+fn top_up_verifier_allowance(
+    rt: &impl Runtime,
+    verifier: ActorID,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    let top_up = tokens_to_datacap(amount);
+    if !top_up.is_positive() || datacap_to_tokens(&top_up) != *amount {
+        return Err(actor_error!(
+            illegal_argument,
+            "verifier top-up {} must be a positive whole amount of datacap",
+            amount
+        ));
+    }
+
+    let verifier_addr = Address::new_id(verifier);
+    let new_allowance = rt.versioned_transaction(|st: &mut State, rt| {
+        let allowance = st
+            .get_verifier_cap(rt.store(), &verifier_addr)?
+            .with_context_code(ExitCode::USR_FORBIDDEN, || {
+                format!("sender {} is not a verifier", verifier_addr)
+            })?;
+        let new_allowance = allowance + &top_up;
+        st.put_verifier(rt.store(), &verifier_addr, &new_allowance)
+            .context("failed to update verifier allowance")?;
+        Ok(new_allowance)
+    })?;
+
+    burn(rt, &top_up)?;
+    emit::verifier_balance(rt, verifier, &new_allowance, None)
+}
+
 // Checks whether an address has a verifier entry (which could be zero).
 fn is_verifier(rt: &impl Runtime, st: &State, address: Address) -> Result<bool, ActorError> {
     let verifiers = DataCapMap::load(rt.store(), &st.verifiers, DATACAP_MAP_CONFIG, "verifiers")?;
@@ -772,7 +1241,7 @@ fn mint(
     operators: Vec<Address>,
 ) -> Result<(), ActorError> {
     let token_amt = datacap_to_tokens(amount);
-    let params = MintParams { to: *to, amount: token_amt, operators };
+    let params = MintParams { to: *to, amount: NonNegativeTokenAmount::new(token_amt)?, operators };
     extract_send_result(rt.send_simple(
         &DATACAP_TOKEN_ACTOR_ADDR,
         ext::datacap::Method::Mint as u64,
@@ -809,7 +1278,7 @@ fn destroy(rt: &impl Runtime, owner: &Address, amount: &DataCap) -> Result<(), A
         return Ok(());
     }
     let token_amt = datacap_to_tokens(amount);
-    let params = DestroyParams { owner: *owner, amount: token_amt };
+    let params = DestroyParams { owner: *owner, amount: NonNegativeTokenAmount::new(token_amt)? };
     extract_send_result(rt.send_simple(
         &DATACAP_TOKEN_ACTOR_ADDR,
         ext::datacap::Method::Destroy as u64,
@@ -1094,6 +1563,29 @@ fn can_claim_alloc(
 impl ActorCode for Actor {
     type Methods = Method;
 
+    const DEPRECATED_METHODS: &'static [MethodDeprecation] = &[
+        MethodDeprecation {
+            method: Method::AddVerifiedClient as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::AddVerifiedClientExported as u64,
+        },
+        MethodDeprecation {
+            method: Method::RemoveExpiredAllocations as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::RemoveExpiredAllocationsExported as u64,
+        },
+        MethodDeprecation {
+            method: Method::ExtendClaimTerms as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::ExtendClaimTermsExported as u64,
+        },
+        MethodDeprecation {
+            method: Method::RemoveExpiredClaims as u64,
+            deprecated_at: INTERNAL_METHODS_SUNSET_VERSION,
+            replacement: Method::RemoveExpiredClaimsExported as u64,
+        },
+    ];
+
     fn name() -> &'static str {
         "VerifiedRegistry"
     }
@@ -1109,6 +1601,16 @@ impl ActorCode for Actor {
         GetClaims|GetClaimsExported => get_claims,
         ExtendClaimTerms|ExtendClaimTermsExported => extend_claim_terms,
         RemoveExpiredClaims|RemoveExpiredClaimsExported => remove_expired_claims,
+        DisputeClaimExported => dispute_claim,
+        ResolveClaimDisputeExported => resolve_claim_dispute,
+        GetVerifregPolicyExported => get_verifreg_policy,
         UniversalReceiverHook => universal_receiver_hook,
+        ReleaseAllocationsExported => release_allocations,
+        GetClaimTotalsExported => get_claim_totals,
+        AddObserverVerifierExported => add_observer_verifier,
+        RemoveObserverVerifierExported => remove_observer_verifier,
+        ListObserverVerifiersExported => list_observer_verifiers,
+        GetProviderHistoryExported => get_provider_history,
+        RemoveExpiredClaimsLimitedExported => remove_expired_claims_limited,
     }
 }
@@ -3,6 +3,7 @@
 
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntDe;
@@ -13,8 +14,10 @@ use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
 
 use fil_actors_runtime::{
-    ActorError, AsActorError, Config, DEFAULT_HAMT_CONFIG, Map2, MapMap, actor_error,
+    ActorError, AsActorError, Config, DEFAULT_HAMT_CONFIG, Map2, MapMap, VersionedState,
+    actor_error, decode_state_version,
 };
+use ipld_core::ipld::Ipld;
 
 use crate::{AddrPairKey, AllocationID, ClaimID};
 use crate::{DataCap, RemoveDataCapProposalID};
@@ -25,6 +28,15 @@ pub const DATACAP_MAP_CONFIG: Config = DEFAULT_HAMT_CONFIG;
 pub type RemoveDataCapProposalMap<BS> = Map2<BS, AddrPairKey, RemoveDataCapProposalID>;
 pub const REMOVE_DATACAP_PROPOSALS_CONFIG: Config = DEFAULT_HAMT_CONFIG;
 
+pub type ClaimDisputeMap<BS> = Map2<BS, ClaimID, ClaimDispute>;
+pub const CLAIM_DISPUTES_CONFIG: Config = DEFAULT_HAMT_CONFIG;
+
+pub type ObserverVerifierMap<BS> = Map2<BS, ActorID, ObserverVerifier>;
+pub const OBSERVER_VERIFIERS_CONFIG: Config = DEFAULT_HAMT_CONFIG;
+
+pub type ProviderHistoryMap<BS> = Map2<BS, ActorID, ProviderHistory>;
+pub const PROVIDER_HISTORIES_CONFIG: Config = DEFAULT_HAMT_CONFIG;
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone)]
 pub struct State {
     pub root_key: Address,
@@ -38,6 +50,66 @@ pub struct State {
     pub next_allocation_id: u64,
     // Maps provider IDs to allocations claimed by that provider.
     pub claims: Cid, // HAMT[ActorID]HAMT[ClaimID]Claim
+    // Maps claim IDs to open disputes of those claims by their clients.
+    // Absent until the first dispute is opened.
+    #[serde(default)]
+    pub claim_disputes: Option<Cid>, // HAMT[ClaimID]ClaimDispute
+    // Maps verifier addresses to the total data cap they have granted to clients.
+    // Absent until the first grant is recorded.
+    #[serde(default)]
+    pub verifier_grants: Option<Cid>, // HAMT[Address]DataCap
+    // Maps observer verifier IDs to their registration.
+    // Observers are approved notaries which have not (yet) been assigned an allowance.
+    // Absent while there are no observers.
+    #[serde(default)]
+    pub observer_verifiers: Option<Cid>, // HAMT[ActorID]ObserverVerifier
+    // Maps provider IDs to a summary of their claims which have expired and been removed.
+    // Absent until the first expired claim is removed.
+    #[serde(default)]
+    pub provider_histories: Option<Cid>, // HAMT[ActorID]ProviderHistory
+}
+
+// Version 1 added the claim disputes, verifier grants, observer verifiers
+// and provider histories, each absent until first used.
+impl VersionedState for State {
+    const VERSION: u64 = 1;
+
+    fn upgrade(version: u64, state: Ipld) -> Result<Self, ActorError> {
+        match version {
+            0 => {
+                let old: StateV0 = decode_state_version(state)?;
+                Ok(State {
+                    root_key: old.root_key,
+                    verifiers: old.verifiers,
+                    remove_data_cap_proposal_ids: old.remove_data_cap_proposal_ids,
+                    allocations: old.allocations,
+                    next_allocation_id: old.next_allocation_id,
+                    claims: old.claims,
+                    claim_disputes: None,
+                    verifier_grants: None,
+                    observer_verifiers: None,
+                    provider_histories: None,
+                })
+            }
+            _ => Err(actor_error!(
+                illegal_state,
+                "unsupported state version {}, expected {}",
+                version,
+                Self::VERSION
+            )),
+        }
+    }
+}
+
+// The state schema at version 0, stored untagged.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone)]
+struct StateV0 {
+    root_key: Address,
+    verifiers: Cid,
+    remove_data_cap_proposal_ids: Cid,
+    allocations: Cid,
+    next_allocation_id: u64,
+    claims: Cid,
 }
 
 impl State {
@@ -57,6 +129,10 @@ impl State {
             allocations: empty_allocs_claims,
             next_allocation_id: 1,
             claims: empty_allocs_claims,
+            claim_disputes: None,
+            verifier_grants: None,
+            observer_verifiers: None,
+            provider_histories: None,
         })
     }
 
@@ -196,6 +272,181 @@ impl State {
         self.save_claims(&mut st_claims)?;
         Ok(())
     }
+
+    pub fn load_claim_disputes<BS: Blockstore>(
+        &self,
+        store: BS,
+    ) -> Result<ClaimDisputeMap<BS>, ActorError> {
+        match &self.claim_disputes {
+            Some(root) => {
+                ClaimDisputeMap::load(store, root, CLAIM_DISPUTES_CONFIG, "claim disputes")
+            }
+            None => Ok(ClaimDisputeMap::empty(store, CLAIM_DISPUTES_CONFIG, "claim disputes")),
+        }
+    }
+
+    pub fn save_claim_disputes<BS: Blockstore>(
+        &mut self,
+        disputes: &mut ClaimDisputeMap<BS>,
+    ) -> Result<(), ActorError> {
+        self.claim_disputes = if disputes.is_empty() { None } else { Some(disputes.flush()?) };
+        Ok(())
+    }
+
+    pub fn load_verifier_grants<BS: Blockstore>(
+        &self,
+        store: BS,
+    ) -> Result<DataCapMap<BS>, ActorError> {
+        match &self.verifier_grants {
+            Some(root) => DataCapMap::load(store, root, DATACAP_MAP_CONFIG, "verifier grants"),
+            None => Ok(DataCapMap::empty(store, DATACAP_MAP_CONFIG, "verifier grants")),
+        }
+    }
+
+    /// Adds to the total data cap granted by a verifier, returning the new total.
+    pub fn add_verifier_grant(
+        &mut self,
+        store: &impl Blockstore,
+        verifier: &Address,
+        amount: &DataCap,
+    ) -> Result<DataCap, ActorError> {
+        let mut grants = self.load_verifier_grants(store)?;
+        let total = grants.get(verifier)?.map(|g| g.0.clone()).unwrap_or_default() + amount;
+        grants.set(verifier, BigIntDe(total.clone()))?;
+        self.verifier_grants = Some(grants.flush()?);
+        Ok(total)
+    }
+
+    pub fn load_observer_verifiers<BS: Blockstore>(
+        &self,
+        store: BS,
+    ) -> Result<ObserverVerifierMap<BS>, ActorError> {
+        match &self.observer_verifiers {
+            Some(root) => ObserverVerifierMap::load(
+                store,
+                root,
+                OBSERVER_VERIFIERS_CONFIG,
+                "observer verifiers",
+            ),
+            None => Ok(ObserverVerifierMap::empty(
+                store,
+                OBSERVER_VERIFIERS_CONFIG,
+                "observer verifiers",
+            )),
+        }
+    }
+
+    // Lists at most `limit` observer verifiers in order of actor ID, starting from the first
+    // with an ID not below the cursor. Returns the observer from which to continue listing, if any.
+    // Observers are registered by the root key and so are few; listing reads them all
+    // so that a cursor remains valid if that observer is removed between calls.
+    pub fn list_observer_verifiers(
+        &self,
+        store: &impl Blockstore,
+        cursor: Option<ActorID>,
+        limit: u64,
+    ) -> Result<(Vec<(ActorID, ObserverVerifier)>, Option<ActorID>), ActorError> {
+        let start = cursor.unwrap_or(0);
+        let mut listed = Vec::new();
+        self.load_observer_verifiers(store)?.for_each(|id, observer| {
+            if id >= start {
+                listed.push((id, observer.clone()));
+            }
+            Ok(())
+        })?;
+        listed.sort_by_key(|(id, _)| *id);
+        let next = listed.get(limit as usize).map(|(id, _)| *id);
+        listed.truncate(limit as usize);
+        Ok((listed, next))
+    }
+
+    pub fn get_observer_verifier(
+        &self,
+        store: &impl Blockstore,
+        verifier: ActorID,
+    ) -> Result<Option<ObserverVerifier>, ActorError> {
+        Ok(self.load_observer_verifiers(store)?.get(&verifier)?.cloned())
+    }
+
+    // Registers an observer verifier, overwriting any existing registration.
+    pub fn put_observer_verifier(
+        &mut self,
+        store: &impl Blockstore,
+        verifier: ActorID,
+        observer: ObserverVerifier,
+    ) -> Result<(), ActorError> {
+        let mut observers = self.load_observer_verifiers(store)?;
+        observers.set(&verifier, observer)?;
+        self.observer_verifiers = Some(observers.flush()?);
+        Ok(())
+    }
+
+    /// Removes an observer verifier's registration, returning whether it was registered.
+    pub fn remove_observer_verifier(
+        &mut self,
+        store: &impl Blockstore,
+        verifier: ActorID,
+    ) -> Result<bool, ActorError> {
+        if self.observer_verifiers.is_none() {
+            return Ok(false);
+        }
+        let mut observers = self.load_observer_verifiers(store)?;
+        let removed = observers.delete(&verifier)?.is_some();
+        if removed {
+            self.observer_verifiers =
+                if observers.is_empty() { None } else { Some(observers.flush()?) };
+        }
+        Ok(removed)
+    }
+
+    pub fn load_provider_histories<BS: Blockstore>(
+        &self,
+        store: BS,
+    ) -> Result<ProviderHistoryMap<BS>, ActorError> {
+        match &self.provider_histories {
+            Some(root) => ProviderHistoryMap::load(
+                store,
+                root,
+                PROVIDER_HISTORIES_CONFIG,
+                "provider histories",
+            ),
+            None => Ok(ProviderHistoryMap::empty(
+                store,
+                PROVIDER_HISTORIES_CONFIG,
+                "provider histories",
+            )),
+        }
+    }
+
+    // Returns the summary of a provider's removed expired claims, empty if it has none.
+    pub fn get_provider_history(
+        &self,
+        store: &impl Blockstore,
+        provider: ActorID,
+    ) -> Result<ProviderHistory, ActorError> {
+        Ok(self.load_provider_histories(store)?.get(&provider)?.cloned().unwrap_or_default())
+    }
+
+    // Adds expired claims removed from state to the summary of a provider's history.
+    pub fn record_expired_claims<'a>(
+        &mut self,
+        store: &impl Blockstore,
+        provider: ActorID,
+        claims: impl IntoIterator<Item = &'a Claim>,
+    ) -> Result<(), ActorError> {
+        let mut histories = self.load_provider_histories(store)?;
+        let mut history = histories.get(&provider)?.cloned().unwrap_or_default();
+        let before = history.claims;
+        for claim in claims {
+            history.claims += 1;
+            history.total_size += claim.size.0;
+        }
+        if history.claims != before {
+            histories.set(&provider, history)?;
+            self.provider_histories = Some(histories.flush()?);
+        }
+        Ok(())
+    }
 }
 #[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
 pub struct Claim {
@@ -215,6 +466,63 @@ pub struct Claim {
     pub term_start: ChainEpoch,
     // ID of the provider's sector in which the data is committed.
     pub sector: SectorNumber,
+    // SHA-256 digest of the claim's commitment preimage (see `commitment_preimage`),
+    // computed when the claim is created.
+    // Claims created before commitments were introduced have an empty commitment,
+    // and their serialised form lacks this 9th element.
+    #[serde(default, with = "strict_bytes")]
+    pub commitment: Vec<u8>,
+}
+
+/// A client's dispute of a claim, awaiting resolution by the root key.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ClaimDispute {
+    // The provider holding the disputed claim.
+    pub provider: ActorID,
+    // The epoch at which the dispute was raised.
+    pub epoch: ChainEpoch,
+}
+
+/// A notary approved by the root key without a datacap allowance.
+/// Observers are listed alongside verifiers but cannot grant datacap to clients.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ObserverVerifier {
+    // Information identifying the notary, such as its application or governance reference.
+    pub metadata: String,
+    // The epoch at which the observer was (last) registered.
+    pub registered: ChainEpoch,
+}
+
+/// A summary of a provider's claims which have expired and been removed from state,
+/// recording its track record after the claims themselves are gone.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProviderHistory {
+    // Number of expired claims removed.
+    pub claims: u64,
+    // Total (padded) size of the data held under those claims (bytes).
+    pub total_size: u64,
+}
+
+/// Domain separation tag prefixed to a claim's commitment preimage.
+pub const CLAIM_COMMITMENT_DOMAIN: &[u8] = b"fil/verifreg/claim-commitment/v1";
+
+impl Claim {
+    /// Returns the canonical byte string to which a claim's commitment is computed.
+    /// This is the domain separation tag followed by the fixed-width big-endian encoding of
+    /// the provider, client, data commitment digest (32 bytes), size, minimum term, term start
+    /// and sector number.
+    /// The maximum term is excluded, since it may be extended after the claim is created.
+    pub fn commitment_preimage(&self) -> Vec<u8> {
+        let mut preimage = CLAIM_COMMITMENT_DOMAIN.to_vec();
+        preimage.extend_from_slice(&self.provider.to_be_bytes());
+        preimage.extend_from_slice(&self.client.to_be_bytes());
+        preimage.extend_from_slice(self.data.hash().digest());
+        preimage.extend_from_slice(&self.size.0.to_be_bytes());
+        preimage.extend_from_slice(&self.term_min.to_be_bytes());
+        preimage.extend_from_slice(&self.term_start.to_be_bytes());
+        preimage.extend_from_slice(&self.sector.to_be_bytes());
+        preimage
+    }
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
@@ -13,7 +13,9 @@ use fil_actors_runtime::runtime::policy_constants::{
 };
 use fil_actors_runtime::{DEFAULT_HAMT_CONFIG, Map2, MessageAccumulator};
 
-use crate::{Allocation, AllocationID, Claim, ClaimID, DataCap, State};
+use crate::{
+    Allocation, AllocationID, Claim, ClaimID, DataCap, OBSERVER_VERIFIER_METADATA_MAX_LEN, State,
+};
 
 pub struct StateSummary {
     pub verifiers: HashMap<Address, DataCap>,
@@ -133,6 +135,92 @@ pub fn check_state_invariants<BS: Blockstore>(
         Err(e) => acc.add(format!("error loading claims {e}")),
     }
 
+    match state.load_verifier_grants(&store) {
+        Ok(grants) => {
+            let ret = grants.for_each(|verifier: Address, total| {
+                acc.require(
+                    verifier.protocol() == Protocol::ID,
+                    format!("granting verifier {verifier} should have ID protocol"),
+                );
+                acc.require(
+                    total.0.is_positive(),
+                    format!("verifier {verifier} total granted {} is not positive", total.0),
+                );
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating verifier grants");
+        }
+        Err(e) => acc.add(format!("error loading verifier grants {e}")),
+    }
+
+    match state.load_observer_verifiers(&store) {
+        Ok(observers) => {
+            let ret = observers.for_each(|verifier: ActorID, observer| {
+                let verifier_addr = Address::new_id(verifier);
+                acc.require(
+                    !all_verifiers.contains_key(&verifier_addr),
+                    format!("observer verifier {verifier_addr} is also a verifier"),
+                );
+                acc.require(
+                    observer.metadata.len() <= OBSERVER_VERIFIER_METADATA_MAX_LEN,
+                    format!(
+                        "observer verifier {verifier_addr} metadata length {} exceeds maximum",
+                        observer.metadata.len()
+                    ),
+                );
+                acc.require(
+                    observer.registered <= prior_epoch,
+                    format!("observer verifier {verifier_addr} registered in the future"),
+                );
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating observer verifiers");
+        }
+        Err(e) => acc.add(format!("error loading observer verifiers {e}")),
+    }
+
+    match state.load_provider_histories(&store) {
+        Ok(histories) => {
+            let ret = histories.for_each(|provider: ActorID, history| {
+                acc.require(
+                    history.claims > 0,
+                    format!("provider {provider} history records no claims"),
+                );
+                acc.require(
+                    history.total_size >= history.claims,
+                    format!(
+                        "provider {provider} history size {} is less than its {} claims",
+                        history.total_size, history.claims
+                    ),
+                );
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating provider histories");
+        }
+        Err(e) => acc.add(format!("error loading provider histories {e}")),
+    }
+
+    match state.load_claim_disputes(&store) {
+        Ok(disputes) => {
+            let ret = disputes.for_each(|claim_id: ClaimID, dispute| {
+                acc.require(
+                    claim_id < state.next_allocation_id,
+                    format!(
+                        "disputed claim id {} exceeds next {}",
+                        claim_id, state.next_allocation_id
+                    ),
+                );
+                acc.require(
+                    dispute.epoch <= prior_epoch,
+                    format!("claim {} dispute epoch {} is in the future", claim_id, dispute.epoch),
+                );
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating claim disputes");
+        }
+        Err(e) => acc.add(format!("error loading claim disputes {e}")),
+    }
+
     (
         StateSummary { verifiers: all_verifiers, allocations: all_allocations, claims: all_claims },
         acc,
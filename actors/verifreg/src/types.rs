@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use cid::Cid;
-use fil_actors_runtime::{BatchReturn, MapKey};
+use fil_actors_runtime::{BatchReturn, BoundedDataCap, MapKey};
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::ActorID;
 use fvm_shared::address::Address;
@@ -28,8 +28,7 @@ pub struct ConstructorParams {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct VerifierParams {
     pub address: Address,
-    #[serde(with = "bigint_ser")]
-    pub allowance: DataCap,
+    pub allowance: BoundedDataCap,
 }
 
 pub type AddVerifierParams = VerifierParams;
@@ -51,8 +50,7 @@ pub const SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP: &[u8] = b"fil_removedatac
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct RemoveDataCapParams {
     pub verified_client_to_remove: Address,
-    #[serde(with = "bigint_ser")]
-    pub data_cap_amount_to_remove: DataCap,
+    pub data_cap_amount_to_remove: BoundedDataCap,
     pub verifier_request_1: RemoveDataCapRequest,
     pub verifier_request_2: RemoveDataCapRequest,
 }
@@ -133,6 +131,23 @@ pub struct RemoveExpiredAllocationsReturn {
     pub datacap_recovered: DataCap,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ReleaseAllocationsParams {
+    // Client whose allocations are to be released.
+    pub client: ActorID,
+    // Allocations to remove, regardless of expiration.
+    pub allocation_ids: Vec<AllocationID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ReleaseAllocationsReturn {
+    // Results for each allocation.
+    pub results: BatchReturn,
+    // The amount of datacap returned to the client.
+    #[serde(with = "bigint_ser")]
+    pub datacap_recovered: DataCap,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct SectorAllocationClaims {
     pub sector: SectorNumber,
@@ -219,6 +234,18 @@ pub struct AllocationRequests {
     pub extensions: Vec<ClaimExtensionRequest>,
 }
 
+/// Operation tag identifying a [`VerifierTopUpRequest`] in receiver hook operator data.
+pub const VERIFIER_TOP_UP_OPERATION: u64 = 1;
+
+/// Operator-data payload for a datacap token transfer receiver hook which adds the transferred
+/// datacap to the allowance of the sender, which must be a verifier. The datacap is burnt.
+/// The encoding is distinct from that of [`AllocationRequests`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct VerifierTopUpRequest {
+    /// Must be [`VERIFIER_TOP_UP_OPERATION`].
+    pub operation: u64,
+}
+
 /// Recipient data payload in response to a datacap token transfer.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct AllocationsResponse {
@@ -242,6 +269,49 @@ pub struct GetClaimsReturn {
     pub claims: Vec<Claim>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetClaimTotalsParams {
+    pub provider: ActorID,
+    // Window (epochs from the current epoch) within which claims are counted as expiring.
+    pub expiring_within: ChainEpoch,
+}
+
+/// Aggregate totals over all of a provider's claims.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetClaimTotalsReturn {
+    /// Number of claims held by the provider.
+    pub count: u64,
+    /// Total (padded) size of the provider's claimed data (bytes).
+    pub total_size: u64,
+    /// Total (padded) size of claims whose maximum term ends within the window,
+    /// including claims already expired but not yet removed (bytes).
+    pub expiring_size: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetProviderHistoryParams {
+    pub provider: ActorID,
+}
+
+/// The verified registry policy in effect on this network.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetVerifregPolicyReturn {
+    /// Minimum size of a verified allocation, and of a verifier or client allowance (bytes).
+    #[serde(with = "bigint_ser")]
+    pub minimum_allocation_size: StoragePower,
+    /// Minimum term for a verified allocation (epochs).
+    pub minimum_allocation_term: ChainEpoch,
+    /// Maximum term for a verified allocation (epochs).
+    pub maximum_allocation_term: ChainEpoch,
+    /// Maximum time a verified allocation can remain unclaimed (epochs).
+    pub maximum_allocation_expiration: ChainEpoch,
+    /// Period at the end of a sector's life during which its claims can be dropped (epochs).
+    pub end_of_life_claim_drop_period: ChainEpoch,
+    /// Period after a claim is made during which its client may dispute it (epochs).
+    pub claim_dispute_window: ChainEpoch,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct RemoveExpiredClaimsParams {
     // Provider to clean up (need not be the caller)
@@ -258,3 +328,80 @@ pub struct RemoveExpiredClaimsReturn {
     // Results for each processed claim.
     pub results: BatchReturn,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RemoveExpiredClaimsLimitedParams {
+    // Provider to clean up (need not be the caller)
+    pub provider: ActorID,
+    // Maximum number of the provider's claims to examine. Must be positive.
+    pub limit: u64,
+    // Claim from which to continue examining, as returned by a previous call,
+    // or none to start from the beginning.
+    pub cursor: Option<ClaimID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RemoveExpiredClaimsLimitedReturn {
+    // Ids of the expired claims removed.
+    pub removed: Vec<ClaimID>,
+    // Claim from which to continue examining, if any of the provider's claims remain.
+    pub next_cursor: Option<ClaimID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DisputeClaimParams {
+    // Provider holding the claim.
+    pub provider: ActorID,
+    // The claim to dispute.
+    pub claim_id: ClaimID,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ResolveClaimDisputeParams {
+    // The disputed claim.
+    pub claim_id: ClaimID,
+    // Whether the dispute is upheld, revoking the claim.
+    pub upheld: bool,
+}
+
+/// Maximum length (bytes) of an observer verifier's metadata.
+pub const OBSERVER_VERIFIER_METADATA_MAX_LEN: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AddObserverVerifierParams {
+    pub address: Address,
+    // Information identifying the notary, at most OBSERVER_VERIFIER_METADATA_MAX_LEN bytes.
+    pub metadata: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct RemoveObserverVerifierParams {
+    pub verifier: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ObserverVerifierInfo {
+    pub verifier: ActorID,
+    pub metadata: String,
+    // The epoch at which the observer was (last) registered.
+    pub registered: ChainEpoch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListObserverVerifiersParams {
+    // Observer from which to continue listing, as returned by a previous call.
+    // Listing resumes from the next observer by actor ID if that observer has since been removed.
+    // If absent, listing starts from the beginning.
+    pub cursor: Option<ActorID>,
+    // Maximum number of observers to return. Must be positive.
+    pub limit: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListObserverVerifiersReturn {
+    // Observers in order of actor ID.
+    pub observers: Vec<ObserverVerifierInfo>,
+    // Observer from which to continue listing, absent if there are no more observers.
+    pub next_cursor: Option<ActorID>,
+}
@@ -12,6 +12,7 @@ use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PaddedPieceSize;
@@ -23,12 +24,16 @@ use num_traits::{ToPrimitive, Zero};
 use fil_actor_verifreg::state::{DATACAP_MAP_CONFIG, DataCapMap};
 use fil_actor_verifreg::testing::check_state_invariants;
 use fil_actor_verifreg::{
-    Actor as VerifregActor, AddVerifiedClientParams, AddVerifierParams, Allocation,
-    AllocationClaim, AllocationID, AllocationRequest, AllocationRequests, AllocationsResponse,
-    Claim, ClaimAllocationsParams, ClaimAllocationsReturn, ClaimExtensionRequest, ClaimID, DataCap,
-    ExtendClaimTermsParams, ExtendClaimTermsReturn, GetClaimsParams, GetClaimsReturn, Method,
-    RemoveExpiredAllocationsParams, RemoveExpiredAllocationsReturn, RemoveExpiredClaimsParams,
-    RemoveExpiredClaimsReturn, SectorAllocationClaims, State, ext,
+    Actor as VerifregActor, AddObserverVerifierParams, AddVerifiedClientParams, AddVerifierParams,
+    Allocation, AllocationClaim, AllocationID, AllocationRequest, AllocationRequests,
+    AllocationsResponse, Claim, ClaimAllocationsParams, ClaimAllocationsReturn,
+    ClaimExtensionRequest, ClaimID, DataCap, ExtendClaimTermsParams, ExtendClaimTermsReturn,
+    GetClaimTotalsParams, GetClaimTotalsReturn, GetClaimsParams, GetClaimsReturn,
+    GetProviderHistoryParams, ListObserverVerifiersParams, ListObserverVerifiersReturn, Method,
+    ObserverVerifier, ProviderHistory, RemoveExpiredAllocationsParams,
+    RemoveExpiredAllocationsReturn, RemoveExpiredClaimsLimitedParams,
+    RemoveExpiredClaimsLimitedReturn, RemoveExpiredClaimsParams, RemoveExpiredClaimsReturn,
+    SectorAllocationClaims, State, ext,
 };
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::Runtime;
@@ -105,7 +110,7 @@ impl Harness {
         rt.verify();
 
         let empty_map = DataCapMap::empty(&rt.store, DATACAP_MAP_CONFIG, "empty").flush().unwrap();
-        let state: State = rt.get_state();
+        let state: State = rt.get_versioned_state();
         assert_eq!(self.root, state.root_key);
         assert_eq!(empty_map, state.verifiers);
     }
@@ -149,8 +154,22 @@ impl Harness {
                 .field("balance", &BigIntSer(allowance))
                 .build()?,
         );
+        // An observer assigned an allowance is no longer an observer.
+        if verifier_resolved.id().is_ok()
+            && self.get_observer_verifier(rt, &verifier_resolved).is_some()
+        {
+            rt.expect_emitted_event(
+                EventBuilder::new()
+                    .typ("observer-verifier-removed")
+                    .field_indexed("verifier", &verifier_resolved.id().unwrap())
+                    .build()?,
+            );
+        }
 
-        let params = AddVerifierParams { address: *verifier, allowance: allowance.clone() };
+        let params = AddVerifierParams {
+            address: *verifier,
+            allowance: allowance.clone().try_into().unwrap(),
+        };
         let ret = rt.call::<VerifregActor>(
             Method::AddVerifier as MethodNum,
             IpldBlock::serialize_cbor(&params).unwrap(),
@@ -184,6 +203,102 @@ impl Harness {
         Ok(())
     }
 
+    pub fn add_observer_verifier(
+        &self,
+        rt: &MockRuntime,
+        verifier: &Address,
+        metadata: &str,
+    ) -> Result<(), ActorError> {
+        rt.expect_validate_caller_addr(vec![self.root]);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.root);
+        let verifier_resolved = rt.get_id_address(verifier).unwrap_or(*verifier);
+        rt.expect_send(
+            DATACAP_TOKEN_ACTOR_ADDR,
+            ext::datacap::Method::Balance as MethodNum,
+            IpldBlock::serialize_cbor(&verifier_resolved).unwrap(),
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+            IpldBlock::serialize_cbor(&BigIntSer(&DataCap::zero())).unwrap(),
+            ExitCode::OK,
+            None,
+        );
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("observer-verifier-added")
+                .field_indexed("verifier", &verifier_resolved.id().unwrap())
+                .field("metadata", metadata)
+                .build()?,
+        );
+
+        let params =
+            AddObserverVerifierParams { address: *verifier, metadata: metadata.to_string() };
+        let ret = rt.call::<VerifregActor>(
+            Method::AddObserverVerifierExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        assert!(ret.is_none());
+        rt.verify();
+
+        let observer = self.get_observer_verifier(rt, &verifier_resolved).unwrap();
+        assert_eq!(metadata, observer.metadata);
+        assert_eq!(*rt.epoch.borrow(), observer.registered);
+        Ok(())
+    }
+
+    pub fn remove_observer_verifier(
+        &self,
+        rt: &MockRuntime,
+        verifier: &Address,
+    ) -> Result<(), ActorError> {
+        rt.expect_validate_caller_addr(vec![self.root]);
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("observer-verifier-removed")
+                .field_indexed("verifier", &verifier.id().unwrap())
+                .build()?,
+        );
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.root);
+        let ret = rt.call::<VerifregActor>(
+            Method::RemoveObserverVerifierExported as MethodNum,
+            IpldBlock::serialize_cbor(verifier).unwrap(),
+        )?;
+        assert!(ret.is_none());
+        rt.verify();
+
+        assert!(self.get_observer_verifier(rt, verifier).is_none());
+        Ok(())
+    }
+
+    pub fn list_observer_verifiers(
+        &self,
+        rt: &MockRuntime,
+        cursor: Option<ActorID>,
+        limit: u64,
+    ) -> Result<ListObserverVerifiersReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ListObserverVerifiersParams { cursor, limit };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::ListObserverVerifiersExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize list observer verifiers return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn get_observer_verifier(
+        &self,
+        rt: &MockRuntime,
+        verifier: &Address,
+    ) -> Option<ObserverVerifier> {
+        let st: State = rt.get_versioned_state();
+        st.get_observer_verifier(&rt.store, verifier.id().unwrap()).unwrap()
+    }
+
     pub fn assert_verifier_allowance(
         &self,
         rt: &MockRuntime,
@@ -195,13 +310,18 @@ impl Harness {
     }
 
     pub fn get_verifier_allowance(&self, rt: &MockRuntime, verifier: &Address) -> DataCap {
-        let verifiers = rt.get_state::<State>().load_verifiers(&rt.store).unwrap();
+        let verifiers = rt.get_versioned_state::<State>().load_verifiers(&rt.store).unwrap();
         verifiers.get(verifier).unwrap().unwrap().clone().0
     }
 
+    pub fn get_verifier_granted(&self, rt: &MockRuntime, verifier: &Address) -> DataCap {
+        let grants = rt.get_versioned_state::<State>().load_verifier_grants(&rt.store).unwrap();
+        grants.get(verifier).unwrap().map(|g| g.0.clone()).unwrap_or_default()
+    }
+
     pub fn assert_verifier_removed(&self, rt: &MockRuntime, verifier: &Address) {
         let verifier_id_addr = rt.get_id_address(verifier).unwrap();
-        let verifiers = rt.get_state::<State>().load_verifiers(&rt.store).unwrap();
+        let verifiers = rt.get_versioned_state::<State>().load_verifiers(&rt.store).unwrap();
         assert!(!verifiers.contains_key(&verifier_id_addr).unwrap())
     }
 
@@ -220,7 +340,7 @@ impl Harness {
         // Expect tokens to be minted.
         let mint_params = ext::datacap::MintParams {
             to: client_resolved,
-            amount: TokenAmount::from_whole(allowance.to_i64().unwrap()),
+            amount: TokenAmount::from_whole(allowance.to_i64().unwrap()).try_into().unwrap(),
             operators: vec![STORAGE_MARKET_ACTOR_ADDR],
         };
         rt.expect_send_simple(
@@ -232,7 +352,10 @@ impl Harness {
             ExitCode::OK,
         );
 
-        let params = AddVerifiedClientParams { address: *client, allowance: allowance.clone() };
+        let params = AddVerifiedClientParams {
+            address: *client,
+            allowance: allowance.clone().try_into().unwrap(),
+        };
         if client_resolved.id().is_ok() {
             // if the client isn't resolved, we don't expect an event because the call should abort
             rt.expect_emitted_event(
@@ -243,6 +366,16 @@ impl Harness {
                     .field_indexed("client", &client_resolved.id().unwrap())
                     .build()?,
             );
+            let total = self.get_verifier_granted(rt, verifier) + allowance;
+            rt.expect_emitted_event(
+                EventBuilder::new()
+                    .typ("datacap-granted")
+                    .field_indexed("verifier", &verifier.id().unwrap())
+                    .field_indexed("client", &client_resolved.id().unwrap())
+                    .field("amount", &BigIntSer(allowance))
+                    .field("total", &BigIntSer(&total))
+                    .build()?,
+            );
         }
         let ret = rt.call::<VerifregActor>(
             Method::AddVerifiedClient as MethodNum,
@@ -255,7 +388,8 @@ impl Harness {
     }
 
     pub fn check_state(&self, rt: &MockRuntime) {
-        let (_, acc) = check_state_invariants(&rt.get_state(), rt.store(), *rt.epoch.borrow());
+        let (_, acc) =
+            check_state_invariants(&rt.get_versioned_state(), rt.store(), *rt.epoch.borrow());
         acc.assert_empty();
     }
 
@@ -265,7 +399,7 @@ impl Harness {
         rt: &MockRuntime,
         alloc: &Allocation,
     ) -> Result<AllocationID, ActorError> {
-        let mut st: State = rt.get_state();
+        let mut st: State = rt.get_versioned_state();
         let mut allocs = st.load_allocs(rt.store()).unwrap();
         let alloc_id = st.next_allocation_id;
         assert!(
@@ -275,7 +409,7 @@ impl Harness {
         );
         st.next_allocation_id += 1;
         st.allocations = allocs.flush().expect("failed flushing allocation table");
-        rt.replace_state(&st);
+        rt.replace_versioned_state(&st);
         Ok(alloc_id)
     }
 
@@ -285,7 +419,7 @@ impl Harness {
         client: ActorID,
         id: AllocationID,
     ) -> Option<Allocation> {
-        let st: State = rt.get_state();
+        let st: State = rt.get_versioned_state();
         let mut allocs = st.load_allocs(rt.store()).unwrap();
         allocs.get(client, id).unwrap().cloned()
     }
@@ -438,8 +572,47 @@ impl Harness {
         Ok(ret)
     }
 
+    // Invokes the RemoveExpiredClaimsLimited actor method.
+    pub fn remove_expired_claims_limited(
+        &self,
+        rt: &MockRuntime,
+        provider: ActorID,
+        limit: u64,
+        cursor: Option<ClaimID>,
+        expect_removed: Vec<(ClaimID, Claim)>,
+    ) -> Result<RemoveExpiredClaimsLimitedReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        for (id, claim) in expect_removed {
+            expect_claim_emitted(
+                rt,
+                "claim-removed",
+                id,
+                claim.client,
+                claim.provider,
+                &claim.data,
+                claim.size.0,
+                claim.sector,
+                claim.term_min,
+                claim.term_max,
+                claim.term_start,
+            )
+        }
+
+        let params = RemoveExpiredClaimsLimitedParams { provider, limit, cursor };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::RemoveExpiredClaimsLimitedExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize remove expired claims return");
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn load_claim(&self, rt: &MockRuntime, provider: ActorID, id: ClaimID) -> Option<Claim> {
-        let st: State = rt.get_state();
+        let st: State = rt.get_versioned_state();
         let mut claims = st.load_claims(rt.store()).unwrap();
         claims.get(provider, id).unwrap().cloned()
     }
@@ -526,7 +699,7 @@ impl Harness {
 
     // Creates a claim directly in state.
     pub fn create_claim(&self, rt: &MockRuntime, claim: &Claim) -> Result<ClaimID, ActorError> {
-        let mut st: State = rt.get_state();
+        let mut st: State = rt.get_versioned_state();
         let mut claims = st.load_claims(rt.store()).unwrap();
         let id = st.next_allocation_id;
         assert!(
@@ -536,7 +709,7 @@ impl Harness {
         );
         st.next_allocation_id += 1;
         st.claims = claims.flush().expect("failed flushing allocation table");
-        rt.replace_state(&st);
+        rt.replace_versioned_state(&st);
         Ok(id)
     }
 
@@ -560,6 +733,42 @@ impl Harness {
         Ok(ret)
     }
 
+    pub fn get_claim_totals(
+        &self,
+        rt: &MockRuntime,
+        provider: ActorID,
+        expiring_within: ChainEpoch,
+    ) -> Result<GetClaimTotalsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = GetClaimTotalsParams { provider, expiring_within };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::GetClaimTotalsExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize get claim totals return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn get_provider_history(&self, rt: &MockRuntime, provider: ActorID) -> ProviderHistory {
+        rt.expect_validate_caller_any();
+        let params = GetProviderHistoryParams { provider };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::GetProviderHistoryExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize get provider history return");
+        rt.verify();
+        ret
+    }
+
     pub fn extend_claim_terms(
         &self,
         rt: &MockRuntime,
@@ -743,11 +952,12 @@ pub fn make_claim(
         term_max,
         term_start,
         sector,
+        commitment: vec![],
     }
 }
 
 pub fn claim_from_alloc(alloc: &Allocation, term_start: ChainEpoch, sector: SectorNumber) -> Claim {
-    Claim {
+    let mut claim = Claim {
         provider: alloc.provider,
         client: alloc.client,
         data: alloc.data,
@@ -756,7 +966,15 @@ pub fn claim_from_alloc(alloc: &Allocation, term_start: ChainEpoch, sector: Sect
         term_max: alloc.term_max,
         term_start,
         sector,
-    }
+        commitment: vec![],
+    };
+    claim.commitment = claim_commitment(&claim);
+    claim
+}
+
+pub fn claim_commitment(claim: &Claim) -> Vec<u8> {
+    let (digest, len) = hash(SupportedHashes::Sha2_256, &claim.commitment_preimage());
+    digest[..len].to_vec()
 }
 
 pub fn make_receiver_hook_token_payload(
@@ -784,7 +1002,7 @@ pub fn assert_allocation(
     id: AllocationID,
     expected: &Allocation,
 ) {
-    let st: State = rt.get_state();
+    let st: State = rt.get_versioned_state();
     let store = &rt.store();
     let mut allocs = st.load_allocs(store).unwrap();
 
@@ -792,7 +1010,7 @@ pub fn assert_allocation(
 }
 
 pub fn assert_claim(rt: &MockRuntime, provider: ActorID, id: ClaimID, expected: &Claim) {
-    let st: State = rt.get_state();
+    let st: State = rt.get_versioned_state();
     let store = &rt.store();
     let mut claims = st.load_claims(store).unwrap();
 
@@ -808,7 +1026,7 @@ pub fn assert_alloc_claimed(
     epoch: ChainEpoch,
     sector: SectorNumber,
 ) -> Claim {
-    let st: State = rt.get_state();
+    let st: State = rt.get_versioned_state();
     let store = &rt.store();
 
     // Alloc is gone
@@ -76,6 +76,39 @@ mod construction {
     }
 }
 
+mod state_version {
+    use fil_actor_verifreg::State;
+    use harness::*;
+    use util::*;
+
+    use crate::*;
+
+    #[test]
+    fn upgrades_state_stored_before_versioning() {
+        let (h, rt) = new_harness();
+        let st: State = rt.get_versioned_state();
+        // The untagged layout from before the state was versioned.
+        rt.replace_state(&(
+            st.root_key,
+            st.verifiers,
+            st.remove_data_cap_proposal_ids,
+            st.allocations,
+            st.next_allocation_id,
+            st.claims,
+        ));
+        let allowance = verifier_allowance(&rt);
+        h.add_verifier(&rt, &VERIFIER, &allowance).unwrap();
+
+        // The next write stores the state at the current version.
+        let (version, upgraded): (u64, State) = rt.get_state();
+        assert_eq!(1, version);
+        assert_eq!(st.next_allocation_id, upgraded.next_allocation_id);
+        assert!(upgraded.claim_disputes.is_none());
+        assert!(upgraded.provider_histories.is_none());
+        h.check_state(&rt);
+    }
+}
+
 mod verifiers {
     use std::ops::Deref;
 
@@ -97,8 +130,10 @@ mod verifiers {
         let (h, rt) = new_harness();
         rt.expect_validate_caller_addr(vec![h.root]);
         rt.set_caller(*VERIFREG_ACTOR_CODE_ID, Address::new_id(501));
-        let params =
-            AddVerifierParams { address: Address::new_id(201), allowance: verifier_allowance(&rt) };
+        let params = AddVerifierParams {
+            address: Address::new_id(201),
+            allowance: verifier_allowance(&rt).try_into().unwrap(),
+        };
         expect_abort(
             ExitCode::USR_FORBIDDEN,
             rt.call::<VerifregActor>(
@@ -114,7 +149,8 @@ mod verifiers {
         let (h, rt) = new_harness();
         let allowance: DataCap = rt.policy.minimum_verified_allocation_size.clone() - 1;
 
-        let params = AddVerifierParams { address: *VERIFIER, allowance };
+        let params =
+            AddVerifierParams { address: *VERIFIER, allowance: allowance.try_into().unwrap() };
         let result = rt.call::<VerifregActor>(
             Method::AddVerifier as MethodNum,
             IpldBlock::serialize_cbor(&params).unwrap(),
@@ -160,7 +196,10 @@ mod verifiers {
             ExitCode::OK,
         );
 
-        let params = AddVerifierParams { address: verifier_key_address, allowance };
+        let params = AddVerifierParams {
+            address: verifier_key_address,
+            allowance: allowance.try_into().unwrap(),
+        };
         let result = rt.call::<VerifregActor>(
             Method::AddVerifier as MethodNum,
             IpldBlock::serialize_cbor(&params).unwrap(),
@@ -290,6 +329,9 @@ mod clients {
         // No more allowance left
         h.assert_verifier_allowance(&rt, &VERIFIER, &DataCap::from(0));
         h.assert_verifier_allowance(&rt, &VERIFIER2, &DataCap::from(0));
+        // Each verifier's grants are totalled.
+        assert_eq!(allowance_verifier, h.get_verifier_granted(&rt, &VERIFIER));
+        assert_eq!(allowance_verifier, h.get_verifier_granted(&rt, &VERIFIER2));
         h.check_state(&rt);
     }
 
@@ -393,7 +435,10 @@ mod clients {
         let caller = Address::new_id(209);
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
         rt.expect_validate_caller_any();
-        let params = AddVerifiedClientParams { address: *CLIENT, allowance: allowance_client };
+        let params = AddVerifiedClientParams {
+            address: *CLIENT,
+            allowance: allowance_client.try_into().unwrap(),
+        };
         expect_abort(
             ExitCode::USR_NOT_FOUND,
             rt.call::<VerifregActor>(
@@ -411,8 +456,10 @@ mod clients {
         let allowance_client = client_allowance(&rt);
         h.add_verifier(&rt, &VERIFIER, &allowance_verifier).unwrap();
 
-        let params =
-            AddVerifiedClientParams { address: *CLIENT, allowance: allowance_client.clone() };
+        let params = AddVerifiedClientParams {
+            address: *CLIENT,
+            allowance: allowance_client.clone().try_into().unwrap(),
+        };
 
         // set caller to not-builtin
         rt.set_caller(*EVM_ACTOR_CODE_ID, *VERIFIER);
@@ -432,7 +479,7 @@ mod clients {
 
         let mint_params = ext::datacap::MintParams {
             to: *CLIENT,
-            amount: TokenAmount::from_whole(allowance_client.to_i64().unwrap()),
+            amount: TokenAmount::from_whole(allowance_client.to_i64().unwrap()).try_into().unwrap(),
             operators: vec![STORAGE_MARKET_ACTOR_ADDR],
         };
         rt.expect_send_simple(
@@ -453,6 +500,16 @@ mod clients {
                 .build()
                 .unwrap(),
         );
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("datacap-granted")
+                .field_indexed("verifier", &VERIFIER.id().unwrap())
+                .field_indexed("client", &CLIENT.id().unwrap())
+                .field("amount", &BigIntSer(&allowance_client))
+                .field("total", &BigIntSer(&allowance_client))
+                .build()
+                .unwrap(),
+        );
 
         rt.expect_validate_caller_any();
         rt.call::<VerifregActor>(
@@ -525,15 +582,16 @@ mod allocs_claims {
     use fvm_shared::bigint::BigInt;
     use fvm_shared::error::ExitCode;
     use fvm_shared::piece::PaddedPieceSize;
+    use fvm_shared::version::NetworkVersion;
     use fvm_shared::{ActorID, MethodNum};
     use num_traits::Zero;
 
+    use fil_actor_verifreg::state::CLAIM_COMMITMENT_DOMAIN;
     use fil_actor_verifreg::{
-        Actor, AllocationID, ClaimTerm, DataCap, ExtendClaimTermsParams, GetClaimsParams, Method,
-        State,
+        Actor, AllocationID, ClaimID, ClaimTerm, DataCap, ExtendClaimTermsParams, GetClaimsParams,
+        INTERNAL_METHODS_SUNSET_VERSION, Method, RemoveExpiredClaimsParams, State,
     };
-    use fil_actor_verifreg::{Claim, ExtendClaimTermsReturn};
-    use fil_actors_runtime::FailCode;
+    use fil_actor_verifreg::{Claim, ExtendClaimTermsReturn, ProviderHistory};
     use fil_actors_runtime::runtime::policy_constants::{
         MAXIMUM_VERIFIED_ALLOCATION_TERM, MINIMUM_VERIFIED_ALLOCATION_SIZE,
         MINIMUM_VERIFIED_ALLOCATION_TERM,
@@ -541,6 +599,7 @@ mod allocs_claims {
     use fil_actors_runtime::test_utils::{
         ACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID, expect_abort, expect_abort_contains_message,
     };
+    use fil_actors_runtime::{ERR_METHOD_DEPRECATED, FailCode, MethodDeprecated, parse_uint_key};
     use harness::*;
 
     use crate::*;
@@ -563,7 +622,7 @@ mod allocs_claims {
 
         let id1 = h.create_alloc(&rt, &alloc1).unwrap();
         let id2 = h.create_alloc(&rt, &alloc2).unwrap();
-        let state_with_allocs: State = rt.get_state();
+        let state_with_allocs: State = rt.get_versioned_state();
 
         let expect_1 = vec![(id1, alloc1.clone())];
         let expect_2 = vec![(id2, alloc2.clone())];
@@ -599,14 +658,14 @@ mod allocs_claims {
         assert_eq!(DataCap::from(alloc2.size.0), ret.datacap_recovered);
 
         // Reset state and show we can remove two at once.
-        rt.replace_state(&state_with_allocs);
+        rt.replace_versioned_state(&state_with_allocs);
         let ret = h.remove_expired_allocations(&rt, CLIENT1, vec![id1, id2], expect_both).unwrap();
         assert_eq!(vec![1, 2], ret.considered);
         assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.results.codes());
         assert_eq!(DataCap::from(total_size), ret.datacap_recovered);
 
         // Reset state and show that only what was asked for is removed.
-        rt.replace_state(&state_with_allocs);
+        rt.replace_versioned_state(&state_with_allocs);
         let ret = h.remove_expired_allocations(&rt, CLIENT1, vec![id1], expect_1.clone()).unwrap();
         assert_eq!(vec![1], ret.considered);
         assert_eq!(vec![ExitCode::OK], ret.results.codes());
@@ -614,7 +673,7 @@ mod allocs_claims {
 
         // Reset state and show that specifying none removes only expired allocations
         rt.set_epoch(0);
-        rt.replace_state(&state_with_allocs);
+        rt.replace_versioned_state(&state_with_allocs);
         let ret = h.remove_expired_allocations(&rt, CLIENT1, vec![], vec![]).unwrap();
         assert_eq!(Vec::<AllocationID>::new(), ret.considered);
         assert_eq!(Vec::<ExitCode>::new(), ret.results.codes());
@@ -639,7 +698,7 @@ mod allocs_claims {
         assert!(h.load_alloc(&rt, CLIENT1, id2).is_none()); // removed
 
         // Reset state and show that specifying none removes *all* expired allocations
-        rt.replace_state(&state_with_allocs);
+        rt.replace_versioned_state(&state_with_allocs);
         let ret = h
             .remove_expired_allocations(&rt, CLIENT1, vec![], vec![(id1, alloc1), (id2, alloc2)])
             .unwrap();
@@ -651,6 +710,78 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn release_allocs() {
+        use fil_actor_verifreg::{ReleaseAllocationsParams, ReleaseAllocationsReturn};
+        use fil_actors_runtime::test_utils::MARKET_ACTOR_CODE_ID;
+        use fil_actors_runtime::{DATACAP_TOKEN_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR};
+        use frc46_token::token::types::TransferParams;
+        use fvm_ipld_encoding::RawBytes;
+        use fvm_shared::address::Address;
+        use fvm_shared::econ::TokenAmount;
+
+        let (h, rt) = new_harness();
+        let alloc1 = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        let alloc2 = make_alloc("2", CLIENT1, PROVIDER1, ALLOC_SIZE * 2);
+        let id1 = h.create_alloc(&rt, &alloc1).unwrap();
+        let id2 = h.create_alloc(&rt, &alloc2).unwrap();
+        let params = IpldBlock::serialize_cbor(&ReleaseAllocationsParams {
+            client: CLIENT1,
+            allocation_ids: vec![id1, id2 + 1],
+        })
+        .unwrap();
+
+        // Only the market may release allocations.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(CLIENT1));
+        rt.expect_validate_caller_addr(vec![STORAGE_MARKET_ACTOR_ADDR]);
+        expect_abort(
+            ExitCode::USR_FORBIDDEN,
+            rt.call::<Actor>(Method::ReleaseAllocationsExported as MethodNum, params.clone()),
+        );
+        rt.verify();
+
+        // Unexpired allocations are removed and their datacap returned to the client.
+        rt.set_caller(*MARKET_ACTOR_CODE_ID, STORAGE_MARKET_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![STORAGE_MARKET_ACTOR_ADDR]);
+        expect_allocation_emitted(
+            &rt,
+            "allocation-removed",
+            id1,
+            alloc1.client,
+            alloc1.provider,
+            &alloc1.data,
+            alloc1.size.0,
+            alloc1.term_min,
+            alloc1.term_max,
+            alloc1.expiration,
+        );
+        rt.expect_send_simple(
+            DATACAP_TOKEN_ACTOR_ADDR,
+            fil_actor_verifreg::ext::datacap::Method::Transfer as MethodNum,
+            IpldBlock::serialize_cbor(&TransferParams {
+                to: Address::new_id(CLIENT1),
+                amount: TokenAmount::from_whole(ALLOC_SIZE as i64),
+                operator_data: RawBytes::default(),
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        let ret: ReleaseAllocationsReturn = rt
+            .call::<Actor>(Method::ReleaseAllocationsExported as MethodNum, params)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        assert_eq!(vec![ExitCode::OK, ExitCode::USR_NOT_FOUND], ret.results.codes());
+        assert_eq!(DataCap::from(ALLOC_SIZE), ret.datacap_recovered);
+        assert!(h.load_alloc(&rt, CLIENT1, id1).is_none());
+        assert!(h.load_alloc(&rt, CLIENT1, id2).is_some());
+        h.check_state(&rt);
+    }
+
     #[test]
     fn claim_allocs() {
         let (h, rt) = new_harness();
@@ -670,7 +801,7 @@ mod allocs_claims {
         let sector = 1000;
         let expiry = MINIMUM_VERIFIED_ALLOCATION_TERM;
 
-        let prior_state: State = rt.get_state();
+        let prior_state: State = rt.get_versioned_state();
         {
             // Claim two for PROVIDER1 in one sector
             let reqs = vec![make_claim_reqs(sector, expiry, &[(id1, &alloc1), (id2, &alloc2)])];
@@ -694,7 +825,7 @@ mod allocs_claims {
         {
             // Can't find claim for wrong client.
             // Claim in another sector succeeds regardless.
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let mut reqs = vec![
                 make_claim_reqs(sector, expiry, &[(id1, &alloc1)]),
                 make_claim_reqs(sector, expiry, &[(id2, &alloc2)]),
@@ -718,7 +849,7 @@ mod allocs_claims {
         }
         {
             // Can't claim for other provider
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let reqs = vec![
                 make_claim_reqs(sector, expiry, &[(id4, &alloc4)]), // Wrong provider
             ];
@@ -730,7 +861,7 @@ mod allocs_claims {
         }
         {
             // Can't claim same alloc twice in one sector.
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let reqs = vec![make_claim_reqs(sector, expiry, &[(id1, &alloc1), (id1, &alloc1)])];
             expect_abort(
                 ExitCode::USR_ILLEGAL_ARGUMENT,
@@ -767,7 +898,7 @@ mod allocs_claims {
         }
         {
             // Mismatched data / size
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let mut reqs = vec![
                 make_claim_reqs(sector, expiry, &[(id1, &alloc1)]),
                 make_claim_reqs(sector, expiry, &[(id2, &alloc2)]),
@@ -786,7 +917,7 @@ mod allocs_claims {
         }
         {
             // Expired allocation
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let reqs = vec![make_claim_reqs(sector, expiry, &[(id1, &alloc1)])];
             rt.set_epoch(alloc1.expiration + 1);
             let ret = h.claim_allocations(&rt, PROVIDER1, reqs, 0, false, vec![]).unwrap();
@@ -797,7 +928,7 @@ mod allocs_claims {
         }
         {
             // Sector expiration too soon
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let reqs = vec![make_claim_reqs(sector, alloc1.term_min - 1, &[(id1, &alloc1)])];
             let ret = h.claim_allocations(&rt, PROVIDER1, reqs, 0, false, vec![]).unwrap();
             assert_eq!(ret.sector_results.codes(), vec![ExitCode::USR_FORBIDDEN]);
@@ -812,7 +943,7 @@ mod allocs_claims {
         }
         {
             // Without all-or-nothing, a failure aborts the sector but not other sectors
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let mut reqs = vec![
                 make_claim_reqs(sector, expiry, &[(id1, &alloc1), (id2, &alloc2)]),
                 make_claim_reqs(sector, expiry, &[(id3, &alloc3)]),
@@ -836,7 +967,7 @@ mod allocs_claims {
         }
         {
             // Without all-or-nothing, every sector can fail but the method succeeds.
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let mut reqs = vec![
                 make_claim_reqs(sector, expiry, &[(id1, &alloc1), (id2, &alloc2)]),
                 make_claim_reqs(sector, expiry, &[(id3, &alloc3)]),
@@ -852,7 +983,7 @@ mod allocs_claims {
         }
         {
             // With all-or-nothing, a failure aborts everything
-            rt.replace_state(&prior_state);
+            rt.replace_versioned_state(&prior_state);
             let mut reqs = vec![
                 make_claim_reqs(sector, expiry, &[(id1, &alloc1), (id2, &alloc2)]),
                 make_claim_reqs(sector, expiry, &[(id3, &alloc3)]),
@@ -908,6 +1039,80 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn get_claim_totals() {
+        let (h, rt) = new_harness();
+        let size = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
+        let min_term = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim1 = make_claim("1", CLIENT1, PROVIDER1, size, min_term, min_term + 100, 0, 0);
+        let claim2 = make_claim("2", CLIENT2, PROVIDER1, size * 2, min_term, min_term + 500, 0, 0);
+        let claim3 = make_claim("3", CLIENT1, PROVIDER2, size, min_term, min_term, 0, 0);
+        h.create_claim(&rt, &claim1).unwrap();
+        h.create_claim(&rt, &claim2).unwrap();
+        h.create_claim(&rt, &claim3).unwrap();
+
+        rt.set_epoch(min_term);
+        let ret = h.get_claim_totals(&rt, PROVIDER1, 0).unwrap();
+        assert_eq!(2, ret.count);
+        assert_eq!(size * 3, ret.total_size);
+        assert_eq!(0, ret.expiring_size);
+
+        // The window includes its final epoch.
+        let ret = h.get_claim_totals(&rt, PROVIDER1, 100).unwrap();
+        assert_eq!(size, ret.expiring_size);
+        let ret = h.get_claim_totals(&rt, PROVIDER1, 500).unwrap();
+        assert_eq!(size * 3, ret.expiring_size);
+
+        // Expired claims not yet removed are counted as expiring.
+        let ret = h.get_claim_totals(&rt, PROVIDER2, 0).unwrap();
+        assert_eq!(1, ret.count);
+        assert_eq!(size, ret.expiring_size);
+
+        let ret = h.get_claim_totals(&rt, 999, 100).unwrap();
+        assert_eq!(0, ret.count);
+        assert_eq!(0, ret.total_size);
+
+        expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.get_claim_totals(&rt, PROVIDER1, -1));
+        rt.reset();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn claim_commitment_preimage() {
+        let size = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
+        let min_term = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim = make_claim("1", CLIENT1, PROVIDER1, size, min_term, min_term + 1000, 5, 7);
+
+        let preimage = claim.commitment_preimage();
+        assert!(preimage.starts_with(CLAIM_COMMITMENT_DOMAIN));
+        assert_eq!(CLAIM_COMMITMENT_DOMAIN.len() + 6 * 8 + 32, preimage.len());
+        // The data commitment digest follows the provider and client IDs.
+        let data_offset = CLAIM_COMMITMENT_DOMAIN.len() + 16;
+        assert_eq!(claim.data.hash().digest(), &preimage[data_offset..data_offset + 32]);
+
+        // The commitment is unaffected by extension of the claim's term.
+        let extended = Claim { term_max: claim.term_max + 1000, ..claim.clone() };
+        assert_eq!(preimage, extended.commitment_preimage());
+        let moved = Claim { sector: claim.sector + 1, ..claim.clone() };
+        assert_ne!(preimage, moved.commitment_preimage());
+
+        // Claims serialised before commitments were introduced decode with an empty commitment.
+        let legacy = (
+            claim.provider,
+            claim.client,
+            claim.data,
+            claim.size,
+            claim.term_min,
+            claim.term_max,
+            claim.term_start,
+            claim.sector,
+        );
+        let decoded: Claim =
+            fvm_ipld_encoding::from_slice(&fvm_ipld_encoding::to_vec(&legacy).unwrap()).unwrap();
+        assert_eq!(claim, decoded);
+        assert!(decoded.commitment.is_empty());
+    }
+
     #[test]
     fn extend_claims_basic() {
         let (h, rt) = new_harness();
@@ -1069,7 +1274,7 @@ mod allocs_claims {
 
         let id1 = h.create_claim(&rt, &claim1).unwrap();
         let id2 = h.create_claim(&rt, &claim2).unwrap();
-        let state_with_allocs: State = rt.get_state();
+        let state_with_allocs: State = rt.get_versioned_state();
 
         // Removal of expired claims shares most of its implementation with removing expired allocations.
         // The full test suite is not duplicated here,   simple ones to ensure that the expiration
@@ -1090,21 +1295,31 @@ mod allocs_claims {
         let ret = h.remove_expired_claims(&rt, PROVIDER1, vec![id1, id2], expect_1).unwrap();
         assert_eq!(vec![1, 2], ret.considered);
         assert_eq!(vec![ExitCode::OK, ExitCode::USR_FORBIDDEN], ret.results.codes());
+        assert_eq!(
+            ProviderHistory { claims: 1, total_size: ALLOC_SIZE },
+            h.get_provider_history(&rt, PROVIDER1)
+        );
 
         // Both now expired
         rt.set_epoch(term_start + term_min + 200);
         let ret = h.remove_expired_claims(&rt, PROVIDER1, vec![id1, id2], expect_2).unwrap();
         assert_eq!(vec![1, 2], ret.considered);
         assert_eq!(vec![ExitCode::USR_NOT_FOUND, ExitCode::OK], ret.results.codes());
+        assert_eq!(
+            ProviderHistory { claims: 2, total_size: ALLOC_SIZE * 3 },
+            h.get_provider_history(&rt, PROVIDER1)
+        );
+        assert_eq!(ProviderHistory::default(), h.get_provider_history(&rt, PROVIDER2));
 
         // Reset state, and show that specifying none removes only expired allocations
         rt.set_epoch(term_start + term_min);
-        rt.replace_state(&state_with_allocs);
+        rt.replace_versioned_state(&state_with_allocs);
         let ret = h.remove_expired_claims(&rt, PROVIDER1, vec![], vec![]).unwrap();
         assert_eq!(Vec::<AllocationID>::new(), ret.considered);
         assert_eq!(Vec::<ExitCode>::new(), ret.results.codes());
         assert!(h.load_claim(&rt, PROVIDER1, id1).is_some());
         assert!(h.load_claim(&rt, PROVIDER1, id2).is_some());
+        assert_eq!(ProviderHistory::default(), h.get_provider_history(&rt, PROVIDER1));
 
         rt.set_epoch(term_start + term_min + 200);
         let ret = h.remove_expired_claims(&rt, PROVIDER1, vec![], expect_both).unwrap();
@@ -1112,6 +1327,132 @@ mod allocs_claims {
         assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.results.codes());
         assert!(h.load_claim(&rt, PROVIDER1, id1).is_none()); // removed
         assert!(h.load_claim(&rt, PROVIDER1, id2).is_none()); // removed
+        assert_eq!(
+            ProviderHistory { claims: 2, total_size: ALLOC_SIZE * 3 },
+            h.get_provider_history(&rt, PROVIDER1)
+        );
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn expire_claims_limited() {
+        let (h, rt) = new_harness();
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+
+        // Claim i expires at term_min + 100 * i.
+        let claims: Vec<(ClaimID, Claim)> = (1..=5)
+            .map(|i| {
+                let claim = make_claim(
+                    &i.to_string(),
+                    CLIENT1,
+                    PROVIDER1,
+                    ALLOC_SIZE,
+                    term_min,
+                    term_min + 100 * i,
+                    0,
+                    0,
+                );
+                (h.create_claim(&rt, &claim).unwrap(), claim)
+            })
+            .collect();
+
+        // Claims are examined in the claim map's internal order.
+        let st: State = rt.get_state();
+        let mut order = Vec::<ClaimID>::new();
+        st.load_claims(rt.store())
+            .unwrap()
+            .for_each_in(PROVIDER1, |key, _| {
+                order.push(parse_uint_key(key).unwrap());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(5, order.len());
+
+        // Four claims have expired. Each call examines at most two claims,
+        // removing those which have expired, until no claims remain to examine.
+        rt.set_epoch(term_min + 400);
+        let mut cursor = None;
+        let mut removed = Vec::<ClaimID>::new();
+        for examined in order.chunks(2) {
+            assert_eq!(examined.first().copied(), cursor.or(Some(order[0])));
+            let expect_removed: Vec<(ClaimID, Claim)> = examined
+                .iter()
+                .filter(|id| **id != claims[4].0)
+                .map(|id| claims.iter().find(|(claim_id, _)| claim_id == id).unwrap().clone())
+                .collect();
+            let ret = h
+                .remove_expired_claims_limited(&rt, PROVIDER1, 2, cursor, expect_removed.clone())
+                .unwrap();
+            assert_eq!(expect_removed.iter().map(|(id, _)| *id).collect::<Vec<_>>(), ret.removed);
+            removed.extend(ret.removed);
+            cursor = ret.next_cursor;
+        }
+        assert_eq!(None, cursor);
+        removed.sort_unstable();
+        assert_eq!(claims[..4].iter().map(|(id, _)| *id).collect::<Vec<_>>(), removed);
+        assert!(h.load_claim(&rt, PROVIDER1, claims[4].0).is_some());
+        assert_eq!(
+            ProviderHistory { claims: 4, total_size: ALLOC_SIZE * 4 },
+            h.get_provider_history(&rt, PROVIDER1)
+        );
+
+        // Nothing remains to remove.
+        let ret = h.remove_expired_claims_limited(&rt, PROVIDER1, 3, None, vec![]).unwrap();
+        assert!(ret.removed.is_empty());
+        assert_eq!(None, ret.next_cursor);
+
+        expect_abort_contains_message(
+            ExitCode::USR_NOT_FOUND,
+            "to start from",
+            h.remove_expired_claims_limited(&rt, PROVIDER1, 3, Some(claims[0].0), vec![]),
+        );
+        rt.reset();
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "limit must be positive",
+            h.remove_expired_claims_limited(&rt, PROVIDER1, 0, None, vec![]),
+        );
+        rt.reset();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn internal_method_deprecated_at_sunset_version() {
+        let (h, rt) = new_harness();
+        let params = RemoveExpiredClaimsParams { provider: PROVIDER1, claim_ids: vec![] };
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(CLIENT1));
+
+        // Before the sunset version, the internal method is dispatched as usual.
+        rt.set_network_version(NetworkVersion::new(u32::from(INTERNAL_METHODS_SUNSET_VERSION) - 1));
+        rt.expect_validate_caller_any();
+        rt.call::<Actor>(
+            Method::RemoveExpiredClaims as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap();
+        rt.verify();
+
+        rt.set_network_version(INTERNAL_METHODS_SUNSET_VERSION);
+        let mut err = rt
+            .call::<Actor>(
+                Method::RemoveExpiredClaims as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )
+            .unwrap_err();
+        assert_eq!(ERR_METHOD_DEPRECATED, err.exit_code());
+        let data: MethodDeprecated = err.take_data().unwrap().deserialize().unwrap();
+        assert_eq!(Method::RemoveExpiredClaims as MethodNum, data.method);
+        assert_eq!(Method::RemoveExpiredClaimsExported as MethodNum, data.replacement);
+        rt.verify();
+
+        // The exported method remains available.
+        rt.expect_validate_caller_any();
+        rt.call::<Actor>(
+            Method::RemoveExpiredClaimsExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap();
+        rt.verify();
         h.check_state(&rt);
     }
 
@@ -1178,6 +1519,273 @@ mod allocs_claims {
     }
 }
 
+mod claim_disputes {
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_shared::address::Address;
+    use fvm_shared::clock::ChainEpoch;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use fvm_shared::{ActorID, MethodNum};
+    use num_traits::Zero;
+
+    use fil_actor_verifreg::{
+        Actor as VerifregActor, Claim, ClaimID, DisputeClaimParams, Method,
+        ResolveClaimDisputeParams, State, ext,
+    };
+    use fil_actors_runtime::runtime::Policy;
+    use fil_actors_runtime::runtime::policy_constants::{
+        MINIMUM_VERIFIED_ALLOCATION_SIZE, MINIMUM_VERIFIED_ALLOCATION_TERM,
+    };
+    use fil_actors_runtime::test_utils::*;
+    use fil_actors_runtime::{ActorError, DATACAP_TOKEN_ACTOR_ADDR, EventBuilder};
+    use harness::*;
+
+    use crate::*;
+
+    const CLIENT: ActorID = 102;
+    const OTHER_CLIENT: ActorID = 103;
+    const PROVIDER: ActorID = 301;
+    const SIZE: u64 = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
+    const TERM_START: ChainEpoch = 10;
+    const WINDOW: ChainEpoch = 100;
+    const SECTOR: u64 = 7;
+
+    #[test]
+    fn disputes_disabled_by_default() {
+        let (h, rt) = new_harness();
+        let (id, _) = create_claim(&h, &rt);
+
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "disabled",
+            dispute_claim(&rt, CLIENT, PROVIDER, id),
+        );
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn dispute_restricted_to_client_within_window() {
+        let (h, rt) = new_harness_with_window();
+        let (id, _) = create_claim(&h, &rt);
+
+        expect_abort(ExitCode::USR_FORBIDDEN, dispute_claim(&rt, OTHER_CLIENT, PROVIDER, id));
+        rt.reset();
+        expect_abort(ExitCode::USR_NOT_FOUND, dispute_claim(&rt, CLIENT, PROVIDER, id + 1));
+        rt.reset();
+        expect_abort(ExitCode::USR_NOT_FOUND, dispute_claim(&rt, CLIENT, PROVIDER + 1, id));
+        rt.reset();
+
+        rt.set_epoch(TERM_START + WINDOW);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "dispute window",
+            dispute_claim(&rt, CLIENT, PROVIDER, id),
+        );
+        rt.reset();
+        assert!(load_dispute_epoch(&rt, id).is_none());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn upheld_dispute_revokes_claim() {
+        let (h, rt) = new_harness_with_window();
+        let (id, claim) = create_claim(&h, &rt);
+
+        rt.set_epoch(TERM_START + WINDOW - 1);
+        expect_claim_disputed(&rt, id, &claim);
+        dispute_claim(&rt, CLIENT, PROVIDER, id).unwrap();
+        rt.verify();
+        assert_eq!(Some(TERM_START + WINDOW - 1), load_dispute_epoch(&rt, id));
+        h.check_state(&rt);
+
+        // A claim can only be disputed once.
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "already disputed",
+            dispute_claim(&rt, CLIENT, PROVIDER, id),
+        );
+        rt.reset();
+
+        expect_claim_emitted(
+            &rt,
+            "claim-removed",
+            id,
+            CLIENT,
+            PROVIDER,
+            &claim.data,
+            SIZE,
+            SECTOR,
+            claim.term_min,
+            claim.term_max,
+            TERM_START,
+        );
+        expect_dispute_resolved(&rt, id, true);
+        rt.expect_send_simple(
+            DATACAP_TOKEN_ACTOR_ADDR,
+            ext::datacap::Method::Mint as MethodNum,
+            IpldBlock::serialize_cbor(&ext::datacap::MintParams {
+                to: Address::new_id(CLIENT),
+                amount: TokenAmount::from_whole(SIZE).try_into().unwrap(),
+                operators: vec![],
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        rt.expect_send_simple(
+            Address::new_id(PROVIDER),
+            ext::miner::ON_CLAIM_REVOKED_METHOD,
+            IpldBlock::serialize_cbor(&ext::miner::OnClaimRevokedParams {
+                sector: SECTOR,
+                space: SIZE,
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        resolve_claim_dispute(&rt, id, true).unwrap();
+        rt.verify();
+
+        assert!(h.load_claim(&rt, PROVIDER, id).is_none());
+        assert!(load_dispute_epoch(&rt, id).is_none());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn rejected_dispute_keeps_claim() {
+        let (h, rt) = new_harness_with_window();
+        let (id, claim) = create_claim(&h, &rt);
+        expect_claim_disputed(&rt, id, &claim);
+        dispute_claim(&rt, CLIENT, PROVIDER, id).unwrap();
+        rt.verify();
+
+        // Only the root key may resolve a dispute.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(CLIENT));
+        rt.expect_validate_caller_addr(vec![h.root]);
+        expect_abort(
+            ExitCode::USR_FORBIDDEN,
+            rt.call::<VerifregActor>(
+                Method::ResolveClaimDisputeExported as MethodNum,
+                IpldBlock::serialize_cbor(&ResolveClaimDisputeParams {
+                    claim_id: id,
+                    upheld: true,
+                })
+                .unwrap(),
+            ),
+        );
+        rt.reset();
+
+        expect_dispute_resolved(&rt, id, false);
+        resolve_claim_dispute(&rt, id, false).unwrap();
+        rt.verify();
+        assert_claim(&rt, PROVIDER, id, &claim);
+        assert!(load_dispute_epoch(&rt, id).is_none());
+
+        expect_abort(ExitCode::USR_NOT_FOUND, resolve_claim_dispute(&rt, id, false));
+        rt.reset();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn upheld_dispute_of_removed_claim_is_dropped() {
+        let (h, rt) = new_harness_with_window();
+        let (id, claim) = create_claim(&h, &rt);
+        expect_claim_disputed(&rt, id, &claim);
+        dispute_claim(&rt, CLIENT, PROVIDER, id).unwrap();
+        rt.verify();
+
+        // Remove the claim directly from state, as if it had expired.
+        let mut st: State = rt.get_versioned_state();
+        let mut claims = st.load_claims(rt.store()).unwrap();
+        claims.remove(PROVIDER, id).unwrap();
+        st.save_claims(&mut claims).unwrap();
+        rt.replace_versioned_state(&st);
+
+        // No datacap is restored, nor is the provider notified.
+        expect_dispute_resolved(&rt, id, true);
+        resolve_claim_dispute(&rt, id, true).unwrap();
+        rt.verify();
+        assert!(load_dispute_epoch(&rt, id).is_none());
+        h.check_state(&rt);
+    }
+
+    fn new_harness_with_window() -> (Harness, MockRuntime) {
+        let (h, mut rt) = new_harness();
+        rt.set_policy(Policy { verified_claim_dispute_window: WINDOW, ..Policy::default() });
+        (h, rt)
+    }
+
+    fn create_claim(h: &Harness, rt: &MockRuntime) -> (ClaimID, Claim) {
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim = make_claim("1", CLIENT, PROVIDER, SIZE, term_min, term_min, TERM_START, SECTOR);
+        let id = h.create_claim(rt, &claim).unwrap();
+        rt.set_epoch(TERM_START);
+        (id, claim)
+    }
+
+    fn dispute_claim(
+        rt: &MockRuntime,
+        caller: ActorID,
+        provider: ActorID,
+        claim_id: ClaimID,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(caller));
+        rt.expect_validate_caller_any();
+        rt.call::<VerifregActor>(
+            Method::DisputeClaimExported as MethodNum,
+            IpldBlock::serialize_cbor(&DisputeClaimParams { provider, claim_id }).unwrap(),
+        )
+    }
+
+    fn resolve_claim_dispute(
+        rt: &MockRuntime,
+        claim_id: ClaimID,
+        upheld: bool,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ROOT_ADDR);
+        rt.expect_validate_caller_addr(vec![ROOT_ADDR]);
+        rt.call::<VerifregActor>(
+            Method::ResolveClaimDisputeExported as MethodNum,
+            IpldBlock::serialize_cbor(&ResolveClaimDisputeParams { claim_id, upheld }).unwrap(),
+        )
+    }
+
+    fn load_dispute_epoch(rt: &MockRuntime, claim_id: ClaimID) -> Option<ChainEpoch> {
+        let st: State = rt.get_versioned_state();
+        let disputes = st.load_claim_disputes(rt.store()).unwrap();
+        disputes.get(&claim_id).unwrap().map(|d| d.epoch)
+    }
+
+    fn expect_claim_disputed(rt: &MockRuntime, id: ClaimID, claim: &Claim) {
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("claim-disputed")
+                .field_indexed("id", &id)
+                .field_indexed("client", &claim.client)
+                .field_indexed("provider", &claim.provider)
+                .field_indexed("piece-cid", &claim.data)
+                .field("piece-size", &claim.size.0)
+                .field_indexed("sector", &claim.sector)
+                .build()
+                .unwrap(),
+        );
+    }
+
+    fn expect_dispute_resolved(rt: &MockRuntime, id: ClaimID, upheld: bool) {
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("claim-dispute-resolved")
+                .field_indexed("id", &id)
+                .field_indexed("provider", &PROVIDER)
+                .field("upheld", &upheld)
+                .build()
+                .unwrap(),
+        );
+    }
+}
+
 mod datacap {
     use frc46_token::receiver::FRC46_TOKEN_TYPE;
     use fvm_actor_utils::receiver::UniversalReceiverParams;
@@ -1225,7 +1833,7 @@ mod datacap {
             // Verify allocations in state.
             assert_allocation(&rt, CLIENT1, 1, &alloc_from_req(CLIENT1, &reqs[0]));
             assert_allocation(&rt, CLIENT1, 2, &alloc_from_req(CLIENT1, &reqs[1]));
-            let st: State = rt.get_state();
+            let st: State = rt.get_versioned_state();
             assert_eq!(3, st.next_allocation_id);
         }
         {
@@ -1236,7 +1844,7 @@ mod datacap {
 
             // Verify allocations in state.
             assert_allocation(&rt, CLIENT2, 3, &alloc_from_req(CLIENT2, &reqs[0]));
-            let st: State = rt.get_state();
+            let st: State = rt.get_versioned_state();
             assert_eq!(4, st.next_allocation_id);
         }
         {
@@ -1330,7 +1938,7 @@ mod datacap {
         assert_claim(&rt, PROVIDER1, cid1, &Claim { term_max: term_max + 1000, ..claim1 });
         assert_claim(&rt, PROVIDER2, cid2, &Claim { term_max: term_max + 2000, ..claim2 });
 
-        let st: State = rt.get_state();
+        let st: State = rt.get_versioned_state();
         assert_eq!(5, st.next_allocation_id);
         h.check_state(&rt);
     }
@@ -1519,11 +2127,11 @@ mod datacap {
             make_claim("1", CLIENT1, PROVIDER1, SIZE, term_min, term_max, term_start, sector);
 
         let cid1 = h.create_claim(&rt, &claim1).unwrap();
-        let st: State = rt.get_state();
+        let st: State = rt.get_versioned_state();
 
         // Extension too long
         {
-            rt.replace_state(&st);
+            rt.replace_versioned_state(&st);
             let epoch = term_start + 1000;
             rt.set_epoch(epoch);
             let max_allowed_term = epoch - term_start + MAXIMUM_VERIFIED_ALLOCATION_TERM;
@@ -1544,7 +2152,7 @@ mod datacap {
         }
         {
             // Claim already expired
-            rt.replace_state(&st);
+            rt.replace_versioned_state(&st);
             let epoch = term_start + term_max + 1;
             let new_term = epoch - term_start + MINIMUM_VERIFIED_ALLOCATION_TERM;
             rt.set_epoch(epoch);
@@ -1568,7 +2176,7 @@ mod datacap {
         }
         {
             // Extension is zero
-            rt.replace_state(&st);
+            rt.replace_versioned_state(&st);
             rt.set_epoch(term_start + 100);
             let reqs = vec![make_extension_req(PROVIDER1, cid1, term_max)];
             let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, SIZE);
@@ -1597,6 +2205,362 @@ mod datacap {
     }
 }
 
+mod verifier_top_up {
+    use frc46_token::receiver::{FRC46_TOKEN_TYPE, FRC46TokenReceived};
+    use frc46_token::token::types::{BurnParams, BurnReturn};
+    use fvm_actor_utils::receiver::UniversalReceiverParams;
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_shared::MethodNum;
+    use fvm_shared::address::Address;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use num_traits::Zero;
+
+    use fil_actor_verifreg::{
+        Actor as VerifregActor, AllocationsResponse, DataCap, Method, VERIFIER_TOP_UP_OPERATION,
+        VerifierTopUpRequest, ext,
+    };
+    use fil_actors_runtime::cbor::serialize;
+    use fil_actors_runtime::test_utils::*;
+    use fil_actors_runtime::{
+        ActorError, BatchReturn, DATACAP_TOKEN_ACTOR_ADDR, EventBuilder,
+        VERIFIED_REGISTRY_ACTOR_ADDR,
+    };
+    use harness::*;
+    use util::*;
+
+    use crate::*;
+
+    fn top_up(
+        rt: &MockRuntime,
+        from: &Address,
+        amount: TokenAmount,
+        operation: u64,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        let payload = FRC46TokenReceived {
+            from: from.id().unwrap(),
+            to: VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap(),
+            operator: from.id().unwrap(),
+            amount,
+            operator_data: serialize(&VerifierTopUpRequest { operation }, "operator data").unwrap(),
+            token_data: Default::default(),
+        };
+        let params = UniversalReceiverParams {
+            type_: FRC46_TOKEN_TYPE,
+            payload: serialize(&payload, "payload").unwrap(),
+        };
+        rt.set_caller(*DATACAP_TOKEN_ACTOR_CODE_ID, DATACAP_TOKEN_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![DATACAP_TOKEN_ACTOR_ADDR]);
+        rt.call::<VerifregActor>(
+            Method::UniversalReceiverHook as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+    }
+
+    #[test]
+    fn top_up_increases_allowance_and_burns() {
+        let (h, rt) = new_harness();
+        let allowance = verifier_allowance(&rt);
+        h.add_verifier(&rt, &VERIFIER, &allowance).unwrap();
+
+        let expected = &allowance + DataCap::from(10);
+        rt.expect_send_simple(
+            DATACAP_TOKEN_ACTOR_ADDR,
+            ext::datacap::Method::Burn as MethodNum,
+            IpldBlock::serialize_cbor(&BurnParams { amount: TokenAmount::from_whole(10) }).unwrap(),
+            TokenAmount::zero(),
+            IpldBlock::serialize_cbor(&BurnReturn { balance: TokenAmount::zero() }).unwrap(),
+            ExitCode::OK,
+        );
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("verifier-balance")
+                .field_indexed("verifier", &VERIFIER.id().unwrap())
+                .field("balance", &BigIntSer(&expected))
+                .build()
+                .unwrap(),
+        );
+        let ret: AllocationsResponse =
+            top_up(&rt, &VERIFIER, TokenAmount::from_whole(10), VERIFIER_TOP_UP_OPERATION)
+                .unwrap()
+                .unwrap()
+                .deserialize()
+                .unwrap();
+        rt.verify();
+
+        assert_eq!(BatchReturn::empty(), ret.allocation_results);
+        assert_eq!(BatchReturn::empty(), ret.extension_results);
+        assert!(ret.new_allocations.is_empty());
+        assert_eq!(expected, h.get_verifier_allowance(&rt, &VERIFIER));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn top_up_requires_verifier() {
+        let (h, rt) = new_harness();
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "is not a verifier",
+            top_up(&rt, &CLIENT, TokenAmount::from_whole(10), VERIFIER_TOP_UP_OPERATION),
+        );
+        rt.verify();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn top_up_requires_whole_datacap() {
+        let (h, rt) = new_harness();
+        let allowance = verifier_allowance(&rt);
+        h.add_verifier(&rt, &VERIFIER, &allowance).unwrap();
+
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "positive whole amount of datacap",
+            top_up(
+                &rt,
+                &VERIFIER,
+                TokenAmount::from_whole(10) + TokenAmount::from_atto(1),
+                VERIFIER_TOP_UP_OPERATION,
+            ),
+        );
+        rt.verify();
+        assert_eq!(allowance, h.get_verifier_allowance(&rt, &VERIFIER));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn unknown_operation_is_not_a_top_up() {
+        let (h, rt) = new_harness();
+        let allowance = verifier_allowance(&rt);
+        h.add_verifier(&rt, &VERIFIER, &allowance).unwrap();
+
+        // Any other tag is parsed as allocation requests, and fails.
+        expect_abort(
+            ExitCode::USR_SERIALIZATION,
+            top_up(&rt, &VERIFIER, TokenAmount::from_whole(10), VERIFIER_TOP_UP_OPERATION + 1),
+        );
+        rt.verify();
+        assert_eq!(allowance, h.get_verifier_allowance(&rt, &VERIFIER));
+        h.check_state(&rt);
+    }
+}
+
+mod observer_verifiers {
+    use std::ops::Deref;
+
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_shared::MethodNum;
+    use fvm_shared::address::Address;
+    use fvm_shared::error::ExitCode;
+
+    use fil_actor_verifreg::{
+        Actor as VerifregActor, AddVerifiedClientParams, DataCap, ListObserverVerifiersReturn,
+        Method, OBSERVER_VERIFIER_METADATA_MAX_LEN, ObserverVerifierInfo,
+    };
+    use fil_actors_runtime::test_utils::*;
+    use harness::*;
+    use util::*;
+
+    use crate::*;
+
+    #[test]
+    fn add_list_and_remove_observers() {
+        let (h, rt) = new_harness();
+        assert!(h.list_observer_verifiers(&rt, None, 10).unwrap().observers.is_empty());
+
+        rt.set_epoch(10);
+        h.add_observer_verifier(&rt, &VERIFIER2, "notary two").unwrap();
+        h.add_observer_verifier(&rt, &VERIFIER, "notary one").unwrap();
+        // Registering again replaces the metadata.
+        rt.set_epoch(20);
+        h.add_observer_verifier(&rt, &VERIFIER2, "notary two, renewed").unwrap();
+        let listed = h.list_observer_verifiers(&rt, None, 10).unwrap();
+        assert_eq!(None, listed.next_cursor);
+        assert_eq!(
+            vec![
+                ObserverVerifierInfo {
+                    verifier: VERIFIER.id().unwrap(),
+                    metadata: "notary one".to_string(),
+                    registered: 10,
+                },
+                ObserverVerifierInfo {
+                    verifier: VERIFIER2.id().unwrap(),
+                    metadata: "notary two, renewed".to_string(),
+                    registered: 20,
+                },
+            ],
+            listed.observers
+        );
+        h.check_state(&rt);
+
+        h.remove_observer_verifier(&rt, &VERIFIER).unwrap();
+        assert_eq!(
+            vec![VERIFIER2.id().unwrap()],
+            h.list_observer_verifiers(&rt, None, 10)
+                .unwrap()
+                .observers
+                .iter()
+                .map(|o| o.verifier)
+                .collect::<Vec<_>>()
+        );
+        expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.remove_observer_verifier(&rt, &VERIFIER));
+        rt.reset();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn list_observers_in_pages() {
+        let (h, rt) = new_harness();
+        h.add_observer_verifier(&rt, &VERIFIER, "notary one").unwrap();
+        h.add_observer_verifier(&rt, &VERIFIER2, "notary two").unwrap();
+        h.add_observer_verifier(&rt, &CLIENT, "notary three").unwrap();
+
+        expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.list_observer_verifiers(&rt, None, 0));
+        rt.reset();
+
+        let ids = |ret: &ListObserverVerifiersReturn| {
+            ret.observers.iter().map(|o| o.verifier).collect::<Vec<_>>()
+        };
+        let first = h.list_observer_verifiers(&rt, None, 1).unwrap();
+        assert_eq!(vec![VERIFIER.id().unwrap()], ids(&first));
+        assert_eq!(VERIFIER2.id().ok(), first.next_cursor);
+
+        // Removing the observer at the cursor doesn't disrupt listing.
+        h.remove_observer_verifier(&rt, &VERIFIER2).unwrap();
+        let second = h.list_observer_verifiers(&rt, first.next_cursor, 1).unwrap();
+        assert_eq!(vec![CLIENT.id().unwrap()], ids(&second));
+        assert_eq!(None, second.next_cursor);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn add_observer_rejects_invalid_registration() {
+        let (h, rt) = new_harness();
+        let allowance = verifier_allowance(&rt);
+
+        let too_long = "x".repeat(OBSERVER_VERIFIER_METADATA_MAX_LEN + 1);
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            h.add_observer_verifier(&rt, &VERIFIER, &too_long),
+        );
+        rt.reset();
+        expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.add_observer_verifier(&rt, &ROOT_ADDR, ""));
+        rt.reset();
+
+        // A verifier with an allowance cannot become an observer.
+        h.add_verifier(&rt, &VERIFIER, &allowance).unwrap();
+        expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.add_observer_verifier(&rt, &VERIFIER, ""));
+        rt.reset();
+        assert!(h.get_observer_verifier(&rt, &VERIFIER).is_none());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn remove_observer_requires_root() {
+        let (h, rt) = new_harness();
+        h.add_observer_verifier(&rt, &VERIFIER, "notary").unwrap();
+
+        let caller = Address::new_id(501);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+        rt.expect_validate_caller_addr(vec![h.root]);
+        expect_abort(
+            ExitCode::USR_FORBIDDEN,
+            rt.call::<VerifregActor>(
+                Method::RemoveObserverVerifierExported as MethodNum,
+                IpldBlock::serialize_cbor(VERIFIER.deref()).unwrap(),
+            ),
+        );
+        rt.verify();
+        assert!(h.get_observer_verifier(&rt, &VERIFIER).is_some());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn observer_cannot_grant_datacap() {
+        let (h, rt) = new_harness();
+        let allowance = client_allowance(&rt);
+        h.add_observer_verifier(&rt, &VERIFIER, "notary").unwrap();
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *VERIFIER);
+        rt.expect_validate_caller_any();
+        let params =
+            AddVerifiedClientParams { address: *CLIENT, allowance: allowance.try_into().unwrap() };
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "cannot grant datacap",
+            rt.call::<VerifregActor>(
+                Method::AddVerifiedClientExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        rt.verify();
+
+        // Nor can an observer be added as a client.
+        let verifier_allowance = verifier_allowance(&rt);
+        h.add_verifier(&rt, &VERIFIER2, &verifier_allowance).unwrap();
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            h.add_client(&rt, &VERIFIER2, &VERIFIER, &client_allowance(&rt), &DataCap::from(1)),
+        );
+        rt.reset();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn promote_observer_to_verifier() {
+        let (h, rt) = new_harness();
+        let allowance = verifier_allowance(&rt);
+        h.add_observer_verifier(&rt, &VERIFIER, "notary").unwrap();
+
+        // Assigning an allowance ends the observer registration.
+        h.add_verifier(&rt, &VERIFIER, &allowance).unwrap();
+        assert!(h.get_observer_verifier(&rt, &VERIFIER).is_none());
+        assert!(h.list_observer_verifiers(&rt, None, 10).unwrap().observers.is_empty());
+
+        let client_allowance = client_allowance(&rt);
+        h.add_client(&rt, &VERIFIER, &CLIENT, &client_allowance, &(&allowance - &client_allowance))
+            .unwrap();
+        h.check_state(&rt);
+    }
+}
+
+mod policy {
+    use fil_actor_verifreg::{Actor as VerifregActor, GetVerifregPolicyReturn, Method};
+    use fil_actors_runtime::test_utils::*;
+    use fvm_shared::MethodNum;
+    use fvm_shared::address::Address;
+    use harness::*;
+
+    use crate::*;
+
+    #[test]
+    fn get_policy_returns_runtime_policy() {
+        let (_h, rt) = new_harness();
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1000));
+        rt.expect_validate_caller_any();
+        let ret: GetVerifregPolicyReturn = rt
+            .call::<VerifregActor>(Method::GetVerifregPolicyExported as MethodNum, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+
+        let policy = &rt.policy;
+        assert_eq!(
+            GetVerifregPolicyReturn {
+                minimum_allocation_size: policy.minimum_verified_allocation_size.clone(),
+                minimum_allocation_term: policy.minimum_verified_allocation_term,
+                maximum_allocation_term: policy.maximum_verified_allocation_term,
+                maximum_allocation_expiration: policy.maximum_verified_allocation_expiration,
+                end_of_life_claim_drop_period: policy.end_of_life_claim_drop_period,
+                claim_dispute_window: policy.verified_claim_dispute_window,
+            },
+            ret
+        );
+    }
+}
+
 // Tests to match with Go github.com/filecoin-project/go-state-types/builtin/*/verifreg
 mod serialization {
     use std::str::FromStr;
@@ -93,6 +93,7 @@ impl<'vm> DealBatcher<'vm> {
             storage_price_per_epoch: opts.price_per_epoch,
             provider_collateral: opts.provider_collateral,
             client_collateral: opts.client_collateral,
+            payment_token: None,
         };
         self.deals.push(deal)
     }
@@ -25,7 +25,7 @@ use fil_actor_power::{UpdateClaimedPowerParams, UpdatePledgeTotalParams};
 use fil_actor_verifreg::GetClaimsParams;
 use fil_actors_runtime::{
     BURNT_FUNDS_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ID, EventBuilder,
-    REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID,
+    REWARD_ACTOR_ADDR, REWARD_ACTOR_ID, STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID,
     STORAGE_POWER_ACTOR_ADDR, STORAGE_POWER_ACTOR_ID, VERIFIED_REGISTRY_ACTOR_ADDR,
     VERIFIED_REGISTRY_ACTOR_ID,
 };
@@ -42,9 +42,10 @@ impl Expect {
     pub fn burn(from: ActorID, v: Option<TokenAmount>) -> ExpectInvocation {
         Self::send(from, BURNT_FUNDS_ACTOR_ADDR, v)
     }
+    /// Expects activation of deals, each given with its piece CID and verified flag.
     pub fn market_activate_deals(
         from: ActorID,
-        deals: Vec<DealID>,
+        deals: Vec<(DealID, Cid, bool)>,
         client_id: ActorID,
         sector_number: SectorNumber,
         sector_expiry: ChainEpoch,
@@ -54,7 +55,7 @@ impl Expect {
         let params = IpldBlock::serialize_cbor(&BatchActivateDealsParams {
             sectors: vec![SectorDeals {
                 sector_number,
-                deal_ids: deals.clone(),
+                deal_ids: deals.iter().map(|(deal_id, _, _)| *deal_id).collect(),
                 sector_expiry,
                 sector_type,
             }],
@@ -64,7 +65,16 @@ impl Expect {
 
         let events: Vec<EmittedEvent> = deals
             .iter()
-            .map(|deal_id| Expect::build_market_event("deal-activated", *deal_id, client_id, from))
+            .map(|(deal_id, piece_cid, verified)| {
+                Expect::build_deal_activated_event(
+                    *deal_id,
+                    client_id,
+                    from,
+                    sector_number,
+                    piece_cid,
+                    *verified,
+                )
+            })
             .collect();
 
         ExpectInvocation {
@@ -204,6 +214,17 @@ impl Expect {
             to: REWARD_ACTOR_ADDR,
             method: fil_actor_reward::Method::UpdateNetworkKPI as u64,
             value: Some(TokenAmount::zero()),
+            subinvocs: Some(vec![Self::power_notify_epoch_reward()]),
+            ..Default::default()
+        }
+    }
+    pub fn power_notify_epoch_reward() -> ExpectInvocation {
+        // Note: params are unchecked
+        ExpectInvocation {
+            from: REWARD_ACTOR_ID,
+            to: STORAGE_POWER_ACTOR_ADDR,
+            method: fil_actor_power::Method::NotifyEpochRewardExported as u64,
+            value: Some(TokenAmount::zero()),
             subinvocs: Some(vec![]),
             ..Default::default()
         }
@@ -435,6 +456,29 @@ impl Expect {
         }
     }
 
+    pub fn build_deal_activated_event(
+        deal_id: DealID,
+        client: ActorID,
+        provider: ActorID,
+        sector: SectorNumber,
+        piece_cid: &Cid,
+        verified: bool,
+    ) -> EmittedEvent {
+        EmittedEvent {
+            emitter: STORAGE_MARKET_ACTOR_ID,
+            event: EventBuilder::new()
+                .typ("deal-activated")
+                .field_indexed("id", &deal_id)
+                .field_indexed("client", &client)
+                .field_indexed("provider", &provider)
+                .field_indexed("sector", &sector)
+                .field_indexed("piece-cid", piece_cid)
+                .field_indexed("verified", &verified)
+                .build()
+                .unwrap(),
+        }
+    }
+
     pub fn build_miner_event(
         typ: &str,
         miner_id: ActorID,
@@ -450,6 +494,17 @@ impl Expect {
         }
     }
 
+    pub fn build_miner_pledge_event(miner_id: ActorID, delta: &TokenAmount) -> EmittedEvent {
+        EmittedEvent {
+            emitter: miner_id,
+            event: EventBuilder::new()
+                .typ("pledge-delta")
+                .field_bigint("delta", delta.atto())
+                .build()
+                .unwrap(),
+        }
+    }
+
     pub fn build_sector_activation_event(
         typ: &str,
         miner_id: ActorID,
@@ -15,7 +15,8 @@ use crate::expects::Expect;
 use crate::util::{
     advance_by_deadline_to_epoch, advance_to_proving_deadline, assert_invariants, create_accounts,
     create_miner, cron_tick, expect_invariants, get_network_stats, invariant_failure_patterns,
-    miner_balance, miner_prove_sector, precommit_sectors_v2, submit_windowed_post,
+    miner_balance, miner_pledge_collateral, miner_prove_sector, precommit_sectors_v2,
+    submit_windowed_post,
 };
 use fil_actor_cron::Method as CronMethod;
 use fil_actor_market::Method as MarketMethod;
@@ -30,7 +31,7 @@ use fil_actors_runtime::{
     STORAGE_POWER_ACTOR_ID,
 };
 use vm_api::VM;
-use vm_api::trace::{EmittedEvent, ExpectInvocation};
+use vm_api::trace::ExpectInvocation;
 use vm_api::util::{DynBlockstore, apply_code, apply_ok, get_state};
 
 struct SectorInfo {
@@ -92,10 +93,7 @@ fn setup(v: &dyn VM) -> (MinerInfo, SectorInfo) {
                 from: CRON_ACTOR_ID,
                 to: STORAGE_POWER_ACTOR_ADDR,
                 method: PowerMethod::OnEpochTickEnd as u64,
-                subinvocs: Some(vec![
-                    Expect::reward_this_epoch(STORAGE_POWER_ACTOR_ID),
-                    Expect::reward_update_kpi(),
-                ]),
+                subinvocs: Some(vec![Expect::reward_update_kpi()]),
                 events: Some(vec![]),
                 ..Default::default()
             },
@@ -166,7 +164,6 @@ pub fn submit_post_succeeds_test(v: &dyn VM) {
                 to: STORAGE_POWER_ACTOR_ADDR,
                 method: PowerMethod::OnEpochTickEnd as u64,
                 subinvocs: Some(vec![
-                    Expect::reward_this_epoch(STORAGE_POWER_ACTOR_ID),
                     ExpectInvocation {
                         from: STORAGE_POWER_ACTOR_ID,
                         to: miner_info.miner_id,
@@ -221,6 +218,7 @@ pub fn skip_sector_test(v: &dyn VM) {
         }],
         chain_commit_epoch: sector_info.deadline_info.challenge,
         chain_commit_rand: Randomness(TEST_VM_RAND_ARRAY.into()),
+        recover_faults: false,
     };
 
     // PoSt is rejected for skipping all sectors.
@@ -268,7 +266,6 @@ pub fn missed_first_post_deadline_test(v: &dyn VM) {
                 to: STORAGE_POWER_ACTOR_ADDR,
                 method: PowerMethod::OnEpochTickEnd as u64,
                 subinvocs: Some(vec![
-                    Expect::reward_this_epoch(STORAGE_POWER_ACTOR_ID),
                     ExpectInvocation {
                         from: STORAGE_POWER_ACTOR_ID,
                         to: miner_info.miner_id,
@@ -370,7 +367,6 @@ pub fn overdue_precommit_test(v: &dyn VM) {
                 to: STORAGE_POWER_ACTOR_ADDR,
                 method: PowerMethod::OnEpochTickEnd as u64,
                 subinvocs: Some(vec![
-                    Expect::reward_this_epoch(STORAGE_POWER_ACTOR_ID),
                     ExpectInvocation {
                         from: STORAGE_POWER_ACTOR_ID,
                         to: id_addr,
@@ -746,6 +742,7 @@ pub fn aggregate_one_precommit_expires_test(v: &dyn VM) {
         aggregate_proof: vec![].into(),
     };
     let prove_params_ser = IpldBlock::serialize_cbor(&prove_params).unwrap();
+    let pledge_before = miner_pledge_collateral(v, &miner_addr);
     apply_ok(
         v,
         &worker,
@@ -754,21 +751,20 @@ pub fn aggregate_one_precommit_expires_test(v: &dyn VM) {
         MinerMethod::ProveCommitAggregate as u64,
         Some(prove_params),
     );
-
-    let events: Vec<EmittedEvent> = later_precommits
-        .iter()
-        .map(|info| {
-            let pieces: Vec<(Cid, u64)> = vec![];
-            let unsealed_cid = info.info.unsealed_cid.0;
-            Expect::build_sector_activation_event(
-                "sector-activated",
-                miner_id,
-                info.info.sector_number,
-                unsealed_cid,
-                &pieces,
-            )
-        })
-        .collect();
+    let pledge_delta = miner_pledge_collateral(v, &miner_addr) - pledge_before;
+
+    let mut events = vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta)];
+    events.extend(later_precommits.iter().map(|info| {
+        let pieces: Vec<(Cid, u64)> = vec![];
+        let unsealed_cid = info.info.unsealed_cid.0;
+        Expect::build_sector_activation_event(
+            "sector-activated",
+            miner_id,
+            info.info.sector_number,
+            unsealed_cid,
+            &pieces,
+        )
+    }));
 
     ExpectInvocation {
         from: worker_id,
@@ -37,7 +37,11 @@ pub fn datacap_transfer_test(v: &dyn VM) {
     let data_cap_amt = TokenAmount::from_whole(
         MINIMUM_VERIFIED_ALLOCATION_SIZE + MINIMUM_VERIFIED_ALLOCATION_SIZE / 2,
     );
-    let mint_params = MintParams { to: client, amount: data_cap_amt, operators: vec![operator] };
+    let mint_params = MintParams {
+        to: client,
+        amount: data_cap_amt.try_into().unwrap(),
+        operators: vec![operator],
+    };
 
     // cannot mint from non-verifreg
     apply_code(
@@ -29,9 +29,9 @@ use crate::util::{
     advance_by_deadline_to_index, advance_to_proving_deadline, bf_all, create_accounts,
     create_miner, cron_tick, expect_invariants, invariant_failure_patterns,
     make_piece_manifests_from_deal_ids, market_add_balance, market_pending_deal_allocations,
-    market_publish_deal, miner_precommit_one_sector_v2, miner_prove_sector,
-    override_compute_unsealed_sector_cid, precommit_meta_data_from_deals, sector_deadline,
-    submit_windowed_post, verifreg_add_client, verifreg_add_verifier,
+    market_publish_deal, miner_pledge_collateral, miner_precommit_one_sector_v2,
+    miner_prove_sector, override_compute_unsealed_sector_cid, precommit_meta_data_from_deals,
+    sector_deadline, submit_windowed_post, verifreg_add_client, verifreg_add_verifier,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -658,6 +658,7 @@ pub fn extend_updated_sector_with_claims_test(v: &dyn VM) {
         update_proof_type: fvm_shared::sector::RegisteredUpdateProof::StackedDRG32GiBV1,
         replica_proof: vec![].into(),
     };
+    let pledge_before = miner_pledge_collateral(v, &miner_addr);
     let updated_sectors: BitField = apply_ok(
         v,
         &worker,
@@ -669,6 +670,7 @@ pub fn extend_updated_sector_with_claims_test(v: &dyn VM) {
     .deserialize()
     .unwrap();
     assert_eq!(vec![sector_number], bf_all(updated_sectors));
+    let pledge_delta = miner_pledge_collateral(v, &miner_addr) - pledge_before;
 
     let old_power = power_for_sector(seal_proof.sector_size().unwrap(), &initial_sector_info);
 
@@ -688,7 +690,7 @@ pub fn extend_updated_sector_with_claims_test(v: &dyn VM) {
         subinvocs: Some(vec![
             Expect::market_activate_deals(
                 miner_id,
-                deal_ids,
+                deal_ids.iter().map(|id| (*id, piece_cid, true)).collect(),
                 verified_client.id().unwrap(),
                 sector_number,
                 initial_sector_info.expiration,
@@ -721,13 +723,16 @@ pub fn extend_updated_sector_with_claims_test(v: &dyn VM) {
                 PowerPair { raw: StoragePower::zero(), qa: 9 * old_power.qa },
             ),
         ]),
-        events: Some(vec![Expect::build_sector_activation_event(
-            "sector-updated",
-            miner_id,
-            sector_number,
-            Some(unsealed_cid),
-            &pieces,
-        )]),
+        events: Some(vec![
+            Expect::build_sector_activation_event(
+                "sector-updated",
+                miner_id,
+                sector_number,
+                Some(unsealed_cid),
+                &pieces,
+            ),
+            Expect::build_miner_pledge_event(miner_id, &pledge_delta),
+        ]),
         ..Default::default()
     }
     .matches(v.take_invocations().last().unwrap());
@@ -42,3 +42,5 @@ mod prove_commit_niporep_test;
 pub use prove_commit_niporep_test::*;
 mod replica_update3_test;
 pub use replica_update3_test::*;
+mod upgrade_rehearsal_test;
+pub use upgrade_rehearsal_test::*;
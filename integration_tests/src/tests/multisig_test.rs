@@ -40,6 +40,7 @@ pub fn proposal_hash_test(v: &dyn VM) {
         value: fil_delta.clone(),
         method: METHOD_SEND,
         params: RawBytes::default(),
+        expiration: None,
     };
     apply_ok(
         v,
@@ -56,6 +57,7 @@ pub fn proposal_hash_test(v: &dyn VM) {
         method: METHOD_SEND,
         approved: vec![alice],
         params: RawBytes::default(),
+        expiration: None,
     };
 
     let wrong_hash = compute_proposal_hash(&wrong_tx, v.primitives()).unwrap();
@@ -77,6 +79,7 @@ pub fn proposal_hash_test(v: &dyn VM) {
         method: METHOD_SEND,
         approved: vec![alice],
         params: RawBytes::default(),
+        expiration: None,
     };
 
     let correct_hash = compute_proposal_hash(&correct_tx, v.primitives()).unwrap();
@@ -119,6 +122,7 @@ pub fn test_delete_self_inner_test(v: &dyn VM, signers: u64, threshold: usize, r
         value: TokenAmount::zero(),
         method: MsigMethod::RemoveSigner as u64,
         params: remove_param_ser,
+        expiration: None,
     };
 
     // first proposal goes ok and should have txnid = 0
@@ -181,6 +185,7 @@ pub fn swap_self_1_of_2_test(v: &dyn VM) {
         value: TokenAmount::zero(),
         method: MsigMethod::SwapSigner as u64,
         params: serialize(&swap_params, "swap params").unwrap(),
+        expiration: None,
     };
     // alice succeeds when trying to execute the tx swapping alice for chuck
     apply_ok(
@@ -210,6 +215,7 @@ pub fn swap_self_2_of_3_test(v: &dyn VM) {
         value: TokenAmount::zero(),
         method: MsigMethod::SwapSigner as u64,
         params: serialize(&swap_params, "swap params").unwrap(),
+        expiration: None,
     };
 
     // proposal from swapped addr goes ok with txnid 0
@@ -242,6 +248,7 @@ pub fn swap_self_2_of_3_test(v: &dyn VM) {
         value: TokenAmount::zero(),
         method: MsigMethod::SwapSigner as u64,
         params: serialize(&swap_params, "swap params").unwrap(),
+        expiration: None,
     };
 
     // proposal from non swapped goes ok, txnid = 1
@@ -157,6 +157,7 @@ pub fn cron_tick_test(v: &dyn VM) {
         to: STORAGE_POWER_ACTOR_ADDR,
         method: PowerMethod::OnEpochTickEnd as u64,
         subinvocs: Some(vec![
+            // no reward has yet been pushed to the power actor, so it queries the reward
             Expect::reward_this_epoch(STORAGE_POWER_ACTOR_ID),
             // expect miner call to be missing
             Expect::reward_update_kpi(),
@@ -181,7 +182,6 @@ pub fn cron_tick_test(v: &dyn VM) {
     );
 
     let sub_invocs = vec![
-        Expect::reward_this_epoch(STORAGE_POWER_ACTOR_ID),
         // expect call back to miner that was set up in create miner
         ExpectInvocation {
             from: STORAGE_POWER_ACTOR_ID,
@@ -28,7 +28,7 @@ use fil_actors_runtime::{
     EPOCHS_IN_DAY, EPOCHS_IN_YEAR, STORAGE_MARKET_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use vm_api::VM;
-use vm_api::trace::{EmittedEvent, ExpectInvocation};
+use vm_api::trace::ExpectInvocation;
 use vm_api::util::apply_ok;
 
 use crate::deals::{DealBatcher, DealOptions};
@@ -36,8 +36,8 @@ use crate::expects::Expect;
 use crate::util::{
     PrecommitMetadata, advance_by_deadline_to_epoch, create_accounts, create_miner,
     datacap_create_allocations, market_add_balance, market_list_deals, market_list_sectors_deals,
-    override_compute_unsealed_sector_cid, precommit_sectors_v2, sector_info, verifreg_add_client,
-    verifreg_add_verifier, verifreg_list_claims,
+    miner_pledge_collateral, override_compute_unsealed_sector_cid, precommit_sectors_v2,
+    sector_info, verifreg_add_client, verifreg_add_verifier, verifreg_list_claims,
 };
 
 #[vm_test]
@@ -127,6 +127,7 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
                 size: full_piece_size,
                 verified_allocation_key: None,
                 notify: vec![],
+                verified_allocation_parts: vec![],
             }],
         },
         // Sector 2: two pieces for verified claims.
@@ -143,6 +144,7 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
                         id: alloc_ids_s2[i],
                     }),
                     notify: vec![],
+                    verified_allocation_parts: vec![],
                 })
                 .collect(),
         },
@@ -157,6 +159,7 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
                     address: STORAGE_MARKET_ACTOR_ADDR,
                     payload: serialize(&deal_ids_s3[0], "dealid").unwrap(),
                 }],
+                verified_allocation_parts: vec![],
             }],
         },
         // Sector 4: a half-sized, verified deal, and implicit empty space
@@ -173,6 +176,7 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
                     address: STORAGE_MARKET_ACTOR_ADDR,
                     payload: serialize(&deal_ids_s4[0], "deal id").unwrap(),
                 }],
+                verified_allocation_parts: vec![],
             }],
         },
     ];
@@ -214,6 +218,7 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
         require_activation_success: true,
         require_notification_success: true,
     };
+    let pledge_before = miner_pledge_collateral(v, &maddr);
     apply_ok(
         v,
         &worker,
@@ -222,23 +227,21 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
         MinerMethod::ProveCommitSectors3 as u64,
         Some(params.clone()),
     );
+    let pledge_delta = miner_pledge_collateral(v, &maddr) - pledge_before;
 
-    let events: Vec<EmittedEvent> = manifests
-        .iter()
-        .enumerate()
-        .map(|(i, sa)| {
-            let unsealed_cid = meta.get(i).unwrap().commd.0;
+    let mut events = vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta)];
+    events.extend(manifests.iter().enumerate().map(|(i, sa)| {
+        let unsealed_cid = meta.get(i).unwrap().commd.0;
 
-            let pieces: Vec<(Cid, u64)> = sa.pieces.iter().map(|p| (p.cid, p.size.0)).collect();
-            Expect::build_sector_activation_event(
-                "sector-activated",
-                miner_id,
-                sa.sector_number,
-                unsealed_cid,
-                &pieces,
-            )
-        })
-        .collect();
+        let pieces: Vec<(Cid, u64)> = sa.pieces.iter().map(|p| (p.cid, p.size.0)).collect();
+        Expect::build_sector_activation_event(
+            "sector-activated",
+            miner_id,
+            sa.sector_number,
+            unsealed_cid,
+            &pieces,
+        )
+    }));
 
     let claim_event_1 = Expect::build_verifreg_claim_event(
         "claim",
@@ -359,20 +362,24 @@ pub fn prove_commit_sectors2_test(v: &dyn VM) {
                 ),
                 value: Some(TokenAmount::zero()),
                 subinvocs: Some(vec![]),
-                events: Some(
-                    deal_ids_s3
-                        .iter()
-                        .chain(deal_ids_s4.iter())
-                        .map(|deal_id| {
-                            Expect::build_market_event(
-                                "deal-activated",
-                                *deal_id,
-                                client_id,
-                                miner_id,
-                            )
-                        })
-                        .collect::<Vec<_>>(),
-                ),
+                events: Some(vec![
+                    Expect::build_deal_activated_event(
+                        deal_ids_s3[0],
+                        client_id,
+                        miner_id,
+                        first_sector_number + 3,
+                        &make_piece_cid(b"s3p1"),
+                        false,
+                    ),
+                    Expect::build_deal_activated_event(
+                        deal_ids_s4[0],
+                        client_id,
+                        miner_id,
+                        first_sector_number + 4,
+                        &make_piece_cid(b"s4p1"),
+                        true,
+                    ),
+                ]),
                 ..Default::default()
             },
         ]),
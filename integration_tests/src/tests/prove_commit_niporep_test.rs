@@ -16,14 +16,14 @@ use fil_actor_miner::{
 };
 use fil_actors_runtime::test_utils::make_sealed_cid;
 use vm_api::VM;
-use vm_api::trace::{EmittedEvent, ExpectInvocation};
+use vm_api::trace::ExpectInvocation;
 use vm_api::util::{DynBlockstore, apply_ok};
 
 use crate::expects::Expect;
 use crate::util::{
     advance_by_deadline_to_epoch, advance_by_deadline_to_index, create_accounts, create_miner,
-    deadline_state, declare_recovery, override_compute_unsealed_sector_cid, sector_info,
-    submit_windowed_post, try_sector_info,
+    deadline_state, declare_recovery, miner_pledge_collateral,
+    override_compute_unsealed_sector_cid, sector_info, submit_windowed_post, try_sector_info,
 };
 
 #[vm_test]
@@ -83,6 +83,7 @@ pub fn prove_commit_ni_whole_success_test(v: &dyn VM) {
 
     v.set_epoch(activation_epoch);
 
+    let pledge_before = miner_pledge_collateral(v, &maddr);
     let pcsni_ret: ProveCommitSectorsNIReturn = apply_ok(
         v,
         &worker,
@@ -93,23 +94,22 @@ pub fn prove_commit_ni_whole_success_test(v: &dyn VM) {
     )
     .deserialize()
     .unwrap();
+    let pledge_delta = miner_pledge_collateral(v, &maddr) - pledge_before;
 
     assert_eq!(pcsni_ret.activation_results.size(), 5);
     assert!(pcsni_ret.activation_results.all_ok());
     assert_eq!(pcsni_ret.activation_results.codes(), [ExitCode::OK].repeat(5));
 
-    let events: Vec<EmittedEvent> = sector_nos
-        .iter()
-        .map(|sector_number| {
-            Expect::build_sector_activation_event(
-                "sector-activated",
-                miner_id,
-                *sector_number,
-                None,
-                &vec![],
-            )
-        })
-        .collect();
+    let mut events = vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta)];
+    events.extend(sector_nos.iter().map(|sector_number| {
+        Expect::build_sector_activation_event(
+            "sector-activated",
+            miner_id,
+            *sector_number,
+            None,
+            &vec![],
+        )
+    }));
 
     ExpectInvocation {
         from: worker_id,
@@ -217,6 +217,7 @@ pub fn prove_commit_ni_partial_success_not_required_test(v: &dyn VM) {
 
     v.set_epoch(activation_epoch);
 
+    let pledge_before = miner_pledge_collateral(v, &maddr);
     let pcsni_ret: ProveCommitSectorsNIReturn = apply_ok(
         v,
         &worker,
@@ -227,6 +228,7 @@ pub fn prove_commit_ni_partial_success_not_required_test(v: &dyn VM) {
     )
     .deserialize()
     .unwrap();
+    let pledge_delta = miner_pledge_collateral(v, &maddr) - pledge_before;
 
     assert_eq!(pcsni_ret.activation_results.size(), sector_nos.len());
     assert!(!pcsni_ret.activation_results.all_ok());
@@ -237,18 +239,16 @@ pub fn prove_commit_ni_partial_success_not_required_test(v: &dyn VM) {
             .collect::<Vec<_>>()
     );
 
-    let events: Vec<EmittedEvent> = valid_sector_nos
-        .iter()
-        .map(|sector_number| {
-            Expect::build_sector_activation_event(
-                "sector-activated",
-                miner_id,
-                *sector_number,
-                None,
-                &vec![],
-            )
-        })
-        .collect();
+    let mut events = vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta)];
+    events.extend(valid_sector_nos.iter().map(|sector_number| {
+        Expect::build_sector_activation_event(
+            "sector-activated",
+            miner_id,
+            *sector_number,
+            None,
+            &vec![],
+        )
+    }));
 
     ExpectInvocation {
         from: worker_id,
@@ -416,6 +416,7 @@ pub fn prove_commit_ni_next_deadline_post_required_test(v: &dyn VM) {
 
     v.set_epoch(activation_epoch);
 
+    let pledge_before = miner_pledge_collateral(v, &maddr);
     let pcsni_ret: ProveCommitSectorsNIReturn = apply_ok(
         v,
         &worker,
@@ -426,6 +427,7 @@ pub fn prove_commit_ni_next_deadline_post_required_test(v: &dyn VM) {
     )
     .deserialize()
     .unwrap();
+    let pledge_delta = miner_pledge_collateral(v, &maddr) - pledge_before;
 
     assert_eq!(pcsni_ret.activation_results.size(), 1);
     assert!(pcsni_ret.activation_results.all_ok());
@@ -437,13 +439,16 @@ pub fn prove_commit_ni_next_deadline_post_required_test(v: &dyn VM) {
         method: MinerMethod::ProveCommitSectorsNI as u64,
         params: Some(IpldBlock::serialize_cbor(&params).unwrap()),
         subinvocs: None,
-        events: Some(vec![Expect::build_sector_activation_event(
-            "sector-activated",
-            miner_id,
-            sector_number,
-            None,
-            &vec![],
-        )]),
+        events: Some(vec![
+            Expect::build_miner_pledge_event(miner_id, &pledge_delta),
+            Expect::build_sector_activation_event(
+                "sector-activated",
+                miner_id,
+                sector_number,
+                None,
+                &vec![],
+            ),
+        ]),
         ..Default::default()
     }
     .matches(v.take_invocations().last().unwrap());
@@ -1,5 +1,7 @@
 use fil_actor_account::Method as AccountMethod;
-use fil_actor_account::types::AuthenticateMessageParams;
+use fil_actor_account::types::{
+    AuthenticateMessageParams, BatchAuthenticateMessageParams, BatchAuthenticateMessageReturn,
+};
 use fil_actor_market::{
     ClientDealProposal, DealProposal, Label, Method as MarketMethod, PublishStorageDealsParams,
 };
@@ -70,7 +72,7 @@ fn setup(v: &dyn VM) -> (Addrs, ChainEpoch) {
     verifreg_add_verifier(v, &verifier, StoragePower::from((32_u64 << 40) as u128));
     let add_client_params = AddVerifiedClientParams {
         address: verified_client,
-        allowance: StoragePower::from(1_u64 << 32),
+        allowance: StoragePower::from(1_u64 << 32).try_into().unwrap(),
     };
     apply_ok(
         v,
@@ -463,6 +465,7 @@ pub fn psd_bad_sig_test(v: &dyn VM) {
         storage_price_per_epoch: price_per_epoch,
         provider_collateral,
         client_collateral,
+        payment_token: None,
     };
 
     let invalid_sig_bytes = "very_invalid_sig".as_bytes().to_vec();
@@ -540,6 +543,61 @@ pub fn all_deals_are_good_test(v: &dyn VM) {
     assert_invariants(v, &Policy::default(), None)
 }
 
+#[vm_test]
+pub fn psd_batch_authenticates_per_client_test(v: &dyn VM) {
+    let (a, deal_start) = setup(v);
+    let opts = DealOptions { deal_start, ..DealOptions::default() };
+    let mut batcher = DealBatcher::new(v, opts);
+
+    batcher.stage(a.client1, a.maddr);
+    batcher.stage(a.client2, a.maddr);
+    batcher.stage(a.client1, a.maddr);
+    batcher.stage(a.client1, a.maddr);
+
+    let deal_ret = batcher.publish_ok(a.worker);
+    assert_eq!(vec![0, 1, 2, 3], bf_all(deal_ret.valid_deals));
+
+    // Signature verification dominates the cost of publishing deals, so the number of calls
+    // made to authenticate them is bounded by the number of distinct clients.
+    // The three deals from client1 are authenticated with a single batch call,
+    // while the single deal from client2 uses the plain method.
+    let publish = v.take_invocations().pop().unwrap();
+    let auth_calls: Vec<(Address, u64)> = publish
+        .subinvocations
+        .iter()
+        .filter(|inv| {
+            inv.method == AccountMethod::AuthenticateMessageExported as u64
+                || inv.method == AccountMethod::BatchAuthenticateMessageExported as u64
+        })
+        .map(|inv| (inv.to, inv.method))
+        .collect();
+    assert_eq!(
+        vec![
+            (a.client1, AccountMethod::BatchAuthenticateMessageExported as u64),
+            (a.client2, AccountMethod::AuthenticateMessageExported as u64),
+        ],
+        auth_calls
+    );
+
+    let batch = publish.subinvocations.iter().find(|inv| inv.to == a.client1).unwrap();
+    let batch_params: BatchAuthenticateMessageParams =
+        batch.params.as_ref().unwrap().deserialize().unwrap();
+    assert_eq!(3, batch_params.messages.len());
+    let batch_ret: BatchAuthenticateMessageReturn =
+        batch.return_value.as_ref().unwrap().deserialize().unwrap();
+    assert!(batch_ret.results.all_ok());
+
+    // Authenticating the three deals in one batch is cheaper than authenticating each alone,
+    // while still paying for every signature.
+    let single = publish.subinvocations.iter().find(|inv| inv.to == a.client2).unwrap();
+    assert!(single.gas_charged > 0);
+    assert!(batch.gas_charged > single.gas_charged);
+    assert!(batch.gas_charged < 3 * single.gas_charged);
+    assert!(publish.gas_charged > batch.gas_charged + single.gas_charged);
+
+    assert_invariants(v, &Policy::default(), None)
+}
+
 #[vm_test]
 pub fn psd_valid_deals_with_ones_longer_than_540_test(v: &dyn VM) {
     let (a, deal_start) = setup(v);
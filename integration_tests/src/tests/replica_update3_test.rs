@@ -29,7 +29,7 @@ use fil_actors_runtime::{
     EPOCHS_IN_DAY, EPOCHS_IN_YEAR, STORAGE_MARKET_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use vm_api::VM;
-use vm_api::trace::{EmittedEvent, ExpectInvocation};
+use vm_api::trace::ExpectInvocation;
 use vm_api::util::apply_ok;
 
 use crate::deals::{DealBatcher, DealOptions};
@@ -37,7 +37,7 @@ use crate::expects::Expect;
 use crate::util::{
     PrecommitMetadata, advance_by_deadline_to_epoch, advance_by_deadline_to_index,
     advance_to_proving_deadline, create_accounts, create_miner, datacap_create_allocations,
-    market_add_balance, market_list_deals, market_list_sectors_deals,
+    market_add_balance, market_list_deals, market_list_sectors_deals, miner_pledge_collateral,
     override_compute_unsealed_sector_cid, precommit_sectors_v2, sector_info, submit_windowed_post,
     verifreg_add_client, verifreg_add_verifier, verifreg_list_claims,
 };
@@ -189,6 +189,7 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
                 size: full_piece_size,
                 verified_allocation_key: None,
                 notify: vec![],
+                verified_allocation_parts: vec![],
             }],
             new_sealed_cid: make_sealed_cid(b"s1"),
         },
@@ -208,6 +209,7 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
                         id: alloc_ids_s2[i],
                     }),
                     notify: vec![],
+                    verified_allocation_parts: vec![],
                 })
                 .collect(),
             new_sealed_cid: make_sealed_cid(b"s2"),
@@ -225,6 +227,7 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
                     address: STORAGE_MARKET_ACTOR_ADDR,
                     payload: serialize(&deal_ids_s3[0], "dealid").unwrap(),
                 }],
+                verified_allocation_parts: vec![],
             }],
             new_sealed_cid: make_sealed_cid(b"s3"),
         },
@@ -244,6 +247,7 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
                     address: STORAGE_MARKET_ACTOR_ADDR,
                     payload: serialize(&deal_ids_s4[0], "deal id").unwrap(),
                 }],
+                verified_allocation_parts: vec![],
             }],
             new_sealed_cid: make_sealed_cid(b"s4"),
         },
@@ -298,6 +302,7 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
         require_activation_success: true,
         require_notification_success: true,
     };
+    let pledge_before = miner_pledge_collateral(v, &maddr);
     apply_ok(
         v,
         &worker,
@@ -306,6 +311,7 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
         MinerMethod::ProveReplicaUpdates3 as u64,
         Some(params.clone()),
     );
+    let pledge_delta = miner_pledge_collateral(v, &maddr) - pledge_before;
     let expected_power = StoragePower::from(
         manifests
             .iter()
@@ -314,29 +320,27 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
             .sum::<u64>(),
     );
 
-    let events: Vec<EmittedEvent> = manifests
-        .iter()
-        .map(|m| {
-            let pieces: Vec<(Cid, u64)> = m.pieces.iter().map(|p| (p.cid, p.size.0)).collect();
+    let mut events = vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta)];
+    events.extend(manifests.iter().map(|m| {
+        let pieces: Vec<(Cid, u64)> = m.pieces.iter().map(|p| (p.cid, p.size.0)).collect();
 
-            let pis: Vec<PieceInfo> =
-                m.pieces.iter().map(|p| PieceInfo { cid: p.cid, size: p.size }).collect();
+        let pis: Vec<PieceInfo> =
+            m.pieces.iter().map(|p| PieceInfo { cid: p.cid, size: p.size }).collect();
 
-            let unsealed_cid: Option<Cid> = if pis.is_empty() {
-                None
-            } else {
-                Some(v.primitives().compute_unsealed_sector_cid(seal_proof, &pis).unwrap())
-            };
+        let unsealed_cid: Option<Cid> = if pis.is_empty() {
+            None
+        } else {
+            Some(v.primitives().compute_unsealed_sector_cid(seal_proof, &pis).unwrap())
+        };
 
-            Expect::build_sector_activation_event(
-                "sector-updated",
-                miner_id,
-                m.sector,
-                unsealed_cid,
-                &pieces,
-            )
-        })
-        .collect();
+        Expect::build_sector_activation_event(
+            "sector-updated",
+            miner_id,
+            m.sector,
+            unsealed_cid,
+            &pieces,
+        )
+    }));
 
     ExpectInvocation {
         from: worker_id,
@@ -419,20 +423,24 @@ pub fn prove_replica_update2_test(v: &dyn VM) {
                 ),
                 value: Some(TokenAmount::zero()),
                 subinvocs: Some(vec![]),
-                events: Some(
-                    deal_ids_s3
-                        .iter()
-                        .chain(deal_ids_s4.iter())
-                        .map(|deal_id| {
-                            Expect::build_market_event(
-                                "deal-activated",
-                                *deal_id,
-                                client_id,
-                                miner_id,
-                            )
-                        })
-                        .collect::<Vec<_>>(),
-                ),
+                events: Some(vec![
+                    Expect::build_deal_activated_event(
+                        deal_ids_s3[0],
+                        client_id,
+                        miner_id,
+                        first_sector_number + 3,
+                        &make_piece_cid(b"s3p1"),
+                        false,
+                    ),
+                    Expect::build_deal_activated_event(
+                        deal_ids_s4[0],
+                        client_id,
+                        miner_id,
+                        first_sector_number + 4,
+                        &make_piece_cid(b"s4p1"),
+                        true,
+                    ),
+                ]),
 
                 ..Default::default()
             },
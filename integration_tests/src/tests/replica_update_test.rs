@@ -37,9 +37,9 @@ use crate::util::{
     assert_invariants, bf_all, check_sector_active, check_sector_faulty, create_accounts,
     create_miner, cron_tick, deadline_state, declare_recovery, expect_invariants, get_deal_weights,
     get_network_stats, invariant_failure_patterns, make_bitfield, market_publish_deal,
-    miner_balance, miner_power, miner_prove_sector, override_compute_unsealed_sector_cid,
-    precommit_sectors_v2, prove_commit_sectors, sector_info, submit_invalid_post,
-    submit_windowed_post, verifreg_add_client, verifreg_add_verifier,
+    miner_balance, miner_pledge_collateral, miner_power, miner_prove_sector,
+    override_compute_unsealed_sector_cid, precommit_sectors_v2, prove_commit_sectors, sector_info,
+    submit_invalid_post, submit_windowed_post, verifreg_add_client, verifreg_add_verifier,
 };
 
 #[vm_test]
@@ -964,6 +964,7 @@ pub fn replica_update_verified_deal_test(v: &dyn VM) {
         update_proof_type: fvm_shared::sector::RegisteredUpdateProof::StackedDRG32GiBV1,
         replica_proof: vec![].into(),
     };
+    let pledge_before = miner_pledge_collateral(v, &maddr);
     let updated_sectors: BitField = apply_ok(
         v,
         &worker,
@@ -975,6 +976,7 @@ pub fn replica_update_verified_deal_test(v: &dyn VM) {
     .deserialize()
     .unwrap();
     assert_eq!(vec![100], bf_all(updated_sectors));
+    let pledge_delta = miner_pledge_collateral(v, &maddr) - pledge_before;
 
     let claim_id = 1_u64;
     let deal_term = proposal.end_epoch - proposal.start_epoch;
@@ -1006,7 +1008,7 @@ pub fn replica_update_verified_deal_test(v: &dyn VM) {
         subinvocs: Some(vec![
             Expect::market_activate_deals(
                 miner_id,
-                deal_ids.clone(),
+                vec![(deal_ids[0], proposal.piece_cid, proposal.verified_deal)],
                 client.id().unwrap(),
                 sector_number,
                 old_sector_info.expiration,
@@ -1029,13 +1031,16 @@ pub fn replica_update_verified_deal_test(v: &dyn VM) {
                 PowerPair { raw: StoragePower::zero(), qa: 9 * old_power.qa },
             ),
         ]),
-        events: Some(vec![Expect::build_sector_activation_event(
-            "sector-updated",
-            miner_id,
-            sector_number,
-            Some(unsealed_cid),
-            &pieces,
-        )]),
+        events: Some(vec![
+            Expect::build_sector_activation_event(
+                "sector-updated",
+                miner_id,
+                sector_number,
+                Some(unsealed_cid),
+                &pieces,
+            ),
+            Expect::build_miner_pledge_event(miner_id, &pledge_delta),
+        ]),
         ..Default::default()
     }
     .matches(v.take_invocations().last().unwrap());
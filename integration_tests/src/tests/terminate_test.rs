@@ -33,7 +33,7 @@ use crate::util::{
     advance_by_deadline_to_epoch, advance_by_deadline_to_epoch_while_proving,
     advance_to_proving_deadline, assert_invariants, create_accounts, create_miner, cron_tick,
     deal_cid_for_testing, make_bitfield, make_piece_manifests_from_deal_ids, market_publish_deal,
-    miner_balance, miner_precommit_one_sector_v2, miner_prove_sector,
+    miner_balance, miner_pledge_collateral, miner_precommit_one_sector_v2, miner_prove_sector,
     precommit_meta_data_from_deals, submit_windowed_post, verifreg_add_verifier,
 };
 
@@ -64,7 +64,7 @@ pub fn terminate_sectors_test(v: &dyn VM) {
 
     let add_client_params = VerifierParams {
         address: verified_client,
-        allowance: StoragePower::from_i64(32 << 40_i64).unwrap(),
+        allowance: StoragePower::from_i64(32 << 40_i64).unwrap().try_into().unwrap(),
     };
     apply_ok(
         v,
@@ -222,6 +222,7 @@ pub fn terminate_sectors_test(v: &dyn VM) {
     ];
 
     // Terminate Sector
+    let pledge_before = miner_pledge_collateral(v, &miner_id_addr);
     apply_ok(
         v,
         &worker,
@@ -236,6 +237,7 @@ pub fn terminate_sectors_test(v: &dyn VM) {
             }],
         }),
     );
+    let pledge_delta = miner_pledge_collateral(v, &miner_id_addr) - pledge_before;
     ExpectInvocation {
         from: worker_id,
         to: miner_id_addr,
@@ -253,7 +255,7 @@ pub fn terminate_sectors_test(v: &dyn VM) {
             ),
             Expect::power_update_claim(miner_id, sector_power.neg()),
         ]),
-        events: Some(vec![expect_event]),
+        events: Some(vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta), expect_event]),
         ..Default::default()
     }
     .matches(v.take_invocations().last().unwrap());
@@ -292,6 +294,7 @@ pub fn terminate_sectors_test(v: &dyn VM) {
         Some(WithdrawBalanceParams {
             provider_or_client: verified_client,
             amount: withdrawal.clone(),
+            beneficiary: None,
         }),
     );
     ExpectInvocation {
@@ -313,7 +316,11 @@ pub fn terminate_sectors_test(v: &dyn VM) {
         &STORAGE_MARKET_ACTOR_ADDR,
         &TokenAmount::zero(),
         MarketMethod::WithdrawBalance as u64,
-        Some(WithdrawBalanceParams { provider_or_client: miner_id_addr, amount: miner_collateral }),
+        Some(WithdrawBalanceParams {
+            provider_or_client: miner_id_addr,
+            amount: miner_collateral,
+            beneficiary: None,
+        }),
     );
 
     let value_withdrawn = v.take_invocations().last().unwrap().subinvocations[1].value.clone();
@@ -0,0 +1,109 @@
+use fil_actor_datacap::Method as DataCapMethod;
+use fil_actor_verifreg::{AddVerifiedClientParams, Method as VerifregMethod};
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{DATACAP_TOKEN_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR};
+use fvm_shared::METHOD_SEND;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::StoragePower;
+use num_traits::Zero;
+use vm_api::VM;
+
+use crate::util::{
+    RehearsalMessage, assert_invariants, create_accounts, rehearse_upgrade, run_rehearsal,
+    verifreg_add_verifier,
+};
+
+fn setup(v: &dyn VM) -> Vec<Address> {
+    let addrs = create_accounts(v, 3, &TokenAmount::from_whole(10_000));
+    verifreg_add_verifier(v, &addrs[0], StoragePower::from(4 * 1048576u64));
+    addrs
+}
+
+/// Rehearses an upgrade between two actor bundles by running a sequence of verified registry,
+/// datacap and value transfer messages against `pre` and against `post` after `upgrade` switches
+/// it to another bundle. Both VMs must be freshly constructed with the same singletons and
+/// bundle. The outcomes must match, and a migration which alters balances must be reported as a
+/// difference.
+pub fn upgrade_rehearsal_test(pre: &dyn VM, post: &dyn VM, upgrade: impl FnOnce(&dyn VM)) {
+    let addrs = setup(pre);
+    assert_eq!(addrs, setup(post));
+    let (verifier, client, other) = (addrs[0], addrs[1], addrs[2]);
+
+    let allowance = StoragePower::from(1048576u64);
+    let messages = vec![
+        RehearsalMessage::new(
+            &verifier,
+            &VERIFIED_REGISTRY_ACTOR_ADDR,
+            &TokenAmount::zero(),
+            VerifregMethod::AddVerifiedClientExported as u64,
+            Some(AddVerifiedClientParams {
+                address: client,
+                allowance: allowance.clone().try_into().unwrap(),
+            }),
+        ),
+        // Only verifiers may add clients.
+        RehearsalMessage::new(
+            &other,
+            &VERIFIED_REGISTRY_ACTOR_ADDR,
+            &TokenAmount::zero(),
+            VerifregMethod::AddVerifiedClientExported as u64,
+            Some(AddVerifiedClientParams {
+                address: other,
+                allowance: allowance.try_into().unwrap(),
+            }),
+        ),
+        RehearsalMessage::new(
+            &client,
+            &DATACAP_TOKEN_ACTOR_ADDR,
+            &TokenAmount::zero(),
+            DataCapMethod::BalanceExported as u64,
+            Some(client),
+        ),
+        RehearsalMessage::new(
+            &client,
+            &other,
+            &TokenAmount::from_whole(1),
+            METHOD_SEND,
+            None::<()>,
+        ),
+    ];
+
+    let diffs = rehearse_upgrade(pre, post, upgrade, &messages);
+    assert!(diffs.is_empty(), "upgrade changed behaviour:\n{}", diffs.join("\n"));
+
+    // The runs executed different bundles: each actor's code changed, but not its type.
+    let (pre_manifest, post_manifest) = (pre.actor_manifest(), post.actor_manifest());
+    let post_actors = post.actor_states();
+    for (addr, actor) in pre.actor_states() {
+        let upgraded = &post_actors[&addr];
+        assert_ne!(actor.code, upgraded.code, "actor {} was not upgraded", addr);
+        assert_eq!(pre_manifest.get(&actor.code), post_manifest.get(&upgraded.code));
+    }
+
+    // The rehearsal observed each message's receipt and events.
+    let run = run_rehearsal(pre, &messages[..2]);
+    assert_eq!(ExitCode::OK, run.outcomes[0].code);
+    assert_eq!(2, run.outcomes[0].events.len());
+    assert_eq!(ExitCode::USR_NOT_FOUND, run.outcomes[1].code);
+    assert!(run.outcomes[1].events.is_empty());
+    run_rehearsal(post, &messages[..2]);
+
+    // A migration which moves funds between accounts is reported.
+    let transfer_migration = |v: &dyn VM| {
+        let mut from = v.actor(&client).unwrap();
+        let mut to = v.actor(&other).unwrap();
+        from.balance -= &TokenAmount::from_whole(1);
+        to.balance += &TokenAmount::from_whole(1);
+        v.set_actor(&client, from);
+        v.set_actor(&other, to);
+    };
+    let diffs = rehearse_upgrade(pre, post, transfer_migration, &messages[3..]);
+    assert_eq!(2, diffs.len(), "unexpected differences:\n{}", diffs.join("\n"));
+    assert!(diffs[0].starts_with(&format!("actor {}", client)));
+    assert!(diffs[1].starts_with(&format!("actor {}", other)));
+
+    assert_invariants(pre, &Policy::default(), None);
+    assert_invariants(post, &Policy::default(), None);
+}
@@ -3,6 +3,7 @@ use std::ops::Neg;
 use export_macro::vm_test;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::bigint::Zero;
+use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PaddedPieceSize;
@@ -20,7 +21,6 @@ use fil_actor_miner::{
 use fil_actor_power::State as PowerState;
 use fil_actor_verifreg::{
     Claim, Method as VerifregMethod, RemoveExpiredClaimsParams, RemoveExpiredClaimsReturn,
-    State as VerifregState,
 };
 use fil_actors_runtime::cbor::deserialize;
 use fil_actors_runtime::runtime::Policy;
@@ -45,6 +45,7 @@ use crate::util::{
     miner_precommit_one_sector_v2, miner_prove_sector, precommit_meta_data_from_deals,
     provider_settle_deal_payments, sector_deadline, submit_windowed_post, verifreg_add_client,
     verifreg_add_verifier, verifreg_extend_claim_terms, verifreg_remove_expired_allocations,
+    verifreg_state,
 };
 
 /// Tests a scenario involving a verified deal from the built-in market, with associated
@@ -169,24 +170,25 @@ pub fn verified_claim_scenario_test(v: &dyn VM) {
     );
 
     // Verify claim state
-    let verifreg_state: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let verifreg_state = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     let mut claims = verifreg_state.load_claims(&store).unwrap();
     let claim = claims.get(miner_id.id().unwrap(), claim_id).unwrap().unwrap();
     assert_eq!(sector_number, claim.sector);
-    assert_eq!(
-        &Claim {
-            provider: miner_id.id().unwrap(),
-            client: verified_client.id().unwrap(),
-            data: make_piece_cid("deal1".as_bytes()),
-            size: PaddedPieceSize(deal_size),
-            term_min: deal_term_min,
-            term_max: deal_term_min + 90 * EPOCHS_IN_DAY,
-            term_start: deal_start,
-            sector: sector_number,
-        },
-        claim
-    );
+    let mut expected_claim = Claim {
+        provider: miner_id.id().unwrap(),
+        client: verified_client.id().unwrap(),
+        data: make_piece_cid("deal1".as_bytes()),
+        size: PaddedPieceSize(deal_size),
+        term_min: deal_term_min,
+        term_max: deal_term_min + 90 * EPOCHS_IN_DAY,
+        term_start: deal_start,
+        sector: sector_number,
+        commitment: vec![],
+    };
+    expected_claim.commitment =
+        v.primitives().hash(SupportedHashes::Sha2_256, &expected_claim.commitment_preimage());
+    assert_eq!(&expected_claim, claim);
 
     // Advance to proving period and submit post
     let (deadline_info, partition_index) = advance_to_proving_deadline(v, &miner_id, sector_number);
@@ -455,7 +457,7 @@ pub fn expired_allocations_test(v: &dyn VM) {
 
     // Allocation still exists until explicit cleanup
     let alloc_id = 1;
-    let verifreg_state: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let verifreg_state = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     let mut allocs = verifreg_state.load_allocs(&store).unwrap();
     assert!(allocs.get(verified_client.id().unwrap(), alloc_id).unwrap().is_some());
@@ -470,7 +472,7 @@ pub fn expired_allocations_test(v: &dyn VM) {
     );
 
     // Allocation is gone
-    let verifreg_state: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let verifreg_state = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     let mut allocs = verifreg_state.load_allocs(&store).unwrap();
     assert!(allocs.get(verified_client.id().unwrap(), alloc_id).unwrap().is_none());
@@ -8,7 +8,7 @@ use fil_actor_verifreg::{
     RemoveDataCapReturn, SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP,
 };
 use fil_actor_verifreg::{AddrPairKey, Method as VerifregMethod};
-use fil_actor_verifreg::{RemoveDataCapProposal, RemoveDataCapProposalID, State as VerifregState};
+use fil_actor_verifreg::{RemoveDataCapProposal, RemoveDataCapProposalID};
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
     DATACAP_TOKEN_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
@@ -32,7 +32,8 @@ use vm_api::util::{DynBlockstore, apply_code, apply_ok, get_state};
 use crate::expects::Expect;
 
 use crate::util::{
-    assert_invariants, create_accounts, verifier_balance_event_with_client, verifreg_add_verifier,
+    assert_invariants, create_accounts, datacap_granted_event, verifier_balance_event_with_client,
+    verifreg_add_verifier, verifreg_state,
 };
 use crate::{TEST_VERIFREG_ROOT_ADDR, TEST_VERIFREG_ROOT_ID};
 
@@ -53,11 +54,13 @@ pub fn remove_datacap_simple_successful_path_test(v: &dyn VM) {
     verifreg_add_verifier(v, &verifier2, verifier_allowance.clone());
 
     // register the verified client
-    let add_verified_client_params =
-        AddVerifiedClientParams { address: verified_client, allowance: verifier_allowance.clone() };
+    let add_verified_client_params = AddVerifiedClientParams {
+        address: verified_client,
+        allowance: verifier_allowance.clone().try_into().unwrap(),
+    };
     let mint_params = MintParams {
         to: verified_client,
-        amount: TokenAmount::from_whole(verifier_allowance.to_i64().unwrap()),
+        amount: TokenAmount::from_whole(verifier_allowance.to_i64().unwrap()).try_into().unwrap(),
         operators: vec![STORAGE_MARKET_ACTOR_ADDR],
     };
     apply_ok(
@@ -84,17 +87,25 @@ pub fn remove_datacap_simple_successful_path_test(v: &dyn VM) {
             subinvocs: None,
             ..Default::default()
         }]),
-        events: Some(vec![verifier_balance_event_with_client(
-            verifier1.id().unwrap(),
-            verifier_datacap,
-            verified_client.id().unwrap(),
-        )]),
+        events: Some(vec![
+            verifier_balance_event_with_client(
+                verifier1.id().unwrap(),
+                verifier_datacap,
+                verified_client.id().unwrap(),
+            ),
+            datacap_granted_event(
+                verifier1.id().unwrap(),
+                verified_client.id().unwrap(),
+                &verifier_allowance,
+                &verifier_allowance,
+            ),
+        ]),
         ..Default::default()
     }
     .matches(v.take_invocations().last().unwrap());
 
     // state checks on the 2 verifiers and the client
-    let v_st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let v_st = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     let verifiers =
         make_map_with_root_and_bitwidth::<_, BigIntDe>(&v_st.verifiers, &store, HAMT_BIT_WIDTH)
@@ -160,7 +171,7 @@ pub fn remove_datacap_simple_successful_path_test(v: &dyn VM) {
 
     let mut remove_datacap_params = RemoveDataCapParams {
         verified_client_to_remove: verified_client_id_addr,
-        data_cap_amount_to_remove: allowance_to_remove.clone(),
+        data_cap_amount_to_remove: allowance_to_remove.clone().try_into().unwrap(),
         verifier_request_1: RemoveDataCapRequest {
             verifier: verifier1_id_addr,
             signature: Signature { sig_type: SignatureType::Secp256k1, bytes: verifier1_payload },
@@ -203,7 +214,7 @@ pub fn remove_datacap_simple_successful_path_test(v: &dyn VM) {
         TokenAmount::from_whole(verifier_allowance.sub(&allowance_to_remove).to_i64().unwrap())
     );
 
-    let v_st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let v_st = verifreg_state(v);
     // confirm proposalIds has changed as expected
     let store = DynBlockstore::wrap(v.blockstore());
     proposal_ids = RemoveDataCapProposalMap::load(
@@ -252,7 +263,7 @@ pub fn remove_datacap_simple_successful_path_test(v: &dyn VM) {
 
     remove_datacap_params = RemoveDataCapParams {
         verified_client_to_remove: verified_client_id_addr,
-        data_cap_amount_to_remove: allowance_to_remove.clone(),
+        data_cap_amount_to_remove: allowance_to_remove.clone().try_into().unwrap(),
         verifier_request_1: RemoveDataCapRequest {
             verifier: verifier1_id_addr,
             signature: Signature { sig_type: SignatureType::Secp256k1, bytes: verifier1_payload },
@@ -293,7 +304,7 @@ pub fn remove_datacap_simple_successful_path_test(v: &dyn VM) {
     assert_eq!(balance, TokenAmount::zero());
 
     // confirm proposalIds has changed as expected
-    let v_st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let v_st = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     proposal_ids = RemoveDataCapProposalMap::load(
         &store,
@@ -345,7 +356,7 @@ pub fn remove_datacap_fails_on_verifreg_test(v: &dyn VM) {
 
     let remove_datacap_params = RemoveDataCapParams {
         verified_client_to_remove: VERIFIED_REGISTRY_ACTOR_ADDR,
-        data_cap_amount_to_remove: allowance_to_remove,
+        data_cap_amount_to_remove: allowance_to_remove.try_into().unwrap(),
         verifier_request_1: RemoveDataCapRequest {
             verifier: verifier1_id_addr,
             signature: Signature {
@@ -385,7 +396,7 @@ fn expect_remove_datacap(
         SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP,
         RawBytes::serialize(RemoveDataCapProposal {
             removal_proposal_id: proposal_id1,
-            data_cap_amount: params.data_cap_amount_to_remove.clone(),
+            data_cap_amount: params.data_cap_amount_to_remove.clone().into_inner(),
             verified_client: params.verified_client_to_remove,
         })
         .unwrap()
@@ -396,7 +407,7 @@ fn expect_remove_datacap(
         SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP,
         RawBytes::serialize(RemoveDataCapProposal {
             removal_proposal_id: proposal_id2,
-            data_cap_amount: params.data_cap_amount_to_remove.clone(),
+            data_cap_amount: params.data_cap_amount_to_remove.clone().into_inner(),
             verified_client: params.verified_client_to_remove,
         })
         .unwrap()
@@ -435,7 +446,9 @@ fn expect_remove_datacap(
                         owner: params.verified_client_to_remove,
                         amount: TokenAmount::from_whole(
                             params.data_cap_amount_to_remove.to_i64().unwrap(),
-                        ),
+                        )
+                        .try_into()
+                        .unwrap(),
                     })
                     .unwrap(),
                 ),
@@ -13,7 +13,7 @@ use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::test_utils::make_piece_cid;
 use fil_actors_runtime::{
     MessageAccumulator, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
-    VERIFIED_REGISTRY_ACTOR_ADDR, parse_uint_key, runtime::Policy,
+    VERIFIED_REGISTRY_ACTOR_ADDR, Versioned, parse_uint_key, runtime::Policy,
 };
 use fil_builtin_actors_state::check::check_state_invariants;
 use fvm_ipld_bitfield::BitField;
@@ -33,10 +33,13 @@ use vm_api::{
     VM,
     util::{DynBlockstore, apply_ok, get_state, pk_addrs_from},
 };
+
+pub use rehearsal::*;
 pub use workflows::*;
 
 use crate::{MinerBalances, NetworkStats, TEST_FAUCET_ADDR};
 
+mod rehearsal;
 mod workflows;
 
 const ACCOUNT_SEED: u64 = 93837778;
@@ -101,6 +104,12 @@ pub fn miner_balance(v: &dyn VM, m: &Address) -> MinerBalances {
     }
 }
 
+/// Returns the miner's pledge collateral, i.e. its initial pledge plus locked rewards.
+pub fn miner_pledge_collateral(v: &dyn VM, m: &Address) -> TokenAmount {
+    let st: MinerState = get_state(v, m).unwrap();
+    st.initial_pledge + st.locked_funds
+}
+
 pub fn miner_info(v: &dyn VM, m: &Address) -> MinerInfo {
     let st: MinerState = get_state(v, m).unwrap();
     DynBlockstore::wrap(v.blockstore()).get_cbor(&st.info).unwrap().unwrap()
@@ -226,8 +235,12 @@ pub fn market_list_sectors_deals(
     found
 }
 
+pub fn verifreg_state(v: &dyn VM) -> VerifregState {
+    get_state::<Versioned<VerifregState>>(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap().0
+}
+
 pub fn verifreg_list_claims(v: &dyn VM, provider: ActorID) -> HashMap<ClaimID, Claim> {
-    let st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let st = verifreg_state(v);
     let bs = &DynBlockstore::wrap(v.blockstore());
     let mut claims = st.load_claims(bs).unwrap();
     let mut found: HashMap<ClaimID, Claim> = HashMap::new();
@@ -0,0 +1,166 @@
+//! Upgrade rehearsals run the same message sequence against a network before and after an
+//! actor upgrade, and report any difference in the outcomes observable by users.
+//!
+//! A rehearsal takes two VMs brought to the same state by the caller: one which keeps running
+//! the pre-upgrade bundle of actors, and one to which the upgrade is applied. The upgrade
+//! switches the second VM to the post-upgrade bundle and migrates its state, so the two runs
+//! execute different actor code, and actors are compared by type rather than code CID.
+
+use std::collections::BTreeMap;
+
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::MethodNum;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use vm_api::VM;
+use vm_api::builtin::Type;
+use vm_api::trace::{EmittedEvent, InvocationTrace};
+
+/// A top-level message applied in a rehearsal.
+#[derive(Clone, Debug)]
+pub struct RehearsalMessage {
+    pub from: Address,
+    pub to: Address,
+    pub value: TokenAmount,
+    pub method: MethodNum,
+    pub params: Option<IpldBlock>,
+}
+
+impl RehearsalMessage {
+    pub fn new<S: serde::Serialize>(
+        from: &Address,
+        to: &Address,
+        value: &TokenAmount,
+        method: MethodNum,
+        params: Option<S>,
+    ) -> Self {
+        RehearsalMessage {
+            from: *from,
+            to: *to,
+            value: value.clone(),
+            method,
+            params: params.map(|p| IpldBlock::serialize_cbor(&p).unwrap().unwrap()),
+        }
+    }
+}
+
+/// The observable outcome of a message: its receipt and the events emitted while executing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageOutcome {
+    pub code: ExitCode,
+    pub ret: Option<IpldBlock>,
+    pub events: Vec<EmittedEvent>,
+}
+
+/// A summary of an actor's state which is expected to be preserved by an upgrade.
+/// Actor code and state roots are excluded, as upgrades may change both.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActorSummary {
+    pub typ: Option<Type>,
+    pub sequence: u64,
+    pub balance: TokenAmount,
+}
+
+/// The outcomes of a message sequence, and a summary of the resulting state.
+#[derive(Clone, Debug)]
+pub struct RehearsalRun {
+    pub outcomes: Vec<MessageOutcome>,
+    pub actors: BTreeMap<Address, ActorSummary>,
+}
+
+/// Applies a message sequence, recording the outcome of each message.
+/// Messages are applied regardless of the outcomes of earlier ones.
+pub fn run_rehearsal(v: &dyn VM, messages: &[RehearsalMessage]) -> RehearsalRun {
+    // Discard traces of messages applied before the rehearsal.
+    v.take_invocations();
+    let outcomes = messages
+        .iter()
+        .map(|m| {
+            let res =
+                v.execute_message(&m.from, &m.to, &m.value, m.method, m.params.clone()).unwrap();
+            let mut events = vec![];
+            v.take_invocations().iter().for_each(|trace| collect_events(trace, &mut events));
+            MessageOutcome { code: res.code, ret: res.ret, events }
+        })
+        .collect();
+    RehearsalRun { outcomes, actors: summarize_actors(v) }
+}
+
+/// Migrates `post` and then applies the same message sequence to `pre` and `post`,
+/// returning a description of each difference between the two runs.
+/// The caller is responsible for bringing both VMs to the same state beforehand.
+pub fn rehearse_upgrade(
+    pre: &dyn VM,
+    post: &dyn VM,
+    migrate: impl FnOnce(&dyn VM),
+    messages: &[RehearsalMessage],
+) -> Vec<String> {
+    migrate(post);
+    diff_runs(&run_rehearsal(pre, messages), &run_rehearsal(post, messages))
+}
+
+/// Describes each difference between the outcomes and state summaries of two runs.
+pub fn diff_runs(pre: &RehearsalRun, post: &RehearsalRun) -> Vec<String> {
+    let mut diffs = vec![];
+    for (i, (a, b)) in pre.outcomes.iter().zip(post.outcomes.iter()).enumerate() {
+        if a.code != b.code {
+            diffs.push(format!(
+                "message {}: exit code {} before upgrade, {} after",
+                i, a.code, b.code
+            ));
+        }
+        if a.ret != b.ret {
+            diffs.push(format!(
+                "message {}: return {:?} before upgrade, {:?} after",
+                i, a.ret, b.ret
+            ));
+        }
+        if a.events != b.events {
+            diffs.push(format!(
+                "message {}: events {:?} before upgrade, {:?} after",
+                i, a.events, b.events
+            ));
+        }
+    }
+    if pre.outcomes.len() != post.outcomes.len() {
+        diffs.push(format!(
+            "{} outcomes before upgrade, {} after",
+            pre.outcomes.len(),
+            post.outcomes.len()
+        ));
+    }
+    for (addr, a) in pre.actors.iter() {
+        match post.actors.get(addr) {
+            None => diffs.push(format!("actor {}: missing after upgrade", addr)),
+            Some(b) if a != b => {
+                diffs.push(format!("actor {}: {:?} before upgrade, {:?} after", addr, a, b))
+            }
+            Some(_) => {}
+        }
+    }
+    for addr in post.actors.keys().filter(|addr| !pre.actors.contains_key(addr)) {
+        diffs.push(format!("actor {}: only present after upgrade", addr));
+    }
+    diffs
+}
+
+fn summarize_actors(v: &dyn VM) -> BTreeMap<Address, ActorSummary> {
+    let manifest = v.actor_manifest();
+    v.actor_states()
+        .into_iter()
+        .map(|(addr, actor)| {
+            let summary = ActorSummary {
+                typ: manifest.get(&actor.code).copied(),
+                sequence: actor.sequence,
+                balance: actor.balance,
+            };
+            (addr, summary)
+        })
+        .collect()
+}
+
+fn collect_events(trace: &InvocationTrace, events: &mut Vec<EmittedEvent>) {
+    events.extend(trace.events.iter().cloned());
+    trace.subinvocations.iter().for_each(|sub| collect_events(sub, events));
+}
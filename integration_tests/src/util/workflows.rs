@@ -53,8 +53,7 @@ use fil_actor_verifreg::ClaimExtensionRequest;
 use fil_actor_verifreg::ext::datacap::MintParams;
 use fil_actor_verifreg::{
     AddVerifiedClientParams, AllocationID, ClaimID, ClaimTerm, ExtendClaimTermsParams,
-    Method as VerifregMethod, RemoveExpiredAllocationsParams, State as VerifregState,
-    VerifierParams,
+    Method as VerifregMethod, RemoveExpiredAllocationsParams, VerifierParams,
 };
 use fil_actor_verifreg::{AllocationRequest, DataCap};
 use fil_actor_verifreg::{AllocationRequests, state};
@@ -88,7 +87,9 @@ use crate::*;
 use super::make_bitfield;
 use super::market_pending_deal_allocations_raw;
 use super::miner_dline_info;
+use super::miner_pledge_collateral;
 use super::sector_deadline;
+use super::verifreg_state;
 
 pub fn cron_tick(v: &dyn VM) {
     apply_ok_implicit(
@@ -387,6 +388,7 @@ pub fn prove_commit_sectors(
         let prove_commit_aggregate_params_ser =
             IpldBlock::serialize_cbor(&prove_commit_aggregate_params).unwrap();
 
+        let pledge_before = miner_pledge_collateral(v, maddr);
         apply_ok(
             v,
             worker,
@@ -395,28 +397,27 @@ pub fn prove_commit_sectors(
             MinerMethod::ProveCommitAggregate as u64,
             Some(prove_commit_aggregate_params),
         );
+        let pledge_delta = miner_pledge_collateral(v, maddr) - pledge_before;
 
         let st: MarketState = get_state(v, &STORAGE_MARKET_ACTOR_ADDR).unwrap();
         let store = DynBlockstore::wrap(v.blockstore());
-        let events: Vec<EmittedEvent> = to_prove
-            .iter()
-            .map(|ps| {
-                let mut pieces: Vec<(Cid, u64)> = vec![];
-                for deal_id in &ps.info.deal_ids {
-                    let proposal = st.get_proposal(&store, *deal_id).unwrap();
-                    pieces.push((proposal.piece_cid, proposal.piece_size.0));
-                }
-
-                let unsealed_cid = ps.info.unsealed_cid.0;
-                Expect::build_sector_activation_event(
-                    "sector-activated",
-                    miner_id,
-                    ps.info.sector_number,
-                    unsealed_cid,
-                    &pieces,
-                )
-            })
-            .collect();
+        let mut events = vec![Expect::build_miner_pledge_event(miner_id, &pledge_delta)];
+        events.extend(to_prove.iter().map(|ps| {
+            let mut pieces: Vec<(Cid, u64)> = vec![];
+            for deal_id in &ps.info.deal_ids {
+                let proposal = st.get_proposal(&store, *deal_id).unwrap();
+                pieces.push((proposal.piece_cid, proposal.piece_size.0));
+            }
+
+            let unsealed_cid = ps.info.unsealed_cid.0;
+            Expect::build_sector_activation_event(
+                "sector-activated",
+                miner_id,
+                ps.info.sector_number,
+                unsealed_cid,
+                &pieces,
+            )
+        }));
 
         ExpectInvocation {
             from: worker_id,
@@ -621,6 +622,7 @@ pub fn submit_windowed_post(
         }],
         chain_commit_epoch: dline_info.challenge,
         chain_commit_rand: Randomness(TEST_VM_RAND_ARRAY.into()),
+        recover_faults: false,
     };
     apply_ok(
         v,
@@ -737,6 +739,7 @@ pub fn submit_invalid_post(
         }],
         chain_commit_epoch: dline_info.challenge,
         chain_commit_rand: Randomness(TEST_VM_RAND_ARRAY.into()),
+        recover_faults: false,
     };
     apply_ok(
         v,
@@ -777,14 +780,35 @@ pub fn verifier_balance_event_with_client(
     }
 }
 
+pub fn datacap_granted_event(
+    verifier: ActorID,
+    client: ActorID,
+    amount: &DataCap,
+    total: &DataCap,
+) -> EmittedEvent {
+    EmittedEvent {
+        emitter: VERIFIED_REGISTRY_ACTOR_ID,
+        event: EventBuilder::new()
+            .typ("datacap-granted")
+            .field_indexed("verifier", &verifier)
+            .field_indexed("client", &client)
+            .field("amount", &BigIntSer(amount))
+            .field("total", &BigIntSer(total))
+            .build()
+            .unwrap(),
+    }
+}
+
 pub fn verifreg_add_verifier(v: &dyn VM, verifier: &Address, data_cap: StoragePower) {
-    let add_verifier_params = VerifierParams { address: *verifier, allowance: data_cap.clone() };
+    let add_verifier_params =
+        VerifierParams { address: *verifier, allowance: data_cap.clone().try_into().unwrap() };
     // root address is msig, send proposal from root key
     let proposal = ProposeParams {
         to: VERIFIED_REGISTRY_ACTOR_ADDR,
         value: TokenAmount::zero(),
         method: VerifregMethod::AddVerifier as u64,
         params: serialize(&add_verifier_params, "verifreg add verifier params").unwrap(),
+        expiration: None,
     };
 
     apply_ok(
@@ -823,16 +847,20 @@ pub fn verifreg_add_client(
     client: &Address,
     allowance: StoragePower,
 ) {
-    let v_st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let v_st = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
 
     let verifier_cap = v_st.get_verifier_cap(&store, verifier).unwrap().unwrap();
+    let granted = v_st.load_verifier_grants(&store).unwrap().get(verifier).unwrap().cloned();
+    let total_granted = granted.map(|g| g.0).unwrap_or_default() + &allowance;
 
     let updated_verifier_balance = verifier_cap - allowance.clone();
 
     let verifier_id = v.resolve_id_address(verifier).unwrap().id().unwrap();
-    let add_client_params =
-        AddVerifiedClientParams { address: *client, allowance: allowance.clone() };
+    let add_client_params = AddVerifiedClientParams {
+        address: *client,
+        allowance: allowance.clone().try_into().unwrap(),
+    };
     apply_ok(
         v,
         verifier,
@@ -853,7 +881,7 @@ pub fn verifreg_add_client(
             params: Some(
                 IpldBlock::serialize_cbor(&MintParams {
                     to: *client,
-                    amount: allowance_tokens.clone(),
+                    amount: allowance_tokens.clone().try_into().unwrap(),
                     operators: vec![STORAGE_MARKET_ACTOR_ADDR],
                 })
                 .unwrap(),
@@ -869,11 +897,19 @@ pub fn verifreg_add_client(
             )]),
             ..Default::default()
         }]),
-        events: Some(vec![verifier_balance_event_with_client(
-            verifier.id().unwrap(),
-            updated_verifier_balance,
-            client.id().unwrap(),
-        )]),
+        events: Some(vec![
+            verifier_balance_event_with_client(
+                verifier.id().unwrap(),
+                updated_verifier_balance,
+                client.id().unwrap(),
+            ),
+            datacap_granted_event(
+                verifier.id().unwrap(),
+                client.id().unwrap(),
+                &allowance,
+                &total_granted,
+            ),
+        ]),
         ..Default::default()
     }
     .matches(v.take_invocations().last().unwrap());
@@ -911,7 +947,7 @@ pub fn verifreg_remove_expired_allocations(
     datacap_refund: u64,
     expected_expirations: Vec<AllocationID>,
 ) {
-    let v_st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let v_st = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     let mut allocs = v_st.load_allocs(&store).unwrap();
     let expected_events: Vec<EmittedEvent> = expected_expirations
@@ -1055,7 +1091,7 @@ pub fn datacap_extend_claim(
     new_term: ChainEpoch,
 ) {
     // read existing claim with claim id from VerifReg state
-    let v_st: fil_actor_verifreg::State = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let v_st = verifreg_state(v);
     let store = DynBlockstore::wrap(v.blockstore());
     let mut claims = v_st.load_claims(&store).unwrap();
     let mut existing_claim =
@@ -1153,6 +1189,7 @@ pub fn market_publish_deal(
         storage_price_per_epoch: TokenAmount::from_atto((1 << 20) as u64),
         provider_collateral: TokenAmount::from_whole(2),
         client_collateral: TokenAmount::from_whole(1),
+        payment_token: None,
     };
 
     let signature = Signature {
@@ -1219,7 +1256,7 @@ pub fn market_publish_deal(
             extensions: vec![],
         };
 
-        let v_st: fil_actor_verifreg::State = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+        let v_st = verifreg_state(v);
         let alloc_id = v_st.next_allocation_id - 1;
         let alloc_req = alloc_reqs.allocations[0].clone();
         let alloc_event = Expect::build_verifreg_allocation_event(
@@ -1322,6 +1359,7 @@ pub fn generate_deal_proposal(
         storage_price_per_epoch,
         provider_collateral: provider_collateral.clone(),
         client_collateral: client_collateral.clone(),
+        payment_token: None,
     }
 }
 
@@ -1369,6 +1407,7 @@ pub fn make_piece_manifests_from_deal_ids(
                 address: STORAGE_MARKET_ACTOR_ADDR,
                 payload: serialize(&deal_id, "dealid").unwrap(),
             }],
+            verified_allocation_parts: vec![],
         });
     }
     piece_manifests
@@ -13,7 +13,18 @@ static NETWORKS: &[(&str, &[&str])] = &[
     ),
     ("butterflynet", &["sector-512m", "sector-32g", "sector-64g", "min-power-2g"]),
     ("calibrationnet", &["sector-32g", "sector-64g", "min-power-32g"]),
-    ("devnet", &["sector-2k", "sector-8m", "small-deals", "short-precommit", "min-power-2k"]),
+    (
+        "devnet",
+        &[
+            "sector-2k",
+            "sector-8m",
+            "small-deals",
+            "short-precommit",
+            "min-power-2k",
+            "experimental-seal-proofs",
+            "deal-label-index",
+        ],
+    ),
     (
         "testing",
         &[
@@ -26,6 +37,8 @@ static NETWORKS: &[(&str, &[&str])] = &[
             "short-precommit",
             "min-power-2k",
             "no-provider-deal-collateral",
+            "experimental-seal-proofs",
+            "deal-label-index",
         ],
     ),
     (
@@ -40,6 +53,8 @@ static NETWORKS: &[(&str, &[&str])] = &[
             "short-precommit",
             "min-power-2k",
             "no-provider-deal-collateral",
+            "experimental-seal-proofs",
+            "deal-label-index",
             "fake-proofs",
         ],
     ),
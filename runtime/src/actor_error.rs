@@ -88,6 +88,11 @@ impl ActorError {
         &self.msg
     }
 
+    /// Returns the optional associated data.
+    pub fn data(&self) -> Option<&IpldBlock> {
+        self.data.as_ref()
+    }
+
     /// Extracts the optional associated data without copying.
     pub fn take_data(&mut self) -> Option<IpldBlock> {
         std::mem::take(&mut self.data)
@@ -0,0 +1,26 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Parameter types for datacap token actor methods invoked by other builtin actors.
+//! These are defined here so that the datacap actor and its callers share a single definition.
+
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
+
+use crate::NonNegativeTokenAmount;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MintParams {
+    // Recipient of the newly minted tokens.
+    pub to: Address,
+    // Amount of tokens to mint.
+    pub amount: NonNegativeTokenAmount,
+    // Addresses to be granted effectively-infinite operator allowance for the recipient.
+    pub operators: Vec<Address>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DestroyParams {
+    pub owner: Address,
+    pub amount: NonNegativeTokenAmount,
+}
@@ -0,0 +1,351 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Reverse lookup of builtin actor method numbers to method names, for rendering
+//! human-readable names from on-chain messages.
+//! Exported methods are named by their FRC-42 name, from which their number is derived.
+//! These tables must be kept in sync with each actor's `Method` enum.
+
+use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND, MethodNum};
+use num_traits::FromPrimitive;
+
+use crate::runtime::builtins::Type;
+
+/// Builds a method table from explicitly numbered methods followed by exported methods,
+/// the latter numbered by the FRC-42 hash of their name.
+macro_rules! method_table {
+    ($($num:expr => $name:tt),* ; $($exported:tt,)*) => {
+        &[$(($num, $name),)* $((frc42_dispatch::method_hash!($exported), $exported),)*]
+    };
+}
+
+type MethodTable = &'static [(MethodNum, &'static str)];
+
+const SYSTEM_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor";
+    "GetMethodNames",
+);
+
+const INIT_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "Exec",
+    3 => "Exec4";
+    "ExecPaymentChannel",
+    "GetRobustAddress",
+    "ExecBatch",
+);
+
+const CRON_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "EpochTick",
+    3 => "SetEntries";
+);
+
+const ACCOUNT_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "PubkeyAddress";
+    "AuthenticateMessage",
+    "BatchAuthenticateMessage",
+);
+
+const POWER_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "CreateMiner",
+    3 => "UpdateClaimedPower",
+    4 => "EnrollCronEvent",
+    5 => "OnEpochTickEnd",
+    6 => "UpdatePledgeTotal",
+    9 => "CurrentTotalPower";
+    "CreateMiner",
+    "NetworkRawPower",
+    "MinerRawPower",
+    "MinerCount",
+    "MinerConsensusCount",
+    "MinerPower",
+    "MinerClaimProof",
+    "BatchGetMinerInfo",
+    "ConsensusMinPower",
+    "ListMiners",
+    "MinerCounts",
+    "GetMinerPowerBatch",
+    "GetPowerHistory",
+    "CreateMiners",
+    "GetCronQueueStats",
+    "NotifyEpochReward",
+    "RegisterExperimentalSealProofs",
+    "GetExperimentalSealProofs",
+);
+
+const MINER_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "ControlAddresses",
+    3 => "ChangeWorkerAddress",
+    4 => "ChangePeerID",
+    5 => "SubmitWindowedPoSt",
+    8 => "ExtendSectorExpiration",
+    9 => "TerminateSectors",
+    10 => "DeclareFaults",
+    11 => "DeclareFaultsRecovered",
+    12 => "OnDeferredCronEvent",
+    13 => "CheckSectorProven",
+    14 => "ApplyRewards",
+    15 => "ReportConsensusFault",
+    16 => "WithdrawBalance",
+    17 => "InternalSectorSetupForPreseal",
+    18 => "ChangeMultiaddrs",
+    19 => "CompactPartitions",
+    20 => "CompactSectorNumbers",
+    21 => "ConfirmChangeWorkerAddress",
+    22 => "RepayDebt",
+    23 => "ChangeOwnerAddress",
+    24 => "DisputeWindowedPoSt",
+    26 => "ProveCommitAggregate",
+    27 => "ProveReplicaUpdates",
+    28 => "PreCommitSectorBatch2",
+    30 => "ChangeBeneficiary",
+    31 => "GetBeneficiary",
+    32 => "ExtendSectorExpiration2",
+    34 => "ProveCommitSectors3",
+    35 => "ProveReplicaUpdates3",
+    36 => "ProveCommitSectorsNI";
+    "ChangeWorkerAddress",
+    "ChangePeerID",
+    "WithdrawBalance",
+    "ChangeMultiaddrs",
+    "ConfirmChangeWorkerAddress",
+    "RepayDebt",
+    "ChangeOwnerAddress",
+    "ChangeBeneficiary",
+    "GetBeneficiary",
+    "GetOwner",
+    "IsControllingAddress",
+    "GetSectorSize",
+    "GetAvailableBalance",
+    "GetVestingFunds",
+    "GetPeerID",
+    "GetMultiaddrs",
+    "MaxTerminationFee",
+    "InitialPledge",
+    "GetDeadlineTiming",
+    "OnClaimRevoked",
+    "GetPendingBeneficiaryChange",
+    "GetSectors",
+    "GetDeadlineSummaries",
+    "GetPartitionSummaries",
+    "ExtendSectorExpirationsByClass",
+    "AuthorizePledgeLender",
+    "DepositPledgeFacility",
+    "WithdrawPledgeFacility",
+    "GetPledgeFacility",
+    "GetPendingWorkerKeyChange",
+    "CancelChangeWorkerAddress",
+    "EstimateTerminationFee",
+    "GetSectorEconomics",
+    "GetPartitionPoStStats",
+    "SetFaultTerminationThreshold",
+    "SimulatePenalty",
+    "DeclareFaults2",
+    "DeclareFaultsRecovered2",
+    "GetCompactablePartitions",
+    "ChangeWorkerAddress2",
+    "SetSectorMetadata",
+    "GetSectorMetadata",
+    "GetPowerBreakdown",
+    "GetAvailableBalanceBreakdown",
+);
+
+const MARKET_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "AddBalance",
+    3 => "WithdrawBalance",
+    4 => "PublishStorageDeals",
+    5 => "VerifyDealsForActivation",
+    6 => "BatchActivateDeals",
+    7 => "OnMinerSectorsTerminate",
+    9 => "CronTick";
+    "AddBalance",
+    "WithdrawBalance",
+    "PublishStorageDeals",
+    "GetBalance",
+    "GetDealDataCommitment",
+    "GetDealClient",
+    "GetDealProvider",
+    "GetDealLabel",
+    "GetDealTerm",
+    "GetDealTotalPrice",
+    "GetDealClientCollateral",
+    "GetDealProviderCollateral",
+    "GetDealVerified",
+    "GetDealActivation",
+    "GetDealSector",
+    "SettleDealPayments",
+    "GetDealsForPiece",
+    "GetDealsForLabel",
+    "BatchGetDealActivation",
+    "BatchGetDealTerm",
+    "BatchGetDealParties",
+    "BatchGetDealVerified",
+    "ListProviderDeals",
+    "CancelStorageDeals",
+    "SettleDealPaymentsPreview",
+    "DepositCollateralPool",
+    "WithdrawCollateralPool",
+    "SetDealAcceptancePolicy",
+    "GetDealAcceptancePolicy",
+    "ListProviderPendingDeals",
+    "SectorContentChanged",
+    "MutuallyCancelDeals",
+    "SetClientWithdrawalWatch",
+    "Receive",
+    "WithdrawTokenBalance",
+    "GetTokenBalance",
+    "PublishStorageDeals2",
+    "GetDealSectorLocation",
+);
+
+const PAYCH_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "UpdateChannelState",
+    3 => "Settle",
+    4 => "Collect";
+    "PruneLanes",
+    "CloseLanes",
+    "UpdateChannelStateBatch",
+    "GetLaneStates",
+    "SetWatchtower",
+);
+
+const MULTISIG_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "Propose",
+    3 => "Approve",
+    4 => "Cancel",
+    5 => "AddSigner",
+    6 => "RemoveSigner",
+    7 => "SwapSigner",
+    8 => "ChangeNumApprovalsThreshold",
+    9 => "LockBalance";
+    "Receive",
+    "ProposalHash",
+    "PruneExpired",
+    "ApproveBatch",
+    "SetSpendingLimit",
+    "ListPendingTransactions",
+    "SwapSignerAtomic",
+);
+
+const REWARD_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "AwardBlockReward",
+    3 => "ThisEpochReward",
+    4 => "UpdateNetworkKPI";
+    "GetRewardEstimate",
+);
+
+const VERIFREG_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "AddVerifier",
+    3 => "RemoveVerifier",
+    4 => "AddVerifiedClient",
+    7 => "RemoveVerifiedClientDataCap",
+    8 => "RemoveExpiredAllocations",
+    9 => "ClaimAllocations",
+    10 => "GetClaims",
+    11 => "ExtendClaimTerms",
+    12 => "RemoveExpiredClaims";
+    "AddVerifiedClient",
+    "RemoveExpiredAllocations",
+    "GetClaims",
+    "ExtendClaimTerms",
+    "RemoveExpiredClaims",
+    "DisputeClaim",
+    "ResolveClaimDispute",
+    "GetVerifregPolicy",
+    "Receive",
+    "ReleaseAllocations",
+    "GetClaimTotals",
+    "AddObserverVerifier",
+    "RemoveObserverVerifier",
+    "ListObserverVerifiers",
+    "GetProviderHistory",
+    "RemoveExpiredClaimsLimited",
+);
+
+const DATACAP_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor";
+    "Mint",
+    "Destroy",
+    "Name",
+    "Symbol",
+    "Granularity",
+    "TotalSupply",
+    "Balance",
+    "Transfer",
+    "TransferFrom",
+    "IncreaseAllowance",
+    "DecreaseAllowance",
+    "RevokeAllowance",
+    "Burn",
+    "BurnFrom",
+    "Allowance",
+    "GetAllowances",
+);
+
+const EVM_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "Resurrect",
+    3 => "GetBytecode",
+    4 => "GetBytecodeHash",
+    5 => "GetStorageAt",
+    6 => "InvokeContractDelegate";
+    "InvokeEVM",
+);
+
+const EAM_METHODS: MethodTable = method_table!(
+    METHOD_CONSTRUCTOR => "Constructor",
+    2 => "Create",
+    3 => "Create2",
+    4 => "CreateExternal";
+);
+
+const ETHACCOUNT_METHODS: MethodTable = method_table!(METHOD_CONSTRUCTOR => "Constructor";);
+
+/// Returns the numbers and names of the methods of a builtin actor type.
+/// Exported methods are listed by their FRC-42 name, so a name may appear twice: once for
+/// a legacy method number and once for its exported counterpart.
+pub fn method_names(typ: Type) -> &'static [(MethodNum, &'static str)] {
+    match typ {
+        Type::System => SYSTEM_METHODS,
+        Type::Init => INIT_METHODS,
+        Type::Cron => CRON_METHODS,
+        Type::Account => ACCOUNT_METHODS,
+        Type::Power => POWER_METHODS,
+        Type::Miner => MINER_METHODS,
+        Type::Market => MARKET_METHODS,
+        Type::PaymentChannel => PAYCH_METHODS,
+        Type::Multisig => MULTISIG_METHODS,
+        Type::Reward => REWARD_METHODS,
+        Type::VerifiedRegistry => VERIFREG_METHODS,
+        Type::DataCap => DATACAP_METHODS,
+        Type::Placeholder => &[],
+        Type::EVM => EVM_METHODS,
+        Type::EAM => EAM_METHODS,
+        Type::EthAccount => ETHACCOUNT_METHODS,
+    }
+}
+
+/// Returns the name of a builtin actor type's method, if known.
+/// Method zero is a plain value transfer to any actor, named "Send".
+pub fn method_name(typ: Type, method: MethodNum) -> Option<&'static str> {
+    if method == METHOD_SEND {
+        return Some("Send");
+    }
+    method_names(typ).iter().find(|(num, _)| *num == method).map(|(_, name)| *name)
+}
+
+/// Returns the builtin actor type with the given name, as in the system actor's registry.
+pub fn builtin_type_by_name(name: &str) -> Option<Type> {
+    (Type::System as i32..=Type::EthAccount as i32)
+        .filter_map(Type::from_i32)
+        .find(|typ| typ.name() == name)
+}
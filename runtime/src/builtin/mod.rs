@@ -5,7 +5,11 @@ pub use self::network::*;
 pub use self::shared::*;
 pub use self::singletons::*;
 
+pub mod datacap;
+pub mod method_names;
+pub mod miner;
 pub mod network;
+pub mod power;
 pub mod reward;
 pub mod shared;
 pub mod singletons;
@@ -0,0 +1,47 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Parameter and return types for power actor methods invoked by other builtin actors.
+//! These are defined here so that the power actor and its callers share a single definition.
+
+use fvm_ipld_encoding::RawBytes;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::bigint::bigint_ser;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::{RegisteredSealProof, StoragePower};
+
+use crate::reward::FilterEstimate;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct UpdateClaimedPowerParams {
+    #[serde(with = "bigint_ser")]
+    pub raw_byte_delta: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub quality_adjusted_delta: StoragePower,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct EnrollCronEventParams {
+    pub event_epoch: ChainEpoch,
+    pub payload: RawBytes,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CurrentTotalPowerReturn {
+    #[serde(with = "bigint_ser")]
+    pub raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub quality_adj_power: StoragePower,
+    pub pledge_collateral: TokenAmount,
+    pub quality_adj_power_smoothed: FilterEstimate,
+    pub ramp_start_epoch: i64,
+    pub ramp_duration_epochs: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetExperimentalSealProofsReturn {
+    /// Seal proof types registered as experimental, in order of registration.
+    pub proofs: Vec<RegisteredSealProof>,
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::bigint::bigint_ser;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::sector::StoragePower;
 
 pub mod math;
@@ -16,3 +17,12 @@ pub struct ThisEpochRewardReturn {
     #[serde(with = "bigint_ser")]
     pub this_epoch_baseline_power: StoragePower,
 }
+
+/// The reward actor's summary of an epoch's reward, pushed to subscribing builtin actors
+/// each time it is computed, as an alternative to their querying `ThisEpochReward`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct EpochRewardNotification {
+    /// The epoch for which the reward was computed.
+    pub epoch: ChainEpoch,
+    pub reward: ThisEpochRewardReturn,
+}
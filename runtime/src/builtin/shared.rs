@@ -4,8 +4,11 @@
 use crate::runtime::builtins::Type;
 use crate::{ActorContext, ActorError, actor_error};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
 use fvm_shared::METHOD_SEND;
 use fvm_shared::address::Address;
+use fvm_shared::error::ExitCode;
+use fvm_shared::version::NetworkVersion;
 use fvm_shared::{ActorID, MethodNum};
 use std::fmt::{Display, Formatter};
 
@@ -93,6 +96,64 @@ where
     Ok(())
 }
 
+/// Exit code for invoking a method which is deprecated at the current network version,
+/// returned with exit data identifying its replacement.
+/// Any actor may return it, so it lies above the actor-specific codes from
+/// `FIRST_ACTOR_SPECIFIC_EXIT_CODE` that actors define for themselves, and below the
+/// miner's codes from 1000. It is distinct from `USR_UNHANDLED_MESSAGE`, which a method
+/// returns once removed.
+pub const ERR_METHOD_DEPRECATED: ExitCode = ExitCode::new(900);
+
+/// An entry in an actor's table of deprecated methods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MethodDeprecation {
+    /// The deprecated method number.
+    pub method: MethodNum,
+    /// The first network version at which the method is rejected.
+    pub deprecated_at: NetworkVersion,
+    /// The method number to invoke instead.
+    pub replacement: MethodNum,
+}
+
+/// Exit data returned with `ERR_METHOD_DEPRECATED`, pointing callers at the replacement method.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct MethodDeprecated {
+    pub method: MethodNum,
+    pub deprecated_at: u32,
+    pub replacement: MethodNum,
+}
+
+// Checks whether a method has been deprecated at the current network version.
+// Deprecated methods abort with ERR_METHOD_DEPRECATED and data identifying the replacement.
+pub fn restrict_deprecated_methods<RT>(
+    rt: &RT,
+    method: MethodNum,
+    deprecations: &[MethodDeprecation],
+) -> Result<(), ActorError>
+where
+    RT: Runtime,
+{
+    let Some(deprecation) = deprecations.iter().find(|d| d.method == method) else {
+        return Ok(());
+    };
+    if rt.network_version() < deprecation.deprecated_at {
+        return Ok(());
+    }
+    let data = MethodDeprecated {
+        method,
+        deprecated_at: deprecation.deprecated_at.into(),
+        replacement: deprecation.replacement,
+    };
+    Err(ActorError::unchecked_with_data(
+        ERR_METHOD_DEPRECATED,
+        format!(
+            "method {} is deprecated since network version {}, use method {}",
+            method, deprecation.deprecated_at, deprecation.replacement
+        ),
+        IpldBlock::serialize_cbor(&data)?,
+    ))
+}
+
 /// An error returned on a failed send. Can be automatically converted into an [`ActorError`] with
 /// the question-mark operator.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
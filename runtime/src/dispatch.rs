@@ -59,6 +59,7 @@ macro_rules! actor_dispatch {
             RT::Blockstore: Clone,
         {
             $crate::builtin::shared::restrict_internal_api(rt, method)?;
+            $crate::builtin::shared::restrict_deprecated_methods(rt, method, Self::DEPRECATED_METHODS)?;
             match <Self::Methods as num_traits::FromPrimitive>::from_u64(method) {
                 $($(#[$m])*
                   $crate::actor_dispatch!(@pattern $($method)|*) =>
@@ -93,6 +94,7 @@ macro_rules! actor_dispatch_unrestricted {
             RT: $crate::runtime::Runtime,
             RT::Blockstore: Clone,
         {
+            $crate::builtin::shared::restrict_deprecated_methods(rt, method, Self::DEPRECATED_METHODS)?;
             match <Self::Methods as num_traits::FromPrimitive>::from_u64(method) {
                 $($(#[$m])*
                   $crate::actor_dispatch!(@pattern $($method)|*) =>
@@ -5,11 +5,14 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::MethodNum;
 
-use crate::{ActorError, Runtime};
+use crate::{ActorError, MethodDeprecation, Runtime};
 
 /// Interface for invoking methods on an Actor
 pub trait ActorCode {
     type Methods;
+    /// Methods which are rejected from some network version onwards, in favour of a replacement.
+    /// Checked by the dispatcher before invoking any method.
+    const DEPRECATED_METHODS: &'static [MethodDeprecation] = &[];
     /// A name for the actor type, used in debugging.
     fn name() -> &'static str;
     /// Invokes method with runtime on the actor's code. Method number will match one
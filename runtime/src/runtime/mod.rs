@@ -17,7 +17,7 @@ pub use self::actor_code::*;
 pub use self::policy::*;
 pub use self::randomness::DomainSeparationTag;
 use crate::runtime::builtins::Type;
-use crate::{ActorError, SendError, actor_error};
+use crate::{ActorError, SendError, Versioned, VersionedRef, VersionedState, actor_error};
 
 mod actor_code;
 pub mod builtins;
@@ -150,6 +150,19 @@ pub trait Runtime: Primitives + RuntimePolicy {
             .expect("State does not exist for actor state root"))
     }
 
+    /// Initializes the state object, encoded with its schema version.
+    /// This is only valid when the state has not yet been initialized.
+    fn create_versioned<T: VersionedState>(&self, obj: &T) -> Result<(), ActorError> {
+        self.create(&VersionedRef(obj))
+    }
+
+    /// Loads a readonly copy of the versioned state of the receiver,
+    /// upgrading it from the version at which it was stored if necessary.
+    /// The stored state is not rewritten.
+    fn versioned_state<T: VersionedState>(&self) -> Result<T, ActorError> {
+        Ok(self.state::<Versioned<T>>()?.0)
+    }
+
     /// Gets the state-root.
     fn get_state_root(&self) -> Result<Cid, ActorError>;
 
@@ -168,6 +181,17 @@ pub trait Runtime: Primitives + RuntimePolicy {
         S: Serialize + DeserializeOwned,
         F: FnOnce(&mut S, &Self) -> Result<RT, ActorError>;
 
+    /// Like [`Runtime::transaction`], for versioned state.
+    /// State stored at an earlier version is upgraded when loaded, and is put back
+    /// to the store at the current version.
+    fn versioned_transaction<S, RT, F>(&self, f: F) -> Result<RT, ActorError>
+    where
+        S: VersionedState,
+        F: FnOnce(&mut S, &Self) -> Result<RT, ActorError>,
+    {
+        self.transaction(|st: &mut Versioned<S>, rt| f(&mut st.0, rt))
+    }
+
     /// Returns reference to blockstore
     fn store(&self) -> &Self::Blockstore;
 
@@ -1,6 +1,9 @@
+use fvm_shared::ActorID;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, StoragePower};
+use fvm_shared::version::NetworkVersion;
 use num_traits::FromPrimitive;
 use serde::{Deserialize, Serialize};
 
@@ -58,6 +61,9 @@ pub struct Policy {
     /// MaxMultiaddrData is the maximum amount of data that can be stored in multiaddrs.
     pub max_multiaddr_data: usize,
 
+    /// Maximum size of the metadata a miner may attach to each of its sectors.
+    pub max_sector_metadata_size: usize,
+
     /// The maximum number of partitions that may be required to be loaded in a single invocation.
     /// This limits the number of simultaneous fault, recovery, or sector-extension declarations.
     pub addressed_partitions_max: u64,
@@ -142,6 +148,10 @@ pub struct Policy {
     /// daily block reward.
     pub daily_fee_block_reward_cap_denom: i64,
 
+    /// Number of epochs a proposed beneficiary change remains open for approval.
+    #[serde(default = "default_beneficiary_proposal_lifetime")]
+    pub beneficiary_proposal_lifetime: ChainEpoch,
+
     //
     // --- verifreg policy ---
     //
@@ -156,6 +166,9 @@ pub struct Policy {
     pub maximum_verified_allocation_expiration: i64,
     // Period of time at the end of a sector's life during which claims can be dropped
     pub end_of_life_claim_drop_period: ChainEpoch,
+    /// Period after a claim is made during which its client may dispute it (epochs).
+    /// Zero disables claim disputes.
+    pub verified_claim_dispute_window: ChainEpoch,
 
     //
     //  --- market policy ---
@@ -175,11 +188,63 @@ pub struct Policy {
     /// allocation's maximum term.
     pub market_default_allocation_term_buffer: i64,
 
+    /// Minimum (inclusive) duration of a storage deal.
+    pub market_min_deal_duration: ChainEpoch,
+
+    /// Maximum (inclusive) duration of a storage deal.
+    pub market_max_deal_duration: ChainEpoch,
+
+    /// Minimum (inclusive) storage price per epoch of a storage deal.
+    pub market_min_storage_price_per_epoch: TokenAmount,
+
+    /// Whether the market indexes published deals by label, so they can be found by label.
+    /// Only test networks enable this.
+    #[serde(default)]
+    pub market_index_deal_labels: bool,
+
+    /// IDs of the FRC-46 token actors in which deal storage fees may be paid.
+    /// The market accepts deposits of no other tokens. None are accepted on mainnet.
+    #[serde(default)]
+    pub market_payment_tokens: Vec<ActorID>,
+
     //
     // --- power policy ---
     //
     /// Minimum miner consensus power
     pub minimum_consensus_power: StoragePower,
+    /// Whether seal proof types not otherwise accepted may be registered with the power actor
+    /// as experimental, after which miners accept them for new sectors.
+    /// Only test networks enable this.
+    #[serde(default)]
+    pub allow_experimental_seal_proofs: bool,
+    /// The only actor which may register experimental seal proof types, where they are allowed.
+    /// None may register them if this is not set.
+    #[serde(default)]
+    pub experimental_seal_proofs_governor: Option<ActorID>,
+
+    //
+    // --- reward policy ---
+    //
+    /// Q.128 formatted factor by which baseline power grows each epoch.
+    #[serde(default = "default_reward_baseline_exponent")]
+    pub reward_baseline_exponent: StoragePower,
+    /// Baseline power at the first epoch after genesis.
+    #[serde(default = "default_reward_baseline_initial_value")]
+    pub reward_baseline_initial_value: StoragePower,
+    /// Total tokens minted over all time by the simple (exponential decay) reward.
+    #[serde(default = "default_reward_simple_total")]
+    pub reward_simple_total: TokenAmount,
+    /// Total tokens minted over all time by the baseline reward.
+    #[serde(default = "default_reward_baseline_total")]
+    pub reward_baseline_total: TokenAmount,
+
+    //
+    // --- evm policy ---
+    //
+    /// Network version from which value-bearing CALLs forward the transfer stipend in addition
+    /// to the requested gas, as in Ethereum.
+    #[serde(default = "default_evm_ethereum_call_gas_version")]
+    pub evm_ethereum_call_gas_version: NetworkVersion,
 }
 
 impl Default for Policy {
@@ -200,6 +265,7 @@ impl Default for Policy {
             max_control_addresses: policy_constants::MAX_CONTROL_ADDRESSES,
             max_peer_id_length: policy_constants::MAX_PEER_ID_LENGTH,
             max_multiaddr_data: policy_constants::MAX_MULTIADDR_DATA,
+            max_sector_metadata_size: policy_constants::MAX_SECTOR_METADATA_SIZE,
             addressed_partitions_max: policy_constants::ADDRESSED_PARTITIONS_MAX,
             addressed_sectors_max: policy_constants::ADDRESSED_SECTORS_MAX,
             posted_partitions_max: policy_constants::POSTED_PARTITIONS_MAX,
@@ -230,6 +296,7 @@ impl Default for Policy {
             )
             .unwrap(),
             daily_fee_block_reward_cap_denom: policy_constants::DAILY_FEE_BLOCK_REWARD_CAP_DENOM,
+            beneficiary_proposal_lifetime: policy_constants::BENEFICIARY_PROPOSAL_LIFETIME,
 
             valid_post_proof_type: ProofSet::default_post_proofs(),
             valid_pre_commit_proof_type: ProofSet::default_precommit_seal_proofs(),
@@ -242,6 +309,7 @@ impl Default for Policy {
             maximum_verified_allocation_expiration:
                 policy_constants::MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION,
             end_of_life_claim_drop_period: policy_constants::END_OF_LIFE_CLAIM_DROP_PERIOD,
+            verified_claim_dispute_window: policy_constants::VERIFIED_CLAIM_DISPUTE_WINDOW,
             deal_updates_interval: policy_constants::DEAL_UPDATES_INTERVAL,
             prov_collateral_percent_supply_num:
                 policy_constants::PROV_COLLATERAL_PERCENT_SUPPLY_NUM,
@@ -249,15 +317,56 @@ impl Default for Policy {
                 policy_constants::PROV_COLLATERAL_PERCENT_SUPPLY_DENOM,
             market_default_allocation_term_buffer:
                 policy_constants::MARKET_DEFAULT_ALLOCATION_TERM_BUFFER,
+            market_min_deal_duration: policy_constants::MARKET_MIN_DEAL_DURATION,
+            market_max_deal_duration: policy_constants::MARKET_MAX_DEAL_DURATION,
+            market_min_storage_price_per_epoch: TokenAmount::from_atto(
+                policy_constants::MARKET_MIN_STORAGE_PRICE_PER_EPOCH,
+            ),
+            market_index_deal_labels: policy_constants::MARKET_INDEX_DEAL_LABELS,
+            market_payment_tokens: Vec::new(),
 
             minimum_consensus_power: StoragePower::from(policy_constants::MINIMUM_CONSENSUS_POWER),
+            allow_experimental_seal_proofs: policy_constants::ALLOW_EXPERIMENTAL_SEAL_PROOFS,
+            experimental_seal_proofs_governor: None,
+
+            reward_baseline_exponent: default_reward_baseline_exponent(),
+            reward_baseline_initial_value: default_reward_baseline_initial_value(),
+            reward_simple_total: default_reward_simple_total(),
+            reward_baseline_total: default_reward_baseline_total(),
+
+            evm_ethereum_call_gas_version: policy_constants::EVM_ETHEREUM_CALL_GAS_VERSION,
         }
     }
 }
 
+fn default_beneficiary_proposal_lifetime() -> ChainEpoch {
+    policy_constants::BENEFICIARY_PROPOSAL_LIFETIME
+}
+
+fn default_reward_baseline_exponent() -> StoragePower {
+    (StoragePower::from(1) << 128) + policy_constants::REWARD_BASELINE_EXPONENT_FRACTION
+}
+
+fn default_reward_baseline_initial_value() -> StoragePower {
+    StoragePower::from(policy_constants::REWARD_BASELINE_INITIAL_VALUE)
+}
+
+fn default_reward_simple_total() -> TokenAmount {
+    TokenAmount::from_whole(policy_constants::REWARD_SIMPLE_TOTAL)
+}
+
+fn default_reward_baseline_total() -> TokenAmount {
+    TokenAmount::from_whole(policy_constants::REWARD_BASELINE_TOTAL)
+}
+
+fn default_evm_ethereum_call_gas_version() -> NetworkVersion {
+    policy_constants::EVM_ETHEREUM_CALL_GAS_VERSION
+}
+
 pub mod policy_constants {
     use fvm_shared::clock::ChainEpoch;
     use fvm_shared::sector::SectorNumber;
+    use fvm_shared::version::NetworkVersion;
 
     use crate::builtin::*;
 
@@ -307,6 +416,10 @@ pub mod policy_constants {
 
     pub const MAX_MULTIADDR_DATA: usize = 1024;
 
+    // Enough for a content identifier or an external reference, but bounded so that metadata
+    // stays a small fraction of sector state.
+    pub const MAX_SECTOR_METADATA_SIZE: usize = 64;
+
     // With 48 deadlines (half-hour), 300 partitions per declaration permits addressing a full EiB
     // of partitions of 32GiB sectors with 1 message per epoch within a single half-hour deadline.
     // A miner can of course submit more messages.
@@ -376,6 +489,8 @@ pub mod policy_constants {
     // 50% of estimated daily block rewards
     pub const DAILY_FEE_BLOCK_REWARD_CAP_DENOM: i64 = 2;
 
+    pub const BENEFICIARY_PROPOSAL_LIFETIME: ChainEpoch = 30 * EPOCHS_IN_DAY;
+
     //
     // --- verifreg policy ---
     //
@@ -388,6 +503,7 @@ pub mod policy_constants {
     pub const MAXIMUM_VERIFIED_ALLOCATION_TERM: i64 = 5 * EPOCHS_IN_YEAR;
     pub const MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION: i64 = 60 * EPOCHS_IN_DAY;
     pub const END_OF_LIFE_CLAIM_DROP_PERIOD: ChainEpoch = 30 * EPOCHS_IN_DAY;
+    pub const VERIFIED_CLAIM_DISPUTE_WINDOW: ChainEpoch = 0;
 
     //
     // --- market policy ---
@@ -404,6 +520,18 @@ pub mod policy_constants {
 
     pub const MARKET_DEFAULT_ALLOCATION_TERM_BUFFER: i64 = 90 * EPOCHS_IN_DAY;
 
+    pub const MARKET_MIN_DEAL_DURATION: ChainEpoch = 180 * EPOCHS_IN_DAY;
+
+    pub const MARKET_MAX_DEAL_DURATION: ChainEpoch = 1278 * EPOCHS_IN_DAY;
+
+    /// Minimum storage price per epoch, in attoFIL.
+    pub const MARKET_MIN_STORAGE_PRICE_PER_EPOCH: u64 = 0;
+
+    #[cfg(feature = "deal-label-index")]
+    pub const MARKET_INDEX_DEAL_LABELS: bool = true;
+    #[cfg(not(feature = "deal-label-index"))]
+    pub const MARKET_INDEX_DEAL_LABELS: bool = false;
+
     //
     // --- power policy ---
     //
@@ -420,6 +548,37 @@ pub mod policy_constants {
         feature = "min-power-32g"
     )))]
     pub const MINIMUM_CONSENSUS_POWER: i64 = 10 << 40;
+
+    #[cfg(feature = "experimental-seal-proofs")]
+    pub const ALLOW_EXPERIMENTAL_SEAL_PROOFS: bool = true;
+    #[cfg(not(feature = "experimental-seal-proofs"))]
+    pub const ALLOW_EXPERIMENTAL_SEAL_PROOFS: bool = false;
+
+    //
+    // --- reward policy ---
+    //
+
+    /// Floor(e^(ln[1 + 100%] / epochsInYear) * 2^128)
+    /// Q.128 formatted number such that f(epoch) = baseExponent^epoch grows 100% in one
+    /// year of epochs. The exponent exceeds u128, so this is its fractional part: the
+    /// exponent is 2^128 plus this value (340282591298641078465964189926313473653).
+    /// Calculation here: https://www.wolframalpha.com/input?i=IntegerPart%5BExp%5BLog%5B1%2B100%25%5D%2F%28%28365+days%29%2F%2830+seconds%29%29%5D*2%5E128%5D
+    pub const REWARD_BASELINE_EXPONENT_FRACTION: u128 = 224377702615002589582494545262197;
+
+    /// 2.5057116798121726 EiB
+    pub const REWARD_BASELINE_INITIAL_VALUE: u128 = 2_888_888_880_000_000_000;
+
+    /// Whole FIL minted by the simple reward, 330M for mainnet.
+    pub const REWARD_SIMPLE_TOTAL: u64 = 330_000_000;
+
+    /// Whole FIL minted by the baseline reward, 770M for mainnet.
+    pub const REWARD_BASELINE_TOTAL: u64 = 770_000_000;
+
+    //
+    // --- evm policy ---
+    //
+
+    pub const EVM_ETHEREUM_CALL_GAS_VERSION: NetworkVersion = NetworkVersion::new(28);
 }
 
 /// A set indicating which proofs are considered valid, optimised for lookup of a small number of
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use core::fmt;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::rc::Rc;
 
@@ -38,7 +38,7 @@ use crate::runtime::{
     ActorCode, DomainSeparationTag, EMPTY_ARR_CID, MessageInfo, Policy, Primitives, Runtime,
     RuntimePolicy,
 };
-use crate::{ActorError, SendError, actor_error};
+use crate::{ActorError, SendError, Versioned, VersionedRef, VersionedState, actor_error};
 use rand::prelude::*;
 use serde::Serialize;
 use vm_api::MockPrimitives;
@@ -504,6 +504,14 @@ impl MockRuntime {
         self.state.replace(Some(self.store_put(obj)));
     }
 
+    pub fn get_versioned_state<T: VersionedState>(&self) -> T {
+        self.get_state::<Versioned<T>>().0
+    }
+
+    pub fn replace_versioned_state<T: VersionedState>(&self, obj: &T) {
+        self.replace_state(&VersionedRef(obj))
+    }
+
     pub fn set_balance(&self, amount: TokenAmount) {
         self.balance.replace(amount);
     }
@@ -1577,6 +1585,54 @@ impl Drop for Expectations {
     }
 }
 
+/// The first actor ID handed out by a default [`TestIdAllocator`], above the singleton actors and
+/// the small IDs which test suites assign by hand.
+pub const FIRST_TEST_ACTOR_ID: ActorID = 10_000;
+
+/// Hands out distinct actor ID addresses in sequence, registering the code of each with a mock
+/// runtime, so tests need not maintain their own non-conflicting ID constants.
+#[derive(Debug)]
+pub struct TestIdAllocator {
+    next: Cell<ActorID>,
+}
+
+impl Default for TestIdAllocator {
+    fn default() -> Self {
+        Self::starting_at(FIRST_TEST_ACTOR_ID)
+    }
+}
+
+impl TestIdAllocator {
+    pub fn starting_at(first: ActorID) -> Self {
+        Self { next: Cell::new(first) }
+    }
+
+    /// Returns the next unallocated ID, without registering an actor for it.
+    pub fn next_id(&self) -> ActorID {
+        let id = self.next.get();
+        self.next.set(id.checked_add(1).expect("test actor IDs exhausted"));
+        id
+    }
+
+    /// Allocates an ID address and registers it with the runtime as an actor with some code.
+    pub fn new_actor(&self, rt: &MockRuntime, code: Cid) -> Address {
+        let addr = Address::new_id(self.next_id());
+        rt.set_address_actor_type(addr, code);
+        addr
+    }
+
+    /// Allocates an ID address and registers it with the runtime as an account actor.
+    pub fn new_account(&self, rt: &MockRuntime) -> Address {
+        self.new_actor(rt, *ACCOUNT_ACTOR_CODE_ID)
+    }
+
+    /// Allocates a number of ID addresses, registering each with the runtime as an actor with
+    /// some code.
+    pub fn new_actors(&self, rt: &MockRuntime, code: Cid, count: usize) -> Vec<Address> {
+        (0..count).map(|_| self.new_actor(rt, code)).collect()
+    }
+}
+
 pub fn blake2b_256(data: &[u8]) -> [u8; 32] {
     blake2b_simd::Params::new()
         .hash_length(32)
@@ -1647,6 +1703,33 @@ pub fn make_sealed_cid(input: &[u8]) -> Cid {
     make_cid_poseidon(input, FIL_COMMITMENT_SEALED)
 }
 
+/// Returns CIDs which are each invalid as a piece (CommP) or unsealed sector (CommD) CID
+/// in exactly one respect: codec, hash function, or digest length.
+pub fn make_invalid_piece_cids(input: &[u8]) -> Vec<Cid> {
+    vec![
+        make_cid_sha(input, FIL_COMMITMENT_SEALED),
+        make_cid_poseidon(input, FIL_COMMITMENT_UNSEALED),
+        make_truncated_cid(input, FIL_COMMITMENT_UNSEALED, MhCode::Sha256TruncPaddedFake),
+    ]
+}
+
+/// Returns CIDs which are each invalid as a sealed sector (CommR) CID
+/// in exactly one respect: codec, hash function, or digest length.
+pub fn make_invalid_sealed_cids(input: &[u8]) -> Vec<Cid> {
+    vec![
+        make_cid_poseidon(input, FIL_COMMITMENT_UNSEALED),
+        make_cid_sha(input, FIL_COMMITMENT_SEALED),
+        make_truncated_cid(input, FIL_COMMITMENT_SEALED, MhCode::PoseidonFake),
+    ]
+}
+
+// Returns a CID whose digest is one byte shorter than a commitment.
+fn make_truncated_cid(input: &[u8], prefix: u64, hash: MhCode) -> Cid {
+    let digest = hash.digest(input);
+    let truncated = &digest.digest()[..digest.size() as usize - 1];
+    Cid::new_v1(prefix, Multihash::wrap(u64::from(hash), truncated).unwrap())
+}
+
 pub fn new_bls_addr(s: u8) -> Address {
     let seed = [s; 32];
     let mut rng: StdRng = SeedableRng::from_seed(seed);
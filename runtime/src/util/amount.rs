@@ -0,0 +1,132 @@
+//! Amounts in method parameters which are validated when decoded.
+//! Amounts are encoded as usual, but decoding rejects values which are negative or absurdly
+//! large, so that a method never sees them rather than having to check them itself.
+
+use std::fmt;
+use std::ops::Deref;
+
+use fvm_shared::bigint::{BigInt, bigint_ser};
+use fvm_shared::econ::TokenAmount;
+use num_traits::Signed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::ActorError;
+
+/// The largest number of bits in the magnitude of a valid amount.
+/// This is far beyond any meaningful token balance or data cap, but bounds the cost of
+/// arithmetic on amounts provided by a caller.
+pub const MAX_AMOUNT_BITS: u64 = 256;
+
+fn check_amount(v: &BigInt) -> Result<(), String> {
+    if v.is_negative() {
+        return Err(format!("negative amount {}", v));
+    }
+    if v.bits() > MAX_AMOUNT_BITS {
+        return Err(format!("amount exceeds {} bits", MAX_AMOUNT_BITS));
+    }
+    Ok(())
+}
+
+/// A token amount which is non-negative and at most `MAX_AMOUNT_BITS` bits.
+/// Encoded like `TokenAmount`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NonNegativeTokenAmount(TokenAmount);
+
+impl NonNegativeTokenAmount {
+    pub fn new(v: TokenAmount) -> Result<Self, ActorError> {
+        check_amount(v.atto()).map_err(ActorError::illegal_argument)?;
+        Ok(Self(v))
+    }
+
+    pub fn into_inner(self) -> TokenAmount {
+        self.0
+    }
+}
+
+impl TryFrom<TokenAmount> for NonNegativeTokenAmount {
+    type Error = ActorError;
+
+    fn try_from(v: TokenAmount) -> Result<Self, Self::Error> {
+        Self::new(v)
+    }
+}
+
+impl Deref for NonNegativeTokenAmount {
+    type Target = TokenAmount;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for NonNegativeTokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for NonNegativeTokenAmount {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for NonNegativeTokenAmount {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let v = TokenAmount::deserialize(d)?;
+        check_amount(v.atto()).map_err(de::Error::custom)?;
+        Ok(Self(v))
+    }
+}
+
+/// A data cap, an integer number of bytes, which is non-negative and at most
+/// `MAX_AMOUNT_BITS` bits. Encoded like `bigint_ser`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BoundedDataCap(BigInt);
+
+impl BoundedDataCap {
+    pub fn new(v: BigInt) -> Result<Self, ActorError> {
+        check_amount(&v).map_err(ActorError::illegal_argument)?;
+        Ok(Self(v))
+    }
+
+    pub fn into_inner(self) -> BigInt {
+        self.0
+    }
+}
+
+impl TryFrom<BigInt> for BoundedDataCap {
+    type Error = ActorError;
+
+    fn try_from(v: BigInt) -> Result<Self, Self::Error> {
+        Self::new(v)
+    }
+}
+
+impl Deref for BoundedDataCap {
+    type Target = BigInt;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for BoundedDataCap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for BoundedDataCap {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        bigint_ser::serialize(&self.0, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for BoundedDataCap {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let v = bigint_ser::deserialize(d)?;
+        check_amount(&v).map_err(de::Error::custom)?;
+        Ok(Self(v))
+    }
+}
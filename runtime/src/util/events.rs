@@ -1,13 +1,52 @@
 use crate::ActorError;
 use crate::cbor::serialize_vec;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::BytesSer;
+use fvm_shared::bigint::BigInt;
+use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::event::{ActorEvent, Entry, Flags};
+use multihash_codetable::{Code, MultihashDigest};
 use serde::ser;
+use thiserror::Error;
 
 // Codec identifier for CBOR-encoded data.
 const IPLD_CBOR: u64 = 0x51;
 
 const EVENT_TYPE_KEY: &str = "$type";
 
+/// Maximum number of entries in a single event, as enforced by the FVM.
+pub const MAX_EVENT_ENTRIES: usize = 255;
+/// Maximum length in bytes of an event entry key, as enforced by the FVM.
+pub const MAX_EVENT_KEY_LEN: usize = 31;
+/// Maximum total size in bytes of all entry values in a single event, as enforced by the FVM.
+pub const MAX_EVENT_VALUES_SIZE: usize = 8 << 10;
+
+/// Returns the key unchanged, failing compilation when evaluated in a const context
+/// if the key exceeds the FVM's maximum key length.
+/// E.g. `const PIECE_CID: &str = event_key("piece-cid");`
+pub const fn event_key(key: &'static str) -> &'static str {
+    assert!(key.len() <= MAX_EVENT_KEY_LEN, "event key exceeds maximum length");
+    key
+}
+
+/// An event that would be rejected by the FVM's event validation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    #[error("event has {0} entries, more than the maximum {MAX_EVENT_ENTRIES}")]
+    TooManyEntries(usize),
+    #[error("event key {0:?} is {1} bytes, more than the maximum {MAX_EVENT_KEY_LEN}")]
+    KeyTooLong(String, usize),
+    #[error("event values total {0} bytes, more than the maximum {MAX_EVENT_VALUES_SIZE}")]
+    ValuesTooLarge(usize),
+}
+
+impl From<EventError> for ActorError {
+    fn from(e: EventError) -> Self {
+        // An invalid event is a bug in the emitting actor.
+        ActorError::assertion_failed(format!("invalid event: {}", e))
+    }
+}
+
 /// Builder for ActorEvent objects, accumulating key/value pairs.
 pub struct EventBuilder {
     entries: Result<Vec<Entry>, ActorError>,
@@ -34,9 +73,40 @@ impl EventBuilder {
         self.push_entry(name, value, Flags::FLAG_INDEXED_ALL)
     }
 
+    /// Pushes an entry with an indexed key and an un-indexed big integer value,
+    /// serialized in the same format as other big integers in state.
+    pub fn field_bigint(self, name: &str, value: &BigInt) -> Self {
+        self.field(name, &BigIntSer(value))
+    }
+
+    /// Pushes an entry with an indexed key and an indexed big integer value.
+    pub fn field_indexed_bigint(self, name: &str, value: &BigInt) -> Self {
+        self.field_indexed(name, &BigIntSer(value))
+    }
+
+    /// Pushes an entry with an indexed key and an un-indexed value that is the Blake2b-256
+    /// digest of the bitfield's RLE+ encoding.
+    /// Bitfields can grow beyond the limit on event value sizes, so events carry a fixed-size
+    /// digest that can be matched against a bitfield known to the observer.
+    pub fn field_bitfield_digest(self, name: &str, value: &BitField) -> Self {
+        let digest = Code::Blake2b256.digest(&value.to_bytes());
+        self.field(name, &BytesSer(digest.digest()))
+    }
+
+    /// Checks the entries accumulated so far against the FVM's event limits.
+    pub fn validate(&self) -> Result<(), ActorError> {
+        if let Ok(entries) = &self.entries {
+            validate_entries(entries)?;
+        }
+        Ok(())
+    }
+
     /// Returns an actor event ready to emit (consuming self).
+    /// Fails if the event would be rejected by the FVM's event limits.
     pub fn build(self) -> Result<ActorEvent, ActorError> {
-        Ok(ActorEvent { entries: self.entries? })
+        let entries = self.entries?;
+        validate_entries(&entries)?;
+        Ok(ActorEvent { entries })
     }
 
     /// Pushes an entry with an IPLD-CBOR-serialized value.
@@ -66,11 +136,34 @@ impl Default for EventBuilder {
     }
 }
 
+fn validate_entries(entries: &[Entry]) -> Result<(), EventError> {
+    if entries.len() > MAX_EVENT_ENTRIES {
+        return Err(EventError::TooManyEntries(entries.len()));
+    }
+    let mut values_size = 0;
+    for entry in entries {
+        if entry.key.len() > MAX_EVENT_KEY_LEN {
+            return Err(EventError::KeyTooLong(entry.key.clone(), entry.key.len()));
+        }
+        values_size += entry.value.len();
+    }
+    if values_size > MAX_EVENT_VALUES_SIZE {
+        return Err(EventError::ValuesTooLarge(values_size));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::EventBuilder;
     use crate::cbor::serialize_vec;
-    use crate::util::events::{EVENT_TYPE_KEY, IPLD_CBOR};
+    use crate::util::events::{
+        EVENT_TYPE_KEY, IPLD_CBOR, MAX_EVENT_ENTRIES, MAX_EVENT_KEY_LEN, MAX_EVENT_VALUES_SIZE,
+        event_key,
+    };
+    use fvm_ipld_bitfield::BitField;
+    use fvm_shared::bigint::BigInt;
+    use fvm_shared::error::ExitCode;
     use fvm_shared::event::{ActorEvent, Entry, Flags};
 
     #[test]
@@ -124,4 +217,43 @@ mod test {
             e
         );
     }
+
+    #[test]
+    fn bigint_and_bitfield_values() {
+        let e = EventBuilder::new()
+            .field_bigint("v1", &BigInt::from(256))
+            .field_bitfield_digest("v2", &BitField::try_from_bits([1, 2, 3]).unwrap())
+            .build()
+            .unwrap();
+        // Big integers are serialized as sign-prefixed big-endian bytes.
+        assert_eq!(vec![0x43, 0x00, 0x01, 0x00], e.entries[0].value);
+        // 32-byte digest, CBOR byte-string header is 2 bytes.
+        assert_eq!(34, e.entries[1].value.len());
+    }
+
+    #[test]
+    fn limits() {
+        const KEY: &str = event_key("key");
+        assert_eq!("key", KEY);
+
+        let mut e = EventBuilder::new();
+        for _ in 0..MAX_EVENT_ENTRIES {
+            e = e.field("k", &0);
+        }
+        assert!(e.validate().is_ok());
+        let err = e.field("k", &0).build().unwrap_err();
+        assert_eq!(ExitCode::USR_ASSERTION_FAILED, err.exit_code());
+
+        let long_key = "k".repeat(MAX_EVENT_KEY_LEN + 1);
+        assert!(EventBuilder::new().field(&long_key, &0).build().is_err());
+        assert!(EventBuilder::new().field(&long_key[1..], &0).build().is_ok());
+
+        let big_value = vec![0u8; MAX_EVENT_VALUES_SIZE];
+        assert!(
+            EventBuilder::new()
+                .field("k", &fvm_ipld_encoding::BytesSer(&big_value))
+                .build()
+                .is_err()
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use crate::builtin::HAMT_BIT_WIDTH;
 use crate::{ActorError, AsActorError, Hasher};
+use anyhow::anyhow;
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_hamt as hamt;
@@ -165,6 +166,49 @@ where
         }
         Ok(())
     }
+
+    /// Iterates over at most `max` key-value pairs in the map, starting at `starting_key`
+    /// (or the beginning), in the map's internal order.
+    /// Returns the number of entries traversed and the key at which to resume iteration,
+    /// if any entries remain.
+    pub fn for_each_range<F>(
+        &self,
+        starting_key: Option<&K>,
+        max: Option<usize>,
+        mut f: F,
+    ) -> Result<(usize, Option<K>), ActorError>
+    where
+        F: FnMut(K, &V) -> Result<(), ActorError>,
+    {
+        let starting_key = starting_key
+            .map(|k| k.to_bytes())
+            .transpose()
+            .context_code(ExitCode::USR_ASSERTION_FAILED, "invalid key")?;
+        // An error returned by the callback is passed through unchanged, rather than
+        // being reported as a failure to traverse the HAMT.
+        let mut callback_err = None;
+        let res = self.hamt.for_each_ranged(starting_key.as_ref(), max, |k, v| {
+            let k = K::from_bytes(k).map_err(|e| anyhow!("invalid key: {}", e))?;
+            f(k, v).map_err(|e| {
+                let err = anyhow!(e.to_string());
+                callback_err = Some(e);
+                err
+            })
+        });
+        if let Some(e) = callback_err {
+            return Err(e);
+        }
+        let (traversed, next) = res.with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+            format!("error traversing HAMT {}", self.name)
+        })?;
+        let next = next
+            .map(|k| K::from_bytes(&k))
+            .transpose()
+            .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                format!("invalid key in HAMT {}", self.name)
+            })?;
+        Ok((traversed, next))
+    }
 }
 
 impl MapKey for Vec<u8> {
@@ -254,4 +298,14 @@ mod tests {
         assert!(res.is_err());
         assert_eq!(res.unwrap_err(), ActorError::forbidden("test".to_string()));
     }
+
+    #[test]
+    fn for_each_range_callback_exitcode_propagates() {
+        let bs = MemoryBlockstore::new();
+        let mut m = Map2::<_, u64, String>::empty(bs, DEFAULT_HAMT_CONFIG, "empty");
+        m.set(&1234, "1234".to_string()).unwrap();
+        let res =
+            m.for_each_range(None, None, |_, _| Err(ActorError::forbidden("test".to_string())));
+        assert_eq!(res.unwrap_err(), ActorError::forbidden("test".to_string()));
+    }
 }
@@ -122,6 +122,27 @@ where
         in_map.for_each(f)
     }
 
+    // Runs a function over at most `max` values for one outer key, starting at `start`
+    // (or the beginning), in the inner map's internal order.
+    // Returns the number of values traversed and the inner key at which to resume, if any
+    // values remain.
+    pub fn for_each_in_ranged<F>(
+        &mut self,
+        outside_k: K1,
+        start: Option<K2>,
+        max: Option<usize>,
+        f: F,
+    ) -> Result<(usize, Option<BytesKey>), Error>
+    where
+        F: FnMut(&BytesKey, &V) -> anyhow::Result<()>,
+    {
+        let (is_empty, in_map) = self.load_inner_map(outside_k)?;
+        if is_empty {
+            return Ok((0, None));
+        }
+        in_map.for_each_ranged(start.map(|k| k.key()).as_ref(), max, f)
+    }
+
     // Puts a key value pair in the MapMap, overwriting any existing value.
     // Returns the previous value, if any.
     pub fn put(&mut self, outside_k: K1, inside_k: K2, value: V) -> Result<Option<V>, Error> {
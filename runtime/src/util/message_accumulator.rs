@@ -1,17 +1,30 @@
 use itertools::Itertools;
-use std::{cell::RefCell, fmt::Display, rc::Rc};
+use std::{cell::RefCell, fmt::Display, panic::Location, rc::Rc};
 
 use regex::Regex;
 
+/// A message recorded by a [`MessageAccumulator`], with where and in what context it was added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccumulatedMessage {
+    /// The message text, including any prefix.
+    pub text: String,
+    /// Source location of the call that added the message.
+    pub location: &'static Location<'static>,
+    /// Context of the accumulator the message was added to, e.g. the actor or method being checked.
+    pub context: Option<String>,
+}
+
 /// Accumulates a sequence of messages (e.g. validation failures).
 #[derive(Debug, Default)]
 pub struct MessageAccumulator {
     /// Accumulated messages.
     /// This is a `Rc<RefCell>` to support accumulators derived from `with_prefix()` accumulating to
     /// the same underlying collection.
-    msgs: Rc<RefCell<Vec<String>>>,
+    msgs: Rc<RefCell<Vec<AccumulatedMessage>>>,
     /// Optional prefix to all new messages, e.g. describing higher level context.
     prefix: String,
+    /// Optional context recorded with new messages, by which reports are grouped.
+    context: Option<String>,
 }
 
 impl MessageAccumulator {
@@ -21,6 +34,22 @@ impl MessageAccumulator {
         MessageAccumulator {
             msgs: self.msgs.clone(),
             prefix: self.prefix.to_owned() + prefix.as_ref(),
+            context: self.context.clone(),
+        }
+    }
+
+    /// Returns a new accumulator backed by the same collection, that will record each new message
+    /// in a context such as an actor address or method name. Contexts nest, so an accumulator
+    /// derived from one with a context records both, outermost first.
+    pub fn with_context<S: AsRef<str>>(&self, context: S) -> Self {
+        let context = match &self.context {
+            Some(outer) => format!("{} > {}", outer, context.as_ref()),
+            None => context.as_ref().to_owned(),
+        };
+        MessageAccumulator {
+            msgs: self.msgs.clone(),
+            prefix: self.prefix.clone(),
+            context: Some(context),
         }
     }
 
@@ -29,6 +58,11 @@ impl MessageAccumulator {
     }
 
     pub fn messages(&self) -> Vec<String> {
+        self.msgs.borrow().iter().map(|m| m.text.clone()).collect()
+    }
+
+    /// Returns the accumulated messages along with their source locations and contexts.
+    pub fn entries(&self) -> Vec<AccumulatedMessage> {
         self.msgs.borrow().to_owned()
     }
 
@@ -38,16 +72,27 @@ impl MessageAccumulator {
     }
 
     /// Adds a message to the accumulator
+    #[track_caller]
     pub fn add<S: AsRef<str>>(&self, msg: S) {
-        self.msgs.borrow_mut().push(format!("{}{}", self.prefix, msg.as_ref()));
+        self.msgs.borrow_mut().push(AccumulatedMessage {
+            text: format!("{}{}", self.prefix, msg.as_ref()),
+            location: Location::caller(),
+            context: self.context.clone(),
+        });
     }
 
-    /// Adds messages from another accumulator to this one
+    /// Adds messages from another accumulator to this one.
+    /// Messages keep their source locations, and those without a context take this accumulator's.
     pub fn add_all(&self, other: &Self) {
-        self.msgs.borrow_mut().extend_from_slice(&other.msgs.borrow());
+        let others = other.entries();
+        self.msgs.borrow_mut().extend(others.into_iter().map(|m| AccumulatedMessage {
+            context: m.context.or_else(|| self.context.clone()),
+            ..m
+        }));
     }
 
     /// Adds a message if predicate is false
+    #[track_caller]
     pub fn require<S: AsRef<str>>(&self, predicate: bool, msg: S) {
         if !predicate {
             self.add(msg);
@@ -55,16 +100,32 @@ impl MessageAccumulator {
     }
 
     /// Adds a message if result is `Err`. Underlying error must be `Display`.
+    #[track_caller]
     pub fn require_no_error<V, E: Display, S: AsRef<str>>(&self, result: Result<V, E>, msg: S) {
         if let Err(e) = result {
             self.add(format!("{}: {e}", msg.as_ref()));
         }
     }
 
+    /// Renders the accumulated messages grouped by context, in order of each context's first
+    /// message, with the source location of each message.
+    pub fn report(&self) -> String {
+        let msgs = self.msgs.borrow();
+        let groups = msgs.iter().into_group_map_by(|m| m.context.clone());
+        let mut report = format!("{} messages in {} contexts", msgs.len(), groups.len());
+        for context in msgs.iter().map(|m| &m.context).unique() {
+            report += &format!("\n{}:", context.as_deref().unwrap_or("(no context)"));
+            for m in &groups[context] {
+                report += &format!("\n  {} [{}:{}]", m.text, m.location.file(), m.location.line());
+            }
+        }
+        report
+    }
+
     /// Panic if the accumulator isn't empty. The acculumated messages are included in the panic message.
     #[track_caller]
     pub fn assert_empty(&self) {
-        assert!(self.is_empty(), "{}", self.messages().join("\n"))
+        assert!(self.is_empty(), "{}", self.report())
     }
 
     /// Asserts the accumulator contains messages matching provided pattern *in the given order*.
@@ -74,7 +135,7 @@ impl MessageAccumulator {
         assert!(
             messages.len() == expected_patterns.len(),
             "Incorrect number of accumulator messages.\nActual: {}.\nExpected: {}",
-            messages.join("\n"),
+            self.report(),
             expected_patterns.iter().map(|regex| regex.as_str()).join("\n")
         );
 
@@ -162,4 +223,60 @@ mod test {
         assert_eq!(2, acc3.len());
         assert_eq!(acc3.messages(), vec!["Cthulhu", "Azathoth"]);
     }
+
+    #[test]
+    fn records_locations() {
+        let acc = MessageAccumulator::default();
+        let line = line!() + 1;
+        acc.require(false, "Cthulhu");
+
+        let entries = acc.entries();
+        assert_eq!(file!(), entries[0].location.file());
+        assert_eq!(line, entries[0].location.line());
+    }
+
+    #[test]
+    fn contexts() {
+        let acc = MessageAccumulator::default();
+        acc.add("peasant");
+
+        let gods_acc = acc.with_context("f01000").with_prefix("elder god -> ");
+        gods_acc.add("Cthulhu");
+        gods_acc.with_context("Dream").add("Azathoth");
+
+        let other = MessageAccumulator::default();
+        other.add("Nyarlathotep");
+        gods_acc.add_all(&other);
+
+        let contexts = acc.entries().into_iter().map(|m| m.context).collect::<Vec<_>>();
+        assert_eq!(
+            contexts,
+            vec![
+                None,
+                Some("f01000".to_owned()),
+                Some("f01000 > Dream".to_owned()),
+                Some("f01000".to_owned())
+            ]
+        );
+        assert_eq!(
+            acc.messages(),
+            vec!["peasant", "elder god -> Cthulhu", "elder god -> Azathoth", "Nyarlathotep"]
+        );
+
+        let report = acc.report();
+        let lines = report.lines().map(|l| l.split(" [").next().unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "4 messages in 3 contexts",
+                "(no context):",
+                "  peasant",
+                "f01000:",
+                "  elder god -> Cthulhu",
+                "  Nyarlathotep",
+                "f01000 > Dream:",
+                "  elder god -> Azathoth",
+            ]
+        );
+    }
 }
@@ -1,17 +1,22 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+pub use self::amount::*;
 pub use self::batch_return::*;
 pub use self::downcast::*;
 pub use self::events::*;
 pub use self::map::*;
 pub use self::mapmap::MapMap;
-pub use self::message_accumulator::MessageAccumulator;
+pub use self::message_accumulator::{AccumulatedMessage, MessageAccumulator};
 pub use self::multimap::*;
+pub use self::proof::*;
+pub use self::rate_limiter::{RateLimit, RateLimiter, RateUsage};
 pub use self::set::Set;
 pub use self::set_multimap::SetMultimap;
 pub use self::set_multimap::SetMultimapConfig;
+pub use self::versioned_state::*;
 
+mod amount;
 mod batch_return;
 pub mod cbor;
 mod downcast;
@@ -20,5 +25,8 @@ mod map;
 mod mapmap;
 mod message_accumulator;
 mod multimap;
+mod proof;
+mod rate_limiter;
 mod set;
 mod set_multimap;
+mod versioned_state;
@@ -0,0 +1,81 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+use cid::Cid;
+use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
+use fvm_ipld_encoding::strict_bytes;
+use fvm_ipld_encoding::tuple::*;
+use multihash_codetable::{Code, MultihashDigest};
+
+use crate::{ActorError, actor_error};
+
+/// An IPLD block included in a state proof.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ProofBlock {
+    pub cid: Cid,
+    #[serde(with = "strict_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// A blockstore which records the blocks read through it.
+/// A traversal of state (such as a HAMT lookup) through this store yields the blocks
+/// a verifier needs to repeat the same traversal from the same root, and so prove its result.
+pub struct RecordingBlockstore<'a, BS> {
+    inner: &'a BS,
+    seen: RefCell<BTreeSet<Cid>>,
+    blocks: RefCell<Vec<ProofBlock>>,
+}
+
+impl<'a, BS: Blockstore> RecordingBlockstore<'a, BS> {
+    pub fn new(inner: &'a BS) -> Self {
+        Self { inner, seen: Default::default(), blocks: Default::default() }
+    }
+
+    /// Returns the distinct blocks read, in the order they were first read.
+    pub fn into_proof(self) -> Vec<ProofBlock> {
+        self.blocks.into_inner()
+    }
+}
+
+impl<BS: Blockstore> Blockstore for RecordingBlockstore<'_, BS> {
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        let data = self.inner.get(k)?;
+        if let Some(data) = &data {
+            if self.seen.borrow_mut().insert(*k) {
+                self.blocks.borrow_mut().push(ProofBlock { cid: *k, data: data.clone() });
+            }
+        }
+        Ok(data)
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+        self.inner.put_keyed(k, block)
+    }
+}
+
+/// Builds a blockstore holding only the blocks of a proof, after checking that each block's
+/// data hashes to its CID.
+/// A traversal through the returned store either reaches the same result as the traversal
+/// which recorded the proof, or fails for lack of a block.
+pub fn proof_blockstore(blocks: &[ProofBlock]) -> Result<MemoryBlockstore, ActorError> {
+    let store = MemoryBlockstore::new();
+    for block in blocks {
+        let code = Code::try_from(block.cid.hash().code()).map_err(|_| {
+            actor_error!(illegal_argument, "unsupported hash in proof block {}", block.cid)
+        })?;
+        if code.digest(&block.data) != *block.cid.hash() {
+            return Err(actor_error!(
+                illegal_argument,
+                "proof block data does not match cid {}",
+                block.cid
+            ));
+        }
+        store.put_keyed(&block.cid, &block.data).map_err(|e| {
+            actor_error!(illegal_state, "failed to store proof block {}: {}", block.cid, e)
+        })?;
+    }
+    Ok(store)
+}
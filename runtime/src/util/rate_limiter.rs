@@ -0,0 +1,149 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::clock::ChainEpoch;
+
+use crate::{ActorError, Config, Map2, MapKey, actor_error};
+
+/// The amount that may be consumed by each key within a window of epochs.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Length of a window, in epochs. Windows are aligned to multiples of their length.
+    pub window: ChainEpoch,
+    /// Maximum amount consumed by a key within a single window.
+    pub max_per_window: u64,
+}
+
+/// A key's consumption within the window beginning at an epoch.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateUsage {
+    pub window_start: ChainEpoch,
+    pub used: u64,
+}
+
+/// Limits the amount consumed by each key (typically an actor) within fixed epoch windows.
+/// Usage is persisted in a HAMT, with an entry for each key which has consumed anything.
+/// Usage recorded in an earlier window is disregarded, and overwritten on next consumption,
+/// so entries only need pruning to reclaim state for keys which stop consuming.
+pub struct RateLimiter<BS, K>
+where
+    BS: Blockstore,
+    K: MapKey,
+{
+    usage: Map2<BS, K, RateUsage>,
+    limit: RateLimit,
+}
+
+impl<BS, K> RateLimiter<BS, K>
+where
+    BS: Blockstore,
+    K: MapKey,
+{
+    /// Initializes a new limiter with no recorded usage.
+    pub fn empty(
+        bs: BS,
+        config: Config,
+        limit: RateLimit,
+        name: &'static str,
+    ) -> Result<Self, ActorError> {
+        validate_limit(&limit)?;
+        Ok(Self { usage: Map2::empty(bs, config, name), limit })
+    }
+
+    /// Loads a limiter's usage from a root Cid.
+    /// The limit may differ from that with which usage was previously recorded.
+    pub fn load(
+        bs: BS,
+        root: &Cid,
+        config: Config,
+        limit: RateLimit,
+        name: &'static str,
+    ) -> Result<Self, ActorError> {
+        validate_limit(&limit)?;
+        Ok(Self { usage: Map2::load(bs, root, config, name)?, limit })
+    }
+
+    /// Flushes recorded usage, returning the root Cid.
+    pub fn flush(&mut self) -> Result<Cid, ActorError> {
+        self.usage.flush()
+    }
+
+    /// Returns the limit applied by this limiter.
+    pub fn limit(&self) -> &RateLimit {
+        &self.limit
+    }
+
+    /// Returns the first epoch of the window containing an epoch.
+    pub fn window_start(&self, epoch: ChainEpoch) -> ChainEpoch {
+        epoch - epoch.rem_euclid(self.limit.window)
+    }
+
+    /// Returns the amount consumed by a key in the window containing an epoch.
+    pub fn used(&self, key: &K, epoch: ChainEpoch) -> Result<u64, ActorError> {
+        let window_start = self.window_start(epoch);
+        Ok(match self.usage.get(key)? {
+            Some(usage) if usage.window_start == window_start => usage.used,
+            _ => 0,
+        })
+    }
+
+    /// Returns the amount a key may yet consume in the window containing an epoch.
+    pub fn remaining(&self, key: &K, epoch: ChainEpoch) -> Result<u64, ActorError> {
+        Ok(self.limit.max_per_window.saturating_sub(self.used(key, epoch)?))
+    }
+
+    /// Records consumption of an amount by a key at an epoch, returning the amount remaining
+    /// in the window.
+    /// Fails with USR_FORBIDDEN, recording nothing, if the amount exceeds that remaining.
+    pub fn consume(&mut self, key: &K, epoch: ChainEpoch, amount: u64) -> Result<u64, ActorError> {
+        let used = self.used(key, epoch)?;
+        let remaining = self.limit.max_per_window.saturating_sub(used);
+        if amount > remaining {
+            return Err(actor_error!(
+                forbidden,
+                "rate limit exceeded for {:?}: {} requested, {} of {} remaining in window of {} epochs",
+                key,
+                amount,
+                remaining,
+                self.limit.max_per_window,
+                self.limit.window
+            ));
+        }
+        if amount > 0 {
+            let usage = RateUsage { window_start: self.window_start(epoch), used: used + amount };
+            self.usage.set(key, usage)?;
+        }
+        Ok(remaining - amount)
+    }
+
+    /// Removes usage recorded in windows before that containing an epoch,
+    /// returning the number of entries removed.
+    pub fn prune(&mut self, epoch: ChainEpoch) -> Result<usize, ActorError> {
+        let window_start = self.window_start(epoch);
+        let mut stale = Vec::new();
+        self.usage.for_each(|key, usage| {
+            if usage.window_start < window_start {
+                stale.push(key);
+            }
+            Ok(())
+        })?;
+        for key in &stale {
+            self.usage.delete(key)?;
+        }
+        Ok(stale.len())
+    }
+}
+
+fn validate_limit(limit: &RateLimit) -> Result<(), ActorError> {
+    if limit.window <= 0 {
+        return Err(actor_error!(
+            illegal_argument,
+            "rate limit window must be positive, was {}",
+            limit.window
+        ));
+    }
+    Ok(())
+}
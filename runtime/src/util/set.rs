@@ -44,6 +44,12 @@ where
         self.0.set(key, ())
     }
 
+    /// Returns whether the set has no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Checks if key exists in the set.
     #[inline]
     pub fn has(&self, key: &K) -> Result<bool, ActorError> {
@@ -64,6 +70,21 @@ where
         self.0.for_each(|s, _| f(s))
     }
 
+    /// Iterates through at most `max` keys in the set, starting at `starting_key`
+    /// (or the beginning), in the set's internal order.
+    /// Returns the number of keys traversed and the key at which to resume, if any remain.
+    pub fn for_each_range<F>(
+        &self,
+        starting_key: Option<&K>,
+        max: Option<usize>,
+        mut f: F,
+    ) -> Result<(usize, Option<K>), ActorError>
+    where
+        F: FnMut(K) -> Result<(), ActorError>,
+    {
+        self.0.for_each_range(starting_key, max, |s, _| f(s))
+    }
+
     /// Collects all keys from the set into a vector.
     pub fn collect_keys(&self) -> Result<Vec<K>, ActorError> {
         let mut ret_keys = Vec::new();
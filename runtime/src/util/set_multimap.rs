@@ -62,6 +62,12 @@ where
         self.outer.flush()
     }
 
+    /// Returns whether the multimap has no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.outer.is_empty()
+    }
+
     /// Puts a value in the set associated with a key.
     pub fn put(&mut self, key: &K, value: V) -> Result<(), ActorError> {
         // Load HAMT from retrieved cid or create a new empty one.
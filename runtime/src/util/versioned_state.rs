@@ -0,0 +1,89 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use ipld_core::ipld::Ipld;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{ActorError, actor_error};
+
+/// An actor state type whose encoding carries a schema version.
+///
+/// State at version 0 is stored untagged, exactly as it was before the type adopted versioning.
+/// State at any later version is stored as the pair `[version, state]`.
+/// Loading state stored at an earlier version dispatches to [`VersionedState::upgrade`],
+/// and the next write stores it at the current version.
+/// State can thus evolve one actor at a time, migrating lazily as it is written,
+/// rather than all at once in a network upgrade.
+///
+/// A type may only move beyond version 0 if its untagged encoding is not a two-element list
+/// beginning with an integer, which would be mistaken for a tagged encoding.
+pub trait VersionedState: Serialize + DeserializeOwned {
+    /// The schema version of this type.
+    const VERSION: u64;
+
+    /// Decodes state stored at an earlier version, upgrading it to this schema.
+    /// The default implementation supports no earlier versions.
+    fn upgrade(version: u64, _state: Ipld) -> Result<Self, ActorError> {
+        Err(actor_error!(
+            illegal_state,
+            "unsupported state version {}, expected {}",
+            version,
+            Self::VERSION
+        ))
+    }
+}
+
+/// Decodes state of a specific earlier schema from its IPLD representation.
+/// Intended for use by [`VersionedState::upgrade`] implementations.
+pub fn decode_state_version<T: DeserializeOwned>(state: Ipld) -> Result<T, ActorError> {
+    ipld_core::serde::from_ipld(state)
+        .map_err(|e| actor_error!(serialization, "failed to decode versioned state: {}", e))
+}
+
+/// A wrapper which encodes state with its version tag and decodes state of any supported version.
+pub struct Versioned<S>(pub S);
+
+/// A borrowing counterpart of [`Versioned`], for writing state without taking ownership.
+pub(crate) struct VersionedRef<'a, S>(pub &'a S);
+
+impl<S: VersionedState> Serialize for VersionedRef<'_, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        if S::VERSION == 0 {
+            self.0.serialize(serializer)
+        } else {
+            (S::VERSION, self.0).serialize(serializer)
+        }
+    }
+}
+
+impl<S: VersionedState> Serialize for Versioned<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        VersionedRef(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de, S: VersionedState> Deserialize<'de> for Versioned<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if S::VERSION == 0 {
+            // No later version has been written, so the state must be untagged.
+            return S::deserialize(deserializer).map(Versioned);
+        }
+        let (version, state) = match Ipld::deserialize(deserializer)? {
+            Ipld::List(mut items) if items.len() == 2 && matches!(items[0], Ipld::Integer(_)) => {
+                let state = items.pop().unwrap();
+                let Some(Ipld::Integer(version)) = items.pop() else { unreachable!() };
+                let version = u64::try_from(version)
+                    .map_err(|_| de::Error::custom(format!("invalid state version {}", version)))?;
+                (version, state)
+            }
+            untagged => (0, untagged),
+        };
+        let state = if version == S::VERSION {
+            decode_state_version(state)
+        } else {
+            S::upgrade(version, state)
+        };
+        state.map(Versioned).map_err(|e| de::Error::custom(e.msg()))
+    }
+}
@@ -0,0 +1,68 @@
+use fil_actors_runtime::datacap::{DestroyParams, MintParams};
+use fil_actors_runtime::{BoundedDataCap, MAX_AMOUNT_BITS, NonNegativeTokenAmount};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
+use fvm_shared::bigint::{BigInt, bigint_ser};
+use fvm_shared::econ::TokenAmount;
+
+// Mirrors the encoding of the wrapped parameters without their checks, like a foreign caller.
+#[derive(Serialize_tuple)]
+struct UncheckedDestroyParams {
+    owner: Address,
+    amount: TokenAmount,
+}
+
+#[derive(Serialize_tuple)]
+#[serde(transparent)]
+struct UncheckedDataCap {
+    #[serde(with = "bigint_ser")]
+    amount: BigInt,
+}
+
+fn decode_destroy(amount: TokenAmount) -> Result<DestroyParams, String> {
+    let params = UncheckedDestroyParams { owner: Address::new_id(1000), amount };
+    IpldBlock::serialize_cbor(&params).unwrap().unwrap().deserialize().map_err(|e| e.to_string())
+}
+
+fn decode_data_cap(amount: BigInt) -> Result<BoundedDataCap, String> {
+    let params = UncheckedDataCap { amount };
+    IpldBlock::serialize_cbor(&params).unwrap().unwrap().deserialize().map_err(|e| e.to_string())
+}
+
+#[test]
+fn accepts_amounts_in_range() {
+    let max = (BigInt::from(1) << MAX_AMOUNT_BITS) - 1;
+    for amount in [BigInt::from(0), BigInt::from(1), max] {
+        let tokens = TokenAmount::from_atto(amount.clone());
+        assert_eq!(tokens, *decode_destroy(tokens.clone()).unwrap().amount);
+        assert_eq!(amount, decode_data_cap(amount.clone()).unwrap().into_inner());
+    }
+
+    let params = MintParams {
+        to: Address::new_id(1000),
+        amount: TokenAmount::from_atto(1).try_into().unwrap(),
+        operators: vec![],
+    };
+    let decoded: MintParams =
+        IpldBlock::serialize_cbor(&params).unwrap().unwrap().deserialize().unwrap();
+    assert_eq!(params, decoded);
+}
+
+#[test]
+fn rejects_amounts_out_of_range() {
+    let too_big = BigInt::from(1) << MAX_AMOUNT_BITS;
+
+    assert!(decode_destroy(TokenAmount::from_atto(-1)).unwrap_err().contains("negative amount"));
+    assert!(
+        decode_destroy(TokenAmount::from_atto(too_big.clone()))
+            .unwrap_err()
+            .contains("amount exceeds")
+    );
+    assert!(decode_data_cap(BigInt::from(-1)).unwrap_err().contains("negative amount"));
+    assert!(decode_data_cap(too_big.clone()).unwrap_err().contains("amount exceeds"));
+
+    // Values out of range can't be constructed either.
+    assert!(NonNegativeTokenAmount::new(TokenAmount::from_atto(-1)).is_err());
+    assert!(BoundedDataCap::new(too_big).is_err());
+}
@@ -0,0 +1,18 @@
+use fil_actors_runtime::test_utils::*;
+use fvm_shared::commcid::{FIL_COMMITMENT_SEALED, FIL_COMMITMENT_UNSEALED};
+
+#[test]
+fn invalid_cids_differ_from_valid() {
+    let sealed = make_sealed_cid(b"sector");
+    let unsealed = make_piece_cid(b"sector");
+    assert_eq!(FIL_COMMITMENT_SEALED, sealed.codec());
+    assert_eq!(FIL_COMMITMENT_UNSEALED, unsealed.codec());
+    assert_eq!(32, sealed.hash().size());
+
+    for cid in make_invalid_piece_cids(b"sector") {
+        assert_ne!(unsealed, cid);
+    }
+    for cid in make_invalid_sealed_cids(b"sector") {
+        assert_ne!(sealed, cid);
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+
+use fil_actors_runtime::method_names::{builtin_type_by_name, method_name, method_names};
+use fil_actors_runtime::runtime::builtins::Type;
+use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
+use num_traits::FromPrimitive;
+
+fn all_types() -> Vec<Type> {
+    (Type::System as i32..=Type::EthAccount as i32).filter_map(Type::from_i32).collect()
+}
+
+#[test]
+fn method_numbers_are_unique() {
+    for typ in all_types() {
+        let mut seen = HashSet::new();
+        for (method, name) in method_names(typ) {
+            assert!(
+                seen.insert(*method),
+                "duplicate method {} ({}) for {}",
+                method,
+                name,
+                typ.name()
+            );
+            assert_ne!(METHOD_SEND, *method);
+        }
+    }
+}
+
+#[test]
+fn looks_up_methods() {
+    assert_eq!(Some("Send"), method_name(Type::Miner, METHOD_SEND));
+    assert_eq!(Some("Constructor"), method_name(Type::Miner, METHOD_CONSTRUCTOR));
+    assert_eq!(Some("SubmitWindowedPoSt"), method_name(Type::Miner, 5));
+    assert_eq!(
+        Some("AddBalance"),
+        method_name(Type::Market, frc42_dispatch::method_hash!("AddBalance"))
+    );
+    assert_eq!(
+        Some("Receive"),
+        method_name(Type::Multisig, frc42_dispatch::method_hash!("Receive"))
+    );
+    assert_eq!(None, method_name(Type::Miner, 6));
+    assert_eq!(None, method_name(Type::Placeholder, METHOD_CONSTRUCTOR));
+}
+
+#[test]
+fn resolves_types_by_name() {
+    for typ in all_types() {
+        assert_eq!(Some(typ), builtin_type_by_name(typ.name()));
+    }
+    assert_eq!(None, builtin_type_by_name("unknown"));
+}
@@ -0,0 +1,72 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use fil_actors_runtime::{DEFAULT_HAMT_CONFIG, RateLimit, RateLimiter};
+use fvm_shared::ActorID;
+use fvm_shared::error::ExitCode;
+
+const LIMIT: RateLimit = RateLimit { window: 10, max_per_window: 5 };
+
+#[test]
+fn consumes_within_window() {
+    let store = MemoryBlockstore::new();
+    let mut limiter =
+        RateLimiter::<_, ActorID>::empty(&store, DEFAULT_HAMT_CONFIG, LIMIT, "t").unwrap();
+
+    assert_eq!(5, limiter.remaining(&1, 12).unwrap());
+    assert_eq!(2, limiter.consume(&1, 12, 3).unwrap());
+    assert_eq!(0, limiter.consume(&1, 19, 2).unwrap());
+
+    // Exceeding the limit fails without consuming anything.
+    let err = limiter.consume(&1, 15, 1).unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+    assert_eq!(5, limiter.used(&1, 15).unwrap());
+
+    // Keys are limited independently.
+    assert_eq!(0, limiter.consume(&2, 15, 5).unwrap());
+    assert_eq!(ExitCode::USR_FORBIDDEN, limiter.consume(&2, 15, 1).unwrap_err().exit_code());
+}
+
+#[test]
+fn resets_in_next_window() {
+    let store = MemoryBlockstore::new();
+    let mut limiter =
+        RateLimiter::<_, ActorID>::empty(&store, DEFAULT_HAMT_CONFIG, LIMIT, "t").unwrap();
+    limiter.consume(&1, 9, 5).unwrap();
+    assert_eq!(0, limiter.remaining(&1, 9).unwrap());
+
+    // Windows are aligned to multiples of their length.
+    assert_eq!(10, limiter.window_start(10));
+    assert_eq!(5, limiter.remaining(&1, 10).unwrap());
+    assert_eq!(1, limiter.consume(&1, 10, 4).unwrap());
+}
+
+#[test]
+fn persists_and_prunes_usage() {
+    let store = MemoryBlockstore::new();
+    let mut limiter =
+        RateLimiter::<_, ActorID>::empty(&store, DEFAULT_HAMT_CONFIG, LIMIT, "t").unwrap();
+    limiter.consume(&1, 5, 2).unwrap();
+    limiter.consume(&2, 15, 3).unwrap();
+    let root = limiter.flush().unwrap();
+
+    let mut limiter =
+        RateLimiter::<_, ActorID>::load(&store, &root, DEFAULT_HAMT_CONFIG, LIMIT, "t").unwrap();
+    assert_eq!(2, limiter.used(&1, 5).unwrap());
+    assert_eq!(3, limiter.used(&2, 15).unwrap());
+
+    // Only usage from earlier windows is pruned.
+    assert_eq!(1, limiter.prune(15).unwrap());
+    assert_eq!(0, limiter.prune(15).unwrap());
+    assert_eq!(3, limiter.used(&2, 15).unwrap());
+}
+
+#[test]
+fn rejects_empty_window() {
+    let store = MemoryBlockstore::new();
+    let limit = RateLimit { window: 0, max_per_window: 5 };
+    let err =
+        RateLimiter::<_, ActorID>::empty(&store, DEFAULT_HAMT_CONFIG, limit, "t").err().unwrap();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+}
@@ -31,6 +31,37 @@ fn collect_keys() {
     assert_eq!(set.collect_keys().unwrap().len(), 4);
 }
 
+#[test]
+fn for_each_range() {
+    let store = fil_actors_runtime::test_blockstores::MemoryBlockstore::new();
+    let mut set = Set::<_, u64>::empty(&store, DEFAULT_HAMT_CONFIG, "t");
+    for k in 0..10u64 {
+        set.put(&k).unwrap();
+    }
+
+    // Page through the set three keys at a time.
+    let mut keys = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut page = Vec::new();
+        let (traversed, next) = set
+            .for_each_range(cursor.as_ref(), Some(3), |k| {
+                page.push(k);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(page.len(), traversed);
+        assert!(traversed <= 3);
+        keys.extend(page);
+        cursor = next;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(set.collect_keys().unwrap(), keys);
+}
+
 #[test]
 fn delete() {
     let store = fil_actors_runtime::test_blockstores::MemoryBlockstore::new();
@@ -0,0 +1,33 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actors_runtime::test_utils::*;
+use fvm_shared::address::Address;
+
+#[test]
+fn allocates_distinct_registered_ids() {
+    let rt = MockRuntime::default();
+    let ids = TestIdAllocator::default();
+
+    let account = ids.new_account(&rt);
+    let miners = ids.new_actors(&rt, *MINER_ACTOR_CODE_ID, 2);
+    assert_eq!(Address::new_id(FIRST_TEST_ACTOR_ID), account);
+    assert_eq!(
+        vec![Address::new_id(FIRST_TEST_ACTOR_ID + 1), Address::new_id(FIRST_TEST_ACTOR_ID + 2)],
+        miners
+    );
+    assert_eq!(Some(&*ACCOUNT_ACTOR_CODE_ID), rt.actor_code_cids.borrow().get(&account));
+    assert_eq!(Some(&*MINER_ACTOR_CODE_ID), rt.actor_code_cids.borrow().get(&miners[1]));
+
+    // IDs taken without registration are not handed out again.
+    assert_eq!(FIRST_TEST_ACTOR_ID + 3, ids.next_id());
+    assert_eq!(Address::new_id(FIRST_TEST_ACTOR_ID + 4), ids.new_account(&rt));
+}
+
+#[test]
+fn allocates_from_chosen_start() {
+    let rt = MockRuntime::default();
+    let ids = TestIdAllocator::starting_at(500);
+    assert_eq!(Address::new_id(500), ids.new_account(&rt));
+    assert_eq!(501, ids.next_id());
+}
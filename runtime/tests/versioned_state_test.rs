@@ -0,0 +1,77 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actors_runtime::{ActorError, Versioned, VersionedState, decode_state_version};
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{from_slice, to_vec};
+use ipld_core::ipld::Ipld;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, PartialEq)]
+struct StateV0 {
+    name: String,
+    count: u64,
+}
+
+impl VersionedState for StateV0 {
+    const VERSION: u64 = 0;
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, PartialEq)]
+struct StateV1 {
+    name: String,
+    count: u64,
+    limit: u64,
+}
+
+impl VersionedState for StateV1 {
+    const VERSION: u64 = 1;
+
+    fn upgrade(version: u64, state: Ipld) -> Result<Self, ActorError> {
+        match version {
+            0 => {
+                let old: StateV0 = decode_state_version(state)?;
+                Ok(StateV1 { name: old.name, count: old.count, limit: 10 })
+            }
+            _ => Err(ActorError::illegal_state(format!("unsupported version {}", version))),
+        }
+    }
+}
+
+#[test]
+fn version_zero_is_untagged() {
+    let st = StateV0 { name: "a".to_string(), count: 1 };
+    let encoded = to_vec(&Versioned(st)).unwrap();
+    assert_eq!(to_vec(&StateV0 { name: "a".to_string(), count: 1 }).unwrap(), encoded);
+
+    let decoded: Versioned<StateV0> = from_slice(&encoded).unwrap();
+    assert_eq!(StateV0 { name: "a".to_string(), count: 1 }, decoded.0);
+}
+
+#[test]
+fn later_version_is_tagged() {
+    let st = StateV1 { name: "a".to_string(), count: 1, limit: 5 };
+    let encoded = to_vec(&Versioned(st)).unwrap();
+    let expected = StateV1 { name: "a".to_string(), count: 1, limit: 5 };
+    assert_eq!(to_vec(&(1u64, &expected)).unwrap(), encoded);
+
+    let decoded: Versioned<StateV1> = from_slice(&encoded).unwrap();
+    assert_eq!(expected, decoded.0);
+}
+
+#[test]
+fn upgrades_earlier_version() {
+    let encoded = to_vec(&StateV0 { name: "a".to_string(), count: 1 }).unwrap();
+    let decoded: Versioned<StateV1> = from_slice(&encoded).unwrap();
+    assert_eq!(StateV1 { name: "a".to_string(), count: 1, limit: 10 }, decoded.0);
+
+    // The upgraded state is written at the current version.
+    let reencoded = to_vec(&decoded).unwrap();
+    let tagged: (u64, StateV1) = from_slice(&reencoded).unwrap();
+    assert_eq!(1, tagged.0);
+}
+
+#[test]
+fn rejects_unsupported_version() {
+    let encoded = to_vec(&(2u64, StateV0 { name: "a".to_string(), count: 1 })).unwrap();
+    assert!(from_slice::<Versioned<StateV1>>(&encoded).is_err());
+}
@@ -23,6 +23,7 @@ use fil_actor_verifreg::{DataCap, State as VerifregState};
 use fil_actors_runtime::DealWeight;
 use fil_actors_runtime::MessageAccumulator;
 use fil_actors_runtime::VERIFIED_REGISTRY_ACTOR_ADDR;
+use fil_actors_runtime::Versioned;
 use fil_actors_runtime::runtime::Policy;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::CborStore;
@@ -84,7 +85,8 @@ pub fn check_state_invariants<BS: Blockstore>(
     let mut datacap_summary: Option<frc46_token::token::state::StateSummary> = None;
 
     tree.iter().try_for_each(|(key, actor)| -> anyhow::Result<()> {
-        let acc = acc.with_prefix(format!("{key} "));
+        let actor_type = manifest.get(&actor.code).map_or("unknown", |typ| typ.name());
+        let acc = acc.with_prefix(format!("{key} ")).with_context(format!("{key} ({actor_type})"));
 
         if key.protocol() != Protocol::ID {
             acc.add(format!("unexpected address protocol in state tree root: {key}"));
@@ -151,7 +153,7 @@ pub fn check_state_invariants<BS: Blockstore>(
                 reward_summary = Some(summary);
             }
             Some(Type::VerifiedRegistry) => {
-                let state = get_state!(store, actor, VerifregState);
+                let state = get_state!(store, actor, Versioned<VerifregState>).0;
                 let (summary, msgs) = verifreg::check_state_invariants(&state, store, prior_epoch);
                 acc.with_prefix("verifreg: ").add_all(&msgs);
                 verifreg_summary = Some(summary);
@@ -176,21 +178,41 @@ pub fn check_state_invariants<BS: Blockstore>(
 
     // Perform cross-actor checks from state summaries here.
     if let Some(power_summary) = power_summary {
-        check_miner_against_power(&acc, &miner_summaries, &power_summary);
+        check_miner_against_power(
+            &acc.with_context("miners against power"),
+            &miner_summaries,
+            &power_summary,
+        );
     }
 
     if let Some(market_summary) = market_summary.clone() {
-        check_deal_states_against_sectors(&acc, &miner_summaries, &market_summary);
+        check_deal_states_against_sectors(
+            &acc.with_context("deals against sectors"),
+            &miner_summaries,
+            &market_summary,
+        );
     }
 
     if let Some(verifreg_summary) = verifreg_summary {
         if let Some(datacap_summary) = datacap_summary {
-            check_verifreg_against_datacap(&acc, &verifreg_summary, &datacap_summary);
+            check_verifreg_against_datacap(
+                &acc.with_context("verifreg against datacap"),
+                &verifreg_summary,
+                &datacap_summary,
+            );
         }
         if let Some(market_summary) = market_summary {
-            check_market_against_verifreg(&acc, &market_summary, &verifreg_summary);
+            check_market_against_verifreg(
+                &acc.with_context("market against verifreg"),
+                &market_summary,
+                &verifreg_summary,
+            );
         }
-        check_verifreg_against_miners(&acc, &verifreg_summary, &miner_summaries);
+        check_verifreg_against_miners(
+            &acc.with_context("verifreg against miners"),
+            &verifreg_summary,
+            &miner_summaries,
+        );
     }
 
     if let Some(expected_balance_total) = expected_balance_total {
@@ -20,3 +20,9 @@ pub const TEST_VM_RAND_ARRAY: [u8; 32] = [
     26, 27, 28, 29, 30, 31, 32,
 ];
 pub const TEST_VM_INVALID_POST: &str = "i_am_invalid_post";
+
+// A simplified gas price list, approximating the FVM's flat charges for the operations which
+// dominate the cost of builtin actor methods. Other syscalls are not charged.
+pub const TEST_VM_SEND_GAS: u64 = 29_233;
+pub const TEST_VM_SECP_SIG_GAS: u64 = 1_637_292;
+pub const TEST_VM_BLS_SIG_GAS: u64 = 16_598_605;
@@ -18,7 +18,7 @@ use fil_actors_runtime::{
     STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
     VERIFIED_REGISTRY_ACTOR_ADDR,
 };
-use fil_actors_runtime::{DEFAULT_HAMT_CONFIG, Map2, test_utils::*};
+use fil_actors_runtime::{DEFAULT_HAMT_CONFIG, Map2, Versioned, test_utils::*};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::CborStore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -39,7 +39,7 @@ use std::rc::Rc;
 use vm_api::trace::InvocationTrace;
 use vm_api::{ActorState, MessageResult, MockPrimitives, VM, VMError, new_actor};
 
-use vm_api::util::{get_state, serialize_ok};
+use vm_api::util::{get_state, mutate_state, serialize_ok};
 
 mod constants;
 pub use constants::*;
@@ -60,6 +60,8 @@ pub struct TestVM {
     circulating_supply: RefCell<TokenAmount>,
     base_fee: RefCell<TokenAmount>,
     timestamp: RefCell<u64>,
+    // code CIDs of the builtin actor bundle the VM is running
+    bundle: RefCell<BTreeMap<Type, Cid>>,
 }
 
 impl TestVM {
@@ -83,6 +85,7 @@ impl TestVM {
             invocations: RefCell::new(vec![]),
             base_fee: RefCell::new(TokenAmount::zero()),
             timestamp: RefCell::new(0),
+            bundle: RefCell::new(ACTOR_CODES.clone()),
         }
     }
 
@@ -114,7 +117,7 @@ impl TestVM {
 
         // reward
 
-        let reward_head = v.put_store(&RewardState::new(StoragePower::zero()));
+        let reward_head = v.put_store(&RewardState::new(&Policy::default(), StoragePower::zero()));
         v.set_actor(
             &REWARD_ACTOR_ADDR,
             new_actor(*REWARD_ACTOR_CODE_ID, reward_head, 0, reward_total, None),
@@ -131,7 +134,7 @@ impl TestVM {
                 method_num: MarketMethod::CronTick as u64,
             },
         ];
-        let cron_head = v.put_store(&CronState { entries: builtin_entries });
+        let cron_head = v.put_store(&CronState { entries: builtin_entries, gas_limits: vec![] });
         v.set_actor(
             &CRON_ACTOR_ADDR,
             new_actor(*CRON_ACTOR_CODE_ID, cron_head, 0, TokenAmount::zero(), None),
@@ -194,7 +197,8 @@ impl TestVM {
         let root_msig_addr = msig_ctor_ret.id_address;
         assert_eq!(TEST_VERIFREG_ROOT_ADDR, root_msig_addr);
         // verifreg
-        let verifreg_head = v.put_store(&VerifRegState::new(&v.store, root_msig_addr).unwrap());
+        let verifreg_head =
+            v.put_store(&Versioned(VerifRegState::new(&v.store, root_msig_addr).unwrap()));
         v.set_actor(
             &VERIFIED_REGISTRY_ACTOR_ADDR,
             new_actor(*VERIFREG_ACTOR_CODE_ID, verifreg_head, 0, TokenAmount::zero(), None),
@@ -266,6 +270,44 @@ impl TestVM {
         self.actors_dirty.replace(false);
     }
 
+    /// Installs an actor with the given code, state and balance at the next ID allocated by the
+    /// init actor, without invoking a constructor, and returns its ID address.
+    /// IDs so allocated cannot conflict with those of actors created by messages.
+    pub fn install_actor(&self, code: Cid, head: Cid, balance: TokenAmount) -> Address {
+        let mut id = 0;
+        mutate_state(self, &INIT_ACTOR_ADDR, |st: &mut InitState| {
+            id = st.next_id;
+            st.next_id += 1;
+        });
+        let addr = Address::new_id(id);
+        self.set_actor(&addr, new_actor(code, head, 0, balance, None));
+        addr
+    }
+
+    /// Returns the code CID of a builtin actor type in the bundle the VM is running.
+    pub fn builtin_code(&self, typ: Type) -> Cid {
+        self.bundle.borrow()[&typ]
+    }
+
+    /// Returns the builtin actor type of a code CID in the bundle the VM is running.
+    pub fn builtin_type(&self, code: &Cid) -> Option<Type> {
+        self.bundle.borrow().iter().find(|(_, c)| *c == code).map(|(typ, _)| *typ)
+    }
+
+    /// Upgrades the VM to another bundle of builtin actors, given by the code CID of each type.
+    /// Every builtin actor is switched to the new bundle's code for its type; actor state is
+    /// left as is, so a state migration must be applied separately.
+    pub fn upgrade_bundle(&self, codes: BTreeMap<Type, Cid>) {
+        for (addr, mut actor) in self.actor_states() {
+            if let Some(typ) = self.builtin_type(&actor.code) {
+                actor.code = codes[&typ];
+                self.set_actor(&addr, actor);
+            }
+        }
+        self.bundle.replace(codes);
+        self.checkpoint();
+    }
+
     fn actor_map(&self) -> Map2<&MemoryBlockstore, Address, ActorState> {
         Map2::load(self.store.as_ref(), &self.checkpoint(), DEFAULT_HAMT_CONFIG, "actors").unwrap()
     }
@@ -291,10 +333,10 @@ impl VM for TestVM {
         let call_seq = a.sequence;
         a.sequence = call_seq + 1;
         // EthAccount abstractions turns Placeholders into EthAccounts
-        if a.code == *PLACEHOLDER_ACTOR_CODE_ID {
+        if a.code == self.builtin_code(Type::Placeholder) {
             // TODO: for non-implicit calls validate that the actor has a
             // delegated f4 address in the EAM's namespace
-            a.code = *ETHACCOUNT_ACTOR_CODE_ID;
+            a.code = self.builtin_code(Type::EthAccount);
         }
         self.set_actor(from_id, a);
 
@@ -325,6 +367,7 @@ impl VM for TestVM {
             policy: &Policy::default(),
             subinvocations: RefCell::new(vec![]),
             events: RefCell::new(vec![]),
+            gas_charged: RefCell::new(0),
         };
         let res = new_ctx.invoke();
 
@@ -397,7 +440,7 @@ impl VM for TestVM {
     }
 
     fn actor_manifest(&self) -> BTreeMap<Cid, Type> {
-        ACTOR_TYPES.clone()
+        self.bundle.borrow().iter().map(|(typ, code)| (*code, *typ)).collect()
     }
 
     fn actor_states(&self) -> BTreeMap<Address, ActorState> {
@@ -447,3 +490,15 @@ impl VM for TestVM {
         &self.primitives
     }
 }
+
+/// Returns a bundle of builtin actors distinct from the default one, with each type's code CID
+/// named `fil/<version>/<type>`, to rehearse upgrades between bundles.
+pub fn versioned_bundle(version: &str) -> BTreeMap<Type, Cid> {
+    ACTOR_CODES
+        .keys()
+        .map(|typ| {
+            let name = format!("fil/{}/{}", version, format!("{:?}", typ).to_lowercase());
+            (*typ, make_identity_cid(name.as_bytes()))
+        })
+        .collect()
+}
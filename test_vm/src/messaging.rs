@@ -36,7 +36,7 @@ use fvm_shared::clock::ChainEpoch;
 use fvm_shared::consensus::ConsensusFault;
 use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::crypto::signature::{
-    SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE, Signature,
+    SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE, Signature, SignatureType,
 };
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
@@ -64,7 +64,10 @@ use fil_actors_runtime::test_blockstores::MemoryBlockstore;
 use std::ops::Add;
 use std::rc::Rc;
 
-use crate::{TEST_VM_INVALID_POST, TEST_VM_RAND_ARRAY, TestVM};
+use crate::{
+    TEST_VM_BLS_SIG_GAS, TEST_VM_INVALID_POST, TEST_VM_RAND_ARRAY, TEST_VM_SECP_SIG_GAS,
+    TEST_VM_SEND_GAS, TestVM,
+};
 
 #[derive(Clone)]
 pub struct TopCtx {
@@ -114,6 +117,8 @@ pub struct InvocationCtx<'invocation> {
     pub policy: &'invocation Policy,
     pub subinvocations: RefCell<Vec<InvocationTrace>>,
     pub events: RefCell<Vec<EmittedEvent>>,
+    /// Gas charged so far by this invocation and its completed subinvocations.
+    pub gas_charged: RefCell<u64>,
 }
 
 impl<'invocation> InvocationCtx<'invocation> {
@@ -179,17 +184,21 @@ impl<'invocation> InvocationCtx<'invocation> {
                 policy: self.policy,
                 subinvocations: RefCell::new(vec![]),
                 events: RefCell::new(vec![]),
+                gas_charged: RefCell::new(0),
             };
             if is_account {
-                new_ctx.create_actor(*ACCOUNT_ACTOR_CODE_ID, target_id, None).unwrap();
+                new_ctx.create_actor(self.v.builtin_code(Type::Account), target_id, None).unwrap();
                 let res = new_ctx.invoke();
                 let invoc = new_ctx.gather_trace(res);
+                *self.gas_charged.borrow_mut() += invoc.gas_charged;
                 RefMut::map(self.subinvocations.borrow_mut(), |subinvocs| {
                     subinvocs.push(invoc);
                     subinvocs
                 });
             } else {
-                new_ctx.create_actor(*PLACEHOLDER_ACTOR_CODE_ID, target_id, Some(*target)).unwrap();
+                new_ctx
+                    .create_actor(self.v.builtin_code(Type::Placeholder), target_id, Some(*target))
+                    .unwrap();
             }
         }
 
@@ -221,6 +230,7 @@ impl<'invocation> InvocationCtx<'invocation> {
             exit_code: code,
             subinvocations: self.subinvocations.take(),
             events: self.events.take(),
+            gas_charged: TEST_VM_SEND_GAS + self.gas_charged.take(),
         }
     }
 
@@ -271,8 +281,8 @@ impl<'invocation> InvocationCtx<'invocation> {
         // call target actor
         let to_actor = self.v.actor(&to_addr).unwrap();
         let params = self.msg.params.clone();
-        let mut res = match ACTOR_TYPES.get(&to_actor.code).expect("Target actor is not a builtin")
-        {
+        let to_type = self.v.builtin_type(&to_actor.code).expect("Target actor is not a builtin");
+        let mut res = match to_type {
             Type::Account => AccountActor::invoke_method(self, self.msg.method, params),
             Type::Cron => CronActor::invoke_method(self, self.msg.method, params),
             Type::Init => InitActor::invoke_method(self, self.msg.method, params),
@@ -312,7 +322,12 @@ impl Runtime for InvocationCtx<'_> {
         actor_id: ActorID,
         predictable_address: Option<Address>,
     ) -> Result<(), ActorError> {
-        match NON_SINGLETON_CODES.get(&code_id) {
+        // the default bundle's singletons are those of the bundle the VM is running
+        match self
+            .v
+            .builtin_type(&code_id)
+            .and_then(|typ| NON_SINGLETON_CODES.get(&ACTOR_CODES[&typ]))
+        {
             Some(_) => (),
             None => {
                 return Err(ActorError::unchecked(
@@ -323,7 +338,7 @@ impl Runtime for InvocationCtx<'_> {
         }
         let addr = &Address::new_id(actor_id);
         let actor = match self.v.actor(addr) {
-            Some(mut act) if act.code == *PLACEHOLDER_ACTOR_CODE_ID => {
+            Some(mut act) if act.code == self.v.builtin_code(Type::Placeholder) => {
                 act.code = code_id;
                 act
             }
@@ -450,9 +465,11 @@ impl Runtime for InvocationCtx<'_> {
             ));
         }
         self.caller_validated.replace(true);
-        let to_match =
-            ACTOR_TYPES.get(&self.v.actor(&Address::new_id(self.msg.from)).unwrap().code).unwrap();
-        if types.into_iter().any(|t| *t == *to_match) {
+        let to_match = self
+            .v
+            .builtin_type(&self.v.actor(&Address::new_id(self.msg.from)).unwrap().code)
+            .unwrap();
+        if types.into_iter().any(|t| *t == to_match) {
             return Ok(());
         }
         Err(ActorError::unchecked(
@@ -517,9 +534,11 @@ impl Runtime for InvocationCtx<'_> {
             policy: self.policy,
             subinvocations: RefCell::new(vec![]),
             events: RefCell::new(vec![]),
+            gas_charged: RefCell::new(0),
         };
         let res = new_ctx.invoke();
         let invoc = new_ctx.gather_trace(res.clone());
+        *self.gas_charged.borrow_mut() += invoc.gas_charged;
         RefMut::map(self.subinvocations.borrow_mut(), |subinvocs| {
             subinvocs.push(invoc);
             subinvocs
@@ -615,18 +634,20 @@ impl Runtime for InvocationCtx<'_> {
     }
 
     fn resolve_builtin_actor_type(&self, code_id: &Cid) -> Option<Type> {
-        Some(ACTOR_TYPES.get(code_id).cloned())
+        self.v.builtin_type(code_id)
     }
 
     fn get_code_cid_for_type(&self, typ: Type) -> Cid {
-        ACTOR_CODES.get(&typ).cloned().unwrap()
+        self.v.builtin_code(typ)
     }
 
     fn total_fil_circ_supply(&self) -> TokenAmount {
         self.top.circ_supply.clone()
     }
 
-    fn charge_gas(&self, _name: &'static str, _compute: i64) {}
+    fn charge_gas(&self, _name: &'static str, compute: i64) {
+        *self.gas_charged.borrow_mut() += compute as u64;
+    }
 
     fn base_fee(&self) -> TokenAmount {
         TokenAmount::zero()
@@ -667,6 +688,10 @@ impl Primitives for InvocationCtx<'_> {
         signer: &Address,
         plaintext: &[u8],
     ) -> Result<(), anyhow::Error> {
+        *self.gas_charged.borrow_mut() += match signature.sig_type {
+            SignatureType::Secp256k1 => TEST_VM_SECP_SIG_GAS,
+            SignatureType::BLS => TEST_VM_BLS_SIG_GAS,
+        };
         self.v.primitives().verify_signature(signature, signer, plaintext)
     }
 
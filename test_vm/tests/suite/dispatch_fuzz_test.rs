@@ -0,0 +1,181 @@
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use fil_actor_init::{ExecParams, ExecReturn, Method as InitMethod};
+use fil_actors_integration_tests::util::{create_accounts, create_miner};
+use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::method_names::method_names;
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use fil_actors_runtime::test_utils::{MULTISIG_ACTOR_CODE_ID, PAYCH_ACTOR_CODE_ID};
+use fil_actors_runtime::{
+    CRON_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR, EAM_ACTOR_ADDR, FIRST_EXPORTED_METHOD_NUMBER,
+    INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
+    SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::{CBOR, IPLD_RAW};
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::RegisteredPoStProof;
+use fvm_shared::{METHOD_SEND, MethodNum};
+use num_traits::Zero;
+use test_vm::TestVM;
+use vm_api::VM;
+use vm_api::util::apply_ok;
+
+/// Sends every method of every builtin actor a fixed corpus of malformed and boundary-value
+/// parameters, from both an unprivileged account and the system actor, and checks that each
+/// message fails cleanly: with success or a user exit code, never a panic or system error.
+/// EVM and Ethereum account actors are not covered, as instances require deployed bytecode
+/// or delegated addresses.
+#[test]
+fn dispatch_rejects_adversarial_messages() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    let addrs = create_accounts(&v, 3, &TokenAmount::from_whole(10_000));
+    let (owner, payee, sender) = (addrs[0], addrs[1], addrs[2]);
+
+    let mut targets = vec![
+        (SYSTEM_ACTOR_ADDR, Type::System),
+        (INIT_ACTOR_ADDR, Type::Init),
+        (CRON_ACTOR_ADDR, Type::Cron),
+        (REWARD_ACTOR_ADDR, Type::Reward),
+        (STORAGE_POWER_ACTOR_ADDR, Type::Power),
+        (STORAGE_MARKET_ACTOR_ADDR, Type::Market),
+        (VERIFIED_REGISTRY_ACTOR_ADDR, Type::VerifiedRegistry),
+        (DATACAP_TOKEN_ACTOR_ADDR, Type::DataCap),
+        (EAM_ACTOR_ADDR, Type::EAM),
+        (owner, Type::Account),
+    ];
+    let (miner, _) = create_miner(
+        &v,
+        &owner,
+        &owner,
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        &TokenAmount::from_whole(1_000),
+    );
+    targets.push((miner, Type::Miner));
+    let msig_params = fil_actor_multisig::ConstructorParams {
+        signers: vec![owner],
+        num_approvals_threshold: 1,
+        unlock_duration: 0,
+        start_epoch: 0,
+    };
+    targets.push((exec_actor(&v, &owner, *MULTISIG_ACTOR_CODE_ID, &msig_params), Type::Multisig));
+    let paych_params = fil_actor_paych::ConstructorParams { from: owner, to: payee };
+    targets
+        .push((exec_actor(&v, &owner, *PAYCH_ACTOR_CODE_ID, &paych_params), Type::PaymentChannel));
+
+    let root = v.checkpoint();
+    let mut failures = vec![];
+    let mut sent = 0;
+    for (target, typ) in &targets {
+        for method in methods_to_fuzz(*typ) {
+            for params in param_corpus() {
+                for from in [sender, SYSTEM_ACTOR_ADDR] {
+                    let desc = format!("{:?} {} method {} from {}", typ, target, method, from);
+                    let res = catch_unwind(AssertUnwindSafe(|| {
+                        v.execute_message_implicit(
+                            &from,
+                            target,
+                            &TokenAmount::zero(),
+                            method,
+                            params.clone(),
+                        )
+                    }));
+                    match res {
+                        Ok(Ok(ret)) if !acceptable(ret.code) => failures
+                            .push(format!("{desc}: exit code {} ({})", ret.code, ret.message)),
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => failures.push(format!("{desc}: VM error {e:?}")),
+                        Err(_) => failures.push(format!("{desc}: panicked")),
+                    }
+                    sent += 1;
+                    // Discard any effects, so each message sees the same state.
+                    v.take_invocations();
+                    v.rollback(root);
+                }
+            }
+        }
+    }
+    assert!(sent > 0);
+    assert!(
+        failures.is_empty(),
+        "{} of {sent} messages failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+/// A message may succeed, or fail with a user exit code other than assertion failure,
+/// which the test VM also uses to report a method which returned without validating its caller.
+fn acceptable(code: ExitCode) -> bool {
+    code.is_success()
+        || (code.value() >= ExitCode::FIRST_USER_EXIT_CODE
+            && code != ExitCode::USR_ASSERTION_FAILED)
+}
+
+/// The methods of an actor type, plus a plain value transfer and numbers which name no method:
+/// the first after the legacy methods, the last before the exported range, and an exported one.
+fn methods_to_fuzz(typ: Type) -> Vec<MethodNum> {
+    let mut methods: Vec<MethodNum> = method_names(typ).iter().map(|(num, _)| *num).collect();
+    let last_legacy =
+        methods.iter().copied().filter(|m| *m < FIRST_EXPORTED_METHOD_NUMBER).max().unwrap_or(0);
+    methods.extend([
+        METHOD_SEND,
+        last_legacy + 1,
+        FIRST_EXPORTED_METHOD_NUMBER - 1,
+        u32::MAX as MethodNum,
+    ]);
+    methods
+}
+
+/// A fixed corpus of parameters which no method accepts as well-formed, or which hold
+/// boundary values in otherwise plausible shapes.
+fn param_corpus() -> Vec<Option<IpldBlock>> {
+    let max = [0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+    let min = [0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+    let cbor = |data: Vec<u8>| Some(IpldBlock { codec: CBOR, data });
+    vec![
+        None,
+        // Malformed CBOR: empty, a stray break, a truncated array and a truncated byte string.
+        cbor(vec![]),
+        cbor(vec![0xff]),
+        cbor(vec![0x82, 0x01]),
+        cbor(vec![0x5a, 0x00, 0x01, 0x00, 0x00]),
+        // Well-formed CBOR of the wrong shape, or with boundary values.
+        cbor(vec![0xf6]),
+        cbor(max.to_vec()),
+        cbor(min.to_vec()),
+        cbor(vec![0x80]),
+        cbor([vec![0x81], max.to_vec()].concat()),
+        cbor([vec![0x82], max.to_vec(), min.to_vec()].concat()),
+        cbor([vec![0x58, 0x40], vec![0xff; 64]].concat()),
+        cbor([vec![0x81; 64], vec![0x80]].concat()),
+        // Parameters in a codec other than CBOR.
+        Some(IpldBlock { codec: IPLD_RAW, data: vec![0x80] }),
+    ]
+}
+
+fn exec_actor<S: serde::Serialize>(
+    v: &dyn VM,
+    from: &Address,
+    code_cid: cid::Cid,
+    params: &S,
+) -> Address {
+    let ret: ExecReturn = apply_ok(
+        v,
+        from,
+        &INIT_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        InitMethod::Exec as u64,
+        Some(ExecParams {
+            code_cid,
+            constructor_params: serialize(params, "constructor params").unwrap(),
+        }),
+    )
+    .deserialize()
+    .unwrap();
+    ret.id_address
+}
@@ -5,6 +5,7 @@ mod change_beneficiary_test;
 mod change_owner_test;
 mod commit_post_test;
 mod datacap_tests;
+mod dispatch_fuzz_test;
 mod evm_test;
 mod extend_sectors_test;
 mod init_test;
@@ -18,6 +19,7 @@ mod replica_update3_test;
 mod replica_update_test;
 mod terminate_test;
 mod test_vm_test;
+mod upgrade_rehearsal_test;
 mod verified_claim_test;
 mod verifreg_remove_datacap_test;
 mod withdraw_balance_test;
@@ -108,6 +108,25 @@ fn test_sent() {
     assert_invariants(&v, &Policy::default(), None)
 }
 
+#[test]
+fn install_actor_takes_next_id() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+
+    let installed = v.install_actor(*PAYCH_ACTOR_CODE_ID, EMPTY_ARR_CID, TokenAmount::from_atto(7));
+    assert_eq!(Address::new_id(FIRST_TEST_USER_ADDR), installed);
+    let actor = v.actor(&installed).unwrap();
+    assert_eq!(*PAYCH_ACTOR_CODE_ID, actor.code);
+    assert_eq!(TokenAmount::from_atto(7), actor.balance);
+
+    // Actors subsequently created by messages take the following IDs.
+    let addr = Address::new_bls(&[1; fvm_shared::address::BLS_PUB_LEN]).unwrap();
+    v.execute_message(&TEST_FAUCET_ADDR, &addr, &TokenAmount::from_atto(42u8), METHOD_SEND, None)
+        .unwrap();
+    let expect_id_addr = Address::new_id(FIRST_TEST_USER_ADDR + 1);
+    assert_account_actor(0, TokenAmount::from_atto(42u8), addr, &v, expect_id_addr);
+}
+
 #[test]
 fn test_pk_gen() {
     let addrs = pk_addrs_from(5, 2);
@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::upgrade_rehearsal_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::{TestVM, versioned_bundle};
+
+#[test]
+fn upgrade_rehearsal() {
+    let pre = TestVM::new_with_singletons(MemoryBlockstore::new());
+    let post = TestVM::new_with_singletons(MemoryBlockstore::new());
+    upgrade_rehearsal_test(&pre, &post, |_| post.upgrade_bundle(versioned_bundle("next")));
+}
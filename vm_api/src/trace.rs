@@ -28,6 +28,9 @@ pub struct InvocationTrace {
     pub return_value: ReturnValue,
     pub subinvocations: Vec<InvocationTrace>,
     pub events: Vec<EmittedEvent>,
+    /// Gas charged for the invocation, including its subinvocations.
+    /// A VM which does not meter gas reports zero.
+    pub gas_charged: u64,
 }
 
 /// An expectation for a method invocation trace.